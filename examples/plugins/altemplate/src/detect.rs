@@ -26,6 +26,7 @@ use suricata::cast_pointer;
 use suricata::core::{STREAM_TOCLIENT, STREAM_TOSERVER};
 use suricata::detect::{helper_keyword_register_sticky_buffer, SigTableElmtStickyBuffer};
 use suricata::direction::Direction;
+use suricata::SCLogError;
 use suricata_sys::sys::{
     DetectEngineCtx, SCDetectBufferSetActiveList, SCDetectHelperBufferMpmRegister,
     SCDetectSignatureSetAppProto, Signature,
@@ -74,7 +75,9 @@ pub(super) unsafe extern "C" fn detect_template_register() {
         url: String::from("/rules/template-keywords.html#buffer"),
         setup: template_buffer_setup,
     };
-    let _g_template_buffer_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_TEMPLATE_BUFFER_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"altemplate.buffer\0".as_ptr() as *const libc::c_char,
         b"template.buffer intern description\0".as_ptr() as *const libc::c_char,
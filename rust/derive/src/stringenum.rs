@@ -22,8 +22,12 @@ use quote::quote;
 use syn::{self, parse_macro_input, DeriveInput};
 use std::str::FromStr;
 
-pub fn derive_enum_string<T: std::str::FromStr + quote::ToTokens>(input: TokenStream, ustr: &str) -> TokenStream where <T as FromStr>::Err: std::fmt::Display {
+pub fn derive_enum_string<T: std::str::FromStr + quote::ToTokens + Ord + std::fmt::Display>(input: TokenStream, ustr: &str) -> TokenStream where <T as FromStr>::Err: std::fmt::Display {
     let input = parse_macro_input!(input as DeriveInput);
+    proc_macro::TokenStream::from(enum_string_impl::<T>(input, ustr))
+}
+
+fn enum_string_impl<T: std::str::FromStr + quote::ToTokens + Ord + std::fmt::Display>(input: DeriveInput, ustr: &str) -> proc_macro2::TokenStream where <T as FromStr>::Err: std::fmt::Display {
     let name = input.ident;
     let mut values = Vec::new();
     let mut names = Vec::new();
@@ -59,6 +63,19 @@ pub fn derive_enum_string<T: std::str::FromStr + quote::ToTokens>(input: TokenSt
         panic!("EnumString can only be derived for enums");
     }
 
+    // from_u does a binary search on the values, sorted here once and
+    // for all, which also catches variants sharing a value
+    let mut sorted: Vec<usize> = (0..values.len()).collect();
+    sorted.sort_by(|a, b| values[*a].cmp(&values[*b]));
+    for w in sorted.windows(2) {
+        if values[w[0]] == values[w[1]] {
+            panic!("EnumString requires unique values, {} is used by {} and {}",
+                values[w[0]], fields[w[0]], fields[w[1]]);
+        }
+    }
+    let sorted_values: Vec<&T> = sorted.iter().map(|i| &values[*i]).collect();
+    let sorted_fields: Vec<&syn::Ident> = sorted.iter().map(|i| &fields[*i]).collect();
+
     let is_suricata = std::env::var("CARGO_PKG_NAME").map(|var| var == "suricata").unwrap_or(false);
     let crate_id = if is_suricata {
         syn::Ident::new("crate", proc_macro2::Span::call_site())
@@ -71,10 +88,13 @@ pub fn derive_enum_string<T: std::str::FromStr + quote::ToTokens>(input: TokenSt
     let expanded = quote! {
         impl #crate_id::detect::EnumString<#utype_str> for #name {
             fn from_u(v: #utype_str) -> Option<Self> {
-                match v {
-                    #( #values => Some(#name::#fields) ,)*
-                    _ => None,
-                }
+                const TABLE: &[(#utype_str, fn() -> #name)] = &[
+                    #( (#sorted_values, || #name::#sorted_fields) ,)*
+                ];
+                TABLE
+                    .binary_search_by_key(&v, |(value, _)| *value)
+                    .ok()
+                    .map(|i| (TABLE[i].1)())
             }
             fn into_u(self) -> #utype_str {
                 match self {
@@ -86,6 +106,9 @@ pub fn derive_enum_string<T: std::str::FromStr + quote::ToTokens>(input: TokenSt
                     #( #name::#fields => #names ,)*
                 }
             }
+            fn all_variants() -> &'static [Self] {
+                &[ #( #name::#fields ,)* ]
+            }
             fn from_str(s: &str) -> Option<Self> {
                 match s.to_ascii_uppercase().as_str() {
                     #( #names_upper => Some(#name::#fields) ,)*
@@ -95,5 +118,40 @@ pub fn derive_enum_string<T: std::str::FromStr + quote::ToTokens>(input: TokenSt
         }
     };
 
-    proc_macro::TokenStream::from(expanded)
+    expanded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_enum_string_values() {
+        let input: DeriveInput = syn::parse_quote! {
+            enum Sparse {
+                High = 200,
+                Low = 1,
+                Middle = 100,
+            }
+        };
+        let out = enum_string_impl::<u8>(input, "u8").to_string();
+        // the lookup table is sorted by value
+        let low = out.find("Low").unwrap();
+        let middle = out.find("Middle").unwrap();
+        let high = out.find("High").unwrap();
+        assert!(low < middle && middle < high);
+    }
+
+    #[test]
+    #[should_panic(expected = "EnumString requires unique values, 1 is used by One and Uno")]
+    fn test_enum_string_duplicate_values() {
+        let input: DeriveInput = syn::parse_quote! {
+            enum Duplicate {
+                One = 1,
+                Two = 2,
+                Uno = 1,
+            }
+        };
+        enum_string_impl::<u8>(input, "u8");
+    }
 }
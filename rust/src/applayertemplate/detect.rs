@@ -77,7 +77,9 @@ pub unsafe extern "C" fn SCDetectTemplateRegister() {
         url: String::from("/rules/template-keywords.html#buffer"),
         setup: template_buffer_setup,
     };
-    let _g_template_buffer_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_TEMPLATE_BUFFER_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"template.buffer\0".as_ptr() as *const libc::c_char,
         b"template.buffer intern description\0".as_ptr() as *const libc::c_char,
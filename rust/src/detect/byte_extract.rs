@@ -23,9 +23,7 @@ use crate::detect::*;
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 
-use nom7::bytes::complete::tag;
-use nom7::character::complete::multispace0;
-use nom7::sequence::preceded;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use nom7::{Err, IResult};
 use std::str;
 
@@ -38,6 +36,7 @@ pub const DETECT_BYTE_EXTRACT_FLAG_MULTIPLIER: u16 = 0x20;
 pub const DETECT_BYTE_EXTRACT_FLAG_NBYTES: u16 = 0x40;
 pub const DETECT_BYTE_EXTRACT_FLAG_OFFSET: u16 = 0x80;
 pub const DETECT_BYTE_EXTRACT_FLAG_BASE: u16 = 0x100;
+pub const DETECT_BYTE_EXTRACT_FLAG_BASE64: u16 = 0x200;
 
 pub const DETECT_BYTE_EXTRACT_MULTIPLIER_DEFAULT: u16 = 1;
 
@@ -47,6 +46,10 @@ const BASE_DEFAULT: ByteBase = ByteBase::BaseDec;
 const DETECT_BYTE_EXTRACT_FIXED_PARAM_COUNT: usize = 3;
 // Optional parameters: endian, relative, string, dce, slice, align, multiplier
 const DETECT_BYTE_EXTRACT_MAX_PARAM_COUNT: usize = 10;
+// Optional parameters which take no value
+const DETECT_BYTE_EXTRACT_BARE_OPTIONS: [&str; 10] = [
+    "relative", "string", "oct", "dec", "hex", "base64", "slice", "dce", "big", "little",
+];
 
 #[repr(C)]
 #[derive(Debug)]
@@ -95,10 +98,7 @@ fn parse_byteextract(input: &str) -> IResult<&str, SCDetectByteExtractData, Rule
     fn make_error(reason: String) -> nom7::Err<RuleParseError<&'static str>> {
         Err::Error(RuleParseError::InvalidByteExtract(reason))
     }
-    let (_, values) = nom7::multi::separated_list1(
-        tag(","),
-        preceded(multispace0, nom7::bytes::complete::is_not(",")),
-    )(input)?;
+    let values: Vec<&str> = input.split(',').map(|v| v.trim()).collect();
 
     if values.len() < DETECT_BYTE_EXTRACT_FIXED_PARAM_COUNT
         || values.len() > DETECT_BYTE_EXTRACT_MAX_PARAM_COUNT
@@ -106,6 +106,13 @@ fn parse_byteextract(input: &str) -> IResult<&str, SCDetectByteExtractData, Rule
         return Err(make_error(format!("Incorrect argument string; at least {} values must be specified but no more than {}: {:?}",
             DETECT_BYTE_EXTRACT_FIXED_PARAM_COUNT, DETECT_BYTE_EXTRACT_MAX_PARAM_COUNT, input)));
     }
+    // an empty value, like in "4, 2, one,, relative", is a typo
+    if values.iter().any(|v| v.is_empty()) {
+        return Err(make_error(format!(
+            "empty byte_extract argument: {:?}",
+            input
+        )));
+    }
 
     let mut byte_extract = {
         SCDetectByteExtractData {
@@ -115,13 +122,29 @@ fn parse_byteextract(input: &str) -> IResult<&str, SCDetectByteExtractData, Rule
             ..Default::default()
         }
     };
+    if byte_extract.nbytes == 0 {
+        return Err(make_error(format!(
+            "invalid nbytes value: must be at least 1: {}",
+            values[0]
+        )));
+    }
 
     let value = values[1]
         .parse::<i16>()
         .map_err(|_| make_error(format!("invalid offset value: {}", values[1])))?;
     byte_extract.offset = value;
 
-    let (_, value) = parse_token(values[2])?;
+    let (rest, value) = parse_token(values[2])?;
+    if !rest.is_empty() || value.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(make_error(format!("invalid variable name: {}", values[2])));
+    }
+    // the name is missing, and an option was taken for it
+    if DETECT_BYTE_EXTRACT_BARE_OPTIONS.contains(&value) {
+        return Err(make_error(format!(
+            "variable name expected, found option: {}",
+            value
+        )));
+    }
     if let Ok(newval) = CString::new(value) {
         byte_extract.name = newval.into_raw();
     } else {
@@ -134,6 +157,9 @@ fn parse_byteextract(input: &str) -> IResult<&str, SCDetectByteExtractData, Rule
         let (mut val, mut name) = take_until_whitespace(value)?;
         val = val.trim();
         name = name.trim();
+        if !val.is_empty() && DETECT_BYTE_EXTRACT_BARE_OPTIONS.contains(&name) {
+            return Err(make_error(format!("{} takes no value: {}", name, value)));
+        }
         match name {
             "align" => {
                 if 0 != (byte_extract.flags & DETECT_BYTE_EXTRACT_FLAG_ALIGN) {
@@ -160,11 +186,7 @@ fn parse_byteextract(input: &str) -> IResult<&str, SCDetectByteExtractData, Rule
                 if 0 != (byte_extract.flags & DETECT_BYTE_EXTRACT_FLAG_ENDIAN) {
                     return Err(make_error("endianess already set".to_string()));
                 }
-                if let Some(endian) = get_endian_value(name) {
-                    byte_extract.endian = endian;
-                } else {
-                    return Err(make_error(format!("invalid endian value: {}", val)));
-                };
+                byte_extract.endian = get_endian_value(name).map_err(make_error)?;
                 byte_extract.flags |= DETECT_BYTE_EXTRACT_FLAG_ENDIAN;
             }
             "string" => {
@@ -185,13 +207,19 @@ fn parse_byteextract(input: &str) -> IResult<&str, SCDetectByteExtractData, Rule
                 if 0 != (byte_extract.flags & DETECT_BYTE_EXTRACT_FLAG_BASE) {
                     return Err(make_error("base already set".to_string()));
                 }
-                if let Some(base) = get_string_value(name) {
-                    byte_extract.base = base;
-                } else {
-                    return Err(make_error(format!("invalid string value: {}", val)));
-                };
+                byte_extract.base = get_string_value(name).map_err(make_error)?;
                 byte_extract.flags |= DETECT_BYTE_EXTRACT_FLAG_BASE;
             }
+            "base64" => {
+                if 0 == (byte_extract.flags & DETECT_BYTE_EXTRACT_FLAG_STRING) {
+                    return Err(make_error("string must be set first".to_string()));
+                }
+                if 0 != (byte_extract.flags & DETECT_BYTE_EXTRACT_FLAG_BASE) {
+                    return Err(make_error("base already set".to_string()));
+                }
+                byte_extract.flags |=
+                    DETECT_BYTE_EXTRACT_FLAG_BASE | DETECT_BYTE_EXTRACT_FLAG_BASE64;
+            }
             "relative" => {
                 if 0 != (byte_extract.flags & DETECT_BYTE_EXTRACT_FLAG_RELATIVE) {
                     return Err(make_error("relative already set".to_string()));
@@ -230,8 +258,10 @@ fn parse_byteextract(input: &str) -> IResult<&str, SCDetectByteExtractData, Rule
         return Err(make_error("must specify string with base".to_string()));
     }
 
+    // base64 decodes to binary data, which is interpreted using the endianness
     if 0 != byte_extract.flags & DETECT_BYTE_EXTRACT_FLAG_STRING
         && 0 != byte_extract.flags & DETECT_BYTE_EXTRACT_FLAG_ENDIAN
+        && 0 == byte_extract.flags & DETECT_BYTE_EXTRACT_FLAG_BASE64
     {
         return Err(make_error(
             "can't specify string and an endian value".to_string(),
@@ -249,6 +279,81 @@ fn parse_byteextract(input: &str) -> IResult<&str, SCDetectByteExtractData, Rule
     Ok((input, byte_extract))
 }
 
+/// Decodes base64 `input` and interprets the decoded bytes as an integer.
+///
+/// Returns the value and the number of decoded bytes, or `None` if the input
+/// is not valid base64 or does not decode to 1 to 8 bytes.
+fn byte_extract_base64(input: &[u8], endian: ByteEndian) -> Option<(u64, usize)> {
+    let decoded = STANDARD.decode(input).ok()?;
+    if decoded.is_empty() || decoded.len() > 8 {
+        return None;
+    }
+    let value = match endian {
+        ByteEndian::LittleEndian => decoded
+            .iter()
+            .rev()
+            .fold(0u64, |acc, &b| (acc << 8) | u64::from(b)),
+        _ => decoded
+            .iter()
+            .fold(0u64, |acc, &b| (acc << 8) | u64::from(b)),
+    };
+    Some((value, decoded.len()))
+}
+
+/// Extracts an integer from `len` bytes of base64 encoded data.
+///
+/// Returns the number of decoded bytes, or 0 if the data could not be
+/// decoded, in which case `value` is left untouched.
+#[no_mangle]
+pub unsafe extern "C" fn SCByteExtractBase64Uint64(
+    data: *const u8, len: u32, endian: ByteEndian, value: *mut u64,
+) -> u32 {
+    if data.is_null() || value.is_null() {
+        return 0;
+    }
+    let input = build_slice!(data, len as usize);
+    match byte_extract_base64(input, endian) {
+        Some((val, decoded)) => {
+            *value = val;
+            decoded as u32
+        }
+        None => 0,
+    }
+}
+
+/// Applies the multiplier and alignment to an extracted value.
+///
+/// Returns `None` if the adjusted value does not fit in a u64.
+fn byte_extract_scale(data: &SCDetectByteExtractData, value: u64) -> Option<u64> {
+    let mut value = value.checked_mul(u64::from(data.multiplier_value))?;
+    if 0 != data.flags & DETECT_BYTE_EXTRACT_FLAG_ALIGN && data.align_value > 0 {
+        let align = u64::from(data.align_value);
+        if value % align != 0 {
+            value = value.checked_add(align - (value % align))?;
+        }
+    }
+    Some(value)
+}
+
+/// Scales an extracted value with the multiplier and alignment options.
+///
+/// Returns false on overflow, which must be treated as a non-match.
+#[no_mangle]
+pub unsafe extern "C" fn SCByteExtractScaleValue(
+    data: &SCDetectByteExtractData, value: u64, out: *mut u64,
+) -> bool {
+    if out.is_null() {
+        return false;
+    }
+    match byte_extract_scale(data, value) {
+        Some(val) => {
+            *out = val;
+            true
+        }
+        None => false,
+    }
+}
+
 /// Intermediary function between the C code and the parsing functions.
 #[no_mangle]
 pub unsafe extern "C" fn SCByteExtractParse(c_arg: *const c_char) -> *mut SCDetectByteExtractData {
@@ -259,6 +364,10 @@ pub unsafe extern "C" fn SCByteExtractParse(c_arg: *const c_char) -> *mut SCDete
     if let Ok(arg) = CStr::from_ptr(c_arg).to_str() {
         match parse_byteextract(arg) {
             Ok((_, detect)) => return Box::into_raw(Box::new(detect)),
+            Err(Err::Error(RuleParseError::InvalidByteExtract(e))) => {
+                SCLogError!("{}", e);
+                return std::ptr::null_mut();
+            }
             Err(_) => return std::ptr::null_mut(),
         };
     };
@@ -375,6 +484,36 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn test_parser_malformed() {
+        for args in [
+            "",
+            ",,",
+            "4, 2, one,",
+            "4, 2, one,, relative",
+            "4,, 2, one",
+            "0, 2, one",
+            "4, 2, one two",
+            "4, 2, 1one",
+            "4, 2, relative",
+            "4, 2, relative, one",
+            "4, relative, 2, one",
+            "relative, 4, 2, one",
+            "4, 2, one, relative, relative",
+            "4, 2, one, relative yes",
+            "4, 2, one, string hex",
+            "4, 2, one, little big",
+            "4, 2, one, align",
+            "4, 2, one, align 4 4",
+            "4, 2, one, hex, string",
+            "4, 2, one, string, hex, dec",
+            "4, 2, one, big, little",
+            "4, 2, one, string, slice",
+        ] {
+            assert!(parse_byteextract(args).is_err(), "{}", args);
+        }
+    }
+
     #[test]
     fn test_parser_valid() {
         valid_test(
@@ -448,4 +587,172 @@ mod tests {
                 | DETECT_BYTE_EXTRACT_FLAG_SLICE,
         );
     }
+
+    #[test]
+    fn test_parser_base64() {
+        valid_test(
+            "8, 0, one, string, base64",
+            8,
+            0,
+            "one",
+            BASE_DEFAULT,
+            ByteEndian::BigEndian,
+            0,
+            DETECT_BYTE_EXTRACT_MULTIPLIER_DEFAULT,
+            DETECT_BYTE_EXTRACT_FLAG_STRING
+                | DETECT_BYTE_EXTRACT_FLAG_BASE
+                | DETECT_BYTE_EXTRACT_FLAG_BASE64,
+        );
+        valid_test(
+            "8, 2, one, relative, string, base64, little, align 4",
+            8,
+            2,
+            "one",
+            BASE_DEFAULT,
+            ByteEndian::LittleEndian,
+            4,
+            DETECT_BYTE_EXTRACT_MULTIPLIER_DEFAULT,
+            DETECT_BYTE_EXTRACT_FLAG_RELATIVE
+                | DETECT_BYTE_EXTRACT_FLAG_STRING
+                | DETECT_BYTE_EXTRACT_FLAG_BASE
+                | DETECT_BYTE_EXTRACT_FLAG_BASE64
+                | DETECT_BYTE_EXTRACT_FLAG_ENDIAN
+                | DETECT_BYTE_EXTRACT_FLAG_ALIGN,
+        );
+        assert!(parse_byteextract("8, 0, one, base64").is_err());
+        assert!(parse_byteextract("8, 0, one, string, hex, base64").is_err());
+        assert!(parse_byteextract("8, 0, one, string, base64, base64").is_err());
+        assert!(parse_byteextract("8, 0, one, string, base64, slice").is_err());
+        // endianness still can't be used with textual bases
+        assert!(parse_byteextract("8, 0, one, string, hex, little").is_err());
+    }
+
+    #[test]
+    fn test_byte_extract_base64() {
+        // 0x00000100 big endian
+        assert_eq!(
+            byte_extract_base64(b"AAABAA==", ByteEndian::BigEndian),
+            Some((256, 4))
+        );
+        assert_eq!(
+            byte_extract_base64(b"AAABAA==", ByteEndian::LittleEndian),
+            Some((0x10000, 4))
+        );
+        assert_eq!(
+            byte_extract_base64(b"3q2+7w==", ByteEndian::BigEndian),
+            Some((0xdeadbeef, 4))
+        );
+        // malformed input, or too many decoded bytes, does not match
+        assert_eq!(
+            byte_extract_base64(b"AA!BAA==", ByteEndian::BigEndian),
+            None
+        );
+        assert_eq!(byte_extract_base64(b"AAABA", ByteEndian::BigEndian), None);
+        assert_eq!(byte_extract_base64(b"", ByteEndian::BigEndian), None);
+        assert_eq!(
+            byte_extract_base64(b"AAAAAAAAAAAA", ByteEndian::BigEndian),
+            None
+        );
+    }
+
+    #[test]
+    fn test_byte_extract_base64_align() {
+        // 8 encoded bytes decoding to 0x00000005: alignment applies to the
+        // decoded value (5 -> 8), not to the encoded length
+        let mut value = 0u64;
+        let data = b"AAAABQ==";
+        let decoded = unsafe {
+            SCByteExtractBase64Uint64(
+                data.as_ptr(),
+                data.len() as u32,
+                ByteEndian::BigEndian,
+                &mut value,
+            )
+        };
+        assert_eq!(decoded, 4);
+        assert_eq!(value, 5);
+        let (_, bed) = parse_byteextract("8, 0, one, string, base64, align 4").unwrap();
+        let mut out = 0u64;
+        assert!(unsafe { SCByteExtractScaleValue(&bed, value, &mut out) });
+        assert_eq!(out, 8);
+
+        let mut value = 42u64;
+        let data = b"not base64";
+        let decoded = unsafe {
+            SCByteExtractBase64Uint64(
+                data.as_ptr(),
+                data.len() as u32,
+                ByteEndian::BigEndian,
+                &mut value,
+            )
+        };
+        assert_eq!(decoded, 0);
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn test_parser_multiplier() {
+        valid_test(
+            "2, 0, len, multiplier 4",
+            2,
+            0,
+            "len",
+            BASE_DEFAULT,
+            ByteEndian::BigEndian,
+            0,
+            4,
+            DETECT_BYTE_EXTRACT_FLAG_MULTIPLIER,
+        );
+        valid_test(
+            "2, 0, len, relative, multiplier 4",
+            2,
+            0,
+            "len",
+            BASE_DEFAULT,
+            ByteEndian::BigEndian,
+            0,
+            4,
+            DETECT_BYTE_EXTRACT_FLAG_RELATIVE | DETECT_BYTE_EXTRACT_FLAG_MULTIPLIER,
+        );
+    }
+
+    #[test]
+    fn test_byte_extract_scale() {
+        let (_, bed) = parse_byteextract("2, 0, len, multiplier 4").unwrap();
+        assert_eq!(byte_extract_scale(&bed, 0), Some(0));
+        assert_eq!(byte_extract_scale(&bed, 0x1234), Some(0x1234 * 4));
+
+        let mut out = 0;
+        assert!(unsafe { SCByteExtractScaleValue(&bed, 10, &mut out) });
+        assert_eq!(out, 40);
+
+        // no multiplier: the value is stored as is
+        let (_, bed) = parse_byteextract("8, 0, len").unwrap();
+        assert_eq!(byte_extract_scale(&bed, u64::MAX), Some(u64::MAX));
+
+        // multiplier applied before alignment
+        let (_, bed) = parse_byteextract("1, 0, len, multiplier 3, align 4").unwrap();
+        assert_eq!(byte_extract_scale(&bed, 3), Some(12));
+        assert_eq!(byte_extract_scale(&bed, 4), Some(12));
+    }
+
+    #[test]
+    fn test_byte_extract_scale_overflow() {
+        let (_, bed) = parse_byteextract("8, 0, len, multiplier 4").unwrap();
+        assert_eq!(
+            byte_extract_scale(&bed, u64::MAX / 4),
+            Some(u64::MAX / 4 * 4)
+        );
+        assert_eq!(byte_extract_scale(&bed, u64::MAX / 4 + 1), None);
+        assert_eq!(byte_extract_scale(&bed, u64::MAX), None);
+
+        let mut out = 7;
+        assert!(!unsafe { SCByteExtractScaleValue(&bed, u64::MAX, &mut out) });
+        assert_eq!(out, 7);
+
+        // rounding up to the alignment may overflow too
+        let (_, bed) = parse_byteextract("8, 0, len, align 4").unwrap();
+        assert_eq!(byte_extract_scale(&bed, u64::MAX - 3), Some(u64::MAX - 3));
+        assert_eq!(byte_extract_scale(&bed, u64::MAX - 2), None);
+    }
 }
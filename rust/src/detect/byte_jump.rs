@@ -0,0 +1,653 @@
+/* Copyright (C) 2026 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use crate::detect::byte_test::{is_var_name, parse_c_uint};
+use crate::detect::error::RuleParseError;
+use crate::detect::{get_endian_value, get_string_value, ByteEndian};
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+use nom7::Err;
+
+pub const DETECT_BYTEJUMP_BASE_UNSET: u8 = 0; // automatic, as a C integer literal
+pub const DETECT_BYTEJUMP_BASE_OCT: u8 = 8;
+pub const DETECT_BYTEJUMP_BASE_DEC: u8 = 10;
+pub const DETECT_BYTEJUMP_BASE_HEX: u8 = 16;
+
+pub const DETECT_BYTEJUMP_BEGIN: u16 = 0x01; // "from_beginning" jump
+pub const DETECT_BYTEJUMP_LITTLE: u16 = 0x02;
+pub const DETECT_BYTEJUMP_BIG: u16 = 0x04;
+pub const DETECT_BYTEJUMP_STRING: u16 = 0x08;
+pub const DETECT_BYTEJUMP_RELATIVE: u16 = 0x10;
+pub const DETECT_BYTEJUMP_ALIGN: u16 = 0x20;
+pub const DETECT_BYTEJUMP_DCE: u16 = 0x40;
+pub const DETECT_BYTEJUMP_OFFSET_BE: u16 = 0x80;
+pub const DETECT_BYTEJUMP_END: u16 = 0x100; // "from_end" jump
+pub const DETECT_BYTEJUMP_NBYTES_VAR: u16 = 0x200;
+pub const DETECT_BYTEJUMP_OFFSET_VAR: u16 = 0x400;
+pub const DETECT_BYTEJUMP_POST_OFFSET_VAR: u16 = 0x800;
+
+// Fixed position parameter count: bytes, offset
+const DETECT_BYTEJUMP_FIXED_PARAM_COUNT: usize = 2;
+// With the optional parameters: relative, multiplier, endian, string, base,
+// align, from_beginning, from_end, post_offset, dce
+const DETECT_BYTEJUMP_MAX_PARAM_COUNT: usize = 12;
+
+// Largest string that does not overflow a u64: octal with a zero prefix,
+// "01777777777777777777777"
+const DETECT_BYTEJUMP_MAX_STRING_BYTES: i64 = 23;
+const DETECT_BYTEJUMP_MAX_BYTES: i64 = 8;
+
+const DETECT_BYTEJUMP_MAX_OFFSET: i32 = 65535;
+// bare "align" rounds up to 32 bits
+const DETECT_BYTEJUMP_ALIGN_DEFAULT: u8 = 4;
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct DetectBytejumpData {
+    pub nbytes: u8,
+    pub base: u8,
+    pub flags: u16,
+    pub offset: i32,
+    pub post_offset: i32,
+    pub multiplier: u16,
+    pub align: u8,
+    /// byte_extract variables, resolved when the rule is set up
+    pub nbytes_str: *const c_char,
+    pub offset_str: *const c_char,
+    pub post_offset_str: *const c_char,
+}
+
+impl Drop for DetectBytejumpData {
+    fn drop(&mut self) {
+        unsafe {
+            for s in [self.nbytes_str, self.offset_str, self.post_offset_str] {
+                if !s.is_null() {
+                    let _ = CString::from_raw(s as *mut c_char);
+                }
+            }
+        }
+    }
+}
+
+impl Default for DetectBytejumpData {
+    fn default() -> Self {
+        DetectBytejumpData {
+            nbytes: 0,
+            base: DETECT_BYTEJUMP_BASE_UNSET,
+            flags: 0,
+            offset: 0,
+            post_offset: 0,
+            multiplier: 1,
+            align: 0,
+            nbytes_str: std::ptr::null(),
+            offset_str: std::ptr::null(),
+            post_offset_str: std::ptr::null(),
+        }
+    }
+}
+
+fn var_name_to_c(name: &str) -> Result<*const c_char, Err<RuleParseError<&'static str>>> {
+    CString::new(name)
+        .map(|s| s.into_raw() as *const c_char)
+        .map_err(|_| {
+            Err::Error(RuleParseError::InvalidByteJump(
+                "parse string not safely convertible to C".to_string(),
+            ))
+        })
+}
+
+/// Parses a decimal offset within the range byte_jump accepts.
+fn parse_offset(s: &str) -> Option<i32> {
+    s.parse::<i32>()
+        .ok()
+        .filter(|v| (-DETECT_BYTEJUMP_MAX_OFFSET..=DETECT_BYTEJUMP_MAX_OFFSET).contains(v))
+}
+
+fn parse_bytejump(input: &str) -> Result<DetectBytejumpData, Err<RuleParseError<&'static str>>> {
+    // Inner utility function for easy error creation.
+    fn make_error(reason: String) -> Err<RuleParseError<&'static str>> {
+        Err::Error(RuleParseError::InvalidByteJump(reason))
+    }
+    let values: Vec<&str> = input.split(',').map(|v| v.trim()).collect();
+    if values.len() < DETECT_BYTEJUMP_FIXED_PARAM_COUNT
+        || values.len() > DETECT_BYTEJUMP_MAX_PARAM_COUNT
+    {
+        return Err(make_error(format!(
+            "Incorrect argument string; at least {} values must be specified but no more than {}: {:?}",
+            DETECT_BYTEJUMP_FIXED_PARAM_COUNT, DETECT_BYTEJUMP_MAX_PARAM_COUNT, input
+        )));
+    }
+    for (i, value) in values.iter().enumerate() {
+        if value.is_empty()
+            || (i < DETECT_BYTEJUMP_FIXED_PARAM_COUNT && value.contains(char::is_whitespace))
+        {
+            return Err(make_error(format!(
+                "invalid byte_jump argument: {:?}",
+                value
+            )));
+        }
+    }
+
+    let mut byte_jump = DetectBytejumpData::default();
+
+    let mut nbytes = 0;
+    if is_var_name(values[0]) {
+        byte_jump.nbytes_str = var_name_to_c(values[0])?;
+        byte_jump.flags |= DETECT_BYTEJUMP_NBYTES_VAR;
+    } else {
+        nbytes = values[0]
+            .parse::<u32>()
+            .map_err(|_| make_error(format!("Malformed number of bytes: {}", values[0])))?;
+    }
+
+    if is_var_name(values[1]) {
+        byte_jump.offset_str = var_name_to_c(values[1])?;
+    } else {
+        byte_jump.offset = parse_offset(values[1])
+            .ok_or_else(|| make_error(format!("Malformed offset: {}", values[1])))?;
+    }
+
+    let mut multiplier_set = false;
+    let mut post_offset_set = false;
+    for value in values.iter().skip(DETECT_BYTEJUMP_FIXED_PARAM_COUNT) {
+        let (name, arg) = match value.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, Some(arg.trim())),
+            None => (*value, None),
+        };
+        let lname = name.to_ascii_lowercase();
+        match (lname.as_str(), arg) {
+            ("multiplier", Some(arg)) => {
+                if multiplier_set {
+                    return Err(make_error("multiplier already set".to_string()));
+                }
+                byte_jump.multiplier = match arg.parse::<u16>() {
+                    Ok(mult) if mult > 0 => mult,
+                    _ => return Err(make_error(format!("Malformed multiplier: {}", arg))),
+                };
+                multiplier_set = true;
+            }
+            ("post_offset", Some(arg)) => {
+                if post_offset_set {
+                    return Err(make_error("post_offset already set".to_string()));
+                }
+                if is_var_name(arg) {
+                    byte_jump.post_offset_str = var_name_to_c(arg)?;
+                } else {
+                    byte_jump.post_offset = parse_offset(arg)
+                        .ok_or_else(|| make_error(format!("Malformed post_offset: {}", arg)))?;
+                }
+                post_offset_set = true;
+            }
+            ("align", Some(arg)) => {
+                if byte_jump.flags & DETECT_BYTEJUMP_ALIGN != 0 {
+                    return Err(make_error("align already set".to_string()));
+                }
+                byte_jump.align = match arg.parse::<u8>() {
+                    Ok(align) if align == 2 || align == 4 => align,
+                    _ => {
+                        return Err(make_error(format!(
+                            "invalid align value: must be 2 or 4: {}",
+                            arg
+                        )))
+                    }
+                };
+                byte_jump.flags |= DETECT_BYTEJUMP_ALIGN;
+            }
+            (_, Some(_)) => {
+                return Err(make_error(format!("Unknown option: \"{}\"", value)));
+            }
+            ("align", None) => {
+                if byte_jump.flags & DETECT_BYTEJUMP_ALIGN != 0 {
+                    return Err(make_error("align already set".to_string()));
+                }
+                byte_jump.align = DETECT_BYTEJUMP_ALIGN_DEFAULT;
+                byte_jump.flags |= DETECT_BYTEJUMP_ALIGN;
+            }
+            ("relative", None) if name == "relative" => {
+                byte_jump.flags |= DETECT_BYTEJUMP_RELATIVE;
+            }
+            ("string", None) => {
+                byte_jump.flags |= DETECT_BYTEJUMP_STRING;
+            }
+            ("from_beginning", None) => {
+                byte_jump.flags |= DETECT_BYTEJUMP_BEGIN;
+            }
+            ("from_end", None) => {
+                byte_jump.flags |= DETECT_BYTEJUMP_END;
+            }
+            ("oct" | "dec" | "hex", None) => {
+                byte_jump.base = get_string_value(&lname).map_err(make_error)? as u8;
+            }
+            ("big" | "little" | "dce", None) => match get_endian_value(&lname) {
+                Ok(ByteEndian::BigEndian) => {
+                    byte_jump.flags &= !DETECT_BYTEJUMP_LITTLE;
+                    byte_jump.flags |= DETECT_BYTEJUMP_BIG;
+                }
+                Ok(ByteEndian::LittleEndian) => byte_jump.flags |= DETECT_BYTEJUMP_LITTLE,
+                Ok(ByteEndian::EndianDCE) => byte_jump.flags |= DETECT_BYTEJUMP_DCE,
+                Err(e) => return Err(make_error(e)),
+            },
+            _ => {
+                return Err(make_error(format!("Unknown option: \"{}\"", value)));
+            }
+        }
+    }
+
+    if byte_jump.flags & DETECT_BYTEJUMP_BEGIN != 0 && byte_jump.flags & DETECT_BYTEJUMP_END != 0 {
+        return Err(make_error(
+            "'from_end' and 'from_beginning' cannot be used in the same byte_jump statement"
+                .to_string(),
+        ));
+    }
+    let string = byte_jump.flags & DETECT_BYTEJUMP_STRING != 0;
+    if !string && byte_jump.base != DETECT_BYTEJUMP_BASE_UNSET {
+        return Err(make_error(format!(
+            "Cannot use a base without \"string\": {}",
+            input
+        )));
+    }
+    if byte_jump.flags & DETECT_BYTEJUMP_NBYTES_VAR == 0 {
+        if !byte_jump_nbytes_valid(&byte_jump, i64::from(nbytes)) {
+            return Err(make_error(format!(
+                "Cannot jump by more than {} bytes {} \"string\": {}",
+                if string {
+                    DETECT_BYTEJUMP_MAX_STRING_BYTES
+                } else {
+                    DETECT_BYTEJUMP_MAX_BYTES
+                },
+                if string { "with" } else { "without" },
+                input
+            )));
+        }
+        byte_jump.nbytes = nbytes as u8;
+    }
+
+    Ok(byte_jump)
+}
+
+/// Checks if `nbytes` can be extracted, for a count from a variable.
+fn byte_jump_nbytes_valid(data: &DetectBytejumpData, nbytes: i64) -> bool {
+    let max = if data.flags & DETECT_BYTEJUMP_STRING != 0 {
+        DETECT_BYTEJUMP_MAX_STRING_BYTES
+    } else {
+        DETECT_BYTEJUMP_MAX_BYTES
+    };
+    (0..=max).contains(&nbytes)
+}
+
+/// Extracts the jump value from `input`, the bytes to extract.
+///
+/// Returns the value and the number of bytes it used, which may be less
+/// than the length of `input` for a string. Zero bytes extract a value of
+/// 0, to jump by the post_offset only.
+fn byte_jump_extract(data: &DetectBytejumpData, flags: u16, input: &[u8]) -> Option<(u64, usize)> {
+    if flags & DETECT_BYTEJUMP_STRING != 0 {
+        return parse_c_uint(input, u32::from(data.base));
+    }
+    if input.len() > 8 {
+        return None;
+    }
+    let value = if flags & DETECT_BYTEJUMP_LITTLE != 0 {
+        input
+            .iter()
+            .rev()
+            .fold(0u64, |acc, &b| (acc << 8) | u64::from(b))
+    } else {
+        input.iter().fold(0u64, |acc, &b| (acc << 8) | u64::from(b))
+    };
+    Some((value, input.len()))
+}
+
+/// Applies the multiplier, alignment and `post_offset` to an extracted
+/// value.
+///
+/// Returns None if the jump overflows, which is past the end of any buffer.
+fn byte_jump_distance(
+    data: &DetectBytejumpData, flags: u16, value: u64, post_offset: i64,
+) -> Option<i64> {
+    let mut value = i64::try_from(value)
+        .ok()?
+        .checked_mul(i64::from(data.multiplier))?;
+    if flags & DETECT_BYTEJUMP_ALIGN != 0 && data.align > 0 {
+        let align = i64::from(data.align);
+        if value % align != 0 {
+            value = value.checked_add(align - (value % align))?;
+        }
+    }
+    value.checked_add(post_offset)
+}
+
+/// Returns the offset in `payload` the inspection moves to, or None if the
+/// jump does not match.
+///
+/// `flags` are the ones of `data` with the endianness of dce resolved, and
+/// `nbytes`, `offset` and `post_offset` are the ones of `data` or the
+/// values of their variables.
+///
+/// A jump to before the start of the buffer moves to its start, a jump past
+/// its end does not match.
+fn byte_jump_match(
+    data: &DetectBytejumpData, flags: u16, payload: &[u8], buffer_offset: u32, nbytes: i64,
+    offset: i64, post_offset: i64,
+) -> Option<u32> {
+    if payload.is_empty() {
+        return None;
+    }
+    if flags & DETECT_BYTEJUMP_NBYTES_VAR != 0 && !byte_jump_nbytes_valid(data, nbytes) {
+        return None;
+    }
+    let len = payload.len() as i64;
+    let mut start = offset;
+    if flags & DETECT_BYTEJUMP_RELATIVE != 0 {
+        start += i64::from(buffer_offset);
+        // no match if there is no relative base
+        if nbytes != 0 && start >= len {
+            return None;
+        }
+    }
+    if start < 0 || start + nbytes > len {
+        return None;
+    }
+    let start = start as usize;
+    let input = &payload[start..start + nbytes as usize];
+    let (value, extbytes) = byte_jump_extract(data, flags, input)?;
+    let distance = byte_jump_distance(data, flags, value, post_offset)?;
+
+    let target = if flags & DETECT_BYTEJUMP_BEGIN != 0 {
+        distance
+    } else if flags & DETECT_BYTEJUMP_END != 0 {
+        len.checked_add(distance)?
+    } else {
+        (start as i64 + extbytes as i64).checked_add(distance)?
+    };
+    if target > len {
+        return None;
+    }
+    Some(target.max(0) as u32)
+}
+
+/// Intermediary function between the C code and the parsing functions.
+#[no_mangle]
+pub unsafe extern "C" fn SCByteJumpParse(c_arg: *const c_char) -> *mut DetectBytejumpData {
+    if c_arg.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let arg = match CStr::from_ptr(c_arg).to_str() {
+        Ok(arg) => arg,
+        Err(_) => {
+            return std::ptr::null_mut();
+        }
+    };
+    match parse_bytejump(arg) {
+        Ok(detect) => return Box::into_raw(Box::new(detect)),
+        Err(Err::Error(RuleParseError::InvalidByteJump(e))) => {
+            SCLogError!("{}", e);
+            return std::ptr::null_mut();
+        }
+        Err(_) => return std::ptr::null_mut(),
+    }
+}
+
+/// Jumps from the value extracted from `payload`, setting `new_offset` to
+/// the offset the inspection moves to on a match.
+///
+/// `flags` are the ones of `ctx` with the endianness of dce resolved, and
+/// `buffer_offset` is the offset a relative byte_jump starts from.
+#[no_mangle]
+pub unsafe extern "C" fn SCByteJumpMatch(
+    ctx: &DetectBytejumpData, flags: u16, payload: *const u8, payload_len: u32, buffer_offset: u32,
+    nbytes: i32, offset: i32, post_offset: i64, new_offset: *mut u32,
+) -> bool {
+    if payload.is_null() || new_offset.is_null() {
+        return false;
+    }
+    let payload = build_slice!(payload, payload_len as usize);
+    match byte_jump_match(
+        ctx,
+        flags,
+        payload,
+        buffer_offset,
+        i64::from(nbytes),
+        i64::from(offset),
+        post_offset,
+    ) {
+        Some(target) => {
+            *new_offset = target;
+            return true;
+        }
+        None => return false,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCByteJumpFree(ptr: *mut DetectBytejumpData) {
+    if !ptr.is_null() {
+        let _ = Box::from_raw(ptr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(s: *const c_char) -> &'static str {
+        unsafe { CStr::from_ptr(s).to_str().unwrap() }
+    }
+
+    #[test]
+    fn test_parser_valid() {
+        let bj = parse_bytejump("4, 0").unwrap();
+        assert_eq!(bj.nbytes, 4);
+        assert_eq!(bj.offset, 0);
+        assert_eq!(bj.flags, 0);
+        assert_eq!(bj.multiplier, 1);
+        assert_eq!(bj.post_offset, 0);
+        assert_eq!(bj.base, DETECT_BYTEJUMP_BASE_UNSET);
+
+        let bj =
+            parse_bytejump(" 4,0 , relative , little, string, dec, align, from_beginning").unwrap();
+        assert_eq!(
+            bj.flags,
+            DETECT_BYTEJUMP_RELATIVE
+                | DETECT_BYTEJUMP_LITTLE
+                | DETECT_BYTEJUMP_STRING
+                | DETECT_BYTEJUMP_ALIGN
+                | DETECT_BYTEJUMP_BEGIN
+        );
+        assert_eq!(bj.base, DETECT_BYTEJUMP_BASE_DEC);
+        assert_eq!(bj.align, 4);
+
+        let bj = parse_bytejump("4, -2, multiplier 2 , post_offset -16, align 2, big").unwrap();
+        assert_eq!(bj.offset, -2);
+        assert_eq!(bj.multiplier, 2);
+        assert_eq!(bj.post_offset, -16);
+        assert_eq!(bj.align, 2);
+        assert_eq!(bj.flags, DETECT_BYTEJUMP_ALIGN | DETECT_BYTEJUMP_BIG);
+
+        let bj = parse_bytejump("23, 0, string, oct, from_end, dce").unwrap();
+        assert_eq!(bj.nbytes, 23);
+        assert_eq!(
+            bj.flags,
+            DETECT_BYTEJUMP_STRING | DETECT_BYTEJUMP_END | DETECT_BYTEJUMP_DCE
+        );
+    }
+
+    #[test]
+    fn test_parser_vars() {
+        let bj = parse_bytejump("nb, off, relative, post_offset len").unwrap();
+        assert_eq!(
+            bj.flags,
+            DETECT_BYTEJUMP_NBYTES_VAR | DETECT_BYTEJUMP_RELATIVE
+        );
+        assert_eq!(var(bj.nbytes_str), "nb");
+        assert_eq!(var(bj.offset_str), "off");
+        assert_eq!(var(bj.post_offset_str), "len");
+
+        let bj = parse_bytejump("2, 0, post_offset -4").unwrap();
+        assert!(bj.nbytes_str.is_null());
+        assert!(bj.offset_str.is_null());
+        assert!(bj.post_offset_str.is_null());
+    }
+
+    #[test]
+    fn test_parser_invalid() {
+        for args in [
+            "4",
+            "9, 0",
+            "24, 0, string, dec",
+            "4, 0xffffffffffffffff",
+            "4, 65536",
+            "4, 0, dec",
+            "4, 0, from_beginning, from_end",
+            "4, 0, multiplier 0",
+            "4, 0, multiplier 65536",
+            "4, 0, multiplier",
+            "4, 0, multiplier 2, multiplier 2",
+            "4, 0, post_offset 65536",
+            "4, 0, post_offset 1, post_offset 1",
+            "4, 0, align 3",
+            "4, 0, align, align 4",
+            "4, 0, relative 1",
+            "4, 0, Relative",
+            "4, 0, bitmask 0x0f",
+            "4, 0, unknown",
+            "4 4, 0",
+            "4, 0,",
+        ] {
+            assert!(parse_bytejump(args).is_err(), "{}", args);
+        }
+    }
+
+    fn jump(args: &str, payload: &[u8], buffer_offset: u32) -> Option<u32> {
+        let bj = parse_bytejump(args).unwrap();
+        byte_jump_match(
+            &bj,
+            bj.flags,
+            payload,
+            buffer_offset,
+            i64::from(bj.nbytes),
+            i64::from(bj.offset),
+            i64::from(bj.post_offset),
+        )
+    }
+
+    #[test]
+    fn test_byte_jump_literal() {
+        let payload = [0x00, 0x02, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+        // the jump is from after the extracted bytes
+        assert_eq!(jump("2, 0", &payload, 0), Some(4));
+        assert_eq!(jump("1, 1", &payload, 0), Some(4));
+        assert_eq!(jump("2, 0, little", &[0x02, 0x00, 0, 0, 0, 0], 0), Some(4));
+        assert_eq!(jump("2, 0, multiplier 2", &payload, 0), Some(6));
+        assert_eq!(jump("2, 0, post_offset -1", &payload, 0), Some(3));
+        assert_eq!(jump("2, 0, from_beginning", &payload, 0), Some(2));
+        assert_eq!(jump("0, 0, from_end, post_offset -3", &payload, 0), Some(5));
+        // relative to the previous match
+        assert_eq!(jump("1, 0, relative", &payload, 1), Some(4));
+        assert_eq!(jump("1, -1, relative", &payload, 2), Some(4));
+    }
+
+    #[test]
+    fn test_byte_jump_align() {
+        let payload = [0x01, 0, 0, 0, 0, 0, 0, 0];
+        // 1 rounded up to 4, then from after the byte
+        assert_eq!(jump("1, 0, align", &payload, 0), Some(5));
+        assert_eq!(jump("1, 0, align 2", &payload, 0), Some(3));
+        assert_eq!(jump("1, 0, align 4, from_beginning", &payload, 0), Some(4));
+        // 0 is aligned already
+        assert_eq!(jump("1, 1, align", &payload, 0), Some(2));
+    }
+
+    #[test]
+    fn test_byte_jump_string() {
+        assert_eq!(jump("2, 0, string, dec", b"04abcdef", 0), Some(6));
+        assert_eq!(jump("1, 0, string, hex", b"aabcdefghijk", 0), Some(11));
+        // the jump is from after the digits only
+        assert_eq!(jump("3, 0, string, dec", b"2 abcdef", 0), Some(3));
+        assert_eq!(jump("2, 0, string, dec", b"ab", 0), None);
+    }
+
+    #[test]
+    fn test_byte_jump_variable() {
+        let payload = [0x00, 0x03, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+        // jump by an extracted value only
+        let bj = parse_bytejump("0, 0, relative, post_offset len").unwrap();
+        assert_eq!(
+            byte_jump_match(&bj, bj.flags, &payload, 2, 0, 0, 3),
+            Some(5)
+        );
+        assert_eq!(
+            byte_jump_match(&bj, bj.flags, &payload, 2, 0, 0, -1),
+            Some(1)
+        );
+        assert_eq!(byte_jump_match(&bj, bj.flags, &payload, 2, 0, 0, 7), None);
+        // number of bytes and offset from variables
+        let bj = parse_bytejump("nb, off").unwrap();
+        assert_eq!(
+            byte_jump_match(&bj, bj.flags, &payload, 0, 2, 0, 0),
+            Some(5)
+        );
+        assert_eq!(
+            byte_jump_match(&bj, bj.flags, &payload, 0, 1, 1, 0),
+            Some(5)
+        );
+        // a count from a variable is checked at match time
+        assert_eq!(byte_jump_match(&bj, bj.flags, &payload, 0, 9, 0, 0), None);
+        assert_eq!(byte_jump_match(&bj, bj.flags, &payload, 0, -1, 0, 0), None);
+    }
+
+    #[test]
+    fn test_byte_jump_past_end() {
+        let payload = [0x00, 0x10, 0xaa, 0xbb];
+        assert_eq!(jump("2, 0", &payload, 0), None);
+        assert_eq!(jump("2, 0, from_beginning", &payload, 0), None);
+        assert_eq!(jump("0, 0, from_end, post_offset 1", &payload, 0), None);
+        // exactly the end of the buffer
+        assert_eq!(jump("2, 0, post_offset -14", &payload, 0), Some(4));
+        assert_eq!(jump("0, 0, from_end", &payload, 0), Some(4));
+        // the value does not fit in the buffer
+        assert_eq!(jump("4, 2", &payload, 0), None);
+        assert_eq!(jump("1, 0, relative", &payload, 4), None);
+        assert_eq!(jump("8, 0, multiplier 2", &[0xff; 8], 0), None);
+        assert_eq!(jump("2, 0", &[], 0), None);
+    }
+
+    #[test]
+    fn test_byte_jump_before_start() {
+        let payload = [0x00, 0x01, 0xaa, 0xbb];
+        // a jump before the start of the buffer moves to its start
+        assert_eq!(jump("2, 0, post_offset -10", &payload, 0), Some(0));
+        assert_eq!(
+            jump("0, 0, from_end, post_offset -10", &payload, 0),
+            Some(0)
+        );
+        // but the value must be in the buffer
+        assert_eq!(jump("2, -1", &payload, 0), None);
+    }
+
+    #[test]
+    fn test_byte_jump_dce() {
+        // dce is little or big endian depending on the DCE/RPC header
+        let bj = parse_bytejump("2, 0, dce").unwrap();
+        let payload = [0x02, 0x00, 0, 0, 0, 0];
+        let le = bj.flags | DETECT_BYTEJUMP_LITTLE;
+        assert_eq!(byte_jump_match(&bj, le, &payload, 0, 2, 0, 0), Some(4));
+        assert_eq!(byte_jump_match(&bj, bj.flags, &payload, 0, 2, 0, 0), None);
+    }
+}
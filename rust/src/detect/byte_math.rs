@@ -17,8 +17,8 @@
 
 // Author: Jeff Lucovsky <jlucovsky@oisf.net>
 
-use crate::detect::error::RuleParseError;
-use crate::detect::parser::{parse_var, take_until_whitespace, ResultValue};
+use crate::detect::error::{DetectParseError, RuleParseError};
+use crate::detect::parser::{parse_digits, parse_var, take_until_whitespace, ResultValue};
 use crate::detect::{get_endian_value, get_string_value, ByteBase, ByteEndian};
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
@@ -35,6 +35,7 @@ pub const DETECT_BYTEMATH_FLAG_BITMASK: u8 = 0x04;
 pub const DETECT_BYTEMATH_FLAG_ENDIAN: u8 = 0x08;
 pub const DETECT_BYTEMATH_FLAG_RVALUE_VAR: u8 = 0x10;
 pub const DETECT_BYTEMATH_FLAG_NBYTES_VAR: u8 = 0x20;
+pub const DETECT_BYTEMATH_FLAG_OVERFLOW: u8 = 0x40;
 
 // Ensure required values are provided
 const DETECT_BYTEMATH_FLAG_NBYTES: u8 = 0x1;
@@ -50,7 +51,7 @@ const DETECT_BYTEMATH_FLAG_REQUIRED: u8 = DETECT_BYTEMATH_FLAG_RESULT
 
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-// operators: +, -, /, *, <<, >>
+// operators: +, -, /, *, <<, >>, &, |, ^
 pub enum ByteMathOperator {
     OperatorNone = 1,
     Addition = 2,
@@ -59,6 +60,18 @@ pub enum ByteMathOperator {
     Multiplication = 5,
     LeftShift = 6,
     RightShift = 7,
+    BitwiseAnd = 8,
+    BitwiseOr = 9,
+    BitwiseXor = 10,
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+// what to do when the result does not fit in 64 bits
+pub enum ByteMathOverflow {
+    OverflowWrap = 0,
+    OverflowSaturate = 1,
+    OverflowError = 2,
 }
 
 pub const DETECT_BYTEMATH_ENDIAN_DEFAULT: ByteEndian = ByteEndian::BigEndian;
@@ -69,8 +82,8 @@ const BASE_DEFAULT: ByteBase = ByteBase::BaseDec;
 // result is not parsed with the fixed position parameters as it's
 // often swapped with optional parameters
 pub const DETECT_BYTEMATH_FIXED_PARAM_COUNT: usize = 5;
-// Optional parameters: endian, relative, string, dce, bitmask
-pub const DETECT_BYTEMATH_MAX_PARAM_COUNT: usize = 10;
+// Optional parameters: endian, relative, string, dce, bitmask, overflow
+pub const DETECT_BYTEMATH_MAX_PARAM_COUNT: usize = 11;
 
 #[repr(C)]
 #[derive(Debug)]
@@ -89,6 +102,7 @@ pub struct DetectByteMathData {
     oper: ByteMathOperator,
     endian: ByteEndian, // big, little, dce
     base: ByteBase,     // From string or dce
+    overflow: ByteMathOverflow,
 }
 
 impl Drop for DetectByteMathData {
@@ -124,6 +138,7 @@ impl Default for DetectByteMathData {
             bitmask_val: 0,
             bitmask_shift_count: 0,
             id: 0,
+            overflow: ByteMathOverflow::OverflowWrap,
         }
     }
 }
@@ -144,17 +159,57 @@ fn get_oper_value(value: &str) -> Result<ByteMathOperator, ()> {
         "*" => ByteMathOperator::Multiplication,
         "<<" => ByteMathOperator::LeftShift,
         ">>" => ByteMathOperator::RightShift,
+        "&" => ByteMathOperator::BitwiseAnd,
+        "|" => ByteMathOperator::BitwiseOr,
+        "^" => ByteMathOperator::BitwiseXor,
         _ => return Err(()),
     };
 
     Ok(res)
 }
 
+fn get_overflow_value(value: &str) -> Result<ByteMathOverflow, ()> {
+    let res = match value {
+        "wrap" => ByteMathOverflow::OverflowWrap,
+        "saturate" => ByteMathOverflow::OverflowSaturate,
+        "error" => ByteMathOverflow::OverflowError,
+        _ => return Err(()),
+    };
+
+    Ok(res)
+}
+
+/// Parses an integer constant with a base prefix, `0x` for hex or `0o` for
+/// octal, returning None for anything else, like a decimal value or a
+/// variable name. Digits invalid for the base are an error.
+fn parse_base_literal(val: &str) -> Option<Result<u64, String>> {
+    let prefix = val.get(..2)?.to_ascii_lowercase();
+    let base = match prefix.as_str() {
+        "0x" => get_string_value("hex").ok()?,
+        "0o" => get_string_value("oct").ok()?,
+        _ => return None,
+    };
+    let digits = &val[2..];
+    let radix = base as u32;
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return Some(Err(format!("invalid digits for base {}: {}", radix, val)));
+    }
+    Some(u64::from_str_radix(digits, radix).map_err(|_| format!("value too large: {}", val)))
+}
+
 fn parse_bytemath(input: &str) -> IResult<&str, DetectByteMathData, RuleParseError<&str>> {
     // Inner utility function for easy error creation.
     fn make_error(reason: String) -> nom7::Err<RuleParseError<&'static str>> {
         Err::Error(RuleParseError::InvalidByteMath(reason))
     }
+    // For a `token` of `input` which is not valid here.
+    fn unexpected(input: &str, token: &str) -> nom7::Err<RuleParseError<&'static str>> {
+        Err::Error(RuleParseError::UnexpectedToken(DetectParseError::new(
+            "byte_math",
+            input,
+            token,
+        )))
+    }
     let (_, values) = nom7::multi::separated_list1(
         tag(","),
         preceded(multispace0, nom7::bytes::complete::is_not(",")),
@@ -179,12 +234,7 @@ fn parse_bytemath(input: &str) -> IResult<&str, DetectByteMathData, RuleParseErr
                 if 0 != (required_flags & DETECT_BYTEMATH_FLAG_OPER) {
                     return Err(make_error("operator already set".to_string()));
                 }
-                byte_math.oper = match get_oper_value(val) {
-                    Ok(val) => val,
-                    Err(_) => {
-                        return Err(make_error(format!("unknown oper value {}", val)));
-                    }
-                };
+                byte_math.oper = get_oper_value(val).map_err(|_| unexpected(input, val))?;
                 required_flags |= DETECT_BYTEMATH_FLAG_OPER;
             }
             "result" => {
@@ -210,7 +260,12 @@ fn parse_bytemath(input: &str) -> IResult<&str, DetectByteMathData, RuleParseErr
                 if 0 != (required_flags & DETECT_BYTEMATH_FLAG_RVALUE) {
                     return Err(make_error("rvalue already set".to_string()));
                 }
-                let (_, res) = parse_var(val)?;
+                let res = match parse_base_literal(val) {
+                    Some(literal) => ResultValue::Numeric(
+                        literal.map_err(|e| make_error(format!("invalid rvalue value: {}", e)))?,
+                    ),
+                    None => parse_var(val)?.1,
+                };
                 match res {
                     ResultValue::Numeric(val) => {
                         if val >= u64::from(u32::MIN) && val <= u64::from(u32::MAX) {
@@ -242,11 +297,7 @@ fn parse_bytemath(input: &str) -> IResult<&str, DetectByteMathData, RuleParseErr
                 if 0 != (byte_math.flags & DETECT_BYTEMATH_FLAG_ENDIAN) {
                     return Err(make_error("endianess already set".to_string()));
                 }
-                if let Some(endian) = get_endian_value(val) {
-                    byte_math.endian = endian;
-                } else {
-                    return Err(make_error(format!("invalid endian value: {}", val)));
-                };
+                byte_math.endian = get_endian_value(val).map_err(|_| unexpected(input, val))?;
                 byte_math.flags |= DETECT_BYTEMATH_FLAG_ENDIAN;
             }
             "dce" => {
@@ -260,11 +311,7 @@ fn parse_bytemath(input: &str) -> IResult<&str, DetectByteMathData, RuleParseErr
                 if 0 != (byte_math.flags & DETECT_BYTEMATH_FLAG_STRING) {
                     return Err(make_error("string already set".to_string()));
                 }
-                if let Some(base) = get_string_value(val) {
-                    byte_math.base = base;
-                } else {
-                    return Err(make_error(format!("invalid string value: {}", val)));
-                };
+                byte_math.base = get_string_value(val).map_err(|_| unexpected(input, val))?;
                 byte_math.flags |= DETECT_BYTEMATH_FLAG_STRING;
             }
             "relative" => {
@@ -288,13 +335,46 @@ fn parse_bytemath(input: &str) -> IResult<&str, DetectByteMathData, RuleParseErr
                 byte_math.bitmask_val = val;
                 byte_math.flags |= DETECT_BYTEMATH_FLAG_BITMASK;
             }
+            "overflow" => {
+                if 0 != (byte_math.flags & DETECT_BYTEMATH_FLAG_OVERFLOW) {
+                    return Err(make_error("overflow already set".to_string()));
+                }
+                byte_math.overflow = get_overflow_value(val).map_err(|_| unexpected(input, val))?;
+                byte_math.flags |= DETECT_BYTEMATH_FLAG_OVERFLOW;
+            }
             "offset" => {
                 if 0 != (required_flags & DETECT_BYTEMATH_FLAG_OFFSET) {
                     return Err(make_error("offset already set".to_string()));
                 }
-                byte_math.offset = val
-                    .parse::<i32>()
-                    .map_err(|_| make_error(format!("invalid offset value: {}", val)))?;
+                let (negative, unsigned) = match val.strip_prefix('-') {
+                    Some(unsigned) => (true, unsigned),
+                    None => (false, val),
+                };
+                byte_math.offset = match parse_base_literal(unsigned) {
+                    Some(literal) => {
+                        let offset = literal
+                            .map_err(|e| make_error(format!("invalid offset value: {}", e)))?;
+                        let offset = i32::try_from(offset)
+                            .map_err(|_| make_error(format!("invalid offset value: {}", val)))?;
+                        if negative {
+                            -offset
+                        } else {
+                            offset
+                        }
+                    }
+                    None => {
+                        let offset = match parse_digits::<RuleParseError<&str>>(unsigned) {
+                            Ok(("", digits)) => digits.parse::<i32>().ok(),
+                            _ => None,
+                        }
+                        .ok_or_else(|| make_error(format!("invalid offset value: {}", val)))?;
+                        if negative {
+                            -offset
+                        } else {
+                            offset
+                        }
+                    }
+                };
                 if byte_math.offset > 65535 || byte_math.offset < -65535 {
                     return Err(make_error(format!(
                         "invalid offset value: must be between -65535 and 65535: {}",
@@ -334,7 +414,7 @@ fn parse_bytemath(input: &str) -> IResult<&str, DetectByteMathData, RuleParseErr
                 required_flags |= DETECT_BYTEMATH_FLAG_NBYTES;
             }
             _ => {
-                return Err(make_error(format!("unknown byte_math keyword: {}", name)));
+                return Err(unexpected(input, name));
             }
         };
     }
@@ -361,6 +441,40 @@ fn parse_bytemath(input: &str) -> IResult<&str, DetectByteMathData, RuleParseErr
     Ok((input, byte_math))
 }
 
+/// Applies the operator of `data` to the extracted `val`.
+///
+/// Returns None when there is no result: on a division by zero, or on an
+/// overflow with `overflow error`.
+fn byte_math_compute(data: &DetectByteMathData, val: u64, rvalue: u64) -> Option<u64> {
+    let (res, overflow) = match data.oper {
+        ByteMathOperator::OperatorNone => (val, false),
+        ByteMathOperator::Addition => val.overflowing_add(rvalue),
+        ByteMathOperator::Subtraction => val.overflowing_sub(rvalue),
+        ByteMathOperator::Division => return val.checked_div(rvalue),
+        ByteMathOperator::Multiplication => val.overflowing_mul(rvalue),
+        ByteMathOperator::LeftShift => {
+            // shifting by 64 or more gives 0, the bits shifted out overflow
+            let res = if rvalue < 64 { val << rvalue } else { 0 };
+            (res, val != 0 && (rvalue >= 64 || res >> rvalue != val))
+        }
+        ByteMathOperator::RightShift => (if rvalue < 64 { val >> rvalue } else { 0 }, false),
+        ByteMathOperator::BitwiseAnd => (val & rvalue, false),
+        ByteMathOperator::BitwiseOr => (val | rvalue, false),
+        ByteMathOperator::BitwiseXor => (val ^ rvalue, false),
+    };
+    if !overflow {
+        return Some(res);
+    }
+    match data.overflow {
+        ByteMathOverflow::OverflowWrap => Some(res),
+        ByteMathOverflow::OverflowSaturate => match data.oper {
+            ByteMathOperator::Subtraction => Some(0),
+            _ => Some(u64::MAX),
+        },
+        ByteMathOverflow::OverflowError => None,
+    }
+}
+
 /// Intermediary function between the C code and the parsing functions.
 #[no_mangle]
 pub unsafe extern "C" fn SCByteMathParse(c_arg: *const c_char) -> *mut DetectByteMathData {
@@ -376,10 +490,34 @@ pub unsafe extern "C" fn SCByteMathParse(c_arg: *const c_char) -> *mut DetectByt
     };
     match parse_bytemath(arg) {
         Ok((_, detect)) => return Box::into_raw(Box::new(detect)),
+        Err(Err::Error(RuleParseError::UnexpectedToken(e))) => {
+            SCLogError!("{}", e);
+            return std::ptr::null_mut();
+        }
+        Err(Err::Error(RuleParseError::InvalidByteMath(e))) => {
+            SCLogError!("{}", e);
+            return std::ptr::null_mut();
+        }
         Err(_) => return std::ptr::null_mut(),
     }
 }
 
+/// Computes the result of `ctx` for the extracted `val` into `result`.
+///
+/// Returns false if there is no result, so no match.
+#[no_mangle]
+pub unsafe extern "C" fn SCByteMathCompute(
+    ctx: &DetectByteMathData, val: u64, rvalue: u64, result: *mut u64,
+) -> bool {
+    match byte_math_compute(ctx, val, rvalue) {
+        Some(res) => {
+            *result = res;
+            return true;
+        }
+        None => return false,
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn SCByteMathFree(ptr: *mut DetectByteMathData) {
     if !ptr.is_null() {
@@ -430,6 +568,7 @@ mod tests {
                 && self.bitmask_val == other.bitmask_val
                 && self.bitmask_shift_count == other.bitmask_shift_count
                 && self.id == other.id
+                && self.overflow == other.overflow
         }
     }
 
@@ -731,6 +870,41 @@ mod tests {
         assert_eq!(val, bmd);
     }
 
+    #[test]
+    fn test_parser_base_literals() {
+        let (_, val) =
+            parse_bytemath("bytes 4, offset 0x10, oper +, rvalue 17, result foo").unwrap();
+        assert_eq!(val.offset, 16);
+        assert_eq!(val.rvalue, 17);
+        let (_, val) =
+            parse_bytemath("bytes 4, offset 12, oper *, rvalue 0o17, result foo").unwrap();
+        assert_eq!(val.offset, 12);
+        assert_eq!(val.rvalue, 15);
+        let (_, val) =
+            parse_bytemath("bytes 4, offset -0X1f, oper &, rvalue 0xFFFF0000, result foo").unwrap();
+        assert_eq!(val.offset, -31);
+        assert_eq!(val.rvalue, 0xffff0000);
+        assert_eq!(val.flags & DETECT_BYTEMATH_FLAG_RVALUE_VAR, 0);
+        // a decimal value with a leading zero is not octal
+        let (_, val) =
+            parse_bytemath("bytes 4, offset 010, oper +, rvalue 017, result foo").unwrap();
+        assert_eq!(val.offset, 10);
+        assert_eq!(val.rvalue, 17);
+    }
+
+    #[test]
+    fn test_parser_base_literals_invalid() {
+        assert!(parse_bytemath("bytes 4, offset 0, oper +, rvalue 0x1g, result foo").is_err());
+        assert!(parse_bytemath("bytes 4, offset 0, oper +, rvalue 0o18, result foo").is_err());
+        assert!(parse_bytemath("bytes 4, offset 0, oper +, rvalue 0x, result foo").is_err());
+        assert!(parse_bytemath("bytes 4, offset 0, oper +, rvalue 0x+1, result foo").is_err());
+        assert!(
+            parse_bytemath("bytes 4, offset 0, oper +, rvalue 0x100000000, result foo").is_err()
+        );
+        assert!(parse_bytemath("bytes 4, offset 0o9, oper +, rvalue 1, result foo").is_err());
+        assert!(parse_bytemath("bytes 4, offset 0x10000, oper +, rvalue 1, result foo").is_err());
+    }
+
     #[test]
     fn test_parser_endian_invalid() {
         assert!(parse_bytemath(
@@ -800,12 +974,80 @@ mod tests {
         let (_, val) =
             parse_bytemath("bytes 4, offset 3933, oper <<, rvalue myrvalue, result foo").unwrap();
         assert_eq!(val, bmd);
+
+        bmd.oper = ByteMathOperator::BitwiseAnd;
+        let (_, val) =
+            parse_bytemath("bytes 4, offset 3933, oper &, rvalue myrvalue, result foo").unwrap();
+        assert_eq!(val, bmd);
+
+        bmd.oper = ByteMathOperator::BitwiseOr;
+        let (_, val) =
+            parse_bytemath("bytes 4, offset 3933, oper |, rvalue myrvalue, result foo").unwrap();
+        assert_eq!(val, bmd);
+
+        bmd.oper = ByteMathOperator::BitwiseXor;
+        let (_, val) =
+            parse_bytemath("bytes 4, offset 3933, oper ^, rvalue myrvalue, result foo").unwrap();
+        assert_eq!(val, bmd);
+    }
+
+    #[test]
+    fn test_parser_oper_shift() {
+        for (args, oper, rvalue) in [
+            (
+                "bytes 2, offset 0, oper <<, rvalue 4, result x",
+                ByteMathOperator::LeftShift,
+                4,
+            ),
+            (
+                "bytes 2, offset 0, oper >>, rvalue 20, result x",
+                ByteMathOperator::RightShift,
+                20,
+            ),
+            (
+                "bytes 2, offset 0, oper >>, rvalue 4294967295, result x",
+                ByteMathOperator::RightShift,
+                u32::MAX,
+            ),
+        ] {
+            let (_, val) = parse_bytemath(args).unwrap();
+            assert_eq!(val.oper, oper);
+            assert_eq!(val.rvalue, rvalue);
+        }
+        let (_, val) =
+            parse_bytemath("bytes 2, offset 0, oper <<, rvalue shift, result x").unwrap();
+        assert_eq!(val.flags, DETECT_BYTEMATH_FLAG_RVALUE_VAR);
+        assert!(parse_bytemath("bytes 5, offset 0, oper >>, rvalue 4, result x").is_err());
+    }
+
+    #[test]
+    fn test_parser_oper_bitwise_literal() {
+        let bmd = DetectByteMathData {
+            nbytes: 1,
+            offset: 0,
+            oper: ByteMathOperator::BitwiseAnd,
+            rvalue: 15,
+            result: CString::new("low").unwrap().into_raw(),
+            endian: ByteEndian::BigEndian,
+            base: BASE_DEFAULT,
+            ..Default::default()
+        };
+        let (_, val) = parse_bytemath("bytes 1, offset 0, oper &, rvalue 15, result low").unwrap();
+        assert_eq!(val, bmd);
+
+        // bitwise operators are not restricted to 4 bytes like the shifts
+        let (_, val) =
+            parse_bytemath("bytes 10, offset 0, oper ^, rvalue 255, result x, string dec").unwrap();
+        assert_eq!(val.oper, ByteMathOperator::BitwiseXor);
+        assert_eq!(val.nbytes, 10);
     }
 
     #[test]
     fn test_parser_oper_invalid() {
         assert!(parse_bytemath("bytes 4, offset 0, oper !, rvalue myvalue, result foo").is_err());
-        assert!(parse_bytemath("bytes 4, offset 0, oper ^, rvalue myvalue, result foo").is_err());
+        assert!(parse_bytemath("bytes 4, offset 0, oper &&, rvalue myvalue, result foo").is_err());
+        assert!(parse_bytemath("bytes 4, offset 0, oper ||, rvalue myvalue, result foo").is_err());
+        assert!(parse_bytemath("bytes 4, offset 0, oper ~, rvalue myvalue, result foo").is_err());
         assert!(parse_bytemath("bytes 4, offset 0, oper <>, rvalue myvalue, result foo").is_err());
         assert!(parse_bytemath("bytes 4, offset 0, oper ><, rvalue myvalue, result foo").is_err());
         assert!(parse_bytemath("bytes 4, offset 0, oper <, rvalue myvalue, result foo").is_err());
@@ -831,6 +1073,11 @@ mod tests {
                 .unwrap();
         assert_eq!(val, bmd);
 
+        let (_, val) =
+            parse_bytemath("bytes 4, offset 47_303, oper *, rvalue 4_294_967_295, result foo")
+                .unwrap();
+        assert_eq!(val, bmd);
+
         bmd.rvalue = 1;
         let (_, val) =
             parse_bytemath("bytes 4, offset 47303, oper *, rvalue 1, result foo").unwrap();
@@ -872,6 +1119,11 @@ mod tests {
         let (_, val) =
             parse_bytemath("bytes 4, offset 65535, oper *, rvalue myrvalue, result foo").unwrap();
         assert_eq!(val, bmd);
+
+        bmd.offset = -65_535;
+        let (_, val) =
+            parse_bytemath("bytes 4, offset -65_535, oper *, rvalue myrvalue, result foo").unwrap();
+        assert_eq!(val, bmd);
     }
 
     #[test]
@@ -883,6 +1135,13 @@ mod tests {
         assert!(
             parse_bytemath("bytes 4, offset 70000, oper +, rvalue myvalue, result foo").is_err()
         );
+        for offset in ["_10", "10_", "1__0", "-_10"] {
+            let args = format!(
+                "bytes 4, offset {}, oper +, rvalue myvalue, result foo",
+                offset
+            );
+            assert!(parse_bytemath(&args).is_err(), "{}", offset);
+        }
     }
 
     #[test]
@@ -922,6 +1181,34 @@ mod tests {
             parse_bytemath("bytes 4, offset 0, oper +, rvalue myrvalue, endian endian").is_err()
         );
     }
+
+    fn unexpected_token(args: &str) -> DetectParseError {
+        match parse_bytemath(args) {
+            Err(Err::Error(RuleParseError::UnexpectedToken(e))) => e,
+            _ => panic!("no unexpected token in {}", args),
+        }
+    }
+
+    #[test]
+    fn test_parser_unexpected_token() {
+        let e = unexpected_token("bytes 4, foo 1, offset 0, oper +, rvalue 1, result bar");
+        assert_eq!(
+            e.to_string(),
+            "keyword 'byte_math': unexpected token 'foo' at offset 9"
+        );
+        let e = unexpected_token("bytes 4,  offset 0, oper ><, rvalue 1, result bar");
+        assert_eq!(
+            e.to_string(),
+            "keyword 'byte_math': unexpected token '><' at offset 25"
+        );
+        let e = unexpected_token("bytes 4, offset 0, oper +, rvalue 1, result bar, endian middle");
+        assert_eq!(e.token, "middle");
+        assert_eq!(e.offset, 56);
+        let e = unexpected_token("bytes 4, offset 0, oper +, rvalue 1, result bar, overflow no");
+        assert_eq!(e.token, "no");
+        assert_eq!(e.offset, 58);
+    }
+
     #[test]
     fn test_parser_multiple() {
         assert!(parse_bytemath(
@@ -943,4 +1230,93 @@ mod tests {
         )
         .is_err());
     }
+
+    #[test]
+    fn test_parser_overflow() {
+        let (_, bmd) = parse_bytemath("bytes 4, offset 0, oper +, rvalue 1, result foo").unwrap();
+        assert_eq!(bmd.overflow, ByteMathOverflow::OverflowWrap);
+        for (mode, overflow) in [
+            ("wrap", ByteMathOverflow::OverflowWrap),
+            ("saturate", ByteMathOverflow::OverflowSaturate),
+            ("error", ByteMathOverflow::OverflowError),
+        ] {
+            let args = format!(
+                "bytes 4, offset 0, oper +, rvalue 1, result foo, overflow {}",
+                mode
+            );
+            let (_, bmd) = parse_bytemath(&args).unwrap();
+            assert_eq!(bmd.overflow, overflow);
+        }
+        assert!(
+            parse_bytemath("bytes 4, offset 0, oper +, rvalue 1, result foo, overflow clamp")
+                .is_err()
+        );
+        assert!(parse_bytemath(
+            "bytes 4, offset 0, oper +, rvalue 1, result foo, overflow wrap, overflow error"
+        )
+        .is_err());
+        assert!(parse_bytemath(
+            "bytes 4, offset 0, oper +, rvalue 1, result foo, endian big, relative, \
+             string dec, bitmask 0xff, overflow error"
+        )
+        .is_ok());
+    }
+
+    fn compute(oper: &str, overflow: &str, val: u64, rvalue: u64) -> Option<u64> {
+        let args = format!(
+            "bytes 4, offset 0, oper {}, rvalue 1, result foo, overflow {}",
+            oper, overflow
+        );
+        let (_, bmd) = parse_bytemath(&args).unwrap();
+        byte_math_compute(&bmd, val, rvalue)
+    }
+
+    #[test]
+    fn test_compute_overflow_wrap() {
+        let max32 = u64::from(u32::MAX);
+        assert_eq!(compute("+", "wrap", max32, 1), Some(max32 + 1));
+        assert_eq!(compute("*", "wrap", max32, max32), Some(max32 * max32));
+        assert_eq!(compute("<<", "wrap", max32, 32), Some(max32 << 32));
+        assert_eq!(compute("+", "wrap", u64::MAX, 1), Some(0));
+        assert_eq!(compute("-", "wrap", 0, 1), Some(u64::MAX));
+        assert_eq!(compute("*", "wrap", u64::MAX, 2), Some(u64::MAX - 1));
+        assert_eq!(compute("<<", "wrap", max32 + 1, 32), Some(0));
+        assert_eq!(compute("<<", "wrap", 1, 64), Some(0));
+    }
+
+    #[test]
+    fn test_compute_overflow_saturate() {
+        let max32 = u64::from(u32::MAX);
+        assert_eq!(compute("+", "saturate", max32, 1), Some(max32 + 1));
+        assert_eq!(compute("<<", "saturate", max32, 32), Some(max32 << 32));
+        assert_eq!(compute("+", "saturate", u64::MAX - 1, 1), Some(u64::MAX));
+        assert_eq!(compute("+", "saturate", u64::MAX, u64::MAX), Some(u64::MAX));
+        assert_eq!(compute("-", "saturate", 0, 1), Some(0));
+        assert_eq!(compute("-", "saturate", max32, u64::MAX), Some(0));
+        assert_eq!(
+            compute("*", "saturate", max32 + 1, max32 + 1),
+            Some(u64::MAX)
+        );
+        assert_eq!(compute("<<", "saturate", max32 + 1, 32), Some(u64::MAX));
+        assert_eq!(compute("<<", "saturate", 1, 64), Some(u64::MAX));
+        assert_eq!(compute("<<", "saturate", 0, 64), Some(0));
+    }
+
+    #[test]
+    fn test_compute_overflow_error() {
+        let max32 = u64::from(u32::MAX);
+        assert_eq!(compute("+", "error", max32, 1), Some(max32 + 1));
+        assert_eq!(compute("*", "error", max32, max32), Some(max32 * max32));
+        assert_eq!(compute("-", "error", 1, 1), Some(0));
+        assert_eq!(compute("+", "error", u64::MAX, 1), None);
+        assert_eq!(compute("-", "error", 0, 1), None);
+        assert_eq!(compute("*", "error", max32 + 1, max32 + 1), None);
+        assert_eq!(compute("<<", "error", 1 << 63, 1), None);
+        assert_eq!(compute("<<", "error", 1, 63), Some(1 << 63));
+        // no overflow possible for the other operators
+        assert_eq!(compute("/", "error", u64::MAX, 1), Some(u64::MAX));
+        assert_eq!(compute("/", "error", u64::MAX, 0), None);
+        assert_eq!(compute(">>", "error", u64::MAX, 64), Some(0));
+        assert_eq!(compute("|", "error", u64::MAX, 1), Some(u64::MAX));
+    }
 }
@@ -0,0 +1,664 @@
+/* Copyright (C) 2026 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use crate::detect::error::RuleParseError;
+use crate::detect::{get_endian_value, get_string_value, ByteEndian};
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+use nom7::Err;
+
+pub const DETECT_BYTETEST_OP_LT: u8 = 1; // "less than" operator
+pub const DETECT_BYTETEST_OP_GT: u8 = 2; // "greater than" operator
+pub const DETECT_BYTETEST_OP_EQ: u8 = 3; // "equals" operator
+pub const DETECT_BYTETEST_OP_AND: u8 = 4; // "bitwise and" operator
+pub const DETECT_BYTETEST_OP_OR: u8 = 5; // "bitwise or" operator, written ^
+pub const DETECT_BYTETEST_OP_GE: u8 = 6; // greater than equal operator
+pub const DETECT_BYTETEST_OP_LE: u8 = 7; // less than equal operator
+
+pub const DETECT_BYTETEST_BASE_UNSET: u8 = 0; // automatic, as a C integer literal
+pub const DETECT_BYTETEST_BASE_OCT: u8 = 8;
+pub const DETECT_BYTETEST_BASE_DEC: u8 = 10;
+pub const DETECT_BYTETEST_BASE_HEX: u8 = 16;
+
+pub const DETECT_BYTETEST_LITTLE: u16 = 0x01;
+pub const DETECT_BYTETEST_BIG: u16 = 0x02;
+pub const DETECT_BYTETEST_STRING: u16 = 0x04;
+pub const DETECT_BYTETEST_RELATIVE: u16 = 0x08;
+pub const DETECT_BYTETEST_DCE: u16 = 0x10;
+pub const DETECT_BYTETEST_BITMASK: u16 = 0x20;
+pub const DETECT_BYTETEST_VALUE_VAR: u16 = 0x40;
+pub const DETECT_BYTETEST_OFFSET_VAR: u16 = 0x80;
+pub const DETECT_BYTETEST_NBYTES_VAR: u16 = 0x100;
+
+// Fixed position parameter count: bytes, operator, value, offset
+const DETECT_BYTETEST_FIXED_PARAM_COUNT: usize = 4;
+// Optional parameters: relative, endian, string, base, dce, bitmask
+const DETECT_BYTETEST_MAX_PARAM_COUNT: usize = 10;
+
+// Largest string that does not overflow a u64: octal with a zero prefix,
+// "01777777777777777777777"
+const DETECT_BYTETEST_MAX_STRING_BYTES: u32 = 23;
+const DETECT_BYTETEST_MAX_BYTES: u32 = 8;
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct DetectBytetestData {
+    pub nbytes: u8,
+    pub op: u8,
+    pub base: u8,
+    pub bitmask_shift_count: u8,
+    pub flags: u16,
+    pub neg_op: bool,
+    pub offset: i32,
+    pub bitmask: u32,
+    pub value: u64,
+    /// byte_extract variables, resolved when the rule is set up
+    pub value_str: *const c_char,
+    pub offset_str: *const c_char,
+    pub nbytes_str: *const c_char,
+}
+
+impl Drop for DetectBytetestData {
+    fn drop(&mut self) {
+        unsafe {
+            for s in [self.value_str, self.offset_str, self.nbytes_str] {
+                if !s.is_null() {
+                    let _ = CString::from_raw(s as *mut c_char);
+                }
+            }
+        }
+    }
+}
+
+impl Default for DetectBytetestData {
+    fn default() -> Self {
+        DetectBytetestData {
+            nbytes: 0,
+            op: 0,
+            base: DETECT_BYTETEST_BASE_UNSET,
+            bitmask_shift_count: 0,
+            flags: 0,
+            neg_op: false,
+            offset: 0,
+            bitmask: 0,
+            value: 0,
+            value_str: std::ptr::null(),
+            offset_str: std::ptr::null(),
+            nbytes_str: std::ptr::null(),
+        }
+    }
+}
+
+/// Parses an unsigned integer like `strtoull`: leading whitespace and a
+/// sign are skipped, base 0 picks the base from a `0x` or `0` prefix, and
+/// parsing stops at the first invalid digit.
+///
+/// Returns the value and the number of bytes used, or None if there is no
+/// digit or the value overflows.
+pub(crate) fn parse_c_uint(input: &[u8], base: u32) -> Option<(u64, usize)> {
+    let mut i = input
+        .iter()
+        .position(|c| !c.is_ascii_whitespace() && *c != 0x0b)
+        .unwrap_or(input.len());
+    let negative = match input.get(i) {
+        Some(b'-') => {
+            i += 1;
+            true
+        }
+        Some(b'+') => {
+            i += 1;
+            false
+        }
+        _ => false,
+    };
+    let hex_prefix = matches!(input.get(i..i + 2), Some(b"0x" | b"0X"))
+        && matches!(input.get(i + 2), Some(c) if c.is_ascii_hexdigit());
+    let radix = match base {
+        0 if hex_prefix => 16,
+        0 if input.get(i) == Some(&b'0') => 8,
+        0 => 10,
+        _ => base,
+    };
+    if radix == 16 && hex_prefix {
+        i += 2;
+    }
+    let start = i;
+    let mut value: u64 = 0;
+    while let Some(digit) = input.get(i).and_then(|c| (*c as char).to_digit(radix)) {
+        value = value
+            .checked_mul(u64::from(radix))?
+            .checked_add(u64::from(digit))?;
+        i += 1;
+    }
+    if i == start {
+        return None;
+    }
+    // strtoull negates in the unsigned type
+    Some((
+        if negative {
+            value.wrapping_neg()
+        } else {
+            value
+        },
+        i,
+    ))
+}
+
+/// A variable name starts with a letter.
+pub(crate) fn is_var_name(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_alphabetic())
+}
+
+fn var_name_to_c(name: &str) -> Result<*const c_char, Err<RuleParseError<&'static str>>> {
+    CString::new(name)
+        .map(|s| s.into_raw() as *const c_char)
+        .map_err(|_| {
+            Err::Error(RuleParseError::InvalidByteTest(
+                "parse string not safely convertible to C".to_string(),
+            ))
+        })
+}
+
+fn parse_bytetest(input: &str) -> Result<DetectBytetestData, Err<RuleParseError<&'static str>>> {
+    // Inner utility function for easy error creation.
+    fn make_error(reason: String) -> Err<RuleParseError<&'static str>> {
+        Err::Error(RuleParseError::InvalidByteTest(reason))
+    }
+    let values: Vec<&str> = input.split(',').map(|v| v.trim()).collect();
+    if values.len() < DETECT_BYTETEST_FIXED_PARAM_COUNT
+        || values.len() > DETECT_BYTETEST_MAX_PARAM_COUNT
+    {
+        return Err(make_error(format!(
+            "Incorrect argument string; at least {} values must be specified but no more than {}: {:?}",
+            DETECT_BYTETEST_FIXED_PARAM_COUNT, DETECT_BYTETEST_MAX_PARAM_COUNT, input
+        )));
+    }
+    for (i, value) in values.iter().enumerate() {
+        // the operator is checked on its own, it may have whitespace after
+        // its negation
+        if i == 1 {
+            continue;
+        }
+        if value.is_empty()
+            || (i < DETECT_BYTETEST_FIXED_PARAM_COUNT && value.contains(char::is_whitespace))
+        {
+            return Err(make_error(format!(
+                "invalid byte_test argument: {:?}",
+                value
+            )));
+        }
+    }
+
+    let mut byte_test = DetectBytetestData::default();
+
+    let mut nbytes = 0;
+    if is_var_name(values[0]) {
+        byte_test.nbytes_str = var_name_to_c(values[0])?;
+        byte_test.flags |= DETECT_BYTETEST_NBYTES_VAR;
+    } else {
+        nbytes = match parse_c_uint(values[0].as_bytes(), 10) {
+            Some((n, len)) if len == values[0].len() && n <= u64::from(u32::MAX) => n as u32,
+            _ => {
+                return Err(make_error(format!(
+                    "Malformed number of bytes: {}",
+                    values[0]
+                )))
+            }
+        };
+    }
+
+    let op = match values[1].strip_prefix('!') {
+        Some(op) => {
+            byte_test.neg_op = true;
+            op.trim_start()
+        }
+        None => values[1],
+    };
+    byte_test.op = match op {
+        "=" => DETECT_BYTETEST_OP_EQ,
+        "" if byte_test.neg_op => DETECT_BYTETEST_OP_EQ,
+        "<" => DETECT_BYTETEST_OP_LT,
+        ">" => DETECT_BYTETEST_OP_GT,
+        "&" => DETECT_BYTETEST_OP_AND,
+        "^" => DETECT_BYTETEST_OP_OR,
+        ">=" => DETECT_BYTETEST_OP_GE,
+        "<=" => DETECT_BYTETEST_OP_LE,
+        _ => return Err(make_error(format!("Invalid operator: {}", values[1]))),
+    };
+
+    // anything after the number, like an ULL suffix, is ignored
+    if is_var_name(values[2]) {
+        byte_test.value_str = var_name_to_c(values[2])?;
+    } else {
+        byte_test.value = parse_c_uint(values[2].as_bytes(), 0)
+            .ok_or_else(|| make_error(format!("Malformed value: {}", values[2])))?
+            .0;
+    }
+
+    if is_var_name(values[3]) {
+        byte_test.offset_str = var_name_to_c(values[3])?;
+    } else {
+        let (unsigned, negative) = match values[3].strip_prefix('-') {
+            Some(unsigned) => (unsigned, true),
+            None => (values[3], false),
+        };
+        let offset = parse_c_uint(unsigned.as_bytes(), 0)
+            .and_then(|(offset, _)| i64::try_from(offset).ok())
+            .map(|offset| if negative { -offset } else { offset })
+            .and_then(|offset| i32::try_from(offset).ok())
+            .ok_or_else(|| make_error(format!("Malformed offset: {}", values[3])))?;
+        byte_test.offset = offset;
+    }
+
+    for value in values.iter().skip(DETECT_BYTETEST_FIXED_PARAM_COUNT) {
+        let (name, arg) = match value.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, Some(arg.trim())),
+            None => (*value, None),
+        };
+        let lname = name.to_ascii_lowercase();
+        match (lname.as_str(), arg) {
+            ("bitmask", Some(arg)) => {
+                byte_test.bitmask = match parse_c_uint(arg.as_bytes(), 0) {
+                    Some((mask, _)) if mask <= u64::from(u32::MAX) => mask as u32,
+                    _ => return Err(make_error(format!("Malformed bitmask value: {}", arg))),
+                };
+                byte_test.flags |= DETECT_BYTETEST_BITMASK;
+            }
+            (_, Some(_)) => {
+                return Err(make_error(format!("Unknown value: \"{}\"", value)));
+            }
+            ("relative", None) if name == "relative" => {
+                byte_test.flags |= DETECT_BYTETEST_RELATIVE;
+            }
+            ("string", None) => {
+                byte_test.flags |= DETECT_BYTETEST_STRING;
+            }
+            ("oct" | "dec" | "hex", None) => {
+                if byte_test.base != DETECT_BYTETEST_BASE_UNSET {
+                    return Err(make_error(format!("Only one base can be set: {}", input)));
+                }
+                byte_test.base = get_string_value(&lname).map_err(make_error)? as u8;
+            }
+            ("big" | "little" | "dce", None) => match get_endian_value(&lname) {
+                Ok(ByteEndian::BigEndian) => {
+                    byte_test.flags &= !DETECT_BYTETEST_LITTLE;
+                    byte_test.flags |= DETECT_BYTETEST_BIG;
+                }
+                Ok(ByteEndian::LittleEndian) => byte_test.flags |= DETECT_BYTETEST_LITTLE,
+                Ok(ByteEndian::EndianDCE) => byte_test.flags |= DETECT_BYTETEST_DCE,
+                Err(e) => return Err(make_error(e)),
+            },
+            _ => {
+                return Err(make_error(format!("Unknown value: \"{}\"", value)));
+            }
+        }
+    }
+
+    let string = byte_test.flags & DETECT_BYTETEST_STRING != 0;
+    if !string && byte_test.base != DETECT_BYTETEST_BASE_UNSET {
+        return Err(make_error(format!(
+            "Cannot use a base without \"string\": {}",
+            input
+        )));
+    }
+    if byte_test.flags & DETECT_BYTETEST_NBYTES_VAR == 0 {
+        if string && nbytes > DETECT_BYTETEST_MAX_STRING_BYTES {
+            return Err(make_error(format!(
+                "Cannot test more than {} bytes with \"string\": {}",
+                DETECT_BYTETEST_MAX_STRING_BYTES, input
+            )));
+        }
+        if !string && nbytes > DETECT_BYTETEST_MAX_BYTES {
+            return Err(make_error(format!(
+                "Cannot test more than {} bytes without \"string\": {}",
+                DETECT_BYTETEST_MAX_BYTES, input
+            )));
+        }
+        byte_test.nbytes = nbytes as u8;
+        // a mask can only test bits of the extracted bytes
+        if !string
+            && byte_test.op == DETECT_BYTETEST_OP_AND
+            && byte_test.value_str.is_null()
+            && nbytes < 8
+            && byte_test.value >> (8 * nbytes) != 0
+        {
+            return Err(make_error(format!(
+                "Mask {:#x} is wider than {} bytes: {}",
+                byte_test.value, nbytes, input
+            )));
+        }
+    }
+
+    // the value is shifted right once per trailing zero of the mask
+    if byte_test.bitmask != 0 {
+        byte_test.bitmask_shift_count = byte_test.bitmask.trailing_zeros() as u8;
+    }
+
+    Ok(byte_test)
+}
+
+/// Checks if `nbytes` can be extracted, for a count from a variable.
+fn byte_test_nbytes_valid(data: &DetectBytetestData, nbytes: u32) -> bool {
+    if data.flags & DETECT_BYTETEST_STRING != 0 {
+        nbytes <= DETECT_BYTETEST_MAX_STRING_BYTES
+    } else {
+        nbytes <= DETECT_BYTETEST_MAX_BYTES
+    }
+}
+
+/// Extracts the value to test from `input`, the bytes to extract.
+///
+/// `flags` are the ones of `data` with the endianness of dce resolved.
+fn byte_test_extract(data: &DetectBytetestData, flags: u16, input: &[u8]) -> Option<u64> {
+    if flags & DETECT_BYTETEST_STRING != 0 {
+        // a NUL stops the number, like any other non digit
+        return parse_c_uint(input, u32::from(data.base)).map(|(value, _)| value);
+    }
+    if input.is_empty() || input.len() > 8 {
+        return None;
+    }
+    let value = if flags & DETECT_BYTETEST_LITTLE != 0 {
+        input
+            .iter()
+            .rev()
+            .fold(0u64, |acc, &b| (acc << 8) | u64::from(b))
+    } else {
+        input.iter().fold(0u64, |acc, &b| (acc << 8) | u64::from(b))
+    };
+    Some(value)
+}
+
+/// Compares an extracted value to the tested `value`, once masked.
+fn byte_test_compare(data: &DetectBytetestData, flags: u16, val: u64, value: u64) -> bool {
+    let mut val = val;
+    if flags & DETECT_BYTETEST_BITMASK != 0 {
+        val &= u64::from(data.bitmask);
+        val >>= data.bitmask_shift_count;
+    }
+    let matched = match data.op {
+        DETECT_BYTETEST_OP_EQ => val == value,
+        DETECT_BYTETEST_OP_LT => val < value,
+        DETECT_BYTETEST_OP_GT => val > value,
+        DETECT_BYTETEST_OP_AND => val & value != 0,
+        DETECT_BYTETEST_OP_OR => val ^ value != 0,
+        DETECT_BYTETEST_OP_GE => val >= value,
+        DETECT_BYTETEST_OP_LE => val <= value,
+        _ => return false,
+    };
+    matched != data.neg_op
+}
+
+fn byte_test_match(data: &DetectBytetestData, flags: u16, input: &[u8], value: u64) -> bool {
+    if !byte_test_nbytes_valid(data, input.len() as u32) {
+        return false;
+    }
+    match byte_test_extract(data, flags, input) {
+        Some(val) => byte_test_compare(data, flags, val, value),
+        None => false,
+    }
+}
+
+/// Intermediary function between the C code and the parsing functions.
+#[no_mangle]
+pub unsafe extern "C" fn SCByteTestParse(c_arg: *const c_char) -> *mut DetectBytetestData {
+    if c_arg.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let arg = match CStr::from_ptr(c_arg).to_str() {
+        Ok(arg) => arg,
+        Err(_) => {
+            return std::ptr::null_mut();
+        }
+    };
+    match parse_bytetest(arg) {
+        Ok(detect) => return Box::into_raw(Box::new(detect)),
+        Err(Err::Error(RuleParseError::InvalidByteTest(e))) => {
+            SCLogError!("{}", e);
+            return std::ptr::null_mut();
+        }
+        Err(_) => return std::ptr::null_mut(),
+    }
+}
+
+/// Tests the `nbytes` bytes at `data`, which the caller checked are in the
+/// buffer, against `value`.
+///
+/// `flags` are the ones of `ctx` with the endianness of dce resolved.
+#[no_mangle]
+pub unsafe extern "C" fn SCByteTestMatch(
+    ctx: &DetectBytetestData, flags: u16, data: *const u8, nbytes: u32, value: u64,
+) -> bool {
+    if data.is_null() {
+        return false;
+    }
+    let input = build_slice!(data, nbytes as usize);
+    return byte_test_match(ctx, flags, input, value);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCByteTestFree(ptr: *mut DetectBytetestData) {
+    if !ptr.is_null() {
+        let _ = Box::from_raw(ptr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(s: *const c_char) -> &'static str {
+        unsafe { CStr::from_ptr(s).to_str().unwrap() }
+    }
+
+    #[test]
+    fn test_parser_valid() {
+        let bt = parse_bytetest("4, =, 1 , 0").unwrap();
+        assert_eq!(bt.nbytes, 4);
+        assert_eq!(bt.op, DETECT_BYTETEST_OP_EQ);
+        assert_eq!(bt.value, 1);
+        assert_eq!(bt.offset, 0);
+        assert!(!bt.neg_op);
+        assert_eq!(bt.base, DETECT_BYTETEST_BASE_UNSET);
+
+        let bt = parse_bytetest("4,!^,5,0,little,string,relative,hex").unwrap();
+        assert_eq!(bt.op, DETECT_BYTETEST_OP_OR);
+        assert!(bt.neg_op);
+        assert_eq!(
+            bt.flags,
+            DETECT_BYTETEST_LITTLE | DETECT_BYTETEST_STRING | DETECT_BYTETEST_RELATIVE
+        );
+        assert_eq!(bt.base, DETECT_BYTETEST_BASE_HEX);
+
+        let bt = parse_bytetest("\t4 , ! &, 5\t, -3 , little ").unwrap();
+        assert_eq!(bt.op, DETECT_BYTETEST_OP_AND);
+        assert!(bt.neg_op);
+        assert_eq!(bt.offset, -3);
+
+        let bt = parse_bytetest("23,=,0xffffffffffffffffULL,0,string,oct").unwrap();
+        assert_eq!(bt.nbytes, 23);
+        assert_eq!(bt.value, u64::MAX);
+        assert_eq!(bt.base, DETECT_BYTETEST_BASE_OCT);
+
+        let bt = parse_bytetest("2, =, 0x10, 0, bitmask 0x3f0").unwrap();
+        assert_eq!(bt.flags, DETECT_BYTETEST_BITMASK);
+        assert_eq!(bt.bitmask, 0x3f0);
+        assert_eq!(bt.bitmask_shift_count, 4);
+
+        let bt = parse_bytetest("4, <, 5, 0, little, big").unwrap();
+        assert_eq!(bt.flags, DETECT_BYTETEST_BIG);
+    }
+
+    #[test]
+    fn test_parser_operators() {
+        for (op, code, neg) in [
+            ("<", DETECT_BYTETEST_OP_LT, false),
+            (">", DETECT_BYTETEST_OP_GT, false),
+            ("=", DETECT_BYTETEST_OP_EQ, false),
+            ("<=", DETECT_BYTETEST_OP_LE, false),
+            (">=", DETECT_BYTETEST_OP_GE, false),
+            ("&", DETECT_BYTETEST_OP_AND, false),
+            ("^", DETECT_BYTETEST_OP_OR, false),
+            ("!=", DETECT_BYTETEST_OP_EQ, true),
+            ("!", DETECT_BYTETEST_OP_EQ, true),
+            ("!<", DETECT_BYTETEST_OP_LT, true),
+        ] {
+            let bt = parse_bytetest(&format!("1, {}, 1, 0", op)).unwrap();
+            assert_eq!((bt.op, bt.neg_op), (code, neg), "{}", op);
+        }
+        assert!(parse_bytetest("1, ==, 1, 0").is_err());
+        assert!(parse_bytetest("1, , 1, 0").is_err());
+    }
+
+    #[test]
+    fn test_parser_vars() {
+        let bt = parse_bytetest("nb, =, myval, myoffset, relative").unwrap();
+        assert_eq!(
+            bt.flags,
+            DETECT_BYTETEST_NBYTES_VAR | DETECT_BYTETEST_RELATIVE
+        );
+        assert_eq!(var(bt.nbytes_str), "nb");
+        assert_eq!(var(bt.value_str), "myval");
+        assert_eq!(var(bt.offset_str), "myoffset");
+
+        let bt = parse_bytetest("2, =, 1, 0").unwrap();
+        assert!(bt.value_str.is_null());
+        assert!(bt.offset_str.is_null());
+        assert!(bt.nbytes_str.is_null());
+    }
+
+    #[test]
+    fn test_parser_invalid() {
+        for args in [
+            "4, =, 1",
+            "4, =, 1, 0, hex",
+            "9, =, 1, 0",
+            "24, =, 0xffffffffffffffffULL, 0, string",
+            "4,=,0,0xffffffffffffffffULL",
+            "4, =, 1, 0, relative 1",
+            "4, =, 1, 0, bitmask",
+            "4, =, 1, 0, bitmask 0x1ffffffff",
+            "4, =, 1, 0, unknown",
+            "4, =, 1, 0, Relative",
+            "4, =, 1, 0, string, hex, dec",
+            "4, =, 1, 0, string, oct, oct",
+            "4 4, =, 1, 0",
+            "4, =, 1 2, 0",
+            "4, =, 1, 0, relative, relative, relative, relative, relative, relative, relative",
+        ] {
+            assert!(parse_bytetest(args).is_err(), "{}", args);
+        }
+    }
+
+    #[test]
+    fn test_parse_c_uint() {
+        assert_eq!(parse_c_uint(b"123", 10), Some((123, 3)));
+        assert_eq!(parse_c_uint(b"  12ab", 10), Some((12, 4)));
+        assert_eq!(parse_c_uint(b"0x1F", 0), Some((0x1f, 4)));
+        assert_eq!(parse_c_uint(b"0x1F", 16), Some((0x1f, 4)));
+        assert_eq!(parse_c_uint(b"017", 0), Some((0o17, 3)));
+        assert_eq!(parse_c_uint(b"0", 0), Some((0, 1)));
+        assert_eq!(parse_c_uint(b"0x", 0), Some((0, 1)));
+        assert_eq!(parse_c_uint(b"-1", 10), Some((u64::MAX, 2)));
+        assert_eq!(parse_c_uint(b"18446744073709551616", 10), None);
+        assert_eq!(parse_c_uint(b"", 10), None);
+        assert_eq!(parse_c_uint(b"ab", 10), None);
+        assert_eq!(parse_c_uint(b"9", 8), None);
+    }
+
+    fn matches(args: &str, input: &[u8]) -> bool {
+        let bt = parse_bytetest(args).unwrap();
+        byte_test_match(&bt, bt.flags, &input[..bt.nbytes as usize], bt.value)
+    }
+
+    #[test]
+    fn test_byte_test_match_endian() {
+        let input = [0x01, 0x02, 0x03, 0x04];
+        assert!(matches("2, =, 0x0102, 0", &input));
+        assert!(matches("2, =, 0x0102, 0, big", &input));
+        assert!(matches("2, =, 0x0201, 0, little", &input));
+        assert!(matches("4, =, 67305985, 0, little", &input));
+        assert!(!matches("4, =, 67305985, 0, big", &input));
+        assert!(matches("4, >, 0x01020300, 0", &input));
+        assert!(matches("1, <=, 1, 0", &input));
+        assert!(matches("1, !=, 2, 0", &input));
+    }
+
+    #[test]
+    fn test_byte_test_match_string() {
+        assert!(matches("3, =, 123, 0, string, dec", b"123"));
+        assert!(matches("3, =, 0x123, 0, string, hex", b"123"));
+        assert!(matches("3, =, 83, 0, string, oct", b"123"));
+        assert!(matches("4, =, 0x1f, 0, string, hex", b"0x1f"));
+        // without a base, the string is read as a C integer literal
+        assert!(matches("4, =, 0x1f, 0, string", b"0x1f"));
+        assert!(matches("3, =, 15, 0, string", b"017"));
+        // the number ends at the first non digit
+        assert!(matches("4, =, 12, 0, string, dec", b"12ab"));
+        assert!(!matches("2, =, 0, 0, string, dec", b"ab"));
+    }
+
+    #[test]
+    fn test_byte_test_match_extracted_var() {
+        // a value from byte_extract is compared as a literal would be
+        let bt = parse_bytetest("2, =, myvar, 0, little").unwrap();
+        assert!(byte_test_match(&bt, bt.flags, &[0x34, 0x12], 0x1234));
+        assert!(!byte_test_match(&bt, bt.flags, &[0x12, 0x34], 0x1234));
+        let bt = parse_bytetest("nb, >, 100, 0, string, dec").unwrap();
+        assert!(byte_test_match(&bt, bt.flags, b"101", 100));
+        assert!(!byte_test_match(&bt, bt.flags, b"099", 100));
+        // a count from a variable is checked at match time
+        assert!(!byte_test_match(&bt, bt.flags, &[b'1'; 24], 100));
+    }
+
+    #[test]
+    fn test_byte_test_match_bitmask() {
+        assert!(matches("2, =, 0x10, 0, bitmask 0x0ff0", &[0x01, 0x0f]));
+        assert!(!matches("2, =, 0x10, 0, bitmask 0x0ff0", &[0x02, 0x0f]));
+        assert!(matches("1, &, 0x80, 0", &[0x81]));
+        assert!(!matches("1, &, 0x80, 0", &[0x7f]));
+        assert!(matches("1, ^, 0x80, 0", &[0x81]));
+        assert!(!matches("1, ^, 0x81, 0", &[0x81]));
+    }
+
+    #[test]
+    fn test_byte_test_match_and() {
+        assert!(matches("1, &, 0x80, 0", &[0x80]));
+        assert!(!matches("1, &, 0x80, 0", &[0x7f]));
+        assert!(matches("1, !&, 0x80, 0", &[0x7f]));
+        assert!(matches("2, &, 0x8001, 0", &[0x80, 0x00]));
+        assert!(matches("2, &, 0x8001, 0", &[0x00, 0x01]));
+        assert!(!matches("2, &, 0x8001, 0", &[0x7f, 0xfe]));
+        assert!(matches("2, &, 0x8001, 0, little", &[0x00, 0x80]));
+        assert!(!matches("2, &, 0x8001, 0, little", &[0x80, 0x00]));
+        // the mask cannot be wider than the tested bytes
+        assert!(parse_bytetest("1, &, 0x100, 0").is_err());
+        assert!(parse_bytetest("2, &, 0x10000, 0").is_err());
+        assert!(parse_bytetest("8, &, 0xffffffffffffffff, 0").is_ok());
+        assert!(parse_bytetest("1, &, mask, 0").is_ok());
+    }
+
+    #[test]
+    fn test_byte_test_match_dce() {
+        // dce is little or big endian depending on the DCE/RPC header
+        let bt = parse_bytetest("2, =, 0x0201, 0, dce").unwrap();
+        let le = bt.flags | DETECT_BYTETEST_LITTLE;
+        assert!(byte_test_match(&bt, le, &[0x01, 0x02], bt.value));
+        assert!(!byte_test_match(&bt, bt.flags, &[0x01, 0x02], bt.value));
+    }
+}
@@ -19,14 +19,25 @@
 
 //! This module exposes items from the datasets C code to Rust.
 
+use super::uint::{detect_match_uint, DetectUintData};
 use base64::{self, Engine};
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
 use std::ffi::{c_char, CStr};
 use std::fs::{File, OpenOptions};
-use std::io::{self, BufRead};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, Write};
 use std::mem::transmute;
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 /// Opaque Dataset type defined in C
 #[derive(Copy, Clone)]
@@ -37,9 +48,23 @@ pub enum Dataset {}
 #[repr(C)]
 pub struct DataRepType {
     pub value: u16,
+    /// number of times the value was added to the set
+    pub count: u32,
+    /// time in seconds the value was last added to the set
+    pub ts: u64,
 }
 
-#[derive(Debug)]
+impl DataRepType {
+    pub fn new(value: u16) -> Self {
+        DataRepType {
+            value,
+            count: 0,
+            ts: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
 #[repr(C)]
 pub enum DatasetType {
     DSString = 0,
@@ -57,6 +82,179 @@ extern "C" {
         -> i32;
 }
 
+/// Updates the count and timestamp of an entry just added to a set at time
+/// `now`. `is_new` is false if the value was already in the set.
+#[no_mangle]
+pub extern "C" fn SCDatasetRepAdded(rep: &mut DataRepType, is_new: bool, now: u64) {
+    if is_new {
+        rep.count = 1;
+    } else {
+        rep.count = rep.count.saturating_add(1);
+    }
+    rep.ts = now;
+}
+
+/// Checks if an entry is older than `ttl` seconds at time `now`. A `ttl` of 0
+/// means entries never expire.
+#[no_mangle]
+pub extern "C" fn SCDatasetRepExpired(rep: &DataRepType, ttl: u32, now: u64) -> bool {
+    ttl != 0 && now.saturating_sub(rep.ts) >= ttl as u64
+}
+
+/// Compares the count of a value with `du32`. A value that is not in the set
+/// has a count of 0.
+#[no_mangle]
+pub extern "C" fn SCDatasetCountMatch(
+    found: bool, rep: &DataRepType, du32: &DetectUintData<u32>,
+) -> bool {
+    let count = if found { rep.count } else { 0 };
+    detect_match_uint(du32, count)
+}
+
+/// Size of a buffer large enough for any value encoded by
+/// [`dataset_var_encode`].
+pub const DATASET_VAR_MAX_LEN: usize = 20;
+
+/// Encodes the value of a `byte_extract` or `byte_math` variable the way it
+/// is stored in a set of type `dstype`: decimal text for string sets and 4
+/// bytes in network order for ipv4 sets. Returns the encoded length, or None
+/// if the value can't be stored in such a set.
+pub fn dataset_var_encode(value: u64, dstype: &DatasetType, out: &mut [u8]) -> Option<usize> {
+    match dstype {
+        DatasetType::DSString => {
+            let mut cursor = io::Cursor::new(out);
+            write!(cursor, "{}", value).ok()?;
+            Some(cursor.position() as usize)
+        }
+        DatasetType::DSIpv4 => {
+            let addr = u32::try_from(value).ok()?.to_be_bytes();
+            out.get_mut(..addr.len())?.copy_from_slice(&addr);
+            Some(addr.len())
+        }
+        _ => None,
+    }
+}
+
+/// C wrapper around [`dataset_var_encode`], returns 0 on failure.
+#[no_mangle]
+pub unsafe extern "C" fn SCDatasetVarEncode(
+    value: u64, dstype: DatasetType, out: *mut u8, out_len: u32,
+) -> u32 {
+    let out = std::slice::from_raw_parts_mut(out, out_len as usize);
+    dataset_var_encode(value, &dstype, out).map_or(0, |len| len as u32)
+}
+
+/// Probabilistic set membership for large datasets. Values that were added
+/// are always found, values that were not may be found with a probability
+/// of at most the false positive rate the filter was sized for.
+#[derive(Debug)]
+pub struct DatasetBloom {
+    bits: Vec<AtomicU64>,
+    nbits: u64,
+    hashes: u32,
+}
+
+/// Upper limit on the size of a filter, 1 GiB
+const BLOOM_MAX_BITS: u64 = 1 << 33;
+
+impl DatasetBloom {
+    /// Sizes a filter for `entries` values with a false positive rate of
+    /// `fp_rate`. Returns `None` if the parameters are invalid or the filter
+    /// would be too large.
+    ///
+    /// Using `ceil(log2(1 / fp_rate))` hashes and `hashes * entries / ln(2)`
+    /// bits gives a false positive rate of `0.5^hashes`, which is no more
+    /// than `fp_rate`.
+    pub fn new(entries: u64, fp_rate: f64) -> Option<Self> {
+        if entries == 0 || fp_rate.is_nan() || fp_rate <= 0.0 || fp_rate >= 1.0 {
+            return None;
+        }
+        let hashes = (1.0 / fp_rate).log2().ceil().max(1.0);
+        let nbits = (hashes * entries as f64 / std::f64::consts::LN_2).ceil();
+        if nbits > BLOOM_MAX_BITS as f64 {
+            return None;
+        }
+        let nbits = (nbits as u64).max(64);
+        let words = ((nbits + 63) / 64) as usize;
+        let mut bits = Vec::with_capacity(words);
+        bits.resize_with(words, || AtomicU64::new(0));
+        Some(DatasetBloom {
+            bits,
+            nbits,
+            hashes: hashes as u32,
+        })
+    }
+
+    /// Bit positions for `data`, using double hashing.
+    fn positions(&self, data: &[u8]) -> impl Iterator<Item = u64> {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        let h1 = hasher.finish();
+        0xb10fu16.hash(&mut hasher);
+        // odd, so that it cycles through all positions
+        let h2 = hasher.finish() | 1;
+        let nbits = self.nbits;
+        (0..self.hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % nbits)
+    }
+
+    /// Adds `data`. Returns true if it was not in the filter before.
+    pub fn insert(&self, data: &[u8]) -> bool {
+        let mut is_new = false;
+        for pos in self.positions(data) {
+            let mask = 1u64 << (pos % 64);
+            let old = self.bits[(pos / 64) as usize].fetch_or(mask, Ordering::Relaxed);
+            if old & mask == 0 {
+                is_new = true;
+            }
+        }
+        is_new
+    }
+
+    pub fn contains(&self, data: &[u8]) -> bool {
+        self.positions(data).all(|pos| {
+            let mask = 1u64 << (pos % 64);
+            self.bits[(pos / 64) as usize].load(Ordering::Relaxed) & mask != 0
+        })
+    }
+}
+
+/// Returns a filter sized for `entries` values and a false positive rate of
+/// `fp_rate`, or NULL if these are invalid.
+#[no_mangle]
+pub extern "C" fn SCDatasetBloomNew(entries: u64, fp_rate: f64) -> *mut DatasetBloom {
+    match DatasetBloom::new(entries, fp_rate) {
+        Some(bloom) => Box::into_raw(Box::new(bloom)),
+        None => std::ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDatasetBloomFree(bloom: *mut DatasetBloom) {
+    if !bloom.is_null() {
+        std::mem::drop(Box::from_raw(bloom));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDatasetBloomAdd(
+    bloom: &DatasetBloom, data: *const u8, len: u32,
+) -> bool {
+    bloom.insert(build_slice!(data, len as usize))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDatasetBloomLookup(
+    bloom: &DatasetBloom, data: *const u8, len: u32,
+) -> bool {
+    bloom.contains(build_slice!(data, len as usize))
+}
+
+/// Loads the values of a dataset file into `set`. A file starting with a
+/// JSON object is loaded as JSON lines, see [`load_json`], otherwise as CSV
+/// lines.
+///
+/// Returns 1 for JSON lines, 0 for CSV, -1 if the file cannot be opened and
+/// -2 for a fatal error.
 #[no_mangle]
 pub unsafe extern "C" fn ParseDatasets(
     set: &Dataset, name: *const c_char, fname: *const c_char, fmode: *const c_char,
@@ -68,11 +266,17 @@ pub unsafe extern "C" fn ParseDatasets(
     let filename = Path::new(file_string);
     let mut no_rep = false;
     let mut with_rep = false;
-    let lines = match read_or_create_file(filename, mode) {
+    let mut reader = match read_or_create_file(filename, mode) {
         Ok(fp) => fp,
         Err(_) => return -1,
     };
-    for line in lines.map_while(Result::ok) {
+    if starts_with_json(&mut reader) {
+        load_json(reader, &dstype, set_name, |data, rep| {
+            DatasetAddwRep(set, data.as_ptr(), data.len() as u32, rep);
+        });
+        return 1;
+    }
+    for line in reader.lines().map_while(Result::ok) {
         let v: Vec<&str> = line.split(',').collect();
         // Ignore empty and invalid lines in dataset/rep file
         if v.is_empty() || v.len() > 2 {
@@ -148,7 +352,7 @@ unsafe fn process_string_set(
     if no_rep {
         DatasetAdd(set, decoded.as_ptr(), decoded.len() as u32);
     } else if let Ok(val) = v[1].to_string().parse::<u16>() {
-        let rep: DataRepType = DataRepType { value: val };
+        let rep: DataRepType = DataRepType::new(val);
         DatasetAddwRep(set, decoded.as_ptr(), decoded.len() as u32, &rep);
     } else {
         SCFatalErrorOnInit!(
@@ -172,7 +376,7 @@ unsafe fn process_md5_set(
     if no_rep {
         DatasetAdd(set, md5_string.as_ptr(), 16);
     } else if let Ok(val) = v[1].to_string().parse::<u16>() {
-        let rep: DataRepType = DataRepType { value: val };
+        let rep: DataRepType = DataRepType::new(val);
         DatasetAddwRep(set, md5_string.as_ptr(), 16, &rep);
     } else {
         SCFatalErrorOnInit!(
@@ -196,7 +400,7 @@ unsafe fn process_sha256_set(
     if no_rep {
         DatasetAdd(set, sha256_string.as_ptr(), 32);
     } else if let Ok(val) = v[1].to_string().parse::<u16>() {
-        let rep: DataRepType = DataRepType { value: val };
+        let rep: DataRepType = DataRepType::new(val);
         DatasetAddwRep(set, sha256_string.as_ptr(), 32, &rep);
     } else {
         SCFatalErrorOnInit!(
@@ -222,7 +426,7 @@ unsafe fn process_ipv4_set(
     if no_rep {
         DatasetAdd(set, ipv4.octets().as_ptr(), 4);
     } else if let Ok(val) = v[1].to_string().parse::<u16>() {
-        let rep: DataRepType = DataRepType { value: val };
+        let rep: DataRepType = DataRepType::new(val);
         DatasetAddwRep(set, ipv4.octets().as_ptr(), 4, &rep);
     } else {
         SCFatalErrorOnInit!(
@@ -245,6 +449,26 @@ unsafe fn process_ipv6_set(
             return -1;
         }
     };
+    let fin_ipv6 = ipv6_to_internal(ipv6);
+    if no_rep {
+        DatasetAdd(set, fin_ipv6.octets().as_ptr(), 16);
+    } else if let Ok(val) = v[1].to_string().parse::<u16>() {
+        let rep: DataRepType = DataRepType::new(val);
+        DatasetAddwRep(set, fin_ipv6.octets().as_ptr(), 16, &rep);
+    } else {
+        SCFatalErrorOnInit!(
+            "invalid datarep value {} in {}",
+            set_name,
+            filename.display()
+        );
+        return -1;
+    }
+    0
+}
+
+/// Converts an IPv4-mapped IPv6 address into the form used by the sets: the
+/// IPv4 address in the first 4 bytes, followed by zeros.
+fn ipv6_to_internal(ipv6: Ipv6Addr) -> Ipv6Addr {
     let mut fin_ipv6 = ipv6;
 
     if ipv6.to_ipv4_mapped().is_some() {
@@ -270,23 +494,201 @@ unsafe fn process_ipv6_set(
         ]
         .into();
     }
-    if no_rep {
-        DatasetAdd(set, fin_ipv6.octets().as_ptr(), 16);
-    } else if let Ok(val) = v[1].to_string().parse::<u16>() {
-        let rep: DataRepType = DataRepType { value: val };
-        DatasetAddwRep(set, fin_ipv6.octets().as_ptr(), 16, &rep);
-    } else {
-        SCFatalErrorOnInit!(
-            "invalid datarep value {} in {}",
+    fin_ipv6
+}
+
+/// Version of the dataset file formats this Suricata reads and writes, for
+/// rules to check with `requires: dataset-format`. Version 1 is the CSV
+/// line format, version 2 adds JSON lines.
+pub const DATASET_FORMAT_VERSION: u32 = 2;
+
+/// Decodes a value of a dataset file: base64 for strings, hex for hashes and
+/// the text form for IP addresses.
+fn dataset_decode_value(value: &str, dstype: &DatasetType) -> Option<Vec<u8>> {
+    match dstype {
+        DatasetType::DSString => base64::engine::general_purpose::STANDARD.decode(value).ok(),
+        DatasetType::DSMd5 => hex::decode(value).ok().filter(|v| v.len() == 16),
+        DatasetType::DSSha256 => hex::decode(value).ok().filter(|v| v.len() == 32),
+        DatasetType::DSIpv4 => Ipv4Addr::from_str(value).ok().map(|a| a.octets().to_vec()),
+        DatasetType::DSIpv6 => match IpAddr::from_str(value).ok()? {
+            IpAddr::V4(a) => {
+                let mut internal = [0u8; 16];
+                internal[..4].copy_from_slice(&a.octets());
+                Some(internal.to_vec())
+            }
+            IpAddr::V6(a) => Some(ipv6_to_internal(a).octets().to_vec()),
+        },
+    }
+}
+
+/// Encodes a value of a set the way it is written in a dataset file. This is
+/// the reverse of [`dataset_decode_value`].
+fn dataset_encode_value(data: &[u8], dstype: &DatasetType) -> Option<String> {
+    match dstype {
+        DatasetType::DSString => Some(base64::engine::general_purpose::STANDARD.encode(data)),
+        DatasetType::DSMd5 | DatasetType::DSSha256 => Some(hex::encode(data)),
+        DatasetType::DSIpv4 => {
+            let octets: [u8; 4] = data.try_into().ok()?;
+            Some(Ipv4Addr::from(octets).to_string())
+        }
+        DatasetType::DSIpv6 => {
+            let octets: [u8; 16] = data.try_into().ok()?;
+            if octets[4..].iter().all(|b| *b == 0) {
+                Some(Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]).to_string())
+            } else {
+                Some(Ipv6Addr::from(octets).to_string())
+            }
+        }
+    }
+}
+
+/// Result of a JSON lines load.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct JsonLoadStats {
+    /// number of values passed on to the set
+    pub loaded: u32,
+    /// number of malformed lines that were skipped
+    pub skipped: u32,
+}
+
+/// Parses a `{"value": "...", "count": N}` line. `count` is optional and
+/// defaults to 1.
+fn json_parse_line(line: &str, dstype: &DatasetType) -> Option<(Vec<u8>, u32)> {
+    let obj: serde_json::Value = serde_json::from_str(line).ok()?;
+    let data = dataset_decode_value(obj.get("value")?.as_str()?, dstype)?;
+    let count = match obj.get("count") {
+        Some(c) => u32::try_from(c.as_u64()?).ok()?,
+        None => 1,
+    };
+    Some((data, count))
+}
+
+/// Loads a dataset from JSON lines, calling `add` for each value with its
+/// count in the `DataRepType`. Empty lines are ignored, malformed lines are
+/// skipped and reported in a single warning.
+pub fn load_json<R, F>(reader: R, dstype: &DatasetType, set_name: &str, mut add: F) -> JsonLoadStats
+where
+    R: BufRead,
+    F: FnMut(&[u8], &DataRepType),
+{
+    let mut stats = JsonLoadStats::default();
+    for line in reader.lines().map_while(Result::ok) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some((data, count)) = json_parse_line(line, dstype) {
+            let mut rep = DataRepType::new(0);
+            rep.count = count;
+            add(&data, &rep);
+            stats.loaded += 1;
+        } else {
+            stats.skipped += 1;
+        }
+    }
+    if stats.skipped > 0 {
+        SCLogWarning!(
+            "dataset {}: skipped {} malformed JSON line(s)",
             set_name,
-            filename.display()
+            stats.skipped
         );
-        return -1;
     }
-    0
+    stats
+}
+
+/// Saves a dataset as JSON lines, one `{"value": "...", "count": N}` object
+/// per entry.
+pub fn save_json<'a, W, I>(writer: &mut W, dstype: &DatasetType, entries: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = (&'a [u8], &'a DataRepType)>,
+{
+    for (data, rep) in entries {
+        let value = dataset_encode_value(data, dstype).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "invalid value for dataset type")
+        })?;
+        writeln!(
+            writer,
+            "{{\"value\":{},\"count\":{}}}",
+            serde_json::Value::from(value),
+            rep.count
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the JSON line of one entry of a set to `out`, for the C save
+/// path. Returns the length written, or 0 if it does not fit.
+#[no_mangle]
+pub unsafe extern "C" fn SCDatasetJsonLine(
+    dstype: DatasetType, data: *const u8, len: u32, rep: &DataRepType, out: *mut c_char,
+    out_size: usize,
+) -> i32 {
+    if data.is_null() || out.is_null() || out_size == 0 {
+        return 0;
+    }
+    let data = build_slice!(data, len as usize);
+    let buf = std::slice::from_raw_parts_mut(out as *mut u8, out_size);
+    // keep a byte for the terminating nul
+    let mut w = &mut buf[..out_size - 1];
+    if save_json(&mut w, &dstype, std::iter::once((data, rep))).is_err() {
+        return 0;
+    }
+    let written = out_size - 1 - w.len();
+    buf[written] = 0;
+    written as i32
+}
+
+/// Whether the buffered start of a dataset file is a JSON object, rather
+/// than a CSV line.
+fn starts_with_json<R: BufRead>(reader: &mut R) -> bool {
+    reader.fill_buf().map_or(false, |b| {
+        b.iter().find(|c| !c.is_ascii_whitespace()) == Some(&b'{')
+    })
+}
+
+/// Size of the stack buffer a lookup encodes its query in. It fits any
+/// value but the strings longer than 192 bytes.
+const DATASET_ENCODED_STACK_LEN: usize = 256;
+
+/// Encodes a value like [`dataset_encode_value`], into `buf`. Returns None
+/// if it does not fit.
+fn dataset_encode_into<'b>(
+    data: &[u8], dstype: &DatasetType, buf: &'b mut [u8],
+) -> Option<&'b [u8]> {
+    let len = match dstype {
+        DatasetType::DSString => base64::engine::general_purpose::STANDARD
+            .encode_slice(data, buf)
+            .ok()?,
+        DatasetType::DSMd5 | DatasetType::DSSha256 => {
+            let len = data.len() * 2;
+            hex::encode_to_slice(data, buf.get_mut(..len)?).ok()?;
+            len
+        }
+        DatasetType::DSIpv4 | DatasetType::DSIpv6 => {
+            let addr: IpAddr = match (dstype, data.len()) {
+                (DatasetType::DSIpv4, 4) => {
+                    Ipv4Addr::new(data[0], data[1], data[2], data[3]).into()
+                }
+                (DatasetType::DSIpv6, 16) if data[4..].iter().all(|b| *b == 0) => {
+                    Ipv4Addr::new(data[0], data[1], data[2], data[3]).into()
+                }
+                (DatasetType::DSIpv6, 16) => {
+                    let octets: [u8; 16] = data.try_into().ok()?;
+                    Ipv6Addr::from(octets).into()
+                }
+                _ => return None,
+            };
+            let mut w = &mut buf[..];
+            write!(w, "{}", addr).ok()?;
+            let left = w.len();
+            buf.len() - left
+        }
+    };
+    Some(&buf[..len])
 }
 
-fn read_or_create_file<P>(filename: P, fmode: &str) -> io::Result<io::Lines<io::BufReader<File>>>
+fn read_or_create_file<P>(filename: P, fmode: &str) -> io::Result<io::BufReader<File>>
 where
     P: AsRef<Path>,
 {
@@ -299,5 +701,1058 @@ where
             .read(true)
             .open(filename)?
     };
-    Ok(io::BufReader::new(file).lines())
+    Ok(io::BufReader::new(file))
+}
+
+/// Where the values of a read-only dataset are kept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub enum DatasetBackend {
+    /// values are decoded and copied to the heap
+    DSBackendMemory = 0,
+    /// the file is mapped and only an index of its values is kept
+    DSBackendMmap,
+}
+
+/// Error of the operations of a `DatasetStore`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DatasetStoreError {
+    /// the store is memory mapped and cannot be changed
+    ReadOnly,
+}
+
+impl std::fmt::Display for DatasetStoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatasetStoreError::ReadOnly => write!(f, "memory mapped dataset is read-only"),
+        }
+    }
+}
+
+impl std::error::Error for DatasetStoreError {}
+
+/// A read-only memory mapping of a whole file.
+///
+/// The file must not be changed in place while it is mapped, a new version
+/// has to be renamed over it. As a change in place can still happen, the
+/// size and modification time of the file are checked before the mapping
+/// is used, and a changed file is no longer looked up.
+#[cfg(unix)]
+struct DatasetMmap {
+    ptr: *mut libc::c_void,
+    len: usize,
+    file: File,
+    mtime: (i64, i64),
+    /// set once a change of the file is noticed
+    changed: AtomicBool,
+}
+
+// the mapping is never written to
+#[cfg(unix)]
+unsafe impl Send for DatasetMmap {}
+#[cfg(unix)]
+unsafe impl Sync for DatasetMmap {}
+
+#[cfg(unix)]
+impl DatasetMmap {
+    fn open(file: &File) -> io::Result<Self> {
+        let meta = file.metadata()?;
+        let len = usize::try_from(meta.len())
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+        if len == 0 {
+            // mmap rejects empty mappings
+            return Err(io::Error::from(io::ErrorKind::InvalidInput));
+        }
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(DatasetMmap {
+            ptr,
+            len,
+            file: file.try_clone()?,
+            mtime: (meta.mtime(), meta.mtime_nsec()),
+            changed: AtomicBool::new(false),
+        })
+    }
+
+    /// Whether the mapped file is still the one that was indexed: the inode
+    /// of the descriptor is the mapped one, so a change in place shows in
+    /// its size or modification time.
+    fn unchanged(&self) -> bool {
+        if self.changed.load(Ordering::Relaxed) {
+            return false;
+        }
+        let unchanged = self.file.metadata().map_or(false, |m| {
+            m.len() == self.len as u64 && (m.mtime(), m.mtime_nsec()) == self.mtime
+        });
+        if !unchanged && !self.changed.swap(true, Ordering::Relaxed) {
+            SCLogWarning!(
+                "mapped dataset file changed in place, its mapped values are ignored until a reload"
+            );
+        }
+        unchanged
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for DatasetMmap {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
+
+/// Files are only mapped on unix, elsewhere the sets are loaded in memory.
+#[cfg(not(unix))]
+enum DatasetMmap {}
+
+#[cfg(not(unix))]
+impl DatasetMmap {
+    fn open(_file: &File) -> io::Result<Self> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    fn unchanged(&self) -> bool {
+        match *self {}
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        match *self {}
+    }
+}
+
+enum DatasetValues {
+    Memory(HashSet<Vec<u8>>),
+    /// offset and length of the values of the mapped file that are written
+    /// the way the set encodes them, sorted by their encoding. The values
+    /// written another way, like uppercase hex, are kept decoded in `other`.
+    Mmap {
+        map: DatasetMmap,
+        index: Vec<(usize, u32)>,
+        other: HashSet<Vec<u8>>,
+    },
+}
+
+/// Set of the values of a dataset file, for membership checks, using the
+/// same `value[,rep]` lines as `ParseDatasets`. The reputation is ignored,
+/// and empty or invalid lines are skipped.
+///
+/// A `nocase` string set lowercases the values it stores and the ones it
+/// is queried with.
+pub struct DatasetStore {
+    dstype: DatasetType,
+    nocase: bool,
+    values: DatasetValues,
+}
+
+/// Decodes a value of a dataset file, lowercased for a `nocase` set.
+fn dataset_decode(value: &str, dstype: &DatasetType, nocase: bool) -> Option<Vec<u8>> {
+    let mut decoded = dataset_decode_value(value, dstype)?;
+    if nocase {
+        decoded.make_ascii_lowercase();
+    }
+    Some(decoded)
+}
+
+/// Calls `f` with the offset and the decoded value of each valid line.
+fn dataset_lines<F>(data: &[u8], dstype: &DatasetType, nocase: bool, mut f: F)
+where
+    F: FnMut(usize, &[u8], Vec<u8>),
+{
+    let mut offset = 0;
+    for line in data.split(|&c| c == b'\n') {
+        let start = offset;
+        offset += line.len() + 1;
+        let v: Vec<&str> = match std::str::from_utf8(line) {
+            Ok(line) => line.split(',').collect(),
+            Err(_) => continue,
+        };
+        let value = v[0].trim();
+        if value.is_empty() || v.len() > 2 {
+            continue;
+        }
+        if let Some(decoded) = dataset_decode(value, dstype, nocase) {
+            // offset of the trimmed value within the line
+            let value_start = start + (value.as_ptr() as usize - line.as_ptr() as usize);
+            f(value_start, value.as_bytes(), decoded);
+        }
+    }
+}
+
+impl DatasetStore {
+    /// Loads the values of `filename` with `backend`. A file which cannot
+    /// be mapped, like an empty file or a pipe, is loaded in memory. Only
+    /// string sets can be `nocase`.
+    pub fn open<P: AsRef<Path>>(
+        filename: P, dstype: DatasetType, backend: DatasetBackend, nocase: bool,
+    ) -> io::Result<Self> {
+        if nocase && !matches!(dstype, DatasetType::DSString) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "nocase is only supported for string sets",
+            ));
+        }
+        let mut file = File::open(filename)?;
+        if backend == DatasetBackend::DSBackendMmap {
+            match DatasetMmap::open(&file) {
+                Ok(map) => return Ok(Self::from_mmap(map, dstype, nocase)),
+                Err(_e) => {
+                    SCLogDebug!("dataset cannot be mapped, loading it in memory: {}", _e);
+                }
+            }
+        }
+        let mut data = Vec::new();
+        io::Read::read_to_end(&mut file, &mut data)?;
+        let mut set = HashSet::new();
+        dataset_lines(&data, &dstype, nocase, |_, _, decoded| {
+            set.insert(decoded);
+        });
+        Ok(DatasetStore {
+            dstype,
+            nocase,
+            values: DatasetValues::Memory(set),
+        })
+    }
+
+    fn from_mmap(map: DatasetMmap, dstype: DatasetType, nocase: bool) -> Self {
+        let mut index = Vec::new();
+        let mut other = HashSet::new();
+        dataset_lines(map.as_slice(), &dstype, nocase, |offset, value, decoded| {
+            // lookups compare the encoded query with the file
+            if dataset_encode_value(&decoded, &dstype).map_or(false, |e| e.as_bytes() == value) {
+                index.push((offset, value.len() as u32));
+            } else {
+                other.insert(decoded);
+            }
+        });
+        let data = map.as_slice();
+        let value = |&(offset, len): &(usize, u32)| &data[offset..offset + len as usize];
+        index.sort_unstable_by(|a, b| value(a).cmp(value(b)));
+        index.dedup_by(|a, b| value(a) == value(b));
+        DatasetStore {
+            dstype,
+            nocase,
+            values: DatasetValues::Mmap { map, index, other },
+        }
+    }
+
+    pub fn backend(&self) -> DatasetBackend {
+        match self.values {
+            DatasetValues::Memory(_) => DatasetBackend::DSBackendMemory,
+            DatasetValues::Mmap { .. } => DatasetBackend::DSBackendMmap,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.values {
+            DatasetValues::Memory(set) => set.len(),
+            DatasetValues::Mmap { index, other, .. } => index.len() + other.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn nocase(&self) -> bool {
+        self.nocase
+    }
+
+    /// `data` as stored in the set.
+    fn normalize<'a>(&self, data: &'a [u8]) -> Cow<'a, [u8]> {
+        if self.nocase {
+            Cow::Owned(data.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(data)
+        }
+    }
+
+    pub fn contains(&self, data: &[u8]) -> bool {
+        let data = self.normalize(data);
+        let data = data.as_ref();
+        match &self.values {
+            DatasetValues::Memory(set) => set.contains(data),
+            DatasetValues::Mmap { map, index, other } => {
+                if !map.unchanged() {
+                    return other.contains(data);
+                }
+                let map = map.as_slice();
+                let mut buf = [0u8; DATASET_ENCODED_STACK_LEN];
+                let heap;
+                let encoded = match dataset_encode_into(data, &self.dstype, &mut buf) {
+                    Some(encoded) => Some(encoded),
+                    // only long strings don't fit
+                    None => {
+                        heap = dataset_encode_value(data, &self.dstype);
+                        heap.as_ref().map(|e| e.as_bytes())
+                    }
+                };
+                let found = encoded.map_or(false, |encoded| {
+                    index
+                        .binary_search_by(|&(offset, len)| {
+                            map[offset..offset + len as usize].cmp(encoded)
+                        })
+                        .is_ok()
+                });
+                found || other.contains(data)
+            }
+        }
+    }
+
+    /// Adds `data`. Returns true if it was not in the set before.
+    pub fn insert(&mut self, data: &[u8]) -> Result<bool, DatasetStoreError> {
+        let data = self.normalize(data).into_owned();
+        match &mut self.values {
+            DatasetValues::Memory(set) => Ok(set.insert(data)),
+            DatasetValues::Mmap { .. } => Err(DatasetStoreError::ReadOnly),
+        }
+    }
+}
+
+/// Opens the dataset file `fname` with `backend`, or returns NULL on error.
+#[no_mangle]
+pub unsafe extern "C" fn SCDatasetStoreOpen(
+    fname: *const c_char, dstype: DatasetType, backend: DatasetBackend, nocase: bool,
+) -> *mut DatasetStore {
+    let fname = unwrap_or_return!(CStr::from_ptr(fname).to_str(), std::ptr::null_mut());
+    match DatasetStore::open(fname, dstype, backend, nocase) {
+        Ok(store) => Box::into_raw(Box::new(store)),
+        Err(e) => {
+            SCLogError!("failed to open dataset {}: {}", fname, e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Backend in use, which is memory if the file could not be mapped.
+#[no_mangle]
+pub extern "C" fn SCDatasetStoreBackend(store: &DatasetStore) -> DatasetBackend {
+    store.backend()
+}
+
+/// A dataset store that can be replaced while it is in use. Lookups see
+/// either the whole previous store or the whole new one, never a store
+/// being loaded.
+///
+/// Readers register in the counter of the current epoch, out of two,
+/// before loading the store pointer. A reload bumps the epoch after
+/// swapping the pointer, and frees the previous store once the readers of
+/// the previous epoch are done, readers coming after it not delaying it.
+pub struct DatasetSwap {
+    current: AtomicPtr<DatasetStore>,
+    epoch: AtomicUsize,
+    readers: [AtomicUsize; 2],
+    /// serializes the reloads
+    reload: Mutex<()>,
+}
+
+/// Unregisters a reader, even if the lookup panics.
+struct DatasetSwapReader<'a>(&'a AtomicUsize);
+
+impl Drop for DatasetSwapReader<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl DatasetSwap {
+    pub fn new(store: DatasetStore) -> Self {
+        DatasetSwap {
+            current: AtomicPtr::new(Box::into_raw(Box::new(store))),
+            epoch: AtomicUsize::new(0),
+            readers: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            reload: Mutex::new(()),
+        }
+    }
+
+    /// Calls `f` with the current store, which stays valid for the call
+    /// even if a reload swaps it in the meantime.
+    pub fn with<R, F: FnOnce(&DatasetStore) -> R>(&self, f: F) -> R {
+        let _reader = loop {
+            let epoch = self.epoch.load(Ordering::SeqCst);
+            let slot = &self.readers[epoch & 1];
+            slot.fetch_add(1, Ordering::SeqCst);
+            let reader = DatasetSwapReader(slot);
+            // with a stale epoch, the reload which frees the store we load
+            // may not wait for our slot
+            if self.epoch.load(Ordering::SeqCst) == epoch {
+                break reader;
+            }
+        };
+        let store = unsafe { &*self.current.load(Ordering::SeqCst) };
+        f(store)
+    }
+
+    pub fn contains(&self, data: &[u8]) -> bool {
+        self.with(|store| store.contains(data))
+    }
+
+    /// Replaces the store by `store`, returning once no reader uses the
+    /// previous one anymore.
+    pub fn swap(&self, store: DatasetStore) {
+        let _reload = self.reload.lock().unwrap_or_else(|e| e.into_inner());
+        let old = self
+            .current
+            .swap(Box::into_raw(Box::new(store)), Ordering::SeqCst);
+        let epoch = self.epoch.fetch_add(1, Ordering::SeqCst);
+        while self.readers[epoch & 1].load(Ordering::SeqCst) != 0 {
+            std::thread::yield_now();
+        }
+        std::mem::drop(unsafe { Box::from_raw(old) });
+    }
+
+    /// Loads `filename` aside, with the type of the current store, and
+    /// swaps it in. On error, the current store is kept.
+    pub fn reload_atomic<P: AsRef<Path>>(
+        &self, filename: P, backend: DatasetBackend,
+    ) -> io::Result<()> {
+        let (dstype, nocase) = self.with(|store| (store.dstype, store.nocase));
+        let store = DatasetStore::open(filename, dstype, backend, nocase)?;
+        self.swap(store);
+        Ok(())
+    }
+}
+
+impl Drop for DatasetSwap {
+    fn drop(&mut self) {
+        std::mem::drop(unsafe { Box::from_raw(*self.current.get_mut()) });
+    }
+}
+
+/// Takes ownership of `store` to make it reloadable.
+#[no_mangle]
+pub unsafe extern "C" fn SCDatasetSwapNew(store: *mut DatasetStore) -> *mut DatasetSwap {
+    if store.is_null() {
+        return std::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(DatasetSwap::new(*Box::from_raw(store))))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDatasetSwapLookup(
+    swap: &DatasetSwap, data: *const u8, len: u32,
+) -> bool {
+    swap.contains(build_slice!(data, len as usize))
+}
+
+/// Reloads the set from `fname`, keeping the current one on error.
+#[no_mangle]
+pub unsafe extern "C" fn SCDatasetSwapReload(
+    swap: &DatasetSwap, fname: *const c_char, backend: DatasetBackend,
+) -> bool {
+    let fname = unwrap_or_return!(CStr::from_ptr(fname).to_str(), false);
+    if let Err(e) = swap.reload_atomic(fname, backend) {
+        SCLogError!("failed to reload dataset {}: {}", fname, e);
+        return false;
+    }
+    true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDatasetSwapFree(swap: *mut DatasetSwap) {
+    if !swap.is_null() {
+        std::mem::drop(Box::from_raw(swap));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::uint::detect_parse_uint;
+
+    #[test]
+    fn test_dataset_count_threshold() {
+        let du32 = detect_parse_uint::<u32>(">3").unwrap().1;
+        let mut rep = DataRepType::new(0);
+
+        SCDatasetRepAdded(&mut rep, true, 0);
+        assert_eq!(rep.count, 1);
+        assert!(!SCDatasetCountMatch(true, &rep, &du32));
+        SCDatasetRepAdded(&mut rep, false, 0);
+        SCDatasetRepAdded(&mut rep, false, 0);
+        assert_eq!(rep.count, 3);
+        assert!(!SCDatasetCountMatch(true, &rep, &du32));
+        // fourth time the value is added
+        SCDatasetRepAdded(&mut rep, false, 0);
+        assert!(SCDatasetCountMatch(true, &rep, &du32));
+
+        // an entry removed then added again starts over
+        SCDatasetRepAdded(&mut rep, true, 0);
+        assert_eq!(rep.count, 1);
+
+        rep.count = u32::MAX;
+        SCDatasetRepAdded(&mut rep, false, 0);
+        assert_eq!(rep.count, u32::MAX);
+    }
+
+    #[test]
+    fn test_dataset_count_not_found() {
+        let rep = DataRepType {
+            value: 0,
+            count: 5,
+            ts: 0,
+        };
+        // absent values have a count of 0, whatever is in `rep`
+        let du32 = detect_parse_uint::<u32>("<2").unwrap().1;
+        assert!(SCDatasetCountMatch(false, &rep, &du32));
+        let du32 = detect_parse_uint::<u32>(">=1").unwrap().1;
+        assert!(!SCDatasetCountMatch(false, &rep, &du32));
+        let du32 = detect_parse_uint::<u32>("2<>10").unwrap().1;
+        assert!(!SCDatasetCountMatch(false, &rep, &du32));
+        assert!(SCDatasetCountMatch(true, &rep, &du32));
+    }
+
+    #[test]
+    fn test_dataset_ttl_expiry() {
+        let mut rep = DataRepType::new(0);
+        SCDatasetRepAdded(&mut rep, true, 100);
+        assert!(!SCDatasetRepExpired(&rep, 60, 100));
+        assert!(!SCDatasetRepExpired(&rep, 60, 159));
+        assert!(SCDatasetRepExpired(&rep, 60, 160));
+
+        // adding the value again refreshes it
+        SCDatasetRepAdded(&mut rep, false, 150);
+        assert!(!SCDatasetRepExpired(&rep, 60, 160));
+        assert!(SCDatasetRepExpired(&rep, 60, 210));
+
+        // no ttl, no expiry
+        assert!(!SCDatasetRepExpired(&rep, 0, u64::MAX));
+        // clock going backwards
+        assert!(!SCDatasetRepExpired(&rep, 60, 10));
+    }
+
+    fn json_round_trip(dstype: DatasetType, input: &str) -> (String, JsonLoadStats) {
+        let mut entries: Vec<(Vec<u8>, DataRepType)> = Vec::new();
+        let stats = load_json(input.as_bytes(), &dstype, "test", |data, rep| {
+            entries.push((
+                data.to_vec(),
+                DataRepType {
+                    value: rep.value,
+                    count: rep.count,
+                    ts: rep.ts,
+                },
+            ));
+        });
+        let mut out = Vec::new();
+        save_json(
+            &mut out,
+            &dstype,
+            entries.iter().map(|(d, r)| (d.as_slice(), r)),
+        )
+        .unwrap();
+        (String::from_utf8(out).unwrap(), stats)
+    }
+
+    #[test]
+    fn test_dataset_json_round_trip_string() {
+        let input = "{\"value\":\"c3VyaWNhdGE=\",\"count\":3}\n\
+                     {\"count\":1,\"value\":\"\"}\n";
+        let (out, stats) = json_round_trip(DatasetType::DSString, input);
+        assert_eq!(
+            stats,
+            JsonLoadStats {
+                loaded: 2,
+                skipped: 0
+            }
+        );
+        assert_eq!(
+            out,
+            "{\"value\":\"c3VyaWNhdGE=\",\"count\":3}\n{\"value\":\"\",\"count\":1}\n"
+        );
+        let (again, _) = json_round_trip(DatasetType::DSString, &out);
+        assert_eq!(again, out);
+    }
+
+    #[test]
+    fn test_dataset_json_round_trip_ipv4() {
+        let input = "{\"value\":\"192.168.0.1\",\"count\":10}\n\
+                     \n\
+                     {\"value\":\"10.0.0.1\"}\n";
+        let (out, stats) = json_round_trip(DatasetType::DSIpv4, input);
+        assert_eq!(
+            stats,
+            JsonLoadStats {
+                loaded: 2,
+                skipped: 0
+            }
+        );
+        assert_eq!(
+            out,
+            "{\"value\":\"192.168.0.1\",\"count\":10}\n{\"value\":\"10.0.0.1\",\"count\":1}\n"
+        );
+        let (again, _) = json_round_trip(DatasetType::DSIpv4, &out);
+        assert_eq!(again, out);
+    }
+
+    #[test]
+    fn test_dataset_json_malformed_lines() {
+        let input = "{\"value\":\"1.2.3.4\",\"count\":2}\n\
+                     not json\n\
+                     {\"value\":\"1.2.3.256\"}\n\
+                     {\"value\":\"1.2.3.5\",\"count\":-1}\n\
+                     {\"value\":\"1.2.3.6\",\"count\":4294967296}\n\
+                     {\"count\":1}\n\
+                     {\"value\":1}\n\
+                     {\"value\":\"1.2.3.7\",\"count\":5}\n";
+        let (out, stats) = json_round_trip(DatasetType::DSIpv4, input);
+        assert_eq!(
+            stats,
+            JsonLoadStats {
+                loaded: 2,
+                skipped: 6
+            }
+        );
+        assert_eq!(
+            out,
+            "{\"value\":\"1.2.3.4\",\"count\":2}\n{\"value\":\"1.2.3.7\",\"count\":5}\n"
+        );
+
+        // invalid base64 for a string set
+        let (_, stats) = json_round_trip(DatasetType::DSString, "{\"value\":\"!!\"}\n");
+        assert_eq!(stats.skipped, 1);
+    }
+
+    #[test]
+    fn test_dataset_json_line() {
+        let rep = DataRepType {
+            value: 0,
+            count: 7,
+            ts: 0,
+        };
+        let data = [10u8, 0, 0, 1];
+        let mut out = [0 as c_char; 64];
+        let len = unsafe {
+            SCDatasetJsonLine(
+                DatasetType::DSIpv4,
+                data.as_ptr(),
+                4,
+                &rep,
+                out.as_mut_ptr(),
+                out.len(),
+            )
+        };
+        let line = unsafe { CStr::from_ptr(out.as_ptr()) }.to_str().unwrap();
+        assert_eq!(line, "{\"value\":\"10.0.0.1\",\"count\":7}\n");
+        assert_eq!(len as usize, line.len());
+
+        // too small for the line and its nul
+        let mut small = [0 as c_char; 31];
+        let len = unsafe {
+            SCDatasetJsonLine(
+                DatasetType::DSIpv4,
+                data.as_ptr(),
+                4,
+                &rep,
+                small.as_mut_ptr(),
+                small.len(),
+            )
+        };
+        assert_eq!(len, 0);
+    }
+
+    #[test]
+    fn test_dataset_starts_with_json() {
+        let mut r: &[u8] = b"\n  {\"value\":\"1.2.3.4\"}\n";
+        assert!(starts_with_json(&mut r));
+        let mut r: &[u8] = b"1.2.3.4\n";
+        assert!(!starts_with_json(&mut r));
+        let mut r: &[u8] = b"";
+        assert!(!starts_with_json(&mut r));
+    }
+
+    #[test]
+    fn test_dataset_bloom_params() {
+        assert!(DatasetBloom::new(0, 0.01).is_none());
+        assert!(DatasetBloom::new(1000, 0.0).is_none());
+        assert!(DatasetBloom::new(1000, 1.0).is_none());
+        assert!(DatasetBloom::new(1000, f64::NAN).is_none());
+        assert!(DatasetBloom::new(u64::MAX, 0.01).is_none());
+
+        let bloom = DatasetBloom::new(1000, 0.01).unwrap();
+        assert_eq!(bloom.hashes, 7);
+        assert_eq!(bloom.nbits, 10099);
+    }
+
+    #[test]
+    fn test_dataset_var_encode() {
+        let mut out = [0u8; DATASET_VAR_MAX_LEN];
+        let len = dataset_var_encode(1234, &DatasetType::DSString, &mut out).unwrap();
+        assert_eq!(&out[..len], b"1234");
+        let len = dataset_var_encode(u64::MAX, &DatasetType::DSString, &mut out).unwrap();
+        assert_eq!(&out[..len], b"18446744073709551615");
+
+        let len = dataset_var_encode(0xc0a80101, &DatasetType::DSIpv4, &mut out).unwrap();
+        assert_eq!(&out[..len], &[192, 168, 1, 1]);
+        assert!(dataset_var_encode(1 << 32, &DatasetType::DSIpv4, &mut out).is_none());
+
+        assert!(dataset_var_encode(1, &DatasetType::DSMd5, &mut out).is_none());
+        assert!(dataset_var_encode(1, &DatasetType::DSIpv6, &mut out).is_none());
+        // output too small
+        assert!(dataset_var_encode(1234, &DatasetType::DSString, &mut out[..3]).is_none());
+        assert!(dataset_var_encode(1, &DatasetType::DSIpv4, &mut out[..3]).is_none());
+    }
+
+    #[test]
+    fn test_dataset_bloom_fp_rate() {
+        for fp_rate in [0.1, 0.01, 0.005] {
+            let entries = 10_000u32;
+            let bloom = DatasetBloom::new(entries as u64, fp_rate).unwrap();
+            for i in 0..entries {
+                bloom.insert(format!("member-{}", i).as_bytes());
+            }
+            // no false negatives
+            for i in 0..entries {
+                assert!(bloom.contains(format!("member-{}", i).as_bytes()));
+            }
+            let samples = 100_000u32;
+            let fp = (0..samples)
+                .filter(|i| bloom.contains(format!("other-{}", i).as_bytes()))
+                .count();
+            let measured = fp as f64 / samples as f64;
+            assert!(measured <= fp_rate, "{} > {}", measured, fp_rate);
+        }
+    }
+
+    #[test]
+    fn test_dataset_bloom_insert() {
+        let bloom = DatasetBloom::new(100, 0.01).unwrap();
+        assert!(!bloom.contains(b"suricata"));
+        assert!(bloom.insert(b"suricata"));
+        assert!(!bloom.insert(b"suricata"));
+        assert!(bloom.contains(b"suricata"));
+        assert!(bloom.insert(&[]));
+        assert!(bloom.contains(&[]));
+    }
+
+    /// Writes `content` to a file of its own in the temporary directory.
+    fn store_file(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "suricata-dataset-{}-{}.lst",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn stores(name: &str, content: &str, dstype: fn() -> DatasetType) -> [DatasetStore; 2] {
+        let path = store_file(name, content);
+        let mem =
+            DatasetStore::open(&path, dstype(), DatasetBackend::DSBackendMemory, false).unwrap();
+        let map =
+            DatasetStore::open(&path, dstype(), DatasetBackend::DSBackendMmap, false).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(mem.backend(), DatasetBackend::DSBackendMemory);
+        if cfg!(unix) {
+            assert_eq!(map.backend(), DatasetBackend::DSBackendMmap);
+        }
+        [mem, map]
+    }
+
+    #[test]
+    fn test_dataset_store_backends_string() {
+        // suricata, oisf, a duplicate, an invalid line and a datarep line
+        let content = "c3VyaWNhdGE=\nb2lzZg==\r\nc3VyaWNhdGE=\n!!!\n\nZXZl,10\n";
+        let [mem, map] = stores("string", content, || DatasetType::DSString);
+        assert_eq!(mem.len(), 3);
+        assert_eq!(map.len(), 3);
+        for query in [&b"suricata"[..], b"oisf", b"eve", b"", b"suricat", b"OISF"] {
+            assert_eq!(mem.contains(query), map.contains(query), "{:?}", query);
+        }
+        assert!(map.contains(b"suricata"));
+        assert!(map.contains(b"eve"));
+        assert!(!map.contains(b"OISF"));
+    }
+
+    #[test]
+    fn test_dataset_store_backends_ip() {
+        let content = "10.0.0.1\n192.168.1.1\n10.0.0.255\nnot-an-ip\n";
+        let [mem, map] = stores("ipv4", content, || DatasetType::DSIpv4);
+        for last in 0..=255u8 {
+            let query = [10, 0, 0, last];
+            assert_eq!(mem.contains(&query), map.contains(&query), "{:?}", query);
+        }
+        assert!(map.contains(&[192, 168, 1, 1]));
+
+        let content = "2001:db8::1\n::ffff:10.0.0.1\nfe80::1\n";
+        let [mem, map] = stores("ipv6", content, || DatasetType::DSIpv6);
+        let mut v4 = [0u8; 16];
+        v4[..4].copy_from_slice(&[10, 0, 0, 1]);
+        let queries = [
+            "2001:db8::1".parse::<Ipv6Addr>().unwrap().octets(),
+            "2001:db8::2".parse::<Ipv6Addr>().unwrap().octets(),
+            "fe80::1".parse::<Ipv6Addr>().unwrap().octets(),
+            v4,
+        ];
+        for query in queries {
+            assert_eq!(mem.contains(&query), map.contains(&query), "{:?}", query);
+        }
+        assert!(map.contains(&v4));
+    }
+
+    #[test]
+    fn test_dataset_store_backends_md5() {
+        // the last value is not written the way the set encodes it
+        let content = "d41d8cd98f00b204e9800998ecf8427e\n\
+                       900150983cd24fb0d6963f7d28e17f72\n\
+                       0123\n\
+                       E4D909C290D0FB1CA068FFADDF22CBD0\n";
+        let [mem, map] = stores("md5", content, || DatasetType::DSMd5);
+        assert_eq!(map.len(), 3);
+        let empty = hex::decode("d41d8cd98f00b204e9800998ecf8427e").unwrap();
+        let upper = hex::decode("e4d909c290d0fb1ca068ffaddf22cbd0").unwrap();
+        let other = hex::decode("00000000000000000000000000000000").unwrap();
+        for query in [&empty, &upper, &other] {
+            assert_eq!(mem.contains(query), map.contains(query));
+        }
+        assert!(map.contains(&empty));
+        assert!(map.contains(&upper));
+    }
+
+    // files are only mapped on unix
+    #[cfg(unix)]
+    #[test]
+    fn test_dataset_store_read_only() {
+        let [mut mem, mut map] = stores("ro", "b2lzZg==\n", || DatasetType::DSString);
+        assert_eq!(map.insert(b"suricata"), Err(DatasetStoreError::ReadOnly));
+        assert!(!map.contains(b"suricata"));
+        assert_eq!(mem.insert(b"suricata"), Ok(true));
+        assert_eq!(mem.insert(b"oisf"), Ok(false));
+        assert!(mem.contains(b"suricata"));
+    }
+
+    #[test]
+    fn test_dataset_store_mmap_fallback() {
+        // an empty file cannot be mapped
+        let path = store_file("empty", "");
+        let mut store = DatasetStore::open(
+            &path,
+            DatasetType::DSString,
+            DatasetBackend::DSBackendMmap,
+            false,
+        )
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(store.backend(), DatasetBackend::DSBackendMemory);
+        assert!(store.is_empty());
+        assert_eq!(store.insert(b"suricata"), Ok(true));
+    }
+
+    #[test]
+    fn test_dataset_encode_into() {
+        let v6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        let mapped = ipv6_to_internal("::ffff:10.0.0.1".parse().unwrap());
+        let md5 = hex::decode("d41d8cd98f00b204e9800998ecf8427e").unwrap();
+        let cases: [(DatasetType, &[u8]); 6] = [
+            (DatasetType::DSString, b"suricata"),
+            (DatasetType::DSString, b""),
+            (DatasetType::DSMd5, &md5),
+            (DatasetType::DSIpv4, &[192, 168, 0, 1]),
+            (DatasetType::DSIpv6, &v6.octets()),
+            (DatasetType::DSIpv6, &mapped.octets()),
+        ];
+        for (dstype, data) in cases {
+            let mut buf = [0u8; DATASET_ENCODED_STACK_LEN];
+            let expected = dataset_encode_value(data, &dstype).unwrap();
+            assert_eq!(
+                dataset_encode_into(data, &dstype, &mut buf),
+                Some(expected.as_bytes()),
+                "{:?}",
+                data
+            );
+        }
+        assert_eq!(
+            dataset_encode_into(&[10, 0, 0, 1], &DatasetType::DSIpv4, &mut [0u8; 64]),
+            Some(&b"10.0.0.1"[..])
+        );
+
+        // too long for the buffer
+        let mut buf = [0u8; DATASET_ENCODED_STACK_LEN];
+        assert!(dataset_encode_into(&[b'a'; 193], &DatasetType::DSString, &mut buf).is_none());
+        assert!(dataset_encode_into(&[b'a'; 192], &DatasetType::DSString, &mut buf).is_some());
+        assert!(dataset_encode_into(&md5, &DatasetType::DSMd5, &mut [0u8; 31]).is_none());
+        assert!(dataset_encode_into(&[1, 2, 3], &DatasetType::DSIpv4, &mut buf).is_none());
+    }
+
+    #[test]
+    fn test_dataset_store_mmap_long_string() {
+        let long = [b'a'; 300];
+        let content = format!(
+            "{}\n",
+            base64::engine::general_purpose::STANDARD.encode(long)
+        );
+        let [mem, map] = stores("long", &content, || DatasetType::DSString);
+        assert!(mem.contains(&long));
+        assert!(map.contains(&long));
+        assert!(!map.contains(&long[1..]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dataset_store_mmap_changed() {
+        // suricata, oisf
+        let path = store_file("changed", "c3VyaWNhdGE=\nb2lzZg==\n");
+        let store = DatasetStore::open(
+            &path,
+            DatasetType::DSString,
+            DatasetBackend::DSBackendMmap,
+            false,
+        )
+        .unwrap();
+        assert_eq!(store.backend(), DatasetBackend::DSBackendMmap);
+        assert!(store.contains(b"suricata"));
+
+        // a new file renamed over the mapped one leaves the mapping as is
+        let new = store_file("changed-new", "ZXZl\n");
+        std::fs::rename(&new, &path).unwrap();
+        assert!(store.contains(b"suricata"));
+        assert!(!store.contains(b"eve"));
+
+        let store = DatasetStore::open(
+            &path,
+            DatasetType::DSString,
+            DatasetBackend::DSBackendMmap,
+            false,
+        )
+        .unwrap();
+        assert!(store.contains(b"eve"));
+        // a change in place is noticed before the mapping is read
+        std::fs::write(&path, "").unwrap();
+        assert!(!store.contains(b"eve"));
+        std::fs::write(&path, "b2lzZg==\n").unwrap();
+        assert!(!store.contains(b"eve"));
+        assert!(!store.contains(b"oisf"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_dataset_swap_reload() {
+        // a1 and a2 in the first set, b1 and b2 in the second
+        let path_a = store_file("swap-a", "YTE=\nYTI=\n");
+        let path_b = store_file("swap-b", "YjE=\nYjI=\n");
+        let store = DatasetStore::open(
+            &path_a,
+            DatasetType::DSString,
+            DatasetBackend::DSBackendMemory,
+            false,
+        )
+        .unwrap();
+        let swap = DatasetSwap::new(store);
+        let done = std::sync::atomic::AtomicBool::new(false);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    while !done.load(Ordering::Relaxed) {
+                        let (a, b) = swap.with(|store| {
+                            (
+                                [store.contains(b"a1"), store.contains(b"a2")],
+                                [store.contains(b"b1"), store.contains(b"b2")],
+                            )
+                        });
+                        // always one whole set
+                        assert!(
+                            (a == [true, true] && b == [false, false])
+                                || (a == [false, false] && b == [true, true]),
+                            "{:?} {:?}",
+                            a,
+                            b
+                        );
+                        swap.contains(b"a1");
+                    }
+                });
+            }
+            for i in 0..100 {
+                let (path, backend) = if i % 2 == 0 {
+                    (&path_b, DatasetBackend::DSBackendMmap)
+                } else {
+                    (&path_a, DatasetBackend::DSBackendMemory)
+                };
+                swap.reload_atomic(path, backend).unwrap();
+            }
+            done.store(true, Ordering::Relaxed);
+        });
+        // the last reload was of the first set
+        assert!(swap.contains(b"a1"));
+        assert!(!swap.contains(b"b1"));
+
+        // a failed reload keeps the current set
+        std::fs::remove_file(&path_b).unwrap();
+        assert!(swap
+            .reload_atomic(&path_b, DatasetBackend::DSBackendMemory)
+            .is_err());
+        assert!(swap.contains(b"a2"));
+        std::fs::remove_file(&path_a).unwrap();
+    }
+
+    #[test]
+    fn test_dataset_store_nocase() {
+        // Example.COM, oisf.net
+        let path = store_file("nocase", "RXhhbXBsZS5DT00=\nb2lzZi5uZXQ=\n");
+        for backend in [
+            DatasetBackend::DSBackendMemory,
+            DatasetBackend::DSBackendMmap,
+        ] {
+            let exact = DatasetStore::open(&path, DatasetType::DSString, backend, false).unwrap();
+            let nocase = DatasetStore::open(&path, DatasetType::DSString, backend, true).unwrap();
+            assert!(!exact.nocase());
+            assert!(nocase.nocase());
+            for query in [&b"example.com"[..], b"EXAMPLE.COM", b"Example.COM"] {
+                assert!(nocase.contains(query), "{:?}", query);
+            }
+            assert!(exact.contains(b"Example.COM"));
+            assert!(!exact.contains(b"example.com"));
+            assert!(!exact.contains(b"OISF.NET"));
+            assert!(nocase.contains(b"OISF.NET"));
+            assert!(!nocase.contains(b"example.org"));
+        }
+
+        let mut nocase = DatasetStore::open(
+            &path,
+            DatasetType::DSString,
+            DatasetBackend::DSBackendMemory,
+            true,
+        )
+        .unwrap();
+        let mut exact = DatasetStore::open(
+            &path,
+            DatasetType::DSString,
+            DatasetBackend::DSBackendMemory,
+            false,
+        )
+        .unwrap();
+        assert_eq!(nocase.insert(b"Suricata.IO"), Ok(true));
+        assert_eq!(nocase.insert(b"SURICATA.io"), Ok(false));
+        assert_eq!(nocase.insert(b"EXAMPLE.com"), Ok(false));
+        assert!(nocase.contains(b"suricata.io"));
+        // the sets are independent
+        assert!(!exact.contains(b"Suricata.IO"));
+        assert_eq!(exact.insert(b"Suricata.IO"), Ok(true));
+        assert_eq!(exact.insert(b"SURICATA.io"), Ok(true));
+        assert!(!exact.contains(b"suricata.io"));
+
+        // only for strings
+        assert!(DatasetStore::open(
+            &path,
+            DatasetType::DSMd5,
+            DatasetBackend::DSBackendMemory,
+            true
+        )
+        .is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
 }
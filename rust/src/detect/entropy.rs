@@ -18,8 +18,12 @@
 // Author: Jeff Lucovsky <jlucovsky@oisf.net>
 //
 use crate::detect::error::RuleParseError;
-use crate::detect::float::{detect_match_float, detect_parse_float, DetectFloatData};
+use crate::detect::float::{
+    detect_match_float, detect_parse_float, detect_parse_float_tolerance, DetectFloatData,
+};
 use crate::detect::parser::take_until_whitespace;
+use crate::detect::tojson::{detect_byte_histogram_to_json, detect_float_to_json};
+use crate::jsonbuilder::{JsonBuilder, JsonError};
 
 use nom7::bytes::complete::tag;
 use nom7::character::complete::multispace0;
@@ -28,12 +32,42 @@ use nom7::{Err, IResult};
 
 use std::ffi::CStr;
 use std::os::raw::{c_char, c_void};
-use std::slice;
+
+/// Logarithm base of the entropy, base 2 giving bits per byte.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EntropyBase {
+    #[default]
+    Base2,
+    BaseE,
+    Base10,
+}
+
+impl EntropyBase {
+    /// Converts an entropy in bits to this base.
+    pub fn convert_bits(self, bits: f64) -> f64 {
+        match self {
+            EntropyBase::Base2 => bits,
+            EntropyBase::BaseE => bits * std::f64::consts::LN_2,
+            EntropyBase::Base10 => bits * std::f64::consts::LOG10_2,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct DetectEntropyData {
     offset: i32,
     nbytes: i32,
+    // buffer length guards, 0 when not set
+    minlen: u32,
+    maxlen: u32,
+    // sliding window size, 0 when not set
+    window: u32,
+    // compare the chi-square statistic instead of the entropy
+    chisquare: bool,
+    // number of byte values of the histogram logged on match, 0 when not set
+    log_histogram: u32,
+    // base of the entropy, the value is in that base too
+    base: EntropyBase,
     value: DetectFloatData<f64>,
 }
 
@@ -42,6 +76,12 @@ impl Default for DetectEntropyData {
         DetectEntropyData {
             offset: 0,
             nbytes: 0,
+            minlen: 0,
+            maxlen: 0,
+            window: 0,
+            chisquare: false,
+            log_histogram: 0,
+            base: EntropyBase::Base2,
             value: DetectFloatData::<f64>::default(),
         }
     }
@@ -56,13 +96,24 @@ impl DetectEntropyData {
 
 // All options have default values except for the entropy value
 const DETECT_ENTROPY_FIXED_PARAM_COUNT: usize = 1;
-const DETECT_ENTROPY_MAX_PARAM_COUNT: usize = 4;
-pub const DETECT_ENTROPY_FLAG_BYTES: u8 = 0x01;
-pub const DETECT_ENTROPY_FLAG_OFFSET: u8 = 0x02;
-pub const DETECT_ENTROPY_FLAG_VALUE: u8 = 0x04;
+const DETECT_ENTROPY_MAX_PARAM_COUNT: usize = 10;
+pub const DETECT_ENTROPY_FLAG_BYTES: u16 = 0x01;
+pub const DETECT_ENTROPY_FLAG_OFFSET: u16 = 0x02;
+pub const DETECT_ENTROPY_FLAG_VALUE: u16 = 0x04;
+pub const DETECT_ENTROPY_FLAG_MINLEN: u16 = 0x08;
+pub const DETECT_ENTROPY_FLAG_MAXLEN: u16 = 0x10;
+pub const DETECT_ENTROPY_FLAG_WINDOW: u16 = 0x20;
+pub const DETECT_ENTROPY_FLAG_CHISQUARE: u16 = 0x40;
+pub const DETECT_ENTROPY_FLAG_LOG_HISTOGRAM: u16 = 0x80;
+pub const DETECT_ENTROPY_FLAG_BASE: u16 = 0x100;
+pub const DETECT_ENTROPY_FLAG_EPSILON: u16 = 0x200;
+
+// Buffers shorter than this hold less than one expected occurrence of each
+// byte value, the chi-square statistic is meaningless for them.
+pub const DETECT_ENTROPY_CHISQUARE_MIN_LEN: usize = 256;
 
 fn parse_entropy<'a>(
-    input: &'a str, flags: &'a mut u8,
+    input: &'a str, flags: &'a mut u16,
 ) -> IResult<&'a str, DetectEntropyData, RuleParseError<&'a str>> {
     // Inner utility function for easy error creation.
     fn make_error(reason: String) -> nom7::Err<RuleParseError<&'static str>> {
@@ -81,6 +132,7 @@ fn parse_entropy<'a>(
     }
 
     let mut entropy = DetectEntropyData::new();
+    let mut epsilon = None;
     for value in values {
         let (mut val, mut name) = take_until_whitespace(value)?;
         val = val.trim();
@@ -110,11 +162,108 @@ fn parse_entropy<'a>(
                 }
                 *flags |= DETECT_ENTROPY_FLAG_OFFSET;
             }
+            "minlen" => {
+                if 0 != (*flags & DETECT_ENTROPY_FLAG_MINLEN) {
+                    return Err(make_error("minlen already set".to_string()));
+                }
+                entropy.minlen = val
+                    .parse::<u32>()
+                    .map_err(|_| make_error(format!("invalid minlen value: {}", val)))?;
+                *flags |= DETECT_ENTROPY_FLAG_MINLEN;
+            }
+            "maxlen" => {
+                if 0 != (*flags & DETECT_ENTROPY_FLAG_MAXLEN) {
+                    return Err(make_error("maxlen already set".to_string()));
+                }
+                entropy.maxlen = val
+                    .parse::<u32>()
+                    .map_err(|_| make_error(format!("invalid maxlen value: {}", val)))?;
+                if entropy.maxlen == 0 {
+                    return Err(make_error("maxlen must be greater than 0".to_string()));
+                }
+                *flags |= DETECT_ENTROPY_FLAG_MAXLEN;
+            }
+            "window" => {
+                if 0 != (*flags & DETECT_ENTROPY_FLAG_WINDOW) {
+                    return Err(make_error("window already set".to_string()));
+                }
+                entropy.window = val
+                    .parse::<u32>()
+                    .map_err(|_| make_error(format!("invalid window value: {}", val)))?;
+                if entropy.window == 0 {
+                    return Err(make_error("window must be greater than 0".to_string()));
+                }
+                *flags |= DETECT_ENTROPY_FLAG_WINDOW;
+            }
+            "chisquare" => {
+                if 0 != (*flags & DETECT_ENTROPY_FLAG_CHISQUARE) {
+                    return Err(make_error("chisquare already set".to_string()));
+                }
+                if !val.is_empty() {
+                    return Err(make_error(format!("chisquare takes no value: {}", val)));
+                }
+                entropy.chisquare = true;
+                *flags |= DETECT_ENTROPY_FLAG_CHISQUARE;
+            }
+            "log_histogram" => {
+                if 0 != (*flags & DETECT_ENTROPY_FLAG_LOG_HISTOGRAM) {
+                    return Err(make_error("log_histogram already set".to_string()));
+                }
+                entropy.log_histogram = val
+                    .parse::<u32>()
+                    .map_err(|_| make_error(format!("invalid log_histogram value: {}", val)))?;
+                if entropy.log_histogram == 0 || entropy.log_histogram > 256 {
+                    return Err(make_error(format!(
+                        "invalid log_histogram value: must be between 1 and 256: {}",
+                        val
+                    )));
+                }
+                *flags |= DETECT_ENTROPY_FLAG_LOG_HISTOGRAM;
+            }
+            "base" => {
+                if 0 != (*flags & DETECT_ENTROPY_FLAG_BASE) {
+                    return Err(make_error("base already set".to_string()));
+                }
+                entropy.base = match val {
+                    "2" => EntropyBase::Base2,
+                    "e" => EntropyBase::BaseE,
+                    "10" => EntropyBase::Base10,
+                    _ => {
+                        return Err(make_error(format!(
+                            "invalid base value: must be 2, e or 10: {}",
+                            val
+                        )));
+                    }
+                };
+                *flags |= DETECT_ENTROPY_FLAG_BASE;
+            }
+            "epsilon" => {
+                if 0 != (*flags & DETECT_ENTROPY_FLAG_EPSILON) {
+                    return Err(make_error("epsilon already set".to_string()));
+                }
+                match detect_parse_float_tolerance::<f64>(val) {
+                    Ok(("", e)) => epsilon = Some(e),
+                    _ => {
+                        return Err(make_error(format!(
+                            "invalid epsilon value: must be finite and not negative: {}",
+                            val
+                        )));
+                    }
+                }
+                *flags |= DETECT_ENTROPY_FLAG_EPSILON;
+            }
             "value" => {
                 if 0 != (*flags & DETECT_ENTROPY_FLAG_VALUE) {
                     return Err(make_error("value already set".to_string()));
                 }
                 if let Ok((_, ctx)) = detect_parse_float::<f64>(val) {
+                    // an entropy is never NaN nor infinite
+                    if ctx.mode.is_predicate() {
+                        return Err(make_error(format!(
+                            "is_nan and is_inf never match an entropy: {}",
+                            val
+                        )));
+                    }
                     entropy.value = ctx;
                     *flags |= DETECT_ENTROPY_FLAG_VALUE;
                 } else {
@@ -135,54 +284,205 @@ fn parse_entropy<'a>(
         )));
     }
 
+    // epsilon is a separate option, as the options are split on commas
+    if let Some(epsilon) = epsilon {
+        entropy.value.epsilon = epsilon;
+    }
+
+    if entropy.maxlen > 0 && entropy.minlen > entropy.maxlen {
+        return Err(make_error(format!(
+            "minlen {} is greater than maxlen {}",
+            entropy.minlen, entropy.maxlen
+        )));
+    }
+
+    if entropy.chisquare && entropy.window > 0 {
+        return Err(make_error(
+            "window cannot be used with chisquare".to_string(),
+        ));
+    }
+
+    if entropy.chisquare && (*flags & DETECT_ENTROPY_FLAG_BASE) != 0 {
+        return Err(make_error("base cannot be used with chisquare".to_string()));
+    }
+
     Ok((input, entropy))
 }
 
-fn calculate_entropy(data: &[u8]) -> f64 {
-    if data.is_empty() {
-        return 0.0;
+/// Computes the chi-square statistic of the byte histogram of `data` against
+/// a uniform distribution of the 256 byte values.
+///
+/// Returns `None` for buffers shorter than `DETECT_ENTROPY_CHISQUARE_MIN_LEN`.
+pub fn chi_square(data: &[u8]) -> Option<f64> {
+    if data.len() < DETECT_ENTROPY_CHISQUARE_MIN_LEN {
+        return None;
+    }
+
+    let mut frequency = [0u64; 256];
+    for &byte in data.iter() {
+        frequency[byte as usize] += 1;
     }
 
-    // Use a 256-element array to store byte frequencies
-    let mut frequency = [0u32; 256];
+    let expected = data.len() as f64 / 256.0;
+    let chi = frequency.iter().fold(0.0, |chi, &count| {
+        let diff = count as f64 - expected;
+        chi + diff * diff / expected
+    });
+    Some(chi)
+}
 
-    // Calculate the frequency of each byte
+/// Returns the `n` most frequent byte values of `data` with their counts,
+/// most frequent first. Values with the same count are in ascending order
+/// and values not in `data` are left out.
+pub fn byte_histogram_top(data: &[u8], n: usize) -> Vec<(u8, u64)> {
+    let mut frequency = [0u64; 256];
     for &byte in data.iter() {
         frequency[byte as usize] += 1;
     }
 
-    // Calculate entropy using byte frequencies
-    let length_f64 = data.len() as f64;
-    frequency.iter().fold(0.0, |entropy, &count| {
-        if count > 0 {
-            let probability = count as f64 / length_f64;
-            entropy - probability * probability.log2()
-        } else {
-            entropy
+    let mut top: Vec<(u8, u64)> = frequency
+        .iter()
+        .enumerate()
+        .filter(|(_, &count)| count > 0)
+        .map(|(byte, &count)| (byte as u8, count))
+        .collect();
+    // the sort is stable, so ties stay in byte order
+    top.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    top.truncate(n);
+    top
+}
+
+/// Computes the Shannon entropy of data fed in chunks, giving the same
+/// result as [`shannon_entropy`] on all the bytes at once.
+#[derive(Debug, Clone)]
+pub struct EntropyAccumulator {
+    frequency: [u64; 256],
+    len: u64,
+}
+
+impl Default for EntropyAccumulator {
+    fn default() -> Self {
+        EntropyAccumulator {
+            frequency: [0; 256],
+            len: 0,
         }
-    })
+    }
 }
 
-#[no_mangle]
-pub unsafe extern "C" fn SCDetectEntropyMatch(
-    c_data: *const c_void, length: i32, ctx: &DetectEntropyData,
-) -> bool {
-    if c_data.is_null() {
-        return false;
+impl EntropyAccumulator {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    let buffer = std::slice::from_raw_parts(c_data as *const u8, length as usize);
-    let mut start = buffer;
+    /// Adds the bytes of `data` to the histogram.
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data.iter() {
+            self.frequency[byte as usize] += 1;
+        }
+        self.len += data.len() as u64;
+    }
+
+    /// Computes the entropy of all the bytes fed so far, in bits per byte.
+    ///
+    /// Returns 0.0 if no byte was fed; the result is always within
+    /// `[0.0, 8.0]`.
+    pub fn finalize(&self) -> f64 {
+        if self.len == 0 {
+            return 0.0;
+        }
+
+        // Calculate entropy using byte frequencies
+        let length_f64 = self.len as f64;
+        let entropy = self.frequency.iter().fold(0.0, |entropy, &count| {
+            if count > 0 {
+                let probability = count as f64 / length_f64;
+                entropy - probability * probability.log2()
+            } else {
+                entropy
+            }
+        });
+        // rounding may push the sum slightly out of range
+        entropy.clamp(0.0, 8.0)
+    }
+}
+
+/// Computes the Shannon entropy of `data`, in bits per byte.
+///
+/// Returns 0.0 for empty input; the result is always within `[0.0, 8.0]`.
+pub fn shannon_entropy(data: &[u8]) -> f64 {
+    let mut acc = EntropyAccumulator::new();
+    acc.update(data);
+    acc.finalize()
+}
+
+/// Computes the Shannon entropy of the `len` bytes of `data` starting at
+/// `offset`.
+///
+/// Returns `None` if the window does not fit in `data`.
+pub fn shannon_entropy_window(data: &[u8], offset: usize, len: usize) -> Option<f64> {
+    let end = offset.checked_add(len)?;
+    let window = data.get(offset..end)?;
+    Some(shannon_entropy(window))
+}
+
+// count * log2(count), the contribution of a byte value to the entropy sum
+fn count_log2_count(count: u64) -> f64 {
+    if count == 0 {
+        return 0.0;
+    }
+    let c = count as f64;
+    c * c.log2()
+}
+
+/// Computes the maximum Shannon entropy over all windows of `window` bytes
+/// of `data`.
+///
+/// A window of 0 or one not smaller than `data` evaluates the whole buffer.
+pub fn shannon_entropy_max_window(data: &[u8], window: usize) -> f64 {
+    if window == 0 || window >= data.len() {
+        return shannon_entropy(data);
+    }
+
+    // The entropy of a window of size w is log2(w) - sum(c * log2(c)) / w,
+    // so only the sum needs updating as the window slides.
+    let mut frequency = [0u64; 256];
+    for &byte in &data[..window] {
+        frequency[byte as usize] += 1;
+    }
+    let mut sum: f64 = frequency.iter().map(|&c| count_log2_count(c)).sum();
+    let w = window as f64;
+    let log2_w = w.log2();
+    let mut max = log2_w - sum / w;
+
+    for i in window..data.len() {
+        let out = data[i - window] as usize;
+        let inp = data[i] as usize;
+        if out == inp {
+            continue;
+        }
+        sum -= count_log2_count(frequency[out]) + count_log2_count(frequency[inp]);
+        frequency[out] -= 1;
+        frequency[inp] += 1;
+        sum += count_log2_count(frequency[out]) + count_log2_count(frequency[inp]);
+        max = max.max(log2_w - sum / w);
+    }
+    // the running sum accumulates rounding errors
+    max.clamp(0.0, 8.0)
+}
+
+// Start and length of the bytes of a buffer of `length` bytes selected by
+// the offset and bytes options.
+fn entropy_window(ctx: &DetectEntropyData, length: i32) -> Option<(i32, i32)> {
+    let mut offset = 0;
     let mut count = length;
 
     // Adjust start and count based on offset and nbytes from context
     if ctx.offset > 0 {
-        let offset = ctx.offset;
+        offset = ctx.offset;
         if offset > count {
             SCLogDebug!("offset {} exceeds buffer length {}", offset, count);
-            return false;
+            return None;
         }
-        start = &start[offset as usize..];
         count -= offset;
     }
 
@@ -190,20 +490,137 @@ pub unsafe extern "C" fn SCDetectEntropyMatch(
         let nbytes = ctx.nbytes;
         if nbytes > count {
             SCLogDebug!("byte count {} exceeds buffer length {}", nbytes, count);
-            return false;
+            return None;
         }
         count = nbytes;
     }
+    Some((offset, count))
+}
+
+/// Measures the entropy of `c_data`, the inspection buffer after the
+/// transforms of the rule, and compares it with the value of `ctx`.
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectEntropyMatch(
+    c_data: *const c_void, length: i32, ctx: &DetectEntropyData,
+) -> bool {
+    if c_data.is_null() {
+        return false;
+    }
+
+    // Buffers outside of the length window never match
+    if (length as u32) < ctx.minlen || (ctx.maxlen > 0 && length as u32 > ctx.maxlen) {
+        SCLogDebug!(
+            "buffer length {} outside of [{}, {}]",
+            length,
+            ctx.minlen,
+            ctx.maxlen
+        );
+        return false;
+    }
+
+    let buffer = std::slice::from_raw_parts(c_data as *const u8, length as usize);
+    let (offset, count) = match entropy_window(ctx, length) {
+        Some(w) => w,
+        None => return false,
+    };
 
     // Calculate entropy based on the adjusted buffer slice
-    let data_slice = slice::from_raw_parts(start.as_ptr(), count as usize);
-    let entropy = calculate_entropy(data_slice);
+    let entropy = if ctx.chisquare {
+        let end = (offset + count) as usize;
+        match buffer.get(offset as usize..end).and_then(chi_square) {
+            Some(chi) => chi,
+            None => {
+                SCLogDebug!("buffer too short for chi-square: {}", count);
+                return false;
+            }
+        }
+    } else if ctx.window > 0 {
+        let end = (offset + count) as usize;
+        match buffer.get(offset as usize..end) {
+            Some(slice) => ctx
+                .base
+                .convert_bits(shannon_entropy_max_window(slice, ctx.window as usize)),
+            None => return false,
+        }
+    } else {
+        match shannon_entropy_window(buffer, offset as usize, count as usize) {
+            Some(entropy) => ctx.base.convert_bits(entropy),
+            None => return false,
+        }
+    };
     SCLogDebug!("entropy is {}", entropy);
 
     // Use a hypothetical `detect_entropy_match` function to check entropy
     detect_match_float::<f64>(&ctx.value, entropy)
 }
 
+/// Tells if the histogram of the buffer is to be logged when `ctx` matches.
+#[no_mangle]
+pub extern "C" fn SCDetectEntropyLogsHistogram(ctx: &DetectEntropyData) -> bool {
+    ctx.log_histogram > 0
+}
+
+/// Logs the most frequent byte values of the bytes `ctx` computed the
+/// entropy of into the array `js`.
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectEntropyHistogramToJson(
+    c_data: *const c_void, length: i32, ctx: &DetectEntropyData, js: &mut JsonBuilder,
+) -> bool {
+    if c_data.is_null() || length < 0 {
+        return false;
+    }
+    let (offset, count) = match entropy_window(ctx, length) {
+        Some(w) => w,
+        None => return false,
+    };
+    let buffer = std::slice::from_raw_parts(c_data as *const u8, length as usize);
+    let top = byte_histogram_top(
+        &buffer[offset as usize..(offset + count) as usize],
+        ctx.log_histogram as usize,
+    );
+    detect_byte_histogram_to_json(js, &top).is_ok()
+}
+
+/// Logs the options of `ctx` in the open object `js` for the rule
+/// analysis, the comparison being a `value` object logged like
+/// `detect_float_to_json` does.
+fn detect_entropy_to_json(js: &mut JsonBuilder, ctx: &DetectEntropyData) -> Result<(), JsonError> {
+    js.set_int("bytes", ctx.nbytes as i64)?;
+    js.set_int("offset", ctx.offset as i64)?;
+    if ctx.minlen > 0 {
+        js.set_uint("minlen", ctx.minlen)?;
+    }
+    if ctx.maxlen > 0 {
+        js.set_uint("maxlen", ctx.maxlen)?;
+    }
+    if ctx.window > 0 {
+        js.set_uint("window", ctx.window)?;
+    }
+    if ctx.log_histogram > 0 {
+        js.set_uint("log_histogram", ctx.log_histogram)?;
+    }
+    js.set_bool("chisquare", ctx.chisquare)?;
+    js.set_string(
+        "base",
+        match ctx.base {
+            EntropyBase::Base2 => "2",
+            EntropyBase::BaseE => "e",
+            EntropyBase::Base10 => "10",
+        },
+    )?;
+    js.open_object("value")?;
+    detect_float_to_json(js, &ctx.value)?;
+    js.close()?;
+    Ok(())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectEntropyToJson(
+    js: &mut JsonBuilder, ctx: &DetectEntropyData,
+) -> bool {
+    detect_entropy_to_json(js, ctx).is_ok()
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn SCDetectEntropyParse(c_arg: *const c_char) -> *mut DetectEntropyData {
     if c_arg.is_null() {
@@ -231,26 +648,38 @@ pub unsafe extern "C" fn SCDetectEntropyFree(ptr: *mut c_void) {
 mod tests {
     use super::*;
     use crate::detect::float::DetectFloatMode;
+    use crate::jsonbuilder::{SCJbLen, SCJbPtr};
     use num::traits::float::FloatCore;
+    use std::slice;
     // structure equality only used by test cases
     impl PartialEq for DetectEntropyData {
         fn eq(&self, other: &Self) -> bool {
-            self.value == other.value && self.offset == other.offset && self.nbytes == other.nbytes
+            self.value == other.value
+                && self.offset == other.offset
+                && self.nbytes == other.nbytes
+                && self.minlen == other.minlen
+                && self.maxlen == other.maxlen
+                && self.window == other.window
+                && self.chisquare == other.chisquare
+                && self.log_histogram == other.log_histogram
+                && self.base == other.base
         }
     }
 
     fn valid_test(
-        args: &str, nbytes: i32, offset: i32, value: f64, mode: DetectFloatMode, flags: u8,
+        args: &str, nbytes: i32, offset: i32, value: f64, mode: DetectFloatMode, flags: u16,
     ) {
         let ctx = DetectFloatData {
             arg1: value,
             arg2: FloatCore::min_value(),
             mode,
+            epsilon: 0.0,
         };
         let ded = DetectEntropyData {
             offset,
             nbytes,
             value: ctx,
+            ..Default::default()
         };
 
         let mut parsed_flags = 0;
@@ -360,6 +789,149 @@ mod tests {
         assert!(parse_entropy("offset 100", &mut parsed_flags,).is_err());
         assert!(parse_entropy("bytes 100, offset 100", &mut parsed_flags,).is_err());
         assert!(parse_entropy("bytes 1, offset 10, value 7.0, extra", &mut parsed_flags,).is_err());
+        assert!(parse_entropy("value is_nan", &mut parsed_flags,).is_err());
+        assert!(parse_entropy("bytes 4, value is_inf", &mut parsed_flags,).is_err());
+    }
+
+    #[test]
+    fn test_parse_entropy_epsilon() {
+        let mut flags = 0;
+        let (_, val) = parse_entropy("value = 4.0, epsilon 0.1", &mut flags).unwrap();
+        assert_eq!(val.value.mode, DetectFloatMode::DetectFloatModeEqual);
+        assert_eq!(val.value.arg1, 4.0);
+        assert_eq!(val.value.epsilon, 0.1);
+        assert_eq!(
+            flags,
+            DETECT_ENTROPY_FLAG_VALUE | DETECT_ENTROPY_FLAG_EPSILON
+        );
+
+        // before the value too
+        let mut flags = 0;
+        let (_, val) = parse_entropy("epsilon 0.25, value < 7", &mut flags).unwrap();
+        assert_eq!(val.value.epsilon, 0.25);
+
+        for args in [
+            "value 4, epsilon -0.1",
+            "value 4, epsilon inf",
+            "value 4, epsilon NaN",
+            "value 4, epsilon",
+            "value 4, epsilon 0.1x",
+            "value 4, epsilon 0.1, epsilon 0.2",
+            "value is_nan, epsilon 0.1",
+            "epsilon 0.1",
+        ] {
+            let mut flags = 0;
+            assert!(parse_entropy(args, &mut flags).is_err(), "{}", args);
+        }
+    }
+
+    #[test]
+    fn test_entropy_match_epsilon() {
+        // one bit per byte
+        let data = b"abababab";
+        let m = |args: &str| {
+            let mut flags = 0;
+            let (_, ctx) = parse_entropy(args, &mut flags).unwrap();
+            unsafe { SCDetectEntropyMatch(data.as_ptr() as *const c_void, data.len() as i32, &ctx) }
+        };
+        assert!(m("value = 1.05, epsilon 0.1"));
+        assert!(!m("value = 1.05"));
+        assert!(!m("value = 1.2, epsilon 0.1"));
+        // within the tolerance is equal, so not less
+        assert!(!m("value < 1.05, epsilon 0.1"));
+        assert!(m("value <= 0.95, epsilon 0.1"));
+    }
+
+    fn entropy_json(args: &str) -> String {
+        let mut flags = 0;
+        let (_, ctx) = parse_entropy(args, &mut flags).unwrap();
+        let mut js = JsonBuilder::try_new_object().unwrap();
+        assert!(unsafe { SCDetectEntropyToJson(&mut js, &ctx) });
+        js.close().unwrap();
+        let buf = unsafe { slice::from_raw_parts(SCJbPtr(&mut js), SCJbLen(&js)) };
+        String::from_utf8(buf.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_entropy_to_json() {
+        assert_eq!(
+            entropy_json("value > 7"),
+            r#"{"bytes":0,"offset":0,"chisquare":false,"base":"2","value":{"value":7,"mode":"gt"}}"#
+        );
+        assert_eq!(
+            entropy_json(
+                "bytes 64, offset 4, minlen 16, maxlen 1024, window 32, base e, value 1-3"
+            ),
+            r#"{"bytes":64,"offset":4,"minlen":16,"maxlen":1024,"window":32,"chisquare":false,"base":"e","value":{"min":1,"max":3,"mode":"range"}}"#
+        );
+        assert_eq!(
+            entropy_json("value <2, log_histogram 5"),
+            r#"{"bytes":0,"offset":0,"log_histogram":5,"chisquare":false,"base":"2","value":{"value":2,"mode":"lt"}}"#
+        );
+        assert_eq!(
+            entropy_json("value = 4.0, epsilon 0.1"),
+            r#"{"bytes":0,"offset":0,"chisquare":false,"base":"2","value":{"value":4,"mode":"equal","epsilon":0.1}}"#
+        );
+    }
+
+    #[test]
+    fn test_parse_entropy_length_guards() {
+        let mut flags = 0;
+        let (_, val) = parse_entropy("value >7, minlen 64, maxlen 1024", &mut flags).unwrap();
+        assert_eq!(val.minlen, 64);
+        assert_eq!(val.maxlen, 1024);
+        assert_eq!(val.value.mode, DetectFloatMode::DetectFloatModeGt);
+        assert_eq!(
+            flags,
+            DETECT_ENTROPY_FLAG_VALUE | DETECT_ENTROPY_FLAG_MINLEN | DETECT_ENTROPY_FLAG_MAXLEN
+        );
+
+        let mut flags = 0;
+        let (_, val) = parse_entropy("minlen 64,value >7", &mut flags).unwrap();
+        assert_eq!(val.minlen, 64);
+        assert_eq!(val.maxlen, 0);
+
+        let mut flags = 0;
+        let (_, val) = parse_entropy(
+            "bytes 4, offset 30, value 7, minlen 64, maxlen 1024",
+            &mut flags,
+        )
+        .unwrap();
+        assert_eq!(val.nbytes, 4);
+        assert_eq!(val.offset, 30);
+        assert_eq!(val.maxlen, 1024);
+
+        for args in [
+            "value >7, minlen 1024, maxlen 64",
+            "value >7, minlen 1, minlen 2",
+            "value >7, maxlen 0",
+            "value >7, minlen -1",
+            "value >7, maxlen big",
+            "minlen 64",
+        ] {
+            let mut flags = 0;
+            assert!(parse_entropy(args, &mut flags).is_err(), "{}", args);
+        }
+    }
+
+    #[test]
+    fn test_entropy_match_length_guards() {
+        let mut flags = 0;
+        let (_, ctx) = parse_entropy("value >4, minlen 64, maxlen 1024", &mut flags).unwrap();
+        let (_, unguarded) = parse_entropy("value >4", &mut 0).unwrap();
+        let data: Vec<u8> = (0..=255).collect();
+        let m = |len: usize, ctx: &DetectEntropyData| unsafe {
+            SCDetectEntropyMatch(data.as_ptr() as *const c_void, len as i32, ctx)
+        };
+        assert!(m(256, &ctx));
+        assert!(m(200, &ctx));
+        // too short, even though the entropy of 5 is above the threshold
+        assert!(m(32, &unguarded));
+        assert!(!m(32, &ctx));
+
+        let data: Vec<u8> = (0..2048).map(|i| i as u8).collect();
+        let too_long = unsafe { SCDetectEntropyMatch(data.as_ptr() as *const c_void, 2048, &ctx) };
+        assert!(!too_long);
     }
 
     #[test]
@@ -369,7 +941,7 @@ mod tests {
 
         // Calculate entropy
         let data_slice = unsafe { slice::from_raw_parts(data.as_ptr(), data.len()) };
-        let entropy = calculate_entropy(data_slice);
+        let entropy = shannon_entropy(data_slice);
 
         // Expected entropy is 0 (no randomness)
         assert!(
@@ -382,7 +954,7 @@ mod tests {
 
         // Calculate entropy
         let data_slice = unsafe { slice::from_raw_parts(data.as_ptr(), data.len()) };
-        let entropy = calculate_entropy(data_slice);
+        let entropy = shannon_entropy(data_slice);
 
         // Expected entropy is 2 (each byte has 1/4 probability)
         assert!(
@@ -395,7 +967,7 @@ mod tests {
 
         // Calculate entropy
         let data_slice = unsafe { slice::from_raw_parts(data.as_ptr(), data.len()) };
-        let entropy = calculate_entropy(data_slice);
+        let entropy = shannon_entropy(data_slice);
 
         // Expected entropy is 0 (no data)
         assert!(
@@ -408,7 +980,7 @@ mod tests {
 
         // Calculate entropy
         let data_slice = unsafe { slice::from_raw_parts(data.as_ptr(), data.len()) };
-        let entropy = calculate_entropy(data_slice);
+        let entropy = shannon_entropy(data_slice);
 
         // Verify entropy is non-zero and less than maximum
         assert!(
@@ -416,4 +988,299 @@ mod tests {
             "Entropy should be between 0.0 and 8.0"
         );
     }
+
+    #[test]
+    fn test_shannon_entropy_known_values() {
+        let data = vec![0x41u8; 100_000];
+        assert!(shannon_entropy(&data).abs() < 1e-9);
+
+        // every byte value exactly once
+        let data: Vec<u8> = (0..=255).collect();
+        assert!((shannon_entropy(&data) - 8.0).abs() < 1e-9);
+
+        // large uniform buffer
+        let data: Vec<u8> = (0..(1 << 22)).map(|i| i as u8).collect();
+        assert!((shannon_entropy(&data) - 8.0).abs() < 1e-9);
+
+        // two symbols with equal probability
+        assert!((shannon_entropy(b"abababab") - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shannon_entropy_window() {
+        let mut data = vec![0u8; 16];
+        data.extend(0..=255u8);
+        assert!(shannon_entropy_window(&data, 0, 16).unwrap().abs() < 1e-9);
+        assert!((shannon_entropy_window(&data, 16, 256).unwrap() - 8.0).abs() < 1e-9);
+        assert!((shannon_entropy_window(&data, 16, 4).unwrap() - 2.0).abs() < 1e-9);
+        assert_eq!(shannon_entropy_window(&data, data.len(), 0), Some(0.0));
+        assert_eq!(shannon_entropy_window(&data, 16, 257), None);
+        assert_eq!(shannon_entropy_window(&data, data.len() + 1, 0), None);
+        assert_eq!(shannon_entropy_window(&data, usize::MAX, 2), None);
+    }
+
+    #[test]
+    fn test_parse_entropy_window() {
+        let mut flags = 0;
+        let (_, val) = parse_entropy("value >7, window 256", &mut flags).unwrap();
+        assert_eq!(val.window, 256);
+        assert_eq!(
+            flags,
+            DETECT_ENTROPY_FLAG_VALUE | DETECT_ENTROPY_FLAG_WINDOW
+        );
+
+        for args in [
+            "value >7, window 0",
+            "value >7, window -1",
+            "value >7, window 16, window 32",
+        ] {
+            let mut flags = 0;
+            assert!(parse_entropy(args, &mut flags).is_err(), "{}", args);
+        }
+    }
+
+    #[test]
+    fn test_shannon_entropy_max_window() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * i % 251) as u8).collect();
+        for window in [1, 2, 7, 64, 999] {
+            let expected = data
+                .windows(window)
+                .map(shannon_entropy)
+                .fold(0.0, f64::max);
+            let max = shannon_entropy_max_window(&data, window);
+            assert!((max - expected).abs() < 1e-9, "window {}", window);
+        }
+        // windows not smaller than the buffer evaluate the whole buffer
+        assert_eq!(
+            shannon_entropy_max_window(&data, 1000),
+            shannon_entropy(&data)
+        );
+        assert_eq!(
+            shannon_entropy_max_window(&data, 5000),
+            shannon_entropy(&data)
+        );
+        assert_eq!(shannon_entropy_max_window(&[], 16), 0.0);
+    }
+
+    #[test]
+    fn test_entropy_match_window() {
+        // a high entropy region inside a low entropy buffer
+        let mut data = vec![b'a'; 4096];
+        data.splice(2048..2048, 0..=255u8);
+        let m = |args: &str| {
+            let mut flags = 0;
+            let (_, ctx) = parse_entropy(args, &mut flags).unwrap();
+            unsafe { SCDetectEntropyMatch(data.as_ptr() as *const c_void, data.len() as i32, &ctx) }
+        };
+        assert!(!m("value >7"));
+        assert!(m("value >7, window 256"));
+        assert!(!m("value >7, window 1024"));
+        // the window applies to the bytes selected with offset and bytes
+        assert!(!m("value >7, window 256, offset 2400"));
+        assert!(m("value >7, window 256, offset 2000, bytes 400"));
+        // a window larger than the buffer is the whole buffer
+        assert!(!m("value >7, window 8192"));
+        assert!(m("value <2, window 8192"));
+    }
+
+    #[test]
+    fn test_parse_entropy_chisquare() {
+        let mut flags = 0;
+        let (_, val) = parse_entropy("chisquare, value 0-300", &mut flags).unwrap();
+        assert!(val.chisquare);
+        assert_eq!(val.value.mode, DetectFloatMode::DetectFloatModeRange);
+        assert_eq!(
+            flags,
+            DETECT_ENTROPY_FLAG_VALUE | DETECT_ENTROPY_FLAG_CHISQUARE
+        );
+
+        for args in [
+            "chisquare 1, value <300",
+            "chisquare, chisquare, value <300",
+            "chisquare, window 64, value <300",
+            "chisquare",
+        ] {
+            let mut flags = 0;
+            assert!(parse_entropy(args, &mut flags).is_err(), "{}", args);
+        }
+    }
+
+    #[test]
+    fn test_chi_square() {
+        // every byte value exactly four times
+        let uniform: Vec<u8> = (0..1024).map(|i| i as u8).collect();
+        assert_eq!(chi_square(&uniform), Some(0.0));
+
+        let skewed = vec![b'a'; 1024];
+        let chi = chi_square(&skewed).unwrap();
+        assert!((chi - 255.0 * 1024.0).abs() < 1e-6);
+
+        assert_eq!(chi_square(&[]), None);
+        assert_eq!(chi_square(&uniform[..255]), None);
+    }
+
+    #[test]
+    fn test_entropy_match_chisquare() {
+        let m = |args: &str, data: &[u8]| {
+            let mut flags = 0;
+            let (_, ctx) = parse_entropy(args, &mut flags).unwrap();
+            unsafe { SCDetectEntropyMatch(data.as_ptr() as *const c_void, data.len() as i32, &ctx) }
+        };
+        let uniform: Vec<u8> = (0..1024).map(|i| i as u8).collect();
+        let skewed = vec![b'a'; 1024];
+        // the first 16 bytes are moved to byte value 0
+        let mut near_uniform = uniform.clone();
+        near_uniform[..16].fill(0);
+
+        assert!(m("chisquare, value <300", &uniform));
+        assert!(!m("chisquare, value <300", &skewed));
+        assert!(m("chisquare, value >10000", &skewed));
+        assert!(m("chisquare, value 50-300", &near_uniform));
+        assert!(!m("chisquare, value 50-300", &uniform));
+        assert!(!m("chisquare, value 50-300", &skewed));
+
+        // too short buffers never match
+        assert!(!m("chisquare, value <300", &uniform[..128]));
+        assert!(!m("chisquare, value <300", &[]));
+        assert!(!m("chisquare, value <300, offset 900", &uniform));
+    }
+
+    #[test]
+    fn test_parse_entropy_log_histogram() {
+        let mut flags = 0;
+        let (_, val) = parse_entropy("value >7, log_histogram 5", &mut flags).unwrap();
+        assert_eq!(val.log_histogram, 5);
+        assert_eq!(
+            flags,
+            DETECT_ENTROPY_FLAG_VALUE | DETECT_ENTROPY_FLAG_LOG_HISTOGRAM
+        );
+
+        for args in [
+            "value >7, log_histogram 0",
+            "value >7, log_histogram 257",
+            "value >7, log_histogram",
+            "value >7, log_histogram 2, log_histogram 3",
+        ] {
+            let mut flags = 0;
+            assert!(parse_entropy(args, &mut flags).is_err(), "{}", args);
+        }
+    }
+
+    #[test]
+    fn test_byte_histogram_top() {
+        // a skewed buffer: mostly 'a', some 'b' and a few other bytes once
+        let mut data = vec![b'a'; 900];
+        data.extend(vec![b'b'; 90]);
+        data.extend(b"zyxwvutsrq");
+        let top = byte_histogram_top(&data, 3);
+        assert_eq!(top, vec![(b'a', 900), (b'b', 90), (b'q', 1)]);
+        assert_eq!(byte_histogram_top(&data, 1), vec![(b'a', 900)]);
+        assert_eq!(byte_histogram_top(&data, 256).len(), 12);
+        assert!(byte_histogram_top(&[], 5).is_empty());
+    }
+
+    #[test]
+    fn test_entropy_histogram_to_json() {
+        let mut data = vec![0u8; 64];
+        data.extend(vec![b'a'; 900]);
+        data.extend(vec![b'b'; 90]);
+        let mut flags = 0;
+        let (_, ctx) = parse_entropy("value <2, offset 64, log_histogram 2", &mut flags).unwrap();
+        let c_data = data.as_ptr() as *const c_void;
+        assert!(unsafe { SCDetectEntropyMatch(c_data, data.len() as i32, &ctx) });
+        assert!(SCDetectEntropyLogsHistogram(&ctx));
+
+        let mut js = JsonBuilder::try_new_array().unwrap();
+        assert!(unsafe {
+            SCDetectEntropyHistogramToJson(c_data, data.len() as i32, &ctx, &mut js)
+        });
+        js.close().unwrap();
+        // the bytes before the offset are not part of the histogram
+        let buf = unsafe { slice::from_raw_parts(SCJbPtr(&mut js), SCJbLen(&js)) };
+        assert_eq!(buf, br#"[{"byte":97,"count":900},{"byte":98,"count":90}]"#);
+    }
+
+    #[test]
+    fn test_parse_entropy_base() {
+        for (arg, base) in [
+            ("2", EntropyBase::Base2),
+            ("e", EntropyBase::BaseE),
+            ("10", EntropyBase::Base10),
+        ] {
+            let mut flags = 0;
+            let (_, val) = parse_entropy(&format!("value >1, base {}", arg), &mut flags).unwrap();
+            assert_eq!(val.base, base);
+            assert_eq!(flags, DETECT_ENTROPY_FLAG_VALUE | DETECT_ENTROPY_FLAG_BASE);
+        }
+        let mut flags = 0;
+        let (_, val) = parse_entropy("value >1", &mut flags).unwrap();
+        assert_eq!(val.base, EntropyBase::Base2);
+
+        for args in [
+            "value >1, base 3",
+            "value >1, base E",
+            "value >1, base",
+            "value >1, base 2, base 10",
+            "chisquare, value <300, base e",
+        ] {
+            let mut flags = 0;
+            assert!(parse_entropy(args, &mut flags).is_err(), "{}", args);
+        }
+    }
+
+    #[test]
+    fn test_entropy_base() {
+        // every byte value exactly once: 8 bits, 8 ln(2) nats, 8 log10(2) dits
+        let data: Vec<u8> = (0..=255).collect();
+        let bits = shannon_entropy(&data);
+        let nats = EntropyBase::BaseE.convert_bits(bits);
+        let dits = EntropyBase::Base10.convert_bits(bits);
+        assert!((bits - 8.0).abs() < 1e-9);
+        assert!((nats - 256f64.ln()).abs() < 1e-9);
+        assert!((dits - 256f64.log10()).abs() < 1e-9);
+        assert!((nats / bits - std::f64::consts::LN_2).abs() < 1e-9);
+
+        // the threshold is in the same base as the entropy
+        let m = |args: &str| {
+            let mut flags = 0;
+            let (_, ctx) = parse_entropy(args, &mut flags).unwrap();
+            unsafe { SCDetectEntropyMatch(data.as_ptr() as *const c_void, data.len() as i32, &ctx) }
+        };
+        assert!(m("value >7.9"));
+        assert!(!m("value >7.9, base e"));
+        assert!(m("value 5.5-5.6, base e"));
+        assert!(!m("value >2.5, base 10"));
+        assert!(m("value 2.4-2.5, base 10"));
+        assert!(m("value 2.1-2.2, base 10, window 128"));
+        assert!(m("value 4.8-4.9, base e, window 128"));
+    }
+
+    #[test]
+    fn test_entropy_accumulator() {
+        // byte probabilities of 1/2, 1/4, 1/8 and 1/8
+        let data = b"aaaabbcd".repeat(625);
+        let expected = 1.75;
+
+        let mut single = EntropyAccumulator::new();
+        single.update(&data);
+        assert_eq!(single.finalize(), expected);
+
+        let mut bytes = EntropyAccumulator::new();
+        for b in data.chunks(1) {
+            bytes.update(b);
+        }
+        assert_eq!(bytes.finalize(), expected);
+
+        // the last chunk is shorter
+        let mut chunks = EntropyAccumulator::new();
+        for c in data.chunks(7) {
+            chunks.update(c);
+        }
+        assert_eq!(chunks.finalize(), expected);
+
+        // empty chunks don't change the result
+        chunks.update(&[]);
+        assert_eq!(chunks.finalize(), expected);
+        assert_eq!(EntropyAccumulator::new().finalize(), 0.0);
+    }
 }
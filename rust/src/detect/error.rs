@@ -16,6 +16,7 @@
  */
 
 use nom7::error::{ErrorKind, ParseError};
+use std::fmt;
 
 /// Custom rule parse errors.
 ///
@@ -28,7 +29,10 @@ pub enum RuleParseError<I> {
     InvalidIPRep(String),
     InvalidTransformBase64(String),
     InvalidByteExtract(String),
+    InvalidByteTest(String),
+    InvalidByteJump(String),
     InvalidEntropy(String),
+    UnexpectedToken(DetectParseError),
 
     Nom(I, ErrorKind),
 }
@@ -41,3 +45,82 @@ impl<I> ParseError<I> for RuleParseError<I> {
         other
     }
 }
+
+/// A token of a keyword argument string which could not be parsed.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DetectParseError {
+    pub keyword: &'static str,
+    pub token: String,
+    /// Byte offset of the token in the argument string.
+    pub offset: usize,
+    /// Why the token is invalid, when it is not just unexpected.
+    pub reason: Option<&'static str>,
+}
+
+impl DetectParseError {
+    /// Builds the error for `token`, a slice of the argument string `input`.
+    pub fn new(keyword: &'static str, input: &str, token: &str) -> Self {
+        let offset = (token.as_ptr() as usize)
+            .saturating_sub(input.as_ptr() as usize)
+            .min(input.len());
+        DetectParseError {
+            keyword,
+            token: token.to_string(),
+            offset,
+            reason: None,
+        }
+    }
+
+    /// Tells why the token is invalid, like an "inverted range".
+    pub fn with_reason(mut self, reason: &'static str) -> Self {
+        self.reason = Some(reason);
+        self
+    }
+}
+
+impl fmt::Display for DetectParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.token.is_empty() {
+            return write!(
+                f,
+                "keyword '{}': unexpected end of arguments at offset {}",
+                self.keyword, self.offset
+            );
+        }
+        write!(
+            f,
+            "keyword '{}': {} '{}' at offset {}",
+            self.keyword,
+            self.reason.unwrap_or("unexpected token"),
+            self.token,
+            self.offset
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_parse_error_display() {
+        let input = "bytes 4, offset 0, foo 1";
+        let e = DetectParseError::new("byte_math", input, &input[19..22]);
+        assert_eq!(e.token, "foo");
+        assert_eq!(e.offset, 19);
+        assert_eq!(
+            e.to_string(),
+            "keyword 'byte_math': unexpected token 'foo' at offset 19"
+        );
+        let e = DetectParseError::new("xbits", input, &input[input.len()..]);
+        assert_eq!(
+            e.to_string(),
+            "keyword 'xbits': unexpected end of arguments at offset 24"
+        );
+        let e = DetectParseError::new("ttl", "100<>50", "100<>50").with_reason("inverted range");
+        assert_eq!(
+            e.to_string(),
+            "keyword 'ttl': inverted range '100<>50' at offset 0"
+        );
+    }
+}
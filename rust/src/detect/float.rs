@@ -18,10 +18,10 @@
 use nom7::{
     branch::alt,
     bytes::complete::{is_a, tag, tag_no_case, take_while},
-    character::complete::{char, digit1},
+    character::complete::{char, digit1, hex_digit0, hex_digit1},
     combinator::{all_consuming, map, map_opt, opt, recognize, value, verify},
     error::{make_error, ErrorKind},
-    sequence::tuple,
+    sequence::{pair, preceded, tuple},
     Err, IResult,
 };
 
@@ -29,6 +29,8 @@ use num::traits::float::FloatCore;
 use num::traits::{FromPrimitive, ToPrimitive};
 use num::Bounded;
 
+use crate::detect::parser::parse_digits;
+use crate::detect::ByteEndian;
 use std::ffi::CStr;
 
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -42,6 +44,19 @@ pub enum DetectFloatMode {
     DetectFloatModeRange,
     DetectFloatModeNe,
     DetectFloatModeNegRg,
+    // predicates on the value alone, without arguments
+    DetectFloatModeIsNan,
+    DetectFloatModeIsInf,
+}
+
+impl DetectFloatMode {
+    /// If the mode tests the class of the value instead of comparing it.
+    pub fn is_predicate(&self) -> bool {
+        matches!(
+            self,
+            DetectFloatMode::DetectFloatModeIsNan | DetectFloatMode::DetectFloatModeIsInf
+        )
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -50,6 +65,8 @@ pub struct DetectFloatData<T> {
     pub arg1: T,
     pub arg2: T,
     pub mode: DetectFloatMode,
+    /// relative tolerance of the comparisons with arg1, 0 being exact
+    pub epsilon: T,
 }
 
 impl<T: Default> Default for DetectFloatData<T> {
@@ -58,6 +75,7 @@ impl<T: Default> Default for DetectFloatData<T> {
             arg1: T::default(),
             arg2: T::default(),
             mode: DetectFloatMode::DetectFloatModeEqual,
+            epsilon: T::default(),
         }
     }
 }
@@ -77,8 +95,87 @@ where
     }
 }
 
+/// Parse a C99 style hexadecimal float literal, like `0x1.8p3`.
+///
+/// The binary exponent is optional, `0x10` is parsed as 16.0
+fn parse_hex_float_value<T: DetectFloatType>(input: &str) -> IResult<&str, T> {
+    let (i, sign) = opt(alt((char('+'), char('-'))))(input)?;
+    let (i, _) = tag_no_case("0x")(i)?;
+    let (i, int_part) = hex_digit0(i)?;
+    let (i, frac_part) = opt(preceded(char('.'), hex_digit0))(i)?;
+    let frac_part = frac_part.unwrap_or("");
+    if int_part.is_empty() && frac_part.is_empty() {
+        return Err(Err::Error(make_error(i, ErrorKind::HexDigit)));
+    }
+    let (i, exp) = opt(preceded(
+        tag_no_case("p"),
+        recognize(pair(opt(alt((char('+'), char('-')))), digit1)),
+    ))(i)?;
+    let exp = match exp {
+        Some(e) => match e.parse::<i32>() {
+            Ok(e) => e,
+            Err(_) => return Err(Err::Error(make_error(i, ErrorKind::Verify))),
+        },
+        None => 0,
+    };
+
+    let mut mantissa = 0.0f64;
+    for c in int_part.chars() {
+        // hex_digit0 only returned hex digits
+        mantissa = mantissa * 16.0 + f64::from(c.to_digit(16).unwrap());
+    }
+    let mut scale = 1.0f64 / 16.0;
+    for c in frac_part.chars() {
+        mantissa += f64::from(c.to_digit(16).unwrap()) * scale;
+        scale /= 16.0;
+    }
+    let mut val = mantissa * 2.0f64.powi(exp);
+    if sign == Some('-') {
+        val = -val;
+    }
+    match <T as FromPrimitive>::from_f64(val) {
+        Some(v) => Ok((i, v)),
+        None => Err(Err::Error(make_error(i, ErrorKind::Verify))),
+    }
+}
+
+/// Parse a raw IEEE-754 bit pattern, like `bits:0x40490fdb`.
+///
+/// The pattern is interpreted with the width of `T`, so at most 8 hex
+/// digits are accepted for `f32` and 16 for `f64`. NaN and infinity
+/// patterns are kept as such.
+fn parse_float_bits<T: DetectFloatType>(input: &str) -> IResult<&str, T> {
+    let (i, _) = tag_no_case("bits:")(input)?;
+    let (i, _) = tag_no_case("0x")(i)?;
+    let (i, digits) = hex_digit1(i)?;
+    let width = std::mem::size_of::<T>();
+    if digits.len() > width * 2 {
+        return Err(Err::Error(make_error(i, ErrorKind::Verify)));
+    }
+    let bits = match u64::from_str_radix(digits, 16) {
+        Ok(b) => b,
+        Err(_) => return Err(Err::Error(make_error(i, ErrorKind::Verify))),
+    };
+    let val = if width == 4 {
+        <T as FromPrimitive>::from_f32(f32::from_bits(bits as u32))
+    } else {
+        <T as FromPrimitive>::from_f64(f64::from_bits(bits))
+    };
+    match val {
+        Some(v) => Ok((i, v)),
+        None => Err(Err::Error(make_error(i, ErrorKind::Verify))),
+    }
+}
+
+/// Parse decimal digits, which may be grouped with underscores.
+fn digit_groups(i: &str) -> IResult<&str, &str> {
+    recognize(parse_digits)(i)
+}
+
 pub fn parse_float_value<T: DetectFloatType>(input: &str) -> IResult<&str, T> {
     alt((
+        parse_float_bits,
+        parse_hex_float_value,
         // Handle special cases first
         map(tag_no_case("NaN"), |_| {
             <T as DetectFloatType>::from_str("NaN").unwrap()
@@ -96,15 +193,16 @@ pub fn parse_float_value<T: DetectFloatType>(input: &str) -> IResult<&str, T> {
         map_opt(
             recognize(tuple((
                 opt(alt((tag("+"), tag("-")))), // Handle optional signs
-                alt((digit1, recognize(tuple((tag("."), digit1))))), // Handle integers & `.5`
-                opt(tuple((tag("."), digit1))), // Handle decimals like `5.`
+                // Handle integers & `.5`
+                alt((digit_groups, recognize(tuple((tag("."), digit_groups))))),
+                opt(tuple((tag("."), digit_groups))), // Handle decimals like `5.`
                 opt(tuple((
                     tag_no_case("e"),
                     opt(alt((tag("+"), tag("-")))),
-                    digit1,
+                    digit_groups,
                 ))), // Handle `1e10`, `-1e-5`
             ))),
-            |float_str: &str| <T as DetectFloatType>::from_str(float_str),
+            |float_str: &str| <T as DetectFloatType>::from_str(&float_str.replace('_', "")),
         ),
     ))(input)
 }
@@ -120,6 +218,7 @@ fn detect_parse_float_start_equal<T: DetectFloatType>(
             arg1,
             arg2: <T as FloatCore>::min_value(),
             mode: DetectFloatMode::DetectFloatModeEqual,
+            epsilon: <T as num::Zero>::zero(),
         },
     ))
 }
@@ -140,7 +239,15 @@ pub fn detect_parse_float_start_interval<T: DetectFloatType>(
     } else {
         DetectFloatMode::DetectFloatModeRange
     };
-    Ok((i, DetectFloatData { arg1, arg2, mode }))
+    Ok((
+        i,
+        DetectFloatData {
+            arg1,
+            arg2,
+            mode,
+            epsilon: <T as num::Zero>::zero(),
+        },
+    ))
 }
 
 fn detect_parse_float_mode(i: &str) -> IResult<&str, DetectFloatMode> {
@@ -195,32 +302,220 @@ fn detect_parse_float_start_symbol<T: DetectFloatType>(
             arg1,
             arg2: <T as FloatCore>::min_value(),
             mode,
+            epsilon: <T as num::Zero>::zero(),
+        },
+    ))
+}
+
+/// Parses the `is_nan` and `is_inf` predicates.
+fn detect_parse_float_predicate<T: DetectFloatType>(i: &str) -> IResult<&str, DetectFloatData<T>> {
+    let (i, mode) = alt((
+        value(DetectFloatMode::DetectFloatModeIsNan, tag("is_nan")),
+        value(DetectFloatMode::DetectFloatModeIsInf, tag("is_inf")),
+    ))(i)?;
+    Ok((
+        i,
+        DetectFloatData {
+            arg1: <T as num::Zero>::zero(),
+            arg2: <T as FloatCore>::min_value(),
+            mode,
+            epsilon: <T as num::Zero>::zero(),
         },
     ))
 }
 
+/// Returns true if `a` and `b` differ by at most `epsilon` times the
+/// largest of their magnitudes.
+fn float_approx_eq<T: DetectFloatType>(a: T, b: T, epsilon: T) -> bool {
+    if a == b {
+        return true;
+    }
+    // no tolerance makes a finite value close to an infinite one
+    if a.is_infinite() || b.is_infinite() {
+        return false;
+    }
+    return (a - b).abs() <= epsilon * a.abs().max(b.abs());
+}
+
+/// Matches `val` against `x`.
+///
+/// Values within the relative `epsilon` of `arg1` are considered equal
+/// to it, so for instance `<` does not match them, yet `<=` does. The
+/// epsilon is ignored for range modes.
+///
+/// `is_nan` matches NaN values only, and `is_inf` both infinities.
 pub fn detect_match_float<T: DetectFloatType>(x: &DetectFloatData<T>, val: T) -> bool {
+    let eq = || float_approx_eq(val, x.arg1, x.epsilon);
     match x.mode {
-        DetectFloatMode::DetectFloatModeEqual => val == x.arg1,
-        DetectFloatMode::DetectFloatModeNe => val != x.arg1,
-        DetectFloatMode::DetectFloatModeLt => val < x.arg1,
-        DetectFloatMode::DetectFloatModeLte => val <= x.arg1,
-        DetectFloatMode::DetectFloatModeGt => val > x.arg1,
-        DetectFloatMode::DetectFloatModeGte => val >= x.arg1,
+        DetectFloatMode::DetectFloatModeIsNan => val.is_nan(),
+        DetectFloatMode::DetectFloatModeIsInf => val.is_infinite(),
+        // NaN is unordered: only the inequality holds when either side is NaN
+        _ if val.is_nan() || x.arg1.is_nan() => x.mode == DetectFloatMode::DetectFloatModeNe,
+        DetectFloatMode::DetectFloatModeEqual => eq(),
+        DetectFloatMode::DetectFloatModeNe => !eq(),
+        DetectFloatMode::DetectFloatModeLt => val < x.arg1 && !eq(),
+        DetectFloatMode::DetectFloatModeLte => val <= x.arg1 || eq(),
+        DetectFloatMode::DetectFloatModeGt => val > x.arg1 && !eq(),
+        DetectFloatMode::DetectFloatModeGte => val >= x.arg1 || eq(),
         DetectFloatMode::DetectFloatModeRange => val > x.arg1 && val < x.arg2,
         DetectFloatMode::DetectFloatModeNegRg => val <= x.arg1 || val >= x.arg2,
     }
 }
 
+/// Parses the `, epsilon:<value>` suffix, the value being finite and not
+/// negative.
+fn detect_parse_float_epsilon<T: DetectFloatType>(i: &str) -> IResult<&str, T> {
+    let (i, _) = opt(is_a(" "))(i)?;
+    let (i, _) = char(',')(i)?;
+    let (i, _) = opt(is_a(" "))(i)?;
+    let (i, _) = tag("epsilon")(i)?;
+    let (i, _) = opt(is_a(" "))(i)?;
+    let (i, _) = char(':')(i)?;
+    let (i, _) = opt(is_a(" "))(i)?;
+    detect_parse_float_tolerance(i)
+}
+
+/// Parses an `epsilon` relative tolerance, finite and not negative.
+pub fn detect_parse_float_tolerance<T: DetectFloatType>(i: &str) -> IResult<&str, T> {
+    verify(parse_float_value::<T>, |e| {
+        e.is_finite() && *e >= <T as num::Zero>::zero()
+    })(i)
+}
+
+/// Parses a float comparison, with an optional `epsilon` relative
+/// tolerance, as in `=3.14, epsilon:0.01`, which defaults to 0 for an
+/// exact comparison. The `is_nan` and `is_inf` predicates take no
+/// tolerance.
 pub fn detect_parse_float<T: DetectFloatType>(i: &str) -> IResult<&str, DetectFloatData<T>> {
-    let (i, float) = detect_parse_float_notending(i)?;
+    let (i, float) = detect_parse_float_epsilon_notending(i)?;
     let (i, _) = all_consuming(take_while(|c| c == ' '))(i)?;
     Ok((i, float))
 }
 
+fn detect_parse_float_epsilon_notending<T: DetectFloatType>(
+    i: &str,
+) -> IResult<&str, DetectFloatData<T>> {
+    let (i, mut float) = detect_parse_float_notending(i)?;
+    let (i, epsilon) = opt(detect_parse_float_epsilon)(i)?;
+    if epsilon.is_some() && float.mode.is_predicate() {
+        return Err(Err::Error(make_error(i, ErrorKind::Verify)));
+    }
+    if let Some(epsilon) = epsilon {
+        float.epsilon = epsilon;
+    }
+    Ok((i, float))
+}
+
+/// A float comparison on raw IEEE-754 bytes of a buffer.
+#[derive(Debug, PartialEq)]
+#[repr(C)]
+pub struct DetectFloatRawData {
+    /// number of bytes read, 4 for a `f32` and 8 for a `f64`
+    pub nbytes: u8,
+    pub endian: ByteEndian,
+    /// the comparison, a `f32` being widened to a `f64` for it
+    pub value: DetectFloatData<f64>,
+}
+
+/// Parses the `, raw:<4|8>` suffix.
+fn detect_parse_float_raw_nbytes(i: &str) -> IResult<&str, u8> {
+    let (i, _) = opt(is_a(" "))(i)?;
+    let (i, _) = char(',')(i)?;
+    let (i, _) = opt(is_a(" "))(i)?;
+    let (i, _) = tag("raw")(i)?;
+    let (i, _) = opt(is_a(" "))(i)?;
+    let (i, _) = char(':')(i)?;
+    let (i, _) = opt(is_a(" "))(i)?;
+    alt((value(4, char('4')), value(8, char('8'))))(i)
+}
+
+/// Parses the `, endian:<big|little>` suffix.
+fn detect_parse_float_raw_endian(i: &str) -> IResult<&str, ByteEndian> {
+    let (i, _) = opt(is_a(" "))(i)?;
+    let (i, _) = char(',')(i)?;
+    let (i, _) = opt(is_a(" "))(i)?;
+    let (i, _) = tag("endian")(i)?;
+    let (i, _) = opt(is_a(" "))(i)?;
+    let (i, _) = char(':')(i)?;
+    let (i, _) = opt(is_a(" "))(i)?;
+    alt((
+        value(ByteEndian::BigEndian, tag("big")),
+        value(ByteEndian::LittleEndian, tag("little")),
+    ))(i)
+}
+
+/// Parses a float comparison on raw bytes, as in `>1.5, raw:4,
+/// endian:little`: the comparison and its optional epsilon, the number
+/// of bytes of the value, then its optional byte order, which defaults
+/// to big endian like byte_extract.
+pub fn detect_parse_float_raw(i: &str) -> IResult<&str, DetectFloatRawData> {
+    let (i, mut value) = detect_parse_float_epsilon_notending::<f64>(i)?;
+    let (i, nbytes) = detect_parse_float_raw_nbytes(i)?;
+    if nbytes == 4 {
+        // a threshold beyond the f32 range would silently become infinite
+        let fits = |v: f64| !v.is_finite() || (v as f32).is_finite();
+        let range = matches!(
+            value.mode,
+            DetectFloatMode::DetectFloatModeRange | DetectFloatMode::DetectFloatModeNegRg
+        );
+        if !fits(value.arg1) || (range && !fits(value.arg2)) {
+            return Err(Err::Error(make_error(i, ErrorKind::Verify)));
+        }
+        // compare with the f32 the rule writer means, as 0.1 is not
+        // the widened f32 0.1
+        value.arg1 = f64::from(value.arg1 as f32);
+        value.arg2 = f64::from(value.arg2 as f32);
+    }
+    let (i, endian) = opt(detect_parse_float_raw_endian)(i)?;
+    let (i, _) = all_consuming(take_while(|c| c == ' '))(i)?;
+    Ok((
+        i,
+        DetectFloatRawData {
+            nbytes,
+            endian: endian.unwrap_or(ByteEndian::BigEndian),
+            value,
+        },
+    ))
+}
+
+/// Reads the float in the first bytes of `input`, or None if it is too
+/// short.
+pub fn detect_float_raw_value(raw: &DetectFloatRawData, input: &[u8]) -> Option<f64> {
+    let little = raw.endian == ByteEndian::LittleEndian;
+    match raw.nbytes {
+        4 => {
+            let bytes: [u8; 4] = input.get(..4)?.try_into().ok()?;
+            let val = if little {
+                f32::from_le_bytes(bytes)
+            } else {
+                f32::from_be_bytes(bytes)
+            };
+            Some(f64::from(val))
+        }
+        8 => {
+            let bytes: [u8; 8] = input.get(..8)?.try_into().ok()?;
+            if little {
+                Some(f64::from_le_bytes(bytes))
+            } else {
+                Some(f64::from_be_bytes(bytes))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Matches the float in the first bytes of `input` against `raw`.
+pub fn detect_match_float_raw(raw: &DetectFloatRawData, input: &[u8]) -> bool {
+    match detect_float_raw_value(raw, input) {
+        Some(val) => detect_match_float(&raw.value, val),
+        None => false,
+    }
+}
+
 fn detect_parse_float_notending<T: DetectFloatType>(i: &str) -> IResult<&str, DetectFloatData<T>> {
     let (i, _) = opt(is_a(" "))(i)?;
     let (i, float) = alt((
+        detect_parse_float_predicate,
         detect_parse_float_start_interval,
         detect_parse_float_start_equal,
         detect_parse_float_start_symbol,
@@ -322,6 +617,30 @@ mod tests {
 
         assert!(parse_float_value::<f32>(".e10").is_err());
     }
+
+    #[test]
+    fn test_parse_float_value_separators() {
+        for (input, value) in [
+            ("1_000", 1000.0),
+            ("1_000.5", 1000.5),
+            ("0.000_001", 0.000001),
+            ("-1_0e1_0", -1e11),
+        ] {
+            assert_eq!(
+                parse_float_value::<f64>(input),
+                Ok(("", value)),
+                "{}",
+                input
+            );
+        }
+        for input in ["_1.0", "1_.0", "1__0.0", "1.0_", "1e_5"] {
+            assert!(
+                !matches!(parse_float_value::<f64>(input), Ok(("", _))),
+                "{}",
+                input
+            );
+        }
+    }
     #[test]
     fn test_detect_parse_valid() {
         let _ = do_parse("1.0", 1.0, DetectFloatMode::DetectFloatModeEqual);
@@ -438,6 +757,76 @@ mod tests {
         assert!(!detect_match_float(&val, 41.9));
     }
 
+    #[test]
+    fn test_detect_parse_epsilon() {
+        let (_, val) = detect_parse_float::<f64>("=2.75, epsilon:0.01").unwrap();
+        assert_eq!(val.arg1, 2.75);
+        assert_eq!(val.epsilon, 0.01);
+        assert_eq!(val.mode, DetectFloatMode::DetectFloatModeEqual);
+        let (_, val) = detect_parse_float::<f64>("< 2.0 ,epsilon: 0.5 ").unwrap();
+        assert_eq!(val.epsilon, 0.5);
+        assert_eq!(val.mode, DetectFloatMode::DetectFloatModeLt);
+        let (_, val) = detect_parse_float::<f64>("1.0-2.0, epsilon:0.1").unwrap();
+        assert_eq!(val.epsilon, 0.1);
+        // exact by default
+        let (_, val) = detect_parse_float::<f64>("=3.14").unwrap();
+        assert_eq!(val.epsilon, 0.0);
+
+        assert!(detect_parse_float::<f64>("=3.14, epsilon:-0.01").is_err());
+        assert!(detect_parse_float::<f64>("=3.14, epsilon:inf").is_err());
+        assert!(detect_parse_float::<f64>("=3.14, epsilon:NaN").is_err());
+        assert!(detect_parse_float::<f64>("=3.14, epsilon:").is_err());
+        assert!(detect_parse_float::<f64>("=3.14, eps:0.01").is_err());
+    }
+
+    #[test]
+    fn test_detect_match_epsilon() {
+        // powers of two keep the boundaries exact
+        let (_, val) = detect_parse_float::<f64>("=4.0, epsilon:0.25").unwrap();
+        // tolerance is 0.25 * 4.0
+        assert!(detect_match_float(&val, 3.0));
+        assert!(!detect_match_float(&val, 2.9375));
+        // tolerance is 0.25 * val, above arg1
+        assert!(detect_match_float(&val, 5.3));
+        assert!(!detect_match_float(&val, 5.4));
+        assert!(detect_match_float(&val, 4.0));
+
+        let (_, val) = detect_parse_float::<f64>("!=4.0, epsilon:0.25").unwrap();
+        assert!(!detect_match_float(&val, 3.0));
+        assert!(detect_match_float(&val, 2.9375));
+
+        // within the tolerance, values are neither lower nor greater
+        let (_, val) = detect_parse_float::<f64>("<4.0, epsilon:0.25").unwrap();
+        assert!(!detect_match_float(&val, 3.0));
+        assert!(detect_match_float(&val, 2.9375));
+        let (_, val) = detect_parse_float::<f64>("<=4.0, epsilon:0.25").unwrap();
+        assert!(detect_match_float(&val, 5.0));
+        assert!(!detect_match_float(&val, 5.4));
+        let (_, val) = detect_parse_float::<f64>(">=4.0, epsilon:0.25").unwrap();
+        assert!(detect_match_float(&val, 3.0));
+        let (_, val) = detect_parse_float::<f64>(">4.0, epsilon:0.25").unwrap();
+        assert!(!detect_match_float(&val, 5.0));
+        assert!(detect_match_float(&val, 5.4));
+
+        // ignored for ranges
+        let (_, val) = detect_parse_float::<f64>("1.0-2.0, epsilon:0.5").unwrap();
+        assert!(!detect_match_float(&val, 1.0));
+        assert!(!detect_match_float(&val, 2.5));
+    }
+
+    #[test]
+    fn test_detect_match_epsilon_zero() {
+        let (_, val) = detect_parse_float::<f64>("=2.75, epsilon:0").unwrap();
+        assert!(detect_match_float(&val, 2.75));
+        assert!(!detect_match_float(&val, 2.75 + f64::EPSILON * 4.0));
+        assert!(!detect_match_float(&val, 2.75 - f64::EPSILON * 4.0));
+        let (_, val) = detect_parse_float::<f64>("=inf, epsilon:0.1").unwrap();
+        assert!(detect_match_float(&val, f64::INFINITY));
+        assert!(!detect_match_float(&val, f64::MAX));
+        let (_, val) = detect_parse_float::<f64>("=0.0, epsilon:0").unwrap();
+        assert!(detect_match_float(&val, -0.0));
+    }
+
     fn do_match_test(val: &str, arg1: f64, arg1_cmp: f64, arg2: f64, mode: DetectFloatMode) {
         let c_string = CString::new(val).expect("CString::new failed");
         unsafe {
@@ -530,4 +919,199 @@ mod tests {
             DetectFloatMode::DetectFloatModeRange,
         );
     }
+
+    #[test]
+    fn test_parse_float_hex() {
+        let (_, v) = parse_float_value::<f64>("0x1.8p3").unwrap();
+        assert_eq!(v, 12.0);
+        let (_, v) = parse_float_value::<f64>("-0x1p-2").unwrap();
+        assert_eq!(v, -0.25);
+        let (_, v) = parse_float_value::<f32>("0X10").unwrap();
+        assert_eq!(v, 16.0);
+        let (_, v) = parse_float_value::<f64>("0x.8").unwrap();
+        assert_eq!(v, 0.5);
+        assert!(detect_parse_float::<f64>("0x").is_err());
+        assert!(detect_parse_float::<f64>("0x.").is_err());
+        assert!(detect_parse_float::<f64>("0x1p").is_err());
+
+        let val = do_parse("> 0x1.8p1", 3.0, DetectFloatMode::DetectFloatModeGt);
+        assert!(detect_match_float(&val, 3.1));
+        assert!(!detect_match_float(&val, 3.0));
+    }
+
+    #[test]
+    fn test_parse_float_bits() {
+        let (_, v) = parse_float_value::<f32>("bits:0x40490fdb").unwrap();
+        assert_eq!(v, std::f32::consts::PI);
+        let (_, v) = parse_float_value::<f64>("bits:0x400921fb54442d18").unwrap();
+        assert_eq!(v, std::f64::consts::PI);
+        let (_, v) = parse_float_value::<f32>("bits:0x7f800000").unwrap();
+        assert!(v.is_infinite() && v.is_sign_positive());
+        let (_, v) = parse_float_value::<f64>("bits:0xfff0000000000000").unwrap();
+        assert!(v.is_infinite() && v.is_sign_negative());
+        let (_, v) = parse_float_value::<f32>("bits:0x7fc00000").unwrap();
+        assert!(v.is_nan());
+        // too wide for the type
+        assert!(parse_float_value::<f32>("bits:0x400921fb54442d18").is_err());
+        assert!(parse_float_value::<f64>("bits:").is_err());
+
+        let (_, val) = detect_parse_float::<f32>("> bits:0x40490fdb").unwrap();
+        assert!(detect_match_float(&val, 3.2));
+        assert!(!detect_match_float(&val, 3.1));
+    }
+
+    #[test]
+    fn test_match_float_nan_bits() {
+        for op in ["<", ">", "=", "<=", ">="] {
+            let (_, val) = detect_parse_float::<f32>(&format!("{} bits:0x7fc00000", op)).unwrap();
+            assert!(!detect_match_float(&val, 1.0));
+            assert!(!detect_match_float(&val, f32::NAN));
+            assert!(!detect_match_float(&val, f32::INFINITY));
+        }
+        let (_, val) = detect_parse_float::<f32>("!= bits:0x7fc00000").unwrap();
+        assert!(detect_match_float(&val, 1.0));
+        assert!(detect_match_float(&val, f32::NAN));
+
+        // NaN values never compare as ordered either
+        let (_, val) = detect_parse_float::<f64>("> bits:0x7ff0000000000000").unwrap();
+        assert!(!detect_match_float(&val, f64::NAN));
+        let (_, val) = detect_parse_float::<f64>("< bits:0x7ff0000000000000").unwrap();
+        assert!(detect_match_float(&val, 1.0));
+        assert!(!detect_match_float(&val, f64::NAN));
+    }
+
+    #[test]
+    fn test_detect_parse_float_predicate() {
+        let (_, val) = detect_parse_float::<f64>(" is_nan ").unwrap();
+        assert_eq!(val.mode, DetectFloatMode::DetectFloatModeIsNan);
+        let (_, val) = detect_parse_float::<f32>("is_inf").unwrap();
+        assert_eq!(val.mode, DetectFloatMode::DetectFloatModeIsInf);
+        assert!(detect_parse_float::<f64>("is_nan, epsilon:0.1").is_err());
+        assert!(detect_parse_float::<f64>("is_nan 1.0").is_err());
+        assert!(detect_parse_float::<f64>("!is_nan").is_err());
+        assert!(detect_parse_float::<f64>("is_finite").is_err());
+    }
+
+    #[test]
+    fn test_detect_match_float_predicate() {
+        let (_, nan) = detect_parse_float::<f64>("is_nan").unwrap();
+        let (_, inf) = detect_parse_float::<f64>("is_inf").unwrap();
+        assert!(detect_match_float(&nan, f64::NAN));
+        assert!(!detect_match_float(&inf, f64::NAN));
+        for v in [f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(!detect_match_float(&nan, v));
+            assert!(detect_match_float(&inf, v));
+        }
+        for v in [0.0, -1.5, f64::MAX, f64::MIN_POSITIVE] {
+            assert!(!detect_match_float(&nan, v));
+            assert!(!detect_match_float(&inf, v));
+        }
+
+        let (_, nan) = detect_parse_float::<f32>("is_nan").unwrap();
+        assert!(detect_match_float(&nan, f32::from_bits(0x7fc00001)));
+        assert!(!detect_match_float(&nan, 1.0));
+    }
+
+    #[test]
+    fn test_detect_parse_float_raw() {
+        let (_, raw) = detect_parse_float_raw(">1.0, raw:4").unwrap();
+        assert_eq!(raw.nbytes, 4);
+        assert_eq!(raw.endian, ByteEndian::BigEndian);
+        assert_eq!(raw.value.mode, DetectFloatMode::DetectFloatModeGt);
+        let (_, raw) =
+            detect_parse_float_raw("<=2.5, epsilon:0.1 , raw: 8, endian:little ").unwrap();
+        assert_eq!(raw.nbytes, 8);
+        assert_eq!(raw.endian, ByteEndian::LittleEndian);
+        assert_eq!(raw.value.epsilon, 0.1);
+        let (_, raw) = detect_parse_float_raw("is_nan, raw:8, endian:big").unwrap();
+        assert_eq!(raw.value.mode, DetectFloatMode::DetectFloatModeIsNan);
+
+        assert!(detect_parse_float_raw(">1.0").is_err());
+        assert!(detect_parse_float_raw("raw:4").is_err());
+        assert!(detect_parse_float_raw(">1.0, raw:2").is_err());
+        assert!(detect_parse_float_raw(">1.0, raw:48").is_err());
+        assert!(detect_parse_float_raw(">1.0, endian:little").is_err());
+        assert!(detect_parse_float_raw(">1.0, endian:little, raw:4").is_err());
+        assert!(detect_parse_float_raw(">1.0, raw:4, endian:dce").is_err());
+        // raw only applies to raw comparisons
+        assert!(detect_parse_float::<f64>(">1.0, raw:4").is_err());
+    }
+
+    #[test]
+    fn test_detect_parse_float_raw_f32_range() {
+        assert!(detect_parse_float_raw(">3.4e38, raw:4").is_ok());
+        assert!(detect_parse_float_raw("<-3.4e38, raw:4").is_ok());
+        assert!(detect_parse_float_raw(">inf, raw:4").is_ok());
+        assert!(detect_parse_float_raw(">1e39, raw:4").is_err());
+        assert!(detect_parse_float_raw("<-1e39, raw:4").is_err());
+        assert!(detect_parse_float_raw("0-1e39, raw:4").is_err());
+        // f64 thresholds are not limited
+        let (_, raw) = detect_parse_float_raw(">1e39, raw:8").unwrap();
+        assert_eq!(raw.value.arg1, 1e39);
+    }
+
+    #[test]
+    fn test_detect_match_float_raw_endian() {
+        // 1.5 as a f32
+        let be = [0x3f, 0xc0, 0x00, 0x00];
+        let le = [0x00, 0x00, 0xc0, 0x3f];
+        let (_, raw) = detect_parse_float_raw(">1.0, raw:4, endian:big").unwrap();
+        assert_eq!(detect_float_raw_value(&raw, &be), Some(1.5));
+        assert!(detect_match_float_raw(&raw, &be));
+        // the same bytes in the other order are a tiny denormal value
+        assert!(!detect_match_float_raw(&raw, &le));
+        let (_, raw) = detect_parse_float_raw(">1.0, raw:4, endian:little").unwrap();
+        assert_eq!(detect_float_raw_value(&raw, &le), Some(1.5));
+        assert!(detect_match_float_raw(&raw, &le));
+        assert!(!detect_match_float_raw(&raw, &be));
+        let (_, raw) = detect_parse_float_raw("<1.5, raw:4").unwrap();
+        assert!(!detect_match_float_raw(&raw, &be));
+
+        // 100.25 as a f64
+        let be = [0x40, 0x59, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let mut le = be;
+        le.reverse();
+        let (_, raw) = detect_parse_float_raw("100.0-101.0, raw:8").unwrap();
+        assert_eq!(detect_float_raw_value(&raw, &be), Some(100.25));
+        assert!(detect_match_float_raw(&raw, &be));
+        assert!(!detect_match_float_raw(&raw, &le));
+        let (_, raw) = detect_parse_float_raw(">=100.25, raw:8, endian:little").unwrap();
+        assert!(detect_match_float_raw(&raw, &le));
+        assert!(!detect_match_float_raw(&raw, &be));
+    }
+
+    #[test]
+    fn test_detect_match_float_raw_f32_rounding() {
+        // 0.1 as a f32, which is 0.100000001490116... as a f64
+        let be = 0.1f32.to_be_bytes();
+        let (_, raw) = detect_parse_float_raw("=0.1, raw:4").unwrap();
+        assert_eq!(raw.value.arg1, f64::from(0.1f32));
+        assert!(detect_match_float_raw(&raw, &be));
+        let (_, raw) = detect_parse_float_raw("<=0.1, raw:4").unwrap();
+        assert!(detect_match_float_raw(&raw, &be));
+        let (_, raw) = detect_parse_float_raw(">0.1, raw:4").unwrap();
+        assert!(!detect_match_float_raw(&raw, &be));
+        let (_, raw) = detect_parse_float_raw("0.1-0.2, raw:4").unwrap();
+        assert!(!detect_match_float_raw(&raw, &be));
+        // f64 values are compared as they are
+        let (_, raw) = detect_parse_float_raw("=0.1, raw:8").unwrap();
+        assert_eq!(raw.value.arg1, 0.1);
+        assert!(!detect_match_float_raw(
+            &raw,
+            &f64::from(0.1f32).to_be_bytes()
+        ));
+    }
+
+    #[test]
+    fn test_detect_match_float_raw_short() {
+        let (_, raw) = detect_parse_float_raw("!=0.0, raw:8").unwrap();
+        assert!(!detect_match_float_raw(&raw, &[0x40, 0x59, 0x10, 0x00]));
+        assert!(!detect_match_float_raw(&raw, &[]));
+        // only the first bytes are read
+        let (_, raw) = detect_parse_float_raw("=1.5, raw:4").unwrap();
+        assert!(detect_match_float_raw(
+            &raw,
+            &[0x3f, 0xc0, 0x00, 0x00, 0xff]
+        ));
+    }
 }
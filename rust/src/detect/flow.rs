@@ -15,13 +15,32 @@
  * 02110-1301, USA.
  */
 
-use super::uint::{detect_parse_uint, DetectUintData};
+use super::uint::{detect_match_uint, detect_parse_uint, DetectUintData};
 use nom7::branch::alt;
-use nom7::bytes::complete::{is_a, tag};
+use nom7::bytes::complete::{is_a, tag, tag_no_case};
 use nom7::combinator::{opt, value};
-use nom7::IResult;
+use nom7::error::{make_error, ErrorKind};
+use nom7::{Err, IResult};
+use std::collections::HashSet;
 use std::ffi::CStr;
 
+// flow keyword options, stored in DetectFlowData flags
+pub const DETECT_FLOW_FLAG_TOSERVER: u16 = 0x01;
+pub const DETECT_FLOW_FLAG_TOCLIENT: u16 = 0x02;
+pub const DETECT_FLOW_FLAG_ESTABLISHED: u16 = 0x04;
+pub const DETECT_FLOW_FLAG_NOT_ESTABLISHED: u16 = 0x08;
+pub const DETECT_FLOW_FLAG_STATELESS: u16 = 0x10;
+pub const DETECT_FLOW_FLAG_ONLYSTREAM: u16 = 0x20;
+pub const DETECT_FLOW_FLAG_NOSTREAM: u16 = 0x40;
+pub const DETECT_FLOW_FLAG_NO_FRAG: u16 = 0x80;
+pub const DETECT_FLOW_FLAG_ONLY_FRAG: u16 = 0x100;
+pub const DETECT_FLOW_FLAG_HAS_GAPS: u16 = 0x200;
+
+// packet flow flags, see FLOW_PKT_* in flow.h
+const FLOW_PKT_TOSERVER: u8 = 0x01;
+const FLOW_PKT_TOCLIENT: u8 = 0x02;
+const FLOW_PKT_ESTABLISHED: u8 = 0x04;
+
 #[allow(non_camel_case_types)]
 #[derive(PartialEq, Eq, Clone, Debug)]
 #[repr(u8)]
@@ -110,6 +129,50 @@ pub unsafe extern "C" fn SCDetectFlowPktsFree(ctx: &mut DetectFlowPkts) {
     std::mem::drop(Box::from_raw(ctx));
 }
 
+/// Parses the `packets<op><number>` option of the `flow` keyword.
+///
+/// The direction is left as either, the caller narrows it down to the
+/// direction the `flow` keyword matches on.
+fn detect_parse_flow_pkts_option(i: &str) -> IResult<&str, DetectFlowPkts> {
+    let (i, _) = opt(is_a(" \t"))(i)?;
+    let (i, _) = tag_no_case("packets")(i)?;
+    let (i, _) = opt(is_a(" \t"))(i)?;
+    return detect_parse_flow_pkts_dir(i, DetectFlowDir::DETECT_FLOW_TOEITHER);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectFlowPktsParseOption(
+    ustr: *const std::os::raw::c_char,
+) -> *mut DetectFlowPkts {
+    let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
+    if let Ok(s) = ft_name.to_str() {
+        if let Ok((_, ctx)) = detect_parse_flow_pkts_option(s) {
+            let boxed = Box::new(ctx);
+            return Box::into_raw(boxed) as *mut _;
+        }
+    }
+    return std::ptr::null_mut();
+}
+
+/// Matches the packet counters of a flow, `toserver` being the number of
+/// packets sent by the client and `toclient` the number sent by the server.
+pub fn detect_match_flow_pkts(df: &DetectFlowPkts, toserver: u32, toclient: u32) -> bool {
+    match df.dir {
+        DetectFlowDir::DETECT_FLOW_TOSERVER => detect_match_uint(&df.pkt_data, toserver),
+        DetectFlowDir::DETECT_FLOW_TOCLIENT => detect_match_uint(&df.pkt_data, toclient),
+        DetectFlowDir::DETECT_FLOW_TOEITHER => {
+            detect_match_uint(&df.pkt_data, toclient) || detect_match_uint(&df.pkt_data, toserver)
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn SCDetectFlowPktsMatch(
+    ctx: &DetectFlowPkts, toserver: u32, toclient: u32,
+) -> bool {
+    return detect_match_flow_pkts(ctx, toserver, toclient);
+}
+
 fn detect_parse_flow_bytes(i: &str) -> IResult<&str, DetectFlowBytes> {
     let (i, _) = opt(is_a(" \t"))(i)?;
     let (i, fd) = detect_parse_flow_direction(i)?;
@@ -163,6 +226,297 @@ pub unsafe extern "C" fn SCDetectFlowBytesFree(ctx: &mut DetectFlowBytes) {
     std::mem::drop(Box::from_raw(ctx));
 }
 
+/// Parses the `bytes<op><number>` option of the `flow` keyword.
+fn detect_parse_flow_bytes_option(i: &str) -> IResult<&str, DetectFlowBytes> {
+    let (i, _) = opt(is_a(" \t"))(i)?;
+    let (i, _) = tag_no_case("bytes")(i)?;
+    let (i, _) = opt(is_a(" \t"))(i)?;
+    return detect_parse_flow_bytes_dir(i, DetectFlowDir::DETECT_FLOW_TOEITHER);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectFlowBytesParseOption(
+    ustr: *const std::os::raw::c_char,
+) -> *mut DetectFlowBytes {
+    let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
+    if let Ok(s) = ft_name.to_str() {
+        if let Ok((_, ctx)) = detect_parse_flow_bytes_option(s) {
+            let boxed = Box::new(ctx);
+            return Box::into_raw(boxed) as *mut _;
+        }
+    }
+    return std::ptr::null_mut();
+}
+
+/// Matches the byte counters of a flow, see [`detect_match_flow_pkts`].
+pub fn detect_match_flow_bytes(df: &DetectFlowBytes, toserver: u64, toclient: u64) -> bool {
+    match df.dir {
+        DetectFlowDir::DETECT_FLOW_TOSERVER => detect_match_uint(&df.byte_data, toserver),
+        DetectFlowDir::DETECT_FLOW_TOCLIENT => detect_match_uint(&df.byte_data, toclient),
+        DetectFlowDir::DETECT_FLOW_TOEITHER => {
+            detect_match_uint(&df.byte_data, toclient) || detect_match_uint(&df.byte_data, toserver)
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn SCDetectFlowBytesMatch(
+    ctx: &DetectFlowBytes, toserver: u64, toclient: u64,
+) -> bool {
+    return detect_match_flow_bytes(ctx, toserver, toclient);
+}
+
+/// Parses the `age<op><number>[s]` option of the `flow` keyword.
+///
+/// The age is always a number of seconds. A trailing `s` may be used to
+/// make that explicit, but the size units accepted by other integer
+/// keywords, like `kb`, are rejected.
+fn detect_parse_flow_age_option(i: &str) -> IResult<&str, DetectUintData<u32>> {
+    let (i, _) = opt(is_a(" \t"))(i)?;
+    let (i, _) = tag_no_case("age")(i)?;
+    let v = i.trim_end();
+    let v = v.strip_suffix('s').unwrap_or(v);
+    if v.contains(|c: char| matches!(c.to_ascii_lowercase(), 'k' | 'm' | 'g')) {
+        return Err(Err::Error(make_error(i, ErrorKind::Verify)));
+    }
+    let (_, du32) = detect_parse_uint::<u32>(v.trim_start())?;
+    return Ok(("", du32));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectFlowAgeParseOption(
+    ustr: *const std::os::raw::c_char,
+) -> *mut DetectUintData<u32> {
+    let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
+    if let Ok(s) = ft_name.to_str() {
+        if let Ok((_, ctx)) = detect_parse_flow_age_option(s) {
+            let boxed = Box::new(ctx);
+            return Box::into_raw(boxed) as *mut _;
+        }
+    }
+    return std::ptr::null_mut();
+}
+
+/// Matches the age in seconds of a flow started at `start`, as given by
+/// `clock`, against `du32`. Ages above `u32::MAX` are capped.
+pub fn detect_match_flow_age<F: FnOnce() -> u64>(
+    du32: &DetectUintData<u32>, start: u64, clock: F,
+) -> bool {
+    let age = clock().saturating_sub(start);
+    return detect_match_uint(du32, u32::try_from(age).unwrap_or(u32::MAX));
+}
+
+/// Matches the age of a flow, `start` and `now` being timestamps in seconds.
+#[no_mangle]
+pub extern "C" fn SCDetectFlowAgeMatch(ctx: &DetectUintData<u32>, start: u64, now: u64) -> bool {
+    return detect_match_flow_age(ctx, start, || now);
+}
+
+/// Matches the direction, state and fragment options of a flow keyword
+/// against the flow flags of a packet.
+///
+/// The options are grouped by what they look at and each group set in
+/// `dflags` has to be satisfied, `match_cnt` being the number of groups set.
+/// So `not_established` composes with a direction as `established` does:
+/// `to_server,not_established` matches the SYN and the ACK completing the
+/// handshake, as that ACK is only flagged established once it has been
+/// processed by the stream engine.
+///
+/// `stream_gap` tells if the stream engine recorded a gap in the flow, it is
+/// only ever set for TCP flows.
+pub fn detect_match_flow_flags(
+    dflags: u16, match_cnt: u16, flowflags: u8, rebuilt_fragment: bool, stream_gap: bool,
+) -> bool {
+    let mut cnt = 0;
+
+    if (dflags & DETECT_FLOW_FLAG_NO_FRAG != 0 && !rebuilt_fragment)
+        || (dflags & DETECT_FLOW_FLAG_ONLY_FRAG != 0 && rebuilt_fragment)
+    {
+        cnt += 1;
+    }
+
+    if (dflags & DETECT_FLOW_FLAG_TOSERVER != 0 && flowflags & FLOW_PKT_TOSERVER != 0)
+        || (dflags & DETECT_FLOW_FLAG_TOCLIENT != 0 && flowflags & FLOW_PKT_TOCLIENT != 0)
+    {
+        cnt += 1;
+    }
+
+    let established = flowflags & FLOW_PKT_ESTABLISHED != 0;
+    if (dflags & DETECT_FLOW_FLAG_ESTABLISHED != 0 && established)
+        || (dflags & DETECT_FLOW_FLAG_NOT_ESTABLISHED != 0 && !established)
+        || dflags & DETECT_FLOW_FLAG_STATELESS != 0
+    {
+        cnt += 1;
+    }
+
+    if dflags & DETECT_FLOW_FLAG_HAS_GAPS != 0 && stream_gap {
+        cnt += 1;
+    }
+
+    return match_cnt == cnt;
+}
+
+#[no_mangle]
+pub extern "C" fn SCDetectFlowMatchFlags(
+    dflags: u16, match_cnt: u16, flowflags: u8, rebuilt_fragment: bool, stream_gap: bool,
+) -> bool {
+    return detect_match_flow_flags(dflags, match_cnt, flowflags, rebuilt_fragment, stream_gap);
+}
+
+#[allow(non_camel_case_types)]
+#[derive(PartialEq, Eq, Clone, Debug)]
+#[repr(u8)]
+/// This data structure is also used in detect-flow-bit.c
+pub enum DetectFlowBitCmd {
+    DETECT_FLOW_BIT_SET = 1,
+    DETECT_FLOW_BIT_ISSET = 2,
+    DETECT_FLOW_BIT_NOALERT = 3,
+}
+
+/// A `flow.bit` keyword: `set,<name>`, `isset,<name>` or `noalert`.
+#[derive(Debug, PartialEq)]
+pub struct DetectFlowBit {
+    cmd: DetectFlowBitCmd,
+    name: String,
+}
+
+/// The bits set on a flow, kept in the flow storage so they are cleared
+/// once the flow ends or times out.
+#[derive(Debug, Default)]
+pub struct DetectFlowBitState {
+    bits: HashSet<String>,
+}
+
+fn detect_parse_flow_bit(i: &str) -> IResult<&str, DetectFlowBit> {
+    let (i, _) = opt(is_a(" \t"))(i)?;
+    let (i, cmd) = alt((
+        value(DetectFlowBitCmd::DETECT_FLOW_BIT_ISSET, tag("isset")),
+        value(DetectFlowBitCmd::DETECT_FLOW_BIT_SET, tag("set")),
+        value(DetectFlowBitCmd::DETECT_FLOW_BIT_NOALERT, tag("noalert")),
+    ))(i)?;
+    let (i, _) = opt(is_a(" \t"))(i)?;
+    if cmd == DetectFlowBitCmd::DETECT_FLOW_BIT_NOALERT {
+        return Ok((
+            i,
+            DetectFlowBit {
+                cmd,
+                name: String::new(),
+            },
+        ));
+    }
+    let (i, _) = tag(",")(i)?;
+    let name = i.trim();
+    if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ',') {
+        return Err(Err::Error(make_error(i, ErrorKind::Verify)));
+    }
+    return Ok((
+        "",
+        DetectFlowBit {
+            cmd,
+            name: name.to_string(),
+        },
+    ));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectFlowBitParse(
+    ustr: *const std::os::raw::c_char,
+) -> *mut DetectFlowBit {
+    let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
+    if let Ok(s) = ft_name.to_str() {
+        if let Ok((rem, ctx)) = detect_parse_flow_bit(s) {
+            if rem.is_empty() {
+                let boxed = Box::new(ctx);
+                return Box::into_raw(boxed) as *mut _;
+            }
+        }
+    }
+    return std::ptr::null_mut();
+}
+
+#[no_mangle]
+pub extern "C" fn SCDetectFlowBitCmd(ctx: &DetectFlowBit) -> DetectFlowBitCmd {
+    return ctx.cmd.clone();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectFlowBitFree(ctx: &mut DetectFlowBit) {
+    std::mem::drop(Box::from_raw(ctx));
+}
+
+/// Sets or tests the bit of `fb` in the state of a flow, allocating the
+/// state on the first `set`. For `isset`, a flow without state has no bit.
+pub fn detect_match_flow_bit(
+    fb: &DetectFlowBit, state: &mut Option<Box<DetectFlowBitState>>,
+) -> bool {
+    match fb.cmd {
+        DetectFlowBitCmd::DETECT_FLOW_BIT_SET => {
+            let state = state.get_or_insert_with(Box::default);
+            state.bits.insert(fb.name.clone());
+            return true;
+        }
+        DetectFlowBitCmd::DETECT_FLOW_BIT_ISSET => {
+            return matches!(state, Some(s) if s.bits.contains(&fb.name));
+        }
+        DetectFlowBitCmd::DETECT_FLOW_BIT_NOALERT => {
+            return true;
+        }
+    }
+}
+
+/// Matches `ctx` against the flow storage `state` points to. A `set` may
+/// allocate the state, so the caller has to store `*state` back into the
+/// flow if it changed.
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectFlowBitMatch(
+    ctx: &DetectFlowBit, state: *mut *mut DetectFlowBitState,
+) -> bool {
+    let mut fs = if (*state).is_null() {
+        None
+    } else {
+        Some(Box::from_raw(*state))
+    };
+    let r = detect_match_flow_bit(ctx, &mut fs);
+    *state = fs.map(Box::into_raw).unwrap_or(std::ptr::null_mut());
+    return r;
+}
+
+/// Frees the flow storage of `flow.bit`, called when the flow is cleared.
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectFlowBitStateFree(state: *mut std::os::raw::c_void) {
+    if !state.is_null() {
+        std::mem::drop(Box::from_raw(state as *mut DetectFlowBitState));
+    }
+}
+
+/// Parses the direction of a `flow.buffer` keyword, `to_server` or
+/// `to_client`, also accepting the `flow` keyword spelling.
+fn detect_parse_flow_buffer(i: &str) -> IResult<&str, DetectFlowDir> {
+    let (i, _) = opt(is_a(" \t"))(i)?;
+    let (i, dir) = alt((
+        value(DetectFlowDir::DETECT_FLOW_TOSERVER, tag("to_server")),
+        value(DetectFlowDir::DETECT_FLOW_TOCLIENT, tag("to_client")),
+        value(DetectFlowDir::DETECT_FLOW_TOSERVER, tag("toserver")),
+        value(DetectFlowDir::DETECT_FLOW_TOCLIENT, tag("toclient")),
+    ))(i)?;
+    let (i, _) = opt(is_a(" \t"))(i)?;
+    return Ok((i, dir));
+}
+
+/// Returns the direction of a `flow.buffer` option, or 0 if it is invalid.
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectFlowBufferParse(ustr: *const std::os::raw::c_char) -> u8 {
+    let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
+    if let Ok(s) = ft_name.to_str() {
+        if let Ok((rem, dir)) = detect_parse_flow_buffer(s) {
+            if rem.is_empty() {
+                return dir as u8;
+            }
+        }
+    }
+    return 0;
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -183,4 +537,256 @@ mod test {
         );
         assert!(detect_parse_flow_pkts("toserver").is_err());
     }
+
+    #[test]
+    fn test_detect_parse_flow_pkts_option() {
+        assert_eq!(
+            detect_parse_flow_pkts_option("packets>100").unwrap().1,
+            DetectFlowPkts {
+                pkt_data: DetectUintData {
+                    arg1: 100,
+                    arg2: 0,
+                    mode: DetectUintMode::DetectUintModeGt,
+                },
+                dir: DetectFlowDir::DETECT_FLOW_TOEITHER,
+            }
+        );
+        assert_eq!(
+            detect_parse_flow_bytes_option("bytes 10-20").unwrap().1,
+            DetectFlowBytes {
+                byte_data: DetectUintData {
+                    arg1: 10,
+                    arg2: 20,
+                    mode: DetectUintMode::DetectUintModeRange,
+                },
+                dir: DetectFlowDir::DETECT_FLOW_TOEITHER,
+            }
+        );
+        assert!(detect_parse_flow_pkts_option("packets").is_err());
+        assert!(detect_parse_flow_pkts_option("bytes>1").is_err());
+        assert!(detect_parse_flow_bytes_option("bytes>1 x").is_err());
+    }
+
+    #[test]
+    fn test_detect_match_flow_pkts_threshold() {
+        let mut df = detect_parse_flow_pkts_option("packets>100").unwrap().1;
+        df.dir = DetectFlowDir::DETECT_FLOW_TOSERVER;
+        let (mut toserver, mut toclient) = (0, 0);
+        // the server answers every packet twice, so only the to server
+        // counter may trigger the match
+        for _ in 0..100 {
+            toserver += 1;
+            toclient += 2;
+            assert!(!detect_match_flow_pkts(&df, toserver, toclient));
+        }
+        toserver += 1;
+        assert!(detect_match_flow_pkts(&df, toserver, toclient));
+
+        df.dir = DetectFlowDir::DETECT_FLOW_TOCLIENT;
+        assert!(detect_match_flow_pkts(&df, 0, 101));
+        assert!(!detect_match_flow_pkts(&df, 101, 100));
+        df.dir = DetectFlowDir::DETECT_FLOW_TOEITHER;
+        assert!(detect_match_flow_pkts(&df, 101, 0));
+        assert!(detect_match_flow_pkts(&df, 0, 101));
+        assert!(!detect_match_flow_pkts(&df, 100, 100));
+    }
+
+    #[test]
+    fn test_detect_match_flow_bytes_threshold() {
+        let mut df = detect_parse_flow_bytes_option("bytes>=1500").unwrap().1;
+        df.dir = DetectFlowDir::DETECT_FLOW_TOCLIENT;
+        let mut toclient = 0;
+        for _ in 0..2 {
+            toclient += 600;
+            assert!(!detect_match_flow_bytes(&df, 10000, toclient));
+        }
+        toclient += 300;
+        assert!(detect_match_flow_bytes(&df, 10000, toclient));
+    }
+
+    #[test]
+    fn test_detect_parse_flow_age_option() {
+        let du32 = DetectUintData {
+            arg1: 3600,
+            arg2: 0,
+            mode: DetectUintMode::DetectUintModeGt,
+        };
+        assert_eq!(detect_parse_flow_age_option("age>3600").unwrap().1, du32);
+        assert_eq!(
+            detect_parse_flow_age_option(" age > 3600s ").unwrap().1,
+            du32
+        );
+        assert_eq!(
+            detect_parse_flow_age_option("age60-120").unwrap().1,
+            DetectUintData {
+                arg1: 60,
+                arg2: 120,
+                mode: DetectUintMode::DetectUintModeRange,
+            }
+        );
+        assert!(detect_parse_flow_age_option("age>1kb").is_err());
+        assert!(detect_parse_flow_age_option("age>1m").is_err());
+        assert!(detect_parse_flow_age_option("age>").is_err());
+        assert!(detect_parse_flow_age_option("ages>1").is_err());
+    }
+
+    #[test]
+    fn test_detect_match_flow_age() {
+        let du32 = detect_parse_flow_age_option("age>3600").unwrap().1;
+        const NOW: u64 = 1_700_000_000;
+        let clock = || NOW;
+        // flow started an hour and a second ago
+        assert!(detect_match_flow_age(&du32, NOW - 3601, clock));
+        assert!(!detect_match_flow_age(&du32, NOW - 3600, clock));
+        assert!(!detect_match_flow_age(&du32, NOW - 10, clock));
+        // a start time in the future counts as a new flow
+        assert!(!detect_match_flow_age(&du32, NOW + 5000, clock));
+        // ages beyond the u32 range are capped instead of wrapping
+        assert!(detect_match_flow_age(&du32, 0, || u64::MAX));
+    }
+
+    // packets of a tcp handshake followed by the first data packet, with the
+    // flow flags set on them before the stream engine processes them
+    const SYN: u8 = FLOW_PKT_TOSERVER;
+    const SYN_ACK: u8 = FLOW_PKT_TOCLIENT;
+    const ACK: u8 = FLOW_PKT_TOSERVER;
+    const DATA: u8 = FLOW_PKT_TOSERVER | FLOW_PKT_ESTABLISHED;
+
+    fn handshake_matches(dflags: u16, match_cnt: u16) -> [bool; 4] {
+        return [SYN, SYN_ACK, ACK, DATA]
+            .map(|f| detect_match_flow_flags(dflags, match_cnt, f, false, false));
+    }
+
+    #[test]
+    fn test_detect_match_flow_not_established() {
+        let not_est = DETECT_FLOW_FLAG_NOT_ESTABLISHED;
+        assert_eq!(handshake_matches(not_est, 1), [true, true, true, false]);
+        assert_eq!(
+            handshake_matches(not_est | DETECT_FLOW_FLAG_TOSERVER, 2),
+            [true, false, true, false]
+        );
+        assert_eq!(
+            handshake_matches(not_est | DETECT_FLOW_FLAG_TOCLIENT, 2),
+            [false, true, false, false]
+        );
+        assert_eq!(
+            handshake_matches(
+                not_est | DETECT_FLOW_FLAG_TOSERVER | DETECT_FLOW_FLAG_NO_FRAG,
+                3
+            ),
+            [true, false, true, false]
+        );
+    }
+
+    #[test]
+    fn test_detect_match_flow_established() {
+        let est = DETECT_FLOW_FLAG_ESTABLISHED;
+        assert_eq!(handshake_matches(est, 1), [false, false, false, true]);
+        assert_eq!(
+            handshake_matches(est | DETECT_FLOW_FLAG_TOSERVER, 2),
+            [false, false, false, true]
+        );
+        assert_eq!(
+            handshake_matches(est | DETECT_FLOW_FLAG_TOCLIENT, 2),
+            [false, false, false, false]
+        );
+        assert_eq!(
+            handshake_matches(DETECT_FLOW_FLAG_STATELESS | DETECT_FLOW_FLAG_TOCLIENT, 2),
+            [false, true, false, false]
+        );
+    }
+
+    #[test]
+    fn test_detect_match_flow_has_gaps() {
+        let gaps = DETECT_FLOW_FLAG_HAS_GAPS;
+        assert!(detect_match_flow_flags(gaps, 1, DATA, false, true));
+        assert!(!detect_match_flow_flags(gaps, 1, DATA, false, false));
+        let flags = gaps | DETECT_FLOW_FLAG_TOSERVER | DETECT_FLOW_FLAG_ESTABLISHED;
+        assert!(detect_match_flow_flags(flags, 3, DATA, false, true));
+        assert!(!detect_match_flow_flags(flags, 3, DATA, false, false));
+        assert!(!detect_match_flow_flags(flags, 3, SYN_ACK, false, true));
+        // a gap does not count for the other options
+        assert!(!detect_match_flow_flags(
+            DETECT_FLOW_FLAG_TOCLIENT,
+            1,
+            DATA,
+            false,
+            true
+        ));
+    }
+
+    #[test]
+    fn test_detect_parse_flow_bit() {
+        fn bit(cmd: DetectFlowBitCmd, name: &str) -> DetectFlowBit {
+            DetectFlowBit {
+                cmd,
+                name: name.to_string(),
+            }
+        }
+        assert_eq!(
+            detect_parse_flow_bit("set,seen").unwrap().1,
+            bit(DetectFlowBitCmd::DETECT_FLOW_BIT_SET, "seen")
+        );
+        assert_eq!(
+            detect_parse_flow_bit(" isset , seen ").unwrap().1,
+            bit(DetectFlowBitCmd::DETECT_FLOW_BIT_ISSET, "seen")
+        );
+        assert_eq!(
+            detect_parse_flow_bit("noalert").unwrap().1,
+            bit(DetectFlowBitCmd::DETECT_FLOW_BIT_NOALERT, "")
+        );
+        assert!(detect_parse_flow_bit("set").is_err());
+        assert!(detect_parse_flow_bit("set,").is_err());
+        assert!(detect_parse_flow_bit("set,a b").is_err());
+        assert!(detect_parse_flow_bit("set,a,b").is_err());
+        assert!(detect_parse_flow_bit("unset,seen").is_err());
+    }
+
+    #[test]
+    fn test_detect_match_flow_bit_later_packet() {
+        let set = detect_parse_flow_bit("set,login").unwrap().1;
+        let isset = detect_parse_flow_bit("isset,login").unwrap().1;
+        let other = detect_parse_flow_bit("isset,logout").unwrap().1;
+        // the flow storage, as the engine hands it out for each packet
+        let mut state: *mut DetectFlowBitState = std::ptr::null_mut();
+        unsafe {
+            // first packet: nothing set yet, then the bit gets set
+            assert!(!SCDetectFlowBitMatch(&isset, &mut state));
+            assert!(state.is_null());
+            assert!(SCDetectFlowBitMatch(&set, &mut state));
+            assert!(!state.is_null());
+            let stored = state;
+            // later packet of the same flow
+            assert!(SCDetectFlowBitMatch(&isset, &mut state));
+            assert!(!SCDetectFlowBitMatch(&other, &mut state));
+            assert!(SCDetectFlowBitMatch(&set, &mut state));
+            assert_eq!(state, stored);
+            // the flow ends, a new flow starts without the bit
+            SCDetectFlowBitStateFree(state as *mut std::os::raw::c_void);
+            state = std::ptr::null_mut();
+            assert!(!SCDetectFlowBitMatch(&isset, &mut state));
+        }
+    }
+
+    #[test]
+    fn test_detect_parse_flow_buffer() {
+        assert_eq!(
+            detect_parse_flow_buffer("to_server").unwrap(),
+            ("", DetectFlowDir::DETECT_FLOW_TOSERVER)
+        );
+        assert_eq!(
+            detect_parse_flow_buffer(" to_client ").unwrap(),
+            ("", DetectFlowDir::DETECT_FLOW_TOCLIENT)
+        );
+        assert_eq!(
+            detect_parse_flow_buffer("toclient").unwrap(),
+            ("", DetectFlowDir::DETECT_FLOW_TOCLIENT)
+        );
+        assert!(detect_parse_flow_buffer("either").is_err());
+        assert!(detect_parse_flow_buffer("").is_err());
+        assert_ne!(
+            detect_parse_flow_buffer("to_server,to_client").unwrap().0,
+            ""
+        );
+    }
 }
@@ -25,6 +25,8 @@ use nom7::Err;
 use nom7::IResult;
 
 use std::ffi::{CStr, CString};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::os::raw::c_char;
 use std::str::FromStr;
 
@@ -54,11 +56,240 @@ impl std::str::FromStr for DetectIPRepDataCmd {
     }
 }
 
+/// Network the reputation check is restricted to, from the `net:` option.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct DetectIPRepNet {
+    /// 0 when no network is set, otherwise 4 or 6
+    pub version: u8,
+    pub prefix_len: u8,
+    /// network address, with host bits cleared
+    pub addr: [u8; 16],
+}
+
+impl DetectIPRepNet {
+    /// Parses a CIDR such as `10.0.0.0/8` or `2001:db8::/32`. A plain
+    /// address is a host network.
+    pub fn parse(s: &str) -> Option<Self> {
+        IpNetwork::parse(s).map(Self::from)
+    }
+
+    /// Checks if `addr`, 4 bytes for IPv4 or 16 for IPv6, is in the network.
+    /// Always true when no network is set.
+    pub fn contains(&self, addr: &[u8]) -> bool {
+        match (self.version, addr.len()) {
+            (0, _) => true,
+            (4, 4) | (6, 16) => {
+                let mut bytes = [0u8; 16];
+                bytes[..addr.len()].copy_from_slice(addr);
+                mask_bytes(&mut bytes, self.prefix_len);
+                bytes == self.addr
+            }
+            _ => false,
+        }
+    }
+}
+
+impl From<IpNetwork> for DetectIPRepNet {
+    fn from(net: IpNetwork) -> Self {
+        let (version, addr) = match net.addr {
+            IpAddr::V4(a) => {
+                let mut addr = [0u8; 16];
+                addr[..4].copy_from_slice(&a.octets());
+                (4, addr)
+            }
+            IpAddr::V6(a) => (6, a.octets()),
+        };
+        DetectIPRepNet {
+            version,
+            prefix_len: net.prefix_len,
+            addr,
+        }
+    }
+}
+
+/// An IPv4 or IPv6 network, its address having the host bits cleared.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    /// Network of `addr` with a prefix of `prefix_len` bits, clearing the
+    /// host bits of `addr`. None if the prefix is longer than the address.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Option<Self> {
+        let addr = match addr {
+            IpAddr::V4(a) if prefix_len <= 32 => {
+                let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(a) & mask))
+            }
+            IpAddr::V6(a) if prefix_len <= 128 => {
+                let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(a) & mask))
+            }
+            _ => return None,
+        };
+        Some(IpNetwork { addr, prefix_len })
+    }
+
+    fn parse_parts(s: &str) -> Option<(IpAddr, u8)> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr = addr.parse::<IpAddr>().ok()?;
+        let prefix_len = match prefix {
+            Some(p) => {
+                if p.is_empty() || !p.bytes().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+                p.parse::<u8>().ok()?
+            }
+            None if addr.is_ipv4() => 32,
+            None => 128,
+        };
+        Some((addr, prefix_len))
+    }
+
+    /// Parses a CIDR such as `10.0.0.0/8` or `2001:db8::/32`, clearing the
+    /// host bits, so that `192.168.1.77/24` is `192.168.1.0/24`. A plain
+    /// address is a host network.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (addr, prefix_len) = Self::parse_parts(s)?;
+        Self::new(addr, prefix_len)
+    }
+
+    /// Like `parse`, but rejects a CIDR with host bits set.
+    pub fn parse_strict(s: &str) -> Option<Self> {
+        let (addr, prefix_len) = Self::parse_parts(s)?;
+        Self::new(addr, prefix_len).filter(|net| net.addr == addr)
+    }
+
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Checks if `ip` is in the network. An address of the other family
+    /// never is, including IPv4-mapped IPv6 addresses in an IPv4 network.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_)) => {
+                IpNetwork::new(*ip, self.prefix_len) == Some(*self)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+fn mask_bytes(bytes: &mut [u8; 16], prefix_len: u8) {
+    for (i, b) in bytes.iter_mut().enumerate() {
+        let bits = (prefix_len as usize).saturating_sub(i * 8).min(8);
+        *b &= !(0xffu8.checked_shr(bits as u32).unwrap_or(0));
+    }
+}
+
+/// Address as used to key reputation lookups. IPv4-mapped IPv6 addresses
+/// (`::ffff:a.b.c.d`) are keyed by their IPv4 form, so they share its
+/// reputation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct IPRepAddr {
+    /// 4 or 6
+    pub version: u8,
+    /// address, in the first 4 bytes for IPv4
+    pub addr: [u8; 16],
+}
+
+impl IPRepAddr {
+    fn from_ip(ip: IpAddr) -> Self {
+        let ip = match ip {
+            IpAddr::V6(a) => a.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            IpAddr::V4(_) => ip,
+        };
+        match ip {
+            IpAddr::V4(a) => {
+                let mut addr = [0u8; 16];
+                addr[..4].copy_from_slice(&a.octets());
+                IPRepAddr { version: 4, addr }
+            }
+            IpAddr::V6(a) => IPRepAddr {
+                version: 6,
+                addr: a.octets(),
+            },
+        }
+    }
+
+    /// Parses the address of a reputation file entry.
+    pub fn parse(s: &str) -> Option<Self> {
+        s.trim().parse::<IpAddr>().ok().map(Self::from_ip)
+    }
+
+    /// Key for a packet address, 4 bytes for IPv4 or 16 for IPv6.
+    pub fn from_bytes(addr: &[u8]) -> Option<Self> {
+        if let Ok(octets) = <[u8; 4]>::try_from(addr) {
+            Some(Self::from_ip(IpAddr::from(octets)))
+        } else if let Ok(octets) = <[u8; 16]>::try_from(addr) {
+            Some(Self::from_ip(IpAddr::from(octets)))
+        } else {
+            None
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCIPRepAddrParse(s: *const c_char, out: &mut IPRepAddr) -> bool {
+    if let Ok(s) = CStr::from_ptr(s).to_str() {
+        if let Some(key) = IPRepAddr::parse(s) {
+            *out = key;
+            return true;
+        }
+    }
+    false
+}
+
+/// Lookup key for a packet address. `version` is 4 or 6 and `addr` points
+/// to 4 or 16 bytes accordingly.
+#[no_mangle]
+pub unsafe extern "C" fn SCIPRepAddrFromBytes(
+    version: u8, addr: *const u8, out: &mut IPRepAddr,
+) -> bool {
+    let len = match version {
+        4 => 4,
+        6 => 16,
+        _ => return false,
+    };
+    if addr.is_null() {
+        return false;
+    }
+    if let Some(key) = IPRepAddr::from_bytes(std::slice::from_raw_parts(addr, len)) {
+        *out = key;
+        return true;
+    }
+    false
+}
+
 /// value matching is done use `DetectUintData` logic.
 ///
 /// isset matching is done using special `DetectUintData` value ">= 0"
 /// isnotset matching bypasses `DetectUintData` and is handled directly
 /// in the match function (in C).
+///
+/// delta matching compares `du8` (as "> N") to the change of the value
+/// since it was first seen in the flow.
+///
+/// sum matching compares `dsum` to the sum of the values of all categories
+/// and ignores `du8` and `cat`.
 #[derive(Debug)]
 #[repr(C)]
 pub struct DetectIPRepData {
@@ -66,6 +297,19 @@ pub struct DetectIPRepData {
     pub cat: u8,
     pub cmd: DetectIPRepDataCmd,
     pub isnotset: bool, // if true, ignores `du8`
+    pub isdelta: bool,
+    pub net: DetectIPRepNet,
+    pub issum: bool,
+    pub dsum: DetectUintData<u16>,
+}
+
+/// Default for the comparison of the mode not in use.
+fn iprep_unused_uint<T: DetectIntType>() -> DetectUintData<T> {
+    DetectUintData::<T> {
+        arg1: T::min_value(),
+        arg2: T::min_value(),
+        mode: DetectUintMode::DetectUintModeGte,
+    }
 }
 
 pub fn is_alphanumeric_or_slash(chr: char) -> bool {
@@ -78,6 +322,17 @@ pub fn is_alphanumeric_or_slash(chr: char) -> bool {
     return false;
 }
 
+/// Parses the comparison of the `sum` command, as the mode and the value,
+/// like `>, 50`, or as one expression, like `40-60`.
+fn iprep_parse_sum(values: &[&str]) -> Option<DetectUintData<u16>> {
+    let expr = match values {
+        [mode, value] => format!("{}{}", mode.trim(), value.trim()),
+        [expr] => expr.trim().to_string(),
+        _ => return None,
+    };
+    detect_parse_uint::<u16>(&expr).ok().map(|(_, dsum)| dsum)
+}
+
 extern "C" {
     pub fn SRepCatGetByShortname(name: *const c_char) -> u8;
 }
@@ -91,6 +346,18 @@ pub fn detect_parse_iprep(i: &str) -> IResult<&str, DetectIPRepData, RuleParseEr
         tag(","),
         preceded(multispace0, nom7::bytes::complete::is_not(",")),
     )(i)?;
+    let mut values = values;
+
+    let mut net = DetectIPRepNet::default();
+    if let Some(last) = values.last() {
+        if let Some(cidr) = last.trim().strip_prefix("net:") {
+            net = match DetectIPRepNet::parse(cidr.trim()) {
+                Some(net) => net,
+                None => return Err(make_error(format!("invalid network: {}", cidr))),
+            };
+            values.pop();
+        }
+    }
 
     let args = values.len();
     if args == 4 || args == 3 {
@@ -100,6 +367,14 @@ pub fn detect_parse_iprep(i: &str) -> IResult<&str, DetectIPRepData, RuleParseEr
             return Err(make_error("invalid command".to_string()));
         };
         let name = values[1].trim();
+        if name == "sum" {
+            let dsum = match iprep_parse_sum(&values[2..]) {
+                Some(dsum) => dsum,
+                None => return Err(make_error("invalid sum".to_string())),
+            };
+            let du8 = iprep_unused_uint();
+            return Ok((i, DetectIPRepData { du8, cat: 0, cmd, isnotset: false, isdelta: false, net, issum: true, dsum, }));
+        }
         let namez = if let Ok(name) = CString::new(name) {
             name
         } else {
@@ -125,7 +400,18 @@ pub fn detect_parse_iprep(i: &str) -> IResult<&str, DetectIPRepData, RuleParseEr
                 arg2: 0,
                 mode,
             };
-            return Ok((i, DetectIPRepData { du8, cat, cmd, isnotset: false, }));
+            return Ok((i, DetectIPRepData { du8, cat, cmd, isnotset: false, isdelta: false, net, issum: false, dsum: iprep_unused_uint(), }));
+        } else if let Some(delta) = values[2].trim().strip_prefix("delta:") {
+            let arg1 = match delta.trim().parse::<u8>() {
+                Ok(val) if val <= 127 => val,
+                _ => return Err(make_error("invalid delta".to_string())),
+            };
+            let du8 = DetectUintData::<u8> {
+                arg1,
+                arg2: 0,
+                mode: DetectUintMode::DetectUintModeGt,
+            };
+            return Ok((i, DetectIPRepData { du8, cat, cmd, isnotset: false, isdelta: true, net, issum: false, dsum: iprep_unused_uint(), }));
         } else {
             let (isnotset, mode, arg1) = match values[2].trim() {
                 "isset" => { (false, DetectUintMode::DetectUintModeGte, 0) },
@@ -137,7 +423,7 @@ pub fn detect_parse_iprep(i: &str) -> IResult<&str, DetectIPRepData, RuleParseEr
                 arg2: 0,
                 mode,
             };
-            return Ok((i, DetectIPRepData { du8, cat, cmd, isnotset, }));
+            return Ok((i, DetectIPRepData { du8, cat, cmd, isnotset, isdelta: false, net, issum: false, dsum: iprep_unused_uint(), }));
         }
     } else if args < 3 {
         return Err(make_error("too few arguments".to_string()));
@@ -161,8 +447,295 @@ pub unsafe extern "C" fn SCDetectIPRepParse(
     return std::ptr::null_mut();
 }
 
+/// Checks if an address is in the network of the `net:` option. `version`
+/// is 4 or 6 and `addr` points to 4 or 16 bytes accordingly.
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectIPRepNetContains(
+    ctx: &DetectIPRepData, version: u8, addr: *const u8,
+) -> bool {
+    if ctx.net.version == 0 {
+        return true;
+    }
+    if addr.is_null() {
+        return false;
+    }
+    let len = match version {
+        4 => 4,
+        6 => 16,
+        _ => return false,
+    };
+    ctx.net.contains(std::slice::from_raw_parts(addr, len))
+}
+
+/// Delta check for a reputation sample. `first` is the flow slot of the
+/// value first seen, negative while unset: the first sample only fills it.
+fn iprep_delta_match(du8: &DetectUintData<u8>, first: &mut i8, val: i8) -> bool {
+    if val < 0 {
+        return false;
+    }
+    if *first < 0 {
+        *first = val;
+        return false;
+    }
+    let delta = (val as i16 - *first as i16).unsigned_abs() as u8;
+    detect_match_uint(du8, delta)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectIPRepDeltaMatch(
+    ctx: &DetectIPRepData, first: *mut i8, val: i8,
+) -> bool {
+    if first.is_null() {
+        return false;
+    }
+    iprep_delta_match(&ctx.du8, &mut *first, val)
+}
+
+/// Sums the values of all categories for an address, negative values being
+/// categories without an entry, and compares the sum.
+fn iprep_sum_match(dsum: &DetectUintData<u16>, vals: &[i8]) -> bool {
+    let sum: u16 = vals.iter().filter(|v| **v > 0).map(|v| *v as u16).sum();
+    detect_match_uint(dsum, sum)
+}
+
+/// Sum check, `vals` holds the value of each of the `len` categories for
+/// the address.
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectIPRepSumMatch(
+    ctx: &DetectIPRepData, vals: *const i8, len: u32,
+) -> bool {
+    if vals.is_null() {
+        return iprep_sum_match(&ctx.dsum, &[]);
+    }
+    iprep_sum_match(&ctx.dsum, std::slice::from_raw_parts(vals, len as usize))
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn SCDetectIPRepFree(ctx: &mut DetectIPRepData) {
     // Just unbox...
     std::mem::drop(Box::from_raw(ctx));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iprep_net_parse() {
+        let net = DetectIPRepNet::parse("10.0.0.0/8").unwrap();
+        assert_eq!(net.version, 4);
+        assert_eq!(net.prefix_len, 8);
+        assert_eq!(&net.addr[..4], &[10, 0, 0, 0]);
+
+        // host bits are cleared
+        let net = DetectIPRepNet::parse("192.168.1.77/24").unwrap();
+        assert_eq!(&net.addr[..4], &[192, 168, 1, 0]);
+
+        let net = DetectIPRepNet::parse("2001:db8::1/32").unwrap();
+        assert_eq!(net.version, 6);
+        assert_eq!(net.prefix_len, 32);
+        assert_eq!(&net.addr[..4], &[0x20, 0x01, 0x0d, 0xb8]);
+        assert!(net.addr[4..].iter().all(|&b| b == 0));
+
+        let net = DetectIPRepNet::parse("1.2.3.4").unwrap();
+        assert_eq!(net.prefix_len, 32);
+        assert!(DetectIPRepNet::parse("0.0.0.0/0").is_some());
+
+        for bad in [
+            "",
+            "10.0.0.0/",
+            "10.0.0.0/33",
+            "10.0.0.0/-1",
+            "10.0.0.0/+8",
+            "10.0.0/8",
+            "10.0.0.256/24",
+            "2001:db8::/129",
+            "not-an-ip/8",
+        ] {
+            assert!(DetectIPRepNet::parse(bad).is_none(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_iprep_net_contains_edges() {
+        let net = DetectIPRepNet::parse("192.168.1.0/24").unwrap();
+        assert!(net.contains(&[192, 168, 1, 0]));
+        assert!(net.contains(&[192, 168, 1, 255]));
+        assert!(!net.contains(&[192, 168, 0, 255]));
+        assert!(!net.contains(&[192, 168, 2, 0]));
+        // family mismatch
+        assert!(!net.contains(&[0u8; 16]));
+
+        let net = DetectIPRepNet::parse("10.0.0.0/9").unwrap();
+        assert!(net.contains(&[10, 127, 255, 255]));
+        assert!(!net.contains(&[10, 128, 0, 0]));
+
+        let net = DetectIPRepNet::parse("2001:db8:0:1::/64").unwrap();
+        let mut inside = [0u8; 16];
+        inside[..8].copy_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 1]);
+        inside[8..].copy_from_slice(&[0xff; 8]);
+        assert!(net.contains(&inside));
+        inside[7] = 2;
+        assert!(!net.contains(&inside));
+        assert!(!net.contains(&[192, 168, 1, 1]));
+
+        // no network set: everything is in scope
+        assert!(DetectIPRepNet::default().contains(&[1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_ip_network_prefix() {
+        let net = IpNetwork::parse("0.0.0.0/0").unwrap();
+        assert!(net.contains(&"255.255.255.255".parse().unwrap()));
+        let net = IpNetwork::parse("10.1.2.3/32").unwrap();
+        assert!(net.contains(&"10.1.2.3".parse().unwrap()));
+        assert!(!net.contains(&"10.1.2.4".parse().unwrap()));
+        let net = IpNetwork::parse("10.1.2.3").unwrap();
+        assert_eq!(net.prefix_len(), 32);
+        assert!(IpNetwork::parse("10.1.2.3/33").is_none());
+        assert!(IpNetwork::parse("::/0").is_some());
+        assert_eq!(IpNetwork::parse("::1").unwrap().prefix_len(), 128);
+        assert!(IpNetwork::parse("::1/128").is_some());
+        assert!(IpNetwork::parse("::1/129").is_none());
+        assert!(IpNetwork::new("10.0.0.0".parse().unwrap(), 33).is_none());
+
+        let net = IpNetwork::parse("172.16.0.0/12").unwrap();
+        assert!(net.contains(&"172.31.255.255".parse().unwrap()));
+        assert!(!net.contains(&"172.32.0.0".parse().unwrap()));
+        assert!(!net.contains(&"172.15.255.255".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_ip_network_host_bits() {
+        let net = IpNetwork::parse("192.168.1.77/24").unwrap();
+        assert_eq!(net.to_string(), "192.168.1.0/24");
+        assert_eq!(net, IpNetwork::parse("192.168.1.0/24").unwrap());
+        assert!(IpNetwork::parse_strict("192.168.1.77/24").is_none());
+        assert_eq!(IpNetwork::parse_strict("192.168.1.0/24"), Some(net));
+
+        let net = IpNetwork::parse("2001:db8::1/64").unwrap();
+        assert_eq!(net.to_string(), "2001:db8::/64");
+        assert!(IpNetwork::parse_strict("2001:db8::1/64").is_none());
+        assert!(IpNetwork::parse_strict("2001:db8::1/128").is_some());
+    }
+
+    #[test]
+    fn test_ip_network_v6() {
+        let net = IpNetwork::parse("2001:db8:0:1::/63").unwrap();
+        assert!(net.contains(&"2001:db8:0:1:ffff::1".parse().unwrap()));
+        assert!(net.contains(&"2001:db8::1".parse().unwrap()));
+        assert!(!net.contains(&"2001:db8:0:2::".parse().unwrap()));
+        // family mismatch
+        assert!(!net.contains(&"32.1.13.184".parse().unwrap()));
+        let net = IpNetwork::parse("10.0.0.0/8").unwrap();
+        assert!(!net.contains(&"::ffff:10.0.0.1".parse().unwrap()));
+
+        let net = IpNetwork::parse("::ffff:10.0.0.0/104").unwrap();
+        assert!(net.contains(&"::ffff:10.1.2.3".parse().unwrap()));
+        assert_eq!(net.to_string(), "::ffff:10.0.0.0/104");
+
+        for bad in [
+            "2001:db8::/",
+            "2001:db8:::/32",
+            "fe80::1%eth0/64",
+            "2001:db8::/+32",
+        ] {
+            assert!(IpNetwork::parse(bad).is_none(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_iprep_addr_key() {
+        let v4 = IPRepAddr::parse("10.0.0.1").unwrap();
+        assert_eq!(v4.version, 4);
+        assert_eq!(&v4.addr[..4], &[10, 0, 0, 1]);
+        assert!(v4.addr[4..].iter().all(|&b| b == 0));
+
+        let v6 = IPRepAddr::parse("2001:db8::1").unwrap();
+        assert_eq!(v6.version, 6);
+        assert_eq!(&v6.addr[..4], &[0x20, 0x01, 0x0d, 0xb8]);
+        assert_eq!(v6.addr[15], 1);
+        assert_ne!(v6, IPRepAddr::parse("2001:db8::2").unwrap());
+
+        // v4-mapped is keyed as v4, in a file and in a packet
+        assert_eq!(IPRepAddr::parse("::ffff:10.0.0.1"), Some(v4));
+        let mut mapped = [0u8; 16];
+        mapped[10..12].copy_from_slice(&[0xff, 0xff]);
+        mapped[12..].copy_from_slice(&[10, 0, 0, 1]);
+        assert_eq!(IPRepAddr::from_bytes(&mapped), Some(v4));
+        assert_eq!(IPRepAddr::from_bytes(&[10, 0, 0, 1]), Some(v4));
+
+        // the same bytes as v6 are a different key than v4
+        let mut v6bytes = [0u8; 16];
+        v6bytes[..4].copy_from_slice(&[10, 0, 0, 1]);
+        let key = IPRepAddr::from_bytes(&v6bytes).unwrap();
+        assert_eq!(key.version, 6);
+        assert_ne!(key, v4);
+        // deprecated v4-compatible form is not mapped
+        assert_eq!(IPRepAddr::parse("::10.0.0.1").unwrap().version, 6);
+
+        assert!(IPRepAddr::parse("10.0.0.256").is_none());
+        assert!(IPRepAddr::parse("10.0.0.0/8").is_none());
+        assert!(IPRepAddr::from_bytes(&[10, 0, 0]).is_none());
+    }
+
+    #[test]
+    fn test_iprep_sum() {
+        let dsum = iprep_parse_sum(&[" >", " 50"]).unwrap();
+        assert_eq!(dsum.mode, DetectUintMode::DetectUintModeGt);
+        assert_eq!(dsum.arg1, 50);
+
+        // address in two categories
+        let mut vals = [-1i8; 60];
+        vals[1] = 30;
+        vals[2] = 25;
+        assert!(iprep_sum_match(&dsum, &vals));
+        vals[2] = 20;
+        assert!(!iprep_sum_match(&dsum, &vals));
+        // missing address
+        assert!(!iprep_sum_match(&dsum, &[-1i8; 60]));
+        assert!(!iprep_sum_match(&dsum, &[]));
+
+        // more than one category can hold the max value
+        let dsum = iprep_parse_sum(&[">", "254"]).unwrap();
+        assert!(iprep_sum_match(&dsum, &[127, 127, 1]));
+
+        let dsum = iprep_parse_sum(&[" 40-60"]).unwrap();
+        assert_eq!(dsum.mode, DetectUintMode::DetectUintModeRange);
+        assert!(iprep_sum_match(&dsum, &[30, 20]));
+        assert!(!iprep_sum_match(&dsum, &[30, 30]));
+        let dsum = iprep_parse_sum(&["=", "0"]).unwrap();
+        assert!(iprep_sum_match(&dsum, &[-1, -1]));
+
+        assert!(iprep_parse_sum(&["isset"]).is_none());
+        assert!(iprep_parse_sum(&[">", "70000"]).is_none());
+        assert!(iprep_parse_sum(&["delta:10"]).is_none());
+        assert!(iprep_parse_sum(&[]).is_none());
+    }
+
+    #[test]
+    fn test_iprep_delta_samples() {
+        let du8 = DetectUintData::<u8> {
+            arg1: 10,
+            arg2: 0,
+            mode: DetectUintMode::DetectUintModeGt,
+        };
+        let mut first: i8 = -1;
+        // first sample is only recorded
+        assert!(!iprep_delta_match(&du8, &mut first, 20));
+        assert_eq!(first, 20);
+        // second sample changed by more than 10
+        assert!(iprep_delta_match(&du8, &mut first, 31));
+        assert_eq!(first, 20);
+
+        assert!(!iprep_delta_match(&du8, &mut first, 30));
+        assert!(iprep_delta_match(&du8, &mut first, 9));
+        // no value does not reset the slot
+        assert!(!iprep_delta_match(&du8, &mut first, -1));
+        assert_eq!(first, 20);
+
+        let mut first: i8 = -1;
+        assert!(!iprep_delta_match(&du8, &mut first, 127));
+        assert!(iprep_delta_match(&du8, &mut first, 0));
+    }
+}
@@ -18,7 +18,9 @@
 //! Module for rule parsing.
 
 pub mod byte_extract;
+pub mod byte_jump;
 pub mod byte_math;
+pub mod byte_test;
 pub mod entropy;
 pub mod error;
 pub mod flow;
@@ -30,16 +32,26 @@ pub mod transforms;
 pub mod uint;
 pub mod float;
 pub mod uri;
+pub mod tls;
 pub mod tojson;
 pub mod vlan;
 pub mod datasets;
 
-use std::os::raw::c_int;
-use std::ffi::CString;
+use std::os::raw::{c_int, c_void};
+use std::ffi::{CStr, CString, NulError};
+use std::fmt;
+
+#[cfg(feature = "keyword-metrics")]
+use std::collections::BTreeMap;
+#[cfg(feature = "keyword-metrics")]
+use std::sync::Mutex;
+#[cfg(feature = "keyword-metrics")]
+use std::time::{Duration, Instant};
 
 use suricata_sys::sys::{
-    DetectEngineCtx, SCDetectHelperKeywordRegister, SCDetectHelperKeywordSetCleanCString,
-    SCSigTableAppLiteElmt, Signature,
+    DetectEngineCtx, DetectEngineThreadCtx, Flow, SCDetectHelperKeywordExists,
+    SCDetectHelperKeywordRegister, SCDetectHelperKeywordSetCleanCString, SCSigTableAppLiteElmt,
+    SigMatchCtx, Signature,
 };
 
 /// EnumString trait that will be implemented on enums that
@@ -56,6 +68,52 @@ pub trait EnumString<T> {
 
     /// Get an enum variant from parsing a string.
     fn from_str(s: &str) -> Option<Self> where Self: Sized;
+
+    /// Return all the enum variants, in declaration order.
+    fn all_variants() -> &'static [Self] where Self: Sized;
+
+    /// Get an enum variant from parsing a string, ignoring ASCII case.
+    fn from_str_ci(s: &str) -> Option<Self> where Self: Sized {
+        Self::from_str(&s.to_ascii_lowercase())
+    }
+
+    /// Get an enum variant from parsing a string like from_str, or else
+    /// the name of the closest variant, to suggest it in an error. There
+    /// is no suggestion if even the closest variant differs by more than
+    /// a third of its name.
+    fn from_str_suggest(s: &str) -> Result<Self, Option<&'static str>>
+    where
+        Self: Sized + 'static,
+    {
+        if let Some(v) = Self::from_str(s) {
+            return Ok(v);
+        }
+        // a difference of case is the closest there is
+        let s = s.to_ascii_lowercase();
+        let suggestion = Self::all_variants()
+            .iter()
+            .map(|v| (v.to_str(), edit_distance(&s, v.to_str())))
+            .min_by_key(|(_, distance)| *distance)
+            .filter(|(name, distance)| *distance <= (name.chars().count() / 3).max(1))
+            .map(|(name, _)| name);
+        Err(suggestion)
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, counted in chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let subst = diag + usize::from(ca != *cb);
+            diag = row[j + 1];
+            row[j + 1] = subst.min(row[j] + 1).min(diag + 1);
+        }
+    }
+    row[b.len()]
 }
 
 /// Rust app-layer light version of SigTableElmt for simple sticky buffer
@@ -74,24 +132,297 @@ pub struct SigTableElmtStickyBuffer {
     ) -> c_int,
 }
 
-pub fn helper_keyword_register_sticky_buffer(kw: &SigTableElmtStickyBuffer) -> u16 {
-    let name = CString::new(kw.name.as_bytes()).unwrap().into_raw();
-    let desc = CString::new(kw.desc.as_bytes()).unwrap().into_raw();
-    let url = CString::new(kw.url.as_bytes()).unwrap().into_raw();
+impl SigTableElmtStickyBuffer {
+    /// Starts a [`SigTableElmtStickyBufferBuilder`].
+    pub fn builder() -> SigTableElmtStickyBufferBuilder {
+        SigTableElmtStickyBufferBuilder::default()
+    }
+}
+
+/// Builder of a [`SigTableElmtStickyBuffer`]: name and setup are required,
+/// description and url default to empty strings.
+#[derive(Default)]
+pub struct SigTableElmtStickyBufferBuilder {
+    name: Option<String>,
+    desc: Option<String>,
+    url: Option<String>,
+    setup: Option<
+        unsafe extern "C" fn(
+            de: *mut DetectEngineCtx,
+            s: *mut Signature,
+            raw: *const std::os::raw::c_char,
+        ) -> c_int,
+    >,
+}
+
+impl SigTableElmtStickyBufferBuilder {
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn desc(mut self, desc: &str) -> Self {
+        self.desc = Some(desc.to_string());
+        self
+    }
+
+    pub fn url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    pub fn setup(
+        mut self,
+        setup: unsafe extern "C" fn(
+            de: *mut DetectEngineCtx,
+            s: *mut Signature,
+            raw: *const std::os::raw::c_char,
+        ) -> c_int,
+    ) -> Self {
+        self.setup = Some(setup);
+        self
+    }
+
+    pub fn build(self) -> Result<SigTableElmtStickyBuffer, RegistrationError> {
+        let name = self.name.ok_or(RegistrationError::MissingField("name"))?;
+        let setup = self.setup.ok_or(RegistrationError::MissingField("setup"))?;
+        Ok(SigTableElmtStickyBuffer {
+            name,
+            desc: self.desc.unwrap_or_default(),
+            url: self.url.unwrap_or_default(),
+            setup,
+        })
+    }
+}
+
+/// Rust app-layer light version of SigTableElmt for a keyword matching on
+/// transactions
+pub struct SigTableElmtTxMatch {
+    /// keyword name
+    pub name: String,
+    /// keyword description
+    pub desc: String,
+    /// keyword documentation url
+    pub url: String,
+    /// function callback to parse and setup keyword in rule
+    pub setup: unsafe extern "C" fn(
+        de: *mut DetectEngineCtx,
+        s: *mut Signature,
+        raw: *const std::os::raw::c_char,
+    ) -> c_int,
+    /// function callback to match on an app-layer transaction
+    pub tx_match: unsafe extern "C" fn(
+        de: *mut DetectEngineThreadCtx,
+        f: *mut Flow,
+        flags: u8,
+        alstate: *mut c_void,
+        txv: *mut c_void,
+        s: *const Signature,
+        ctx: *const SigMatchCtx,
+    ) -> c_int,
+    /// function callback to free structure allocated by setup if any
+    pub free: Option<unsafe extern "C" fn(de: *mut DetectEngineCtx, ptr: *mut c_void)>,
+    /// if the keyword option has to be quoted
+    pub quotes_mandatory: bool,
+}
+
+/// Error of the keyword registration helpers.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RegistrationError {
+    /// the name, description or url contains a NUL byte
+    InvalidString(NulError),
+    /// the keyword table has no free id left
+    TableFull,
+    /// a required field was not given to a builder
+    MissingField(&'static str),
+    /// the name is empty or has a character other than an ascii
+    /// alphanumeric, `.` or `_`
+    InvalidName(String),
+    /// a keyword or keyword alias is already registered with the name
+    DuplicateName(String),
+}
+
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistrationError::InvalidString(e) => {
+                write!(f, "keyword string has a NUL byte at {}", e.nul_position())
+            }
+            RegistrationError::TableFull => write!(f, "no keyword id left"),
+            RegistrationError::MissingField(field) => write!(f, "keyword has no {}", field),
+            RegistrationError::InvalidName(name) => write!(f, "invalid keyword name {:?}", name),
+            RegistrationError::DuplicateName(name) => {
+                write!(f, "keyword {} is already registered", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistrationError {}
+
+impl From<NulError> for RegistrationError {
+    fn from(e: NulError) -> Self {
+        RegistrationError::InvalidString(e)
+    }
+}
+
+/// Id returned by `SCDetectHelperKeywordRegister` when no id is left.
+const KEYWORD_ID_NONE: u16 = u16::MAX;
+
+/// Registers a keyword in the C table, which takes ownership of its names
+/// on success only.
+fn keyword_register(st: &SCSigTableAppLiteElmt) -> u16 {
+    unsafe {
+        let r = SCDetectHelperKeywordRegister(st);
+        if r != KEYWORD_ID_NONE {
+            SCDetectHelperKeywordSetCleanCString(r);
+        }
+        return r;
+    }
+}
+
+/// Sets the names of `st` and hands it to `register`.
+fn keyword_register_named<F>(
+    mut st: SCSigTableAppLiteElmt, name: &str, desc: &str, url: &str, register: F,
+) -> Result<u16, RegistrationError>
+where
+    F: FnOnce(&SCSigTableAppLiteElmt) -> u16,
+{
+    // all checked before any is handed over
+    let name = CString::new(name)?;
+    let desc = CString::new(desc)?;
+    let url = CString::new(url)?;
+    st.name = name.as_ptr();
+    st.desc = desc.as_ptr();
+    st.url = url.as_ptr();
+    let r = register(&st);
+    if r == KEYWORD_ID_NONE {
+        // the C table did not take the names, they are dropped here
+        return Err(RegistrationError::TableFull);
+    }
+    // the C table owns the names now, and releases them on shutdown
+    let _ = name.into_raw();
+    let _ = desc.into_raw();
+    let _ = url.into_raw();
+    #[cfg(test)]
+    KEYWORD_NAMES_LIVE.with(|n| n.set(n.get() + 3));
+    Ok(r)
+}
+
+#[cfg(test)]
+std::thread_local! {
+    /// Names handed over to the C table by the current thread and not
+    /// released by `SCDetectSigMatchNamesFree` yet.
+    static KEYWORD_NAMES_LIVE: std::cell::Cell<isize> = const { std::cell::Cell::new(0) };
+}
+
+/// Flags of a sticky buffer with the extra `flags` of the caller. A sticky
+/// buffer takes no option unless one is allowed by `flags`.
+fn sticky_buffer_flags(flags: u16) -> u16 {
+    if flags & (SIGMATCH_OPTIONAL_OPT | SIGMATCH_QUOTES_MANDATORY) != 0 {
+        SIGMATCH_INFO_STICKY_BUFFER | flags
+    } else {
+        SIGMATCH_NOOPT | SIGMATCH_INFO_STICKY_BUFFER | flags
+    }
+}
+
+fn sticky_buffer_register<F>(
+    kw: &SigTableElmtStickyBuffer, flags: u16, register: F,
+) -> Result<u16, RegistrationError>
+where
+    F: FnOnce(&SCSigTableAppLiteElmt) -> u16,
+{
     let st = SCSigTableAppLiteElmt {
-        name,
-        desc,
-        url,
+        name: std::ptr::null(),
+        desc: std::ptr::null(),
+        url: std::ptr::null(),
         Setup: Some(kw.setup),
-        flags: SIGMATCH_NOOPT | SIGMATCH_INFO_STICKY_BUFFER,
+        flags: sticky_buffer_flags(flags),
         AppLayerTxMatch: None,
         Free: None,
     };
-    unsafe {
-        let r = SCDetectHelperKeywordRegister(&st);
-        SCDetectHelperKeywordSetCleanCString(r);
-        return r;
+    keyword_register_named(st, &kw.name, &kw.desc, &kw.url, register)
+}
+
+/// Checks that a new keyword `name` only uses the allowed characters and
+/// that `exists` does not know it yet, so it can't shadow another keyword.
+fn keyword_name_validate<E>(name: &str, exists: E) -> Result<(), RegistrationError>
+where
+    E: FnOnce(&CStr) -> bool,
+{
+    if name.is_empty()
+        || !name
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'.' || c == b'_')
+    {
+        return Err(RegistrationError::InvalidName(name.to_string()));
+    }
+    let cname = CString::new(name)?;
+    if exists(&cname) {
+        return Err(RegistrationError::DuplicateName(name.to_string()));
     }
+    Ok(())
+}
+
+/// Looks `name` up in the C keyword table, aliases included.
+fn keyword_exists(name: &CStr) -> bool {
+    unsafe { SCDetectHelperKeywordExists(name.as_ptr()) }
+}
+
+fn tx_match_register<F>(
+    kw: &SigTableElmtTxMatch, flags: u16, register: F,
+) -> Result<u16, RegistrationError>
+where
+    F: FnOnce(&SCSigTableAppLiteElmt) -> u16,
+{
+    let st = SCSigTableAppLiteElmt {
+        name: std::ptr::null(),
+        desc: std::ptr::null(),
+        url: std::ptr::null(),
+        Setup: Some(kw.setup),
+        flags: if kw.quotes_mandatory {
+            SIGMATCH_QUOTES_MANDATORY | flags
+        } else {
+            flags
+        },
+        AppLayerTxMatch: Some(kw.tx_match),
+        Free: kw.free,
+    };
+    keyword_register_named(st, &kw.name, &kw.desc, &kw.url, register)
+}
+
+/// Registers a sticky buffer keyword. The name has to be new and may only
+/// use ascii alphanumerics, `.` and `_`.
+pub fn helper_keyword_register_sticky_buffer(
+    kw: &SigTableElmtStickyBuffer,
+) -> Result<u16, RegistrationError> {
+    keyword_name_validate(&kw.name, keyword_exists)?;
+    sticky_buffer_register(kw, 0, keyword_register)
+}
+
+/// Same as [`helper_keyword_register_sticky_buffer`] with extra `SIGMATCH_*`
+/// flags. `SIGMATCH_OPTIONAL_OPT` or `SIGMATCH_QUOTES_MANDATORY` let the
+/// keyword take an option.
+pub fn helper_keyword_register_sticky_buffer_flags(
+    kw: &SigTableElmtStickyBuffer, flags: u16,
+) -> Result<u16, RegistrationError> {
+    keyword_name_validate(&kw.name, keyword_exists)?;
+    sticky_buffer_register(kw, flags, keyword_register)
+}
+
+/// Registers a keyword that takes options and matches on transactions.
+pub fn helper_keyword_register_tx_match(
+    kw: &SigTableElmtTxMatch,
+) -> Result<u16, RegistrationError> {
+    tx_match_register(kw, 0, keyword_register)
+}
+
+/// Same as [`helper_keyword_register_tx_match`] with extra `SIGMATCH_*`
+/// flags, such as `SIGMATCH_OPTIONAL_OPT`.
+pub fn helper_keyword_register_tx_match_flags(
+    kw: &SigTableElmtTxMatch, flags: u16,
+) -> Result<u16, RegistrationError> {
+    tx_match_register(kw, flags, keyword_register)
 }
 
 #[repr(C)]
@@ -111,12 +442,116 @@ pub unsafe extern "C" fn SCDetectSigMatchNamesFree(kw: &mut SCSigTableNamesElmt)
     let _ = CString::from_raw(kw.name);
     let _ = CString::from_raw(kw.desc);
     let _ = CString::from_raw(kw.url);
+    #[cfg(test)]
+    KEYWORD_NAMES_LIVE.with(|n| n.set(n.get() - 3));
+}
+
+/// Setup callback of a keyword, as called by the rule parser.
+pub type KeywordSetupFn = unsafe extern "C" fn(
+    de: *mut DetectEngineCtx,
+    s: *mut Signature,
+    raw: *const std::os::raw::c_char,
+) -> c_int;
+
+/// Parse-time metrics of a keyword, aggregated over all its setups.
+#[cfg(feature = "keyword-metrics")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeywordSetupMetrics {
+    /// number of setups, failed ones included
+    pub count: u64,
+    /// number of setups that failed
+    pub failures: u64,
+    /// total time spent in the setups
+    pub duration: Duration,
+}
+
+#[cfg(feature = "keyword-metrics")]
+static KEYWORD_SETUP_METRICS: Mutex<BTreeMap<String, KeywordSetupMetrics>> =
+    Mutex::new(BTreeMap::new());
+
+#[cfg(feature = "keyword-metrics")]
+fn keyword_setup_record(name: &str, elapsed: Duration, ok: bool) {
+    let mut metrics = KEYWORD_SETUP_METRICS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if !metrics.contains_key(name) {
+        metrics.insert(name.to_string(), KeywordSetupMetrics::default());
+    }
+    if let Some(m) = metrics.get_mut(name) {
+        m.count += 1;
+        if !ok {
+            m.failures += 1;
+        }
+        m.duration += elapsed;
+    }
+}
+
+/// Calls the `setup` of keyword `name`, recording how long it took and if
+/// it failed.
+#[cfg(feature = "keyword-metrics")]
+pub unsafe fn keyword_setup_timed(
+    name: &str, setup: KeywordSetupFn, de: *mut DetectEngineCtx, s: *mut Signature,
+    raw: *const std::os::raw::c_char,
+) -> c_int {
+    let start = Instant::now();
+    let r = setup(de, s, raw);
+    keyword_setup_record(name, start.elapsed(), r >= 0);
+    return r;
+}
+
+/// Returns the metrics of each keyword set up so far, sorted by name.
+#[cfg(feature = "keyword-metrics")]
+pub fn keyword_setup_metrics() -> Vec<(String, KeywordSetupMetrics)> {
+    let metrics = KEYWORD_SETUP_METRICS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    metrics
+        .iter()
+        .map(|(name, m)| (name.clone(), m.clone()))
+        .collect()
+}
+
+/// Setup hook of the rule parser with `--enable-keyword-metrics`.
+#[cfg(feature = "keyword-metrics")]
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectKeywordSetupTimed(
+    name: *const std::os::raw::c_char, setup: KeywordSetupFn, de: *mut DetectEngineCtx,
+    s: *mut Signature, raw: *const std::os::raw::c_char,
+) -> c_int {
+    let name = CStr::from_ptr(name).to_string_lossy();
+    return keyword_setup_timed(&name, setup, de, s, raw);
+}
+
+/// Returns the metrics of each keyword set up so far like
+/// keyword_setup_metrics, and starts over from zero.
+#[cfg(feature = "keyword-metrics")]
+pub fn keyword_setup_metrics_take() -> Vec<(String, KeywordSetupMetrics)> {
+    let mut metrics = KEYWORD_SETUP_METRICS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    std::mem::take(&mut *metrics).into_iter().collect()
+}
+
+/// Logs the metrics of each keyword set up since the last call, so that
+/// each load of the rules logs its own.
+#[cfg(feature = "keyword-metrics")]
+#[no_mangle]
+pub extern "C" fn SCDetectKeywordSetupMetricsLog() {
+    for (name, m) in keyword_setup_metrics_take() {
+        SCLogPerf!(
+            "keyword {}: {} setups, {} failed, {} us",
+            name,
+            m.count,
+            m.failures,
+            m.duration.as_micros()
+        );
+    }
 }
 
 // TODO bindgen these
 pub const SIGMATCH_NOOPT: u16 = 1; // BIT_U16(0) in detect.h
-pub(crate) const SIGMATCH_OPTIONAL_OPT: u16 = 0x10; // BIT_U16(4) in detect.h
-pub(crate) const SIGMATCH_QUOTES_MANDATORY: u16 = 0x40; // BIT_U16(6) in detect.h
+pub const SIGMATCH_OPTIONAL_OPT: u16 = 0x10; // BIT_U16(4) in detect.h
+pub const SIGMATCH_QUOTES_MANDATORY: u16 = 0x40; // BIT_U16(6) in detect.h
 pub const SIGMATCH_INFO_STICKY_BUFFER: u16 = 0x200; // BIT_U16(9)
 
 #[repr(u8)]
@@ -128,6 +563,38 @@ pub enum ByteEndian {
     EndianDCE = 3,
 }
 
+/// Byte order of `dce` when the inspected buffer has no DCE/RPC data
+/// representation, like a packet payload. It is the NDR default.
+pub const DCE_ENDIAN_DEFAULT: ByteEndian = ByteEndian::LittleEndian;
+
+impl ByteEndian {
+    /// Resolves `EndianDCE` to `dce`, the byte order negotiated by the
+    /// DCE/RPC flow for the inspected buffer, or to `DCE_ENDIAN_DEFAULT`.
+    pub fn resolve_dce(self, dce: Option<ByteEndian>) -> ByteEndian {
+        match self {
+            ByteEndian::EndianDCE => dce.unwrap_or(DCE_ENDIAN_DEFAULT),
+            endian => endian,
+        }
+    }
+}
+
+/// Resolves the endian of byte_extract and byte_math with the
+/// `dce_le` and `dce_be` flags of the inspected buffer.
+#[no_mangle]
+pub extern "C" fn SCDetectByteEndianResolve(endian: u8, dce_le: bool, dce_be: bool) -> u8 {
+    if endian != ByteEndian::EndianDCE as u8 {
+        return endian;
+    }
+    let dce = if dce_le {
+        Some(ByteEndian::LittleEndian)
+    } else if dce_be {
+        Some(ByteEndian::BigEndian)
+    } else {
+        None
+    };
+    return ByteEndian::EndianDCE.resolve_dce(dce) as u8;
+}
+
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ByteBase {
@@ -136,32 +603,37 @@ pub enum ByteBase {
     BaseHex = 16,
 }
 
-fn get_string_value(value: &str) -> Option<ByteBase> {
-    let res = match value {
-        "hex" => Some(ByteBase::BaseHex),
-        "oct" => Some(ByteBase::BaseOct),
-        "dec" => Some(ByteBase::BaseDec),
-        _ => None,
-    };
-
-    res
+/// Parses the name of a number base: `hex`, `oct` or `dec`.
+pub fn get_string_value(value: &str) -> Result<ByteBase, String> {
+    match value {
+        "hex" => Ok(ByteBase::BaseHex),
+        "oct" => Ok(ByteBase::BaseOct),
+        "dec" => Ok(ByteBase::BaseDec),
+        _ => Err(format!(
+            "invalid string value: {}, expected hex, oct or dec",
+            value
+        )),
+    }
 }
 
-fn get_endian_value(value: &str) -> Option<ByteEndian> {
-    let res = match value {
-        "big" => Some(ByteEndian::BigEndian),
-        "little" => Some(ByteEndian::LittleEndian),
-        "dce" => Some(ByteEndian::EndianDCE),
-        _ => None,
-    };
-
-    res
+/// Parses the name of a byte order: `big`, `little` or `dce`.
+pub fn get_endian_value(value: &str) -> Result<ByteEndian, String> {
+    match value {
+        "big" => Ok(ByteEndian::BigEndian),
+        "little" => Ok(ByteEndian::LittleEndian),
+        "dce" => Ok(ByteEndian::EndianDCE),
+        _ => Err(format!(
+            "invalid endian value: {}, expected big, little or dce",
+            value
+        )),
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use suricata_derive::EnumStringU8;
+    use std::cell::RefCell;
+    use suricata_derive::{EnumStringU16, EnumStringU32, EnumStringU8};
 
     #[derive(Clone, Debug, PartialEq, EnumStringU8)]
     #[repr(u8)]
@@ -170,6 +642,357 @@ mod test {
         BestValueEver = 42,
     }
 
+    unsafe extern "C" fn dummy_setup(
+        _de: *mut DetectEngineCtx, _s: *mut Signature, _raw: *const std::os::raw::c_char,
+    ) -> c_int {
+        0
+    }
+
+    unsafe extern "C" fn dummy_tx_match(
+        _de: *mut DetectEngineThreadCtx, _f: *mut Flow, _flags: u8, _state: *mut c_void,
+        _tx: *mut c_void, _sig: *const Signature, _ctx: *const SigMatchCtx,
+    ) -> c_int {
+        1
+    }
+
+    unsafe extern "C" fn dummy_free(_de: *mut DetectEngineCtx, _ptr: *mut c_void) {}
+
+    std::thread_local! {
+        static REGISTERED: RefCell<Option<(String, u16)>> = const { RefCell::new(None) };
+    }
+
+    /// Stands in for the C keyword table: records the name and flags of the
+    /// keyword and releases its names like the C side does on shutdown.
+    fn mock_register(st: &SCSigTableAppLiteElmt) -> u16 {
+        unsafe {
+            assert!(st.Setup.is_some());
+            let name = CStr::from_ptr(st.name).to_str().unwrap().to_string();
+            REGISTERED.with(|r| *r.borrow_mut() = Some((name, st.flags)));
+            let mut names = SCSigTableNamesElmt {
+                name: st.name as *mut _,
+                desc: st.desc as *mut _,
+                url: st.url as *mut _,
+            };
+            SCDetectSigMatchNamesFree(&mut names);
+        }
+        // first id after the builtin keywords
+        400
+    }
+
+    fn registered_flags() -> u16 {
+        REGISTERED.with(|r| r.borrow_mut().take().unwrap().1)
+    }
+
+    fn dummy_sticky_buffer() -> SigTableElmtStickyBuffer {
+        SigTableElmtStickyBuffer {
+            name: String::from("dummy.buffer"),
+            desc: String::from("dummy sticky buffer"),
+            url: String::from("/rules/dummy.html#dummy-buffer"),
+            setup: dummy_setup,
+        }
+    }
+
+    fn dummy_tx_match_kw() -> SigTableElmtTxMatch {
+        SigTableElmtTxMatch {
+            name: String::from("dummy.tx"),
+            desc: String::from("dummy tx match keyword"),
+            url: String::from("/rules/dummy.html#dummy-tx"),
+            setup: dummy_setup,
+            tx_match: dummy_tx_match,
+            free: Some(dummy_free),
+            quotes_mandatory: true,
+        }
+    }
+
+    #[test]
+    fn test_helper_keyword_register_tx_match() {
+        let kw = dummy_tx_match_kw();
+        let id = tx_match_register(&kw, 0, |st| {
+            assert!(st.AppLayerTxMatch.is_some());
+            assert!(st.Free.is_some());
+            mock_register(st)
+        });
+        assert_ne!(id, Ok(0));
+        let (name, flags) = REGISTERED.with(|r| r.borrow_mut().take().unwrap());
+        assert_eq!(name, "dummy.tx");
+        assert_eq!(flags & SIGMATCH_NOOPT, 0);
+        assert_ne!(flags & SIGMATCH_QUOTES_MANDATORY, 0);
+    }
+
+    #[test]
+    fn test_helper_keyword_register_nul() {
+        let mut kw = dummy_sticky_buffer();
+        kw.name = String::from("dummy\0buffer");
+        let r = sticky_buffer_register(&kw, 0, |_| panic!("registered"));
+        match r {
+            Err(RegistrationError::InvalidString(e)) => assert_eq!(e.nul_position(), 5),
+            _ => panic!("unexpected result {:?}", r),
+        }
+
+        let mut kw = dummy_tx_match_kw();
+        kw.url = String::from("/rules/\0");
+        assert!(tx_match_register(&kw, 0, |_| panic!("registered")).is_err());
+    }
+
+    #[test]
+    fn test_helper_keyword_register_full() {
+        let kw = dummy_sticky_buffer();
+        let live = || KEYWORD_NAMES_LIVE.with(|n| n.get());
+        assert_eq!(live(), 0);
+
+        // accepted names are the ones the C table releases
+        let mut kept = None;
+        sticky_buffer_register(&kw, 0, |st| {
+            kept = Some((st.name, st.desc, st.url));
+            400
+        })
+        .unwrap();
+        assert_eq!(live(), 3);
+        let (name, desc, url) = kept.unwrap();
+        unsafe {
+            assert_eq!(CStr::from_ptr(name).to_str(), Ok("dummy.buffer"));
+            let mut names = SCSigTableNamesElmt {
+                name: name as *mut _,
+                desc: desc as *mut _,
+                url: url as *mut _,
+            };
+            SCDetectSigMatchNamesFree(&mut names);
+        }
+        assert_eq!(live(), 0);
+
+        // refused names stay owned by the registration, which drops them
+        let r = sticky_buffer_register(&kw, 0, |_| KEYWORD_ID_NONE);
+        assert_eq!(r, Err(RegistrationError::TableFull));
+        assert_eq!(live(), 0);
+
+        let kw = dummy_tx_match_kw();
+        let r = tx_match_register(&kw, 0, |_| KEYWORD_ID_NONE);
+        assert_eq!(r, Err(RegistrationError::TableFull));
+        assert_eq!(live(), 0);
+    }
+
+    #[test]
+    fn test_keyword_name_validate() {
+        assert_eq!(keyword_name_validate("dummy.buffer_2", |_| false), Ok(()));
+        let invalid = [
+            "",
+            "dummy buffer",
+            "dummy-buffer",
+            "dummy;buffer",
+            "dummy\0",
+            "dümmy",
+        ];
+        for name in invalid {
+            let r = keyword_name_validate(name, |_| panic!("looked up"));
+            assert_eq!(r, Err(RegistrationError::InvalidName(name.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_keyword_name_validate_duplicate() {
+        let registered = ["dummy.buffer", "dummy_alias"];
+        let exists = |name: &CStr| registered.contains(&name.to_str().unwrap());
+        for name in registered {
+            let r = keyword_name_validate(name, exists);
+            assert_eq!(r, Err(RegistrationError::DuplicateName(name.to_string())));
+        }
+        assert_eq!(keyword_name_validate("dummy.other", exists), Ok(()));
+    }
+
+    #[cfg(feature = "keyword-metrics")]
+    #[test]
+    fn test_keyword_setup_metrics() {
+        unsafe extern "C" fn failing_setup(
+            _de: *mut DetectEngineCtx, _s: *mut Signature, _raw: *const std::os::raw::c_char,
+        ) -> c_int {
+            -1
+        }
+        fn metrics(name: &str) -> Option<KeywordSetupMetrics> {
+            keyword_setup_metrics()
+                .into_iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, m)| m)
+        }
+        // what the C table keeps: the names, and the setup the rule parser
+        // calls with the name through `SCDetectKeywordSetupTimed`
+        fn kept(st: &SCSigTableAppLiteElmt) -> (SCSigTableNamesElmt, KeywordSetupFn) {
+            let names = SCSigTableNamesElmt {
+                name: st.name as *mut _,
+                desc: st.desc as *mut _,
+                url: st.url as *mut _,
+            };
+            (names, st.Setup.unwrap())
+        }
+
+        let mut buffer = None;
+        let mut kw = dummy_sticky_buffer();
+        kw.name = String::from("metrics.buffer");
+        sticky_buffer_register(&kw, 0, |st| {
+            buffer = Some(kept(st));
+            400
+        })
+        .unwrap();
+        let (mut buffer_names, buffer_setup) = buffer.unwrap();
+
+        let mut failing = None;
+        let mut kw = dummy_tx_match_kw();
+        kw.name = String::from("metrics.failing");
+        kw.setup = failing_setup;
+        tx_match_register(&kw, 0, |st| {
+            failing = Some(kept(st));
+            401
+        })
+        .unwrap();
+        let (mut failing_names, failing_setup) = failing.unwrap();
+
+        assert_eq!(metrics("metrics.buffer"), None);
+        let (de, s, raw) = (std::ptr::null_mut(), std::ptr::null_mut(), std::ptr::null());
+        for count in 1..=3 {
+            let r =
+                unsafe { SCDetectKeywordSetupTimed(buffer_names.name, buffer_setup, de, s, raw) };
+            assert_eq!(r, 0);
+            let m = metrics("metrics.buffer").unwrap();
+            assert_eq!(m.count, count);
+            assert_eq!(m.failures, 0);
+        }
+
+        let r = unsafe { SCDetectKeywordSetupTimed(failing_names.name, failing_setup, de, s, raw) };
+        assert_eq!(r, -1);
+        let m = metrics("metrics.failing").unwrap();
+        assert_eq!((m.count, m.failures), (1, 1));
+        assert_eq!(metrics("metrics.buffer").unwrap().count, 3);
+
+        // a reload of the rules starts over
+        let taken: Vec<String> = keyword_setup_metrics_take()
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect();
+        assert_eq!(taken, ["metrics.buffer", "metrics.failing"]);
+        assert_eq!(metrics("metrics.buffer"), None);
+        let r = unsafe { SCDetectKeywordSetupTimed(buffer_names.name, buffer_setup, de, s, raw) };
+        assert_eq!(r, 0);
+        assert_eq!(metrics("metrics.buffer").unwrap().count, 1);
+
+        unsafe {
+            SCDetectSigMatchNamesFree(&mut buffer_names);
+            SCDetectSigMatchNamesFree(&mut failing_names);
+        }
+    }
+
+    #[test]
+    fn test_sticky_buffer_builder() {
+        let kw = SigTableElmtStickyBuffer::builder()
+            .name("dummy.buffer")
+            .desc("dummy sticky buffer")
+            .url("/rules/dummy.html#dummy-buffer")
+            .setup(dummy_setup)
+            .build()
+            .unwrap();
+        let expected = dummy_sticky_buffer();
+        assert_eq!(kw.name, expected.name);
+        assert_eq!(kw.desc, expected.desc);
+        assert_eq!(kw.url, expected.url);
+        assert_eq!(kw.setup as usize, expected.setup as usize);
+
+        let kw = SigTableElmtStickyBuffer::builder()
+            .name("dummy.buffer")
+            .setup(dummy_setup)
+            .build()
+            .unwrap();
+        assert_eq!(kw.desc, "");
+        assert_eq!(kw.url, "");
+
+        let r = SigTableElmtStickyBuffer::builder()
+            .setup(dummy_setup)
+            .build();
+        assert_eq!(r.err(), Some(RegistrationError::MissingField("name")));
+        let r = SigTableElmtStickyBuffer::builder()
+            .name("dummy.buffer")
+            .build();
+        assert_eq!(r.err(), Some(RegistrationError::MissingField("setup")));
+    }
+
+    #[test]
+    fn test_helper_keyword_register_flags() {
+        let kw = dummy_sticky_buffer();
+        sticky_buffer_register(&kw, 0, mock_register).unwrap();
+        assert_eq!(
+            registered_flags(),
+            SIGMATCH_NOOPT | SIGMATCH_INFO_STICKY_BUFFER
+        );
+
+        sticky_buffer_register(&kw, SIGMATCH_OPTIONAL_OPT, mock_register).unwrap();
+        assert_eq!(
+            registered_flags(),
+            SIGMATCH_OPTIONAL_OPT | SIGMATCH_INFO_STICKY_BUFFER
+        );
+
+        sticky_buffer_register(&kw, SIGMATCH_QUOTES_MANDATORY, mock_register).unwrap();
+        assert_eq!(
+            registered_flags(),
+            SIGMATCH_QUOTES_MANDATORY | SIGMATCH_INFO_STICKY_BUFFER
+        );
+
+        let mut kw = dummy_tx_match_kw();
+        kw.quotes_mandatory = false;
+        tx_match_register(&kw, 0, mock_register).unwrap();
+        assert_eq!(registered_flags(), 0);
+        tx_match_register(&kw, SIGMATCH_OPTIONAL_OPT, mock_register).unwrap();
+        assert_eq!(registered_flags(), SIGMATCH_OPTIONAL_OPT);
+        kw.quotes_mandatory = true;
+        tx_match_register(&kw, SIGMATCH_OPTIONAL_OPT, mock_register).unwrap();
+        assert_eq!(
+            registered_flags(),
+            SIGMATCH_OPTIONAL_OPT | SIGMATCH_QUOTES_MANDATORY
+        );
+    }
+
+    #[test]
+    fn test_get_string_value() {
+        assert_eq!(get_string_value("hex"), Ok(ByteBase::BaseHex));
+        assert_eq!(get_string_value("oct"), Ok(ByteBase::BaseOct));
+        assert_eq!(get_string_value("dec"), Ok(ByteBase::BaseDec));
+        assert_eq!(
+            get_string_value("HEX"),
+            Err("invalid string value: HEX, expected hex, oct or dec".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_endian_value() {
+        assert_eq!(get_endian_value("big"), Ok(ByteEndian::BigEndian));
+        assert_eq!(get_endian_value("little"), Ok(ByteEndian::LittleEndian));
+        assert_eq!(get_endian_value("dce"), Ok(ByteEndian::EndianDCE));
+        assert_eq!(
+            get_endian_value("middle"),
+            Err("invalid endian value: middle, expected big, little or dce".to_string())
+        );
+    }
+
+    #[test]
+    fn test_byte_endian_resolve_dce() {
+        let dce = ByteEndian::EndianDCE;
+        assert_eq!(
+            dce.resolve_dce(Some(ByteEndian::BigEndian)),
+            ByteEndian::BigEndian
+        );
+        assert_eq!(
+            dce.resolve_dce(Some(ByteEndian::LittleEndian)),
+            ByteEndian::LittleEndian
+        );
+        assert_eq!(dce.resolve_dce(None), DCE_ENDIAN_DEFAULT);
+        // explicit byte orders do not depend on the flow
+        assert_eq!(
+            ByteEndian::BigEndian.resolve_dce(Some(ByteEndian::LittleEndian)),
+            ByteEndian::BigEndian
+        );
+
+        assert_eq!(SCDetectByteEndianResolve(3, false, true), 1);
+        assert_eq!(SCDetectByteEndianResolve(3, true, false), 2);
+        assert_eq!(SCDetectByteEndianResolve(3, false, false), 2);
+        assert_eq!(SCDetectByteEndianResolve(1, true, false), 1);
+    }
+
     #[test]
     fn test_enum_string_u8() {
         assert_eq!(TestEnum::from_u(0), Some(TestEnum::Zero));
@@ -183,4 +1006,179 @@ mod test {
         assert_eq!(TestEnum::from_str("nope"), None);
         assert_eq!(TestEnum::from_str("best_value_ever"), Some(TestEnum::BestValueEver));
     }
+
+    #[derive(Clone, Debug, PartialEq, EnumStringU16)]
+    #[repr(u16)]
+    pub enum TestEnum16 {
+        Zero = 0,
+        BestValueEver = 42,
+        AboveU8 = 0x1301,
+    }
+
+    #[test]
+    fn test_enum_string_u16() {
+        assert_eq!(TestEnum16::from_u(0), Some(TestEnum16::Zero));
+        assert_eq!(TestEnum16::from_u(1), None);
+        assert_eq!(TestEnum16::from_u(0x1301), Some(TestEnum16::AboveU8));
+        // no truncation to the lower byte
+        assert_eq!(TestEnum16::from_u(0x01), None);
+        assert_eq!(TestEnum16::from_u(0x2a2a), None);
+        assert_eq!(TestEnum16::Zero.into_u(), 0);
+        assert_eq!(TestEnum16::AboveU8.into_u(), 0x1301);
+        assert_eq!(TestEnum16::AboveU8.to_str(), "above_u8");
+        assert_eq!(TestEnum16::from_str("above_u8"), Some(TestEnum16::AboveU8));
+        assert_eq!(TestEnum16::from_str("nope"), None);
+        assert_eq!(TestEnum16::from_str("best_value_ever"), Some(TestEnum16::BestValueEver));
+    }
+
+    #[derive(Clone, Debug, PartialEq, EnumStringU32)]
+    #[repr(u32)]
+    pub enum TestEnum32 {
+        Zero = 0,
+        AboveU16 = 0x10000,
+    }
+
+    #[test]
+    fn test_enum_string_u32() {
+        assert_eq!(TestEnum32::from_u(0x10000), Some(TestEnum32::AboveU16));
+        assert_eq!(TestEnum32::from_u(0), Some(TestEnum32::Zero));
+        assert_eq!(TestEnum32::from_u(1), None);
+        assert_eq!(TestEnum32::AboveU16.into_u(), 0x10000);
+        assert_eq!(TestEnum32::from_str("above_u16"), Some(TestEnum32::AboveU16));
+    }
+
+    // declared out of order, so that the derived lookup table has to sort it
+    #[derive(Clone, Debug, PartialEq, EnumStringU8)]
+    #[repr(u8)]
+    pub enum WideEnum {
+        V0 = 0,
+        V1 = 37,
+        V2 = 74,
+        V3 = 111,
+        V4 = 148,
+        V5 = 185,
+        V6 = 222,
+        V7 = 8,
+        V8 = 45,
+        V9 = 82,
+        V10 = 119,
+        V11 = 156,
+        V12 = 193,
+        V13 = 230,
+        V14 = 16,
+        V15 = 53,
+        V16 = 90,
+        V17 = 127,
+        V18 = 164,
+        V19 = 201,
+        V20 = 238,
+        V21 = 24,
+        V22 = 61,
+        V23 = 98,
+        V24 = 135,
+        V25 = 172,
+        V26 = 209,
+        V27 = 246,
+        V28 = 32,
+        V29 = 69,
+        V30 = 106,
+        V31 = 143,
+        V32 = 180,
+        V33 = 217,
+        V34 = 3,
+        V35 = 40,
+        V36 = 77,
+        V37 = 114,
+        V38 = 151,
+        V39 = 188,
+        V40 = 225,
+        V41 = 11,
+        V42 = 48,
+        V43 = 85,
+        V44 = 122,
+        V45 = 159,
+        V46 = 196,
+        V47 = 233,
+        V48 = 19,
+        V49 = 56,
+        V50 = 93,
+        V51 = 130,
+        V52 = 167,
+        V53 = 204,
+        V54 = 241,
+        V55 = 27,
+        V56 = 64,
+        V57 = 101,
+        V58 = 138,
+        V59 = 175,
+        V60 = 212,
+        V61 = 249,
+        V62 = 35,
+        V63 = 72,
+    }
+
+    #[test]
+    fn test_enum_string_u8_wide() {
+        let mut found = 0;
+        for v in 0..=255 {
+            if let Some(e) = WideEnum::from_u(v) {
+                assert_eq!(e.into_u(), v);
+                found += 1;
+            }
+        }
+        assert_eq!(found, 64);
+        for v in WideEnum::all_variants() {
+            assert_eq!(WideEnum::from_u(v.clone().into_u()).as_ref(), Some(v));
+            assert_eq!(WideEnum::from_str(v.to_str()).as_ref(), Some(v));
+        }
+        assert_eq!(WideEnum::from_u(37), Some(WideEnum::V1));
+        assert_eq!(WideEnum::from_u(250), None);
+    }
+
+    #[test]
+    fn test_enum_string_all_variants() {
+        assert_eq!(TestEnum::all_variants(), &[TestEnum::Zero, TestEnum::BestValueEver]);
+        let names: Vec<&str> = TestEnum::all_variants().iter().map(|v| v.to_str()).collect();
+        assert_eq!(names, ["zero", "best_value_ever"]);
+    }
+
+    #[derive(Clone, Debug, PartialEq, EnumStringU8)]
+    #[repr(u8)]
+    pub enum TestEndian {
+        Big = 1,
+        Little = 2,
+        Dce = 3,
+    }
+
+    #[test]
+    fn test_enum_string_suggest() {
+        assert_eq!(
+            TestEndian::from_str_suggest("little"),
+            Ok(TestEndian::Little)
+        );
+        assert_eq!(TestEndian::from_str_suggest("bigg"), Err(Some("big")));
+        assert_eq!(TestEndian::from_str_suggest("litle"), Err(Some("little")));
+        assert_eq!(TestEndian::from_str_suggest("DCE"), Ok(TestEndian::Dce));
+        // too far from any variant to be a typo
+        assert_eq!(TestEndian::from_str_suggest("foo"), Err(None));
+        assert_eq!(TestEndian::from_str_suggest("network"), Err(None));
+        assert_eq!(
+            TestEnum::from_str_suggest("best_value"),
+            Err(Some("best_value_ever"))
+        );
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "big"), 3);
+        assert_eq!(edit_distance("big", "big"), 0);
+    }
+
+    #[test]
+    fn test_enum_string_ci() {
+        assert_eq!(TestEnum::from_str_ci("BEST_Value_Ever"), Some(TestEnum::BestValueEver));
+        assert_eq!(TestEnum::from_str_ci("ZERO"), Some(TestEnum::Zero));
+        assert_eq!(TestEnum::from_str_ci("Nope"), None);
+        // from_str ignores the case too
+        assert_eq!(TestEnum::from_str("BEST_Value_Ever"), Some(TestEnum::BestValueEver));
+        assert_eq!(TestEnum16::from_str("Above_U8"), Some(TestEnum16::AboveU8));
+        assert_eq!(TestEnum16::from_str_ci("Above_U8"), Some(TestEnum16::AboveU8));
+    }
 }
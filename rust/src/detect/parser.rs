@@ -16,11 +16,16 @@
  */
 
 use crate::detect::error::RuleParseError;
+use crate::detect::uint::{detect_parse_uint_notending, detect_parse_uint_unit, DetectUintData};
 
-use nom7::bytes::complete::is_not;
-use nom7::character::complete::multispace0;
-use nom7::sequence::preceded;
-use nom7::IResult;
+use nom7::branch::alt;
+use nom7::bytes::complete::{is_not, tag};
+use nom7::character::complete::{char, digit1, multispace0};
+use nom7::combinator::{map_opt, not, opt, recognize};
+use nom7::error::{make_error, ErrorKind, ParseError};
+use nom7::multi::many0;
+use nom7::sequence::{pair, preceded};
+use nom7::{Err, IResult};
 
 #[derive(Debug)]
 pub enum ResultValue {
@@ -39,6 +44,8 @@ pub fn parse_var(input: &str) -> IResult<&str, ResultValue, RuleParseError<&str>
     let (input, value) = parse_token(input)?;
     if let Ok(val) = value.parse::<u64>() {
         Ok((input, ResultValue::Numeric(val)))
+    } else if let Ok(("", val)) = parse_number::<RuleParseError<&str>>(value) {
+        Ok((input, ResultValue::Numeric(val)))
     } else {
         Ok((input, ResultValue::String(value.to_string())))
     }
@@ -51,3 +58,199 @@ pub fn parse_token(input: &str) -> IResult<&str, &str, RuleParseError<&str>> {
     let terminators = "\n\r\t,;: ";
     preceded(multispace0, is_not(terminators))(input)
 }
+
+/// Parse decimal digits, which may be grouped with underscores as in
+/// `1_000_000`, and return them without the separators.
+///
+/// Leading, trailing and doubled underscores are rejected.
+pub fn parse_digits<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, String, E> {
+    let (input, digits) = recognize(pair(digit1, many0(pair(char('_'), digit1))))(input)?;
+    let (input, _) = not(char('_'))(input)?;
+    Ok((input, digits.replace('_', "")))
+}
+
+/// Parse an unsigned decimal number, with an optional size suffix.
+///
+/// `10kb` is expanded to 10240, a value overflowing a u64 once expanded
+/// is an error.
+pub fn parse_number<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, u64, E> {
+    let (rem, val) = map_opt(parse_digits, |s: String| s.parse::<u64>().ok())(input)?;
+    let (rem, unit) = opt(detect_parse_uint_unit)(rem)?;
+    match unit {
+        Some(unit) => match val.checked_mul(unit) {
+            Some(val) => Ok((rem, val)),
+            None => Err(Err::Error(make_error(input, ErrorKind::Verify))),
+        },
+        None => Ok((rem, val)),
+    }
+}
+
+/// Parse an optional comparison operator followed by a number, or a range.
+///
+/// This is the `[op]<number>` and `[!]<min>-<max>` syntax shared by integer
+/// keywords, parsed by `detect_parse_uint`, and followed by the rest of the
+/// input. Surrounding whitespace is skipped.
+pub fn parse_uint_comparison(
+    input: &str,
+) -> IResult<&str, DetectUintData<u64>, RuleParseError<&str>> {
+    let (input, _) = multispace0(input)?;
+    let (input, du64) =
+        detect_parse_uint_notending(input).map_err(|e| e.map(|e| make_error(e.input, e.code)))?;
+    // an incomplete range is not a plain number
+    let (input, _) = not(preceded(multispace0, alt((tag("-"), tag("<>")))))(input)?;
+    let (input, _) = multispace0(input)?;
+    Ok((input, du64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::uint::{detect_parse_uint, DetectUintMode};
+
+    fn comparison(mode: DetectUintMode, arg1: u64, arg2: u64) -> DetectUintData<u64> {
+        DetectUintData { arg1, arg2, mode }
+    }
+
+    fn number(input: &str) -> IResult<&str, u64, RuleParseError<&str>> {
+        parse_number(input)
+    }
+
+    #[test]
+    fn test_parse_number_separators() {
+        assert_eq!(number("1000000"), Ok(("", 1000000)));
+        assert_eq!(number("1_000_000"), Ok(("", 1000000)));
+        assert_eq!(number("10_00"), Ok(("", 1000)));
+        assert_eq!(number("1_2_3"), Ok(("", 123)));
+        assert_eq!(number("1_000,"), Ok((",", 1000)));
+        for input in ["_1000", "1000_", "1__000", "1_000_", "1_,"] {
+            assert!(number(input).is_err(), "{}", input);
+        }
+        assert_eq!(
+            detect_parse_uint::<u64>(">1_000_000"),
+            Ok(("", comparison(DetectUintMode::DetectUintModeGt, 1000000, 0)))
+        );
+        assert!(matches!(
+            parse_var("1_000"),
+            Ok(("", ResultValue::Numeric(1000)))
+        ));
+        assert!(matches!(
+            parse_var("_1000"),
+            Ok(("", ResultValue::String(_)))
+        ));
+    }
+
+    #[test]
+    fn test_parse_number_suffix() {
+        assert_eq!(number("10"), Ok(("", 10)));
+        assert_eq!(number("10kb"), Ok(("", 10 * 1024)));
+        assert_eq!(number("10KB"), Ok(("", 10 * 1024)));
+        assert_eq!(number("10mb"), Ok(("", 10 * 1024 * 1024)));
+        assert_eq!(number("10Mb"), Ok(("", 10 * 1024 * 1024)));
+        assert_eq!(number("10gb"), Ok(("", 10 * 1024 * 1024 * 1024)));
+        assert_eq!(number("18gb"), Ok(("", 18 * 1024 * 1024 * 1024)));
+        assert_eq!(number("1_000kb"), Ok(("", 1000 * 1024)));
+        assert_eq!(number("0gb"), Ok(("", 0)));
+        assert_eq!(number("10tb"), Ok(("tb", 10)));
+        // 2^34 gb is 2^64
+        assert_eq!(number("17179869183gb"), Ok(("", u64::MAX - (1 << 30) + 1)));
+        assert!(number("17179869184gb").is_err());
+        assert!(number("18014398509481984kb").is_err());
+        assert_eq!(
+            detect_parse_uint::<u64>("> 10mb"),
+            Ok((
+                "",
+                comparison(DetectUintMode::DetectUintModeGt, 10 * 1024 * 1024, 0)
+            ))
+        );
+        assert_eq!(
+            detect_parse_uint::<u64>("1kb-2kb"),
+            Ok((
+                "",
+                comparison(DetectUintMode::DetectUintModeRange, 1024, 2048)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_uint_comparison_operators() {
+        let cases = [
+            ("10", DetectUintMode::DetectUintModeEqual),
+            ("=10", DetectUintMode::DetectUintModeEqual),
+            ("<10", DetectUintMode::DetectUintModeLt),
+            (">10", DetectUintMode::DetectUintModeGt),
+            ("<=10", DetectUintMode::DetectUintModeLte),
+            (">=10", DetectUintMode::DetectUintModeGte),
+            ("!=10", DetectUintMode::DetectUintModeNe),
+            ("!10", DetectUintMode::DetectUintModeNe),
+            (" >= 10 ", DetectUintMode::DetectUintModeGte),
+            ("\t< 10\t", DetectUintMode::DetectUintModeLt),
+        ];
+        for (input, mode) in cases {
+            assert_eq!(
+                parse_uint_comparison(input),
+                Ok(("", comparison(mode, 10, 0))),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_uint_comparison_ranges() {
+        for input in ["1-10", "1<>10", " 1 - 10 ", "1 <> 10"] {
+            assert_eq!(
+                parse_uint_comparison(input),
+                Ok(("", comparison(DetectUintMode::DetectUintModeRange, 1, 10))),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            parse_uint_comparison("!1-10"),
+            Ok(("", comparison(DetectUintMode::DetectUintModeNegRg, 1, 10)))
+        );
+        assert_eq!(
+            parse_uint_comparison("0-2"),
+            Ok(("", comparison(DetectUintMode::DetectUintModeRange, 0, 2)))
+        );
+    }
+
+    #[test]
+    fn test_parse_uint_comparison_rest() {
+        assert_eq!(
+            parse_uint_comparison(" >5 , foo"),
+            Ok((", foo", comparison(DetectUintMode::DetectUintModeGt, 5, 0)))
+        );
+    }
+
+    #[test]
+    fn test_parse_uint_comparison_invalid() {
+        for input in [
+            "",
+            " ",
+            "<>",
+            "1<>",
+            "<>10",
+            "-",
+            "1-",
+            "-10",
+            "<",
+            ">=",
+            "!",
+            "=<10",
+            "a",
+            "<a",
+            // empty ranges
+            "10-10",
+            "10-11",
+            "10<>5",
+            // comparisons that never match
+            "<0",
+            ">18446744073709551615",
+            // does not fit in a u64
+            "18446744073709551616",
+        ] {
+            assert!(parse_uint_comparison(input).is_err(), "{}", input);
+        }
+    }
+}
@@ -29,7 +29,7 @@ use nom7::sequence::tuple;
 use nom7::{
     branch::alt,
     bytes::complete::{tag, take_till},
-    character::complete::{char, multispace0},
+    character::complete::{char, digit1, multispace0},
     combinator::map_res,
     sequence::preceded,
     IResult,
@@ -52,6 +52,12 @@ enum RequiresError {
     /// The requires expression is bad and failed to parse.
     BadRequires,
 
+    /// The version expression failed to parse at the given token.
+    BadVersionToken(String),
+
+    /// The and/or expression failed to parse at the given token.
+    BadExpression(String),
+
     /// MultipleVersions
     MultipleVersions,
 
@@ -63,6 +69,12 @@ enum RequiresError {
 
     /// Suricata does not have support for a required keyword.
     MissingKeyword(String),
+
+    /// The dataset format version does not match the required one.
+    DatasetFormat,
+
+    /// The dataset format expression failed to parse.
+    BadDatasetFormat(String),
 }
 
 impl RequiresError {
@@ -74,10 +86,16 @@ impl RequiresError {
             Self::MissingFeature(_) => "Suricata missing a required feature\0",
             Self::BadSuricataVersion => "Failed to parse running Suricata version\0",
             Self::BadRequires => "Failed to parse requires expression\0",
+            Self::BadVersionToken(_) => "Failed to parse requires version expression\0",
+            Self::BadExpression(_) => {
+                "Failed to parse requires expression, 'and' and ',' bind tighter than 'or', use parentheses to group\0"
+            }
             Self::MultipleVersions => "Version may only be specified once\0",
             Self::Utf8Error => "Requires expression is not valid UTF-8\0",
             Self::UnknownRequirement(_) => "Unknown requirements\0",
             Self::MissingKeyword(_) => "Suricata missing a required keyword\0",
+            Self::DatasetFormat => "Dataset format version does not match required\0",
+            Self::BadDatasetFormat(_) => "Failed to parse requires dataset-format expression\0",
         };
         msg.as_ptr() as *const c_char
     }
@@ -132,34 +150,71 @@ impl SuricataVersion {
     }
 }
 
+/// Parse a version range, `7.0.3 .. 8` being the same as `>= 7.0.3 < 8`.
+fn parse_version_range(input: &str) -> IResult<&str, Vec<RuleRequireVersion>> {
+    let (input, (min, _, max)) = tuple((
+        parse_version,
+        preceded(multispace0, tag("..")),
+        parse_version,
+    ))(input)?;
+    Ok((
+        input,
+        vec![
+            RuleRequireVersion {
+                op: VersionCompareOp::Gte,
+                version: min,
+            },
+            RuleRequireVersion {
+                op: VersionCompareOp::Lt,
+                version: max,
+            },
+        ],
+    ))
+}
+
+/// Parse a single comparison, or a range.
+fn parse_version_term(input: &str) -> IResult<&str, Vec<RuleRequireVersion>> {
+    alt((
+        map(tuple((parse_op, parse_version)), |(op, version)| {
+            vec![RuleRequireVersion { op, version }]
+        }),
+        parse_version_range,
+    ))(input)
+}
+
 /// Parse a version expression.
 ///
 /// Parse into a version expression into a nested array, for example:
 ///
-///    version: >= 7.0.3 < 8 | >= 8.0.3
+///    version: >= 7.0.3 < 8 | 8.0.3 .. 9
 ///
 /// would result in something like:
 ///
 /// [
 ///     [{op: gte, version: 7.0.3}, {op:lt, version: 8}],
-///     [{op: gte, version: 8.0.3}],
+///     [{op: gte, version: 8.0.3}, {op:lt, version: 9}],
 /// ]
 fn parse_version_expression(input: &str) -> IResult<&str, Vec<Vec<RuleRequireVersion>>> {
     let sep = preceded(multispace0, tag("|"));
-    let inner_parser = many1(tuple((parse_op, parse_version)));
-    let (input, versions) = separated_list1(sep, inner_parser)(input)?;
-
-    let versions = versions
-        .into_iter()
-        .map(|versions| {
-            versions
-                .into_iter()
-                .map(|(op, version)| RuleRequireVersion { op, version })
-                .collect()
-        })
-        .collect();
+    let inner_parser = map(many1(parse_version_term), |terms| {
+        terms.into_iter().flatten().collect()
+    });
+    separated_list1(sep, inner_parser)(input)
+}
 
-    Ok((input, versions))
+/// Parse the value of a `version` requirement, which must be a version
+/// expression and nothing else. On error, the input that could not be parsed
+/// is returned.
+fn parse_version_requires(input: &str) -> Result<Vec<Vec<RuleRequireVersion>>, RequiresError> {
+    let (rest, versions) = match parse_version_expression(input) {
+        Ok(ok) => ok,
+        Err(_) => (input, vec![]),
+    };
+    let rest = rest.trim();
+    if versions.is_empty() || !rest.is_empty() {
+        return Err(RequiresError::BadVersionToken(rest.to_string()));
+    }
+    Ok(versions)
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -168,6 +223,31 @@ struct RuleRequireVersion {
     pub version: SuricataVersion,
 }
 
+/// A comparison against the dataset format version.
+#[derive(Debug, Eq, PartialEq)]
+struct RuleRequireDatasetFormat {
+    pub op: VersionCompareOp,
+    pub version: u32,
+}
+
+/// Parse the value of a `dataset-format` requirement, one or more
+/// comparisons like `>= 2 < 4` that must all be true.
+fn parse_dataset_format_requires(
+    input: &str,
+) -> Result<Vec<RuleRequireDatasetFormat>, RequiresError> {
+    let term = map(
+        tuple((
+            parse_op,
+            preceded(multispace0, map_res(digit1, |s: &str| s.parse::<u32>())),
+        )),
+        |(op, version)| RuleRequireDatasetFormat { op, version },
+    );
+    match many1(term)(input) {
+        Ok((rest, formats)) if rest.trim().is_empty() => Ok(formats),
+        _ => Err(RequiresError::BadDatasetFormat(input.trim().to_string())),
+    }
+}
+
 #[derive(Debug, Default, Eq, PartialEq)]
 struct Requires {
     /// Features required to be enabled.
@@ -182,6 +262,9 @@ struct Requires {
     /// - To pass, any of the outer must be true.
     pub version: Vec<Vec<RuleRequireVersion>>,
 
+    /// Comparisons the dataset format version must all satisfy.
+    pub dataset_format: Vec<RuleRequireDatasetFormat>,
+
     /// Unknown parameters to requires.
     pub unknown: Vec<String>,
 }
@@ -208,15 +291,20 @@ fn parse_next_version_part(input: &str) -> IResult<&str, u8> {
     )(input)
 }
 
+/// Check if the version string ends here, with minor or patch omitted.
+fn is_version_end(input: &str) -> bool {
+    input.is_empty() || input.starts_with(' ') || input.starts_with("..")
+}
+
 /// Parse a version string into a SuricataVersion.
 fn parse_version(input: &str) -> IResult<&str, SuricataVersion> {
     let (input, major) = preceded(multispace0, parse_next_version_part)(input)?;
-    let (input, minor) = if input.is_empty() || input.starts_with(' ') {
+    let (input, minor) = if is_version_end(input) {
         (input, 0)
     } else {
         preceded(char('.'), parse_next_version_part)(input)?
     };
-    let (input, patch) = if input.is_empty() || input.starts_with(' ') {
+    let (input, patch) = if is_version_end(input) {
         (input, 0)
     } else {
         preceded(char('.'), parse_next_version_part)(input)?
@@ -225,49 +313,195 @@ fn parse_version(input: &str) -> IResult<&str, SuricataVersion> {
     Ok((input, SuricataVersion::new(major, minor, patch)))
 }
 
+/// Check if `input` starts with the operator `word`, and return what follows
+/// it.
+fn strip_operator<'a>(input: &'a str, word: &str) -> Option<&'a str> {
+    let rest = input.strip_prefix(word)?;
+    if rest.is_empty() || rest.starts_with(|c: char| c.is_whitespace() || c == '(') {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+/// Parse a single requirement, the keyword and its value. The value ends at
+/// a ',', a ')' or an `and` or `or` operator.
 fn parse_key_value(input: &str) -> IResult<&str, (&str, &str)> {
     // Parse the keyword, any sequence of characters, numbers or "-" or "_".
     let (input, key) = preceded(
         multispace0,
         take_while(|c: char| c.is_alphanumeric() || c == '-' || c == '_'),
     )(input)?;
-    let (input, value) = preceded(multispace0, take_till(|c: char| c == ','))(input)?;
-    Ok((input, (key, value)))
+    let (input, _) = multispace0(input)?;
+    let mut end = input.len();
+    let mut word_start = true;
+    for (i, c) in input.char_indices() {
+        if c == ',' || c == ')' {
+            end = i;
+            break;
+        }
+        if word_start
+            && (strip_operator(&input[i..], "and").is_some()
+                || strip_operator(&input[i..], "or").is_some())
+        {
+            end = i;
+            break;
+        }
+        word_start = c.is_whitespace();
+    }
+    Ok((&input[end..], (key, input[..end].trim_end())))
 }
 
-fn parse_requires(mut input: &str) -> Result<Requires, RequiresError> {
-    let mut requires = Requires::default();
+/// A requires expression.
+#[derive(Debug, Eq, PartialEq)]
+enum RequiresExpr {
+    /// Requirements joined with ',' or `and`, all must be met.
+    All(Requires),
 
-    while !input.is_empty() {
-        let (rest, (keyword, value)) =
-            parse_key_value(input).map_err(|_| RequiresError::BadRequires)?;
-        match keyword {
-            "feature" => {
-                requires.features.push(value.trim().to_string());
-            }
-            "version" => {
-                if !requires.version.is_empty() {
-                    return Err(RequiresError::MultipleVersions);
-                }
-                let (_, versions) =
-                    parse_version_expression(value).map_err(|_| RequiresError::BadRequires)?;
-                requires.version = versions;
-            }
-            "keyword" => {
-                requires.keywords.push(value.trim().to_string());
+    /// Sub-expressions that must all be met.
+    And(Vec<RequiresExpr>),
+
+    /// Sub-expressions of which at least one must be met.
+    Or(Vec<RequiresExpr>),
+}
+
+fn add_requirement(
+    requires: &mut Requires, keyword: &str, value: &str,
+) -> Result<(), RequiresError> {
+    match keyword {
+        "feature" => {
+            requires.features.push(value.trim().to_string());
+        }
+        "version" => {
+            if !requires.version.is_empty() {
+                return Err(RequiresError::MultipleVersions);
             }
-            _ => {
-                // Unknown keyword, allow by warn in case we extend
-                // this in the future.
-                SCLogWarning!("Unknown requires keyword: {}", keyword);
-                requires.unknown.push(format!("{} {}", keyword, value));
+            requires.version = parse_version_requires(value)?;
+        }
+        "keyword" => {
+            requires.keywords.push(value.trim().to_string());
+        }
+        "dataset-format" => {
+            requires
+                .dataset_format
+                .extend(parse_dataset_format_requires(value)?);
+        }
+        "" | "and" | "or" => {
+            return Err(RequiresError::BadExpression(keyword.to_string()));
+        }
+        _ => {
+            // Unknown keyword, allow by warn in case we extend
+            // this in the future.
+            SCLogWarning!("Unknown requires keyword: {}", keyword);
+            requires.unknown.push(format!("{} {}", keyword, value));
+        }
+    }
+    Ok(())
+}
+
+/// Return the first token of `input` for error messages.
+fn first_token(input: &str) -> String {
+    let input = input.trim_start();
+    match input.chars().next() {
+        Some(c @ ('(' | ')' | ',')) => c.to_string(),
+        _ => input
+            .split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == ',')
+            .next()
+            .unwrap_or("")
+            .to_string(),
+    }
+}
+
+/// Parse requirements and parenthesized expressions joined with ',' or
+/// `and`.
+fn parse_requires_and(mut input: &str) -> Result<(&str, RequiresExpr), RequiresError> {
+    let mut requires = Requires::default();
+    let mut has_requires = false;
+    let mut exprs = vec![];
+
+    loop {
+        let rest = input.trim_start();
+        if let Some(rest) = rest.strip_prefix('(') {
+            let (rest, expr) = parse_requires_or(rest)?;
+            let rest = rest.trim_start();
+            input = match rest.strip_prefix(')') {
+                Some(rest) => rest,
+                None => return Err(RequiresError::BadExpression(first_token(rest))),
+            };
+            exprs.push(expr);
+        } else {
+            let (rest, (keyword, value)) =
+                parse_key_value(rest).map_err(|_| RequiresError::BadRequires)?;
+            add_requirement(&mut requires, keyword, value).map_err(|err| match err {
+                RequiresError::BadExpression(_) => RequiresError::BadExpression(first_token(input)),
+                err => err,
+            })?;
+            has_requires = true;
+            input = rest;
+        }
+
+        let rest = input.trim_start();
+        if let Some(rest) = rest.strip_prefix(',') {
+            // Allow a trailing ','.
+            if rest.trim().is_empty() {
+                input = rest;
+                break;
             }
+            input = rest;
+        } else if let Some(rest) = strip_operator(rest, "and") {
+            input = rest;
+        } else {
+            break;
         }
+    }
+
+    if exprs.is_empty() {
+        return Ok((input, RequiresExpr::All(requires)));
+    }
+    if has_requires {
+        exprs.push(RequiresExpr::All(requires));
+    }
+    if exprs.len() == 1 {
+        return Ok((input, exprs.pop().unwrap()));
+    }
+    Ok((input, RequiresExpr::And(exprs)))
+}
+
+/// Parse expressions joined with `or`. As `and` binds tighter than `or`,
+/// `a or b and c` is the same as `a or (b and c)`.
+fn parse_requires_or(input: &str) -> Result<(&str, RequiresExpr), RequiresError> {
+    let (mut input, expr) = parse_requires_and(input)?;
+    let mut exprs = vec![expr];
+    while let Some(rest) = strip_operator(input.trim_start(), "or") {
+        let (rest, expr) = parse_requires_and(rest)?;
+        exprs.push(expr);
+        input = rest;
+    }
+    if exprs.len() == 1 {
+        return Ok((input, exprs.pop().unwrap()));
+    }
+    Ok((input, RequiresExpr::Or(exprs)))
+}
+
+/// Parse a full requires expression.
+fn parse_requires_expr(input: &str) -> Result<RequiresExpr, RequiresError> {
+    if input.trim().is_empty() {
+        return Ok(RequiresExpr::All(Requires::default()));
+    }
+    let (rest, expr) = parse_requires_or(input)?;
+    if !rest.trim().is_empty() {
+        return Err(RequiresError::BadExpression(first_token(rest)));
+    }
+    Ok(expr)
+}
 
-        // No consume any remaining ',' or whitespace.
-        input = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+/// Parse a requires expression that only uses ',' or `and`.
+#[cfg(test)]
+fn parse_requires(input: &str) -> Result<Requires, RequiresError> {
+    match parse_requires_expr(input)? {
+        RequiresExpr::All(requires) => Ok(requires),
+        _ => Err(RequiresError::BadRequires),
     }
-    Ok(requires)
 }
 
 fn parse_suricata_version(version: &CStr) -> Result<SuricataVersion, *const c_char> {
@@ -350,9 +584,57 @@ fn check_requires(
         }
     }
 
+    check_dataset_format(
+        &requires.dataset_format,
+        crate::detect::datasets::DATASET_FORMAT_VERSION,
+    )
+}
+
+fn check_dataset_format(
+    formats: &[RuleRequireDatasetFormat], dataset_format: u32,
+) -> Result<(), RequiresError> {
+    for format in formats {
+        let ok = match format.op {
+            VersionCompareOp::Gt => dataset_format > format.version,
+            VersionCompareOp::Gte => dataset_format >= format.version,
+            VersionCompareOp::Lt => dataset_format < format.version,
+            VersionCompareOp::Lte => dataset_format <= format.version,
+        };
+        if !ok {
+            return Err(RequiresError::DatasetFormat);
+        }
+    }
     Ok(())
 }
 
+fn check_requires_expr(
+    expr: &RequiresExpr, suricata_version: &SuricataVersion,
+) -> Result<(), RequiresError> {
+    match expr {
+        RequiresExpr::All(requires) => check_requires(requires, suricata_version),
+        RequiresExpr::And(exprs) => {
+            for expr in exprs {
+                check_requires_expr(expr, suricata_version)?;
+            }
+            Ok(())
+        }
+        RequiresExpr::Or(exprs) => {
+            let mut first_err = None;
+            for expr in exprs {
+                match check_requires_expr(expr, suricata_version) {
+                    Ok(()) => return Ok(()),
+                    Err(err) => {
+                        if first_err.is_none() {
+                            first_err = Some(err);
+                        }
+                    }
+                }
+            }
+            Err(first_err.unwrap_or(RequiresError::BadRequires))
+        }
+    }
+}
+
 /// Status object to hold required features and the latest version of
 /// Suricata required.
 ///
@@ -365,6 +647,14 @@ pub struct SCDetectRequiresStatus {
     /// Number of rules that didn't meet a feature.
     feature_count: u64,
 
+    keywords: HashSet<String>,
+
+    /// Number of rules that required a keyword that is not available.
+    keyword_count: u64,
+
+    /// Number of rules that required another dataset format version.
+    dataset_format_count: u64,
+
     /// Number of rules where the Suricata version wasn't new enough.
     lt_count: u64,
 
@@ -449,12 +739,55 @@ pub unsafe extern "C" fn SCDetectRequiresStatusLog(
         );
         parts.push(msg);
     }
+    if status.keyword_count > 0 {
+        let mut keywords = status.keywords.iter().cloned().collect::<Vec<String>>();
+        keywords.sort();
+        let msg = format!(
+            "{}{} {} skipped because the running Suricata version does not have keyword{}: [{}]",
+            if tenant_id > 0 {
+                format!("tenant id: {}  ", tenant_id)
+            } else {
+                String::new()
+            },
+            status.keyword_count,
+            if status.keyword_count > 1 {
+                "rules were"
+            } else {
+                "rule was"
+            },
+            if status.keywords.len() > 1 { "s" } else { "" },
+            keywords.join(", ")
+        );
+        parts.push(msg);
+    }
+    if status.dataset_format_count > 0 {
+        let msg = format!(
+            "{}{} {} skipped because the running Suricata dataset format version {} does not match",
+            if tenant_id > 0 {
+                format!("tenant id: {}  ", tenant_id)
+            } else {
+                String::new()
+            },
+            status.dataset_format_count,
+            if status.dataset_format_count > 1 {
+                "rules were"
+            } else {
+                "rule was"
+            },
+            crate::detect::datasets::DATASET_FORMAT_VERSION
+        );
+        parts.push(msg);
+    }
 
     let msg = parts.join("; ");
 
     if status.lt_count > 0 {
         SCLogNotice!("{}", &msg);
-    } else if status.gt_count > 0 || status.feature_count > 0 {
+    } else if status.gt_count > 0
+        || status.feature_count > 0
+        || status.keyword_count > 0
+        || status.dataset_format_count > 0
+    {
         SCLogInfo!("{}", &msg);
     }
 }
@@ -484,16 +817,34 @@ pub unsafe extern "C" fn SCDetectCheckRequires(
     let requires = match CStr::from_ptr(requires)
         .to_str()
         .map_err(|_| RequiresError::Utf8Error)
-        .and_then(parse_requires)
+        .and_then(parse_requires_expr)
     {
         Ok(requires) => requires,
         Err(err) => {
+            if let RequiresError::BadVersionToken(rest) = &err {
+                if rest.is_empty() {
+                    SCLogError!("requires: version expression is incomplete");
+                } else {
+                    SCLogError!(
+                        "requires: failed to parse version expression at \"{}\"",
+                        rest
+                    );
+                }
+            } else if let RequiresError::BadDatasetFormat(value) = &err {
+                SCLogError!("requires: bad dataset-format expression \"{}\"", value);
+            } else if let RequiresError::BadExpression(token) = &err {
+                if token.is_empty() {
+                    SCLogError!("requires: expression is incomplete");
+                } else {
+                    SCLogError!("requires: unexpected token \"{}\" in expression", token);
+                }
+            }
             *errstr = err.c_errmsg();
             return -1;
         }
     };
 
-    match check_requires(&requires, &suricata_version) {
+    match check_requires_expr(&requires, &suricata_version) {
         Ok(()) => 0,
         Err(err) => {
             match &err {
@@ -514,6 +865,13 @@ pub unsafe extern "C" fn SCDetectCheckRequires(
                 RequiresError::VersionGt => {
                     status.gt_count += 1;
                 }
+                RequiresError::MissingKeyword(keyword) => {
+                    status.keywords.insert(keyword.to_string());
+                    status.keyword_count += 1;
+                }
+                RequiresError::DatasetFormat => {
+                    status.dataset_format_count += 1;
+                }
                 _ => {}
             }
             *errstr = err.c_errmsg();
@@ -626,6 +984,7 @@ mod test {
                         patch: 0,
                     }
                 }]],
+                dataset_format: vec![],
                 unknown: vec![],
             }
         );
@@ -644,6 +1003,7 @@ mod test {
                         patch: 0,
                     }
                 }]],
+                dataset_format: vec![],
                 unknown: vec![],
             }
         );
@@ -662,6 +1022,7 @@ mod test {
                         patch: 2,
                     }
                 }]],
+                dataset_format: vec![],
                 unknown: vec![],
             }
         );
@@ -690,6 +1051,7 @@ mod test {
                         }
                     }
                 ]],
+                dataset_format: vec![],
                 unknown: vec![],
             }
         );
@@ -801,6 +1163,7 @@ mod test {
                         patch: 3,
                     }
                 }]],
+                dataset_format: vec![],
                 unknown: vec!["foo bar".to_string()],
             }
         );
@@ -849,12 +1212,302 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_parse_version_range() {
+        let requires = parse_requires("version 7.0.3 .. 8").unwrap();
+        assert_eq!(
+            requires.version,
+            vec![vec![
+                RuleRequireVersion {
+                    op: VersionCompareOp::Gte,
+                    version: SuricataVersion::new(7, 0, 3),
+                },
+                RuleRequireVersion {
+                    op: VersionCompareOp::Lt,
+                    version: SuricataVersion::new(8, 0, 0),
+                },
+            ]]
+        );
+        // no spaces, and omitted minor and patch
+        assert_eq!(
+            parse_requires("version 7..8").unwrap().version,
+            parse_requires("version >= 7 < 8").unwrap().version
+        );
+        assert_eq!(
+            parse_requires("version 7.0.3..8 | >= 8.0.3")
+                .unwrap()
+                .version,
+            parse_requires("version >= 7.0.3 < 8 | >= 8.0.3")
+                .unwrap()
+                .version
+        );
+    }
+
+    #[test]
+    fn test_check_version_range() {
+        let requires = parse_requires("version 7.0.3 .. 8.0.0").unwrap();
+        assert!(check_requires(&requires, &SuricataVersion::new(7, 0, 2)).is_err());
+        assert!(check_requires(&requires, &SuricataVersion::new(7, 0, 3)).is_ok());
+        assert!(check_requires(&requires, &SuricataVersion::new(7, 9, 9)).is_ok());
+        assert_eq!(
+            check_requires(&requires, &SuricataVersion::new(8, 0, 0)),
+            Err(RequiresError::VersionGt)
+        );
+
+        // a range further restricted with a comparison
+        let requires = parse_requires("version 7 .. 9 >= 8.0.4").unwrap();
+        assert!(check_requires(&requires, &SuricataVersion::new(7, 0, 5)).is_err());
+        assert!(check_requires(&requires, &SuricataVersion::new(8, 0, 4)).is_ok());
+        assert!(check_requires(&requires, &SuricataVersion::new(9, 0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_check_version_prerelease() {
+        // A pre-release of Suricata compares as the release itself.
+        let suricata_version =
+            parse_suricata_version(CStr::from_bytes_with_nul(b"8.0.0-dev\0").unwrap()).unwrap();
+        assert_eq!(suricata_version, SuricataVersion::new(8, 0, 0));
+        let requires = parse_requires("version >= 8").unwrap();
+        assert!(check_requires(&requires, &suricata_version).is_ok());
+        let requires = parse_requires("version 7.0.3 .. 8").unwrap();
+        assert_eq!(
+            check_requires(&requires, &suricata_version),
+            Err(RequiresError::VersionGt)
+        );
+
+        // but rules can't require a pre-release
+        assert_eq!(
+            parse_requires("version >= 8.0.0-dev"),
+            Err(RequiresError::BadVersionToken("-dev".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_version_bad_token() {
+        for (expr, token) in [
+            ("version >= 7 foo", "foo"),
+            ("version >= 7 < 8 |", "|"),
+            ("version >= 7 | | < 8", "| | < 8"),
+            ("version == 7", "== 7"),
+            ("version 7.0.3 ..", "7.0.3 .."),
+            ("version >= 7.a", ">= 7.a"),
+            ("version >= 7 foo < 8", "foo < 8"),
+            ("version", ""),
+        ] {
+            assert_eq!(
+                parse_requires(expr),
+                Err(RequiresError::BadVersionToken(token.to_string())),
+                "{}",
+                expr
+            );
+        }
+    }
+
+    fn check_expr(expr: &str, version: &SuricataVersion) -> Result<(), RequiresError> {
+        check_requires_expr(&parse_requires_expr(expr).unwrap(), version)
+    }
+
+    #[test]
+    fn test_parse_requires_or() {
+        let expr = parse_requires_expr("version >= 7.0 or feature geoip").unwrap();
+        assert_eq!(
+            expr,
+            RequiresExpr::Or(vec![
+                RequiresExpr::All(parse_requires("version >= 7.0").unwrap()),
+                RequiresExpr::All(parse_requires("feature geoip").unwrap()),
+            ])
+        );
+
+        // 'and' binds tighter than 'or'
+        let expr = parse_requires_expr("feature a or feature b and feature c").unwrap();
+        assert_eq!(
+            expr,
+            RequiresExpr::Or(vec![
+                RequiresExpr::All(parse_requires("feature a").unwrap()),
+                RequiresExpr::All(parse_requires("feature b, feature c").unwrap()),
+            ])
+        );
+
+        let expr = parse_requires_expr("(feature a or feature b) and feature c").unwrap();
+        assert_eq!(
+            expr,
+            RequiresExpr::And(vec![
+                RequiresExpr::Or(vec![
+                    RequiresExpr::All(parse_requires("feature a").unwrap()),
+                    RequiresExpr::All(parse_requires("feature b").unwrap()),
+                ]),
+                RequiresExpr::All(parse_requires("feature c").unwrap()),
+            ])
+        );
+
+        // 'and' and ',' are the same
+        assert_eq!(
+            parse_requires("feature a and keyword b, version >= 8").unwrap(),
+            parse_requires("feature a, keyword b, version >= 8").unwrap()
+        );
+        // operators are words
+        assert_eq!(
+            parse_requires("feature orange").unwrap().features,
+            vec!["orange".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_check_requires_or() {
+        let v7 = SuricataVersion::new(7, 0, 3);
+        let v8 = SuricataVersion::new(8, 0, 0);
+
+        assert!(check_expr("version >= 8 or feature true_geoip", &v7).is_ok());
+        assert!(check_expr("version >= 8 or feature geoip", &v8).is_ok());
+        assert_eq!(
+            check_expr("version >= 8 or feature geoip", &v7),
+            Err(RequiresError::VersionLt(SuricataVersion::new(8, 0, 0)))
+        );
+
+        // mixed: feature geoip or (feature true_lua and version >= 8)
+        let expr = "feature geoip or feature true_lua and version >= 8";
+        assert!(check_expr(expr, &v7).is_err());
+        assert!(check_expr(expr, &v8).is_ok());
+
+        // parenthesized: (feature geoip or feature true_lua) and version >= 8
+        let expr = "(feature geoip or feature true_lua) and version >= 8";
+        assert!(check_expr(expr, &v7).is_err());
+        assert!(check_expr(expr, &v8).is_ok());
+        let expr = "(feature geoip or keyword bar) and version >= 8";
+        assert!(check_expr(expr, &v8).is_err());
+
+        // nested, with a version in each branch
+        let expr =
+            "(version < 8 and feature true_a) or (version >= 8, (keyword bar or keyword true_b))";
+        assert!(check_expr(expr, &v7).is_ok());
+        assert!(check_expr(expr, &v8).is_ok());
+        let expr = "(version < 8 and feature a) or (version >= 8, (keyword bar or keyword true_b))";
+        assert!(check_expr(expr, &v7).is_err());
+    }
+
+    #[test]
+    fn test_parse_requires_expr_bad() {
+        for (expr, token) in [
+            ("feature a or", ""),
+            ("or feature a", "or"),
+            ("feature a and or feature b", "or"),
+            ("(feature a or feature b", ""),
+            ("feature a) or feature b", ")"),
+            ("feature a, , feature b", ","),
+            ("()", ")"),
+        ] {
+            assert_eq!(
+                parse_requires_expr(expr),
+                Err(RequiresError::BadExpression(token.to_string())),
+                "{}",
+                expr
+            );
+        }
+    }
+
     #[test]
     fn test_requires_keyword() {
         let requires = parse_requires("keyword true_bar").unwrap();
         assert!(check_requires(&requires, &SuricataVersion::new(8, 0, 0)).is_ok());
 
         let requires = parse_requires("keyword bar").unwrap();
-        assert!(check_requires(&requires, &SuricataVersion::new(8, 0, 0)).is_err());
+        assert_eq!(
+            check_requires(&requires, &SuricataVersion::new(8, 0, 0)),
+            Err(RequiresError::MissingKeyword("bar".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_requires_keyword_status() {
+        fn check(requires: &str, status: &mut SCDetectRequiresStatus) -> c_int {
+            let requires = std::ffi::CString::new(requires).unwrap();
+            let mut errstr: *const c_char = std::ptr::null();
+            unsafe {
+                SCDetectCheckRequires(
+                    requires.as_ptr(),
+                    b"8.0.0\0".as_ptr() as *const c_char,
+                    &mut errstr,
+                    status,
+                )
+            }
+        }
+        let mut status = SCDetectRequiresStatus::default();
+
+        assert_eq!(check("keyword true_byte_math", &mut status), 0);
+        assert_eq!(status.keyword_count, 0);
+
+        // an absent keyword skips the rule, it is not an error
+        assert_eq!(check("keyword byte_foo", &mut status), -4);
+        assert_eq!(check("keyword byte_foo", &mut status), -4);
+        assert_eq!(status.keyword_count, 2);
+        assert_eq!(status.keywords.len(), 1);
+        assert!(status.keywords.contains("byte_foo"));
+    }
+
+    #[test]
+    fn test_requires_dataset_format() {
+        let requires = parse_requires("dataset-format >= 2").unwrap();
+        assert_eq!(
+            requires.dataset_format,
+            vec![RuleRequireDatasetFormat {
+                op: VersionCompareOp::Gte,
+                version: 2,
+            }]
+        );
+        assert_eq!(check_dataset_format(&requires.dataset_format, 2), Ok(()));
+        assert_eq!(check_dataset_format(&requires.dataset_format, 3), Ok(()));
+        assert_eq!(
+            check_dataset_format(&requires.dataset_format, 1),
+            Err(RequiresError::DatasetFormat)
+        );
+
+        let requires = parse_requires("dataset-format >= 2 < 4").unwrap();
+        assert_eq!(check_dataset_format(&requires.dataset_format, 3), Ok(()));
+        assert_eq!(
+            check_dataset_format(&requires.dataset_format, 4),
+            Err(RequiresError::DatasetFormat)
+        );
+
+        for value in ["", "2", "== 2", ">= two", ">= 2 foo", ">= -1"] {
+            assert_eq!(
+                parse_requires(&format!("dataset-format {}", value)),
+                Err(RequiresError::BadDatasetFormat(value.trim().to_string())),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_requires_dataset_format_status() {
+        fn check(requires: &str, status: &mut SCDetectRequiresStatus) -> c_int {
+            let requires = std::ffi::CString::new(requires).unwrap();
+            let mut errstr: *const c_char = std::ptr::null();
+            unsafe {
+                SCDetectCheckRequires(
+                    requires.as_ptr(),
+                    b"8.0.0\0".as_ptr() as *const c_char,
+                    &mut errstr,
+                    status,
+                )
+            }
+        }
+        let mut status = SCDetectRequiresStatus::default();
+
+        // the format version of this Suricata
+        assert_eq!(check("dataset-format >= 2", &mut status), 0);
+        assert_eq!(
+            check("dataset-format >= 1, dataset-format <= 2", &mut status),
+            0
+        );
+        assert_eq!(status.dataset_format_count, 0);
+
+        // an unmet format version skips the rule, it is not an error
+        assert_eq!(check("dataset-format >= 3", &mut status), -4);
+        assert_eq!(check("dataset-format < 2", &mut status), -4);
+        assert_eq!(status.dataset_format_count, 2);
+
+        assert_eq!(check("dataset-format >= 3 or version >= 8", &mut status), 0);
+        assert_eq!(check("dataset-format 2", &mut status), -1);
     }
 }
@@ -15,10 +15,13 @@
  * 02110-1301, USA.
  */
 
+use super::parser::parse_number;
 use super::uint::*;
+use nom7::branch::alt;
 use nom7::bytes::complete::{is_a, take_while};
-use nom7::character::complete::{alpha0, char, digit1};
+use nom7::character::complete::{alpha0, char};
 use nom7::combinator::{all_consuming, map_opt, map_res, opt};
+use nom7::sequence::preceded;
 use nom7::IResult;
 
 use std::ffi::CStr;
@@ -31,6 +34,8 @@ pub enum DetectStreamSizeDataFlags {
     StreamSizeClient = 2,
     StreamSizeBoth = 3,
     StreamSizeEither = 4,
+    /// compares the ratio of client to server bytes
+    StreamSizeRatio = 5,
 }
 
 impl std::str::FromStr for DetectStreamSizeDataFlags {
@@ -42,6 +47,7 @@ impl std::str::FromStr for DetectStreamSizeDataFlags {
             "client" => Ok(DetectStreamSizeDataFlags::StreamSizeClient),
             "both" => Ok(DetectStreamSizeDataFlags::StreamSizeBoth),
             "either" => Ok(DetectStreamSizeDataFlags::StreamSizeEither),
+            "ratio" => Ok(DetectStreamSizeDataFlags::StreamSizeRatio),
             _ => Err(format!(
                 "'{}' is not a valid value for DetectStreamSizeDataFlags",
                 s
@@ -57,17 +63,74 @@ pub struct DetectStreamSizeData {
     pub du32: DetectUintData<u32>,
 }
 
+/// Returns the mode matching when `mode` does not.
+fn stream_size_negate_mode(mode: DetectUintMode) -> Option<DetectUintMode> {
+    match mode {
+        DetectUintMode::DetectUintModeEqual => Some(DetectUintMode::DetectUintModeNe),
+        DetectUintMode::DetectUintModeLt => Some(DetectUintMode::DetectUintModeGte),
+        DetectUintMode::DetectUintModeGte => Some(DetectUintMode::DetectUintModeLt),
+        DetectUintMode::DetectUintModeGt => Some(DetectUintMode::DetectUintModeLte),
+        DetectUintMode::DetectUintModeLte => Some(DetectUintMode::DetectUintModeGt),
+        _ => None,
+    }
+}
+
+/// Parses the comparison. A `!` prefix negates it, so `!<` is the same as
+/// `>=`, and `!=` or a lone `!` mean not equal.
+fn detect_parse_stream_size_mode(i: &str) -> IResult<&str, DetectUintMode> {
+    alt((
+        preceded(
+            char('!'),
+            map_opt(detect_parse_uint_mode, stream_size_negate_mode),
+        ),
+        detect_parse_uint_mode,
+    ))(i)
+}
+
+/// Matches the ratio `client / server` against `du32`, without dividing:
+/// the ratio is greater than N if `client` is greater than `N * server`.
+/// There is no ratio if the server did not send any data, so that does not
+/// match.
+fn detect_match_stream_size_ratio(du32: &DetectUintData<u32>, client: u32, server: u32) -> bool {
+    if server == 0 {
+        return false;
+    }
+    let du64 = DetectUintData::<u64> {
+        arg1: du32.arg1 as u64 * server as u64,
+        arg2: du32.arg2 as u64 * server as u64,
+        mode: du32.mode.clone(),
+    };
+    detect_match_uint(&du64, client as u64)
+}
+
+/// Matches the stream sizes of both directions against `sd`.
+pub fn detect_match_stream_size(sd: &DetectStreamSizeData, client: u32, server: u32) -> bool {
+    match sd.flags {
+        DetectStreamSizeDataFlags::StreamSizeServer => detect_match_uint(&sd.du32, server),
+        DetectStreamSizeDataFlags::StreamSizeClient => detect_match_uint(&sd.du32, client),
+        DetectStreamSizeDataFlags::StreamSizeBoth => {
+            detect_match_uint(&sd.du32, server) && detect_match_uint(&sd.du32, client)
+        }
+        DetectStreamSizeDataFlags::StreamSizeEither => {
+            detect_match_uint(&sd.du32, server) || detect_match_uint(&sd.du32, client)
+        }
+        DetectStreamSizeDataFlags::StreamSizeRatio => {
+            detect_match_stream_size_ratio(&sd.du32, client, server)
+        }
+    }
+}
+
 pub fn detect_parse_stream_size(i: &str) -> IResult<&str, DetectStreamSizeData> {
     let (i, _) = opt(is_a(" "))(i)?;
     let (i, flags) = map_res(alpha0, DetectStreamSizeDataFlags::from_str)(i)?;
     let (i, _) = opt(is_a(" "))(i)?;
     let (i, _) = char(',')(i)?;
     let (i, _) = opt(is_a(" "))(i)?;
-    let (i, mode) = detect_parse_uint_mode(i)?;
+    let (i, mode) = detect_parse_stream_size_mode(i)?;
     let (i, _) = opt(is_a(" "))(i)?;
     let (i, _) = char(',')(i)?;
     let (i, _) = opt(is_a(" "))(i)?;
-    let (i, arg1) = map_opt(digit1, |s: &str| s.parse::<u32>().ok())(i)?;
+    let (i, arg1) = map_opt(parse_number, |v| u32::try_from(v).ok())(i)?;
     let (i, _) = all_consuming(take_while(|c| c == ' '))(i)?;
     let du32 = DetectUintData::<u32> {
         arg1,
@@ -96,3 +159,119 @@ pub unsafe extern "C" fn SCDetectStreamSizeFree(ctx: &mut DetectStreamSizeData)
     // Just unbox...
     std::mem::drop(Box::from_raw(ctx));
 }
+
+#[no_mangle]
+pub extern "C" fn SCDetectStreamSizeMatch(
+    ctx: &DetectStreamSizeData, client: u32, server: u32,
+) -> bool {
+    detect_match_stream_size(ctx, client, server)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stream_size_negation() {
+        for (dir, flags) in [
+            ("server", DetectStreamSizeDataFlags::StreamSizeServer),
+            ("client", DetectStreamSizeDataFlags::StreamSizeClient),
+            ("both", DetectStreamSizeDataFlags::StreamSizeBoth),
+            ("either", DetectStreamSizeDataFlags::StreamSizeEither),
+        ] {
+            for (op, mode) in [
+                ("!=", DetectUintMode::DetectUintModeNe),
+                ("!", DetectUintMode::DetectUintModeNe),
+                ("!<", DetectUintMode::DetectUintModeGte),
+                ("!<=", DetectUintMode::DetectUintModeGt),
+                ("!>", DetectUintMode::DetectUintModeLte),
+                ("!>=", DetectUintMode::DetectUintModeLt),
+                ("<", DetectUintMode::DetectUintModeLt),
+            ] {
+                let input = format!("{}, {}, 0", dir, op);
+                let (_, sd) = detect_parse_stream_size(&input).unwrap();
+                assert_eq!(sd.flags, flags, "{}", input);
+                assert_eq!(sd.du32.mode, mode, "{}", input);
+                assert_eq!(sd.du32.arg1, 0);
+            }
+        }
+        assert!(detect_parse_stream_size("server, !!=, 0").is_err());
+        assert!(detect_parse_stream_size("server, !!, 0").is_err());
+        assert!(detect_parse_stream_size("!server, <, 0").is_err());
+    }
+
+    #[test]
+    fn test_parse_stream_size_suffix() {
+        let (_, sd) = detect_parse_stream_size("server, >, 10mb").unwrap();
+        assert_eq!(sd.du32.arg1, 10 * 1024 * 1024);
+        let (_, sd) = detect_parse_stream_size("client, <, 2KB").unwrap();
+        assert_eq!(sd.du32.arg1, 2048);
+        let (_, sd) = detect_parse_stream_size("both, =, 1_000").unwrap();
+        assert_eq!(sd.du32.arg1, 1000);
+        // stream sizes are 32 bits
+        assert!(detect_parse_stream_size("server, >, 4gb").is_err());
+    }
+
+    #[test]
+    fn test_match_stream_size() {
+        let (_, server_ne) = detect_parse_stream_size("server, !=, 0").unwrap();
+        let (_, client_ge) = detect_parse_stream_size("client, !<, 100").unwrap();
+        let (_, both_le) = detect_parse_stream_size("both, !>, 100").unwrap();
+        let (_, either_ne) = detect_parse_stream_size("either, !, 0").unwrap();
+
+        // simulated stream: the client sends a 60 byte request, the server
+        // responds with 200 bytes, then the client sends 60 bytes more.
+        let mut client = 0;
+        let mut server = 0;
+        assert!(!detect_match_stream_size(&server_ne, client, server));
+        assert!(!detect_match_stream_size(&client_ge, client, server));
+        assert!(detect_match_stream_size(&both_le, client, server));
+        assert!(!detect_match_stream_size(&either_ne, client, server));
+
+        client += 60;
+        assert!(!detect_match_stream_size(&server_ne, client, server));
+        assert!(!detect_match_stream_size(&client_ge, client, server));
+        assert!(detect_match_stream_size(&both_le, client, server));
+        assert!(detect_match_stream_size(&either_ne, client, server));
+
+        server += 200;
+        assert!(detect_match_stream_size(&server_ne, client, server));
+        assert!(!detect_match_stream_size(&client_ge, client, server));
+        assert!(!detect_match_stream_size(&both_le, client, server));
+
+        client += 60;
+        assert!(detect_match_stream_size(&client_ge, client, server));
+        assert!(!detect_match_stream_size(&both_le, client, server));
+        assert!(detect_match_stream_size(&either_ne, client, server));
+    }
+
+    #[test]
+    fn test_match_stream_size_ratio() {
+        let (_, sd) = detect_parse_stream_size("ratio, >, 10").unwrap();
+        assert_eq!(sd.flags, DetectStreamSizeDataFlags::StreamSizeRatio);
+
+        // 10:1 is not more than 10
+        assert!(!detect_match_stream_size(&sd, 1000, 100));
+        assert!(detect_match_stream_size(&sd, 1001, 100));
+        // no ratio without server bytes
+        assert!(!detect_match_stream_size(&sd, 1000, 0));
+        assert!(!detect_match_stream_size(&sd, 0, 0));
+        // no client bytes is a ratio of 0
+        assert!(!detect_match_stream_size(&sd, 0, 100));
+        let (_, sd) = detect_parse_stream_size("ratio, <, 1").unwrap();
+        assert!(detect_match_stream_size(&sd, 0, 100));
+        assert!(!detect_match_stream_size(&sd, 0, 0));
+
+        let (_, sd) = detect_parse_stream_size("ratio, =, 10").unwrap();
+        assert!(detect_match_stream_size(&sd, 1000, 100));
+        assert!(!detect_match_stream_size(&sd, 1001, 100));
+        let (_, sd) = detect_parse_stream_size("ratio, !<, 10").unwrap();
+        assert!(detect_match_stream_size(&sd, 1000, 100));
+        assert!(!detect_match_stream_size(&sd, 999, 100));
+
+        // no overflow on large values
+        let (_, sd) = detect_parse_stream_size("ratio, >=, 4294967295").unwrap();
+        assert!(detect_match_stream_size(&sd, u32::MAX, 1));
+        assert!(!detect_match_stream_size(&sd, u32::MAX, 2));
+    }
+}
@@ -0,0 +1,173 @@
+/* Copyright (C) 2026 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use crate::core::STREAM_TOSERVER;
+use crate::detect::{helper_keyword_register_sticky_buffer, SigTableElmtStickyBuffer};
+use suricata_sys::sys::{
+    AppProto, AppProtoEnum, DetectEngineCtx, SCDetectBufferSetActiveList,
+    SCDetectHelperBufferMpmRegister, SCDetectSignatureSetAppProto, Signature,
+};
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+
+extern "C" {
+    fn SCSSLStateGetSni(state: *const c_void, sni: *mut *const u8, sni_len: *mut u32) -> bool;
+    fn SCSSLStateGetSniLower(state: *const c_void) -> *const c_char;
+    fn SCSSLStateSetSniLower(state: *mut c_void, sni_lower: *mut c_char);
+}
+
+/// Normalizes a server name into `out`: lowercased, without the trailing
+/// dot of a fully qualified name, so that `dotprefix` and `endswith` work
+/// on it like on any other domain.
+pub fn tls_sni_normalize(sni: &[u8], out: &mut Vec<u8>) {
+    let sni = sni.strip_suffix(b".").unwrap_or(sni);
+    out.extend(sni.iter().map(|c| c.to_ascii_lowercase()));
+}
+
+/// Normalizes `sni` into a string for the TLS state to keep.
+fn tls_sni_lower_new(sni: &[u8]) -> Option<CString> {
+    let mut out = Vec::with_capacity(sni.len());
+    tls_sni_normalize(sni, &mut out);
+    // the parser stops the name at the first NUL, so there is none left
+    CString::new(out).ok()
+}
+
+/// Hands out the normalized SNI kept by the state, or an empty buffer if
+/// there is none.
+unsafe fn tls_sni_lower_buffer(
+    sni_lower: *const c_char, buffer: *mut *const u8, buffer_len: *mut u32,
+) {
+    let sni_lower = if sni_lower.is_null() {
+        &[]
+    } else {
+        CStr::from_ptr(sni_lower).to_bytes()
+    };
+    *buffer = sni_lower.as_ptr();
+    *buffer_len = sni_lower.len() as u32;
+}
+
+static mut G_TLS_SNI_LOWER_BUFFER_ID: c_int = 0;
+
+unsafe extern "C" fn tls_sni_lower_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, _raw: *const std::os::raw::c_char,
+) -> c_int {
+    if SCDetectSignatureSetAppProto(s, AppProtoEnum::ALPROTO_TLS as AppProto) != 0 {
+        return -1;
+    }
+    if SCDetectBufferSetActiveList(de, s, G_TLS_SNI_LOWER_BUFFER_ID) < 0 {
+        return -1;
+    }
+    return 0;
+}
+
+/// The TLS transaction is the state itself, which owns the normalized SNI
+/// from its first use on, like the other TLS buffers.
+unsafe extern "C" fn tls_sni_lower_get(
+    tx: *const c_void, _flags: u8, buffer: *mut *const u8, buffer_len: *mut u32,
+) -> bool {
+    let mut sni_lower = SCSSLStateGetSniLower(tx);
+    if sni_lower.is_null() {
+        let mut sni: *const u8 = std::ptr::null();
+        let mut sni_len = 0;
+        if SCSSLStateGetSni(tx, &mut sni, &mut sni_len) {
+            let sni = build_slice!(sni, sni_len as usize);
+            if let Some(lower) = tls_sni_lower_new(sni) {
+                let lower = lower.into_raw();
+                SCSSLStateSetSniLower(tx as *mut c_void, lower);
+                sni_lower = lower;
+            }
+        }
+    }
+    tls_sni_lower_buffer(sni_lower, buffer, buffer_len);
+    return true;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectTlsSniLowerRegister() {
+    let kw = SigTableElmtStickyBuffer {
+        name: String::from("tls.sni_lower"),
+        desc: String::from("sticky buffer to match on the lowercased TLS SNI"),
+        url: String::from("/rules/tls-keywords.html#tls-sni-lower"),
+        setup: tls_sni_lower_setup,
+    };
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
+    G_TLS_SNI_LOWER_BUFFER_ID = SCDetectHelperBufferMpmRegister(
+        b"tls.sni_lower\0".as_ptr() as *const libc::c_char,
+        b"lowercased TLS Server Name Indication (SNI) extension\0".as_ptr() as *const libc::c_char,
+        AppProtoEnum::ALPROTO_TLS as AppProto,
+        STREAM_TOSERVER,
+        Some(tls_sni_lower_get),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tls_parser::{parse_tls_extensions, TlsExtension};
+
+    fn engine_buffer(sni: Option<&[u8]>) -> Vec<u8> {
+        let sni_lower = sni.map(|sni| tls_sni_lower_new(sni).unwrap());
+        let ptr = sni_lower.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
+        let mut buffer: *const u8 = std::ptr::null();
+        let mut buffer_len = 0;
+        unsafe {
+            tls_sni_lower_buffer(ptr, &mut buffer, &mut buffer_len);
+            std::slice::from_raw_parts(buffer, buffer_len as usize).to_vec()
+        }
+    }
+
+    /// Server name extension of a ClientHello.
+    fn client_hello_sni(name: &[u8]) -> Vec<u8> {
+        let mut ext = vec![0x00, 0x00];
+        ext.extend_from_slice(&(name.len() as u16 + 5).to_be_bytes());
+        ext.extend_from_slice(&(name.len() as u16 + 3).to_be_bytes());
+        ext.push(0x00);
+        ext.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        ext.extend_from_slice(name);
+        return ext;
+    }
+
+    #[test]
+    fn test_tls_sni_lower_client_hello() {
+        let ext = client_hello_sni(b"WWW.Example.COM");
+        let (_, exts) = parse_tls_extensions(&ext).unwrap();
+        let sni = match &exts[0] {
+            TlsExtension::SNI(names) => names[0].1,
+            _ => panic!("no sni extension"),
+        };
+        assert_eq!(engine_buffer(Some(sni)), b"www.example.com");
+    }
+
+    #[test]
+    fn test_tls_sni_lower() {
+        assert_eq!(engine_buffer(Some(b"oisf.net")), b"oisf.net");
+        // the trailing dot of a fully qualified name is dropped
+        assert_eq!(engine_buffer(Some(b"Suricata.IO.")), b"suricata.io");
+        // non ascii bytes are left as is
+        assert_eq!(engine_buffer(Some(b"A\xc3\x89.net")), b"a\xc3\x89.net");
+    }
+
+    #[test]
+    fn test_tls_sni_lower_absent() {
+        engine_buffer(Some(b"oisf.net"));
+        assert_eq!(engine_buffer(None), b"");
+        assert_eq!(engine_buffer(Some(b"")), b"");
+    }
+}
@@ -15,9 +15,122 @@
  * 02110-1301, USA.
  */
 
+use crate::detect::float::{DetectFloatData, DetectFloatMode, DetectFloatType};
 use crate::detect::uint::{DetectIntType, DetectUintData, DetectUintMode};
+use crate::detect::vlan::{
+    DetectVlanIdData, DETECT_VLAN_ID_ALL, DETECT_VLAN_ID_ANY, VLAN_MAX_LAYERS,
+};
+use crate::detect::EnumString;
 use crate::jsonbuilder::{JsonBuilder, JsonError};
+use num::traits::float::FloatCore;
+use std::ffi::CStr;
+use std::fmt;
+use std::os::raw::c_char;
+use suricata_derive::EnumStringU8;
 
+/// Mode of a `DetectUintData`, as logged in its `mode` member.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumStringU8)]
+#[repr(u8)]
+pub enum DetectUintJsonMode {
+    Equal = 0,
+    Diff = 1,
+    Lt = 2,
+    Lte = 3,
+    Gt = 4,
+    Gte = 5,
+    Range = 6,
+    NegatedRange = 7,
+    Bitmask = 8,
+    NegatedBitmask = 9,
+}
+
+impl DetectUintJsonMode {
+    pub fn from_mode(mode: &DetectUintMode) -> Self {
+        match mode {
+            DetectUintMode::DetectUintModeEqual => DetectUintJsonMode::Equal,
+            DetectUintMode::DetectUintModeNe => DetectUintJsonMode::Diff,
+            DetectUintMode::DetectUintModeLt => DetectUintJsonMode::Lt,
+            DetectUintMode::DetectUintModeLte => DetectUintJsonMode::Lte,
+            DetectUintMode::DetectUintModeGt => DetectUintJsonMode::Gt,
+            DetectUintMode::DetectUintModeGte => DetectUintJsonMode::Gte,
+            DetectUintMode::DetectUintModeRange => DetectUintJsonMode::Range,
+            DetectUintMode::DetectUintModeNegRg => DetectUintJsonMode::NegatedRange,
+            DetectUintMode::DetectUintModeBitmask => DetectUintJsonMode::Bitmask,
+            DetectUintMode::DetectUintModeNegBitmask => DetectUintJsonMode::NegatedBitmask,
+        }
+    }
+
+    /// The `is_nan` and `is_inf` predicates have no such mode, for which
+    /// this returns None.
+    pub fn from_float_mode(mode: &DetectFloatMode) -> Option<Self> {
+        let mode = match mode {
+            DetectFloatMode::DetectFloatModeEqual => DetectUintJsonMode::Equal,
+            DetectFloatMode::DetectFloatModeNe => DetectUintJsonMode::Diff,
+            DetectFloatMode::DetectFloatModeLt => DetectUintJsonMode::Lt,
+            DetectFloatMode::DetectFloatModeLte => DetectUintJsonMode::Lte,
+            DetectFloatMode::DetectFloatModeGt => DetectUintJsonMode::Gt,
+            DetectFloatMode::DetectFloatModeGte => DetectUintJsonMode::Gte,
+            DetectFloatMode::DetectFloatModeRange => DetectUintJsonMode::Range,
+            DetectFloatMode::DetectFloatModeNegRg => DetectUintJsonMode::NegatedRange,
+            DetectFloatMode::DetectFloatModeIsNan | DetectFloatMode::DetectFloatModeIsInf => {
+                return None
+            }
+        };
+        Some(mode)
+    }
+
+    pub fn to_mode(self) -> DetectUintMode {
+        match self {
+            DetectUintJsonMode::Equal => DetectUintMode::DetectUintModeEqual,
+            DetectUintJsonMode::Diff => DetectUintMode::DetectUintModeNe,
+            DetectUintJsonMode::Lt => DetectUintMode::DetectUintModeLt,
+            DetectUintJsonMode::Lte => DetectUintMode::DetectUintModeLte,
+            DetectUintJsonMode::Gt => DetectUintMode::DetectUintModeGt,
+            DetectUintJsonMode::Gte => DetectUintMode::DetectUintModeGte,
+            DetectUintJsonMode::Range => DetectUintMode::DetectUintModeRange,
+            DetectUintJsonMode::NegatedRange => DetectUintMode::DetectUintModeNegRg,
+            DetectUintJsonMode::Bitmask => DetectUintMode::DetectUintModeBitmask,
+            DetectUintJsonMode::NegatedBitmask => DetectUintMode::DetectUintModeNegBitmask,
+        }
+    }
+
+    /// Floats have no bitmask modes, for which this returns None.
+    pub fn to_float_mode(self) -> Option<DetectFloatMode> {
+        let mode = match self {
+            DetectUintJsonMode::Equal => DetectFloatMode::DetectFloatModeEqual,
+            DetectUintJsonMode::Diff => DetectFloatMode::DetectFloatModeNe,
+            DetectUintJsonMode::Lt => DetectFloatMode::DetectFloatModeLt,
+            DetectUintJsonMode::Lte => DetectFloatMode::DetectFloatModeLte,
+            DetectUintJsonMode::Gt => DetectFloatMode::DetectFloatModeGt,
+            DetectUintJsonMode::Gte => DetectFloatMode::DetectFloatModeGte,
+            DetectUintJsonMode::Range => DetectFloatMode::DetectFloatModeRange,
+            DetectUintJsonMode::NegatedRange => DetectFloatMode::DetectFloatModeNegRg,
+            DetectUintJsonMode::Bitmask | DetectUintJsonMode::NegatedBitmask => return None,
+        };
+        Some(mode)
+    }
+
+    /// Names of the members for `arg1` and, if used by the mode, `arg2`.
+    pub fn members(&self) -> (&'static str, Option<&'static str>) {
+        match self {
+            DetectUintJsonMode::Range | DetectUintJsonMode::NegatedRange => ("min", Some("max")),
+            DetectUintJsonMode::Bitmask | DetectUintJsonMode::NegatedBitmask => {
+                ("mask", Some("value"))
+            }
+            _ => ("value", None),
+        }
+    }
+
+    fn all() -> impl Iterator<Item = Self> {
+        (0..=u8::MAX).map_while(DetectUintJsonMode::from_u)
+    }
+}
+
+/// Logs `du` in the open object `js`, like `{"min":10,"max":20,"mode":"range"}`
+/// or `{"value":5,"mode":"lt"}`.
+///
+/// The output does not depend on anything but `du`, with keys in a declared
+/// order: the `members` of the mode, `arg1` first, then `mode`.
 pub fn detect_uint_to_json<T: DetectIntType>(
     js: &mut JsonBuilder, du: &DetectUintData<T>,
 ) -> Result<(), JsonError>
@@ -26,49 +139,316 @@ where
 {
     let arg1: u64 = du.arg1.into();
     let arg2: u64 = du.arg2.into();
-    match du.mode {
-        DetectUintMode::DetectUintModeEqual => {
-            js.set_uint("equal", arg1)?;
-        }
-        DetectUintMode::DetectUintModeNe => {
-            js.set_uint("diff", arg1)?;
-        }
-        DetectUintMode::DetectUintModeLt => {
-            js.set_uint("lt", arg1)?;
-        }
-        DetectUintMode::DetectUintModeLte => {
-            js.set_uint("lte", arg1)?;
-        }
-        DetectUintMode::DetectUintModeGt => {
-            js.set_uint("gt", arg1)?;
-        }
-        DetectUintMode::DetectUintModeGte => {
-            js.set_uint("gte", arg1)?;
+    let mode = DetectUintJsonMode::from_mode(&du.mode);
+    let (m1, m2) = mode.members();
+    js.set_uint(m1, arg1)?;
+    if let Some(m2) = m2 {
+        js.set_uint(m2, arg2)?;
+    }
+    js.set_string("mode", mode.to_str())?;
+    Ok(())
+}
+
+/// Sets `key` to `val`, or, as JSON has no literal for them, to the
+/// strings `"NaN"`, `"inf"` or `"-inf"`.
+fn set_float_json_safe(js: &mut JsonBuilder, key: &str, val: f64) -> Result<(), JsonError> {
+    if val.is_nan() {
+        js.set_string(key, "NaN")?;
+    } else if val.is_infinite() {
+        js.set_string(key, if val > 0.0 { "inf" } else { "-inf" })?;
+    } else {
+        js.set_float(key, val)?;
+    }
+    Ok(())
+}
+
+/// Float predicates, logged as their `mode` alone.
+const FLOAT_PREDICATES: [(DetectFloatMode, &str); 2] = [
+    (DetectFloatMode::DetectFloatModeIsNan, "is_nan"),
+    (DetectFloatMode::DetectFloatModeIsInf, "is_inf"),
+];
+
+/// Logs `df` in the open object `js`, like `detect_uint_to_json` does,
+/// for instance `{"min":0.5,"max":7.5,"mode":"range"}`, with an `epsilon`
+/// member after `mode` if the comparisons are not exact. Predicates only
+/// have a `mode`, like `{"mode":"is_nan"}`.
+pub fn detect_float_to_json<T: DetectFloatType>(
+    js: &mut JsonBuilder, df: &DetectFloatData<T>,
+) -> Result<(), JsonError> {
+    let mode = match DetectUintJsonMode::from_float_mode(&df.mode) {
+        Some(mode) => mode,
+        None => {
+            if let Some((_, name)) = FLOAT_PREDICATES.iter().find(|(m, _)| *m == df.mode) {
+                js.set_string("mode", name)?;
+            }
+            return Ok(());
         }
-        DetectUintMode::DetectUintModeRange => {
-            js.open_object("range")?;
-            js.set_uint("min", arg1)?;
-            js.set_uint("max", arg2)?;
-            js.close()?;
+    };
+    let (m1, m2) = mode.members();
+    set_float_json_safe(js, m1, df.arg1.to_f64().unwrap_or(f64::NAN))?;
+    if let Some(m2) = m2 {
+        set_float_json_safe(js, m2, df.arg2.to_f64().unwrap_or(f64::NAN))?;
+    }
+    js.set_string("mode", mode.to_str())?;
+    if df.epsilon != <T as num::Zero>::zero() {
+        js.set_float("epsilon", df.epsilon.to_f64().unwrap_or(f64::NAN))?;
+    }
+    Ok(())
+}
+
+/// Error of the `from_json` functions, reading back what the `to_json`
+/// functions logged.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DetectFromJsonError {
+    /// the value is not a JSON object
+    NotAnObject,
+    /// a required member is absent
+    MissingField(&'static str),
+    /// a string member is not one of the known values
+    UnknownValue(&'static str, String),
+    /// a member has the wrong type, or is out of range
+    InvalidValue(&'static str),
+}
+
+impl fmt::Display for DetectFromJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DetectFromJsonError::NotAnObject => write!(f, "not a JSON object"),
+            DetectFromJsonError::MissingField(key) => write!(f, "missing member {}", key),
+            DetectFromJsonError::UnknownValue(key, val) => {
+                write!(f, "unknown {} {:?}", key, val)
+            }
+            DetectFromJsonError::InvalidValue(key) => write!(f, "invalid member {}", key),
         }
-        DetectUintMode::DetectUintModeNegRg => {
-            js.open_object("negated_range")?;
-            js.set_uint("min", arg1)?;
-            js.set_uint("max", arg2)?;
-            js.close()?;
+    }
+}
+
+impl std::error::Error for DetectFromJsonError {}
+
+fn json_member<'a>(
+    v: &'a serde_json::Value, key: &'static str,
+) -> Result<&'a serde_json::Value, DetectFromJsonError> {
+    let obj = v.as_object().ok_or(DetectFromJsonError::NotAnObject)?;
+    obj.get(key).ok_or(DetectFromJsonError::MissingField(key))
+}
+
+fn json_mode(v: &serde_json::Value) -> Result<DetectUintJsonMode, DetectFromJsonError> {
+    let mode = json_member(v, "mode")?
+        .as_str()
+        .ok_or(DetectFromJsonError::InvalidValue("mode"))?;
+    DetectUintJsonMode::from_str(mode)
+        .ok_or_else(|| DetectFromJsonError::UnknownValue("mode", mode.to_string()))
+}
+
+fn json_uint<T: DetectIntType>(
+    v: &serde_json::Value, key: &'static str,
+) -> Result<T, DetectFromJsonError> {
+    json_member(v, key)?
+        .as_u64()
+        .and_then(T::from_u64)
+        .ok_or(DetectFromJsonError::InvalidValue(key))
+}
+
+/// Reads a float logged by `set_float_json_safe`.
+fn json_float<T: DetectFloatType>(
+    v: &serde_json::Value, key: &'static str,
+) -> Result<T, DetectFromJsonError> {
+    let val = json_member(v, key)?;
+    let val = match val.as_str() {
+        Some("NaN") => f64::NAN,
+        Some("inf") => f64::INFINITY,
+        Some("-inf") => f64::NEG_INFINITY,
+        Some(_) => return Err(DetectFromJsonError::InvalidValue(key)),
+        None => val.as_f64().ok_or(DetectFromJsonError::InvalidValue(key))?,
+    };
+    T::from_f64(val).ok_or(DetectFromJsonError::InvalidValue(key))
+}
+
+/// Reads back a `DetectUintData` logged by `detect_uint_to_json`.
+///
+/// Modes with a single value get `arg2` as set by the parser.
+pub fn detect_uint_from_json<T: DetectIntType>(
+    v: &serde_json::Value,
+) -> Result<DetectUintData<T>, DetectFromJsonError> {
+    let mode = json_mode(v)?;
+    let (m1, m2) = mode.members();
+    let arg1 = json_uint(v, m1)?;
+    let arg2 = match m2 {
+        Some(m2) => json_uint(v, m2)?,
+        None => T::min_value(),
+    };
+    Ok(DetectUintData {
+        arg1,
+        arg2,
+        mode: mode.to_mode(),
+    })
+}
+
+/// Reads back a `DetectFloatData` logged by `detect_float_to_json`.
+pub fn detect_float_from_json<T: DetectFloatType>(
+    v: &serde_json::Value,
+) -> Result<DetectFloatData<T>, DetectFromJsonError> {
+    let predicate = v.get("mode").and_then(|m| m.as_str()).and_then(|m| {
+        FLOAT_PREDICATES
+            .iter()
+            .find(|(_, name)| *name == m)
+            .map(|(mode, _)| mode.clone())
+    });
+    if let Some(mode) = predicate {
+        return Ok(DetectFloatData {
+            arg1: <T as num::Zero>::zero(),
+            arg2: <T as FloatCore>::min_value(),
+            mode,
+            epsilon: <T as num::Zero>::zero(),
+        });
+    }
+    let mode = json_mode(v)?;
+    let float_mode = mode
+        .to_float_mode()
+        .ok_or_else(|| DetectFromJsonError::UnknownValue("mode", mode.to_str().to_string()))?;
+    let (m1, m2) = mode.members();
+    let arg1 = json_float(v, m1)?;
+    let arg2 = match m2 {
+        Some(m2) => json_float(v, m2)?,
+        None => <T as FloatCore>::min_value(),
+    };
+    let epsilon = match v.get("epsilon") {
+        Some(_) => json_float(v, "epsilon")?,
+        None => <T as num::Zero>::zero(),
+    };
+    Ok(DetectFloatData {
+        arg1,
+        arg2,
+        mode: float_mode,
+        epsilon,
+    })
+}
+
+/// Logs `vd` in the open object `js`, as its vlan ids followed by its
+/// `layer`, which is `"any"`, `"all"` or the index of the layer.
+pub fn detect_vlan_id_to_json(
+    js: &mut JsonBuilder, vd: &DetectVlanIdData,
+) -> Result<(), JsonError> {
+    detect_uint_to_json(js, &vd.du16)?;
+    match vd.layer {
+        DETECT_VLAN_ID_ANY => js.set_string("layer", "any")?,
+        DETECT_VLAN_ID_ALL => js.set_string("layer", "all")?,
+        layer => js.set_int("layer", layer as i64)?,
+    };
+    Ok(())
+}
+
+/// Reads back a `DetectVlanIdData` logged by `detect_vlan_id_to_json`.
+pub fn detect_vlan_id_from_json(
+    v: &serde_json::Value,
+) -> Result<DetectVlanIdData, DetectFromJsonError> {
+    let du16 = detect_uint_from_json(v)?;
+    let layer = json_member(v, "layer")?;
+    let layer = match layer.as_str() {
+        Some("any") => DETECT_VLAN_ID_ANY,
+        Some("all") => DETECT_VLAN_ID_ALL,
+        Some(s) => return Err(DetectFromJsonError::UnknownValue("layer", s.to_string())),
+        None => layer
+            .as_i64()
+            .and_then(|l| i8::try_from(l).ok())
+            .filter(|l| (-VLAN_MAX_LAYERS..VLAN_MAX_LAYERS).contains(l))
+            .ok_or(DetectFromJsonError::InvalidValue("layer"))?,
+    };
+    Ok(DetectVlanIdData { du16, layer })
+}
+
+fn json_schema_uint(js: &mut JsonBuilder, key: &str, max: u64) -> Result<(), JsonError> {
+    js.open_object(key)?;
+    js.set_string("type", "integer")?;
+    js.set_uint("minimum", 0u64)?;
+    js.set_uint("maximum", max)?;
+    js.close()?;
+    Ok(())
+}
+
+/// Writes in the open object `js` the JSON Schema of the output of
+/// `detect_uint_to_json` for integers of type T.
+///
+/// There is one `oneOf` alternative per `DetectUintJsonMode` value, in
+/// order, each with its required members and `mode` string.
+pub fn detect_uint_json_schema<T: DetectIntType>(js: &mut JsonBuilder) -> Result<(), JsonError>
+where
+    u64: From<T>,
+{
+    let max: u64 = T::max_value().into();
+    js.set_string("$schema", "https://json-schema.org/draft/2020-12/schema")?;
+    js.set_string("type", "object")?;
+    js.open_object("properties")?;
+    js.open_object("mode")?;
+    js.open_array("enum")?;
+    for mode in DetectUintJsonMode::all() {
+        js.append_string(mode.to_str())?;
+    }
+    js.close()?;
+    js.close()?;
+    js.close()?;
+    js.open_array("oneOf")?;
+    for mode in DetectUintJsonMode::all() {
+        let (m1, m2) = mode.members();
+        js.start_object()?;
+        js.open_array("required")?;
+        js.append_string(m1)?;
+        if let Some(m2) = m2 {
+            js.append_string(m2)?;
         }
-        DetectUintMode::DetectUintModeBitmask => {
-            js.open_object("bitmask")?;
-            js.set_uint("mask", arg1)?;
-            js.set_uint("value", arg2)?;
-            js.close()?;
+        js.append_string("mode")?;
+        js.close()?;
+        js.open_object("properties")?;
+        json_schema_uint(js, m1, max)?;
+        if let Some(m2) = m2 {
+            json_schema_uint(js, m2, max)?;
         }
-        DetectUintMode::DetectUintModeNegBitmask => {
-            js.open_object("negated_bitmask")?;
-            js.set_uint("mask", arg1)?;
-            js.set_uint("value", arg2)?;
-            js.close()?;
+        js.open_object("mode")?;
+        js.set_string("const", mode.to_str())?;
+        js.close()?;
+        js.close()?;
+        js.set_bool("additionalProperties", false)?;
+        js.close()?;
+    }
+    js.close()?;
+    Ok(())
+}
+
+/// A transform of a buffer, as logged by detect_transforms_to_json.
+pub struct DetectTransformJson<'a> {
+    pub name: &'a str,
+    /// The options of the transform as written in the rule, like the key
+    /// of `xor`, for transforms which have options.
+    pub options: Option<&'a str>,
+}
+
+/// Logs the `transforms` of a buffer, in the order they are applied.
+pub fn detect_transforms_to_json(
+    js: &mut JsonBuilder, transforms: &[DetectTransformJson],
+) -> Result<(), JsonError> {
+    js.open_array("transforms")?;
+    for t in transforms {
+        js.start_object()?;
+        js.set_string("name", t.name)?;
+        if let Some(options) = t.options {
+            js.set_string("options", options)?;
         }
+        js.close()?;
+    }
+    js.close()?;
+    Ok(())
+}
+
+/// Logs a byte histogram, as `(byte value, count)` pairs, into the array
+/// `js`.
+pub fn detect_byte_histogram_to_json(
+    js: &mut JsonBuilder, histogram: &[(u8, u64)],
+) -> Result<(), JsonError> {
+    for &(byte, count) in histogram {
+        js.start_object()?;
+        js.set_uint("byte", byte)?;
+        js.set_uint("count", count)?;
+        js.close()?;
     }
     Ok(())
 }
@@ -86,3 +466,545 @@ pub unsafe extern "C" fn SCDetectU32ToJson(
 ) -> bool {
     return detect_uint_to_json(js, du).is_ok();
 }
+
+/// Logs the `cnt` transforms in `names`, their option strings being in
+/// `options`, NULL for a transform without options.
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectTransformsToJson(
+    js: &mut JsonBuilder, names: *const *const c_char, options: *const *const c_char, cnt: u32,
+) -> bool {
+    if cnt > 0 && (names.is_null() || options.is_null()) {
+        return false;
+    }
+    let mut transforms = Vec::with_capacity(cnt as usize);
+    for i in 0..cnt as usize {
+        let name = match CStr::from_ptr(*names.add(i)).to_str() {
+            Ok(name) => name,
+            Err(_) => return false,
+        };
+        let optstr = *options.add(i);
+        let options = if optstr.is_null() {
+            None
+        } else {
+            match CStr::from_ptr(optstr).to_str() {
+                Ok(options) => Some(options),
+                Err(_) => return false,
+            }
+        };
+        transforms.push(DetectTransformJson { name, options });
+    }
+    return detect_transforms_to_json(js, &transforms).is_ok();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectU16JsonSchema(js: &mut JsonBuilder) -> bool {
+    return detect_uint_json_schema::<u16>(js).is_ok();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectU32JsonSchema(js: &mut JsonBuilder) -> bool {
+    return detect_uint_json_schema::<u32>(js).is_ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::float::detect_parse_float;
+    use crate::detect::uint::detect_parse_uint;
+    use crate::detect::vlan::detect_parse_vlan_id;
+    use crate::jsonbuilder::{SCJbLen, SCJbPtr};
+    use serde_json::Value;
+
+    fn to_string(js: &mut JsonBuilder) -> String {
+        let buf = unsafe { std::slice::from_raw_parts(SCJbPtr(js), SCJbLen(js)) };
+        String::from_utf8(buf.to_vec()).unwrap()
+    }
+
+    fn to_value(js: &mut JsonBuilder) -> Value {
+        serde_json::from_str(&to_string(js)).unwrap()
+    }
+
+    fn uint_to_json(arg1: u32, arg2: u32, mode: DetectUintMode) -> String {
+        let du = DetectUintData::<u32> { arg1, arg2, mode };
+        let mut js = JsonBuilder::try_new_object().unwrap();
+        js.open_object("flow_age").unwrap();
+        detect_uint_to_json(&mut js, &du).unwrap();
+        js.close().unwrap();
+        js.close().unwrap();
+        to_string(&mut js)
+    }
+
+    #[test]
+    fn test_detect_uint_to_json_golden() {
+        assert_eq!(
+            uint_to_json(10, 20, DetectUintMode::DetectUintModeRange),
+            r#"{"flow_age":{"min":10,"max":20,"mode":"range"}}"#
+        );
+        // same output on every call
+        assert_eq!(
+            uint_to_json(10, 20, DetectUintMode::DetectUintModeRange),
+            uint_to_json(10, 20, DetectUintMode::DetectUintModeRange)
+        );
+    }
+
+    #[test]
+    fn test_detect_uint_to_json_modes() {
+        let cases = [
+            (
+                DetectUintMode::DetectUintModeEqual,
+                r#"{"value":10,"mode":"equal"}"#,
+            ),
+            (
+                DetectUintMode::DetectUintModeLt,
+                r#"{"value":10,"mode":"lt"}"#,
+            ),
+            (
+                DetectUintMode::DetectUintModeLte,
+                r#"{"value":10,"mode":"lte"}"#,
+            ),
+            (
+                DetectUintMode::DetectUintModeGt,
+                r#"{"value":10,"mode":"gt"}"#,
+            ),
+            (
+                DetectUintMode::DetectUintModeGte,
+                r#"{"value":10,"mode":"gte"}"#,
+            ),
+            (
+                DetectUintMode::DetectUintModeRange,
+                r#"{"min":10,"max":20,"mode":"range"}"#,
+            ),
+            (
+                DetectUintMode::DetectUintModeNe,
+                r#"{"value":10,"mode":"diff"}"#,
+            ),
+            (
+                DetectUintMode::DetectUintModeNegRg,
+                r#"{"min":10,"max":20,"mode":"negated_range"}"#,
+            ),
+            (
+                DetectUintMode::DetectUintModeBitmask,
+                r#"{"mask":10,"value":20,"mode":"bitmask"}"#,
+            ),
+            (
+                DetectUintMode::DetectUintModeNegBitmask,
+                r#"{"mask":10,"value":20,"mode":"negated_bitmask"}"#,
+            ),
+        ];
+        for (mode, expected) in cases {
+            let du = DetectUintData::<u32> {
+                arg1: 10,
+                arg2: 20,
+                mode,
+            };
+            let mut js = JsonBuilder::try_new_object().unwrap();
+            detect_uint_to_json(&mut js, &du).unwrap();
+            js.close().unwrap();
+            assert_eq!(to_string(&mut js), expected);
+        }
+    }
+
+    fn float_to_json(arg1: f64, arg2: f64, mode: DetectFloatMode) -> Value {
+        let df = DetectFloatData::<f64> {
+            arg1,
+            arg2,
+            mode,
+            ..Default::default()
+        };
+        let mut js = JsonBuilder::try_new_object().unwrap();
+        detect_float_to_json(&mut js, &df).unwrap();
+        js.close().unwrap();
+        to_value(&mut js)
+    }
+
+    #[test]
+    fn test_detect_float_to_json() {
+        let mut js = JsonBuilder::try_new_object().unwrap();
+        let df = DetectFloatData::<f64> {
+            arg1: 7.5,
+            arg2: 0.0,
+            mode: DetectFloatMode::DetectFloatModeGte,
+            epsilon: 0.0,
+        };
+        detect_float_to_json(&mut js, &df).unwrap();
+        js.close().unwrap();
+        assert_eq!(to_string(&mut js), r#"{"value":7.5,"mode":"gte"}"#);
+        assert_eq!(
+            float_to_json(-1.25, 0.0, DetectFloatMode::DetectFloatModeNe),
+            serde_json::json!({"value": -1.25, "mode": "diff"})
+        );
+    }
+
+    #[test]
+    fn test_detect_transforms_to_json() {
+        let mut js = JsonBuilder::try_new_object().unwrap();
+        let transforms = [
+            DetectTransformJson {
+                name: "to_lowercase",
+                options: None,
+            },
+            DetectTransformJson {
+                name: "dotprefix",
+                options: None,
+            },
+        ];
+        detect_transforms_to_json(&mut js, &transforms).unwrap();
+        js.close().unwrap();
+        assert_eq!(
+            to_string(&mut js),
+            r#"{"transforms":[{"name":"to_lowercase"},{"name":"dotprefix"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_detect_transforms_to_json_options() {
+        let names = [
+            b"xor\0".as_ptr() as *const c_char,
+            b"to_lowercase\0".as_ptr() as *const c_char,
+        ];
+        let options = [b"0d0a\0".as_ptr() as *const c_char, std::ptr::null()];
+        let mut js = JsonBuilder::try_new_object().unwrap();
+        assert!(unsafe { SCDetectTransformsToJson(&mut js, names.as_ptr(), options.as_ptr(), 2) });
+        js.close().unwrap();
+        assert_eq!(
+            to_value(&mut js),
+            serde_json::json!({"transforms": [
+                {"name": "xor", "options": "0d0a"},
+                {"name": "to_lowercase"}
+            ]})
+        );
+    }
+
+    #[test]
+    fn test_detect_float_to_json_range() {
+        assert_eq!(
+            float_to_json(0.5, 7.5, DetectFloatMode::DetectFloatModeRange),
+            serde_json::json!({"min": 0.5, "max": 7.5, "mode": "range"})
+        );
+        assert_eq!(
+            float_to_json(1.5, 2.5, DetectFloatMode::DetectFloatModeNegRg),
+            serde_json::json!({"min": 1.5, "max": 2.5, "mode": "negated_range"})
+        );
+    }
+
+    #[test]
+    fn test_detect_float_to_json_not_finite() {
+        assert_eq!(
+            float_to_json(f64::INFINITY, 0.0, DetectFloatMode::DetectFloatModeLt),
+            serde_json::json!({"value": "inf", "mode": "lt"})
+        );
+        assert_eq!(
+            float_to_json(
+                f64::NEG_INFINITY,
+                f64::NAN,
+                DetectFloatMode::DetectFloatModeRange
+            ),
+            serde_json::json!({"min": "-inf", "max": "NaN", "mode": "range"})
+        );
+    }
+
+    #[test]
+    fn test_detect_uint_json_schema_golden() {
+        let mut js = JsonBuilder::try_new_object().unwrap();
+        detect_uint_json_schema::<u16>(&mut js).unwrap();
+        js.close().unwrap();
+        let schema = to_string(&mut js);
+        assert!(schema.starts_with(
+            r#"{"$schema":"https://json-schema.org/draft/2020-12/schema","type":"object","#
+        ));
+        assert!(schema.contains(
+            r#"{"required":["min","max","mode"],"properties":{"min":{"type":"integer","minimum":0,"maximum":65535},"max":{"type":"integer","minimum":0,"maximum":65535},"mode":{"const":"range"}},"additionalProperties":false}"#
+        ));
+        let equal = schema.find(r#"{"const":"equal"}"#).unwrap();
+        let diff = schema.find(r#"{"const":"diff"}"#).unwrap();
+        let negated_bitmask = schema.find(r#"{"const":"negated_bitmask"}"#).unwrap();
+        assert!(equal < diff && diff < negated_bitmask);
+    }
+
+    /// Validates the subset of JSON Schema used by `detect_uint_json_schema`.
+    fn validate(schema: &Value, v: &Value) -> bool {
+        if let Some(c) = schema.get("const") {
+            if c != v {
+                return false;
+            }
+        }
+        if let Some(values) = schema["enum"].as_array() {
+            if !values.contains(v) {
+                return false;
+            }
+        }
+        if let Some(alts) = schema["oneOf"].as_array() {
+            if alts.iter().filter(|alt| validate(alt, v)).count() != 1 {
+                return false;
+            }
+        }
+        match schema["type"].as_str() {
+            Some("integer") => {
+                return match v.as_u64() {
+                    Some(n) => {
+                        schema["minimum"].as_u64().unwrap_or(0) <= n
+                            && n <= schema["maximum"].as_u64().unwrap_or(u64::MAX)
+                    }
+                    None => false,
+                };
+            }
+            Some("object") if !v.is_object() => return false,
+            _ => {}
+        }
+        let obj = match v.as_object() {
+            Some(obj) => obj,
+            None => return true,
+        };
+        if let Some(required) = schema["required"].as_array() {
+            let has = |r: &Value| obj.contains_key(r.as_str().unwrap_or_default());
+            if !required.iter().all(has) {
+                return false;
+            }
+        }
+        for (key, val) in obj {
+            match schema["properties"].get(key) {
+                Some(sub) => {
+                    if !validate(sub, val) {
+                        return false;
+                    }
+                }
+                None => {
+                    if schema["additionalProperties"] == Value::Bool(false) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn schema_u16() -> Value {
+        let mut js = JsonBuilder::try_new_object().unwrap();
+        detect_uint_json_schema::<u16>(&mut js).unwrap();
+        js.close().unwrap();
+        to_value(&mut js)
+    }
+
+    #[test]
+    fn test_detect_uint_json_schema_modes() {
+        let schema = schema_u16();
+        let names: Vec<&str> = schema["properties"]["mode"]["enum"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n.as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "equal",
+                "diff",
+                "lt",
+                "lte",
+                "gt",
+                "gte",
+                "range",
+                "negated_range",
+                "bitmask",
+                "negated_bitmask"
+            ]
+        );
+        assert_eq!(schema["oneOf"].as_array().unwrap().len(), names.len());
+        assert_eq!(schema["oneOf"][2]["properties"]["value"]["maximum"], 65535);
+        assert_eq!(schema["oneOf"][6]["required"][1], "max");
+    }
+
+    #[test]
+    fn test_detect_uint_json_schema_validates_output() {
+        let schema = schema_u16();
+        for mode in [
+            DetectUintMode::DetectUintModeEqual,
+            DetectUintMode::DetectUintModeLt,
+            DetectUintMode::DetectUintModeLte,
+            DetectUintMode::DetectUintModeGt,
+            DetectUintMode::DetectUintModeGte,
+            DetectUintMode::DetectUintModeRange,
+            DetectUintMode::DetectUintModeNe,
+            DetectUintMode::DetectUintModeNegRg,
+            DetectUintMode::DetectUintModeBitmask,
+            DetectUintMode::DetectUintModeNegBitmask,
+        ] {
+            let du = DetectUintData::<u16> {
+                arg1: 1,
+                arg2: 65535,
+                mode: mode.clone(),
+            };
+            let mut js = JsonBuilder::try_new_object().unwrap();
+            detect_uint_to_json(&mut js, &du).unwrap();
+            js.close().unwrap();
+            let v = to_value(&mut js);
+            assert!(validate(&schema, &v), "{:?}: {}", mode, v);
+        }
+
+        // drift is caught
+        for bad in [
+            r#"{"min":1,"high":2,"mode":"range"}"#,
+            r#"{"min":1,"mode":"range"}"#,
+            r#"{"value":1,"mode":"equals"}"#,
+            r#"{"value":65536,"mode":"lt"}"#,
+            r#"{"value":1}"#,
+            r#"{"lt":1}"#,
+        ] {
+            let bad: Value = serde_json::from_str(bad).unwrap();
+            assert!(!validate(&schema, &bad), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_detect_byte_histogram_to_json() {
+        let mut js = JsonBuilder::try_new_array().unwrap();
+        detect_byte_histogram_to_json(&mut js, &[(0x41, 1000), (0, 24)]).unwrap();
+        js.close().unwrap();
+        assert_eq!(
+            to_string(&mut js),
+            r#"[{"byte":65,"count":1000},{"byte":0,"count":24}]"#
+        );
+
+        let mut js = JsonBuilder::try_new_array().unwrap();
+        detect_byte_histogram_to_json(&mut js, &[]).unwrap();
+        js.close().unwrap();
+        assert_eq!(to_string(&mut js), "[]");
+    }
+
+    fn uint_roundtrip(s: &str) {
+        let (_, du) = detect_parse_uint::<u32>(s).unwrap();
+        let mut js = JsonBuilder::try_new_object().unwrap();
+        detect_uint_to_json(&mut js, &du).unwrap();
+        js.close().unwrap();
+        assert_eq!(
+            detect_uint_from_json::<u32>(&to_value(&mut js)),
+            Ok(du),
+            "{}",
+            s
+        );
+    }
+
+    #[test]
+    fn test_detect_uint_from_json_roundtrip() {
+        for s in [
+            "5", "!5", "<5", "<=5", ">5", ">=5", "10-20", "!10-20", "&0x0f=5", "&0x0f!=5",
+        ] {
+            uint_roundtrip(s);
+        }
+    }
+
+    #[test]
+    fn test_detect_float_from_json_roundtrip() {
+        for s in [
+            "=3.5", "!=3.5", "<7.5", ">=-1.25", "0.5-7.5", "!0.5-7.5", "<inf",
+        ] {
+            let (_, df) = detect_parse_float::<f64>(s).unwrap();
+            let mut js = JsonBuilder::try_new_object().unwrap();
+            detect_float_to_json(&mut js, &df).unwrap();
+            js.close().unwrap();
+            assert_eq!(
+                detect_float_from_json::<f64>(&to_value(&mut js)),
+                Ok(df),
+                "{}",
+                s
+            );
+        }
+        for (s, name) in [("is_nan", "is_nan"), (" is_inf", "is_inf")] {
+            let (_, df) = detect_parse_float::<f64>(s).unwrap();
+            let mut js = JsonBuilder::try_new_object().unwrap();
+            detect_float_to_json(&mut js, &df).unwrap();
+            js.close().unwrap();
+            let v = to_value(&mut js);
+            assert_eq!(v, serde_json::json!({"mode": name}));
+            assert_eq!(detect_float_from_json::<f64>(&v), Ok(df));
+        }
+        let (_, df) = detect_parse_float::<f64>("=2.5, epsilon:0.01").unwrap();
+        let mut js = JsonBuilder::try_new_object().unwrap();
+        detect_float_to_json(&mut js, &df).unwrap();
+        js.close().unwrap();
+        let v = to_value(&mut js);
+        assert_eq!(
+            v,
+            serde_json::json!({"value": 2.5, "mode": "equal", "epsilon": 0.01})
+        );
+        assert_eq!(detect_float_from_json::<f64>(&v), Ok(df));
+    }
+
+    #[test]
+    fn test_detect_vlan_id_from_json_roundtrip() {
+        for s in ["300", "200-1000,all", "<1000,0", "!4094,-1", "300,any"] {
+            let vd = detect_parse_vlan_id(s).unwrap();
+            let mut js = JsonBuilder::try_new_object().unwrap();
+            detect_vlan_id_to_json(&mut js, &vd).unwrap();
+            js.close().unwrap();
+            assert_eq!(
+                detect_vlan_id_from_json(&to_value(&mut js)),
+                Ok(vd),
+                "{}",
+                s
+            );
+        }
+        let vd = detect_parse_vlan_id("200-1000,all").unwrap();
+        let mut js = JsonBuilder::try_new_object().unwrap();
+        detect_vlan_id_to_json(&mut js, &vd).unwrap();
+        js.close().unwrap();
+        assert_eq!(
+            to_string(&mut js),
+            r#"{"min":200,"max":1000,"mode":"range","layer":"all"}"#
+        );
+    }
+
+    #[test]
+    fn test_detect_from_json_errors() {
+        use serde_json::json;
+        assert_eq!(
+            detect_uint_from_json::<u32>(&json!({"value": 5, "mode": "about"})),
+            Err(DetectFromJsonError::UnknownValue(
+                "mode",
+                "about".to_string()
+            ))
+        );
+        assert_eq!(
+            detect_uint_from_json::<u32>(&json!({"value": 5})),
+            Err(DetectFromJsonError::MissingField("mode"))
+        );
+        assert_eq!(
+            detect_uint_from_json::<u32>(&json!({"min": 5, "mode": "range"})),
+            Err(DetectFromJsonError::MissingField("max"))
+        );
+        assert_eq!(
+            detect_uint_from_json::<u8>(&json!({"value": 256, "mode": "equal"})),
+            Err(DetectFromJsonError::InvalidValue("value"))
+        );
+        assert_eq!(
+            detect_uint_from_json::<u32>(&json!([5])),
+            Err(DetectFromJsonError::NotAnObject)
+        );
+        assert_eq!(
+            detect_float_from_json::<f64>(&json!({"mask": 1.0, "value": 1.0, "mode": "bitmask"})),
+            Err(DetectFromJsonError::UnknownValue(
+                "mode",
+                "bitmask".to_string()
+            ))
+        );
+        assert_eq!(
+            detect_float_from_json::<f64>(&json!({"value": "infinity", "mode": "lt"})),
+            Err(DetectFromJsonError::InvalidValue("value"))
+        );
+        assert_eq!(
+            detect_vlan_id_from_json(&json!({"value": 300, "mode": "equal"})),
+            Err(DetectFromJsonError::MissingField("layer"))
+        );
+        assert_eq!(
+            detect_vlan_id_from_json(&json!({"value": 300, "mode": "equal", "layer": "first"})),
+            Err(DetectFromJsonError::UnknownValue(
+                "layer",
+                "first".to_string()
+            ))
+        );
+        assert_eq!(
+            detect_vlan_id_from_json(&json!({"value": 300, "mode": "equal", "layer": 3})),
+            Err(DetectFromJsonError::InvalidValue("layer"))
+        );
+    }
+}
@@ -166,6 +166,51 @@ mod tests {
         assert_eq!(out, b" a b c d ");
     }
 
+    #[test]
+    fn test_tolower_transform_utf8() {
+        // only ASCII uppercase is modified, multibyte sequences are left as is
+        let buf = "ÀÉ Straße ΣΑ Ü".as_bytes();
+        let mut out = vec![0; buf.len()];
+        tolower_transform_do(buf, &mut out);
+        assert_eq!(out.len(), buf.len());
+        assert_eq!(out, "ÀÉ straße ΣΑ Ü".as_bytes());
+
+        let buf = b"\xc3\x80\x41\xff\x5a\x7f";
+        let mut out = vec![0; buf.len()];
+        tolower_transform_do(buf, &mut out);
+        assert_eq!(out, b"\xc3\x80\x61\xff\x7a\x7f");
+    }
+
+    #[test]
+    fn test_tolower_transform_in_place() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"Hello WORLD");
+        let still_buf = unsafe { std::slice::from_raw_parts(buf.as_ptr(), buf.len()) };
+        tolower_transform_do(still_buf, &mut buf);
+        assert_eq!(&buf, b"hello world");
+    }
+
+    #[test]
+    fn test_tolower_after_strip_whitespace() {
+        use crate::detect::transforms::strip_whitespace::strip_whitespace_transform_do;
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b" Content-Type :\tTEXT/Html ");
+        let still_buf = unsafe { std::slice::from_raw_parts(buf.as_ptr(), buf.len()) };
+        let nb = strip_whitespace_transform_do(still_buf, &mut buf) as usize;
+        let still_buf = unsafe { std::slice::from_raw_parts(buf.as_ptr(), nb) };
+        tolower_transform_do(still_buf, &mut buf[..nb]);
+        assert_eq!(&buf[..nb], b"content-type:text/html");
+    }
+
+    #[test]
+    fn test_tolower_validate() {
+        let s = b"lower case only";
+        assert!(unsafe { tolower_validate(s.as_ptr(), s.len() as u16, std::ptr::null_mut()) });
+        let s = b"Mixed";
+        assert!(!unsafe { tolower_validate(s.as_ptr(), s.len() as u16, std::ptr::null_mut()) });
+    }
+
     #[test]
     fn test_toupper_transform() {
         let buf = b" A b C D ";
@@ -173,4 +218,33 @@ mod tests {
         toupper_transform_do(buf, &mut out);
         assert_eq!(out, b" A B C D ");
     }
+
+    #[test]
+    fn test_toupper_transform_utf8() {
+        let buf = "get straße é".as_bytes();
+        let mut out = vec![0; buf.len()];
+        toupper_transform_do(buf, &mut out);
+        assert_eq!(out.len(), buf.len());
+        assert_eq!(out, "GET STRAßE é".as_bytes());
+    }
+
+    #[test]
+    fn test_toupper_tolower_chain() {
+        // to_lowercase followed by to_uppercase, as in
+        // "http.method; to_lowercase; to_uppercase; content:\"POST\";"
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"pOsT");
+        let still_buf = unsafe { std::slice::from_raw_parts(buf.as_ptr(), buf.len()) };
+        tolower_transform_do(still_buf, &mut buf);
+        assert_eq!(&buf, b"post");
+        let still_buf = unsafe { std::slice::from_raw_parts(buf.as_ptr(), buf.len()) };
+        toupper_transform_do(still_buf, &mut buf);
+        assert_eq!(&buf, b"POST");
+        // the content that follows must validate against the last transform
+        assert!(unsafe { toupper_validate(buf.as_ptr(), buf.len() as u16, std::ptr::null_mut()) });
+        let content = b"post";
+        assert!(!unsafe {
+            toupper_validate(content.as_ptr(), content.len() as u16, std::ptr::null_mut())
+        });
+    }
 }
@@ -33,27 +33,44 @@ unsafe extern "C" fn dot_prefix_setup(
     return SCDetectSignatureAddTransform(s, G_TRANSFORM_DOT_PREFIX_ID, ptr::null_mut());
 }
 
-fn dot_prefix_transform_do(input: &[u8], output: &mut [u8]) {
+fn dot_prefix_transform_do(input: &[u8], output: &mut [u8]) -> usize {
+    if input.first() == Some(&b'.') {
+        // already dot-prefixed: keep the buffer as is
+        if !std::ptr::eq(output.as_ptr(), input.as_ptr()) {
+            output[..input.len()].copy_from_slice(input);
+        }
+        return input.len();
+    }
     if std::ptr::eq(output.as_ptr(), input.as_ptr()) {
         output.copy_within(0..input.len(), 1);
     } else {
-        output[1..].copy_from_slice(input);
+        output[1..input.len() + 1].copy_from_slice(input);
     }
     output[0] = b'.';
+    return input.len() + 1;
 }
 
 unsafe extern "C" fn dot_prefix_transform(
     _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, _ctx: *mut c_void,
 ) {
     let input_len = (*buffer).inspect_len;
-    if input_len == 0 {
-        return;
+    if input_len > 0 {
+        let input = (*buffer).inspect;
+        if input.is_null() || *input == b'.' {
+            return;
+        }
     }
     let output = SCInspectionBufferCheckAndExpand(buffer, input_len + 1);
     if output.is_null() {
         // allocation failure
         return;
     }
+    if input_len == 0 {
+        // empty buffer becomes a single dot
+        *output = b'.';
+        SCInspectionBufferTruncate(buffer, 1);
+        return;
+    }
     // get input after possible realloc
     let input = (*buffer).inspect;
     if input.is_null() {
@@ -63,9 +80,9 @@ unsafe extern "C" fn dot_prefix_transform(
     let input = build_slice!(input, input_len as usize);
     let output = std::slice::from_raw_parts_mut(output, (input_len + 1) as usize);
 
-    dot_prefix_transform_do(input, output);
+    let nb = dot_prefix_transform_do(input, output);
 
-    SCInspectionBufferTruncate(buffer, input_len + 1);
+    SCInspectionBufferTruncate(buffer, nb as u32);
 }
 
 #[no_mangle]
@@ -110,4 +127,45 @@ mod tests {
         dot_prefix_transform_do(still_buf, &mut buf);
         assert_eq!(&buf, b".hello.example.com");
     }
+
+    #[test]
+    fn test_dot_prefix_transform_empty() {
+        let mut out = vec![0; 1];
+        assert_eq!(dot_prefix_transform_do(b"", &mut out), 1);
+        assert_eq!(out, b".");
+    }
+
+    #[test]
+    fn test_dot_prefix_transform_already_prefixed() {
+        let buf = b".example.com";
+        let mut out = vec![0; buf.len() + 1];
+        let nb = dot_prefix_transform_do(buf, &mut out);
+        assert_eq!(&out[..nb], b".example.com");
+        // applying it twice does not stack dots
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"example.com");
+        buf.push(0);
+        let still_buf = unsafe { std::slice::from_raw_parts(buf.as_ptr(), buf.len() - 1) };
+        let nb = dot_prefix_transform_do(still_buf, &mut buf);
+        let still_buf = unsafe { std::slice::from_raw_parts(buf.as_ptr(), nb) };
+        let nb = dot_prefix_transform_do(still_buf, &mut buf);
+        assert_eq!(&buf[..nb], b".example.com");
+    }
+
+    #[test]
+    fn test_dot_prefix_after_to_lowercase() {
+        let buf = b"Sub.EXAMPLE.com";
+        let mut lower = vec![0; buf.len()];
+        for (i, o) in buf.iter().zip(lower.iter_mut()) {
+            *o = i.to_ascii_lowercase();
+        }
+        let mut out = vec![0; lower.len() + 1];
+        let nb = dot_prefix_transform_do(&lower, &mut out);
+        assert_eq!(&out[..nb], b".sub.example.com");
+        // the dot is not affected by case folding applied afterwards
+        for c in out.iter_mut() {
+            *c = c.to_ascii_lowercase();
+        }
+        assert_eq!(&out[..nb], b".sub.example.com");
+    }
 }
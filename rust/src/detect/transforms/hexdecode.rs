@@ -0,0 +1,265 @@
+/* Copyright (C) 2025 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use crate::detect::transforms::{
+    inspection_buffer_set_empty, SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate,
+};
+use crate::detect::SIGMATCH_OPTIONAL_OPT;
+use suricata_sys::sys::{
+    DetectEngineCtx, DetectEngineThreadCtx, InspectionBuffer, SCDetectHelperTransformRegister,
+    SCDetectSignatureAddTransform, SCTransformTableElmt, Signature,
+};
+
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_void};
+
+static mut G_TRANSFORM_FROM_HEX_ID: c_int = 0;
+
+#[derive(Debug, Default, PartialEq)]
+#[repr(C)]
+struct DetectTransformFromHexData {
+    // skip whitespace and colons between the hex digits
+    ignore_separators: bool,
+}
+
+fn from_hex_parse_do(i: &str) -> Option<DetectTransformFromHexData> {
+    match i.trim() {
+        "" => Some(DetectTransformFromHexData::default()),
+        "ignore_separators" => Some(DetectTransformFromHexData {
+            ignore_separators: true,
+        }),
+        _ => {
+            SCLogError!("Unknown from_hex option {}", i);
+            None
+        }
+    }
+}
+
+unsafe fn from_hex_parse(raw: *const std::os::raw::c_char) -> *mut c_void {
+    let ctx = if raw.is_null() {
+        Some(DetectTransformFromHexData::default())
+    } else if let Ok(s) = CStr::from_ptr(raw).to_str() {
+        from_hex_parse_do(s)
+    } else {
+        None
+    };
+    if let Some(ctx) = ctx {
+        return Box::into_raw(Box::new(ctx)) as *mut _;
+    }
+    return std::ptr::null_mut();
+}
+
+unsafe extern "C" fn from_hex_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, opt_str: *const std::os::raw::c_char,
+) -> c_int {
+    let ctx = from_hex_parse(opt_str);
+    if ctx.is_null() {
+        return -1;
+    }
+    let r = SCDetectSignatureAddTransform(s, G_TRANSFORM_FROM_HEX_ID, ctx);
+    if r != 0 {
+        from_hex_free(de, ctx);
+    }
+    return r;
+}
+
+unsafe extern "C" fn from_hex_free(_de: *mut DetectEngineCtx, ctx: *mut c_void) {
+    std::mem::drop(Box::from_raw(ctx as *mut DetectTransformFromHexData));
+}
+
+unsafe extern "C" fn from_hex_id(data: *mut *const u8, length: *mut u32, ctx: *mut c_void) {
+    if data.is_null() || length.is_null() || ctx.is_null() {
+        return;
+    }
+
+    // flat structure, see from_base64
+    *data = ctx as *const u8;
+    *length = std::mem::size_of::<DetectTransformFromHexData>() as u32;
+}
+
+fn hex_value(i: u8) -> Option<u8> {
+    match i {
+        b'0'..=b'9' => Some(i - b'0'),
+        b'A'..=b'F' => Some(i - b'A' + 10),
+        b'a'..=b'f' => Some(i - b'a' + 10),
+        _ => None,
+    }
+}
+
+/// Decodes the hex digits of `input` to `output`, returning the number of
+/// bytes decoded or `None` for non-hex input or an odd number of digits.
+fn from_hex_transform_do(
+    input: &[u8], output: &mut [u8], ctx: &DetectTransformFromHexData,
+) -> Option<u32> {
+    let mut high = None;
+    let mut nb = 0;
+    for &i in input.iter() {
+        if ctx.ignore_separators && matches!(i, b' ' | b'\t' | b'\r' | b'\n' | b':') {
+            continue;
+        }
+        let v = hex_value(i)?;
+        match high.take() {
+            None => high = Some(v),
+            Some(h) => {
+                // output never gets ahead of input, so they may overlap
+                output[nb] = (h << 4) | v;
+                nb += 1;
+            }
+        }
+    }
+    if high.is_some() {
+        return None;
+    }
+    return Some(nb as u32);
+}
+
+unsafe extern "C" fn from_hex_transform(
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, ctx: *mut c_void,
+) {
+    let input = (*buffer).inspect;
+    let input_len = (*buffer).inspect_len;
+    if input.is_null() || input_len == 0 {
+        return;
+    }
+    let input = build_slice!(input, input_len as usize);
+    if input_len / 2 == 0 {
+        // a single character decodes to nothing
+        inspection_buffer_set_empty(buffer);
+        return;
+    }
+
+    let output = SCInspectionBufferCheckAndExpand(buffer, input_len / 2);
+    if output.is_null() {
+        // allocation failure
+        return;
+    }
+    let output = std::slice::from_raw_parts_mut(output, (input_len / 2) as usize);
+
+    let ctx = cast_pointer!(ctx, DetectTransformFromHexData);
+    // malformed input leaves an empty buffer, so content cannot match
+    let output_len = from_hex_transform_do(input, output, ctx).unwrap_or(0);
+
+    SCInspectionBufferTruncate(buffer, output_len);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn DetectTransformFromHexRegister() {
+    let kw = SCTransformTableElmt {
+        name: b"from_hex\0".as_ptr() as *const libc::c_char,
+        desc: b"modify buffer via hex decoding before inspection\0".as_ptr() as *const libc::c_char,
+        url: b"/rules/transforms.html#from-hex\0".as_ptr() as *const libc::c_char,
+        Setup: Some(from_hex_setup),
+        flags: SIGMATCH_OPTIONAL_OPT,
+        Transform: Some(from_hex_transform),
+        Free: Some(from_hex_free),
+        TransformValidate: None,
+        TransformId: Some(from_hex_id),
+    };
+    unsafe {
+        G_TRANSFORM_FROM_HEX_ID = SCDetectHelperTransformRegister(&kw);
+        if G_TRANSFORM_FROM_HEX_ID < 0 {
+            SCLogWarning!("Failed registering transform from_hex");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::transforms::mock::transform_sample;
+
+    fn decode(input: &[u8], ctx: &DetectTransformFromHexData) -> Option<Vec<u8>> {
+        let mut out = vec![0; input.len() / 2];
+        let nb = from_hex_transform_do(input, &mut out, ctx)?;
+        out.truncate(nb as usize);
+        return Some(out);
+    }
+
+    #[test]
+    fn test_from_hex_parse() {
+        assert_eq!(
+            from_hex_parse_do(""),
+            Some(DetectTransformFromHexData {
+                ignore_separators: false
+            })
+        );
+        assert_eq!(
+            from_hex_parse_do(" ignore_separators"),
+            Some(DetectTransformFromHexData {
+                ignore_separators: true
+            })
+        );
+        assert!(from_hex_parse_do("colons").is_none());
+    }
+
+    #[test]
+    fn test_from_hex_transform() {
+        let strict = DetectTransformFromHexData::default();
+        assert_eq!(decode(b"deadbeef", &strict).unwrap(), b"\xde\xad\xbe\xef");
+        assert_eq!(decode(b"DEADBEEF", &strict).unwrap(), b"\xde\xad\xbe\xef");
+        assert_eq!(decode(b"4142aB", &strict).unwrap(), b"AB\xab");
+        // separators are only skipped when asked to
+        assert!(decode(b"de:ad:be:ef", &strict).is_none());
+
+        let sep = from_hex_parse_do("ignore_separators").unwrap();
+        assert_eq!(decode(b"de:ad:be:ef", &sep).unwrap(), b"\xde\xad\xbe\xef");
+        assert_eq!(
+            decode(b"DE AD\r\n\tBE EF", &sep).unwrap(),
+            b"\xde\xad\xbe\xef"
+        );
+        assert_eq!(decode(b"::", &sep).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_from_hex_transform_malformed() {
+        let strict = DetectTransformFromHexData::default();
+        let sep = from_hex_parse_do("ignore_separators").unwrap();
+        // odd number of digits
+        assert!(decode(b"abc", &strict).is_none());
+        assert!(decode(b"a", &strict).is_none());
+        assert!(decode(b"ab:c", &sep).is_none());
+        assert!(decode(b"a:b:c:", &sep).is_none());
+        // not hex
+        assert!(decode(b"zz", &strict).is_none());
+        assert!(decode(b"0x41", &strict).is_none());
+        assert!(decode(b"de-ad", &sep).is_none());
+        assert!(decode("é1".as_bytes(), &strict).is_none());
+    }
+
+    #[test]
+    fn test_from_hex_transform_in_place() {
+        let ctx = from_hex_parse_do("ignore_separators").unwrap();
+        let mut buf = b"41:42:43".to_vec();
+        let still_buf = unsafe { std::slice::from_raw_parts(buf.as_ptr(), buf.len()) };
+        assert_eq!(from_hex_transform_do(still_buf, &mut buf, &ctx), Some(3));
+        assert_eq!(&buf[..3], b"ABC");
+    }
+
+    #[test]
+    fn test_from_hex_transform_buffer() {
+        let mut ctx = from_hex_parse_do("").unwrap();
+        let ctx_ptr = &mut ctx as *mut DetectTransformFromHexData as *mut c_void;
+        assert_eq!(
+            transform_sample(from_hex_transform, ctx_ptr, b"4142"),
+            b"AB"
+        );
+        // the input is not left in the buffer
+        assert_eq!(transform_sample(from_hex_transform, ctx_ptr, b"a"), b"");
+        assert_eq!(transform_sample(from_hex_transform, ctx_ptr, b"4"), b"");
+        assert_eq!(transform_sample(from_hex_transform, ctx_ptr, b"abc"), b"");
+    }
+}
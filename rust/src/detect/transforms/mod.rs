@@ -23,7 +23,88 @@ pub mod compress_whitespace;
 pub mod domain;
 pub mod dotprefix;
 pub mod hash;
+pub mod hexdecode;
 pub mod http_headers;
+pub mod normalize_newlines;
+pub mod pad;
+pub mod regex_replace;
+pub mod reverse;
+pub mod slice;
+pub mod strip_length_prefix;
 pub mod strip_whitespace;
+pub mod truncate;
+pub mod unfold;
 pub mod urldecode;
 pub mod xor;
+
+use suricata_sys::sys::InspectionBuffer;
+// the engine functions for the transforms, their mock versions in tests
+#[cfg(not(test))]
+pub(crate) use suricata_sys::sys::{SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate};
+
+#[cfg(test)]
+pub(crate) use crate::detect::transforms::mock::{
+    SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate,
+};
+
+/// Empties `buffer`. Truncating expects the buffer to have its own data,
+/// which it does not have yet for the first transform of a list.
+pub(crate) unsafe fn inspection_buffer_set_empty(buffer: *mut InspectionBuffer) {
+    if SCInspectionBufferCheckAndExpand(buffer, 1).is_null() {
+        // allocation failure
+        return;
+    }
+    SCInspectionBufferTruncate(buffer, 0);
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::os::raw::c_void;
+    use suricata_sys::sys::{DetectEngineThreadCtx, InspectionBuffer};
+
+    type TransformFn =
+        unsafe extern "C" fn(*mut DetectEngineThreadCtx, *mut InspectionBuffer, *mut c_void);
+
+    // Test versions of the engine functions, which check the buffer
+    // has its own data when truncated, like the engine does
+    #[allow(non_snake_case)]
+    pub(crate) unsafe fn SCInspectionBufferCheckAndExpand(
+        buffer: *mut InspectionBuffer, min_size: u32,
+    ) -> *mut u8 {
+        if (*buffer).size < min_size {
+            let ptr = libc::realloc((*buffer).buf as *mut c_void, min_size as usize);
+            if ptr.is_null() {
+                return std::ptr::null_mut();
+            }
+            (*buffer).buf = ptr as *mut u8;
+            (*buffer).size = min_size;
+        }
+        return (*buffer).buf;
+    }
+
+    #[allow(non_snake_case)]
+    pub(crate) unsafe fn SCInspectionBufferTruncate(buffer: *mut InspectionBuffer, buf_len: u32) {
+        assert!(!(*buffer).buf.is_null());
+        assert!(buf_len <= (*buffer).size);
+        (*buffer).inspect = (*buffer).buf;
+        (*buffer).inspect_len = buf_len;
+        (*buffer).initialized = true;
+    }
+
+    /// Runs `transform` with `ctx` on a buffer of the list, without data of
+    /// its own yet, as for the first transform, and returns the result.
+    pub(crate) fn transform_sample(
+        transform: TransformFn, ctx: *mut c_void, input: &[u8],
+    ) -> Vec<u8> {
+        let mut ibuf: InspectionBuffer = unsafe { std::mem::zeroed() };
+        ibuf.inspect = input.as_ptr();
+        ibuf.inspect_len = input.len() as u32;
+        unsafe {
+            transform(std::ptr::null_mut(), &mut ibuf, ctx);
+            let inspect = ibuf.inspect;
+            let output = build_slice!(inspect, ibuf.inspect_len as usize).to_vec();
+            libc::free(ibuf.buf as *mut c_void);
+            output
+        }
+    }
+}
@@ -0,0 +1,135 @@
+/* Copyright (C) 2026 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use crate::detect::SIGMATCH_NOOPT;
+use suricata_sys::sys::{
+    DetectEngineCtx, DetectEngineThreadCtx, InspectionBuffer, SCDetectHelperTransformRegister,
+    SCDetectSignatureAddTransform, SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate,
+    SCTransformTableElmt, Signature,
+};
+
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+static mut G_TRANSFORM_NORMALIZE_NEWLINES_ID: c_int = 0;
+
+unsafe extern "C" fn normalize_newlines_setup(
+    _de: *mut DetectEngineCtx, s: *mut Signature, _raw: *const std::os::raw::c_char,
+) -> c_int {
+    return SCDetectSignatureAddTransform(s, G_TRANSFORM_NORMALIZE_NEWLINES_ID, ptr::null_mut());
+}
+
+/// Turns CRLF and lone CR line endings into LF.
+fn normalize_newlines_transform_do(input: &[u8], output: &mut [u8]) -> u32 {
+    let mut nb = 0;
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] == b'\r' {
+            output[nb] = b'\n';
+            if input.get(i + 1) == Some(&b'\n') {
+                i += 1;
+            }
+        } else {
+            // output never gets ahead of input, so they may overlap
+            output[nb] = input[i];
+        }
+        nb += 1;
+        i += 1;
+    }
+    return nb as u32;
+}
+
+unsafe extern "C" fn normalize_newlines_transform(
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, _ctx: *mut c_void,
+) {
+    let input = (*buffer).inspect;
+    let input_len = (*buffer).inspect_len;
+    if input.is_null() || input_len == 0 {
+        return;
+    }
+    let input = build_slice!(input, input_len as usize);
+
+    let output = SCInspectionBufferCheckAndExpand(buffer, input_len);
+    if output.is_null() {
+        // allocation failure
+        return;
+    }
+    let output = std::slice::from_raw_parts_mut(output, input_len as usize);
+
+    let output_len = normalize_newlines_transform_do(input, output);
+
+    SCInspectionBufferTruncate(buffer, output_len);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn DetectTransformNormalizeNewlinesRegister() {
+    let kw = SCTransformTableElmt {
+        name: b"normalize_newlines\0".as_ptr() as *const libc::c_char,
+        desc: b"modify buffer to turn CRLF and CR line endings into LF before inspection\0".as_ptr()
+            as *const libc::c_char,
+        url: b"/rules/transforms.html#normalize-newlines\0".as_ptr() as *const libc::c_char,
+        Setup: Some(normalize_newlines_setup),
+        flags: SIGMATCH_NOOPT,
+        Transform: Some(normalize_newlines_transform),
+        Free: None,
+        TransformValidate: None,
+        TransformId: None,
+    };
+    unsafe {
+        G_TRANSFORM_NORMALIZE_NEWLINES_ID = SCDetectHelperTransformRegister(&kw);
+        if G_TRANSFORM_NORMALIZE_NEWLINES_ID < 0 {
+            SCLogWarning!("Failed registering transform normalize_newlines");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(input: &[u8]) -> Vec<u8> {
+        let mut out = vec![0; input.len()];
+        let nb = normalize_newlines_transform_do(input, &mut out);
+        out.truncate(nb as usize);
+        return out;
+    }
+
+    #[test]
+    fn test_normalize_newlines_transform() {
+        assert_eq!(normalize(b"a\r\nb\rc\nd"), b"a\nb\nc\nd");
+        assert_eq!(normalize(b"\r\r\n\n\r"), b"\n\n\n\n");
+        assert_eq!(normalize(b"\n\r\n"), b"\n\n");
+
+        // nothing to normalize
+        assert_eq!(normalize(b"a\nb\n"), b"a\nb\n");
+        assert_eq!(normalize(b"no newline"), b"no newline");
+    }
+
+    #[test]
+    fn test_normalize_newlines_transform_trailing_cr() {
+        assert_eq!(normalize(b"line\r"), b"line\n");
+        assert_eq!(normalize(b"\r"), b"\n");
+    }
+
+    #[test]
+    fn test_normalize_newlines_transform_in_place() {
+        let mut buf = b"GET / HTTP/1.1\r\nHost: a\r\n\r\n".to_vec();
+        let still_buf = unsafe { std::slice::from_raw_parts(buf.as_ptr(), buf.len()) };
+        assert_eq!(normalize_newlines_transform_do(still_buf, &mut buf), 24);
+        assert_eq!(&buf[..24], b"GET / HTTP/1.1\nHost: a\n\n");
+    }
+}
@@ -0,0 +1,312 @@
+/* Copyright (C) 2026 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use suricata_sys::sys::{
+    DetectEngineCtx, DetectEngineThreadCtx, InspectionBuffer, SCDetectHelperTransformRegister,
+    SCDetectSignatureAddTransform, SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate,
+    SCTransformTableElmt, Signature,
+};
+
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+static mut G_TRANSFORM_PAD_LEFT_ID: c_int = 0;
+static mut G_TRANSFORM_PAD_RIGHT_ID: c_int = 0;
+
+#[derive(Debug, PartialEq)]
+#[repr(C)]
+struct DetectTransformPadData {
+    // length the buffer is padded to
+    len: u16,
+    // byte the buffer is padded with
+    fill: u8,
+    // len and fill serialized for pad_id, without the padding of the struct
+    id: [u8; 3],
+}
+
+impl DetectTransformPadData {
+    fn new(len: u16, fill: u8) -> Self {
+        let l = len.to_le_bytes();
+        Self {
+            len,
+            fill,
+            id: [l[0], l[1], fill],
+        }
+    }
+}
+
+fn pad_parse_fill(s: &str) -> Option<u8> {
+    if let Some(hex) = s.strip_prefix("0x") {
+        return u8::from_str_radix(hex, 16).ok();
+    }
+    return s.parse::<u8>().ok();
+}
+
+/// Parses `<len>[,<fill>]`, the fill byte defaulting to 0.
+fn pad_parse_do(name: &str, i: &str) -> Option<DetectTransformPadData> {
+    let (len, fill) = match i.split_once(',') {
+        Some((len, fill)) => (len.trim(), Some(fill.trim())),
+        None => (i.trim(), None),
+    };
+    let len = match len.parse::<u16>() {
+        Ok(len) if len > 0 => len,
+        _ => {
+            SCLogError!(
+                "{} transform expects a length between 1 and {}: {}",
+                name,
+                u16::MAX,
+                len
+            );
+            return None;
+        }
+    };
+    let fill = match fill.map(pad_parse_fill) {
+        None => 0,
+        Some(Some(fill)) => fill,
+        Some(None) => {
+            SCLogError!("{} transform expects a fill byte like 0x20: {}", name, i);
+            return None;
+        }
+    };
+    return Some(DetectTransformPadData::new(len, fill));
+}
+
+unsafe fn pad_parse(name: &str, raw: *const std::os::raw::c_char) -> *mut c_void {
+    if raw.is_null() {
+        return std::ptr::null_mut();
+    }
+    if let Ok(s) = CStr::from_ptr(raw).to_str() {
+        if let Some(ctx) = pad_parse_do(name, s) {
+            return Box::into_raw(Box::new(ctx)) as *mut _;
+        }
+    }
+    return std::ptr::null_mut();
+}
+
+unsafe fn pad_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, opt_str: *const std::os::raw::c_char, name: &str,
+    id: c_int,
+) -> c_int {
+    let ctx = pad_parse(name, opt_str);
+    if ctx.is_null() {
+        return -1;
+    }
+    let r = SCDetectSignatureAddTransform(s, id, ctx);
+    if r != 0 {
+        pad_free(de, ctx);
+    }
+    return r;
+}
+
+unsafe extern "C" fn pad_left_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, opt_str: *const std::os::raw::c_char,
+) -> c_int {
+    return pad_setup(de, s, opt_str, "pad_left", G_TRANSFORM_PAD_LEFT_ID);
+}
+
+unsafe extern "C" fn pad_right_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, opt_str: *const std::os::raw::c_char,
+) -> c_int {
+    return pad_setup(de, s, opt_str, "pad_right", G_TRANSFORM_PAD_RIGHT_ID);
+}
+
+unsafe extern "C" fn pad_free(_de: *mut DetectEngineCtx, ctx: *mut c_void) {
+    std::mem::drop(Box::from_raw(ctx as *mut DetectTransformPadData));
+}
+
+unsafe extern "C" fn pad_id(data: *mut *const u8, length: *mut u32, ctx: *mut c_void) {
+    if data.is_null() || length.is_null() || ctx.is_null() {
+        return;
+    }
+
+    let ctx = cast_pointer!(ctx, DetectTransformPadData);
+    *data = ctx.id.as_ptr();
+    *length = ctx.id.len() as u32;
+}
+
+/// Returns `input` padded with the fill byte up to the length of `ctx`, in
+/// front of it if `left` is set, or None if it is already long enough.
+fn pad_transform_do(input: &[u8], ctx: &DetectTransformPadData, left: bool) -> Option<Vec<u8>> {
+    let len = ctx.len as usize;
+    if input.len() >= len {
+        return None;
+    }
+    let mut output = Vec::with_capacity(len);
+    if left {
+        output.resize(len - input.len(), ctx.fill);
+        output.extend_from_slice(input);
+    } else {
+        output.extend_from_slice(input);
+        output.resize(len, ctx.fill);
+    }
+    return Some(output);
+}
+
+unsafe fn pad_transform(buffer: *mut InspectionBuffer, ctx: *mut c_void, left: bool) {
+    let input = (*buffer).inspect;
+    let input_len = (*buffer).inspect_len;
+    // an empty buffer is padded too
+    let input = if input.is_null() {
+        &[]
+    } else {
+        build_slice!(input, input_len as usize)
+    };
+
+    let ctx = cast_pointer!(ctx, DetectTransformPadData);
+    // the padded data is built aside as growing the buffer may move the
+    // input it points to
+    let padded = match pad_transform_do(input, ctx, left) {
+        Some(padded) => padded,
+        None => return,
+    };
+
+    let output = SCInspectionBufferCheckAndExpand(buffer, padded.len() as u32);
+    if output.is_null() {
+        // allocation failure
+        return;
+    }
+    ptr::copy_nonoverlapping(padded.as_ptr(), output, padded.len());
+
+    SCInspectionBufferTruncate(buffer, padded.len() as u32);
+}
+
+unsafe extern "C" fn pad_left_transform(
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, ctx: *mut c_void,
+) {
+    pad_transform(buffer, ctx, true);
+}
+
+unsafe extern "C" fn pad_right_transform(
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, ctx: *mut c_void,
+) {
+    pad_transform(buffer, ctx, false);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn DetectTransformPadLeftRegister() {
+    let kw = SCTransformTableElmt {
+        name: b"pad_left\0".as_ptr() as *const libc::c_char,
+        desc: b"modify buffer to pad it at the front to a fixed length before inspection\0".as_ptr()
+            as *const libc::c_char,
+        url: b"/rules/transforms.html#pad-left-and-pad-right\0".as_ptr() as *const libc::c_char,
+        Setup: Some(pad_left_setup),
+        flags: 0,
+        Transform: Some(pad_left_transform),
+        Free: Some(pad_free),
+        TransformValidate: None,
+        TransformId: Some(pad_id),
+    };
+    G_TRANSFORM_PAD_LEFT_ID = SCDetectHelperTransformRegister(&kw);
+    if G_TRANSFORM_PAD_LEFT_ID < 0 {
+        SCLogWarning!("Failed registering transform pad_left");
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn DetectTransformPadRightRegister() {
+    let kw = SCTransformTableElmt {
+        name: b"pad_right\0".as_ptr() as *const libc::c_char,
+        desc: b"modify buffer to pad it at the end to a fixed length before inspection\0".as_ptr()
+            as *const libc::c_char,
+        url: b"/rules/transforms.html#pad-left-and-pad-right\0".as_ptr() as *const libc::c_char,
+        Setup: Some(pad_right_setup),
+        flags: 0,
+        Transform: Some(pad_right_transform),
+        Free: Some(pad_free),
+        TransformValidate: None,
+        TransformId: Some(pad_id),
+    };
+    G_TRANSFORM_PAD_RIGHT_ID = SCDetectHelperTransformRegister(&kw);
+    if G_TRANSFORM_PAD_RIGHT_ID < 0 {
+        SCLogWarning!("Failed registering transform pad_right");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pad_parse() {
+        assert_eq!(
+            pad_parse_do("pad_left", "8"),
+            Some(DetectTransformPadData::new(8, 0))
+        );
+        assert_eq!(
+            pad_parse_do("pad_left", " 8 , 0x20 "),
+            Some(DetectTransformPadData::new(8, 0x20))
+        );
+        assert_eq!(
+            pad_parse_do("pad_left", "8,48"),
+            Some(DetectTransformPadData::new(8, b'0'))
+        );
+        assert!(pad_parse_do("pad_left", "").is_none());
+        assert!(pad_parse_do("pad_left", "0").is_none());
+        assert!(pad_parse_do("pad_left", "65536").is_none());
+        assert!(pad_parse_do("pad_left", "8,").is_none());
+        assert!(pad_parse_do("pad_left", "8,0x100").is_none());
+        assert!(pad_parse_do("pad_left", "8,space").is_none());
+    }
+
+    #[test]
+    fn test_pad_id() {
+        let mut ctx = pad_parse_do("pad_left", "264,0x20").unwrap();
+        let mut data: *const u8 = ptr::null();
+        let mut length = 0;
+        unsafe {
+            pad_id(&mut data, &mut length, &mut ctx as *mut _ as *mut c_void);
+            assert_eq!(build_slice!(data, length as usize), [0x08, 0x01, 0x20]);
+        }
+    }
+
+    #[test]
+    fn test_pad_left_transform() {
+        let ctx = pad_parse_do("pad_left", "6").unwrap();
+        assert_eq!(
+            pad_transform_do(b"abc", &ctx, true).unwrap(),
+            b"\x00\x00\x00abc"
+        );
+        assert_eq!(pad_transform_do(b"", &ctx, true).unwrap(), [0; 6]);
+    }
+
+    #[test]
+    fn test_pad_right_transform() {
+        let ctx = pad_parse_do("pad_right", "6").unwrap();
+        assert_eq!(
+            pad_transform_do(b"abc", &ctx, false).unwrap(),
+            b"abc\x00\x00\x00"
+        );
+    }
+
+    #[test]
+    fn test_pad_transform_fill() {
+        let ctx = pad_parse_do("pad_left", "5,0x30").unwrap();
+        assert_eq!(pad_transform_do(b"42", &ctx, true).unwrap(), b"00042");
+        let ctx = pad_parse_do("pad_right", "5,0x20").unwrap();
+        assert_eq!(pad_transform_do(b"ab", &ctx, false).unwrap(), b"ab   ");
+    }
+
+    #[test]
+    fn test_pad_transform_long() {
+        let ctx = pad_parse_do("pad_right", "4").unwrap();
+        // at or above the length, the buffer is unchanged
+        assert!(pad_transform_do(b"abcd", &ctx, false).is_none());
+        assert!(pad_transform_do(b"abcdef", &ctx, true).is_none());
+        assert!(pad_transform_do(b"abcdef", &ctx, false).is_none());
+    }
+}
@@ -0,0 +1,262 @@
+/* Copyright (C) 2025 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use crate::detect::transforms::{SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate};
+use crate::detect::SIGMATCH_QUOTES_MANDATORY;
+use regex::bytes::Regex;
+use suricata_sys::sys::{
+    DetectEngineCtx, DetectEngineThreadCtx, InspectionBuffer, SCDetectHelperTransformRegister,
+    SCDetectSignatureAddTransform, SCTransformTableElmt, Signature,
+};
+
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_void};
+
+static mut G_TRANSFORM_REGEX_REPLACE_ID: c_int = 0;
+
+#[derive(Debug)]
+struct DetectTransformRegexReplaceData {
+    // compiled once at rule load, shared by all the inspected buffers
+    regex: Regex,
+    replacement: Vec<u8>,
+    // the option string, identifying the transform
+    id: Vec<u8>,
+}
+
+/// Splits `<pattern>/<replacement>` at the first `/` not escaped by a
+/// backslash. `\/` in the pattern stands for a literal `/`.
+fn regex_replace_split(i: &str) -> Option<(String, &str)> {
+    let mut pattern = String::with_capacity(i.len());
+    let mut chars = i.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, '/')) => pattern.push('/'),
+                Some((_, n)) => {
+                    pattern.push('\\');
+                    pattern.push(n);
+                }
+                None => pattern.push('\\'),
+            },
+            '/' => return Some((pattern, &i[idx + 1..])),
+            _ => pattern.push(c),
+        }
+    }
+    return None;
+}
+
+fn regex_replace_parse_do(i: &str) -> Option<DetectTransformRegexReplaceData> {
+    let (pattern, replacement) = match regex_replace_split(i) {
+        Some(split) => split,
+        None => {
+            SCLogError!("regex_replace transform expects \"<pattern>/<replacement>\"");
+            return None;
+        }
+    };
+    if pattern.is_empty() {
+        SCLogError!("regex_replace transform pattern must not be empty");
+        return None;
+    }
+    match Regex::new(&pattern) {
+        Ok(regex) => Some(DetectTransformRegexReplaceData {
+            regex,
+            replacement: replacement.as_bytes().to_vec(),
+            id: i.as_bytes().to_vec(),
+        }),
+        Err(e) => {
+            SCLogError!("regex_replace transform invalid pattern {}: {}", pattern, e);
+            None
+        }
+    }
+}
+
+unsafe fn regex_replace_parse(raw: *const std::os::raw::c_char) -> *mut c_void {
+    let raw: &CStr = CStr::from_ptr(raw); //unsafe
+    if let Ok(s) = raw.to_str() {
+        if let Some(ctx) = regex_replace_parse_do(s) {
+            let boxed = Box::new(ctx);
+            return Box::into_raw(boxed) as *mut _;
+        }
+    }
+    return std::ptr::null_mut();
+}
+
+unsafe extern "C" fn regex_replace_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, opt_str: *const std::os::raw::c_char,
+) -> c_int {
+    let ctx = regex_replace_parse(opt_str);
+    if ctx.is_null() {
+        return -1;
+    }
+    let r = SCDetectSignatureAddTransform(s, G_TRANSFORM_REGEX_REPLACE_ID, ctx);
+    if r != 0 {
+        regex_replace_free(de, ctx);
+    }
+    return r;
+}
+
+fn regex_replace_transform_do<'a>(
+    input: &'a [u8], ctx: &DetectTransformRegexReplaceData,
+) -> Cow<'a, [u8]> {
+    return ctx.regex.replace_all(input, ctx.replacement.as_slice());
+}
+
+unsafe extern "C" fn regex_replace_transform(
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, ctx: *mut c_void,
+) {
+    let input = (*buffer).inspect;
+    let input_len = (*buffer).inspect_len;
+    if input.is_null() || input_len == 0 {
+        return;
+    }
+    let input = build_slice!(input, input_len as usize);
+
+    let ctx = cast_pointer!(ctx, DetectTransformRegexReplaceData);
+    let replaced = match regex_replace_transform_do(input, ctx) {
+        // no match, the buffer is unchanged
+        Cow::Borrowed(_) => return,
+        Cow::Owned(replaced) => replaced,
+    };
+
+    let output = SCInspectionBufferCheckAndExpand(buffer, replaced.len() as u32);
+    if output.is_null() {
+        // allocation failure
+        return;
+    }
+    let output = std::slice::from_raw_parts_mut(output, replaced.len());
+    output.copy_from_slice(&replaced);
+
+    SCInspectionBufferTruncate(buffer, replaced.len() as u32);
+}
+
+unsafe extern "C" fn regex_replace_free(_de: *mut DetectEngineCtx, ctx: *mut c_void) {
+    std::mem::drop(Box::from_raw(ctx as *mut DetectTransformRegexReplaceData));
+}
+
+unsafe extern "C" fn regex_replace_id(data: *mut *const u8, length: *mut u32, ctx: *mut c_void) {
+    if data.is_null() || length.is_null() || ctx.is_null() {
+        return;
+    }
+
+    let ctx = cast_pointer!(ctx, DetectTransformRegexReplaceData);
+    *data = ctx.id.as_ptr();
+    *length = ctx.id.len() as u32;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn DetectTransformRegexReplaceRegister() {
+    let kw = SCTransformTableElmt {
+        name: b"regex_replace\0".as_ptr() as *const libc::c_char,
+        desc: b"modify buffer via regex substitution before inspection\0".as_ptr()
+            as *const libc::c_char,
+        url: b"/rules/transforms.html#regex-replace\0".as_ptr() as *const libc::c_char,
+        Setup: Some(regex_replace_setup),
+        flags: SIGMATCH_QUOTES_MANDATORY,
+        Transform: Some(regex_replace_transform),
+        Free: Some(regex_replace_free),
+        TransformValidate: None,
+        TransformId: Some(regex_replace_id),
+    };
+    unsafe {
+        G_TRANSFORM_REGEX_REPLACE_ID = SCDetectHelperTransformRegister(&kw);
+        if G_TRANSFORM_REGEX_REPLACE_ID < 0 {
+            SCLogWarning!("Failed registering transform regex_replace");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::transforms::mock::transform_sample;
+
+    #[test]
+    fn test_regex_replace_parse() {
+        let ctx = regex_replace_parse_do(r"\s+/ ").unwrap();
+        assert_eq!(ctx.regex.as_str(), r"\s+");
+        assert_eq!(ctx.replacement, b" ");
+
+        // escaped delimiter in the pattern, none needed in the replacement
+        let ctx = regex_replace_parse_do(r"\\\/+/\/").unwrap();
+        assert_eq!(ctx.regex.as_str(), r"\\/+");
+        assert_eq!(ctx.replacement, br"\/");
+
+        let ctx = regex_replace_parse_do("a/").unwrap();
+        assert!(ctx.replacement.is_empty());
+
+        assert!(regex_replace_parse_do("").is_none());
+        assert!(regex_replace_parse_do("abc").is_none());
+        assert!(regex_replace_parse_do(r"abc\/").is_none());
+        assert!(regex_replace_parse_do("/abc").is_none());
+        assert!(regex_replace_parse_do("(abc/x").is_none());
+        assert!(regex_replace_parse_do("a{2,1}/x").is_none());
+    }
+
+    #[test]
+    fn test_regex_replace_transform() {
+        let ctx = regex_replace_parse_do(r"\s+/ ").unwrap();
+        assert_eq!(
+            regex_replace_transform_do(b"GET  /index.html \t\r\n HTTP/1.1", &ctx),
+            &b"GET /index.html HTTP/1.1"[..]
+        );
+        // the buffer is borrowed as is when nothing matches
+        assert!(matches!(
+            regex_replace_transform_do(b"nospace", &ctx),
+            Cow::Borrowed(_)
+        ));
+
+        let ctx = regex_replace_parse_do(r"(\w+)=(\w+)/$2=$1").unwrap();
+        assert_eq!(
+            regex_replace_transform_do(b"a=1&bb=22", &ctx),
+            &b"1=a&22=bb"[..]
+        );
+    }
+
+    #[test]
+    fn test_regex_replace_multiple_buffers() {
+        let ctx = regex_replace_parse_do("[0-9]+/N").unwrap();
+        // one parsed context is applied to several buffers in turn
+        for (input, exp) in [
+            (&b"id 1234"[..], &b"id N"[..]),
+            (b"1 2 3", b"N N N"),
+            (b"none", b"none"),
+        ] {
+            assert_eq!(regex_replace_transform_do(input, &ctx), exp);
+        }
+    }
+
+    #[test]
+    fn test_regex_replace_transform_reuses_regex() {
+        let mut ctx = regex_replace_parse_do(r"\s+/ ").unwrap();
+        // the regex compiled at rule load, a recompiled one would not
+        // share its pattern
+        let pattern = ctx.regex.as_str().as_ptr();
+        let ctx_ptr = &mut ctx as *mut DetectTransformRegexReplaceData as *mut c_void;
+        for (input, exp) in [
+            (&b"a  b"[..], &b"a b"[..]),
+            (b"\tc\r\nd ", b" c d "),
+            (b"none", b"none"),
+        ] {
+            assert_eq!(
+                transform_sample(regex_replace_transform, ctx_ptr, input),
+                exp
+            );
+        }
+        assert_eq!(ctx.regex.as_str().as_ptr(), pattern);
+    }
+}
@@ -0,0 +1,115 @@
+/* Copyright (C) 2025 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use crate::detect::SIGMATCH_NOOPT;
+use suricata_sys::sys::{
+    DetectEngineCtx, DetectEngineThreadCtx, InspectionBuffer, SCDetectHelperTransformRegister,
+    SCDetectSignatureAddTransform, SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate,
+    SCTransformTableElmt, Signature,
+};
+
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+static mut G_TRANSFORM_REVERSE_ID: c_int = 0;
+
+unsafe extern "C" fn reverse_setup(
+    _de: *mut DetectEngineCtx, s: *mut Signature, _raw: *const std::os::raw::c_char,
+) -> c_int {
+    return SCDetectSignatureAddTransform(s, G_TRANSFORM_REVERSE_ID, ptr::null_mut());
+}
+
+fn reverse_transform_do(buf: &mut [u8]) {
+    buf.reverse();
+}
+
+unsafe extern "C" fn reverse_transform(
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, _ctx: *mut c_void,
+) {
+    let input = (*buffer).inspect;
+    let input_len = (*buffer).inspect_len;
+    if input.is_null() || input_len == 0 {
+        return;
+    }
+
+    let output = SCInspectionBufferCheckAndExpand(buffer, input_len);
+    if output.is_null() {
+        // allocation failure
+        return;
+    }
+    // input and output may point to the same data
+    ptr::copy(input, output, input_len as usize);
+    let output = std::slice::from_raw_parts_mut(output, input_len as usize);
+
+    reverse_transform_do(output);
+
+    SCInspectionBufferTruncate(buffer, input_len);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn DetectTransformReverseRegister() {
+    let kw = SCTransformTableElmt {
+        name: b"reverse\0".as_ptr() as *const libc::c_char,
+        desc: b"modify buffer to reverse its bytes before inspection\0".as_ptr()
+            as *const libc::c_char,
+        url: b"/rules/transforms.html#reverse\0".as_ptr() as *const libc::c_char,
+        Setup: Some(reverse_setup),
+        flags: SIGMATCH_NOOPT,
+        Transform: Some(reverse_transform),
+        Free: None,
+        TransformValidate: None,
+        TransformId: None,
+    };
+    unsafe {
+        G_TRANSFORM_REVERSE_ID = SCDetectHelperTransformRegister(&kw);
+        if G_TRANSFORM_REVERSE_ID < 0 {
+            SCLogWarning!("Failed registering transform reverse");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse_transform() {
+        let mut buf = b"\x01\x02\x03\x04".to_vec();
+        reverse_transform_do(&mut buf);
+        assert_eq!(&buf, b"\x04\x03\x02\x01");
+
+        // odd length, the middle byte stays in place
+        let mut buf = b"abcde".to_vec();
+        reverse_transform_do(&mut buf);
+        assert_eq!(&buf, b"edcba");
+
+        let mut buf = b"z".to_vec();
+        reverse_transform_do(&mut buf);
+        assert_eq!(&buf, b"z");
+    }
+
+    #[test]
+    fn test_reverse_transform_twice() {
+        for input in [&b"abcdef"[..], b"abcdefg", b"", b"\x00\xff\x00"] {
+            let mut buf = input.to_vec();
+            reverse_transform_do(&mut buf);
+            assert_eq!(buf.len(), input.len());
+            reverse_transform_do(&mut buf);
+            assert_eq!(buf, input);
+        }
+    }
+}
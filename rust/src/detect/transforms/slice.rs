@@ -0,0 +1,251 @@
+/* Copyright (C) 2026 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use crate::detect::transforms::{
+    inspection_buffer_set_empty, SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate,
+};
+use suricata_sys::sys::{
+    DetectEngineCtx, DetectEngineThreadCtx, InspectionBuffer, SCDetectHelperTransformRegister,
+    SCDetectSignatureAddTransform, SCTransformTableElmt, Signature,
+};
+
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+static mut G_TRANSFORM_SLICE_ID: c_int = 0;
+
+#[derive(Debug, PartialEq)]
+#[repr(C)]
+struct DetectTransformSliceData {
+    // offset of the window in the buffer
+    offset: u32,
+    // length of the window
+    len: u32,
+}
+
+/// Parses `<offset>,<length>`.
+fn slice_parse_do(i: &str) -> Option<DetectTransformSliceData> {
+    let (offset, len) = match i.split_once(',') {
+        Some((offset, len)) => (offset.trim(), len.trim()),
+        None => {
+            SCLogError!("slice transform expects an offset and a length: {}", i);
+            return None;
+        }
+    };
+    let offset = match offset.parse::<u32>() {
+        Ok(offset) => offset,
+        _ => {
+            SCLogError!(
+                "slice transform expects an offset between 0 and {}: {}",
+                u32::MAX,
+                offset
+            );
+            return None;
+        }
+    };
+    let len = match len.parse::<u32>() {
+        Ok(len) => len,
+        _ => {
+            SCLogError!(
+                "slice transform expects a length between 0 and {}: {}",
+                u32::MAX,
+                len
+            );
+            return None;
+        }
+    };
+    return Some(DetectTransformSliceData { offset, len });
+}
+
+unsafe fn slice_parse(raw: *const std::os::raw::c_char) -> *mut c_void {
+    if raw.is_null() {
+        return std::ptr::null_mut();
+    }
+    if let Ok(s) = CStr::from_ptr(raw).to_str() {
+        if let Some(ctx) = slice_parse_do(s) {
+            return Box::into_raw(Box::new(ctx)) as *mut _;
+        }
+    }
+    return std::ptr::null_mut();
+}
+
+unsafe extern "C" fn slice_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, opt_str: *const std::os::raw::c_char,
+) -> c_int {
+    let ctx = slice_parse(opt_str);
+    if ctx.is_null() {
+        return -1;
+    }
+    let r = SCDetectSignatureAddTransform(s, G_TRANSFORM_SLICE_ID, ctx);
+    if r != 0 {
+        slice_free(de, ctx);
+    }
+    return r;
+}
+
+unsafe extern "C" fn slice_free(_de: *mut DetectEngineCtx, ctx: *mut c_void) {
+    std::mem::drop(Box::from_raw(ctx as *mut DetectTransformSliceData));
+}
+
+unsafe extern "C" fn slice_id(data: *mut *const u8, length: *mut u32, ctx: *mut c_void) {
+    if data.is_null() || length.is_null() || ctx.is_null() {
+        return;
+    }
+
+    // flat structure, see from_base64
+    *data = ctx as *const u8;
+    *length = std::mem::size_of::<DetectTransformSliceData>() as u32;
+}
+
+/// Returns the window of `input` at the offset and of the length of `ctx`,
+/// clamped to the bytes available: empty if the offset is past the end, and
+/// shorter if the length goes past it.
+fn slice_transform_do<'a>(input: &'a [u8], ctx: &DetectTransformSliceData) -> &'a [u8] {
+    let start = (ctx.offset as usize).min(input.len());
+    let end = start.saturating_add(ctx.len as usize).min(input.len());
+    return &input[start..end];
+}
+
+unsafe extern "C" fn slice_transform(
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, ctx: *mut c_void,
+) {
+    let input = (*buffer).inspect;
+    let input_len = (*buffer).inspect_len;
+    if input.is_null() || input_len == 0 {
+        return;
+    }
+    let input = build_slice!(input, input_len as usize);
+
+    let ctx = cast_pointer!(ctx, DetectTransformSliceData);
+    let window = slice_transform_do(input, ctx);
+    if window.len() == input.len() {
+        return;
+    }
+    if window.is_empty() {
+        inspection_buffer_set_empty(buffer);
+        return;
+    }
+    let (window, window_len) = (window.as_ptr(), window.len());
+
+    let output = SCInspectionBufferCheckAndExpand(buffer, window_len as u32);
+    if output.is_null() {
+        // allocation failure
+        return;
+    }
+    // input and output may point to the same data
+    ptr::copy(window, output, window_len);
+
+    SCInspectionBufferTruncate(buffer, window_len as u32);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn DetectTransformSliceRegister() {
+    let kw = SCTransformTableElmt {
+        name: b"slice\0".as_ptr() as *const libc::c_char,
+        desc: b"modify buffer to keep the bytes at an offset and of a length before inspection\0"
+            .as_ptr() as *const libc::c_char,
+        url: b"/rules/transforms.html#slice\0".as_ptr() as *const libc::c_char,
+        Setup: Some(slice_setup),
+        flags: 0,
+        Transform: Some(slice_transform),
+        Free: Some(slice_free),
+        TransformValidate: None,
+        TransformId: Some(slice_id),
+    };
+    G_TRANSFORM_SLICE_ID = SCDetectHelperTransformRegister(&kw);
+    if G_TRANSFORM_SLICE_ID < 0 {
+        SCLogWarning!("Failed registering transform slice");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::transforms::mock::transform_sample;
+
+    #[test]
+    fn test_slice_parse() {
+        assert_eq!(
+            slice_parse_do("2,4"),
+            Some(DetectTransformSliceData { offset: 2, len: 4 })
+        );
+        assert_eq!(
+            slice_parse_do(" 0 , 0 "),
+            Some(DetectTransformSliceData { offset: 0, len: 0 })
+        );
+        assert!(slice_parse_do("").is_none());
+        assert!(slice_parse_do("4").is_none());
+        assert!(slice_parse_do("4,").is_none());
+        assert!(slice_parse_do(",4").is_none());
+        assert!(slice_parse_do("-1,4").is_none());
+        assert!(slice_parse_do("1,4294967296").is_none());
+        assert!(slice_parse_do("1,2,3").is_none());
+    }
+
+    #[test]
+    fn test_slice_transform() {
+        let ctx = slice_parse_do("6,5").unwrap();
+        assert_eq!(slice_transform_do(b"hello world", &ctx), b"world");
+        let ctx = slice_parse_do("0,5").unwrap();
+        assert_eq!(slice_transform_do(b"hello world", &ctx), b"hello");
+        let ctx = slice_parse_do("4,3").unwrap();
+        assert_eq!(slice_transform_do(b"hello world", &ctx), b"o w");
+    }
+
+    #[test]
+    fn test_slice_transform_offset_past_end() {
+        let ctx = slice_parse_do("11,4").unwrap();
+        assert_eq!(slice_transform_do(b"hello world", &ctx), b"");
+        let ctx = slice_parse_do("4294967295,4294967295").unwrap();
+        assert_eq!(slice_transform_do(b"hello world", &ctx), b"");
+        assert_eq!(slice_transform_do(b"", &ctx), b"");
+    }
+
+    #[test]
+    fn test_slice_transform_length_past_end() {
+        let ctx = slice_parse_do("6,64").unwrap();
+        assert_eq!(slice_transform_do(b"hello world", &ctx), b"world");
+        let ctx = slice_parse_do("0,4294967295").unwrap();
+        assert_eq!(slice_transform_do(b"hello world", &ctx), b"hello world");
+        let ctx = slice_parse_do("6,0").unwrap();
+        assert_eq!(slice_transform_do(b"hello world", &ctx), b"");
+    }
+
+    #[test]
+    fn test_slice_transform_buffer() {
+        let mut ctx = slice_parse_do("6,5").unwrap();
+        let ctx_ptr = &mut ctx as *mut DetectTransformSliceData as *mut c_void;
+        assert_eq!(
+            transform_sample(slice_transform, ctx_ptr, b"hello world"),
+            b"world"
+        );
+        // offset past the end, as the first transform of the list
+        let mut ctx = slice_parse_do("11,4").unwrap();
+        let ctx_ptr = &mut ctx as *mut DetectTransformSliceData as *mut c_void;
+        assert_eq!(
+            transform_sample(slice_transform, ctx_ptr, b"hello world"),
+            b""
+        );
+        let mut ctx = slice_parse_do("6,0").unwrap();
+        let ctx_ptr = &mut ctx as *mut DetectTransformSliceData as *mut c_void;
+        assert_eq!(
+            transform_sample(slice_transform, ctx_ptr, b"hello world"),
+            b""
+        );
+    }
+}
@@ -0,0 +1,227 @@
+/* Copyright (C) 2025 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use crate::detect::transforms::{
+    inspection_buffer_set_empty, SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate,
+};
+use crate::detect::SIGMATCH_OPTIONAL_OPT;
+use suricata_sys::sys::{
+    DetectEngineCtx, DetectEngineThreadCtx, InspectionBuffer, SCDetectHelperTransformRegister,
+    SCDetectSignatureAddTransform, SCTransformTableElmt, Signature,
+};
+
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+static mut G_TRANSFORM_STRIP_LENGTH_PREFIX_ID: c_int = 0;
+
+#[derive(Debug, PartialEq)]
+#[repr(C)]
+struct DetectTransformStripLengthPrefixData {
+    // number of bytes to strip
+    len: u16,
+}
+
+impl Default for DetectTransformStripLengthPrefixData {
+    fn default() -> Self {
+        // a 32-bit length field
+        DetectTransformStripLengthPrefixData { len: 4 }
+    }
+}
+
+fn strip_length_prefix_parse_do(i: &str) -> Option<DetectTransformStripLengthPrefixData> {
+    let i = i.trim();
+    if i.is_empty() {
+        return Some(DetectTransformStripLengthPrefixData::default());
+    }
+    match i.parse::<u16>() {
+        Ok(len) if len > 0 => Some(DetectTransformStripLengthPrefixData { len }),
+        _ => {
+            SCLogError!(
+                "strip_length_prefix transform expects a length between 1 and {}: {}",
+                u16::MAX,
+                i
+            );
+            None
+        }
+    }
+}
+
+unsafe fn strip_length_prefix_parse(raw: *const std::os::raw::c_char) -> *mut c_void {
+    let ctx = if raw.is_null() {
+        Some(DetectTransformStripLengthPrefixData::default())
+    } else if let Ok(s) = CStr::from_ptr(raw).to_str() {
+        strip_length_prefix_parse_do(s)
+    } else {
+        None
+    };
+    if let Some(ctx) = ctx {
+        return Box::into_raw(Box::new(ctx)) as *mut _;
+    }
+    return std::ptr::null_mut();
+}
+
+unsafe extern "C" fn strip_length_prefix_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, opt_str: *const std::os::raw::c_char,
+) -> c_int {
+    let ctx = strip_length_prefix_parse(opt_str);
+    if ctx.is_null() {
+        return -1;
+    }
+    let r = SCDetectSignatureAddTransform(s, G_TRANSFORM_STRIP_LENGTH_PREFIX_ID, ctx);
+    if r != 0 {
+        strip_length_prefix_free(de, ctx);
+    }
+    return r;
+}
+
+unsafe extern "C" fn strip_length_prefix_free(_de: *mut DetectEngineCtx, ctx: *mut c_void) {
+    std::mem::drop(Box::from_raw(
+        ctx as *mut DetectTransformStripLengthPrefixData,
+    ));
+}
+
+unsafe extern "C" fn strip_length_prefix_id(
+    data: *mut *const u8, length: *mut u32, ctx: *mut c_void,
+) {
+    if data.is_null() || length.is_null() || ctx.is_null() {
+        return;
+    }
+
+    // flat structure, see from_base64
+    *data = ctx as *const u8;
+    *length = std::mem::size_of::<DetectTransformStripLengthPrefixData>() as u32;
+}
+
+/// Returns what is left of `input` once the prefix is removed, buffers not
+/// longer than the prefix become empty.
+fn strip_length_prefix_transform_do<'a>(
+    input: &'a [u8], ctx: &DetectTransformStripLengthPrefixData,
+) -> &'a [u8] {
+    return input.get(ctx.len as usize..).unwrap_or(&[]);
+}
+
+unsafe extern "C" fn strip_length_prefix_transform(
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, ctx: *mut c_void,
+) {
+    let input = (*buffer).inspect;
+    let input_len = (*buffer).inspect_len;
+    if input.is_null() || input_len == 0 {
+        return;
+    }
+    let input = build_slice!(input, input_len as usize);
+
+    let ctx = cast_pointer!(ctx, DetectTransformStripLengthPrefixData);
+    let rest = strip_length_prefix_transform_do(input, ctx);
+    if rest.is_empty() {
+        inspection_buffer_set_empty(buffer);
+        return;
+    }
+    let (rest, rest_len) = (rest.as_ptr(), rest.len());
+
+    let output = SCInspectionBufferCheckAndExpand(buffer, rest_len as u32);
+    if output.is_null() {
+        // allocation failure
+        return;
+    }
+    // input and output may point to the same data
+    ptr::copy(rest, output, rest_len);
+
+    SCInspectionBufferTruncate(buffer, rest_len as u32);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn DetectTransformStripLengthPrefixRegister() {
+    let kw = SCTransformTableElmt {
+        name: b"strip_length_prefix\0".as_ptr() as *const libc::c_char,
+        desc: b"modify buffer to remove its length prefix before inspection\0".as_ptr()
+            as *const libc::c_char,
+        url: b"/rules/transforms.html#strip-length-prefix\0".as_ptr() as *const libc::c_char,
+        Setup: Some(strip_length_prefix_setup),
+        flags: SIGMATCH_OPTIONAL_OPT,
+        Transform: Some(strip_length_prefix_transform),
+        Free: Some(strip_length_prefix_free),
+        TransformValidate: None,
+        TransformId: Some(strip_length_prefix_id),
+    };
+    unsafe {
+        G_TRANSFORM_STRIP_LENGTH_PREFIX_ID = SCDetectHelperTransformRegister(&kw);
+        if G_TRANSFORM_STRIP_LENGTH_PREFIX_ID < 0 {
+            SCLogWarning!("Failed registering transform strip_length_prefix");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::transforms::mock::transform_sample;
+
+    #[test]
+    fn test_strip_length_prefix_parse() {
+        assert_eq!(
+            strip_length_prefix_parse_do(""),
+            Some(DetectTransformStripLengthPrefixData { len: 4 })
+        );
+        assert_eq!(
+            strip_length_prefix_parse_do(" 2 "),
+            Some(DetectTransformStripLengthPrefixData { len: 2 })
+        );
+        assert!(strip_length_prefix_parse_do("0").is_none());
+        assert!(strip_length_prefix_parse_do("-2").is_none());
+        assert!(strip_length_prefix_parse_do("65536").is_none());
+        assert!(strip_length_prefix_parse_do("two").is_none());
+    }
+
+    #[test]
+    fn test_strip_length_prefix_transform() {
+        let ctx = strip_length_prefix_parse_do("2").unwrap();
+        assert_eq!(
+            strip_length_prefix_transform_do(b"\x00\x05hello", &ctx),
+            b"hello"
+        );
+
+        let ctx = strip_length_prefix_parse_do("").unwrap();
+        assert_eq!(
+            strip_length_prefix_transform_do(b"\x00\x00\x00\x05hello", &ctx),
+            b"hello"
+        );
+        // nothing left after the prefix
+        assert_eq!(
+            strip_length_prefix_transform_do(b"\x00\x00\x00\x00", &ctx),
+            b""
+        );
+        // buffers shorter than the prefix become empty
+        assert_eq!(strip_length_prefix_transform_do(b"\x00\x05", &ctx), b"");
+        assert_eq!(strip_length_prefix_transform_do(b"", &ctx), b"");
+    }
+
+    #[test]
+    fn test_strip_length_prefix_transform_buffer() {
+        let mut ctx = strip_length_prefix_parse_do("2").unwrap();
+        let ctx_ptr = &mut ctx as *mut DetectTransformStripLengthPrefixData as *mut c_void;
+        let transform = strip_length_prefix_transform;
+        assert_eq!(
+            transform_sample(transform, ctx_ptr, b"\x00\x05hello"),
+            b"hello"
+        );
+        // shorter than the prefix, as the first transform of the list
+        assert_eq!(transform_sample(transform, ctx_ptr, b"\x00"), b"");
+        assert_eq!(transform_sample(transform, ctx_ptr, b"\x00\x00"), b"");
+    }
+}
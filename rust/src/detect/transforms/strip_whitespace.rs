@@ -33,7 +33,7 @@ unsafe extern "C" fn strip_whitespace_setup(
     return SCDetectSignatureAddTransform(s, G_TRANSFORM_STRIP_WHITESPACE_ID, ptr::null_mut());
 }
 
-fn strip_whitespace_transform_do(input: &[u8], output: &mut [u8]) -> u32 {
+pub(crate) fn strip_whitespace_transform_do(input: &[u8], output: &mut [u8]) -> u32 {
     let mut nb = 0;
     for (i, o) in input
         .iter()
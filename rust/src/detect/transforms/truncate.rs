@@ -0,0 +1,209 @@
+/* Copyright (C) 2026 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use crate::detect::transforms::{
+    inspection_buffer_set_empty, SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate,
+};
+use suricata_sys::sys::{
+    DetectEngineCtx, DetectEngineThreadCtx, InspectionBuffer, SCDetectHelperTransformRegister,
+    SCDetectSignatureAddTransform, SCTransformTableElmt, Signature,
+};
+
+use std::ffi::CStr;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+static mut G_TRANSFORM_TRUNCATE_ID: c_int = 0;
+
+#[derive(Debug, PartialEq)]
+#[repr(C)]
+struct DetectTransformTruncateData {
+    // maximum length of the buffer
+    max_len: u32,
+}
+
+fn truncate_parse_do(i: &str) -> Option<DetectTransformTruncateData> {
+    let i = i.trim();
+    match i.parse::<u32>() {
+        Ok(max_len) => Some(DetectTransformTruncateData { max_len }),
+        _ => {
+            SCLogError!(
+                "truncate transform expects a length between 0 and {}: {}",
+                u32::MAX,
+                i
+            );
+            None
+        }
+    }
+}
+
+unsafe fn truncate_parse(raw: *const std::os::raw::c_char) -> *mut c_void {
+    if raw.is_null() {
+        return std::ptr::null_mut();
+    }
+    if let Ok(s) = CStr::from_ptr(raw).to_str() {
+        if let Some(ctx) = truncate_parse_do(s) {
+            return Box::into_raw(Box::new(ctx)) as *mut _;
+        }
+    }
+    return std::ptr::null_mut();
+}
+
+unsafe extern "C" fn truncate_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, opt_str: *const std::os::raw::c_char,
+) -> c_int {
+    let ctx = truncate_parse(opt_str);
+    if ctx.is_null() {
+        return -1;
+    }
+    let r = SCDetectSignatureAddTransform(s, G_TRANSFORM_TRUNCATE_ID, ctx);
+    if r != 0 {
+        truncate_free(de, ctx);
+    }
+    return r;
+}
+
+unsafe extern "C" fn truncate_free(_de: *mut DetectEngineCtx, ctx: *mut c_void) {
+    std::mem::drop(Box::from_raw(ctx as *mut DetectTransformTruncateData));
+}
+
+unsafe extern "C" fn truncate_id(data: *mut *const u8, length: *mut u32, ctx: *mut c_void) {
+    if data.is_null() || length.is_null() || ctx.is_null() {
+        return;
+    }
+
+    // flat structure, see from_base64
+    *data = ctx as *const u8;
+    *length = std::mem::size_of::<DetectTransformTruncateData>() as u32;
+}
+
+/// Returns the first `max_len` bytes of `input`, or all of it if it is
+/// shorter.
+fn truncate_transform_do<'a>(input: &'a [u8], ctx: &DetectTransformTruncateData) -> &'a [u8] {
+    return input.get(..ctx.max_len as usize).unwrap_or(input);
+}
+
+unsafe extern "C" fn truncate_transform(
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, ctx: *mut c_void,
+) {
+    let input = (*buffer).inspect;
+    let input_len = (*buffer).inspect_len;
+    if input.is_null() || input_len == 0 {
+        return;
+    }
+    let input = build_slice!(input, input_len as usize);
+
+    let ctx = cast_pointer!(ctx, DetectTransformTruncateData);
+    let head = truncate_transform_do(input, ctx);
+    if head.len() == input.len() {
+        return;
+    }
+    if head.is_empty() {
+        inspection_buffer_set_empty(buffer);
+        return;
+    }
+    let (head, head_len) = (head.as_ptr(), head.len());
+
+    let output = SCInspectionBufferCheckAndExpand(buffer, head_len as u32);
+    if output.is_null() {
+        // allocation failure
+        return;
+    }
+    // input and output may point to the same data
+    ptr::copy(head, output, head_len);
+
+    SCInspectionBufferTruncate(buffer, head_len as u32);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn DetectTransformTruncateRegister() {
+    let kw = SCTransformTableElmt {
+        name: b"truncate\0".as_ptr() as *const libc::c_char,
+        desc: b"modify buffer to keep at most its first bytes before inspection\0".as_ptr()
+            as *const libc::c_char,
+        url: b"/rules/transforms.html#truncate\0".as_ptr() as *const libc::c_char,
+        Setup: Some(truncate_setup),
+        flags: 0,
+        Transform: Some(truncate_transform),
+        Free: Some(truncate_free),
+        TransformValidate: None,
+        TransformId: Some(truncate_id),
+    };
+    unsafe {
+        G_TRANSFORM_TRUNCATE_ID = SCDetectHelperTransformRegister(&kw);
+        if G_TRANSFORM_TRUNCATE_ID < 0 {
+            SCLogWarning!("Failed registering transform truncate");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::transforms::mock::transform_sample;
+
+    #[test]
+    fn test_truncate_parse() {
+        assert_eq!(
+            truncate_parse_do(" 16 "),
+            Some(DetectTransformTruncateData { max_len: 16 })
+        );
+        assert_eq!(
+            truncate_parse_do("0"),
+            Some(DetectTransformTruncateData { max_len: 0 })
+        );
+        assert!(truncate_parse_do("").is_none());
+        assert!(truncate_parse_do("-1").is_none());
+        assert!(truncate_parse_do("4294967296").is_none());
+        assert!(truncate_parse_do("ten").is_none());
+    }
+
+    #[test]
+    fn test_truncate_transform() {
+        let ctx = truncate_parse_do("5").unwrap();
+        assert_eq!(truncate_transform_do(b"hello world", &ctx), b"hello");
+        // exactly the limit
+        assert_eq!(truncate_transform_do(b"hello", &ctx), b"hello");
+    }
+
+    #[test]
+    fn test_truncate_transform_short() {
+        let ctx = truncate_parse_do("64").unwrap();
+        assert_eq!(truncate_transform_do(b"hello", &ctx), b"hello");
+        assert_eq!(truncate_transform_do(b"", &ctx), b"");
+    }
+
+    #[test]
+    fn test_truncate_transform_zero() {
+        let ctx = truncate_parse_do("0").unwrap();
+        assert_eq!(truncate_transform_do(b"hello", &ctx), b"");
+        assert_eq!(truncate_transform_do(b"", &ctx), b"");
+    }
+
+    #[test]
+    fn test_truncate_transform_buffer() {
+        let mut ctx = truncate_parse_do("5").unwrap();
+        let ctx_ptr = &mut ctx as *mut DetectTransformTruncateData as *mut c_void;
+        assert_eq!(
+            transform_sample(truncate_transform, ctx_ptr, b"hello world"),
+            b"hello"
+        );
+        let mut ctx = truncate_parse_do("0").unwrap();
+        let ctx_ptr = &mut ctx as *mut DetectTransformTruncateData as *mut c_void;
+        assert_eq!(transform_sample(truncate_transform, ctx_ptr, b"hello"), b"");
+    }
+}
@@ -0,0 +1,137 @@
+/* Copyright (C) 2025 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+use crate::detect::SIGMATCH_NOOPT;
+use suricata_sys::sys::{
+    DetectEngineCtx, DetectEngineThreadCtx, InspectionBuffer, SCDetectHelperTransformRegister,
+    SCDetectSignatureAddTransform, SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate,
+    SCTransformTableElmt, Signature,
+};
+
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+static mut G_TRANSFORM_UNFOLD_ID: c_int = 0;
+
+unsafe extern "C" fn unfold_setup(
+    _de: *mut DetectEngineCtx, s: *mut Signature, _raw: *const std::os::raw::c_char,
+) -> c_int {
+    return SCDetectSignatureAddTransform(s, G_TRANSFORM_UNFOLD_ID, ptr::null_mut());
+}
+
+/// Unfolds header lines as in RFC 5322 section 2.2.3: a CRLF followed by a
+/// space or a tab is removed, the whitespace itself is kept.
+fn unfold_transform_do(input: &[u8], output: &mut [u8]) -> u32 {
+    let mut nb = 0;
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] == b'\r'
+            && input.get(i + 1) == Some(&b'\n')
+            && matches!(input.get(i + 2), Some(b' ' | b'\t'))
+        {
+            i += 2;
+            continue;
+        }
+        // output never gets ahead of input, so they may overlap
+        output[nb] = input[i];
+        nb += 1;
+        i += 1;
+    }
+    return nb as u32;
+}
+
+unsafe extern "C" fn unfold_transform(
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, _ctx: *mut c_void,
+) {
+    let input = (*buffer).inspect;
+    let input_len = (*buffer).inspect_len;
+    if input.is_null() || input_len == 0 {
+        return;
+    }
+    let input = build_slice!(input, input_len as usize);
+
+    let output = SCInspectionBufferCheckAndExpand(buffer, input_len);
+    if output.is_null() {
+        // allocation failure
+        return;
+    }
+    let output = std::slice::from_raw_parts_mut(output, input_len as usize);
+
+    let output_len = unfold_transform_do(input, output);
+
+    SCInspectionBufferTruncate(buffer, output_len);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn DetectTransformUnfoldRegister() {
+    let kw = SCTransformTableElmt {
+        name: b"unfold\0".as_ptr() as *const libc::c_char,
+        desc: b"modify buffer to unfold header lines before inspection\0".as_ptr()
+            as *const libc::c_char,
+        url: b"/rules/transforms.html#unfold\0".as_ptr() as *const libc::c_char,
+        Setup: Some(unfold_setup),
+        flags: SIGMATCH_NOOPT,
+        Transform: Some(unfold_transform),
+        Free: None,
+        TransformValidate: None,
+        TransformId: None,
+    };
+    unsafe {
+        G_TRANSFORM_UNFOLD_ID = SCDetectHelperTransformRegister(&kw);
+        if G_TRANSFORM_UNFOLD_ID < 0 {
+            SCLogWarning!("Failed registering transform unfold");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unfold(input: &[u8]) -> Vec<u8> {
+        let mut out = vec![0; input.len()];
+        let nb = unfold_transform_do(input, &mut out);
+        out.truncate(nb as usize);
+        return out;
+    }
+
+    #[test]
+    fn test_unfold_transform() {
+        let buf = b"Subject: This is\r\n a folded\r\n\tsubject line\r\nFrom: a@example.com\r\n";
+        assert_eq!(
+            unfold(buf),
+            b"Subject: This is a folded\tsubject line\r\nFrom: a@example.com\r\n"
+        );
+
+        // nothing to unfold
+        let buf = b"To: b@example.com\r\nFrom: a@example.com\r\nSubject: hi\r\n\r\n";
+        assert_eq!(unfold(buf), buf);
+
+        // only a CRLF followed by whitespace is a fold
+        assert_eq!(unfold(b"a\n b\r c\r\n"), b"a\n b\r c\r\n");
+        assert_eq!(unfold(b"a\r\n\r\n b"), b"a\r\n b");
+        assert_eq!(unfold(b"\r\n "), b" ");
+    }
+
+    #[test]
+    fn test_unfold_transform_in_place() {
+        let mut buf = b"Subject: a\r\n b".to_vec();
+        let still_buf = unsafe { std::slice::from_raw_parts(buf.as_ptr(), buf.len()) };
+        assert_eq!(unfold_transform_do(still_buf, &mut buf), 12);
+        assert_eq!(&buf[..12], b"Subject: a b");
+    }
+}
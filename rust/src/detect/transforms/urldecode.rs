@@ -15,22 +15,85 @@
  * 02110-1301, USA.
  */
 
-use crate::detect::SIGMATCH_NOOPT;
+use crate::detect::SIGMATCH_OPTIONAL_OPT;
 use suricata_sys::sys::{
     DetectEngineCtx, DetectEngineThreadCtx, InspectionBuffer, SCDetectHelperTransformRegister,
     SCDetectSignatureAddTransform, SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate,
     SCTransformTableElmt, Signature,
 };
 
+use std::ffi::CStr;
 use std::os::raw::{c_int, c_void};
-use std::ptr;
 
 static mut G_TRANSFORM_URL_DECODE_ID: c_int = 0;
 
+#[derive(Debug, PartialEq)]
+#[repr(C)]
+struct DetectTransformUrlDecodeData {
+    plus_to_space: bool,
+}
+
+impl Default for DetectTransformUrlDecodeData {
+    fn default() -> Self {
+        DetectTransformUrlDecodeData {
+            plus_to_space: true,
+        }
+    }
+}
+
+fn url_decode_parse_do(i: &str) -> Option<DetectTransformUrlDecodeData> {
+    match i.trim() {
+        "" => Some(DetectTransformUrlDecodeData::default()),
+        "keep_plus" => Some(DetectTransformUrlDecodeData {
+            plus_to_space: false,
+        }),
+        _ => {
+            SCLogError!("Unknown url_decode option {}", i);
+            None
+        }
+    }
+}
+
+unsafe fn url_decode_parse(raw: *const std::os::raw::c_char) -> *mut c_void {
+    let ctx = if raw.is_null() {
+        Some(DetectTransformUrlDecodeData::default())
+    } else if let Ok(s) = CStr::from_ptr(raw).to_str() {
+        url_decode_parse_do(s)
+    } else {
+        None
+    };
+    if let Some(ctx) = ctx {
+        return Box::into_raw(Box::new(ctx)) as *mut _;
+    }
+    return std::ptr::null_mut();
+}
+
 unsafe extern "C" fn url_decode_setup(
-    _de: *mut DetectEngineCtx, s: *mut Signature, _opt: *const std::os::raw::c_char,
+    de: *mut DetectEngineCtx, s: *mut Signature, opt_str: *const std::os::raw::c_char,
 ) -> c_int {
-    return SCDetectSignatureAddTransform(s, G_TRANSFORM_URL_DECODE_ID, ptr::null_mut());
+    let ctx = url_decode_parse(opt_str);
+    if ctx.is_null() {
+        return -1;
+    }
+    let r = SCDetectSignatureAddTransform(s, G_TRANSFORM_URL_DECODE_ID, ctx);
+    if r != 0 {
+        url_decode_free(de, ctx);
+    }
+    return r;
+}
+
+unsafe extern "C" fn url_decode_free(_de: *mut DetectEngineCtx, ctx: *mut c_void) {
+    std::mem::drop(Box::from_raw(ctx as *mut DetectTransformUrlDecodeData));
+}
+
+unsafe extern "C" fn url_decode_id(data: *mut *const u8, length: *mut u32, ctx: *mut c_void) {
+    if data.is_null() || length.is_null() || ctx.is_null() {
+        return;
+    }
+
+    // flat structure, see from_base64
+    *data = ctx as *const u8;
+    *length = std::mem::size_of::<DetectTransformUrlDecodeData>() as u32;
 }
 
 fn hex_value(i: u8) -> Option<u8> {
@@ -41,7 +104,10 @@ fn hex_value(i: u8) -> Option<u8> {
         _ => None,
     }
 }
-fn url_decode_transform_do(input: &[u8], output: &mut [u8]) -> u32 {
+
+fn url_decode_transform_do(
+    input: &[u8], output: &mut [u8], ctx: &DetectTransformUrlDecodeData,
+) -> u32 {
     let mut state = (0u8, 0u8);
     let mut nb = 0;
     for &i in input.iter() {
@@ -68,7 +134,7 @@ fn url_decode_transform_do(input: &[u8], output: &mut [u8]) -> u32 {
         } else if i == b'%' {
             state = (1u8, 0u8);
         } else {
-            if i == b'+' {
+            if i == b'+' && ctx.plus_to_space {
                 output[nb] = b' ';
             } else {
                 output[nb] = i;
@@ -88,7 +154,7 @@ fn url_decode_transform_do(input: &[u8], output: &mut [u8]) -> u32 {
 }
 
 unsafe extern "C" fn url_decode_transform(
-    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, _ctx: *mut c_void,
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, ctx: *mut c_void,
 ) {
     let input = (*buffer).inspect;
     let input_len = (*buffer).inspect_len;
@@ -104,7 +170,8 @@ unsafe extern "C" fn url_decode_transform(
     }
     let output = std::slice::from_raw_parts_mut(output, input_len as usize);
 
-    let out_len = url_decode_transform_do(input, output);
+    let ctx = cast_pointer!(ctx, DetectTransformUrlDecodeData);
+    let out_len = url_decode_transform_do(input, output, ctx);
 
     SCInspectionBufferTruncate(buffer, out_len);
 }
@@ -117,15 +184,15 @@ pub unsafe extern "C" fn DetectTransformUrlDecodeRegister() {
             as *const libc::c_char,
         url: b"/rules/transforms.html#url-decode\0".as_ptr() as *const libc::c_char,
         Setup: Some(url_decode_setup),
-        flags: SIGMATCH_NOOPT,
+        flags: SIGMATCH_OPTIONAL_OPT,
         Transform: Some(url_decode_transform),
-        Free: None,
+        Free: Some(url_decode_free),
         TransformValidate: None,
-        TransformId: None,
+        TransformId: Some(url_decode_id),
     };
     G_TRANSFORM_URL_DECODE_ID = SCDetectHelperTransformRegister(&kw);
     if G_TRANSFORM_URL_DECODE_ID < 0 {
-        SCLogWarning!("Failed registering transform dot_prefix");
+        SCLogWarning!("Failed registering transform url_decode");
     }
 }
 
@@ -133,16 +200,89 @@ pub unsafe extern "C" fn DetectTransformUrlDecodeRegister() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_url_decode_parse() {
+        assert_eq!(
+            url_decode_parse_do(""),
+            Some(DetectTransformUrlDecodeData {
+                plus_to_space: true
+            })
+        );
+        assert_eq!(
+            url_decode_parse_do(" keep_plus"),
+            Some(DetectTransformUrlDecodeData {
+                plus_to_space: false
+            })
+        );
+        assert!(url_decode_parse_do("plus").is_none());
+    }
+
     #[test]
     fn test_url_decode_transform() {
+        let ctx = DetectTransformUrlDecodeData::default();
         let mut buf = Vec::new();
         buf.extend_from_slice(b"Suricata%20is+%27%61wesome%21%27%25%30%30%ZZ%4");
         let mut out = vec![0; buf.len()];
-        let nb = url_decode_transform_do(&buf, &mut out);
+        let nb = url_decode_transform_do(&buf, &mut out, &ctx);
         assert_eq!(&out[..nb as usize], b"Suricata is 'awesome!'%00%ZZ%4");
         // test in place
         let still_buf = unsafe { std::slice::from_raw_parts(buf.as_ptr(), buf.len()) };
-        let nb = url_decode_transform_do(still_buf, &mut buf);
+        let nb = url_decode_transform_do(still_buf, &mut buf, &ctx);
         assert_eq!(&still_buf[..nb as usize], b"Suricata is 'awesome!'%00%ZZ%4");
     }
+
+    #[test]
+    fn test_url_decode_transform_keep_plus() {
+        let ctx = url_decode_parse_do("keep_plus").unwrap();
+        let buf = b"a+b%2Bc%20d";
+        let mut out = vec![0; buf.len()];
+        let nb = url_decode_transform_do(buf, &mut out, &ctx);
+        assert_eq!(&out[..nb as usize], b"a+b+c d");
+    }
+
+    #[test]
+    fn test_url_decode_transform_double_encoded() {
+        // only one level of encoding is removed
+        let ctx = DetectTransformUrlDecodeData::default();
+        let buf = b"%252e%252E%2F";
+        let mut out = vec![0; buf.len()];
+        let nb = url_decode_transform_do(buf, &mut out, &ctx);
+        assert_eq!(nb, 7);
+        assert_eq!(&out[..nb as usize], b"%2e%2E/");
+        let buf = out[..nb as usize].to_vec();
+        let nb = url_decode_transform_do(&buf, &mut out, &ctx);
+        assert_eq!(&out[..nb as usize], b"../");
+    }
+
+    #[test]
+    fn test_url_decode_transform_invalid() {
+        let ctx = DetectTransformUrlDecodeData::default();
+        let buf = b"%41%G1%4%%%42%";
+        let mut out = vec![0; buf.len()];
+        let nb = url_decode_transform_do(buf, &mut out, &ctx);
+        assert_eq!(&out[..nb as usize], b"A%G1%4%%%42%");
+
+        // trailing partial escapes are kept literal
+        for (input, expected) in [
+            (&b"%"[..], &b"%"[..]),
+            (&b"abc%"[..], &b"abc%"[..]),
+            (&b"abc%4"[..], &b"abc%4"[..]),
+            (&b"%ZZ"[..], &b"%ZZ"[..]),
+        ] {
+            let mut out = vec![0; input.len()];
+            let nb = url_decode_transform_do(input, &mut out, &ctx);
+            assert_eq!(nb as usize, expected.len());
+            assert_eq!(&out[..nb as usize], expected);
+        }
+    }
+
+    #[test]
+    fn test_url_decode_transform_len() {
+        let ctx = DetectTransformUrlDecodeData::default();
+        let buf = b"%41%42%43";
+        let mut out = vec![0; buf.len()];
+        assert_eq!(url_decode_transform_do(buf, &mut out, &ctx), 3);
+        assert_eq!(url_decode_transform_do(b"", &mut out, &ctx), 0);
+        assert_eq!(url_decode_transform_do(b"plain", &mut out, &ctx), 5);
+    }
 }
@@ -16,16 +16,21 @@
  */
 
 use nom7::branch::alt;
-use nom7::bytes::complete::{is_a, tag, tag_no_case, take_while};
-use nom7::character::complete::{char, digit1, hex_digit1};
+use nom7::bytes::complete::{is_a, tag, tag_no_case, take_while, take_while1};
+use nom7::character::complete::{char, hex_digit1};
 use nom7::combinator::{all_consuming, map_opt, opt, value, verify};
-use nom7::error::{make_error, Error, ErrorKind};
+use nom7::error::{make_error, Error, ErrorKind, ParseError};
+use nom7::multi::many0;
+use nom7::sequence::pair;
 use nom7::Err;
 use nom7::IResult;
 
+use super::error::DetectParseError;
+use super::parser::parse_digits;
 use super::EnumString;
 
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
 
 #[derive(PartialEq, Eq, Clone, Debug)]
 #[repr(u8)]
@@ -57,8 +62,9 @@ pub struct DetectUintData<T> {
 /// Will try to parse numerical value first, as any integer detection keyword
 /// And if this fails, will resort to using the enumeration strings.
 ///
-/// Returns Some DetectUintData on success, None on failure
-pub fn detect_parse_uint_enum<T1: DetectIntType, T2: EnumString<T1>>(
+/// Returns Some DetectUintData on success, None on failure, logging the
+/// closest enumeration string if the value looks like a typo of it.
+pub fn detect_parse_uint_enum<T1: DetectIntType, T2: EnumString<T1> + 'static>(
     s: &str,
 ) -> Option<DetectUintData<T1>> {
     if let Ok((_, ctx)) = detect_parse_uint::<T1>(s) {
@@ -72,7 +78,7 @@ pub fn detect_parse_uint_enum<T1: DetectIntType, T2: EnumString<T1>>(
     } else {
         DetectUintMode::DetectUintModeEqual
     };
-    if let Some(enum_val) = T2::from_str(s) {
+    if let Some(enum_val) = T2::from_str_ci(s) {
         let ctx = DetectUintData::<T1> {
             arg1: enum_val.into_u(),
             arg2: T1::min_value(),
@@ -80,6 +86,9 @@ pub fn detect_parse_uint_enum<T1: DetectIntType, T2: EnumString<T1>>(
         };
         return Some(ctx);
     }
+    if let Err(Some(suggestion)) = T2::from_str_suggest(s) {
+        SCLogError!("unknown value \"{}\", did you mean \"{}\"?", s, suggestion);
+    }
     return None;
 }
 
@@ -102,7 +111,7 @@ impl<T> DetectIntType for T where
 {
 }
 
-pub fn detect_parse_uint_unit(i: &str) -> IResult<&str, u64> {
+pub fn detect_parse_uint_unit<'a, E: ParseError<&'a str>>(i: &'a str) -> IResult<&'a str, u64, E> {
     let (i, unit) = alt((
         value(1024, tag_no_case("kb")),
         value(1024 * 1024, tag_no_case("mb")),
@@ -128,8 +137,19 @@ pub fn detect_parse_uint_value<T: DetectIntType>(i: &str) -> IResult<&str, T> {
     Ok((i, arg1))
 }
 
+/// Parses a decimal value with an optional unit suffix.
+///
+/// A leading minus sign is accepted by the grammar, but only signed
+/// types will successfully parse it, so unsigned keywords still reject
+/// negative values.
 pub fn detect_parse_uint_with_unit<T: DetectIntType>(i: &str) -> IResult<&str, T> {
-    let (i, arg1) = map_opt(digit1, |s: &str| s.parse::<T>().ok())(i)?;
+    let (i, arg1) = map_opt(pair(opt(char('-')), parse_digits), |(neg, digits)| {
+        if neg.is_some() {
+            format!("-{}", digits).parse::<T>().ok()
+        } else {
+            digits.parse::<T>().ok()
+        }
+    })(i)?;
     let (i, unit) = opt(detect_parse_uint_unit)(i)?;
     if arg1 >= T::one() {
         if let Some(u) = unit {
@@ -159,18 +179,74 @@ pub fn detect_parse_uint_start_equal<T: DetectIntType>(
     ))
 }
 
-pub fn detect_parse_uint_start_interval<T: DetectIntType>(
-    i: &str,
-) -> IResult<&str, DetectUintData<T>> {
+/// Why a range, whose bounds are excluded, can never match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DetectUintRangeLint {
+    /// The upper bound is below the lower one, like `100<>50`.
+    Inverted,
+    /// No value is between the bounds, like `5<>5` or `5<>6`.
+    Empty,
+}
+
+impl DetectUintRangeLint {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DetectUintRangeLint::Inverted => "inverted range",
+            DetectUintRangeLint::Empty => "empty range",
+        }
+    }
+}
+
+/// Checks the range `arg1<>arg2` has a value between its bounds. A range
+/// with a single one, like `5<>7` matching 6, is fine.
+pub fn detect_uint_range_lint<T: DetectIntType>(arg1: T, arg2: T) -> Option<DetectUintRangeLint> {
+    if arg2 < arg1 {
+        return Some(DetectUintRangeLint::Inverted);
+    }
+    // the difference may overflow for signed types, in which case
+    // the range is large enough anyways
+    if num::CheckedSub::checked_sub(&arg2, &arg1).map_or(false, |d: T| d <= T::one()) {
+        return Some(DetectUintRangeLint::Empty);
+    }
+    return None;
+}
+
+/// Builds the error for the range at `range`, in the argument `input`,
+/// that the parser failed as it can never match.
+fn detect_uint_range_error<T: DetectIntType>(
+    keyword: &'static str, input: &str, range: &str,
+) -> DetectParseError {
+    let (token, lint) = match detect_parse_uint_range_bounds::<T>(range) {
+        Ok((rest, (_, arg1, arg2))) => (
+            &range[..range.len() - rest.len()],
+            detect_uint_range_lint(arg1, arg2).unwrap_or(DetectUintRangeLint::Inverted),
+        ),
+        Err(_) => (range, DetectUintRangeLint::Inverted),
+    };
+    DetectParseError::new(keyword, input, token).with_reason(lint.as_str())
+}
+
+fn detect_parse_uint_range_bounds<T: DetectIntType>(i: &str) -> IResult<&str, (bool, T, T)> {
     let (i, neg) = opt(char('!'))(i)?;
     let (i, arg1) = detect_parse_uint_value(i)?;
     let (i, _) = opt(is_a(" "))(i)?;
     let (i, _) = alt((tag("-"), tag("<>")))(i)?;
     let (i, _) = opt(is_a(" "))(i)?;
-    let (i, arg2) = verify(detect_parse_uint_value, |x| {
-        x > &arg1 && *x - arg1 > T::one()
-    })(i)?;
-    let mode = if neg.is_some() {
+    let (i, arg2) = detect_parse_uint_value(i)?;
+    Ok((i, (neg.is_some(), arg1, arg2)))
+}
+
+pub fn detect_parse_uint_start_interval<T: DetectIntType>(
+    input: &str,
+) -> IResult<&str, DetectUintData<T>> {
+    let (i, (neg, arg1, arg2)) = detect_parse_uint_range_bounds(input)?;
+    if detect_uint_range_lint(arg1, arg2).is_some() {
+        // such a range never matches, or always does once negated, which
+        // is not what the rule means, so it is rejected like a bitmask
+        // that can never match, failing so that the entry point tells why
+        return Err(Err::Failure(make_error(input, ErrorKind::Verify)));
+    }
+    let mode = if neg {
         DetectUintMode::DetectUintModeNegRg
     } else {
         DetectUintMode::DetectUintModeRange
@@ -217,18 +293,20 @@ pub fn detect_parse_uint_bitmask<T: DetectIntType>(
 }
 
 fn detect_parse_uint_start_interval_inclusive<T: DetectIntType>(
-    i: &str,
+    input: &str,
 ) -> IResult<&str, DetectUintData<T>> {
-    let (i, neg) = opt(char('!'))(i)?;
+    let (i, neg) = opt(char('!'))(input)?;
     let (i, arg1) = verify(detect_parse_uint_value::<T>, |x| {
         *x > T::min_value()
     })(i)?;
     let (i, _) = opt(is_a(" "))(i)?;
     let (i, _) = alt((tag("-"), tag("<>")))(i)?;
     let (i, _) = opt(is_a(" "))(i)?;
-    let (i, arg2) = verify(detect_parse_uint_value::<T>, |x| {
-        *x > arg1 && *x < T::max_value()
-    })(i)?;
+    let (i, arg2) = verify(detect_parse_uint_value::<T>, |x| *x < T::max_value())(i)?;
+    // the bounds are included, so only an inverted range never matches
+    if detect_uint_range_lint(arg1 - T::one(), arg2 + T::one()).is_some() {
+        return Err(Err::Failure(make_error(input, ErrorKind::Verify)));
+    }
     let mode = if neg.is_some() {
         DetectUintMode::DetectUintModeNegRg
     } else {
@@ -383,20 +461,51 @@ pub fn detect_parse_uint_inclusive<T: DetectIntType>(i: &str) -> IResult<&str, D
     Ok((i, uint))
 }
 
-#[no_mangle]
-pub unsafe extern "C" fn SCDetectU64Parse(
-    ustr: *const std::os::raw::c_char,
-) -> *mut DetectUintData<u64> {
+/// Parses the integer argument `i` of `keyword` like detect_parse_uint,
+/// the error pointing at what is left unparsed.
+pub fn detect_parse_uint_keyword<T: DetectIntType>(
+    keyword: &'static str, i: &str,
+) -> Result<DetectUintData<T>, DetectParseError> {
+    let rest = match detect_parse_uint_notending::<T>(i) {
+        Ok((rest, uint)) => {
+            if rest.trim_start_matches(' ').is_empty() {
+                return Ok(uint);
+            }
+            rest
+        }
+        Err(Err::Failure(e)) => {
+            return Err(detect_uint_range_error::<T>(keyword, i, e.input));
+        }
+        Err(_) => i,
+    };
+    Err(DetectParseError::new(keyword, i, rest.trim_matches(' ')))
+}
+
+/// Parses the integer argument of a keyword registered in C, logging
+/// why it is invalid.
+unsafe fn detect_parse_uint_ffi<T: DetectIntType>(ustr: *const c_char) -> *mut DetectUintData<T> {
     let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
     if let Ok(s) = ft_name.to_str() {
-        if let Ok((_, ctx)) = detect_parse_uint::<u64>(s) {
-            let boxed = Box::new(ctx);
-            return Box::into_raw(boxed) as *mut _;
+        match detect_parse_uint_keyword::<T>("integer", s) {
+            Ok(ctx) => {
+                let boxed = Box::new(ctx);
+                return Box::into_raw(boxed) as *mut _;
+            }
+            Err(e) => {
+                SCLogError!("{}", e);
+            }
         }
     }
     return std::ptr::null_mut();
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectU64Parse(
+    ustr: *const std::os::raw::c_char,
+) -> *mut DetectUintData<u64> {
+    detect_parse_uint_ffi::<u64>(ustr)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn SCDetectU64Match(
     arg: u64, ctx: &DetectUintData<u64>,
@@ -417,14 +526,7 @@ pub unsafe extern "C" fn SCDetectU64Free(ctx: &mut DetectUintData<u64>) {
 pub unsafe extern "C" fn SCDetectU32Parse(
     ustr: *const std::os::raw::c_char,
 ) -> *mut DetectUintData<u32> {
-    let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
-    if let Ok(s) = ft_name.to_str() {
-        if let Ok((_, ctx)) = detect_parse_uint::<u32>(s) {
-            let boxed = Box::new(ctx);
-            return Box::into_raw(boxed) as *mut _;
-        }
-    }
-    return std::ptr::null_mut();
+    detect_parse_uint_ffi::<u32>(ustr)
 }
 
 #[no_mangle]
@@ -433,9 +535,15 @@ pub unsafe extern "C" fn SCDetectU32ParseInclusive(
 ) -> *mut DetectUintData<u32> {
     let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
     if let Ok(s) = ft_name.to_str() {
-        if let Ok((_, ctx)) = detect_parse_uint_inclusive::<u32>(s) {
-            let boxed = Box::new(ctx);
-            return Box::into_raw(boxed) as *mut _;
+        match detect_parse_uint_inclusive::<u32>(s) {
+            Ok((_, ctx)) => {
+                let boxed = Box::new(ctx);
+                return Box::into_raw(boxed) as *mut _;
+            }
+            Err(Err::Failure(e)) => {
+                SCLogError!("{}", detect_uint_range_error::<u32>("integer", s, e.input));
+            }
+            Err(_) => {}
         }
     }
     return std::ptr::null_mut();
@@ -461,14 +569,7 @@ pub unsafe extern "C" fn SCDetectU32Free(ctx: &mut DetectUintData<u32>) {
 pub unsafe extern "C" fn SCDetectU8Parse(
     ustr: *const std::os::raw::c_char,
 ) -> *mut DetectUintData<u8> {
-    let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
-    if let Ok(s) = ft_name.to_str() {
-        if let Ok((_, ctx)) = detect_parse_uint::<u8>(s) {
-            let boxed = Box::new(ctx);
-            return Box::into_raw(boxed) as *mut _;
-        }
-    }
-    return std::ptr::null_mut();
+    detect_parse_uint_ffi::<u8>(ustr)
 }
 
 #[no_mangle]
@@ -491,9 +592,32 @@ pub unsafe extern "C" fn SCDetectU8Free(ctx: &mut DetectUintData<u8>) {
 pub unsafe extern "C" fn SCDetectU16Parse(
     ustr: *const std::os::raw::c_char,
 ) -> *mut DetectUintData<u16> {
+    detect_parse_uint_ffi::<u16>(ustr)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectU16Match(
+    arg: u16, ctx: &DetectUintData<u16>,
+) -> std::os::raw::c_int {
+    if detect_match_uint(ctx, arg) {
+        return 1;
+    }
+    return 0;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectU16Free(ctx: &mut DetectUintData<u16>) {
+    // Just unbox...
+    std::mem::drop(Box::from_raw(ctx));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectI64Parse(
+    ustr: *const std::os::raw::c_char,
+) -> *mut DetectUintData<i64> {
     let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
     if let Ok(s) = ft_name.to_str() {
-        if let Ok((_, ctx)) = detect_parse_uint::<u16>(s) {
+        if let Ok((_, ctx)) = detect_parse_uint::<i64>(s) {
             let boxed = Box::new(ctx);
             return Box::into_raw(boxed) as *mut _;
         }
@@ -502,8 +626,8 @@ pub unsafe extern "C" fn SCDetectU16Parse(
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn SCDetectU16Match(
-    arg: u16, ctx: &DetectUintData<u16>,
+pub unsafe extern "C" fn SCDetectI64Match(
+    arg: i64, ctx: &DetectUintData<i64>,
 ) -> std::os::raw::c_int {
     if detect_match_uint(ctx, arg) {
         return 1;
@@ -512,11 +636,235 @@ pub unsafe extern "C" fn SCDetectU16Match(
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn SCDetectU16Free(ctx: &mut DetectUintData<u16>) {
+pub unsafe extern "C" fn SCDetectI64Free(ctx: &mut DetectUintData<i64>) {
     // Just unbox...
     std::mem::drop(Box::from_raw(ctx));
 }
 
+/// The bits of a value an integer comparison applies to: the value is
+/// shifted right by `shift` bits, then masked with `mask`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct DetectUintBits {
+    pub shift: u8,
+    pub mask: u64,
+}
+
+impl Default for DetectUintBits {
+    fn default() -> Self {
+        DetectUintBits {
+            shift: 0,
+            mask: u64::MAX,
+        }
+    }
+}
+
+impl DetectUintBits {
+    /// Bits `start` (included) to `end` (excluded), bit 0 being the least
+    /// significant one.
+    fn from_range(start: u8, end: u8) -> Self {
+        let len = end - start;
+        let mask = if len < 64 { (1 << len) - 1 } else { u64::MAX };
+        DetectUintBits { shift: start, mask }
+    }
+
+    /// Selects the bits of `val` to compare.
+    pub fn apply(&self, val: u64) -> u64 {
+        (val >> self.shift) & self.mask
+    }
+}
+
+/// Comparison of a byte_extract or byte_math variable with an integer.
+#[derive(Debug)]
+#[repr(C)]
+pub struct DetectUintVarData {
+    /// Name of the variable. The engine resolves it to `local_id`, the
+    /// slot where byte_extract or byte_math store their result.
+    pub name: *mut c_char,
+    pub local_id: u8,
+    pub du64: DetectUintData<u64>,
+    pub bits: DetectUintBits,
+}
+
+impl Drop for DetectUintVarData {
+    fn drop(&mut self) {
+        if !self.name.is_null() {
+            unsafe {
+                let _ = CString::from_raw(self.name);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DetectUintVarOption {
+    Width(u8),
+    Bits(u8, u8),
+}
+
+fn detect_parse_uint_var_width(i: &str) -> IResult<&str, DetectUintVarOption> {
+    let (i, _) = tag("width")(i)?;
+    let (i, _) = is_a(" ")(i)?;
+    let (i, width) = alt((
+        value(8, tag("8")),
+        value(16, tag("16")),
+        value(32, tag("32")),
+        value(64, tag("64")),
+    ))(i)?;
+    Ok((i, DetectUintVarOption::Width(width)))
+}
+
+fn detect_parse_uint_var_bits(i: &str) -> IResult<&str, DetectUintVarOption> {
+    let (i, _) = tag("bits")(i)?;
+    let (i, _) = is_a(" ")(i)?;
+    let (i, start) = map_opt(parse_digits, |d: String| d.parse::<u8>().ok())(i)?;
+    let (i, _) = char('-')(i)?;
+    let (i, end) = map_opt(parse_digits, |d: String| d.parse::<u8>().ok())(i)?;
+    Ok((i, DetectUintVarOption::Bits(start, end)))
+}
+
+// Parses one of the `, width <8|16|32|64>` or `, bits <start>-<end>`
+// options of a variable comparison.
+fn detect_parse_uint_var_option(i: &str) -> IResult<&str, DetectUintVarOption> {
+    let (i, _) = take_while(|c| c == ' ')(i)?;
+    let (i, _) = char(',')(i)?;
+    let (i, _) = take_while(|c| c == ' ')(i)?;
+    alt((detect_parse_uint_var_width, detect_parse_uint_var_bits))(i)
+}
+
+/// Parses `<variable>, <integer comparison>[, width <8|16|32|64>]
+/// [, bits <start>-<end>]`, as in `len, <100`.
+///
+/// With a width, only that many low bits of the variable are compared.
+/// With bits, only bits `start` to `end` (excluded) are, shifted down to
+/// bit 0, so `bits 4-8` compares the high nibble of a byte. The bits have
+/// to be within the width, 64 if not set, and the values of the
+/// comparison must fit in the compared bits.
+pub fn detect_parse_uint_var(
+    i: &str,
+) -> IResult<&str, (&str, DetectUintData<u64>, DetectUintBits)> {
+    let (i, _) = take_while(|c| c == ' ')(i)?;
+    let (i, name) = verify(
+        take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+        |s: &str| !s.starts_with(|c: char| c.is_ascii_digit()),
+    )(i)?;
+    let (i, _) = take_while(|c| c == ' ')(i)?;
+    let (i, _) = char(',')(i)?;
+    let (i, _) = take_while(|c| c == ' ')(i)?;
+    let (i, du64) = detect_parse_uint_notending::<u64>(i)?;
+    let (i, options) = many0(detect_parse_uint_var_option)(i)?;
+    let (i, _) = all_consuming(take_while(|c| c == ' '))(i)?;
+    let mut width = None;
+    let mut range = None;
+    for option in options {
+        let duplicate = match option {
+            DetectUintVarOption::Width(w) => width.replace(w).is_some(),
+            DetectUintVarOption::Bits(start, end) => range.replace((start, end)).is_some(),
+        };
+        if duplicate {
+            return Err(Err::Error(make_error(i, ErrorKind::Verify)));
+        }
+    }
+    let width = width.unwrap_or(64);
+    let (start, end) = range.unwrap_or((0, width));
+    if start >= end || end > width {
+        return Err(Err::Error(make_error(i, ErrorKind::Verify)));
+    }
+    let bits = DetectUintBits::from_range(start, end);
+    if du64.arg1 > bits.mask || du64.arg2 > bits.mask {
+        return Err(Err::Error(make_error(i, ErrorKind::Verify)));
+    }
+    Ok((i, (name, du64, bits)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectUintVarParse(
+    ustr: *const std::os::raw::c_char,
+) -> *mut DetectUintVarData {
+    let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
+    if let Ok(s) = ft_name.to_str() {
+        if let Ok((_, (name, du64, bits))) = detect_parse_uint_var(s) {
+            if let Ok(name) = CString::new(name) {
+                let boxed = Box::new(DetectUintVarData {
+                    name: name.into_raw(),
+                    local_id: 0,
+                    du64,
+                    bits,
+                });
+                return Box::into_raw(boxed) as *mut _;
+            }
+        }
+    }
+    return std::ptr::null_mut();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectUintVarMatch(
+    arg: u64, ctx: &DetectUintVarData,
+) -> std::os::raw::c_int {
+    if detect_match_uint(&ctx.du64, ctx.bits.apply(arg)) {
+        return 1;
+    }
+    return 0;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectUintVarFree(ctx: &mut DetectUintVarData) {
+    std::mem::drop(Box::from_raw(ctx));
+}
+
+/// How a comparison applies to the occurrences of a repeated field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DetectUintQuantifier {
+    /// at least one occurrence matches
+    Any = 0,
+    /// there are occurrences, and all of them match
+    All = 1,
+}
+
+/// Comparison of the occurrences of a repeated field with an integer.
+#[derive(Debug, PartialEq)]
+#[repr(C)]
+pub struct DetectUintMultiData<T> {
+    pub du: DetectUintData<T>,
+    pub quantifier: DetectUintQuantifier,
+}
+
+fn detect_parse_uint_quantifier(i: &str) -> IResult<&str, DetectUintQuantifier> {
+    let (i, _) = take_while(|c| c == ' ')(i)?;
+    let (i, _) = char(',')(i)?;
+    let (i, _) = take_while(|c| c == ' ')(i)?;
+    alt((
+        value(DetectUintQuantifier::Any, tag("any")),
+        value(DetectUintQuantifier::All, tag("all")),
+    ))(i)
+}
+
+/// Parses `<integer comparison>[, any|all]`, as in `>3, all`. The
+/// quantifier defaults to `any`.
+pub fn detect_parse_uint_multi<T: DetectIntType>(i: &str) -> IResult<&str, DetectUintMultiData<T>> {
+    let (i, du) = detect_parse_uint_notending::<T>(i)?;
+    let (i, quantifier) = opt(detect_parse_uint_quantifier)(i)?;
+    let (i, _) = all_consuming(take_while(|c| c == ' '))(i)?;
+    let quantifier = quantifier.unwrap_or(DetectUintQuantifier::Any);
+    Ok((i, DetectUintMultiData { du, quantifier }))
+}
+
+/// Matches the occurrences `vals` of a field with `ctx`. Without any
+/// occurrence, neither `any` nor `all` match.
+pub fn detect_match_uint_multi<T: DetectIntType, I: IntoIterator<Item = T>>(
+    ctx: &DetectUintMultiData<T>, vals: I,
+) -> bool {
+    let mut vals = vals.into_iter().peekable();
+    match ctx.quantifier {
+        DetectUintQuantifier::Any => vals.any(|v| detect_match_uint(&ctx.du, v)),
+        DetectUintQuantifier::All => {
+            vals.peek().is_some() && vals.all(|v| detect_match_uint(&ctx.du, v))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -559,6 +907,37 @@ mod tests {
         // could never match
         assert!(detect_parse_uint::<u64>("&0xc0=12").is_err());
     }
+    #[test]
+    fn test_parse_uint_keyword() {
+        let val = detect_parse_uint_keyword::<u8>("ttl", " >3 ").unwrap();
+        assert_eq!(val.arg1, 3);
+        assert_eq!(val.mode, DetectUintMode::DetectUintModeGt);
+
+        let e = detect_parse_uint_keyword::<u8>("ttl", "5 foo").unwrap_err();
+        assert_eq!(e.token, "foo");
+        assert_eq!(e.offset, 2);
+        assert_eq!(
+            e.to_string(),
+            "keyword 'ttl': unexpected token 'foo' at offset 2"
+        );
+        let e = detect_parse_uint_keyword::<u8>("ttl", "1-").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "keyword 'ttl': unexpected token '-' at offset 1"
+        );
+        // nothing could be parsed
+        let e = detect_parse_uint_keyword::<u8>("ttl", "  <300").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "keyword 'ttl': unexpected token '<300' at offset 2"
+        );
+        let e = detect_parse_uint_keyword::<u8>("ttl", "").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "keyword 'ttl': unexpected end of arguments at offset 0"
+        );
+    }
+
     #[test]
     fn test_parse_uint_hex() {
         let (_, val) = detect_parse_uint::<u64>("0x100").unwrap();
@@ -581,6 +960,74 @@ mod tests {
         assert!(detect_match_uint(&val, 6));
     }
 
+    #[test]
+    fn test_parse_uint_range_lint() {
+        // inverted
+        assert_eq!(
+            detect_uint_range_lint(100u32, 50),
+            Some(DetectUintRangeLint::Inverted)
+        );
+        assert!(detect_parse_uint::<u32>("100<>50").is_err());
+        assert!(detect_parse_uint::<u32>("!100-50").is_err());
+        assert!(detect_parse_uint::<i8>("1<>-1").is_err());
+        // empty
+        assert_eq!(
+            detect_uint_range_lint(5u8, 5),
+            Some(DetectUintRangeLint::Empty)
+        );
+        assert_eq!(
+            detect_uint_range_lint(5u8, 6),
+            Some(DetectUintRangeLint::Empty)
+        );
+        assert!(detect_parse_uint::<u8>("5<>5").is_err());
+        assert!(detect_parse_uint::<u8>("5-6").is_err());
+        assert!(detect_parse_uint::<u8>("!5<>6").is_err());
+        assert!(detect_parse_uint::<i64>("-1<>0").is_err());
+        // the keyword tells why
+        let e = detect_parse_uint_keyword::<u32>("ttl", " !100-50 ").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "keyword 'ttl': inverted range '!100-50' at offset 1"
+        );
+        let e = detect_parse_uint_keyword::<u8>("ttl", "5 <> 6").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "keyword 'ttl': empty range '5 <> 6' at offset 0"
+        );
+        // a single value
+        assert_eq!(detect_uint_range_lint(5u8, 7), None);
+        let (_, val) = detect_parse_uint::<u8>("5<>7").unwrap();
+        assert!(!detect_match_uint(&val, 5));
+        assert!(detect_match_uint(&val, 6));
+        assert!(!detect_match_uint(&val, 7));
+        let (_, val) = detect_parse_uint::<i8>("-1<>1").unwrap();
+        assert!(detect_match_uint(&val, 0));
+        // overflowing difference
+        assert_eq!(detect_uint_range_lint(i64::MIN, i64::MAX), None);
+    }
+
+    #[test]
+    fn test_parse_uint_inclusive() {
+        let (_, val) = detect_parse_uint_inclusive::<u32>("3-4").unwrap();
+        assert_eq!(val.arg1, 2);
+        assert_eq!(val.arg2, 5);
+        assert!(detect_match_uint(&val, 3));
+        assert!(detect_match_uint(&val, 4));
+        let (_, val) = detect_parse_uint_inclusive::<u32>("3<>3").unwrap();
+        assert!(!detect_match_uint(&val, 2));
+        assert!(detect_match_uint(&val, 3));
+        assert!(!detect_match_uint(&val, 4));
+        // inverted
+        let e = match detect_parse_uint_inclusive::<u32>("4-3") {
+            Err(Err::Failure(e)) => detect_uint_range_error::<u32>("nfs.version", "4-3", e.input),
+            _ => panic!("inverted range not rejected"),
+        };
+        assert_eq!(
+            e.to_string(),
+            "keyword 'nfs.version': inverted range '4-3' at offset 0"
+        );
+    }
+
     #[test]
     fn test_parse_uint_unit() {
         let (_, val) = detect_parse_uint::<u64>(" 2kb").unwrap();
@@ -592,6 +1039,22 @@ mod tests {
         assert_eq!(val.arg1, 3 * 1024 * 1024);
     }
 
+    #[test]
+    fn test_parse_uint_separators() {
+        let (_, val) = detect_parse_uint::<u32>(">1_000_000").unwrap();
+        assert_eq!(val.arg1, 1000000);
+        let (_, val) = detect_parse_uint::<u64>("1_000-2_000").unwrap();
+        assert_eq!(val.arg1, 1000);
+        assert_eq!(val.arg2, 2000);
+        let (_, val) = detect_parse_uint::<i32>("-1_000").unwrap();
+        assert_eq!(val.arg1, -1000);
+        let (_, val) = detect_parse_uint::<u32>("1_0kb").unwrap();
+        assert_eq!(val.arg1, 10 * 1024);
+        assert!(detect_parse_uint::<u32>("_1000").is_err());
+        assert!(detect_parse_uint::<u32>("1000_").is_err());
+        assert!(detect_parse_uint::<u32>("1__000").is_err());
+    }
+
     #[test]
     fn test_parse_uint_like_mqtt_protocol_version() {
         let (_, val) = detect_parse_uint::<u8>("3").unwrap();
@@ -609,4 +1072,231 @@ mod tests {
         assert!(detect_parse_uint::<u8>("").is_err());
         assert!(detect_parse_uint::<u8>("<444").is_err());
     }
+
+    #[test]
+    fn test_parse_int_signed() {
+        let (_, val) = detect_parse_uint::<i8>("-128<>127").unwrap();
+        assert_eq!(val.arg1, -128);
+        assert_eq!(val.arg2, 127);
+        assert_eq!(val.mode, DetectUintMode::DetectUintModeRange);
+        assert!(!detect_match_uint(&val, i8::MIN));
+        assert!(detect_match_uint(&val, -127));
+        assert!(detect_match_uint(&val, 0));
+        assert!(detect_match_uint(&val, 126));
+        assert!(!detect_match_uint(&val, i8::MAX));
+
+        let (_, val) = detect_parse_uint::<i32>("<-5").unwrap();
+        assert_eq!(val.arg1, -5);
+        assert_eq!(val.mode, DetectUintMode::DetectUintModeLt);
+        assert!(detect_match_uint(&val, -6));
+        assert!(!detect_match_uint(&val, -5));
+
+        let (_, val) = detect_parse_uint::<i32>("-10<>10").unwrap();
+        assert_eq!(val.arg1, -10);
+        assert_eq!(val.arg2, 10);
+        assert!(detect_match_uint(&val, -9));
+        assert!(!detect_match_uint(&val, -10));
+
+        let (_, val) = detect_parse_uint::<i64>("!=-1").unwrap();
+        assert_eq!(val.arg1, -1);
+        assert_eq!(val.mode, DetectUintMode::DetectUintModeNe);
+        assert!(detect_match_uint(&val, 1));
+        assert!(!detect_match_uint(&val, -1));
+
+        let (_, val) = detect_parse_uint::<i16>("-20--10").unwrap();
+        assert_eq!(val.arg1, -20);
+        assert_eq!(val.arg2, -10);
+        assert_eq!(val.mode, DetectUintMode::DetectUintModeRange);
+    }
+
+    #[test]
+    fn test_parse_int_signed_boundaries() {
+        // >= min_value would always match
+        assert!(detect_parse_uint::<i16>(">=-32768").is_err());
+
+        let (_, val) = detect_parse_uint::<i16>("-32768<>32767").unwrap();
+        assert!(detect_match_uint(&val, -32767));
+        assert!(detect_match_uint(&val, 32766));
+        assert!(!detect_match_uint(&val, i16::MIN));
+        assert!(!detect_match_uint(&val, i16::MAX));
+
+        let (_, val) = detect_parse_uint::<i32>("-2147483648<>2147483647").unwrap();
+        assert!(detect_match_uint(&val, 0));
+        assert!(!detect_match_uint(&val, i32::MIN));
+        assert!(!detect_match_uint(&val, i32::MAX));
+
+        let (_, val) =
+            detect_parse_uint::<i64>("-9223372036854775808<>9223372036854775807").unwrap();
+        assert!(detect_match_uint(&val, 0));
+        assert!(!detect_match_uint(&val, i64::MIN));
+        assert!(!detect_match_uint(&val, i64::MAX));
+
+        let (_, val) = detect_parse_uint::<i64>("-9223372036854775808").unwrap();
+        assert_eq!(val.arg1, i64::MIN);
+        assert!(detect_match_uint(&val, i64::MIN));
+
+        // value exceeding the declared width
+        assert!(detect_parse_uint::<i8>("128").is_err());
+        assert!(detect_parse_uint::<i8>("-129").is_err());
+        assert!(detect_parse_uint::<i16>("-32769<>0").is_err());
+        assert!(detect_parse_uint::<i32>("2147483648").is_err());
+        assert!(detect_parse_uint::<i64>("-9223372036854775809").is_err());
+        assert!(detect_parse_uint::<i8>("<-128").is_err());
+
+        // negative values are rejected for unsigned keywords
+        assert!(detect_parse_uint::<u8>("-1").is_err());
+        assert!(detect_parse_uint::<u32>("<-5").is_err());
+        assert!(detect_parse_uint::<u64>("-10<>10").is_err());
+        assert!(detect_parse_uint::<u16>("!=-1").is_err());
+    }
+
+    #[test]
+    fn test_parse_uint_var() {
+        let (_, (name, du, bits)) = detect_parse_uint_var("len, <100").unwrap();
+        assert_eq!(name, "len");
+        assert_eq!(du.mode, DetectUintMode::DetectUintModeLt);
+        assert_eq!(du.arg1, 100);
+        assert_eq!(bits, DetectUintBits::default());
+
+        let (_, (name, du, _)) = detect_parse_uint_var(" computed_len ,10-20").unwrap();
+        assert_eq!(name, "computed_len");
+        assert_eq!(du.mode, DetectUintMode::DetectUintModeRange);
+        assert_eq!(du.arg1, 10);
+        assert_eq!(du.arg2, 20);
+
+        assert!(detect_parse_uint_var("len").is_err());
+        assert!(detect_parse_uint_var("len <100").is_err());
+        assert!(detect_parse_uint_var(", <100").is_err());
+        assert!(detect_parse_uint_var("1len, <100").is_err());
+        assert!(detect_parse_uint_var("len, <100 extra").is_err());
+    }
+
+    #[test]
+    fn test_match_uint_var() {
+        let ctx = unsafe { &mut *SCDetectUintVarParse(b"var, <100\0".as_ptr() as *const c_char) };
+        assert_eq!(unsafe { CStr::from_ptr(ctx.name) }.to_str().unwrap(), "var");
+        assert_eq!(unsafe { SCDetectUintVarMatch(99, ctx) }, 1);
+        assert_eq!(unsafe { SCDetectUintVarMatch(100, ctx) }, 0);
+        unsafe { SCDetectUintVarFree(ctx) };
+
+        let ctx = unsafe { SCDetectUintVarParse(b"var\0".as_ptr() as *const c_char) };
+        assert!(ctx.is_null());
+    }
+
+    #[test]
+    fn test_parse_uint_var_width() {
+        let (_, (_, du, bits)) = detect_parse_uint_var("len, 0x1234, width 16").unwrap();
+        assert_eq!(du.arg1, 0x1234);
+        assert_eq!(bits.mask, 0xffff);
+        let (_, (_, _, bits)) = detect_parse_uint_var("len, 1-200 ,width 8 ").unwrap();
+        assert_eq!(bits.mask, 0xff);
+        let (_, (_, _, bits)) = detect_parse_uint_var("len, >1, width 64").unwrap();
+        assert_eq!(bits, DetectUintBits::default());
+
+        // the values have to fit in the width
+        assert!(detect_parse_uint_var("len, 256, width 8").is_err());
+        assert!(detect_parse_uint_var("len, 1-0x10000, width 16").is_err());
+        assert!(detect_parse_uint_var("len, 0x100000000, width 32").is_err());
+        assert!(detect_parse_uint_var("len, &0x100=0, width 8").is_err());
+        assert!(detect_parse_uint_var("len, 1, width 12").is_err());
+        assert!(detect_parse_uint_var("len, 1, width 88").is_err());
+        assert!(detect_parse_uint_var("len, 1, width").is_err());
+        assert!(detect_parse_uint_var("len, 1, width 8, width 16").is_err());
+    }
+
+    #[test]
+    fn test_parse_uint_var_bits() {
+        let (_, (_, _, bits)) = detect_parse_uint_var("hdr, 5, bits 4-8").unwrap();
+        assert_eq!((bits.shift, bits.mask), (4, 0xf));
+        let (_, (_, _, bits)) = detect_parse_uint_var("hdr, 5, width 8, bits 0-4").unwrap();
+        assert_eq!((bits.shift, bits.mask), (0, 0xf));
+        let (_, (_, _, bits)) = detect_parse_uint_var("hdr, 5, bits 60-64, width 64").unwrap();
+        assert_eq!((bits.shift, bits.mask), (60, 0xf));
+        let (_, (_, _, bits)) = detect_parse_uint_var("hdr, 5, bits 0-64").unwrap();
+        assert_eq!(bits, DetectUintBits::default());
+
+        // ranges outside of the width
+        assert!(detect_parse_uint_var("hdr, 5, width 8, bits 4-9").is_err());
+        assert!(detect_parse_uint_var("hdr, 5, bits 60-65").is_err());
+        assert!(detect_parse_uint_var("hdr, 5, bits 8-12, width 8").is_err());
+        // empty or reversed ranges
+        assert!(detect_parse_uint_var("hdr, 0, bits 4-4").is_err());
+        assert!(detect_parse_uint_var("hdr, 0, bits 8-4").is_err());
+        assert!(detect_parse_uint_var("hdr, 0, bits 4").is_err());
+        assert!(detect_parse_uint_var("hdr, 0, bits 0-4, bits 4-8").is_err());
+        // the values have to fit in the compared bits
+        assert!(detect_parse_uint_var("hdr, 16, bits 4-8").is_err());
+        assert!(detect_parse_uint_var("hdr, 0-16, bits 0-4").is_err());
+    }
+
+    #[test]
+    fn test_match_uint_var_bits() {
+        let parse = |s: &[u8]| unsafe { &mut *SCDetectUintVarParse(s.as_ptr() as *const c_char) };
+        // version 4 and header length 5 of an IPv4 header
+        let byte = 0x45;
+        let high = parse(b"hdr, 4, width 8, bits 4-8\0");
+        let low = parse(b"hdr, 5, width 8, bits 0-4\0");
+        assert_eq!(unsafe { SCDetectUintVarMatch(byte, high) }, 1);
+        assert_eq!(unsafe { SCDetectUintVarMatch(byte, low) }, 1);
+        assert_eq!(unsafe { SCDetectUintVarMatch(0x54, high) }, 0);
+        assert_eq!(unsafe { SCDetectUintVarMatch(0x54, low) }, 0);
+        unsafe { SCDetectUintVarFree(high) };
+        unsafe { SCDetectUintVarFree(low) };
+
+        let low = parse(b"hdr, >5, bits 0-4\0");
+        assert_eq!(unsafe { SCDetectUintVarMatch(0x46, low) }, 1);
+        assert_eq!(unsafe { SCDetectUintVarMatch(0xf5, low) }, 0);
+        unsafe { SCDetectUintVarFree(low) };
+    }
+
+    #[test]
+    fn test_match_uint_var_width() {
+        let parse = |s: &[u8]| unsafe { &mut *SCDetectUintVarParse(s.as_ptr() as *const c_char) };
+        // the same value read as 16 and as 32 bits
+        let val = 0x0001_2345;
+        let w16 = parse(b"var, 0x2345, width 16\0");
+        let w32 = parse(b"var, 0x2345, width 32\0");
+        assert_eq!(unsafe { SCDetectUintVarMatch(val, w16) }, 1);
+        assert_eq!(unsafe { SCDetectUintVarMatch(val, w32) }, 0);
+        assert_eq!(unsafe { SCDetectUintVarMatch(0x2345, w32) }, 1);
+        unsafe { SCDetectUintVarFree(w16) };
+        unsafe { SCDetectUintVarFree(w32) };
+
+        // the bits above the width are ignored by all modes
+        let ctx = parse(b"var, >0xff00, width 16\0");
+        assert_eq!(unsafe { SCDetectUintVarMatch(0xffff_0001, ctx) }, 0);
+        assert_eq!(unsafe { SCDetectUintVarMatch(0x1_ff01, ctx) }, 1);
+        unsafe { SCDetectUintVarFree(ctx) };
+    }
+
+    #[test]
+    fn test_parse_uint_multi() {
+        let (_, ctx) = detect_parse_uint_multi::<u8>(">3").unwrap();
+        assert_eq!(ctx.du.arg1, 3);
+        assert_eq!(ctx.du.mode, DetectUintMode::DetectUintModeGt);
+        assert_eq!(ctx.quantifier, DetectUintQuantifier::Any);
+        let (_, ctx) = detect_parse_uint_multi::<u8>("3-7 , all ").unwrap();
+        assert_eq!(ctx.du.arg2, 7);
+        assert_eq!(ctx.quantifier, DetectUintQuantifier::All);
+        let (_, ctx) = detect_parse_uint_multi::<u8>("17,any").unwrap();
+        assert_eq!(ctx.quantifier, DetectUintQuantifier::Any);
+
+        assert!(detect_parse_uint_multi::<u8>("17,").is_err());
+        assert!(detect_parse_uint_multi::<u8>("17, every").is_err());
+        assert!(detect_parse_uint_multi::<u8>("17, all, any").is_err());
+    }
+
+    #[test]
+    fn test_match_uint_multi() {
+        let (_, any) = detect_parse_uint_multi::<u8>(">3").unwrap();
+        let (_, all) = detect_parse_uint_multi::<u8>(">3, all").unwrap();
+        assert!(detect_match_uint_multi(&any, [1, 5, 2]));
+        assert!(!detect_match_uint_multi(&all, [1, 5, 2]));
+        assert!(detect_match_uint_multi(&any, [4, 5, 6]));
+        assert!(detect_match_uint_multi(&all, [4, 5, 6]));
+        assert!(!detect_match_uint_multi(&any, [1, 2]));
+        assert!(!detect_match_uint_multi(&all, [1, 2]));
+        assert!(!detect_match_uint_multi(&any, []));
+        assert!(!detect_match_uint_multi(&all, []));
+    }
 }
@@ -16,13 +16,31 @@
  */
 
 use super::uint::*;
+use crate::core::STREAM_TOSERVER;
+use crate::detect::transforms::inspection_buffer_set_empty;
+use crate::detect::{
+    helper_keyword_register_sticky_buffer, SigTableElmtStickyBuffer, SIGMATCH_INFO_STICKY_BUFFER,
+    SIGMATCH_OPTIONAL_OPT,
+};
+use htp::c_api::transaction::htp_tx_request_uri;
+use htp::transaction::Transaction;
 use nom7::branch::alt;
 use nom7::bytes::complete::{is_a, tag};
 use nom7::character::complete::char;
 use nom7::combinator::{opt, value};
 use nom7::IResult;
+use suricata_sys::sys::{
+    AppProto, AppProtoEnum, DetectEngineCtx, DetectEngineThreadCtx, InspectionBuffer,
+    SCDetectBufferSetActiveList, SCDetectHelperBufferMpmRegister, SCDetectHelperKeywordRegister,
+    SCDetectHelperTransformRegister, SCDetectSignatureAddTransform, SCDetectSignatureSetAppProto,
+    SCInspectionBufferCheckAndExpand, SCInspectionBufferTruncate, SCSigTableAppLiteElmt,
+    SCTransformTableElmt, Signature,
+};
 
+use std::cell::RefCell;
 use std::ffi::CStr;
+use std::os::raw::{c_int, c_void};
+use std::thread::LocalKey;
 
 #[derive(Debug)]
 #[repr(C)]
@@ -76,3 +94,652 @@ pub unsafe extern "C" fn SCDetectUrilenFree(ctx: &mut DetectUrilenData) {
     // Just unbox...
     std::mem::drop(Box::from_raw(ctx));
 }
+
+fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Characters left encoded in a path when reserved characters are not
+/// decoded: decoding them would change how the path is split into
+/// segments, or where it ends.
+pub const URI_PATH_RESERVED: &[u8] = b"/\\.?#\0";
+
+/// Decodes the `%XX` escapes of `input` into `out`, except the ones for
+/// characters in `keep`, which are copied as is.
+///
+/// Invalid escapes are copied as is.
+fn uri_percent_decode(input: &[u8], keep: &[u8], out: &mut Vec<u8>) {
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] == b'%' {
+            if let (Some(h), Some(l)) = (
+                input.get(i + 1).and_then(|c| hex_value(*c)),
+                input.get(i + 2).and_then(|c| hex_value(*c)),
+            ) {
+                let c = (h << 4) | l;
+                if !keep.contains(&c) {
+                    out.push(c);
+                    i += 3;
+                    continue;
+                }
+            }
+        }
+        out.push(input[i]);
+        i += 1;
+    }
+}
+
+/// Returns the path of a request URI, still encoded, without the query
+/// string. The scheme and authority of an absolute URI are skipped.
+fn uri_raw_path(uri: &[u8]) -> &[u8] {
+    let end = uri.iter().position(|c| *c == b'?').unwrap_or(uri.len());
+    let mut path = &uri[..end];
+    if let Some(scheme_end) = path.windows(3).position(|w| w == b"://") {
+        // only a scheme if there was no path before
+        if !path[..scheme_end].contains(&b'/') {
+            let authority = &path[scheme_end + 3..];
+            let start = authority
+                .iter()
+                .position(|c| *c == b'/')
+                .unwrap_or(authority.len());
+            path = &authority[start..];
+        }
+    }
+    return path;
+}
+
+/// Normalizes the path of a request URI into `out`.
+///
+/// The query string is dropped, `%XX` escapes are decoded and then runs
+/// of slashes are collapsed into one, so that `/a%2f/b` becomes `/a/b`.
+/// An empty path is presented as `/`.
+///
+/// If `decode_reserved` is false, the escapes of [`URI_PATH_RESERVED`]
+/// characters are kept, so `/a%2f/b` stays as is.
+pub fn uri_normalize_path(uri: &[u8], decode_reserved: bool, out: &mut Vec<u8>) {
+    let keep = if decode_reserved {
+        &[][..]
+    } else {
+        URI_PATH_RESERVED
+    };
+    let mut decoded = Vec::with_capacity(uri.len());
+    uri_percent_decode(uri_raw_path(uri), keep, &mut decoded);
+    for c in decoded {
+        if c == b'/' && out.last() == Some(&b'/') {
+            continue;
+        }
+        out.push(c);
+    }
+    if out.is_empty() {
+        out.push(b'/');
+    }
+}
+
+/// Returns the query string of a request URI, that is what follows the
+/// first `?`, as is. There is no difference between a missing and an
+/// empty query string, both are empty.
+pub fn uri_query(uri: &[u8]) -> &[u8] {
+    match uri.iter().position(|c| *c == b'?') {
+        Some(start) => &uri[start + 1..],
+        None => &[],
+    }
+}
+
+/// Decodes a form encoded query string component, `+` being a space.
+fn uri_form_decode(input: &[u8]) -> Vec<u8> {
+    let plus: Vec<u8> = input
+        .iter()
+        .map(|c| if *c == b'+' { b' ' } else { *c })
+        .collect();
+    let mut out = Vec::with_capacity(plus.len());
+    uri_percent_decode(&plus, &[], &mut out);
+    return out;
+}
+
+/// Returns the decoded value of the `name` parameter of a query string,
+/// from its first occurrence, or from its last one if `last` is set.
+///
+/// Parameters are separated by `&`, a parameter without `=` has an empty
+/// value. Names are compared once decoded.
+pub fn uri_query_param(query: &[u8], name: &[u8], last: bool) -> Option<Vec<u8>> {
+    let mut values = query.split(|c| *c == b'&').filter_map(|param| {
+        let (key, value) = match param.iter().position(|c| *c == b'=') {
+            Some(eq) => (&param[..eq], &param[eq + 1..]),
+            None => (param, &[][..]),
+        };
+        if uri_form_decode(key) == name {
+            Some(value)
+        } else {
+            None
+        }
+    });
+    let value = if last {
+        values.next_back()
+    } else {
+        values.next()
+    };
+    return value.map(uri_form_decode);
+}
+
+#[derive(Debug, PartialEq)]
+struct DetectUriParamData {
+    name: Vec<u8>,
+    last: bool,
+    /// identifies the transform, as the name followed by the occurrence
+    id: Vec<u8>,
+}
+
+/// Parses `name=<name>[, first|last]`.
+fn uri_param_parse_do(i: &str) -> Option<DetectUriParamData> {
+    let mut parts = i.split(',').map(|p| p.trim());
+    let name = match parts.next().and_then(|p| p.strip_prefix("name")) {
+        Some(rest) => match rest.trim_start().strip_prefix('=') {
+            Some(name) => name.trim(),
+            None => {
+                SCLogError!("uri.param expects name=<name>");
+                return None;
+            }
+        },
+        None => {
+            SCLogError!("uri.param expects name=<name>");
+            return None;
+        }
+    };
+    if name.is_empty() {
+        SCLogError!("uri.param name must not be empty");
+        return None;
+    }
+    let last = match parts.next() {
+        None | Some("first") => false,
+        Some("last") => true,
+        Some(other) => {
+            SCLogError!("uri.param unknown option {}, expected first or last", other);
+            return None;
+        }
+    };
+    if parts.next().is_some() {
+        SCLogError!("uri.param too many options");
+        return None;
+    }
+    let name = name.as_bytes().to_vec();
+    let mut id = name.clone();
+    id.extend_from_slice(if last { b"\0last" } else { b"\0first" });
+    return Some(DetectUriParamData { name, last, id });
+}
+
+thread_local! {
+    static URI_PATH_BUFFER: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    static URI_PATH_KEEP_RESERVED_BUFFER: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Fills `storage`, the thread local data of one buffer list, with `fill`
+/// and hands it out.
+///
+/// The engine does not copy the buffer but keeps pointing to it until it
+/// is done with the transaction, before getting the list again, so lists
+/// must not share their storage.
+unsafe fn uri_buffer_set<F: FnOnce(&mut Vec<u8>)>(
+    storage: &'static LocalKey<RefCell<Vec<u8>>>, fill: F, buffer: *mut *const u8,
+    buffer_len: *mut u32,
+) {
+    storage.with(|b| {
+        let mut b = b.borrow_mut();
+        b.clear();
+        fill(&mut b);
+        *buffer = b.as_ptr();
+        *buffer_len = b.len() as u32;
+    });
+}
+
+static mut G_URI_PATH_BUFFER_ID: c_int = 0;
+static mut G_URI_PATH_KEEP_RESERVED_BUFFER_ID: c_int = 0;
+static mut G_URI_QUERY_BUFFER_ID: c_int = 0;
+static mut G_URI_PARAM_BUFFER_ID: c_int = 0;
+static mut G_TRANSFORM_QUERY_PARAM_ID: c_int = 0;
+
+/// Parses the optional `uri.path` modifier, returning if reserved
+/// characters are decoded.
+fn uri_path_parse_do(i: &str) -> Option<bool> {
+    match i.trim() {
+        "" | "decode_reserved" => Some(true),
+        "keep_reserved" => Some(false),
+        other => {
+            SCLogError!(
+                "uri.path unknown option {}, expected decode_reserved or keep_reserved",
+                other
+            );
+            None
+        }
+    }
+}
+
+unsafe extern "C" fn uri_path_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, raw: *const std::os::raw::c_char,
+) -> c_int {
+    let decode_reserved = if raw.is_null() {
+        true
+    } else {
+        let raw: &CStr = CStr::from_ptr(raw); //unsafe
+        match raw.to_str().ok().and_then(uri_path_parse_do) {
+            Some(decode_reserved) => decode_reserved,
+            None => return -1,
+        }
+    };
+    let list = if decode_reserved {
+        G_URI_PATH_BUFFER_ID
+    } else {
+        G_URI_PATH_KEEP_RESERVED_BUFFER_ID
+    };
+    if SCDetectSignatureSetAppProto(s, AppProtoEnum::ALPROTO_HTTP1 as AppProto) != 0 {
+        return -1;
+    }
+    if SCDetectBufferSetActiveList(de, s, list) < 0 {
+        return -1;
+    }
+    return 0;
+}
+
+unsafe fn uri_path_get_do(
+    tx: *const c_void, decode_reserved: bool, buffer: *mut *const u8, buffer_len: *mut u32,
+) -> bool {
+    let uri = htp_tx_request_uri(tx as *const Transaction);
+    if uri.is_null() {
+        return false;
+    }
+    let storage = if decode_reserved {
+        &URI_PATH_BUFFER
+    } else {
+        &URI_PATH_KEEP_RESERVED_BUFFER
+    };
+    uri_buffer_set(
+        storage,
+        |out| uri_normalize_path((*uri).as_slice(), decode_reserved, out),
+        buffer,
+        buffer_len,
+    );
+    return true;
+}
+
+unsafe extern "C" fn uri_path_get(
+    tx: *const c_void, _flags: u8, buffer: *mut *const u8, buffer_len: *mut u32,
+) -> bool {
+    return uri_path_get_do(tx, true, buffer, buffer_len);
+}
+
+unsafe extern "C" fn uri_path_keep_reserved_get(
+    tx: *const c_void, _flags: u8, buffer: *mut *const u8, buffer_len: *mut u32,
+) -> bool {
+    return uri_path_get_do(tx, false, buffer, buffer_len);
+}
+
+unsafe extern "C" fn uri_query_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, _raw: *const std::os::raw::c_char,
+) -> c_int {
+    if SCDetectSignatureSetAppProto(s, AppProtoEnum::ALPROTO_HTTP1 as AppProto) != 0 {
+        return -1;
+    }
+    if SCDetectBufferSetActiveList(de, s, G_URI_QUERY_BUFFER_ID) < 0 {
+        return -1;
+    }
+    return 0;
+}
+
+/// The buffer is set, possibly empty, for any transaction with a URI.
+unsafe extern "C" fn uri_query_get(
+    tx: *const c_void, _flags: u8, buffer: *mut *const u8, buffer_len: *mut u32,
+) -> bool {
+    let uri = htp_tx_request_uri(tx as *const Transaction);
+    if uri.is_null() {
+        return false;
+    }
+    let query = uri_query((*uri).as_slice());
+    *buffer = query.as_ptr();
+    *buffer_len = query.len() as u32;
+    return true;
+}
+
+unsafe fn uri_param_parse(raw: *const std::os::raw::c_char) -> *mut c_void {
+    let raw: &CStr = CStr::from_ptr(raw); //unsafe
+    if let Ok(s) = raw.to_str() {
+        if let Some(ctx) = uri_param_parse_do(s) {
+            let boxed = Box::new(ctx);
+            return Box::into_raw(boxed) as *mut _;
+        }
+    }
+    return std::ptr::null_mut();
+}
+
+unsafe extern "C" fn query_param_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, opt_str: *const std::os::raw::c_char,
+) -> c_int {
+    let ctx = uri_param_parse(opt_str);
+    if ctx.is_null() {
+        return -1;
+    }
+    let r = SCDetectSignatureAddTransform(s, G_TRANSFORM_QUERY_PARAM_ID, ctx);
+    if r != 0 {
+        query_param_free(de, ctx);
+    }
+    return r;
+}
+
+/// uri.param is the query string with the query_param transform.
+unsafe extern "C" fn uri_param_setup(
+    de: *mut DetectEngineCtx, s: *mut Signature, opt_str: *const std::os::raw::c_char,
+) -> c_int {
+    if SCDetectSignatureSetAppProto(s, AppProtoEnum::ALPROTO_HTTP1 as AppProto) != 0 {
+        return -1;
+    }
+    if SCDetectBufferSetActiveList(de, s, G_URI_PARAM_BUFFER_ID) < 0 {
+        return -1;
+    }
+    return query_param_setup(de, s, opt_str);
+}
+
+unsafe extern "C" fn query_param_transform(
+    _det: *mut DetectEngineThreadCtx, buffer: *mut InspectionBuffer, ctx: *mut c_void,
+) {
+    let input = (*buffer).inspect;
+    let input_len = (*buffer).inspect_len;
+    if input.is_null() || input_len == 0 {
+        return;
+    }
+    let input = build_slice!(input, input_len as usize);
+    let ctx = cast_pointer!(ctx, DetectUriParamData);
+    // a missing parameter leaves an empty buffer, so content cannot match
+    let value = uri_query_param(input, &ctx.name, ctx.last).unwrap_or_default();
+    if value.is_empty() {
+        inspection_buffer_set_empty(buffer);
+        return;
+    }
+
+    let output = SCInspectionBufferCheckAndExpand(buffer, value.len() as u32);
+    if output.is_null() {
+        // allocation failure
+        return;
+    }
+    let output = std::slice::from_raw_parts_mut(output, value.len());
+    output.copy_from_slice(&value);
+
+    SCInspectionBufferTruncate(buffer, value.len() as u32);
+}
+
+unsafe extern "C" fn query_param_free(_de: *mut DetectEngineCtx, ctx: *mut c_void) {
+    std::mem::drop(Box::from_raw(ctx as *mut DetectUriParamData));
+}
+
+unsafe extern "C" fn query_param_id(data: *mut *const u8, length: *mut u32, ctx: *mut c_void) {
+    if data.is_null() || length.is_null() || ctx.is_null() {
+        return;
+    }
+
+    let ctx = cast_pointer!(ctx, DetectUriParamData);
+    *data = ctx.id.as_ptr();
+    *length = ctx.id.len() as u32;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectUriRegister() {
+    let kw = SCSigTableAppLiteElmt {
+        name: b"uri.path\0".as_ptr() as *const libc::c_char,
+        desc: b"sticky buffer to match on the normalized HTTP request URI path\0".as_ptr()
+            as *const libc::c_char,
+        url: b"/rules/http-keywords.html#uri-path\0".as_ptr() as *const libc::c_char,
+        AppLayerTxMatch: None,
+        Setup: Some(uri_path_setup),
+        Free: None,
+        flags: SIGMATCH_OPTIONAL_OPT | SIGMATCH_INFO_STICKY_BUFFER,
+    };
+    let _g_uri_path_kw_id = SCDetectHelperKeywordRegister(&kw);
+    G_URI_PATH_BUFFER_ID = SCDetectHelperBufferMpmRegister(
+        b"uri.path\0".as_ptr() as *const libc::c_char,
+        b"normalized http request uri path\0".as_ptr() as *const libc::c_char,
+        AppProtoEnum::ALPROTO_HTTP1 as AppProto,
+        STREAM_TOSERVER,
+        Some(uri_path_get),
+    );
+    G_URI_PATH_KEEP_RESERVED_BUFFER_ID = SCDetectHelperBufferMpmRegister(
+        b"uri.path_keep_reserved\0".as_ptr() as *const libc::c_char,
+        b"normalized http request uri path, reserved characters kept encoded\0".as_ptr()
+            as *const libc::c_char,
+        AppProtoEnum::ALPROTO_HTTP1 as AppProto,
+        STREAM_TOSERVER,
+        Some(uri_path_keep_reserved_get),
+    );
+    let kw = SigTableElmtStickyBuffer {
+        name: String::from("uri.query"),
+        desc: String::from("sticky buffer to match on the HTTP request URI query string"),
+        url: String::from("/rules/http-keywords.html#uri-query"),
+        setup: uri_query_setup,
+    };
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
+    G_URI_QUERY_BUFFER_ID = SCDetectHelperBufferMpmRegister(
+        b"uri.query\0".as_ptr() as *const libc::c_char,
+        b"http request uri query string\0".as_ptr() as *const libc::c_char,
+        AppProtoEnum::ALPROTO_HTTP1 as AppProto,
+        STREAM_TOSERVER,
+        Some(uri_query_get),
+    );
+
+    let kw = SCTransformTableElmt {
+        name: b"query_param\0".as_ptr() as *const libc::c_char,
+        desc: b"extract a parameter value from a query string\0".as_ptr() as *const libc::c_char,
+        url: b"/rules/transforms.html#query-param\0".as_ptr() as *const libc::c_char,
+        Setup: Some(query_param_setup),
+        flags: 0,
+        Transform: Some(query_param_transform),
+        Free: Some(query_param_free),
+        TransformValidate: None,
+        TransformId: Some(query_param_id),
+    };
+    G_TRANSFORM_QUERY_PARAM_ID = SCDetectHelperTransformRegister(&kw);
+    if G_TRANSFORM_QUERY_PARAM_ID < 0 {
+        SCLogWarning!("Failed registering transform query_param");
+    }
+    let kw = SCSigTableAppLiteElmt {
+        name: b"uri.param\0".as_ptr() as *const libc::c_char,
+        desc: b"sticky buffer to match on a HTTP request URI query parameter\0".as_ptr()
+            as *const libc::c_char,
+        url: b"/rules/http-keywords.html#uri-param\0".as_ptr() as *const libc::c_char,
+        AppLayerTxMatch: None,
+        Setup: Some(uri_param_setup),
+        Free: None,
+        flags: SIGMATCH_INFO_STICKY_BUFFER,
+    };
+    let _g_uri_param_kw_id = SCDetectHelperKeywordRegister(&kw);
+    G_URI_PARAM_BUFFER_ID = SCDetectHelperBufferMpmRegister(
+        b"uri.param\0".as_ptr() as *const libc::c_char,
+        b"http request uri query parameter\0".as_ptr() as *const libc::c_char,
+        AppProtoEnum::ALPROTO_HTTP1 as AppProto,
+        STREAM_TOSERVER,
+        Some(uri_query_get),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn engine_buffer<F: FnOnce(&mut Vec<u8>)>(
+        storage: &'static LocalKey<RefCell<Vec<u8>>>, fill: F,
+    ) -> Vec<u8> {
+        let mut buffer: *const u8 = std::ptr::null();
+        let mut buffer_len = 0;
+        unsafe {
+            uri_buffer_set(storage, fill, &mut buffer, &mut buffer_len);
+            std::slice::from_raw_parts(buffer, buffer_len as usize).to_vec()
+        }
+    }
+
+    fn path(uri: &[u8]) -> Vec<u8> {
+        engine_buffer(&URI_PATH_BUFFER, |out| uri_normalize_path(uri, true, out))
+    }
+
+    fn path_keep_reserved(uri: &[u8]) -> Vec<u8> {
+        engine_buffer(&URI_PATH_KEEP_RESERVED_BUFFER, |out| {
+            uri_normalize_path(uri, false, out)
+        })
+    }
+
+    #[test]
+    fn test_uri_path_buffers_per_list() {
+        let mut path: *const u8 = std::ptr::null();
+        let mut path_len = 0;
+        let mut kept: *const u8 = std::ptr::null();
+        let mut kept_len = 0;
+        unsafe {
+            uri_buffer_set(
+                &URI_PATH_BUFFER,
+                |out| uri_normalize_path(b"/a%2Fb", true, out),
+                &mut path,
+                &mut path_len,
+            );
+            // the engine still points to the first list when getting the second
+            uri_buffer_set(
+                &URI_PATH_KEEP_RESERVED_BUFFER,
+                |out| uri_normalize_path(b"/a%2Fb", false, out),
+                &mut kept,
+                &mut kept_len,
+            );
+            assert_eq!(std::slice::from_raw_parts(path, path_len as usize), b"/a/b");
+            assert_eq!(
+                std::slice::from_raw_parts(kept, kept_len as usize),
+                b"/a%2Fb"
+            );
+        }
+    }
+
+    #[test]
+    fn test_uri_path() {
+        assert_eq!(path(b"/index.html"), b"/index.html");
+        assert_eq!(path(b"/search?q=a/b"), b"/search");
+        assert_eq!(path(b"/a%20b/c%2Ed"), b"/a b/c.d");
+        assert_eq!(path(b"http://example.com/a/b?c"), b"/a/b");
+        assert_eq!(path(b"/a/http://b"), b"/a/http:/b");
+    }
+
+    #[test]
+    fn test_uri_path_empty() {
+        assert_eq!(path(b""), b"/");
+        assert_eq!(path(b"?a=b"), b"/");
+        assert_eq!(path(b"http://example.com"), b"/");
+        assert_eq!(path(b"http://example.com?a"), b"/");
+    }
+
+    #[test]
+    fn test_uri_path_slashes() {
+        assert_eq!(path(b"//a///b//"), b"/a/b/");
+        // encoded slashes are decoded first, then collapsed the same way
+        assert_eq!(path(b"/a%2f/b"), b"/a/b");
+        assert_eq!(path(b"/a/%2F%2fb"), b"/a/b");
+    }
+
+    #[test]
+    fn test_uri_path_bad_escapes() {
+        assert_eq!(path(b"/100%"), b"/100%");
+        assert_eq!(path(b"/%4"), b"/%4");
+        assert_eq!(path(b"/%zz%41"), b"/%zzA");
+    }
+
+    #[test]
+    fn test_uri_path_reserved() {
+        // decoded by default
+        assert_eq!(path(b"/a%2fb"), b"/a/b");
+        assert_eq!(path(b"/a/%2e%2e/b"), b"/a/../b");
+        assert_eq!(path(b"/a%00.html"), b"/a\0.html");
+        // kept with their original case
+        assert_eq!(path_keep_reserved(b"/a%2fb"), b"/a%2fb");
+        assert_eq!(path_keep_reserved(b"/a/%2E%2e/b"), b"/a/%2E%2e/b");
+        assert_eq!(path_keep_reserved(b"/a%00.html"), b"/a%00.html");
+        assert_eq!(path_keep_reserved(b"/a%5cb%3f%23"), b"/a%5cb%3f%23");
+        // other escapes are still decoded, and real slashes collapsed
+        assert_eq!(path_keep_reserved(b"//a%20b%2f%2f"), b"/a b%2f%2f");
+    }
+
+    #[test]
+    fn test_uri_path_parse() {
+        assert_eq!(uri_path_parse_do(""), Some(true));
+        assert_eq!(uri_path_parse_do("decode_reserved"), Some(true));
+        assert_eq!(uri_path_parse_do(" keep_reserved "), Some(false));
+        assert_eq!(uri_path_parse_do("keep"), None);
+    }
+
+    #[test]
+    fn test_uri_query() {
+        assert_eq!(
+            uri_query(b"/search?q=suricata&lang=en"),
+            b"q=suricata&lang=en"
+        );
+        // only the first question mark starts the query string
+        assert_eq!(uri_query(b"/a?b?c"), b"b?c");
+        assert_eq!(uri_query(b"/a?q=%41"), b"q=%41");
+    }
+
+    #[test]
+    fn test_uri_query_empty() {
+        assert_eq!(uri_query(b"/index.html"), b"");
+        assert_eq!(uri_query(b"/index.html?"), b"");
+        assert_eq!(uri_query(b""), b"");
+    }
+
+    #[test]
+    fn test_uri_query_param() {
+        let query = b"id=42&name=Jane+Doe&path=%2Fetc%2Fpasswd&flag&empty=";
+        assert_eq!(uri_query_param(query, b"id", false), Some(b"42".to_vec()));
+        assert_eq!(
+            uri_query_param(query, b"name", false),
+            Some(b"Jane Doe".to_vec())
+        );
+        assert_eq!(
+            uri_query_param(query, b"path", false),
+            Some(b"/etc/passwd".to_vec())
+        );
+        assert_eq!(uri_query_param(query, b"flag", false), Some(Vec::new()));
+        assert_eq!(uri_query_param(query, b"empty", false), Some(Vec::new()));
+        // encoded names
+        assert_eq!(
+            uri_query_param(b"a%5Bb%5D=1", b"a[b]", false),
+            Some(b"1".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_uri_query_param_missing() {
+        assert_eq!(uri_query_param(b"id=42", b"name", false), None);
+        assert_eq!(uri_query_param(b"", b"id", false), None);
+        assert_eq!(uri_query_param(b"ids=42&xid=1", b"id", true), None);
+        // names are case sensitive
+        assert_eq!(uri_query_param(b"ID=42", b"id", false), None);
+    }
+
+    #[test]
+    fn test_uri_query_param_repeated() {
+        let query = b"id=1&x=y&id=2&id=3";
+        assert_eq!(uri_query_param(query, b"id", false), Some(b"1".to_vec()));
+        assert_eq!(uri_query_param(query, b"id", true), Some(b"3".to_vec()));
+        assert_eq!(uri_query_param(query, b"x", true), Some(b"y".to_vec()));
+    }
+
+    #[test]
+    fn test_uri_param_parse() {
+        let ctx = uri_param_parse_do("name=id").unwrap();
+        assert_eq!(ctx.name, b"id");
+        assert!(!ctx.last);
+        let ctx = uri_param_parse_do(" name = id , last").unwrap();
+        assert_eq!(ctx.name, b"id");
+        assert!(ctx.last);
+        assert_eq!(ctx.id, b"id\0last");
+        assert!(!uri_param_parse_do("name=id,first").unwrap().last);
+        assert!(uri_param_parse_do("id").is_none());
+        assert!(uri_param_parse_do("name=").is_none());
+        assert!(uri_param_parse_do("name=id,middle").is_none());
+        assert!(uri_param_parse_do("name=id,last,first").is_none());
+    }
+}
@@ -15,13 +15,17 @@
  * 02110-1301, USA.
  */
 
-use super::uint::{detect_parse_uint, DetectUintData};
+use super::uint::{detect_match_uint, detect_parse_uint_keyword, DetectUintData};
 use std::ffi::CStr;
 use std::str::FromStr;
 
 pub const DETECT_VLAN_ID_ANY: i8 = i8::MIN;
 pub const DETECT_VLAN_ID_ALL: i8 = i8::MAX;
 pub static VLAN_MAX_LAYERS: i8 = 3;
+/// Highest valid vlan id, 4095 is reserved
+pub const VLAN_ID_MAX: u16 = 4094;
+/// Highest priority code point, encoded on 3 bits
+pub const VLAN_PCP_MAX: u8 = 7;
 
 #[repr(C)]
 #[derive(Debug, PartialEq)]
@@ -38,11 +42,17 @@ pub struct DetectVlanIdData {
 
 pub fn detect_parse_vlan_id(s: &str) -> Option<DetectVlanIdData> {
     let parts: Vec<&str> = s.split(',').collect();
-    let du16 = detect_parse_uint(parts[0]).ok()?.1;
+    let du16 = match detect_parse_uint_keyword::<u16>("vlan.id", parts[0]) {
+        Ok(du16) => du16,
+        Err(e) => {
+            SCLogError!("{}", e);
+            return None;
+        }
+    };
     if parts.len() > 2 {
         return None;
     }
-    if du16.arg1 > 0xFFF || du16.arg2 > 0xFFF {
+    if du16.arg1 > VLAN_ID_MAX || du16.arg2 > VLAN_ID_MAX {
         // vlan id is encoded on 12 bits
         return None;
     }
@@ -64,6 +74,40 @@ pub fn detect_parse_vlan_id(s: &str) -> Option<DetectVlanIdData> {
     return Some(DetectVlanIdData { du16, layer });
 }
 
+/// Matches the vlan ids of a packet, outermost first, on the layer
+/// selected by `ctx`. A layer the packet does not carry does not match.
+pub fn detect_match_vlan_id(ids: &[u16], ctx: &DetectVlanIdData) -> bool {
+    if ids.is_empty() {
+        return false;
+    }
+    match ctx.layer {
+        DETECT_VLAN_ID_ANY => ids.iter().any(|&id| detect_match_uint(&ctx.du16, id)),
+        DETECT_VLAN_ID_ALL => ids.iter().all(|&id| detect_match_uint(&ctx.du16, id)),
+        layer => {
+            let idx = if layer < 0 {
+                ids.len().checked_sub(layer.unsigned_abs() as usize)
+            } else {
+                Some(layer as usize)
+            };
+            match idx.and_then(|i| ids.get(i)) {
+                Some(&id) => detect_match_uint(&ctx.du16, id),
+                None => false,
+            }
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectVlanIdMatch(
+    ids: *const u16, nb: u8, ctx: &DetectVlanIdData,
+) -> bool {
+    if ids.is_null() || nb == 0 {
+        return false;
+    }
+    let nb = std::cmp::min(nb, VLAN_MAX_LAYERS as u8);
+    detect_match_vlan_id(std::slice::from_raw_parts(ids, nb as usize), ctx)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn SCDetectVlanIdParse(
     ustr: *const std::os::raw::c_char,
@@ -78,12 +122,114 @@ pub unsafe extern "C" fn SCDetectVlanIdParse(
     return std::ptr::null_mut();
 }
 
+pub fn detect_parse_vlan_pcp(s: &str) -> Option<DetectUintData<u8>> {
+    let du8 = match detect_parse_uint_keyword::<u8>("vlan.pcp", s) {
+        Ok(du8) => du8,
+        Err(e) => {
+            SCLogError!("{}", e);
+            return None;
+        }
+    };
+    if du8.arg1 > VLAN_PCP_MAX || du8.arg2 > VLAN_PCP_MAX {
+        return None;
+    }
+    Some(du8)
+}
+
+/// Matches the priority code point of the outermost vlan layer.
+pub fn detect_match_vlan_pcp(pcps: &[u8], du8: &DetectUintData<u8>) -> bool {
+    match pcps.first() {
+        Some(&pcp) => detect_match_uint(du8, pcp),
+        None => false,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectVlanPcpParse(
+    ustr: *const std::os::raw::c_char,
+) -> *mut DetectUintData<u8> {
+    let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
+    if let Ok(s) = ft_name.to_str() {
+        if let Some(ctx) = detect_parse_vlan_pcp(s) {
+            let boxed = Box::new(ctx);
+            return Box::into_raw(boxed) as *mut _;
+        }
+    }
+    return std::ptr::null_mut();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectVlanPcpMatch(
+    pcps: *const u8, nb: u8, du8: &DetectUintData<u8>,
+) -> bool {
+    if pcps.is_null() || nb == 0 {
+        return false;
+    }
+    let nb = std::cmp::min(nb, VLAN_MAX_LAYERS as u8);
+    detect_match_vlan_pcp(std::slice::from_raw_parts(pcps, nb as usize), du8)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn SCDetectVlanIdFree(ctx: &mut DetectVlanIdData) {
     // Just unbox...
     std::mem::drop(Box::from_raw(ctx));
 }
 
+#[repr(C)]
+#[derive(Debug, PartialEq)]
+/// This data structure is also used in detect-vlan.c
+pub struct DetectVlanHasTagData {
+    /// Matches tagged packets if true, untagged ones otherwise
+    pub tagged: bool,
+}
+
+/// Parses the optional value of `vlan.has_tag`, which is `true` if
+/// missing. `false` is the inverse, matching untagged packets.
+pub fn detect_parse_vlan_has_tag(s: Option<&str>) -> Option<DetectVlanHasTagData> {
+    let tagged = match s.map(|s| s.trim()) {
+        None | Some("true") => true,
+        Some("false") => false,
+        _ => return None,
+    };
+    Some(DetectVlanHasTagData { tagged })
+}
+
+/// Matches if a packet with `nb` vlan layers carries a tag as `ctx`
+/// expects.
+pub fn detect_match_vlan_has_tag(nb: u8, ctx: &DetectVlanHasTagData) -> bool {
+    (nb > 0) == ctx.tagged
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectVlanHasTagParse(
+    ustr: *const std::os::raw::c_char,
+) -> *mut DetectVlanHasTagData {
+    let s = if ustr.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(ustr).to_str() {
+            Ok(s) => Some(s),
+            _ => return std::ptr::null_mut(),
+        }
+    };
+    if let Some(ctx) = detect_parse_vlan_has_tag(s) {
+        let boxed = Box::new(ctx);
+        return Box::into_raw(boxed) as *mut _;
+    }
+    return std::ptr::null_mut();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectVlanHasTagMatch(nb: u8, ctx: &DetectVlanHasTagData) -> bool {
+    detect_match_vlan_has_tag(nb, ctx)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn SCDetectVlanHasTagFree(ctx: &mut DetectVlanHasTagData) {
+    // Just unbox...
+    std::mem::drop(Box::from_raw(ctx));
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -197,4 +343,157 @@ mod test {
         assert!(detect_parse_vlan_id("123,-4").is_none());
         assert!(detect_parse_vlan_id("1,2,3").is_none());
     }
+
+    #[test]
+    fn test_detect_parse_vlan_id_range() {
+        assert_eq!(
+            detect_parse_vlan_id("100<>200").unwrap(),
+            DetectVlanIdData {
+                du16: DetectUintData {
+                    arg1: 100,
+                    arg2: 200,
+                    mode: DetectUintMode::DetectUintModeRange,
+                },
+                layer: DETECT_VLAN_ID_ANY
+            }
+        );
+        assert_eq!(
+            detect_parse_vlan_id("!100<>200,1").unwrap().du16.mode,
+            DetectUintMode::DetectUintModeNegRg
+        );
+        assert!(detect_parse_vlan_id("0<>4094").is_some());
+        // inverted or empty ranges
+        assert!(detect_parse_vlan_id("200<>100").is_none());
+        assert!(detect_parse_vlan_id("200<>200").is_none());
+        assert!(detect_parse_vlan_id("200<>201").is_none());
+    }
+
+    #[test]
+    fn test_detect_parse_vlan_id_bounds() {
+        assert_eq!(detect_parse_vlan_id("0").unwrap().du16.arg1, 0);
+        assert_eq!(detect_parse_vlan_id("4094").unwrap().du16.arg1, 4094);
+        assert!(detect_parse_vlan_id("4095").is_none());
+        assert!(detect_parse_vlan_id(">4095").is_none());
+        assert!(detect_parse_vlan_id("0<>4095").is_none());
+        assert!(detect_parse_vlan_id("-1").is_none());
+    }
+
+    #[test]
+    fn test_detect_match_vlan_id_range() {
+        let v = detect_parse_vlan_id("100<>200").unwrap();
+        assert!(!detect_match_uint(&v.du16, 100));
+        assert!(detect_match_uint(&v.du16, 101));
+        assert!(detect_match_uint(&v.du16, 199));
+        assert!(!detect_match_uint(&v.du16, 200));
+
+        let v = detect_parse_vlan_id("0<>4094").unwrap();
+        assert!(!detect_match_uint(&v.du16, 0));
+        assert!(detect_match_uint(&v.du16, 1));
+        assert!(detect_match_uint(&v.du16, 4093));
+        assert!(!detect_match_uint(&v.du16, 4094));
+
+        let v = detect_parse_vlan_id("4094").unwrap();
+        assert!(detect_match_uint(&v.du16, 4094));
+        assert!(!detect_match_uint(&v.du16, 4093));
+        let v = detect_parse_vlan_id("0").unwrap();
+        assert!(detect_match_uint(&v.du16, 0));
+        assert!(!detect_match_uint(&v.du16, 1));
+    }
+
+    #[test]
+    fn test_detect_match_vlan_id_layers() {
+        // outer tag 100, inner tag 200
+        let ids = [100u16, 200];
+        let m = |rule: &str| detect_match_vlan_id(&ids, &detect_parse_vlan_id(rule).unwrap());
+
+        assert!(m("100"));
+        assert!(m("200"));
+        assert!(m("200,any"));
+        assert!(!m("200,all"));
+        assert!(m(">50,all"));
+
+        assert!(m("100,0"));
+        assert!(!m("200,0"));
+        assert!(m("200,1"));
+        assert!(!m("100,1"));
+        assert!(m("200,-1"));
+        assert!(m("100,-2"));
+
+        // layers the packet does not carry
+        assert!(!m("200,2"));
+        assert!(!m("!200,2"));
+        assert!(!m("100,-3"));
+
+        let v = detect_parse_vlan_id("100").unwrap();
+        assert!(!detect_match_vlan_id(&[], &v));
+    }
+
+    #[test]
+    fn test_detect_parse_vlan_pcp() {
+        let du8 = detect_parse_vlan_pcp("5").unwrap();
+        assert_eq!(du8.arg1, 5);
+        assert_eq!(du8.mode, DetectUintMode::DetectUintModeEqual);
+        let du8 = detect_parse_vlan_pcp("2<>6").unwrap();
+        assert_eq!((du8.arg1, du8.arg2), (2, 6));
+        assert_eq!(du8.mode, DetectUintMode::DetectUintModeRange);
+        assert!(detect_parse_vlan_pcp("0").is_some());
+        assert!(detect_parse_vlan_pcp("7").is_some());
+        assert!(detect_parse_vlan_pcp("<7").is_some());
+        assert!(detect_parse_vlan_pcp("8").is_none());
+        assert!(detect_parse_vlan_pcp(">8").is_none());
+        assert!(detect_parse_vlan_pcp("0<>8").is_none());
+        assert!(detect_parse_vlan_pcp("6<>2").is_none());
+        assert!(detect_parse_vlan_pcp("abc").is_none());
+    }
+
+    #[test]
+    fn test_detect_match_vlan_pcp() {
+        for pcp in 0..=VLAN_PCP_MAX {
+            let du8 = detect_parse_vlan_pcp(&pcp.to_string()).unwrap();
+            for other in 0..=VLAN_PCP_MAX {
+                assert_eq!(detect_match_vlan_pcp(&[other], &du8), pcp == other);
+            }
+        }
+        let du8 = detect_parse_vlan_pcp("2<>6").unwrap();
+        assert!(!detect_match_vlan_pcp(&[2], &du8));
+        assert!(detect_match_vlan_pcp(&[3], &du8));
+        assert!(detect_match_vlan_pcp(&[5], &du8));
+        assert!(!detect_match_vlan_pcp(&[6], &du8));
+
+        // stacked vlan: only the outermost pcp is used
+        let du8 = detect_parse_vlan_pcp("5").unwrap();
+        assert!(detect_match_vlan_pcp(&[5, 1], &du8));
+        assert!(!detect_match_vlan_pcp(&[1, 5], &du8));
+        assert!(!detect_match_vlan_pcp(&[], &du8));
+    }
+
+    #[test]
+    fn test_detect_parse_vlan_has_tag() {
+        assert!(detect_parse_vlan_has_tag(None).unwrap().tagged);
+        assert!(detect_parse_vlan_has_tag(Some("true")).unwrap().tagged);
+        assert!(!detect_parse_vlan_has_tag(Some("false")).unwrap().tagged);
+        assert!(!detect_parse_vlan_has_tag(Some(" false ")).unwrap().tagged);
+        assert!(detect_parse_vlan_has_tag(Some("")).is_none());
+        assert!(detect_parse_vlan_has_tag(Some("1")).is_none());
+        assert!(detect_parse_vlan_has_tag(Some("!true")).is_none());
+        assert!(detect_parse_vlan_has_tag(Some("100")).is_none());
+    }
+
+    #[test]
+    fn test_detect_match_vlan_has_tag() {
+        let tagged = detect_parse_vlan_has_tag(None).unwrap();
+        let untagged = detect_parse_vlan_has_tag(Some("false")).unwrap();
+
+        // single tag
+        assert!(detect_match_vlan_has_tag(1, &tagged));
+        assert!(!detect_match_vlan_has_tag(1, &untagged));
+        // QinQ, and a third layer
+        assert!(detect_match_vlan_has_tag(2, &tagged));
+        assert!(!detect_match_vlan_has_tag(2, &untagged));
+        assert!(detect_match_vlan_has_tag(3, &tagged));
+        assert!(!detect_match_vlan_has_tag(3, &untagged));
+        // untagged
+        assert!(!detect_match_vlan_has_tag(0, &tagged));
+        assert!(detect_match_vlan_has_tag(0, &untagged));
+    }
 }
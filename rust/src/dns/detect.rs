@@ -354,7 +354,9 @@ pub unsafe extern "C" fn SCDetectDNSRegister() {
         url: String::from("/rules/dns-keywords.html#dns-answer-name"),
         setup: dns_detect_answer_name_setup,
     };
-    let _g_dns_answer_name_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_DNS_ANSWER_NAME_BUFFER_ID = SCDetectHelperMultiBufferProgressMpmRegister(
         b"dns.answer.name\0".as_ptr() as *const libc::c_char,
         b"dns answer name\0".as_ptr() as *const libc::c_char,
@@ -386,7 +388,9 @@ pub unsafe extern "C" fn SCDetectDNSRegister() {
         url: String::from("/rules/dns-keywords.html#dns-query-name"),
         setup: dns_detect_query_name_setup,
     };
-    let _g_dns_query_name_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_DNS_QUERY_NAME_BUFFER_ID = SCDetectHelperMultiBufferProgressMpmRegister(
         b"dns.query.name\0".as_ptr() as *const libc::c_char,
         b"dns query name\0".as_ptr() as *const libc::c_char,
@@ -433,11 +437,15 @@ pub unsafe extern "C" fn SCDetectDNSRegister() {
         url: String::from("/rules/dns-keywords.html#dns-query"),
         setup: dns_detect_query_setup,
     };
-    let g_dns_query_name_kw_id = helper_keyword_register_sticky_buffer(&kw);
-    SCDetectHelperKeywordAliasRegister(
-        g_dns_query_name_kw_id,
-        b"dns_query\0".as_ptr() as *const libc::c_char,
-    );
+    match helper_keyword_register_sticky_buffer(&kw) {
+        Ok(g_dns_query_name_kw_id) => SCDetectHelperKeywordAliasRegister(
+            g_dns_query_name_kw_id,
+            b"dns_query\0".as_ptr() as *const libc::c_char,
+        ),
+        Err(e) => {
+            SCLogError!("{}: {}", kw.name, e);
+        }
+    }
     G_DNS_QUERY_BUFFER_ID = SCDetectHelperMultiBufferProgressMpmRegister(
         b"dns_query\0".as_ptr() as *const libc::c_char,
         b"dns request query\0".as_ptr() as *const libc::c_char,
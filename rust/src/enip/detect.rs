@@ -1605,7 +1605,9 @@ pub unsafe extern "C" fn SCDetectEnipRegister() {
         url: String::from("/rules/enip-keyword.html#enip-product-name"),
         setup: product_name_setup,
     };
-    let _g_enip_product_name_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_ENIP_PRODUCT_NAME_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"enip.product_name\0".as_ptr() as *const libc::c_char,
         b"ENIP product name\0".as_ptr() as *const libc::c_char,
@@ -1619,7 +1621,9 @@ pub unsafe extern "C" fn SCDetectEnipRegister() {
         url: String::from("/rules/enip-keyword.html#enip-service-name"),
         setup: service_name_setup,
     };
-    let _g_enip_service_name_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_ENIP_SERVICE_NAME_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"enip.service_name\0".as_ptr() as *const libc::c_char,
         b"ENIP service name\0".as_ptr() as *const libc::c_char,
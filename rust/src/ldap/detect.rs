@@ -672,7 +672,9 @@ pub unsafe extern "C" fn SCDetectLdapRegister() {
         url: String::from("/rules/ldap-keywords.html#ldap.request.dn"),
         setup: ldap_detect_request_dn_setup,
     };
-    let _g_ldap_request_dn_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_LDAP_REQUEST_DN_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"ldap.request.dn\0".as_ptr() as *const libc::c_char,
         b"LDAP REQUEST DISTINGUISHED_NAME\0".as_ptr() as *const libc::c_char,
@@ -686,7 +688,9 @@ pub unsafe extern "C" fn SCDetectLdapRegister() {
         url: String::from("/rules/ldap-keywords.html#ldap.responses.dn"),
         setup: ldap_detect_responses_dn_setup,
     };
-    let _g_ldap_responses_dn_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_LDAP_RESPONSES_DN_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"ldap.responses.dn\0".as_ptr() as *const libc::c_char,
         b"LDAP RESPONSES DISTINGUISHED_NAME\0".as_ptr() as *const libc::c_char,
@@ -716,7 +720,9 @@ pub unsafe extern "C" fn SCDetectLdapRegister() {
         url: String::from("/rules/ldap-keywords.html#ldap.responses.message"),
         setup: ldap_detect_responses_msg_setup,
     };
-    let _g_ldap_responses_dn_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_LDAP_RESPONSES_MSG_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"ldap.responses.message\0".as_ptr() as *const libc::c_char,
         b"LDAP RESPONSES DISTINGUISHED_NAME\0".as_ptr() as *const libc::c_char,
@@ -730,7 +736,9 @@ pub unsafe extern "C" fn SCDetectLdapRegister() {
         url: String::from("/rules/ldap-keywords.html#ldap.request.attribute_type"),
         setup: ldap_detect_request_attibute_type_setup,
     };
-    let _g_ldap_request_attribute_type_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_LDAP_REQUEST_ATTRIBUTE_TYPE_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"ldap.request.attribute_type\0".as_ptr() as *const libc::c_char,
         b"LDAP REQUEST ATTRIBUTE TYPE\0".as_ptr() as *const libc::c_char,
@@ -744,7 +752,9 @@ pub unsafe extern "C" fn SCDetectLdapRegister() {
         url: String::from("/rules/ldap-keywords.html#ldap.responses.attribute_type"),
         setup: ldap_detect_responses_attibute_type_setup,
     };
-    let _g_ldap_responses_attribute_type_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_LDAP_RESPONSES_ATTRIBUTE_TYPE_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"ldap.responses.attribute_type\0".as_ptr() as *const libc::c_char,
         b"LDAP RESPONSES ATTRIBUTE TYPE\0".as_ptr() as *const libc::c_char,
@@ -19,8 +19,9 @@
 
 use crate::core::{STREAM_TOCLIENT, STREAM_TOSERVER};
 use crate::detect::uint::{
-    detect_match_uint, detect_parse_uint, detect_parse_uint_enum, DetectUintData, DetectUintMode,
-    SCDetectU8Free, SCDetectU8Parse,
+    detect_match_uint, detect_match_uint_multi, detect_parse_uint, detect_parse_uint_enum,
+    detect_parse_uint_multi, DetectUintData, DetectUintMode, DetectUintMultiData, SCDetectU8Free,
+    SCDetectU8Parse,
 };
 use crate::detect::{helper_keyword_register_sticky_buffer, SigTableElmtStickyBuffer};
 use suricata_sys::sys::{
@@ -245,32 +246,25 @@ fn mqtt_tx_get_reason_code(tx: &MQTTTransaction) -> Option<u8> {
     return None;
 }
 
-fn mqtt_tx_suback_unsuback_has_reason_code(
-    tx: &MQTTTransaction, code: &DetectUintData<u8>,
-) -> c_int {
+/// Returns all the reason codes of a transaction: the one of the first
+/// message carrying a single code, then the ones of SUBACK and UNSUBACK.
+fn mqtt_tx_get_reason_codes(tx: &MQTTTransaction) -> Vec<u8> {
+    let mut codes: Vec<u8> = mqtt_tx_get_reason_code(tx).into_iter().collect();
     for msg in tx.msg.iter() {
         match msg.op {
             MQTTOperation::UNSUBACK(ref unsuback) => {
                 if let Some(ref reason_codes) = unsuback.reason_codes {
-                    for rc in reason_codes.iter() {
-                        if detect_match_uint(code, *rc) {
-                            return 1;
-                        }
-                    }
+                    codes.extend_from_slice(reason_codes);
                 }
             }
             MQTTOperation::SUBACK(ref suback) => {
                 // in SUBACK these are stored as "QOS granted" historically
-                for rc in suback.qoss.iter() {
-                    if detect_match_uint(code, *rc) {
-                        return 1;
-                    }
-                }
+                codes.extend_from_slice(&suback.qoss);
             }
             _ => {}
         }
     }
-    return 0;
+    return codes;
 }
 
 static mut UNSUB_TOPIC_MATCH_LIMIT: isize = 100;
@@ -433,13 +427,26 @@ unsafe extern "C" fn mqtt_type_free(_de: *mut DetectEngineCtx, ctx: *mut c_void)
     SCDetectU8Free(ctx);
 }
 
+unsafe fn mqtt_parse_reason_code(
+    ustr: *const std::os::raw::c_char,
+) -> *mut DetectUintMultiData<u8> {
+    let ft_name: &CStr = CStr::from_ptr(ustr); //unsafe
+    if let Ok(s) = ft_name.to_str() {
+        if let Ok((_, ctx)) = detect_parse_uint_multi::<u8>(s) {
+            let boxed = Box::new(ctx);
+            return Box::into_raw(boxed);
+        }
+    }
+    return std::ptr::null_mut();
+}
+
 unsafe extern "C" fn mqtt_reason_code_setup(
     de: *mut DetectEngineCtx, s: *mut Signature, raw: *const libc::c_char,
 ) -> c_int {
     if SCDetectSignatureSetAppProto(s, ALPROTO_MQTT) != 0 {
         return -1;
     }
-    let ctx = SCDetectU8Parse(raw) as *mut c_void;
+    let ctx = mqtt_parse_reason_code(raw) as *mut c_void;
     if ctx.is_null() {
         return -1;
     }
@@ -463,19 +470,16 @@ unsafe extern "C" fn mqtt_reason_code_match(
     tx: *mut c_void, _sig: *const Signature, ctx: *const SigMatchCtx,
 ) -> c_int {
     let tx = cast_pointer!(tx, MQTTTransaction);
-    let ctx = cast_pointer!(ctx, DetectUintData<u8>);
-    if let Some(v) = mqtt_tx_get_reason_code(tx) {
-        if detect_match_uint(ctx, v) {
-            return 1;
-        }
+    let ctx = cast_pointer!(ctx, DetectUintMultiData<u8>);
+    if detect_match_uint_multi(ctx, mqtt_tx_get_reason_codes(tx)) {
+        return 1;
     }
-    return mqtt_tx_suback_unsuback_has_reason_code(tx, ctx);
+    return 0;
 }
 
 unsafe extern "C" fn mqtt_reason_code_free(_de: *mut DetectEngineCtx, ctx: *mut c_void) {
     // Just unbox...
-    let ctx = cast_pointer!(ctx, DetectUintData<u8>);
-    SCDetectU8Free(ctx);
+    std::mem::drop(Box::from_raw(ctx as *mut DetectUintMultiData<u8>));
 }
 
 unsafe extern "C" fn mqtt_parse_qos(ustr: *const std::os::raw::c_char) -> *mut u8 {
@@ -990,7 +994,9 @@ pub unsafe extern "C" fn SCDetectMqttRegister() {
             SCLogError!("Invalid value for app-layer.protocols.mqtt.unsubscribe-topic-match-limit");
         }
     }
-    let _g_mqtt_unsub_topic_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_MQTT_UNSUB_TOPIC_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         keyword_name,
         b"unsubscribe topic query\0".as_ptr() as *const libc::c_char,
@@ -1029,7 +1035,9 @@ pub unsafe extern "C" fn SCDetectMqttRegister() {
             SCLogError!("Invalid value for app-layer.protocols.mqtt.subscribe-topic-match-limit");
         }
     }
-    let _g_mqtt_sub_topic_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_MQTT_SUB_TOPIC_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         keyword_name,
         b"subscribe topic query\0".as_ptr() as *const libc::c_char,
@@ -1092,7 +1100,9 @@ pub unsafe extern "C" fn SCDetectMqttRegister() {
         url: String::from("mqtt-keywords.html#mqtt-publish-topic"),
         setup: mqtt_pub_topic_setup,
     };
-    let _g_mqtt_pub_topic_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_MQTT_PUB_TOPIC_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"mqtt.publish.topic\0".as_ptr() as *const libc::c_char,
         b"MQTT PUBLISH topic\0".as_ptr() as *const libc::c_char,
@@ -1106,7 +1116,9 @@ pub unsafe extern "C" fn SCDetectMqttRegister() {
         url: String::from("mqtt-keywords.html#mqtt-publish-message"),
         setup: mqtt_pub_msg_setup,
     };
-    let _g_mqtt_pub_msg_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_MQTT_PUB_MSG_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"mqtt.publish.message\0".as_ptr() as *const libc::c_char,
         b"MQTT PUBLISH message\0".as_ptr() as *const libc::c_char,
@@ -1165,7 +1177,9 @@ pub unsafe extern "C" fn SCDetectMqttRegister() {
         url: String::from("mqtt-keywords.html#mqtt-connect-willtopic"),
         setup: mqtt_conn_willtopic_setup,
     };
-    let _g_mqtt_conn_willtopic_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_MQTT_CONN_WILLTOPIC_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"mqtt.connect.willtopic\0".as_ptr() as *const libc::c_char,
         b"MQTT CONNECT will topic\0".as_ptr() as *const libc::c_char,
@@ -1179,7 +1193,9 @@ pub unsafe extern "C" fn SCDetectMqttRegister() {
         url: String::from("mqtt-keywords.html#mqtt-connect-willmessage"),
         setup: mqtt_conn_willmsg_setup,
     };
-    let _g_mqtt_conn_willmsg_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_MQTT_CONN_WILLMSG_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"mqtt.connect.willmessage\0".as_ptr() as *const libc::c_char,
         b"MQTT CONNECT will message\0".as_ptr() as *const libc::c_char,
@@ -1193,7 +1209,9 @@ pub unsafe extern "C" fn SCDetectMqttRegister() {
         url: String::from("mqtt-keywords.html#mqtt-connect-username"),
         setup: mqtt_conn_username_setup,
     };
-    let _g_mqtt_conn_username_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_MQTT_CONN_USERNAME_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"mqtt.connect.username\0".as_ptr() as *const libc::c_char,
         b"MQTT CONNECT username\0".as_ptr() as *const libc::c_char,
@@ -1207,7 +1225,9 @@ pub unsafe extern "C" fn SCDetectMqttRegister() {
         url: String::from("mqtt-keywords.html#mqtt-connect-protocol_string"),
         setup: mqtt_conn_protocolstring_setup,
     };
-    let _g_mqtt_conn_protostr_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_MQTT_CONN_PROTOCOLSTRING_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"mqtt.connect.protocol_string\0".as_ptr() as *const libc::c_char,
         b"MQTT CONNECT protocol string\0".as_ptr() as *const libc::c_char,
@@ -1221,7 +1241,9 @@ pub unsafe extern "C" fn SCDetectMqttRegister() {
         url: String::from("mqtt-keywords.html#mqtt-connect-password"),
         setup: mqtt_conn_password_setup,
     };
-    let _g_mqtt_conn_password_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_MQTT_CONN_PASSWORD_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"mqtt.connect.password\0".as_ptr() as *const libc::c_char,
         b"MQTT CONNECT password\0".as_ptr() as *const libc::c_char,
@@ -1235,7 +1257,9 @@ pub unsafe extern "C" fn SCDetectMqttRegister() {
         url: String::from("mqtt-keywords.html#mqtt-connect-clientid"),
         setup: mqtt_conn_clientid_setup,
     };
-    let _g_mqtt_conn_password_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_MQTT_CONN_CLIENTID_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"mqtt.connect.clientid\0".as_ptr() as *const libc::c_char,
         b"MQTT CONNECT clientid\0".as_ptr() as *const libc::c_char,
@@ -1339,6 +1363,52 @@ mod test {
         assert_eq!(ctx.mode, DetectUintMode::DetectUintModeEqual);
     }
 
+    #[test]
+    fn test_reason_code_quantifier() {
+        let mut t = MQTTTransaction::new(
+            MQTTMessage {
+                header: FixedHeader {
+                    message_type: MQTTTypeCode::SUBACK,
+                    dup_flag: false,
+                    qos_level: 0,
+                    retain: false,
+                    remaining_length: 0,
+                },
+                op: MQTTOperation::SUBACK(MQTTSubackData {
+                    message_id: 1,
+                    qoss: vec![0x80, 0x87],
+                    properties: None,
+                }),
+            },
+            Direction::ToClient,
+        );
+        t.msg.push(MQTTMessage {
+            header: FixedHeader {
+                message_type: MQTTTypeCode::UNSUBACK,
+                dup_flag: false,
+                qos_level: 0,
+                retain: false,
+                remaining_length: 0,
+            },
+            op: MQTTOperation::UNSUBACK(MQTTUnsubackData {
+                message_id: 2,
+                properties: None,
+                reason_codes: Some(vec![0x11]),
+            }),
+        });
+        assert_eq!(mqtt_tx_get_reason_codes(&t), vec![0x80, 0x87, 0x11]);
+
+        let (_, any) = detect_parse_uint_multi::<u8>(">=128").unwrap();
+        let (_, all) = detect_parse_uint_multi::<u8>(">=128, all").unwrap();
+        assert!(detect_match_uint_multi(&any, mqtt_tx_get_reason_codes(&t)));
+        assert!(!detect_match_uint_multi(&all, mqtt_tx_get_reason_codes(&t)));
+        let (_, all) = detect_parse_uint_multi::<u8>(">16, all").unwrap();
+        assert!(detect_match_uint_multi(&all, mqtt_tx_get_reason_codes(&t)));
+
+        let t = MQTTTransaction::new_empty(Direction::ToClient);
+        assert!(!detect_match_uint_multi(&all, mqtt_tx_get_reason_codes(&t)));
+    }
+
     #[test]
     fn test_multi_unsubscribe() {
         let mut t = MQTTTransaction::new(
@@ -65,7 +65,9 @@ pub unsafe extern "C" fn SCDetectPgsqlRegister() {
         url: String::from("/rules/pgsql-keywords.html#pgsql.query"),
         setup: pgsql_detect_query_setup,
     };
-    let _g_pgsql_query_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_PGSQL_QUERY_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"pgsql.query\0".as_ptr() as *const libc::c_char,
         b"pgsql query request content\0".as_ptr() as *const libc::c_char,
@@ -188,7 +188,9 @@ pub unsafe extern "C" fn SCDetectRfbRegister() {
         url: String::from("/rules/rfb-keywords.html#rfb-name"),
         setup: rfb_name_setup,
     };
-    let _g_rfb_name_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_RFB_NAME_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"rfb.name\0".as_ptr() as *const libc::c_char,
         b"rfb name\0".as_ptr() as *const libc::c_char,
@@ -642,7 +642,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-session-name"),
         setup: sdp_session_name_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_SESSION_NAME_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sdp.session_name\0".as_ptr() as *const libc::c_char,
         b"sdp.session_name\0".as_ptr() as *const libc::c_char,
@@ -656,7 +658,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-session-info"),
         setup: sdp_session_info_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_SESSION_INFO_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sdp.session_info\0".as_ptr() as *const libc::c_char,
         b"sdp.session_info\0".as_ptr() as *const libc::c_char,
@@ -670,7 +674,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-origin"),
         setup: sdp_origin_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_ORIGIN_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sdp.origin\0".as_ptr() as *const libc::c_char,
         b"sdp.origin\0".as_ptr() as *const libc::c_char,
@@ -684,7 +690,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-uri"),
         setup: sdp_uri_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_URI_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sdp.uri\0".as_ptr() as *const libc::c_char,
         b"sdp.uri\0".as_ptr() as *const libc::c_char,
@@ -698,7 +706,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-email"),
         setup: sdp_email_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_EMAIL_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sdp.email\0".as_ptr() as *const libc::c_char,
         b"sdp.email\0".as_ptr() as *const libc::c_char,
@@ -712,7 +722,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-phone-number"),
         setup: sdp_phone_number_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_PHONE_NUMBER_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sdp.phone_number\0".as_ptr() as *const libc::c_char,
         b"sdp.phone_number\0".as_ptr() as *const libc::c_char,
@@ -726,7 +738,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-connection-data"),
         setup: sdp_conn_data_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_CONNECTION_DATA_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sdp.connection_data\0".as_ptr() as *const libc::c_char,
         b"sdp.connection_data\0".as_ptr() as *const libc::c_char,
@@ -740,7 +754,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-bandwidth"),
         setup: sdp_bandwidth_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_BANDWIDTH_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sdp.bandwidth\0".as_ptr() as *const libc::c_char,
         b"sdp.bandwidth\0".as_ptr() as *const libc::c_char,
@@ -754,7 +770,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#time"),
         setup: sdp_time_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_TIME_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sdp.time\0".as_ptr() as *const libc::c_char,
         b"sdp.time\0".as_ptr() as *const libc::c_char,
@@ -768,7 +786,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#repeat-time"),
         setup: sdp_repeat_time_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_REPEAT_TIME_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sdp.repeat_time\0".as_ptr() as *const libc::c_char,
         b"sdp.repeat_time\0".as_ptr() as *const libc::c_char,
@@ -782,7 +802,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#timezone"),
         setup: sdp_timezone_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_TIMEZONE_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sdp.timezone\0".as_ptr() as *const libc::c_char,
         b"sdp.timezone\0".as_ptr() as *const libc::c_char,
@@ -796,7 +818,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#encryption-key"),
         setup: sdp_encryption_key_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_ENCRYPTION_KEY_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sdp.encryption_key\0".as_ptr() as *const libc::c_char,
         b"sdp.encription_key\0".as_ptr() as *const libc::c_char,
@@ -810,7 +834,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-attribute"),
         setup: sdp_attribute_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_ATTRIBUTE_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sdp.attribute\0".as_ptr() as *const libc::c_char,
         b"sdp.attribute\0".as_ptr() as *const libc::c_char,
@@ -826,7 +852,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#media-description-media"),
         setup: sdp_media_desc_media_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_MEDIA_DESC_MEDIA_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sdp.media.media\0".as_ptr() as *const libc::c_char,
         b"sdp.media.media\0".as_ptr() as *const libc::c_char,
@@ -840,7 +868,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-media-description-session-info"),
         setup: sdp_media_desc_session_info_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_MEDIA_DESC_SESSION_INFO_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sdp.media.media_info\0".as_ptr() as *const libc::c_char,
         b"sdp.media.media_info\0".as_ptr() as *const libc::c_char,
@@ -854,7 +884,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-media-description-connection-data"),
         setup: sdp_media_desc_connection_data_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_MEDIA_DESC_CONNECTION_DATA_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sdp.media.connection_data\0".as_ptr() as *const libc::c_char,
         b"sdp.media.connection_data\0".as_ptr() as *const libc::c_char,
@@ -868,7 +900,9 @@ pub unsafe extern "C" fn SCDetectSdpRegister() {
         url: String::from("/rules/sdp-keywords.html#sdp-media-description-encryption-key"),
         setup: sdp_media_desc_encryption_key_setup,
     };
-    let _ = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SDP_MEDIA_DESC_ENCRYPTION_KEY_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sdp.media.encryption_key\0".as_ptr() as *const libc::c_char,
         b"sdp.media.encryption_key\0".as_ptr() as *const libc::c_char,
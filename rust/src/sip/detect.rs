@@ -423,7 +423,9 @@ pub unsafe extern "C" fn SCDetectSipRegister() {
         url: String::from("/rules/sip-keywords.html#sip-protocol"),
         setup: sip_protocol_setup,
     };
-    let _g_sip_protocol_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SIP_PROTOCOL_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sip.protocol\0".as_ptr() as *const libc::c_char,
         b"sip.protocol\0".as_ptr() as *const libc::c_char,
@@ -437,7 +439,9 @@ pub unsafe extern "C" fn SCDetectSipRegister() {
         url: String::from("/rules/sip-keywords.html#sip-stat-code"),
         setup: sip_stat_code_setup,
     };
-    let _g_sip_stat_code_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SIP_STAT_CODE_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sip.stat_code\0".as_ptr() as *const libc::c_char,
         b"sip.stat_code\0".as_ptr() as *const libc::c_char,
@@ -451,7 +455,9 @@ pub unsafe extern "C" fn SCDetectSipRegister() {
         url: String::from("/rules/sip-keywords.html#sip-stat-msg"),
         setup: sip_stat_msg_setup,
     };
-    let _g_sip_stat_msg_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SIP_STAT_MSG_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sip.stat_msg\0".as_ptr() as *const libc::c_char,
         b"sip.stat_msg\0".as_ptr() as *const libc::c_char,
@@ -465,7 +471,9 @@ pub unsafe extern "C" fn SCDetectSipRegister() {
         url: String::from("/rules/sip-keywords.html#sip-request-line"),
         setup: sip_request_line_setup,
     };
-    let _g_sip_request_line_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SIP_REQUEST_LINE_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sip.request_line\0".as_ptr() as *const libc::c_char,
         b"sip.request_line\0".as_ptr() as *const libc::c_char,
@@ -479,7 +487,9 @@ pub unsafe extern "C" fn SCDetectSipRegister() {
         url: String::from("/rules/sip-keywords.html#sip-response-line"),
         setup: sip_response_line_setup,
     };
-    let _g_sip_response_line_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SIP_RESPONSE_LINE_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"sip.response_line\0".as_ptr() as *const libc::c_char,
         b"sip.response_line\0".as_ptr() as *const libc::c_char,
@@ -493,7 +503,9 @@ pub unsafe extern "C" fn SCDetectSipRegister() {
         url: String::from("/rules/sip-keywords.html#sip-from"),
         setup: sip_from_hdr_setup,
     };
-    let _g_sip_from_hdr_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SIP_FROM_HDR_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sip.from\0".as_ptr() as *const libc::c_char,
         b"sip.from\0".as_ptr() as *const libc::c_char,
@@ -507,7 +519,9 @@ pub unsafe extern "C" fn SCDetectSipRegister() {
         url: String::from("/rules/sip-keywords.html#sip-to"),
         setup: sip_to_hdr_setup,
     };
-    let _g_sip_to_hdr_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SIP_TO_HDR_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sip.to\0".as_ptr() as *const libc::c_char,
         b"sip.to\0".as_ptr() as *const libc::c_char,
@@ -521,7 +535,9 @@ pub unsafe extern "C" fn SCDetectSipRegister() {
         url: String::from("/rules/sip-keywords.html#sip-via"),
         setup: sip_via_hdr_setup,
     };
-    let _g_sip_via_hdr_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SIP_VIA_HDR_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sip.via\0".as_ptr() as *const libc::c_char,
         b"sip.via\0".as_ptr() as *const libc::c_char,
@@ -535,7 +551,9 @@ pub unsafe extern "C" fn SCDetectSipRegister() {
         url: String::from("/rules/sip-keywords.html#sip-user-agent"),
         setup: sip_ua_hdr_setup,
     };
-    let _g_sip_ua_hdr_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SIP_UA_HDR_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sip.ua\0".as_ptr() as *const libc::c_char,
         b"sip.ua\0".as_ptr() as *const libc::c_char,
@@ -549,7 +567,9 @@ pub unsafe extern "C" fn SCDetectSipRegister() {
         url: String::from("/rules/sip-keywords.html#sip-content-type"),
         setup: sip_content_type_hdr_setup,
     };
-    let _g_sip_content_type_hdr_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SIP_CONTENT_TYPE_HDR_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sip.content_type\0".as_ptr() as *const libc::c_char,
         b"sip.content_type\0".as_ptr() as *const libc::c_char,
@@ -563,7 +583,9 @@ pub unsafe extern "C" fn SCDetectSipRegister() {
         url: String::from("/rules/sip-keywords.html#sip-content-length"),
         setup: sip_content_length_hdr_setup,
     };
-    let _g_sip_content_length_hdr_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SIP_CONTENT_LENGTH_HDR_BUFFER_ID = SCDetectHelperMultiBufferMpmRegister(
         b"sip.content_length\0".as_ptr() as *const libc::c_char,
         b"sip.content_length\0".as_ptr() as *const libc::c_char,
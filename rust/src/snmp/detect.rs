@@ -207,7 +207,9 @@ pub(super) unsafe extern "C" fn detect_snmp_register() {
         url: String::from("/rules/snmp-keywords.html#snmp-usm"),
         setup: snmp_detect_usm_setup,
     };
-    let _g_snmp_usm_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SNMP_USM_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"snmp.usm\0".as_ptr() as *const libc::c_char,
         b"SNMP USM\0".as_ptr() as *const libc::c_char,
@@ -222,7 +224,9 @@ pub(super) unsafe extern "C" fn detect_snmp_register() {
         url: String::from("/rules/snmp-keywords.html#snmp-community"),
         setup: snmp_detect_community_setup,
     };
-    let _g_snmp_community_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_SNMP_COMMUNITY_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"snmp.community\0".as_ptr() as *const libc::c_char,
         b"SNMP Community identifier\0".as_ptr() as *const libc::c_char,
@@ -317,7 +317,9 @@ pub unsafe extern "C" fn SCDetectWebsocketRegister() {
         url: String::from("/rules/websocket-keywords.html#websocket-payload"),
         setup: websocket_detect_payload_setup,
     };
-    let _g_ws_payload_kw_id = helper_keyword_register_sticky_buffer(&kw);
+    if let Err(e) = helper_keyword_register_sticky_buffer(&kw) {
+        SCLogError!("{}: {}", kw.name, e);
+    }
     G_WEBSOCKET_PAYLOAD_BUFFER_ID = SCDetectHelperBufferMpmRegister(
         b"websocket.payload\0".as_ptr() as *const libc::c_char,
         b"WebSocket payload\0".as_ptr() as *const libc::c_char,
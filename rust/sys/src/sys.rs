@@ -357,6 +357,9 @@ extern "C" {
 extern "C" {
     pub fn SCDetectHelperKeywordAliasRegister(kwid: u16, alias: *const ::std::os::raw::c_char);
 }
+extern "C" {
+    pub fn SCDetectHelperKeywordExists(name: *const ::std::os::raw::c_char) -> bool;
+}
 extern "C" {
     pub fn SCDetectHelperBufferRegister(
         name: *const ::std::os::raw::c_char, alproto: AppProto, direction: u8,
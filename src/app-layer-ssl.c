@@ -2871,6 +2871,8 @@ static void SSLStateFree(void *p)
         SCFree(ssl_state->client_connp.cert0_fingerprint);
     if (ssl_state->client_connp.sni)
         SCFree(ssl_state->client_connp.sni);
+    if (ssl_state->client_connp.sni_lower)
+        SCRustCStringFree(ssl_state->client_connp.sni_lower);
     if (ssl_state->client_connp.session_id)
         SCFree(ssl_state->client_connp.session_id);
     if (ssl_state->client_connp.hs_buffer)
@@ -3403,3 +3405,38 @@ bool SSLJA4IsEnabled(void)
 {
     return SC_ATOMIC_GET(ssl_config.enable_ja4);
 }
+
+/**
+ * \brief get the SNI sent by the client, if any
+ *
+ * \retval true if the client sent a SNI
+ */
+bool SCSSLStateGetSni(const void *state, const uint8_t **sni, uint32_t *sni_len)
+{
+    const SSLState *ssl_state = (const SSLState *)state;
+    if (ssl_state->client_connp.sni == NULL) {
+        return false;
+    }
+    *sni = (const uint8_t *)ssl_state->client_connp.sni;
+    *sni_len = (uint32_t)strlen(ssl_state->client_connp.sni);
+    return true;
+}
+
+/**
+ * \brief get the normalized SNI of the client, NULL if not set yet
+ */
+const char *SCSSLStateGetSniLower(const void *state)
+{
+    const SSLState *ssl_state = (const SSLState *)state;
+    return ssl_state->client_connp.sni_lower;
+}
+
+/**
+ * \brief set the normalized SNI of the client, a Rust string the state
+ *        frees with SCRustCStringFree
+ */
+void SCSSLStateSetSniLower(void *state, char *sni_lower)
+{
+    SSLState *ssl_state = (SSLState *)state;
+    ssl_state->client_connp.sni_lower = sni_lower;
+}
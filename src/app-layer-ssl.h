@@ -195,6 +195,8 @@ typedef struct SSLStateConnp_ {
     uint16_t cert0_sans_len;
     /* ssl server name indication extension */
     char *sni;
+    /* sni normalized for tls.sni_lower by the detection engine on first use */
+    char *sni_lower;
 
     char *session_id;
 
@@ -254,5 +256,8 @@ void SSLEnableJA3(void);
 bool SSLJA3IsEnabled(void);
 void SSLEnableJA4(void);
 bool SSLJA4IsEnabled(void);
+bool SCSSLStateGetSni(const void *state, const uint8_t **sni, uint32_t *sni_len);
+const char *SCSSLStateGetSniLower(const void *state);
+void SCSSLStateSetSniLower(void *state, char *sni_lower);
 
 #endif /* SURICATA_APP_LAYER_SSL_H */
@@ -90,6 +90,51 @@ uint32_t StringHash(uint32_t hash_seed, void *s)
     return hashlittle_safe(str->ptr, str->len, hash_seed);
 }
 
+/** \brief copy a value of a nocase set, which stores it in lowercase */
+int StringSetNocase(void *dst, void *src)
+{
+    StringType *dst_s = dst;
+
+    if (StringSet(dst, src) != 0)
+        return -1;
+    for (uint32_t i = 0; i < dst_s->len; i++) {
+        dst_s->ptr[i] = u8_tolower(dst_s->ptr[i]);
+    }
+    return 0;
+}
+
+bool StringCompareNocase(void *a, void *b)
+{
+    const StringType *as = a;
+    const StringType *bs = b;
+
+    if (as->len != bs->len)
+        return false;
+
+    for (uint32_t i = 0; i < as->len; i++) {
+        if (u8_tolower(as->ptr[i]) != u8_tolower(bs->ptr[i]))
+            return false;
+    }
+    return true;
+}
+
+uint32_t StringHashNocase(uint32_t hash_seed, void *s)
+{
+    StringType *str = s;
+    uint8_t lower[256];
+    uint32_t hash = hash_seed;
+
+    /* hash the lowercase value one chunk at a time */
+    for (uint32_t offset = 0; offset < str->len; offset += sizeof(lower)) {
+        const uint32_t len = MIN(str->len - offset, (uint32_t)sizeof(lower));
+        for (uint32_t i = 0; i < len; i++) {
+            lower[i] = u8_tolower(str->ptr[offset + i]);
+        }
+        hash = hashlittle_safe(lower, len, hash);
+    }
+    return hash;
+}
+
 uint32_t StringGetLength(void *s)
 {
     StringType *str = s;
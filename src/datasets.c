@@ -142,6 +142,7 @@ static int DatasetLoadIPv4(Dataset *set)
     } else if (retval == -1) {
         return -1;
     }
+    set->json = retval == 1;
 
     THashConsolidateMemcap(set->hash);
 
@@ -196,6 +197,7 @@ static int DatasetLoadIPv6(Dataset *set)
     } else if (retval == -1) {
         return -1;
     }
+    set->json = retval == 1;
 
     THashConsolidateMemcap(set->hash);
 
@@ -219,6 +221,7 @@ static int DatasetLoadMd5(Dataset *set)
     } else if (retval == -1) {
         return -1;
     }
+    set->json = retval == 1;
 
     THashConsolidateMemcap(set->hash);
 
@@ -242,6 +245,7 @@ static int DatasetLoadSha256(Dataset *set)
     } else if (retval == -1) {
         return -1;
     }
+    set->json = retval == 1;
 
     THashConsolidateMemcap(set->hash);
 
@@ -266,6 +270,7 @@ static int DatasetLoadString(Dataset *set)
     } else if (retval == -1) {
         return -1;
     }
+    set->json = retval == 1;
 
     THashConsolidateMemcap(set->hash);
 
@@ -354,8 +359,26 @@ static void DatasetUpdateHashsize(const char *name, uint32_t hash_size)
     }
 }
 
-Dataset *DatasetGet(const char *name, enum DatasetTypes type, const char *save, const char *load,
-        uint64_t memcap, uint32_t hashsize)
+static DatasetType DatasetStoreType(enum DatasetTypes type)
+{
+    switch (type) {
+        case DATASET_TYPE_MD5:
+            return DSMd5;
+        case DATASET_TYPE_SHA256:
+            return DSSha256;
+        case DATASET_TYPE_IPV4:
+            return DSIpv4;
+        case DATASET_TYPE_IPV6:
+            return DSIpv6;
+        case DATASET_TYPE_STRING:
+        default:
+            return DSString;
+    }
+}
+
+static Dataset *DatasetGetInternal(const char *name, enum DatasetTypes type, const char *save,
+        const char *load, uint64_t memcap, uint32_t hashsize, uint64_t bloom_entries,
+        double bloom_fp_rate, DatasetBackend backend, bool nocase)
 {
     uint64_t default_memcap = 0;
     uint32_t default_hashsize = 0;
@@ -392,6 +415,12 @@ Dataset *DatasetGet(const char *name, enum DatasetTypes type, const char *save,
                 return NULL;
             }
         }
+        // a rule may use a nocase set without asking for it, not the opposite
+        if (nocase && !set->nocase) {
+            SCLogError("dataset %s is not nocase", set->name);
+            SCMutexUnlock(&sets_lock);
+            return NULL;
+        }
 
         SCMutexUnlock(&sets_lock);
         return set;
@@ -400,6 +429,10 @@ Dataset *DatasetGet(const char *name, enum DatasetTypes type, const char *save,
             SCLogError("dataset %s not defined", name);
             goto out_err;
         }
+        if (nocase && type != DATASET_TYPE_STRING) {
+            SCLogError("dataset %s: nocase is only supported for string sets", name);
+            goto out_err;
+        }
     }
 
     GetDefaultMemcap(&default_memcap, &default_hashsize);
@@ -418,6 +451,7 @@ Dataset *DatasetGet(const char *name, enum DatasetTypes type, const char *save,
 
     strlcpy(set->name, name, sizeof(set->name));
     set->type = type;
+    set->nocase = nocase;
     if (save && strlen(save)) {
         strlcpy(set->save, save, sizeof(set->save));
         SCLogDebug("name %s save '%s'", name, set->save);
@@ -426,6 +460,34 @@ Dataset *DatasetGet(const char *name, enum DatasetTypes type, const char *save,
         strlcpy(set->load, load, sizeof(set->load));
         SCLogDebug("set \'%s\' loading \'%s\' from \'%s\'", set->name, load, set->load);
     }
+    if (bloom_entries > 0) {
+        if (strlen(set->save) > 0) {
+            SCLogWarning("dataset %s: a bloom filter can't be saved, using an exact set", name);
+        } else if (nocase) {
+            SCLogWarning("dataset %s: a bloom filter can't be nocase, using an exact set", name);
+        } else {
+            set->bloom = SCDatasetBloomNew(bloom_entries, bloom_fp_rate);
+            if (set->bloom == NULL) {
+                SCLogWarning("dataset %s: invalid bloom filter settings, using an exact set", name);
+            }
+        }
+    }
+    if (backend == DSBackendMmap) {
+        if (strlen(set->save) > 0 || strlen(set->load) == 0 || set->bloom != NULL) {
+            SCLogWarning("dataset %s: only a set that is just loaded can be memory mapped, "
+                         "using a hash",
+                    name);
+        } else {
+            DatasetStore *store =
+                    SCDatasetStoreOpen(set->load, DatasetStoreType(type), backend, nocase);
+            if (store == NULL)
+                goto out_err;
+            SCLogConfig("dataset: %s %s '%s'", set->name,
+                    SCDatasetStoreBackend(store) == DSBackendMmap ? "mapped from" : "loaded from",
+                    set->load);
+            set->store = SCDatasetSwapNew(store);
+        }
+    }
 
     char cnf_name[128];
     snprintf(cnf_name, sizeof(cnf_name), "datasets.%s.hash", name);
@@ -437,16 +499,18 @@ Dataset *DatasetGet(const char *name, enum DatasetTypes type, const char *save,
                     memcap > 0 ? memcap : default_memcap, hashsize);
             if (set->hash == NULL)
                 goto out_err;
-            if (DatasetLoadMd5(set) < 0)
+            if (set->store == NULL && DatasetLoadMd5(set) < 0)
                 goto out_err;
             break;
         case DATASET_TYPE_STRING:
-            set->hash = THashInit(cnf_name, sizeof(StringType), StringSet, StringFree, StringHash,
-                    StringCompare, NULL, StringGetLength, load != NULL ? 1 : 0,
-                    memcap > 0 ? memcap : default_memcap, hashsize);
+            set->hash = THashInit(cnf_name, sizeof(StringType),
+                    nocase ? StringSetNocase : StringSet, StringFree,
+                    nocase ? StringHashNocase : StringHash,
+                    nocase ? StringCompareNocase : StringCompare, NULL, StringGetLength,
+                    load != NULL ? 1 : 0, memcap > 0 ? memcap : default_memcap, hashsize);
             if (set->hash == NULL)
                 goto out_err;
-            if (DatasetLoadString(set) < 0)
+            if (set->store == NULL && DatasetLoadString(set) < 0)
                 goto out_err;
             break;
         case DATASET_TYPE_SHA256:
@@ -455,7 +519,7 @@ Dataset *DatasetGet(const char *name, enum DatasetTypes type, const char *save,
                     memcap > 0 ? memcap : default_memcap, hashsize);
             if (set->hash == NULL)
                 goto out_err;
-            if (DatasetLoadSha256(set) < 0)
+            if (set->store == NULL && DatasetLoadSha256(set) < 0)
                 goto out_err;
             break;
         case DATASET_TYPE_IPV4:
@@ -464,7 +528,7 @@ Dataset *DatasetGet(const char *name, enum DatasetTypes type, const char *save,
                     memcap > 0 ? memcap : default_memcap, hashsize);
             if (set->hash == NULL)
                 goto out_err;
-            if (DatasetLoadIPv4(set) < 0)
+            if (set->store == NULL && DatasetLoadIPv4(set) < 0)
                 goto out_err;
             break;
         case DATASET_TYPE_IPV6:
@@ -473,7 +537,7 @@ Dataset *DatasetGet(const char *name, enum DatasetTypes type, const char *save,
                     memcap > 0 ? memcap : default_memcap, hashsize);
             if (set->hash == NULL)
                 goto out_err;
-            if (DatasetLoadIPv6(set) < 0)
+            if (set->store == NULL && DatasetLoadIPv6(set) < 0)
                 goto out_err;
             break;
     }
@@ -503,12 +567,21 @@ out_err:
         if (set->hash) {
             THashShutdown(set->hash);
         }
+        SCDatasetBloomFree(set->bloom);
+        SCDatasetSwapFree(set->store);
         SCFree(set);
     }
     SCMutexUnlock(&sets_lock);
     return NULL;
 }
 
+Dataset *DatasetGet(const char *name, enum DatasetTypes type, const char *save, const char *load,
+        uint64_t memcap, uint32_t hashsize, bool nocase)
+{
+    return DatasetGetInternal(
+            name, type, save, load, memcap, hashsize, 0, 0.0, DSBackendMemory, nocase);
+}
+
 static bool DatasetIsStatic(const char *save, const char *load)
 {
     /* A set is static if it does not have any dynamic properties like
@@ -527,10 +600,20 @@ void DatasetReload(void)
      * and clean them up later.
      * New datasets shall be created with the rule reload and do not require
      * any intervention.
+     * The read-only stores of the sets from the yaml are reloaded in place:
+     * the lookups keep using the current values until the new ones are
+     * loaded.
      * */
     SCMutexLock(&sets_lock);
     Dataset *set = sets;
     while (set) {
+        if (set->store != NULL && set->from_yaml) {
+            if (SCDatasetSwapReload(set->store, set->load, DSBackendMmap)) {
+                SCLogConfig("dataset: %s reloaded from '%s'", set->name, set->load);
+            }
+            set = set->next;
+            continue;
+        }
         if (!DatasetIsStatic(set->save, set->load) || set->from_yaml) {
             SCLogDebug("Not a static set, skipping %s", set->name);
             set = set->next;
@@ -567,6 +650,8 @@ void DatasetPostReloadCleanup(void)
             sets = next;
         }
         THashShutdown(cur->hash);
+        SCDatasetBloomFree(cur->bloom);
+        SCDatasetSwapFree(cur->store);
         SCFree(cur);
         cur = next;
     }
@@ -641,6 +726,10 @@ int DatasetsInit(void)
             char load[PATH_MAX] = "";
             uint64_t memcap = 0;
             uint32_t hashsize = 0;
+            uint64_t bloom_entries = 0;
+            double bloom_fp_rate = DATASET_BLOOM_DEFAULT_FP_RATE;
+            DatasetBackend backend = DSBackendMemory;
+            bool nocase = false;
 
             const char *set_name = iter->name;
             if (strlen(set_name) > DATASET_NAME_MAX_LEN) {
@@ -684,15 +773,50 @@ int DatasetsInit(void)
                     hashsize = 0;
                 }
             }
+            SCConfNode *set_bloom_entries = SCConfNodeLookupChild(iter, "bloom-entries");
+            if (set_bloom_entries) {
+                if (StringParseUint64(&bloom_entries, 10, 0, set_bloom_entries->val) <= 0) {
+                    SCLogWarning("bloom-entries value cannot be"
+                                 " deduced: %s, using an exact set",
+                            set_bloom_entries->val);
+                    bloom_entries = 0;
+                }
+            }
+            SCConfNode *set_bloom_fp_rate = SCConfNodeLookupChild(iter, "bloom-fp-rate");
+            if (set_bloom_fp_rate) {
+                char *endptr = NULL;
+                bloom_fp_rate = strtod(set_bloom_fp_rate->val, &endptr);
+                if (endptr == set_bloom_fp_rate->val || *endptr != '\0') {
+                    SCLogWarning("bloom-fp-rate value cannot be"
+                                 " deduced: %s, resetting to default",
+                            set_bloom_fp_rate->val);
+                    bloom_fp_rate = DATASET_BLOOM_DEFAULT_FP_RATE;
+                }
+            }
+            SCConfNode *set_backend = SCConfNodeLookupChild(iter, "backend");
+            if (set_backend) {
+                if (strcmp(set_backend->val, "mmap") == 0) {
+                    backend = DSBackendMmap;
+                } else if (strcmp(set_backend->val, "memory") != 0) {
+                    SCLogWarning("backend value cannot be"
+                                 " deduced: %s, using memory",
+                            set_backend->val);
+                }
+            }
+            SCConfNode *set_nocase = SCConfNodeLookupChild(iter, "nocase");
+            if (set_nocase) {
+                nocase = SCConfValIsTrue(set_nocase->val);
+            }
             char conf_str[1024];
             snprintf(conf_str, sizeof(conf_str), "datasets.%d.%s", list_pos, set_name);
 
             SCLogDebug("set %s type %s. Conf %s", set_name, set_type->val, conf_str);
 
             if (strcmp(set_type->val, "md5") == 0) {
-                Dataset *dset = DatasetGet(set_name, DATASET_TYPE_MD5, save, load,
+                Dataset *dset = DatasetGetInternal(set_name, DATASET_TYPE_MD5, save, load,
                         memcap > 0 ? memcap : default_memcap,
-                        hashsize > 0 ? hashsize : default_hashsize);
+                        hashsize > 0 ? hashsize : default_hashsize, bloom_entries, bloom_fp_rate,
+                        backend, nocase);
                 if (dset == NULL) {
                     FatalErrorOnInit("failed to setup dataset for %s", set_name);
                     continue;
@@ -701,9 +825,10 @@ int DatasetsInit(void)
                 dset->from_yaml = true;
 
             } else if (strcmp(set_type->val, "sha256") == 0) {
-                Dataset *dset = DatasetGet(set_name, DATASET_TYPE_SHA256, save, load,
+                Dataset *dset = DatasetGetInternal(set_name, DATASET_TYPE_SHA256, save, load,
                         memcap > 0 ? memcap : default_memcap,
-                        hashsize > 0 ? hashsize : default_hashsize);
+                        hashsize > 0 ? hashsize : default_hashsize, bloom_entries, bloom_fp_rate,
+                        backend, nocase);
                 if (dset == NULL) {
                     FatalErrorOnInit("failed to setup dataset for %s", set_name);
                     continue;
@@ -712,9 +837,10 @@ int DatasetsInit(void)
                 dset->from_yaml = true;
 
             } else if (strcmp(set_type->val, "string") == 0) {
-                Dataset *dset = DatasetGet(set_name, DATASET_TYPE_STRING, save, load,
+                Dataset *dset = DatasetGetInternal(set_name, DATASET_TYPE_STRING, save, load,
                         memcap > 0 ? memcap : default_memcap,
-                        hashsize > 0 ? hashsize : default_hashsize);
+                        hashsize > 0 ? hashsize : default_hashsize, bloom_entries, bloom_fp_rate,
+                        backend, nocase);
                 if (dset == NULL) {
                     FatalErrorOnInit("failed to setup dataset for %s", set_name);
                     continue;
@@ -723,9 +849,10 @@ int DatasetsInit(void)
                 dset->from_yaml = true;
 
             } else if (strcmp(set_type->val, "ipv4") == 0) {
-                Dataset *dset = DatasetGet(set_name, DATASET_TYPE_IPV4, save, load,
+                Dataset *dset = DatasetGetInternal(set_name, DATASET_TYPE_IPV4, save, load,
                         memcap > 0 ? memcap : default_memcap,
-                        hashsize > 0 ? hashsize : default_hashsize);
+                        hashsize > 0 ? hashsize : default_hashsize, bloom_entries, bloom_fp_rate,
+                        backend, nocase);
                 if (dset == NULL) {
                     FatalErrorOnInit("failed to setup dataset for %s", set_name);
                     continue;
@@ -734,9 +861,10 @@ int DatasetsInit(void)
                 dset->from_yaml = true;
 
             } else if (strcmp(set_type->val, "ip") == 0) {
-                Dataset *dset = DatasetGet(set_name, DATASET_TYPE_IPV6, save, load,
+                Dataset *dset = DatasetGetInternal(set_name, DATASET_TYPE_IPV6, save, load,
                         memcap > 0 ? memcap : default_memcap,
-                        hashsize > 0 ? hashsize : default_hashsize);
+                        hashsize > 0 ? hashsize : default_hashsize, bloom_entries, bloom_fp_rate,
+                        backend, nocase);
                 if (dset == NULL) {
                     FatalErrorOnInit("failed to setup dataset for %s", set_name);
                     continue;
@@ -761,6 +889,8 @@ void DatasetsDestroy(void)
         SCLogDebug("destroying set %s", set->name);
         Dataset *next = set->next;
         THashShutdown(set->hash);
+        SCDatasetBloomFree(set->bloom);
+        SCDatasetSwapFree(set->store);
         SCFree(set);
         set = next;
     }
@@ -830,6 +960,38 @@ static int IPv6AsAscii(const void *s, char *out, size_t out_size)
     return (int)strlen(out);
 }
 
+/* JSON lines formatters, for the sets loaded from JSON lines */
+static int StringAsJson(const void *s, char *out, size_t out_size)
+{
+    const StringType *str = s;
+    return SCDatasetJsonLine(DSString, str->ptr, str->len, &str->rep, out, out_size);
+}
+
+static int Md5AsJson(const void *s, char *out, size_t out_size)
+{
+    const Md5Type *md5 = s;
+    return SCDatasetJsonLine(DSMd5, md5->md5, sizeof(md5->md5), &md5->rep, out, out_size);
+}
+
+static int Sha256AsJson(const void *s, char *out, size_t out_size)
+{
+    const Sha256Type *sha = s;
+    return SCDatasetJsonLine(
+            DSSha256, sha->sha256, sizeof(sha->sha256), &sha->rep, out, out_size);
+}
+
+static int IPv4AsJson(const void *s, char *out, size_t out_size)
+{
+    const IPv4Type *ip4 = s;
+    return SCDatasetJsonLine(DSIpv4, ip4->ipv4, sizeof(ip4->ipv4), &ip4->rep, out, out_size);
+}
+
+static int IPv6AsJson(const void *s, char *out, size_t out_size)
+{
+    const IPv6Type *ip6 = s;
+    return SCDatasetJsonLine(DSIpv6, ip6->ipv6, sizeof(ip6->ipv6), &ip6->rep, out, out_size);
+}
+
 void DatasetsSave(void)
 {
     SCLogDebug("saving datasets: %p", sets);
@@ -847,19 +1009,19 @@ void DatasetsSave(void)
 
         switch (set->type) {
             case DATASET_TYPE_STRING:
-                THashWalk(set->hash, StringAsBase64, SaveCallback, fp);
+                THashWalk(set->hash, set->json ? StringAsJson : StringAsBase64, SaveCallback, fp);
                 break;
             case DATASET_TYPE_MD5:
-                THashWalk(set->hash, Md5AsAscii, SaveCallback, fp);
+                THashWalk(set->hash, set->json ? Md5AsJson : Md5AsAscii, SaveCallback, fp);
                 break;
             case DATASET_TYPE_SHA256:
-                THashWalk(set->hash, Sha256AsAscii, SaveCallback, fp);
+                THashWalk(set->hash, set->json ? Sha256AsJson : Sha256AsAscii, SaveCallback, fp);
                 break;
             case DATASET_TYPE_IPV4:
-                THashWalk(set->hash, IPv4AsAscii, SaveCallback, fp);
+                THashWalk(set->hash, set->json ? IPv4AsJson : IPv4AsAscii, SaveCallback, fp);
                 break;
             case DATASET_TYPE_IPV6:
-                THashWalk(set->hash, IPv6AsAscii, SaveCallback, fp);
+                THashWalk(set->hash, set->json ? IPv6AsJson : IPv6AsAscii, SaveCallback, fp);
                 break;
         }
 
@@ -871,6 +1033,80 @@ void DatasetsSave(void)
     SCMutexUnlock(&sets_lock);
 }
 
+/** \brief get a value in the form the hash stores it
+ *
+ *  For the sets that don't keep their values in the hash: an IPv4 address
+ *  in an IPv6 set is padded to 16 bytes in \a ipv6.
+ *
+ *  \retval false invalid data
+ */
+static bool DatasetNormalize(
+        const Dataset *set, const uint8_t **data, uint32_t *data_len, uint8_t ipv6[16])
+{
+    switch (set->type) {
+        case DATASET_TYPE_IPV4:
+            if (*data_len < 4)
+                return false;
+            *data_len = 4;
+            break;
+        case DATASET_TYPE_IPV6:
+            if (*data_len != 16 && *data_len != 4)
+                return false;
+            memset(ipv6, 0, 16);
+            memcpy(ipv6, *data, *data_len);
+            *data = ipv6;
+            *data_len = 16;
+            break;
+        default:
+            break;
+    }
+    return true;
+}
+
+/** \brief add to or look up in the bloom filter of a set
+ *
+ *  Values are stored in the same form as in the hash.
+ *
+ *  \retval 1 added / found
+ *  \retval 0 already there / not found
+ *  \retval -2 invalid data
+ */
+static int DatasetBloomOp(Dataset *set, const uint8_t *data, uint32_t data_len, const bool add)
+{
+    uint8_t ipv6[16];
+
+    if (!DatasetNormalize(set, &data, &data_len, ipv6))
+        return -2;
+    if (add)
+        return SCDatasetBloomAdd(set->bloom, data, data_len) ? 1 : 0;
+    return SCDatasetBloomLookup(set->bloom, data, data_len) ? 1 : 0;
+}
+
+static int DatasetBloomAdd(Dataset *set, const uint8_t *data, const uint32_t data_len)
+{
+    return DatasetBloomOp(set, data, data_len, true);
+}
+
+static int DatasetBloomLookup(Dataset *set, const uint8_t *data, const uint32_t data_len)
+{
+    return DatasetBloomOp(set, data, data_len, false);
+}
+
+/** \brief look up in the read-only store of a set
+ *
+ *  \retval 1 found
+ *  \retval 0 not found
+ *  \retval -2 invalid data
+ */
+static int DatasetStoreLookup(Dataset *set, const uint8_t *data, uint32_t data_len)
+{
+    uint8_t ipv6[16];
+
+    if (!DatasetNormalize(set, &data, &data_len, ipv6))
+        return -2;
+    return SCDatasetSwapLookup(set->store, data, data_len) ? 1 : 0;
+}
+
 static int DatasetLookupString(Dataset *set, const uint8_t *data, const uint32_t data_len)
 {
     if (set == NULL)
@@ -1078,14 +1314,25 @@ static DataRepResultType DatasetLookupSha256wRep(Dataset *set,
  *  \param set dataset
  *  \param data data to look up
  *  \param data_len length in bytes of \a data
+ *  \param ts time of the packet being inspected, to expire entries with
  *  \retval -1 error
  *  \retval 0 not found
  *  \retval 1 found
  */
-int DatasetLookup(Dataset *set, const uint8_t *data, const uint32_t data_len)
+int DatasetLookup(Dataset *set, const uint8_t *data, const uint32_t data_len, const SCTime_t ts)
 {
     if (set == NULL)
         return -1;
+    if (set->bloom != NULL)
+        return DatasetBloomLookup(set, data, data_len);
+    if (set->store != NULL)
+        return DatasetStoreLookup(set, data, data_len);
+
+    if (set->ttl != 0) {
+        const DataRepType rep = { .value = 0 };
+        DataRepResultType rrep = DatasetLookupwRep(set, data, data_len, &rep, ts);
+        return rrep.found ? 1 : 0;
+    }
 
     switch (set->type) {
         case DATASET_TYPE_STRING:
@@ -1103,33 +1350,71 @@ int DatasetLookup(Dataset *set, const uint8_t *data, const uint32_t data_len)
 }
 
 DataRepResultType DatasetLookupwRep(Dataset *set, const uint8_t *data, const uint32_t data_len,
-        const DataRepType *rep)
+        const DataRepType *rep, const SCTime_t ts)
 {
     DataRepResultType rrep = { .found = false, .rep = { .value = 0 }};
     if (set == NULL)
         return rrep;
+    /* no reputation in a bloom filter, just membership */
+    if (set->bloom != NULL) {
+        rrep.found = DatasetBloomLookup(set, data, data_len) == 1;
+        return rrep;
+    }
+    if (set->store != NULL) {
+        rrep.found = DatasetStoreLookup(set, data, data_len) == 1;
+        return rrep;
+    }
 
     switch (set->type) {
         case DATASET_TYPE_STRING:
-            return DatasetLookupStringwRep(set, data, data_len, rep);
+            rrep = DatasetLookupStringwRep(set, data, data_len, rep);
+            break;
         case DATASET_TYPE_MD5:
-            return DatasetLookupMd5wRep(set, data, data_len, rep);
+            rrep = DatasetLookupMd5wRep(set, data, data_len, rep);
+            break;
         case DATASET_TYPE_SHA256:
-            return DatasetLookupSha256wRep(set, data, data_len, rep);
+            rrep = DatasetLookupSha256wRep(set, data, data_len, rep);
+            break;
         case DATASET_TYPE_IPV4:
-            return DatasetLookupIPv4wRep(set, data, data_len, rep);
+            rrep = DatasetLookupIPv4wRep(set, data, data_len, rep);
+            break;
         case DATASET_TYPE_IPV6:
-            return DatasetLookupIPv6wRep(set, data, data_len, rep);
+            rrep = DatasetLookupIPv6wRep(set, data, data_len, rep);
+            break;
+    }
+
+    /* expired entries are treated as absent and pruned on access */
+    if (rrep.found && SCDatasetRepExpired(&rrep.rep, set->ttl, SCTIME_SECS(ts))) {
+        (void)DatasetRemove(set, data, data_len);
+        rrep.found = false;
+        rrep.rep = (DataRepType){ .value = 0 };
     }
     return rrep;
 }
 
+/** \brief update count and insertion time of an added entry
+ *
+ *  An expired entry that is added again starts over as a new one.
+ */
+static inline void DatasetRepAdded(
+        const Dataset *set, DataRepType *rep, const bool is_new, const SCTime_t ts)
+{
+    const uint64_t now = SCTIME_SECS(ts);
+    /* a new entry keeps the count it was loaded with */
+    if (is_new && rep->count > 0) {
+        rep->ts = now;
+        return;
+    }
+    SCDatasetRepAdded(rep, is_new || SCDatasetRepExpired(rep, set->ttl, now), now);
+}
+
 /**
  *  \retval 1 data was added to the hash
  *  \retval 0 data was not added to the hash as it is already there
  *  \retval -1 failed to add data to the hash
  */
-static int DatasetAddString(Dataset *set, const uint8_t *data, const uint32_t data_len)
+static int DatasetAddString(
+        Dataset *set, const uint8_t *data, const uint32_t data_len, const SCTime_t ts)
 {
     if (set == NULL)
         return -1;
@@ -1138,6 +1423,7 @@ static int DatasetAddString(Dataset *set, const uint8_t *data, const uint32_t da
         .rep.value = 0 };
     struct THashDataGetResult res = THashGetFromHash(set->hash, &lookup);
     if (res.data) {
+        DatasetRepAdded(set, &((StringType *)res.data->data)->rep, res.is_new, ts);
         DatasetUnlockData(res.data);
         return res.is_new ? 1 : 0;
     }
@@ -1149,8 +1435,8 @@ static int DatasetAddString(Dataset *set, const uint8_t *data, const uint32_t da
  *  \retval 0 data was not added to the hash as it is already there
  *  \retval -1 failed to add data to the hash
  */
-static int DatasetAddStringwRep(
-        Dataset *set, const uint8_t *data, const uint32_t data_len, const DataRepType *rep)
+static int DatasetAddStringwRep(Dataset *set, const uint8_t *data, const uint32_t data_len,
+        const DataRepType *rep, const SCTime_t ts)
 {
     if (set == NULL)
         return -1;
@@ -1159,13 +1445,15 @@ static int DatasetAddStringwRep(
         .rep = *rep };
     struct THashDataGetResult res = THashGetFromHash(set->hash, &lookup);
     if (res.data) {
+        DatasetRepAdded(set, &((StringType *)res.data->data)->rep, res.is_new, ts);
         DatasetUnlockData(res.data);
         return res.is_new ? 1 : 0;
     }
     return -1;
 }
 
-static int DatasetAddIPv4(Dataset *set, const uint8_t *data, const uint32_t data_len)
+static int DatasetAddIPv4(
+        Dataset *set, const uint8_t *data, const uint32_t data_len, const SCTime_t ts)
 {
     if (set == NULL) {
         return -1;
@@ -1179,13 +1467,15 @@ static int DatasetAddIPv4(Dataset *set, const uint8_t *data, const uint32_t data
     memcpy(lookup.ipv4, data, 4);
     struct THashDataGetResult res = THashGetFromHash(set->hash, &lookup);
     if (res.data) {
+        DatasetRepAdded(set, &((IPv4Type *)res.data->data)->rep, res.is_new, ts);
         DatasetUnlockData(res.data);
         return res.is_new ? 1 : 0;
     }
     return -1;
 }
 
-static int DatasetAddIPv6(Dataset *set, const uint8_t *data, const uint32_t data_len)
+static int DatasetAddIPv6(
+        Dataset *set, const uint8_t *data, const uint32_t data_len, const SCTime_t ts)
 {
     if (set == NULL) {
         return -1;
@@ -1199,14 +1489,15 @@ static int DatasetAddIPv6(Dataset *set, const uint8_t *data, const uint32_t data
     memcpy(lookup.ipv6, data, data_len);
     struct THashDataGetResult res = THashGetFromHash(set->hash, &lookup);
     if (res.data) {
+        DatasetRepAdded(set, &((IPv6Type *)res.data->data)->rep, res.is_new, ts);
         DatasetUnlockData(res.data);
         return res.is_new ? 1 : 0;
     }
     return -1;
 }
 
-static int DatasetAddIPv4wRep(
-        Dataset *set, const uint8_t *data, const uint32_t data_len, const DataRepType *rep)
+static int DatasetAddIPv4wRep(Dataset *set, const uint8_t *data, const uint32_t data_len,
+        const DataRepType *rep, const SCTime_t ts)
 {
     if (set == NULL)
         return -1;
@@ -1218,14 +1509,15 @@ static int DatasetAddIPv4wRep(
     memcpy(lookup.ipv4, data, 4);
     struct THashDataGetResult res = THashGetFromHash(set->hash, &lookup);
     if (res.data) {
+        DatasetRepAdded(set, &((IPv4Type *)res.data->data)->rep, res.is_new, ts);
         DatasetUnlockData(res.data);
         return res.is_new ? 1 : 0;
     }
     return -1;
 }
 
-static int DatasetAddIPv6wRep(
-        Dataset *set, const uint8_t *data, const uint32_t data_len, const DataRepType *rep)
+static int DatasetAddIPv6wRep(Dataset *set, const uint8_t *data, const uint32_t data_len,
+        const DataRepType *rep, const SCTime_t ts)
 {
     if (set == NULL)
         return -1;
@@ -1237,13 +1529,15 @@ static int DatasetAddIPv6wRep(
     memcpy(lookup.ipv6, data, 16);
     struct THashDataGetResult res = THashGetFromHash(set->hash, &lookup);
     if (res.data) {
+        DatasetRepAdded(set, &((IPv6Type *)res.data->data)->rep, res.is_new, ts);
         DatasetUnlockData(res.data);
         return res.is_new ? 1 : 0;
     }
     return -1;
 }
 
-static int DatasetAddMd5(Dataset *set, const uint8_t *data, const uint32_t data_len)
+static int DatasetAddMd5(
+        Dataset *set, const uint8_t *data, const uint32_t data_len, const SCTime_t ts)
 {
     if (set == NULL)
         return -1;
@@ -1255,14 +1549,15 @@ static int DatasetAddMd5(Dataset *set, const uint8_t *data, const uint32_t data_
     memcpy(lookup.md5, data, 16);
     struct THashDataGetResult res = THashGetFromHash(set->hash, &lookup);
     if (res.data) {
+        DatasetRepAdded(set, &((Md5Type *)res.data->data)->rep, res.is_new, ts);
         DatasetUnlockData(res.data);
         return res.is_new ? 1 : 0;
     }
     return -1;
 }
 
-static int DatasetAddMd5wRep(
-        Dataset *set, const uint8_t *data, const uint32_t data_len, const DataRepType *rep)
+static int DatasetAddMd5wRep(Dataset *set, const uint8_t *data, const uint32_t data_len,
+        const DataRepType *rep, const SCTime_t ts)
 {
     if (set == NULL)
         return -1;
@@ -1274,14 +1569,15 @@ static int DatasetAddMd5wRep(
     memcpy(lookup.md5, data, 16);
     struct THashDataGetResult res = THashGetFromHash(set->hash, &lookup);
     if (res.data) {
+        DatasetRepAdded(set, &((Md5Type *)res.data->data)->rep, res.is_new, ts);
         DatasetUnlockData(res.data);
         return res.is_new ? 1 : 0;
     }
     return -1;
 }
 
-static int DatasetAddSha256wRep(
-        Dataset *set, const uint8_t *data, const uint32_t data_len, const DataRepType *rep)
+static int DatasetAddSha256wRep(Dataset *set, const uint8_t *data, const uint32_t data_len,
+        const DataRepType *rep, const SCTime_t ts)
 {
     if (set == NULL)
         return -1;
@@ -1293,13 +1589,15 @@ static int DatasetAddSha256wRep(
     memcpy(lookup.sha256, data, 32);
     struct THashDataGetResult res = THashGetFromHash(set->hash, &lookup);
     if (res.data) {
+        DatasetRepAdded(set, &((Sha256Type *)res.data->data)->rep, res.is_new, ts);
         DatasetUnlockData(res.data);
         return res.is_new ? 1 : 0;
     }
     return -1;
 }
 
-static int DatasetAddSha256(Dataset *set, const uint8_t *data, const uint32_t data_len)
+static int DatasetAddSha256(
+        Dataset *set, const uint8_t *data, const uint32_t data_len, const SCTime_t ts)
 {
     if (set == NULL)
         return -1;
@@ -1311,48 +1609,73 @@ static int DatasetAddSha256(Dataset *set, const uint8_t *data, const uint32_t da
     memcpy(lookup.sha256, data, 32);
     struct THashDataGetResult res = THashGetFromHash(set->hash, &lookup);
     if (res.data) {
+        DatasetRepAdded(set, &((Sha256Type *)res.data->data)->rep, res.is_new, ts);
         DatasetUnlockData(res.data);
         return res.is_new ? 1 : 0;
     }
     return -1;
 }
 
-int DatasetAdd(Dataset *set, const uint8_t *data, const uint32_t data_len)
+/**
+ *  \brief add \a data to the set
+ *  \param ts time of the packet \a data comes from, the insertion time
+ *             of the entry
+ *  \retval 1 data was added to the set
+ *  \retval 0 data was not added to the set as it is already there
+ *  \retval -1 failed to add data to the set
+ */
+int DatasetAddTs(Dataset *set, const uint8_t *data, const uint32_t data_len, const SCTime_t ts)
 {
     if (set == NULL)
         return -1;
+    if (set->bloom != NULL)
+        return DatasetBloomAdd(set, data, data_len);
+    /* the store is read-only */
+    if (set->store != NULL)
+        return -1;
 
     switch (set->type) {
         case DATASET_TYPE_STRING:
-            return DatasetAddString(set, data, data_len);
+            return DatasetAddString(set, data, data_len, ts);
         case DATASET_TYPE_MD5:
-            return DatasetAddMd5(set, data, data_len);
+            return DatasetAddMd5(set, data, data_len, ts);
         case DATASET_TYPE_SHA256:
-            return DatasetAddSha256(set, data, data_len);
+            return DatasetAddSha256(set, data, data_len, ts);
         case DATASET_TYPE_IPV4:
-            return DatasetAddIPv4(set, data, data_len);
+            return DatasetAddIPv4(set, data, data_len, ts);
         case DATASET_TYPE_IPV6:
-            return DatasetAddIPv6(set, data, data_len);
+            return DatasetAddIPv6(set, data, data_len, ts);
     }
     return -1;
 }
 
+/** \brief add \a data to the set, outside of the packet path, at the current time */
+int DatasetAdd(Dataset *set, const uint8_t *data, const uint32_t data_len)
+{
+    return DatasetAddTs(set, data, data_len, TimeGet());
+}
+
 int DatasetAddwRep(Dataset *set, const uint8_t *data, const uint32_t data_len, DataRepType *rep)
 {
     if (set == NULL)
         return -1;
+    /* a bloom filter can't hold the reputation, and the store is read-only */
+    if (set->bloom != NULL || set->store != NULL)
+        return -1;
 
+    /* entries with a reputation are loaded, not added from packets */
+    const SCTime_t ts = TimeGet();
     switch (set->type) {
         case DATASET_TYPE_STRING:
-            return DatasetAddStringwRep(set, data, data_len, rep);
+            return DatasetAddStringwRep(set, data, data_len, rep, ts);
         case DATASET_TYPE_MD5:
-            return DatasetAddMd5wRep(set, data, data_len, rep);
+            return DatasetAddMd5wRep(set, data, data_len, rep, ts);
         case DATASET_TYPE_SHA256:
-            return DatasetAddSha256wRep(set, data, data_len, rep);
+            return DatasetAddSha256wRep(set, data, data_len, rep, ts);
         case DATASET_TYPE_IPV4:
-            return DatasetAddIPv4wRep(set, data, data_len, rep);
+            return DatasetAddIPv4wRep(set, data, data_len, rep, ts);
         case DATASET_TYPE_IPV6:
-            return DatasetAddIPv6wRep(set, data, data_len, rep);
+            return DatasetAddIPv6wRep(set, data, data_len, rep, ts);
     }
     return -1;
 }
@@ -1426,8 +1749,14 @@ static int DatasetOpSerialized(Dataset *set, const char *string, DatasetOpFunc D
  */
 int DatasetAddSerialized(Dataset *set, const char *string)
 {
-    return DatasetOpSerialized(set, string, DatasetAddString, DatasetAddMd5, DatasetAddSha256,
-            DatasetAddIPv4, DatasetAddIPv6);
+    if (set != NULL && set->bloom != NULL) {
+        return DatasetOpSerialized(set, string, DatasetBloomAdd, DatasetBloomAdd, DatasetBloomAdd,
+                DatasetBloomAdd, DatasetBloomAdd);
+    }
+    if (set != NULL && set->store != NULL)
+        return -1;
+    return DatasetOpSerialized(
+            set, string, DatasetAdd, DatasetAdd, DatasetAdd, DatasetAdd, DatasetAdd);
 }
 
 /** \brief add serialized data to set
@@ -1438,6 +1767,14 @@ int DatasetAddSerialized(Dataset *set, const char *string)
  */
 int DatasetLookupSerialized(Dataset *set, const char *string)
 {
+    if (set != NULL && set->bloom != NULL) {
+        return DatasetOpSerialized(set, string, DatasetBloomLookup, DatasetBloomLookup,
+                DatasetBloomLookup, DatasetBloomLookup, DatasetBloomLookup);
+    }
+    if (set != NULL && set->store != NULL) {
+        return DatasetOpSerialized(set, string, DatasetStoreLookup, DatasetStoreLookup,
+                DatasetStoreLookup, DatasetStoreLookup, DatasetStoreLookup);
+    }
     return DatasetOpSerialized(set, string, DatasetLookupString, DatasetLookupMd5,
             DatasetLookupSha256, DatasetLookupIPv4, DatasetLookupIPv6);
 }
@@ -1516,6 +1853,8 @@ static int DatasetRemoveSha256(Dataset *set, const uint8_t *data, const uint32_t
  *  \retval int -2 DATA error */
 int DatasetRemoveSerialized(Dataset *set, const char *string)
 {
+    if (set != NULL && (set->bloom != NULL || set->store != NULL))
+        return -1;
     return DatasetOpSerialized(set, string, DatasetRemoveString, DatasetRemoveMd5,
             DatasetRemoveSha256, DatasetRemoveIPv4, DatasetRemoveIPv6);
 }
@@ -1524,6 +1863,9 @@ int DatasetRemove(Dataset *set, const uint8_t *data, const uint32_t data_len)
 {
     if (set == NULL)
         return -1;
+    /* values can't be removed from a bloom filter or the read-only store */
+    if (set->bloom != NULL || set->store != NULL)
+        return -1;
 
     switch (set->type) {
         case DATASET_TYPE_STRING:
@@ -38,6 +38,7 @@ enum DatasetTypes {
 };
 
 #define DATASET_NAME_MAX_LEN 63
+#define DATASET_BLOOM_DEFAULT_FP_RATE 0.01
 typedef struct Dataset {
     char name[DATASET_NAME_MAX_LEN + 1];
     enum DatasetTypes type;
@@ -45,6 +46,11 @@ typedef struct Dataset {
     bool from_yaml;                     /* Mark whether the set was retrieved from YAML */
     bool hidden;                        /* Mark the old sets hidden in case of reload */
     THashTableContext *hash;
+    uint32_t ttl;                       /* seconds an entry stays valid, 0 for no expiry */
+    DatasetBloom *bloom;                /* if set, membership is only tracked in this filter */
+    DatasetSwap *store;                 /* if set, the values are only in this read-only store */
+    bool nocase;                        /* string set matching values whatever their case */
+    bool json;                          /* loaded from JSON lines, saved the same way */
 
     char load[PATH_MAX];
     char save[PATH_MAX];
@@ -55,12 +61,13 @@ typedef struct Dataset {
 enum DatasetTypes DatasetGetTypeFromString(const char *s);
 Dataset *DatasetFind(const char *name, enum DatasetTypes type);
 Dataset *DatasetGet(const char *name, enum DatasetTypes type, const char *save, const char *load,
-        uint64_t memcap, uint32_t hashsize);
+        uint64_t memcap, uint32_t hashsize, bool nocase);
 int DatasetAdd(Dataset *set, const uint8_t *data, const uint32_t data_len);
+int DatasetAddTs(Dataset *set, const uint8_t *data, const uint32_t data_len, const SCTime_t ts);
 int DatasetRemove(Dataset *set, const uint8_t *data, const uint32_t data_len);
-int DatasetLookup(Dataset *set, const uint8_t *data, const uint32_t data_len);
+int DatasetLookup(Dataset *set, const uint8_t *data, const uint32_t data_len, const SCTime_t ts);
 DataRepResultType DatasetLookupwRep(Dataset *set, const uint8_t *data, const uint32_t data_len,
-        const DataRepType *rep);
+        const DataRepType *rep, const SCTime_t ts);
 
 int DatasetAddSerialized(Dataset *set, const char *string);
 int DatasetRemoveSerialized(Dataset *set, const char *string);
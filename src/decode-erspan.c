@@ -105,6 +105,7 @@ int DecodeERSPAN(ThreadVars *tv, DecodeThreadVars *dtv, Packet *p, const uint8_t
             return TM_ECODE_FAILED;
         }
         p->vlan_id[p->vlan_idx] = vlan_id;
+        p->vlan_pcp[p->vlan_idx] = 0;
         p->vlan_idx++;
     }
 
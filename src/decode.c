@@ -473,6 +473,7 @@ Packet *PacketDefragPktSetup(Packet *parent, const uint8_t *pkt, uint32_t len, u
     p->ts = parent->ts;
     p->tenant_id = parent->tenant_id;
     memcpy(&p->vlan_id[0], &parent->vlan_id[0], sizeof(p->vlan_id));
+    memcpy(&p->vlan_pcp[0], &parent->vlan_pcp[0], sizeof(p->vlan_pcp));
     p->vlan_idx = parent->vlan_idx;
     p->livedev = parent->livedev;
 
@@ -518,6 +518,8 @@ typedef struct Packet_
 
     uint16_t vlan_id[VLAN_MAX_LAYERS];
     uint8_t vlan_idx;
+    /* priority code point of each vlan layer */
+    uint8_t vlan_pcp[VLAN_MAX_LAYERS];
 
     /* flow */
     uint8_t flowflags;
@@ -56,6 +56,8 @@
 #define STRING_MAX_BYTES_TO_EXTRACT_FOR_HEX 14
 /* the max no of bytes that can be extracted in non-string mode */
 #define NO_STRING_MAX_BYTES_TO_EXTRACT 8
+/* the max no of base64 encoded bytes, decoding to at most 8 bytes */
+#define BASE64_MAX_BYTES_TO_EXTRACT 12
 
 static int DetectByteExtractSetup(DetectEngineCtx *, Signature *, const char *);
 #ifdef UNITTESTS
@@ -125,7 +127,14 @@ int DetectByteExtractDoMatch(DetectEngineThreadCtx *det_ctx, const SigMatchData
     /* Extract the byte data */
     uint64_t val = 0;
     int extbytes;
-    if (data->flags & DETECT_BYTE_EXTRACT_FLAG_STRING) {
+    if (data->flags & DETECT_BYTE_EXTRACT_FLAG_BASE64) {
+        /* malformed base64 never matches */
+        if (SCByteExtractBase64Uint64(ptr, data->nbytes, endian, &val) == 0) {
+            SCLogDebug("error decoding %d bytes of base64 data", data->nbytes);
+            return 0;
+        }
+        extbytes = data->nbytes;
+    } else if (data->flags & DETECT_BYTE_EXTRACT_FLAG_STRING) {
         extbytes = ByteExtractStringUint64(&val, data->base,
                                            data->nbytes, (const char *)ptr);
         if (extbytes <= 0) {
@@ -149,12 +158,10 @@ int DetectByteExtractDoMatch(DetectEngineThreadCtx *det_ctx, const SigMatchData
         }
     }
 
-    /* Adjust the jump value based on flags */
-    val *= data->multiplier_value;
-    if (data->flags & DETECT_BYTE_EXTRACT_FLAG_ALIGN) {
-        if ((val % data->align_value) != 0) {
-            val += data->align_value - (val % data->align_value);
-        }
+    /* Adjust the jump value based on flags; overflow is a non-match */
+    if (!SCByteExtractScaleValue(data, val, &val)) {
+        SCLogDebug("extracted value %" PRIu64 " overflows after multiplier/align", val);
+        return 0;
     }
 
     ptr += extbytes;
@@ -189,7 +196,16 @@ static inline SCDetectByteExtractData *DetectByteExtractParse(
         SCLogError("byte_extract slice not yet supported; see issue #6831");
         goto error;
     }
-    if (bed->flags & DETECT_BYTE_EXTRACT_FLAG_STRING) {
+    if (bed->flags & DETECT_BYTE_EXTRACT_FLAG_BASE64) {
+        if (bed->nbytes > BASE64_MAX_BYTES_TO_EXTRACT) {
+            SCLogError("byte_extract can't process "
+                       "more than %d bytes in \"string, base64\" extraction",
+                    BASE64_MAX_BYTES_TO_EXTRACT);
+            goto error;
+        }
+        if (!(bed->flags & DETECT_BYTE_EXTRACT_FLAG_ENDIAN))
+            bed->endian = BigEndian;
+    } else if (bed->flags & DETECT_BYTE_EXTRACT_FLAG_STRING) {
         if (bed->base == DETECT_BYTE_EXTRACT_BASE_OCT) {
             /* if are dealing with octal nos, the max no that can fit in a 8
              * byte value is 01777777777777777777777 */
@@ -4049,6 +4065,45 @@ static int DetectByteExtractTest63(void)
     return result;
 }
 
+/**
+ * \test dce is resolved with the representation negotiated by the
+ *       DCE/RPC flow, and is little endian without one.
+ */
+static int DetectByteExtractTestDceEndian(void)
+{
+    const uint8_t buf[] = { 0x01, 0x02 };
+    uint64_t value = 0;
+
+    DetectEngineThreadCtx *det_ctx = SCCalloc(1, sizeof(*det_ctx));
+    FAIL_IF_NULL(det_ctx);
+    SCDetectByteExtractData *bed = DetectByteExtractParse(NULL, "2, 0, one, dce");
+    FAIL_IF_NULL(bed);
+    SigMatchData smd = {
+        .type = DETECT_BYTE_EXTRACT, .is_last = true, .ctx = (SigMatchCtx *)bed
+    };
+
+    uint8_t endian = SCDetectByteEndianResolve(bed->endian, true, false);
+    FAIL_IF_NOT(endian == LittleEndian);
+    FAIL_IF_NOT(
+            DetectByteExtractDoMatch(det_ctx, &smd, NULL, buf, sizeof(buf), &value, endian) == 1);
+    FAIL_IF_NOT(value == 0x0201);
+
+    endian = SCDetectByteEndianResolve(bed->endian, false, true);
+    FAIL_IF_NOT(endian == BigEndian);
+    FAIL_IF_NOT(
+            DetectByteExtractDoMatch(det_ctx, &smd, NULL, buf, sizeof(buf), &value, endian) == 1);
+    FAIL_IF_NOT(value == 0x0102);
+
+    endian = SCDetectByteEndianResolve(bed->endian, false, false);
+    FAIL_IF_NOT(
+            DetectByteExtractDoMatch(det_ctx, &smd, NULL, buf, sizeof(buf), &value, endian) == 1);
+    FAIL_IF_NOT(value == 0x0201);
+
+    DetectByteExtractFree(NULL, bed);
+    SCFree(det_ctx);
+    PASS;
+}
+
 static int DetectByteExtractTestParseNoBase(void)
 {
     int result = 0;
@@ -4162,6 +4217,7 @@ static void DetectByteExtractRegisterTests(void)
     UtRegisterTest("DetectByteExtractTest61", DetectByteExtractTest61);
     UtRegisterTest("DetectByteExtractTest62", DetectByteExtractTest62);
     UtRegisterTest("DetectByteExtractTest63", DetectByteExtractTest63);
+    UtRegisterTest("DetectByteExtractTestDceEndian", DetectByteExtractTestDceEndian);
 
     UtRegisterTest("DetectByteExtractTestParseNoBase",
                    DetectByteExtractTestParseNoBase);
@@ -44,26 +44,10 @@
 #include "detect-pcre.h"
 #include "detect-engine-build.h"
 
-/**
- * \brief Regex for parsing our options
- */
-#define PARSE_REGEX  "^\\s*" \
-                     "([^\\s,]+\\s*,\\s*[^\\s,]+)" \
-                     "(?:\\s*,\\s*((?:multiplier|post_offset)\\s+[^\\s,]+|[^\\s,]+))?" \
-                     "(?:\\s*,\\s*((?:multiplier|post_offset)\\s+[^\\s,]+|[^\\s,]+))?" \
-                     "(?:\\s*,\\s*((?:multiplier|post_offset)\\s+[^\\s,]+|[^\\s,]+))?" \
-                     "(?:\\s*,\\s*((?:multiplier|post_offset)\\s+[^\\s,]+|[^\\s,]+))?" \
-                     "(?:\\s*,\\s*((?:multiplier|post_offset)\\s+[^\\s,]+|[^\\s,]+))?" \
-                     "(?:\\s*,\\s*((?:multiplier|post_offset)\\s+[^\\s,]+|[^\\s,]+))?" \
-                     "(?:\\s*,\\s*((?:multiplier|post_offset)\\s+[^\\s,]+|[^\\s,]+))?" \
-                     "(?:\\s*,\\s*((?:multiplier|post_offset)\\s+[^\\s,]+|[^\\s,]+))?" \
-                     "(?:\\s*,\\s*((?:multiplier|post_offset)\\s+[^\\s,]+|[^\\s,]+))?" \
-                     "\\s*$"
-
-static DetectParseRegex parse_regex;
-
-static DetectBytejumpData *DetectBytejumpParse(
-        DetectEngineCtx *de_ctx, const char *optstr, char **nbytes, char **offset);
+#include "rust.h"
+
+static DetectBytejumpData *DetectBytejumpParse(DetectEngineCtx *de_ctx, const char *optstr,
+        char **nbytes, char **offset, char **post_offset);
 static int DetectBytejumpSetup(DetectEngineCtx *de_ctx, Signature *s, const char *optstr);
 static void DetectBytejumpFree(DetectEngineCtx*, void *ptr);
 #ifdef UNITTESTS
@@ -81,48 +65,6 @@ void DetectBytejumpRegister (void)
 #ifdef UNITTESTS
     sigmatch_table[DETECT_BYTEJUMP].RegisterTests = DetectBytejumpRegisterTests;
 #endif
-    DetectSetupParseRegexes(PARSE_REGEX, &parse_regex);
-}
-
-/* 23 - This is the largest string (octal, with a zero prefix) that
- *      will not overflow uint64_t.  The only way this length
- *      could be over 23 and still not overflow is if it were zero
- *      prefixed and we only support 1 byte of zero prefix for octal.
- *
- * "01777777777777777777777" = 0xffffffffffffffff
- *
- * 8 - Without string, the maximum byte extract count is 8.
- */
-static inline bool DetectBytejumpValidateNbytesOnly(const DetectBytejumpData *data, int32_t nbytes)
-{
-    return (data->flags & DETECT_BYTEJUMP_STRING && nbytes <= 23) || (nbytes <= 8);
-}
-
-static bool DetectBytejumpValidateNbytes(const DetectBytejumpData *data, int32_t nbytes)
-{
-    if (!DetectBytejumpValidateNbytesOnly(data, nbytes)) {
-        if (data->flags & DETECT_BYTEJUMP_STRING) {
-            /* 23 - This is the largest string (octal, with a zero prefix) that
-             *      will not overflow uint64_t.  The only way this length
-             *      could be over 23 and still not overflow is if it were zero
-             *      prefixed and we only support 1 byte of zero prefix for octal.
-             *
-             * "01777777777777777777777" = 0xffffffffffffffff
-             */
-            if (nbytes > 23) {
-                SCLogError("Cannot test more than 23 bytes "
-                           "with \"string\"");
-            }
-        } else {
-            if (nbytes > 8) {
-                SCLogError("Cannot test more than 8 bytes "
-                           "without \"string\"");
-            }
-        }
-        return false;
-    }
-
-    return true;
 }
 
 /** \brief Byte jump match function
@@ -136,319 +78,75 @@ static bool DetectBytejumpValidateNbytes(const DetectBytejumpData *data, int32_t
  */
 bool DetectBytejumpDoMatch(DetectEngineThreadCtx *det_ctx, const Signature *s,
         const SigMatchCtx *ctx, const uint8_t *payload, uint32_t payload_len, uint16_t flags,
-        int32_t nbytes, int32_t offset)
+        int32_t nbytes, int32_t offset, int64_t post_offset)
 {
     SCEnter();
 
     const DetectBytejumpData *data = (const DetectBytejumpData *)ctx;
-    const uint8_t *ptr = NULL;
-    int32_t len = 0;
-    uint64_t val = 0;
-    int extbytes;
-
-    if (payload_len == 0) {
-        SCReturnBool(false);
-    }
-
-    /* Validate the number of bytes we are testing
-     * If the validation is successful, we know that
-     * it contains a value <= 23. Thus, we can
-     * safely cast it when extracting bytes
-     */
-    if (data->flags & DETECT_BYTEJUMP_NBYTES_VAR) {
-        if (!DetectBytejumpValidateNbytesOnly(data, nbytes)) {
-            SCLogDebug("Invalid byte_jump nbytes "
-                       "seen in byte_jump - %d",
-                    nbytes);
-            SCReturnBool(false);
-        }
-    }
-
-    /* Calculate the ptr value for the bytejump and length remaining in
-     * the packet from that point.
-     */
-    ptr = payload + offset;
-    len = payload_len - offset;
-    if (flags & DETECT_BYTEJUMP_RELATIVE) {
-        ptr += det_ctx->buffer_offset;
-        len -= det_ctx->buffer_offset;
-
-        SCLogDebug("[relative] after: ptr %p [len %d]", ptr, len);
-
-        /* No match if there is no relative base */
-        if (ptr == NULL || (nbytes && len <= 0)) {
-            SCReturnBool(false);
-        }
-    }
-
-    /* Verify the to-be-extracted data is within the packet */
-    if (ptr < payload || nbytes > len) {
-        SCLogDebug("Data not within payload "
-                   "pkt=%p, ptr=%p, len=%" PRIi32 ", nbytes=%" PRIi32,
-                payload, ptr, len, nbytes);
+    uint32_t jump_offset = 0;
+
+    /* extract the value and compute the jump location, a jump before the
+     * start of the buffer moves to its start, and one past its end does
+     * not match */
+    if (!SCByteJumpMatch(data, flags, payload, payload_len, det_ctx->buffer_offset, nbytes,
+                offset, post_offset, &jump_offset)) {
+        SCLogDebug("NO MATCH");
         SCReturnBool(false);
     }
 
-    /* Extract the byte data */
-    if (flags & DETECT_BYTEJUMP_STRING) {
-        extbytes = ByteExtractStringUint64(&val, data->base, nbytes, (const char *)ptr);
-        if(extbytes <= 0) {
-            SCLogDebug("error extracting %d bytes of string data: %d", nbytes, extbytes);
-            SCReturnBool(false);
-        }
-    }
-    else {
-        int endianness = (flags & DETECT_BYTEJUMP_LITTLE) ? BYTE_LITTLE_ENDIAN : BYTE_BIG_ENDIAN;
-        extbytes = ByteExtractUint64(&val, endianness, (uint16_t)nbytes, ptr);
-        if (extbytes != nbytes) {
-            SCLogDebug("error extracting %d bytes of numeric data: %d", nbytes, extbytes);
-            SCReturnBool(false);
-        }
-    }
-
-    SCLogDebug("VAL: (%" PRIu64 " x %" PRIu32 ") + %" PRIi32 " + %" PRId32, val, data->multiplier,
-            extbytes, data->post_offset);
-
-    /* Adjust the jump value based on flags */
-    val *= data->multiplier;
-    if (flags & DETECT_BYTEJUMP_ALIGN) {
-        if ((val % 4) != 0) {
-            val += 4 - (val % 4);
-        }
-    }
-    val += data->post_offset;
-    SCLogDebug("val: %" PRIi64 " post_offset: %" PRIi32, val, data->post_offset);
-
-    const uint8_t *jumpptr;
-    /* Calculate the jump location */
-    if (flags & DETECT_BYTEJUMP_BEGIN) {
-        jumpptr = payload + (int64_t)val;
-        SCLogDebug("NEWVAL: payload %p + %" PRIi64 " = %p", payload, (int64_t)val, jumpptr + val);
-    } else if (flags & DETECT_BYTEJUMP_END) {
-        jumpptr = payload + payload_len + (int64_t)val;
-        SCLogDebug(
-                "NEWVAL: payload %p + %" PRIu32 " + %" PRIi64, payload, payload_len, (int64_t)val);
-    } else {
-        jumpptr = ptr + (int64_t)val + extbytes;
-        SCLogDebug("NEWVAL: ptr %p + %" PRIi64 " = %p", ptr, val, jumpptr);
-    }
-
-    /* Validate that the jump location is still in the packet
-     * \todo Should this validate it is still in the *payload*?
-     */
-    if (jumpptr < payload) {
-        jumpptr = payload;
-        SCLogDebug("jump location is before buffer start; resetting to buffer start");
-    } else if (jumpptr > (payload + payload_len)) {
-        SCLogDebug("Jump location (%" PRIu64 ") is not within payload (%" PRIu32 ")",
-                payload_len + val, payload_len);
-        SCReturnBool(false);
-    }
-
-#ifdef DEBUG
-    if (SCLogDebugEnabled()) {
-        const uint8_t *sptr = (flags & DETECT_BYTEJUMP_BEGIN) ? payload : ptr;
-        SCLogDebug("jumping %" PRId64 " bytes from %p (%08x)", val, sptr, (int)(sptr - payload));
-    }
-#endif /* DEBUG */
-
     /* Adjust the detection context to the jump location. */
-    DEBUG_VALIDATE_BUG_ON(jumpptr < payload);
-    det_ctx->buffer_offset = (uint32_t)(jumpptr - payload);
+    DEBUG_VALIDATE_BUG_ON(jump_offset > payload_len);
+    SCLogDebug("jumping from %" PRIu32 " to %" PRIu32, det_ctx->buffer_offset, jump_offset);
+    det_ctx->buffer_offset = jump_offset;
 
     SCReturnBool(true);
 }
 
-static DetectBytejumpData *DetectBytejumpParse(
-        DetectEngineCtx *de_ctx, const char *optstr, char **nbytes_str, char **offset)
+static DetectBytejumpData *DetectBytejumpParse(DetectEngineCtx *de_ctx, const char *optstr,
+        char **nbytes_str, char **offset, char **post_offset)
 {
-    DetectBytejumpData *data = NULL;
-    char args[10][64];
-    int res = 0;
-    size_t pcre2len;
-    int numargs = 0;
-    int i = 0;
-    uint32_t nbytes = 0;
-    char *str_ptr;
-    char *end_ptr;
-    pcre2_match_data *match = NULL;
-
-    memset(args, 0x00, sizeof(args));
-
-    /* Execute the regex and populate args with captures. */
-    int ret = DetectParsePcreExec(&parse_regex, &match, optstr, 0, 0);
-    if (ret < 2 || ret > 10) {
-        SCLogError("parse error, ret %" PRId32 ", string \"%s\"", ret, optstr);
-        goto error;
-    }
-
-    /* The first two arguments are stashed in the first PCRE substring.
-     * This is because byte_jump can take 10 arguments, but PCRE only
-     * supports 9 substrings, sigh.
-     */
-    char str[512] = "";
-    pcre2len = sizeof(str);
-    res = pcre2_substring_copy_bynumber(match, 1, (PCRE2_UCHAR8 *)str, &pcre2len);
-    if (res < 0) {
-        SCLogError("pcre2_substring_copy_bynumber failed "
-                   "for arg 1");
-        goto error;
+    /* errors are logged by the parser */
+    DetectBytejumpData *data = SCByteJumpParse(optstr);
+    if (data == NULL) {
+        return NULL;
     }
 
-    /* Break up first substring into two parameters
-     *
-     * NOTE: Because of this, we cannot free args[1] as it is part of args[0],
-     * and *yes* this *is* ugly.
-     */
-    end_ptr = str;
-    while (!(isspace((unsigned char)*end_ptr) || (*end_ptr == ','))) end_ptr++;
-    *(end_ptr++) = '\0';
-    strlcpy(args[0], str, sizeof(args[0]));
-    numargs++;
-
-    str_ptr = end_ptr;
-    while (isspace((unsigned char)*str_ptr) || (*str_ptr == ',')) str_ptr++;
-    end_ptr = str_ptr;
-    while (!(isspace((unsigned char)*end_ptr) || (*end_ptr == ',')) && (*end_ptr != '\0'))
-        end_ptr++;
-    *(end_ptr++) = '\0';
-    strlcpy(args[1], str_ptr, sizeof(args[1]));
-    numargs++;
-
-    /* The remaining args are directly from PCRE substrings */
-    for (i = 1; i < (ret - 1); i++) {
-        pcre2len = sizeof(args[0]);
-        res = pcre2_substring_copy_bynumber(match, i + 1, (PCRE2_UCHAR8 *)args[i + 1], &pcre2len);
-        if (res < 0) {
-            SCLogError("pcre2_substring_copy_bynumber failed for arg %d", i + 1);
-            goto error;
-        }
-        numargs++;
-    }
-
-    /* Initialize the data */
-    data = SCMalloc(sizeof(DetectBytejumpData));
-    if (unlikely(data == NULL))
-        goto error;
-    data->base = DETECT_BYTEJUMP_BASE_UNSET;
-    data->flags = 0;
-    data->multiplier = 1;
-    data->post_offset = 0;
-
-    /*
-     * The first two options are required and positional.  The
-     * remaining arguments are flags and are not positional.
-     */
-
-    /* Number of bytes */
-    if (args[0][0] != '-' && isalpha((unsigned char)args[0][0])) {
+    if (data->nbytes_str != NULL) {
         if (nbytes_str == NULL) {
             SCLogError("byte_jump supplied with "
                        "var name for nbytes.  \"value\" argument supplied to "
                        "this function has to be non-NULL");
             goto error;
         }
-        *nbytes_str = SCStrdup(args[0]);
+        *nbytes_str = SCStrdup(data->nbytes_str);
         if (*nbytes_str == NULL)
             goto error;
-        data->flags |= DETECT_BYTEJUMP_NBYTES_VAR;
-    } else {
-        if (StringParseUint32(&nbytes, 10, (uint16_t)strlen(args[0]), args[0]) <= 0) {
-            SCLogError("Malformed number of bytes: %s", optstr);
-            goto error;
-        }
     }
 
-    /* Offset */
-    if (args[1][0] != '-' && isalpha((unsigned char)args[1][0])) {
+    if (data->offset_str != NULL) {
         if (offset == NULL) {
             SCLogError("byte_jump supplied with "
                        "var name for offset.  \"value\" argument supplied to "
                        "this function has to be non-NULL");
             goto error;
         }
-        *offset = SCStrdup(args[1]);
+        *offset = SCStrdup(data->offset_str);
         if (*offset == NULL)
             goto error;
-    } else {
-        if (StringParseI32RangeCheck(
-                    &data->offset, 10, (uint16_t)strlen(args[1]), args[1], -65535, 65535) <= 0) {
-            SCLogError("Malformed offset: %s", optstr);
-            goto error;
-        }
-    }
-
-    /* The remaining options are flags. */
-    /** \todo Error on dups? */
-    for (i = 2; i < numargs; i++) {
-        if (strcmp("relative", args[i]) == 0) {
-            data->flags |= DETECT_BYTEJUMP_RELATIVE;
-        } else if (strcasecmp("string", args[i]) == 0) {
-            data->flags |= DETECT_BYTEJUMP_STRING;
-        } else if (strcasecmp("dec", args[i]) == 0) {
-            data->base |= DETECT_BYTEJUMP_BASE_DEC;
-        } else if (strcasecmp("hex", args[i]) == 0) {
-            data->base |= DETECT_BYTEJUMP_BASE_HEX;
-        } else if (strcasecmp("oct", args[i]) == 0) {
-            data->base |= DETECT_BYTEJUMP_BASE_OCT;
-        } else if (strcasecmp("big", args[i]) == 0) {
-            if (data->flags & DETECT_BYTEJUMP_LITTLE) {
-                data->flags ^= DETECT_BYTEJUMP_LITTLE;
-            }
-            data->flags |= DETECT_BYTEJUMP_BIG;
-        } else if (strcasecmp("little", args[i]) == 0) {
-            data->flags |= DETECT_BYTEJUMP_LITTLE;
-        } else if (strcasecmp("from_beginning", args[i]) == 0) {
-            data->flags |= DETECT_BYTEJUMP_BEGIN;
-        } else if (strcasecmp("from_end", args[i]) == 0) {
-            data->flags |= DETECT_BYTEJUMP_END;
-        } else if (strcasecmp("align", args[i]) == 0) {
-            data->flags |= DETECT_BYTEJUMP_ALIGN;
-        } else if (strncasecmp("multiplier ", args[i], 11) == 0) {
-            if (StringParseU16RangeCheck(&data->multiplier, 10, (uint16_t)strlen(args[i]) - 11,
-                        args[i] + 11, 1, 65535) <= 0) {
-                SCLogError("Malformed multiplier: %s", optstr);
-                goto error;
-            }
-        } else if (strncasecmp("post_offset ", args[i], 12) == 0) {
-            if (StringParseI32RangeCheck(&data->post_offset, 10, (uint16_t)strlen(args[i]) - 12,
-                        args[i] + 12, -65535, 65535) <= 0) {
-                SCLogError("Malformed post_offset: %s", optstr);
-                goto error;
-            }
-            SCLogDebug("post_offset: %s [%d]", optstr, data->post_offset);
-        } else if (strcasecmp("dce", args[i]) == 0) {
-            data->flags |= DETECT_BYTEJUMP_DCE;
-        } else {
-            SCLogError("Unknown option: \"%s\"", args[i]);
-            goto error;
-        }
-    }
-
-    if ((data->flags & DETECT_BYTEJUMP_END) && (data->flags & DETECT_BYTEJUMP_BEGIN)) {
-        SCLogError("'from_end' and 'from_beginning' "
-                   "cannot be used in the same byte_jump statement");
-        goto error;
     }
 
-    if (!(data->flags & DETECT_BYTEJUMP_NBYTES_VAR)) {
-        if (!DetectBytejumpValidateNbytes(data, nbytes)) {
+    if (data->post_offset_str != NULL) {
+        if (post_offset == NULL) {
+            SCLogError("byte_jump supplied with "
+                       "var name for post_offset.  \"post_offset\" argument supplied to "
+                       "this function has to be non-NULL");
             goto error;
         }
-
-        /* This is max 23 so it will fit in a byte (see validation function) */
-        data->nbytes = (uint8_t)nbytes;
-    }
-    if (!(data->flags & DETECT_BYTEJUMP_STRING)) {
-        if (data->base != DETECT_BYTEJUMP_BASE_UNSET) {
-            SCLogError("Cannot use a base "
-                       "without \"string\": %s",
-                    optstr);
+        *post_offset = SCStrdup(data->post_offset_str);
+        if (*post_offset == NULL)
             goto error;
-        }
     }
 
-    pcre2_match_data_free(match);
     return data;
 
 error:
@@ -460,11 +158,7 @@ error:
         SCFree(*nbytes_str);
         *nbytes_str = NULL;
     }
-    if (data != NULL)
-        DetectBytejumpFree(de_ctx, data);
-    if (match) {
-        pcre2_match_data_free(match);
-    }
+    DetectBytejumpFree(de_ctx, data);
     return NULL;
 }
 
@@ -474,9 +168,10 @@ static int DetectBytejumpSetup(DetectEngineCtx *de_ctx, Signature *s, const char
     DetectBytejumpData *data = NULL;
     char *offset = NULL;
     char *nbytes = NULL;
+    char *post_offset = NULL;
     int ret = -1;
 
-    data = DetectBytejumpParse(de_ctx, optstr, &nbytes, &offset);
+    data = DetectBytejumpParse(de_ctx, optstr, &nbytes, &offset, &post_offset);
     if (data == NULL)
         goto error;
 
@@ -569,6 +264,20 @@ static int DetectBytejumpSetup(DetectEngineCtx *de_ctx, Signature *s, const char
         offset = NULL;
     }
 
+    if (post_offset != NULL) {
+        DetectByteIndexType index;
+        if (!DetectByteRetrieveSMVar(post_offset, s, &index)) {
+            SCLogError("Unknown byte_extract var "
+                       "seen in byte_jump - %s",
+                    post_offset);
+            goto error;
+        }
+        data->post_offset = index;
+        data->flags |= DETECT_BYTEJUMP_POST_OFFSET_VAR;
+        SCFree(post_offset);
+        post_offset = NULL;
+    }
+
     if (SCSigMatchAppendSMToList(de_ctx, s, DETECT_BYTEJUMP, (SigMatchCtx *)data, sm_list) ==
             NULL) {
         goto error;
@@ -599,6 +308,9 @@ static int DetectBytejumpSetup(DetectEngineCtx *de_ctx, Signature *s, const char
     if (offset != NULL) {
         SCFree(offset);
     }
+    if (post_offset != NULL) {
+        SCFree(post_offset);
+    }
     DetectBytejumpFree(de_ctx, data);
     return ret;
 }
@@ -613,8 +325,7 @@ static void DetectBytejumpFree(DetectEngineCtx *de_ctx, void *ptr)
     if (ptr == NULL)
         return;
 
-    DetectBytejumpData *data = (DetectBytejumpData *)ptr;
-    SCFree(data);
+    SCByteJumpFree(ptr);
 }
 
 
@@ -631,7 +342,7 @@ static int g_dce_stub_data_buffer_id = 0;
 static int DetectBytejumpTestParse01(void)
 {
     DetectBytejumpData *data = NULL;
-    data = DetectBytejumpParse(NULL, "4,0", NULL, NULL);
+    data = DetectBytejumpParse(NULL, "4,0", NULL, NULL, NULL);
     FAIL_IF_NULL(data);
 
     DetectBytejumpFree(NULL, data);
@@ -644,7 +355,7 @@ static int DetectBytejumpTestParse01(void)
 static int DetectBytejumpTestParse02(void)
 {
     DetectBytejumpData *data = NULL;
-    data = DetectBytejumpParse(NULL, "4, 0", NULL, NULL);
+    data = DetectBytejumpParse(NULL, "4, 0", NULL, NULL, NULL);
     FAIL_IF_NULL(data);
     FAIL_IF_NOT(data->nbytes == 4);
     FAIL_IF_NOT(data->offset == 0);
@@ -666,7 +377,7 @@ static int DetectBytejumpTestParse03(void)
     data = DetectBytejumpParse(NULL,
             " 4,0 , relative , little, string, "
             "dec, align, from_beginning",
-            NULL, NULL);
+            NULL, NULL, NULL);
     FAIL_IF_NULL(data);
     FAIL_IF_NOT(data->nbytes == 4);
     FAIL_IF_NOT(data->offset == 0);
@@ -684,8 +395,6 @@ static int DetectBytejumpTestParse03(void)
 /**
  * \test DetectBytejumpTestParse04 is a test for setting the optional flags
  *       with parameters
- *
- * \todo This fails because we can only have 9 captures and there are 10.
  */
 static int DetectBytejumpTestParse04(void)
 {
@@ -694,7 +403,7 @@ static int DetectBytejumpTestParse04(void)
             " 4,0 , relative , little, string, "
             "dec, align, from_beginning , "
             "multiplier 2 , post_offset -16 ",
-            NULL, NULL);
+            NULL, NULL, NULL);
     FAIL_IF_NULL(data);
     FAIL_IF_NOT(data->nbytes == 4);
     FAIL_IF_NOT(data->offset == 0);
@@ -718,7 +427,7 @@ static int DetectBytejumpTestParse05(void)
     data = DetectBytejumpParse(NULL,
             " 4,0 , relative , little, dec, "
             "align, from_beginning",
-            NULL, NULL);
+            NULL, NULL, NULL);
     FAIL_IF_NOT_NULL(data);
 
     PASS;
@@ -730,7 +439,7 @@ static int DetectBytejumpTestParse05(void)
 static int DetectBytejumpTestParse06(void)
 {
     DetectBytejumpData *data = NULL;
-    data = DetectBytejumpParse(NULL, "9, 0", NULL, NULL);
+    data = DetectBytejumpParse(NULL, "9, 0", NULL, NULL, NULL);
     FAIL_IF_NOT_NULL(data);
 
     PASS;
@@ -742,7 +451,7 @@ static int DetectBytejumpTestParse06(void)
 static int DetectBytejumpTestParse07(void)
 {
     DetectBytejumpData *data = NULL;
-    data = DetectBytejumpParse(NULL, "24, 0, string, dec", NULL, NULL);
+    data = DetectBytejumpParse(NULL, "24, 0, string, dec", NULL, NULL, NULL);
     FAIL_IF_NOT_NULL(data);
 
     PASS;
@@ -754,7 +463,7 @@ static int DetectBytejumpTestParse07(void)
 static int DetectBytejumpTestParse08(void)
 {
     DetectBytejumpData *data = NULL;
-    data = DetectBytejumpParse(NULL, "4, 0xffffffffffffffff", NULL, NULL);
+    data = DetectBytejumpParse(NULL, "4, 0xffffffffffffffff", NULL, NULL, NULL);
     FAIL_IF_NOT_NULL(data);
 
     PASS;
@@ -983,7 +692,7 @@ static int DetectBytejumpTestParse13(void)
     DetectBytejumpData *data = DetectBytejumpParse(NULL,
             " 4,0 , relative , little, string, dec, "
             "align, from_end",
-            NULL, NULL);
+            NULL, NULL, NULL);
     FAIL_IF_NULL(data);
     FAIL_IF_NOT(data->flags & DETECT_BYTEJUMP_END);
 
@@ -997,7 +706,7 @@ static int DetectBytejumpTestParse14(void)
     DetectBytejumpData *data = DetectBytejumpParse(NULL,
             " 4,0 , relative , little, string, dec, "
             "align, from_beginning, from_end",
-            NULL, NULL);
+            NULL, NULL, NULL);
 
     FAIL_IF_NOT_NULL(data);
 
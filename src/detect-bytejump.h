@@ -24,34 +24,6 @@
 #ifndef SURICATA_DETECT_BYTEJUMP_H
 #define SURICATA_DETECT_BYTEJUMP_H
 
-/** Bytejump Base */
-#define DETECT_BYTEJUMP_BASE_UNSET  0 /**< Unset type value string (automatic)*/
-#define DETECT_BYTEJUMP_BASE_OCT    8 /**< "oct" type value string */
-#define DETECT_BYTEJUMP_BASE_DEC   10 /**< "dec" type value string */
-#define DETECT_BYTEJUMP_BASE_HEX   16 /**< "hex" type value string */
-
-/** Bytejump Flags */
-#define DETECT_BYTEJUMP_BEGIN     BIT_U16(0) /**< "from_beginning" jump */
-#define DETECT_BYTEJUMP_LITTLE    BIT_U16(1) /**< "little" endian value */
-#define DETECT_BYTEJUMP_BIG       BIT_U16(2) /**< "big" endian value */
-#define DETECT_BYTEJUMP_STRING    BIT_U16(3) /**< "string" value */
-#define DETECT_BYTEJUMP_RELATIVE  BIT_U16(4) /**< "relative" offset */
-#define DETECT_BYTEJUMP_ALIGN     BIT_U16(5) /**< "align" offset */
-#define DETECT_BYTEJUMP_DCE       BIT_U16(6) /**< "dce" enabled */
-#define DETECT_BYTEJUMP_OFFSET_BE BIT_U16(7) /**< "byte extract" enabled */
-#define DETECT_BYTEJUMP_END       BIT_U16(8) /**< "from_end" jump */
-#define DETECT_BYTEJUMP_NBYTES_VAR BIT_U16(9) /**< nbytes string*/
-#define DETECT_BYTEJUMP_OFFSET_VAR BIT_U16(10) /**< byte extract value enabled */
-
-typedef struct DetectBytejumpData_ {
-    uint8_t nbytes;                   /**< Number of bytes to compare */
-    uint8_t base;                     /**< String value base (oct|dec|hex) */
-    uint16_t flags;                   /**< Flags (big|little|relative|string) */
-    int32_t offset;                   /**< Offset in payload to extract value */
-    int32_t post_offset;              /**< Offset to adjust post-jump */
-    uint16_t multiplier;              /**< Multiplier for nbytes (multiplier n)*/
-} DetectBytejumpData;
-
 /* prototypes */
 
 /**
@@ -73,6 +45,6 @@ void DetectBytejumpRegister (void);
  * \retval  true
  */
 bool DetectBytejumpDoMatch(DetectEngineThreadCtx *, const Signature *, const SigMatchCtx *,
-        const uint8_t *, uint32_t, uint16_t, int32_t, int32_t);
+        const uint8_t *, uint32_t, uint16_t, int32_t, int32_t, int64_t);
 
 #endif /* SURICATA_DETECT_BYTEJUMP_H */
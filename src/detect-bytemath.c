@@ -161,35 +161,9 @@ int DetectByteMathDoMatch(DetectEngineThreadCtx *det_ctx, const DetectByteMathDa
 
     ptr += extbytes;
 
-    switch (data->oper) {
-        case OperatorNone:
-            break;
-        case Addition:
-            val += rvalue;
-            break;
-        case Subtraction:
-            val -= rvalue;
-            break;
-        case Division:
-            if (rvalue == 0) {
-                SCLogDebug("avoiding division by zero");
-                return 0;
-            }
-            val /= rvalue;
-            break;
-        case Multiplication:
-            val *= rvalue;
-            break;
-        case LeftShift:
-            if (rvalue < 64) {
-                val <<= rvalue;
-            } else {
-                val = 0;
-            }
-            break;
-        case RightShift:
-            val >>= rvalue;
-            break;
+    if (!SCByteMathCompute(data, val, rvalue, &val)) {
+        SCLogDebug("no result: division by zero or overflow");
+        return 0;
     }
 
     det_ctx->buffer_offset = (uint32_t)(ptr - payload);
@@ -1051,6 +1025,144 @@ static int DetectByteMathContext01(void)
     PASS;
 }
 
+static int DetectByteMathOperTest(
+        const char *arg, uint8_t *buf, uint32_t buf_len, uint64_t *value)
+{
+    DetectEngineThreadCtx *det_ctx = SCCalloc(1, sizeof(*det_ctx));
+    if (det_ctx == NULL)
+        return -1;
+    DetectByteMathData *bmd = DetectByteMathParse(NULL, arg, NULL, NULL);
+    if (bmd == NULL) {
+        SCFree(det_ctx);
+        return -1;
+    }
+    int r = DetectByteMathDoMatch(
+            det_ctx, bmd, NULL, buf, buf_len, bmd->nbytes, bmd->rvalue, value, bmd->endian);
+    DetectByteMathFree(NULL, bmd);
+    SCFree(det_ctx);
+    return r;
+}
+
+static int DetectByteMathBitwiseTest01(void)
+{
+    uint8_t buf[] = { 0x5a, 0x50 };
+    uint64_t value = 0;
+
+    /* mask the low nibble */
+    FAIL_IF_NOT(DetectByteMathOperTest("bytes 1, offset 0, oper &, rvalue 15, result x", buf,
+                        sizeof(buf), &value) == 1);
+    FAIL_IF_NOT(value == 0x0a);
+
+    FAIL_IF_NOT(DetectByteMathOperTest("bytes 1, offset 1, oper |, rvalue 15, result x", buf,
+                        sizeof(buf), &value) == 1);
+    FAIL_IF_NOT(value == 0x5f);
+
+    FAIL_IF_NOT(DetectByteMathOperTest("bytes 2, offset 0, oper &, rvalue 65280, result x", buf,
+                        sizeof(buf), &value) == 1);
+    FAIL_IF_NOT(value == 0x5a00);
+
+    PASS;
+}
+
+static int DetectByteMathBitwiseTest02(void)
+{
+    uint8_t buf[] = { 0x5a };
+    uint64_t value = 0;
+
+    /* xor twice with the same key gives back the original value */
+    FAIL_IF_NOT(DetectByteMathOperTest("bytes 1, offset 0, oper ^, rvalue 255, result x", buf,
+                        sizeof(buf), &value) == 1);
+    FAIL_IF_NOT(value == 0xa5);
+
+    buf[0] = (uint8_t)value;
+    FAIL_IF_NOT(DetectByteMathOperTest("bytes 1, offset 0, oper ^, rvalue 255, result x", buf,
+                        sizeof(buf), &value) == 1);
+    FAIL_IF_NOT(value == 0x5a);
+
+    PASS;
+}
+
+static int DetectByteMathShiftTest01(void)
+{
+    uint8_t buf[] = { 0x12, 0x34 };
+    uint64_t value = 0;
+
+    FAIL_IF_NOT(DetectByteMathOperTest("bytes 2, offset 0, oper >>, rvalue 4, result x", buf,
+                        sizeof(buf), &value) == 1);
+    FAIL_IF_NOT(value == 0x123);
+
+    FAIL_IF_NOT(DetectByteMathOperTest("bytes 2, offset 0, oper <<, rvalue 4, result x", buf,
+                        sizeof(buf), &value) == 1);
+    FAIL_IF_NOT(value == 0x12340);
+
+    /* shifting by more than the extracted width */
+    FAIL_IF_NOT(DetectByteMathOperTest("bytes 2, offset 0, oper >>, rvalue 20, result x", buf,
+                        sizeof(buf), &value) == 1);
+    FAIL_IF_NOT(value == 0);
+
+    PASS;
+}
+
+static int DetectByteMathShiftTest02(void)
+{
+    uint8_t buf[] = { 0x12, 0x34 };
+    uint64_t value = 1;
+
+    /* shifting by the width of the value or more clamps to zero */
+    FAIL_IF_NOT(DetectByteMathOperTest("bytes 2, offset 0, oper >>, rvalue 64, result x", buf,
+                        sizeof(buf), &value) == 1);
+    FAIL_IF_NOT(value == 0);
+
+    value = 1;
+    FAIL_IF_NOT(DetectByteMathOperTest("bytes 2, offset 0, oper <<, rvalue 64, result x", buf,
+                        sizeof(buf), &value) == 1);
+    FAIL_IF_NOT(value == 0);
+
+    value = 1;
+    FAIL_IF_NOT(DetectByteMathOperTest("bytes 2, offset 0, oper >>, rvalue 4294967295, result x",
+                        buf, sizeof(buf), &value) == 1);
+    FAIL_IF_NOT(value == 0);
+
+    PASS;
+}
+
+/**
+ * \test dce is resolved with the representation negotiated by the
+ *       DCE/RPC flow, and is little endian without one.
+ */
+static int DetectByteMathDceEndianTest01(void)
+{
+    uint8_t buf[] = { 0x01, 0x02 };
+    uint64_t value = 0;
+
+    DetectEngineThreadCtx *det_ctx = SCCalloc(1, sizeof(*det_ctx));
+    FAIL_IF_NULL(det_ctx);
+    DetectByteMathData *bmd = DetectByteMathParse(
+            NULL, "bytes 2, offset 0, oper +, rvalue 1, result x, dce", NULL, NULL);
+    FAIL_IF_NULL(bmd);
+
+    uint8_t endian = SCDetectByteEndianResolve(bmd->endian, true, false);
+    FAIL_IF_NOT(endian == LittleEndian);
+    FAIL_IF_NOT(DetectByteMathDoMatch(det_ctx, bmd, NULL, buf, sizeof(buf), bmd->nbytes,
+                        bmd->rvalue, &value, endian) == 1);
+    FAIL_IF_NOT(value == 0x0202);
+
+    endian = SCDetectByteEndianResolve(bmd->endian, false, true);
+    FAIL_IF_NOT(endian == BigEndian);
+    FAIL_IF_NOT(DetectByteMathDoMatch(det_ctx, bmd, NULL, buf, sizeof(buf), bmd->nbytes,
+                        bmd->rvalue, &value, endian) == 1);
+    FAIL_IF_NOT(value == 0x0103);
+
+    endian = SCDetectByteEndianResolve(bmd->endian, false, false);
+    FAIL_IF_NOT(DetectByteMathDoMatch(det_ctx, bmd, NULL, buf, sizeof(buf), bmd->nbytes,
+                        bmd->rvalue, &value, endian) == 1);
+    FAIL_IF_NOT(value == 0x0202);
+
+    DetectByteMathFree(NULL, bmd);
+    SCFree(det_ctx);
+    PASS;
+}
+
 static void DetectByteMathRegisterTests(void)
 {
     UtRegisterTest("DetectByteMathParseTest01", DetectByteMathParseTest01);
@@ -1072,5 +1184,10 @@ static void DetectByteMathRegisterTests(void)
     UtRegisterTest("DetectByteMathPacket01", DetectByteMathPacket01);
     UtRegisterTest("DetectByteMathPacket02", DetectByteMathPacket02);
     UtRegisterTest("DetectByteMathContext01", DetectByteMathContext01);
+    UtRegisterTest("DetectByteMathBitwiseTest01", DetectByteMathBitwiseTest01);
+    UtRegisterTest("DetectByteMathBitwiseTest02", DetectByteMathBitwiseTest02);
+    UtRegisterTest("DetectByteMathShiftTest01", DetectByteMathShiftTest01);
+    UtRegisterTest("DetectByteMathShiftTest02", DetectByteMathShiftTest02);
+    UtRegisterTest("DetectByteMathDceEndianTest01", DetectByteMathDceEndianTest01);
 }
 #endif /* UNITTESTS */
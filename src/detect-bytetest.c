@@ -45,29 +45,7 @@
 #include "util-debug.h"
 #include "detect-pcre.h"
 
-
-/**
- * \brief Regex for parsing our options
- */
-/** \todo We probably just need a simple tokenizer here */
-
-/* PCRE supports 9 substrings so the 2nd and 3rd (negation, operator) and
- * 4th and 5th (test value, offset) are combined
- */
-#define VALID_KW "relative|big|little|string|oct|dec|hex|dce|bitmask"
-#define PARSE_REGEX  "^\\s*" \
-                     "([^\\s,]+)\\s*,\\s*" \
-                     "(\\!?\\s*[^\\s,]*)" \
-                     "\\s*,\\s*([^\\s,]+\\s*,\\s*[^\\s,]+)" \
-                     "(?:\\s*,\\s*((?:"VALID_KW")\\s+[^\\s,]+|["VALID_KW"]+))?" \
-                     "(?:\\s*,\\s*((?:"VALID_KW")\\s+[^\\s,]+|["VALID_KW"]+))?" \
-                     "(?:\\s*,\\s*((?:"VALID_KW")\\s+[^\\s,]+|["VALID_KW"]+))?" \
-                     "(?:\\s*,\\s*((?:"VALID_KW")\\s+[^\\s,]+|["VALID_KW"]+))?" \
-                     "(?:\\s*,\\s*((?:"VALID_KW")\\s+[^\\s,]+|["VALID_KW"]+))?" \
-                     "(?:\\s*,\\s*((?:"VALID_KW")\\s+[^\\s,]+|["VALID_KW"]+))?" \
-                     "\\s*$"
-
-static DetectParseRegex parse_regex;
+#include "rust.h"
 
 static int DetectBytetestSetup(DetectEngineCtx *de_ctx, Signature *s, const char *optstr);
 static void DetectBytetestFree(DetectEngineCtx *, void *ptr);
@@ -85,59 +63,6 @@ void DetectBytetestRegister (void)
 #ifdef UNITTESTS
     sigmatch_table[DETECT_BYTETEST].RegisterTests = DetectBytetestRegisterTests;
 #endif
-    DetectSetupParseRegexes(PARSE_REGEX, &parse_regex);
-}
-
-/* 23 - This is the largest string (octal, with a zero prefix) that
- *      will not overflow uint64_t.  The only way this length
- *      could be over 23 and still not overflow is if it were zero
- *      prefixed and we only support 1 byte of zero prefix for octal.
- *
- * "01777777777777777777777" = 0xffffffffffffffff
- *
- * 8 - Without string, the maximum byte extract count is 8.
- */
-static inline bool DetectBytetestValidateNbytesOnly(const DetectBytetestData *data, int32_t nbytes)
-{
-    return ((data->flags & DETECT_BYTETEST_STRING) && nbytes <= 23) || (nbytes <= 8);
-}
-
-static bool DetectBytetestValidateNbytes(
-        const DetectBytetestData *data, int32_t nbytes, const char *optstr)
-{
-    if (!DetectBytetestValidateNbytesOnly(data, nbytes)) {
-        if (data->flags & DETECT_BYTETEST_STRING) {
-            /* 23 - This is the largest string (octal, with a zero prefix) that
-             *      will not overflow uint64_t.  The only way this length
-             *      could be over 23 and still not overflow is if it were zero
-             *      prefixed and we only support 1 byte of zero prefix for octal.
-             *
-             * "01777777777777777777777" = 0xffffffffffffffff
-             */
-            if (nbytes > 23) {
-                SCLogError("Cannot test more than 23 bytes with \"string\": %s", optstr);
-            }
-        } else {
-            if (nbytes > 8) {
-                SCLogError("Cannot test more than 8 bytes without \"string\": %s", optstr);
-            }
-            if (data->base != DETECT_BYTETEST_BASE_UNSET) {
-                SCLogError("Cannot use a base without \"string\": %s", optstr);
-            }
-        }
-        return false;
-    } else {
-        /*
-         * Even if the value is within the proper range, ensure
-         * that the base is unset unless string is used.
-         */
-        if (!(data->flags & DETECT_BYTETEST_STRING) && (data->base != DETECT_BYTETEST_BASE_UNSET)) {
-            SCLogError("Cannot use a base without \"string\": %s", optstr);
-            return false;
-        }
-    }
-
-    return true;
 }
 
 /** \brief Bytetest detection code
@@ -163,19 +88,8 @@ int DetectBytetestDoMatch(DetectEngineThreadCtx *det_ctx, const Signature *s,
     }
 
     const DetectBytetestData *data = (const DetectBytetestData *)ctx;
-    if (data->flags & DETECT_BYTETEST_NBYTES_VAR) {
-        if (!DetectBytetestValidateNbytesOnly(data, nbytes)) {
-            SCLogDebug("Invalid byte_test nbytes seen in byte_test - %d", nbytes);
-            SCReturnInt(0);
-        }
-    }
-
     const uint8_t *ptr = NULL;
     int32_t len = 0;
-    uint64_t val = 0;
-    int extbytes;
-    int neg;
-    int match;
 
     /* Calculate the ptr value for the bytetest and length remaining in
      * the packet from that point.
@@ -205,369 +119,79 @@ int DetectBytetestDoMatch(DetectEngineThreadCtx *det_ctx, const Signature *s,
     /* Validate that the to-be-extracted is within the packet
      * \todo Should this validate it is in the *payload*?
      */
-    if (ptr < payload || nbytes > len) {
+    if (ptr < payload || nbytes < 0 || nbytes > len) {
         SCLogDebug("Data not within payload pkt=%p, ptr=%p, len=%" PRIu32 ", nbytes=%d", payload,
                 ptr, len, nbytes);
         SCReturnInt(0);
     }
 
-    neg = data->neg_op;
-
-    /* Extract the byte data */
-    if (flags & DETECT_BYTETEST_STRING) {
-        extbytes = ByteExtractStringUint64(&val, data->base, nbytes, (const char *)ptr);
-        if (extbytes <= 0) {
-            /* ByteExtractStringUint64() returns 0 if there is no numeric value in data string */
-            if (val == 0) {
-                SCLogDebug("No Numeric value");
-                SCReturnInt(0);
-            } else {
-                SCLogDebug("error extracting %d "
-                           "bytes of string data: %d",
-                        nbytes, extbytes);
-                SCReturnInt(-1);
-            }
-        }
-
-        SCLogDebug("comparing base %d string 0x%" PRIx64 " %s%u 0x%" PRIx64,
-               data->base, val, (neg ? "!" : ""), data->op, data->value);
-    }
-    else {
-        int endianness = (flags & DETECT_BYTETEST_LITTLE) ?
-                          BYTE_LITTLE_ENDIAN : BYTE_BIG_ENDIAN;
-        extbytes = ByteExtractUint64(&val, endianness, (uint16_t)nbytes, ptr);
-        if (extbytes != nbytes) {
-            SCLogDebug("error extracting %d bytes "
-                       "of numeric data: %d",
-                    nbytes, extbytes);
-            SCReturnInt(-1);
-        }
-
-        SCLogDebug("comparing numeric 0x%" PRIx64 " %s%u 0x%" PRIx64,
-               val, (neg ? "!" : ""), data->op, data->value);
-    }
-
-    /* apply bitmask, if any and then right-shift 1 bit for each trailing 0 in
-     * the bitmask. Note that it's one right shift for each trailing zero (not bit).
-     */
-    if (flags & DETECT_BYTETEST_BITMASK) {
-        val &= data->bitmask;
-        if (val && data->bitmask_shift_count) {
-            val = val >> data->bitmask_shift_count;
-        }
-    }
-
-    /* Compare using the configured operator */
-    match = 0;
-    switch (data->op) {
-        case DETECT_BYTETEST_OP_EQ:
-            if (val == value) {
-                match = 1;
-            }
-            break;
-        case DETECT_BYTETEST_OP_LT:
-            if (val < value) {
-                match = 1;
-            }
-            break;
-        case DETECT_BYTETEST_OP_GT:
-            if (val > value) {
-                match = 1;
-            }
-            break;
-        case DETECT_BYTETEST_OP_AND:
-            if (val & value) {
-                match = 1;
-            }
-            break;
-        case DETECT_BYTETEST_OP_OR:
-            if (val ^ value) {
-                match = 1;
-            }
-            break;
-        case DETECT_BYTETEST_OP_GE:
-            if (val >= value) {
-                match = 1;
-            }
-            break;
-        case DETECT_BYTETEST_OP_LE:
-            if (val <= value) {
-                match = 1;
-            }
-            break;
-        default:
-            /* Should never get here as we handle this in parsing. */
-            SCReturnInt(-1);
-    }
-
-    /* A successful match depends on negation */
-    if ((!neg && match) || (neg && !match)) {
-        SCLogDebug("MATCH [bt] extracted value is %"PRIu64, val);
+    /* extract, apply the bitmask and compare using the configured operator */
+    if (SCByteTestMatch(data, flags, ptr, (uint32_t)nbytes, value)) {
+        SCLogDebug("MATCH [bt]");
         SCReturnInt(1);
     }
 
     SCLogDebug("NO MATCH");
     SCReturnInt(0);
-
 }
 
 static DetectBytetestData *DetectBytetestParse(
         const char *optstr, char **value, char **offset, char **nbytes_str)
 {
-    DetectBytetestData *data = NULL;
-    char *args[9] = {
-        NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL,
-        NULL
-    };
-    char *test_value =  NULL;
-    char *data_offset = NULL;
-    int res = 0;
-    size_t pcre2_len;
-    int i;
-    uint32_t nbytes = 0;
-    const char *str_ptr = NULL;
-    pcre2_match_data *match = NULL;
-
-    /* Execute the regex and populate args with captures. */
-    int ret = DetectParsePcreExec(&parse_regex, &match, optstr, 0, 0);
-    if (ret < 4 || ret > 9) {
-        SCLogError("parse error, ret %" PRId32 ", string %s", ret, optstr);
-        goto error;
+    /* errors are logged by the parser */
+    DetectBytetestData *data = SCByteTestParse(optstr);
+    if (data == NULL) {
+        return NULL;
     }
 
-    /* Subtract two since two values  are conjoined */
-    for (i = 0; i < (ret - 1); i++) {
-        res = pcre2_substring_get_bynumber(match, i + 1, (PCRE2_UCHAR8 **)&str_ptr, &pcre2_len);
-        if (res < 0) {
-            SCLogError("pcre2_substring_get_bynumber failed "
-                       "for arg %d",
-                    i + 1);
-            goto error;
-        }
-        /* args[2] is comma separated test value, offset */
-        if (i == 2) {
-            test_value = (char *) str_ptr;
-            data_offset = SCStrdup((char *) str_ptr);
-            if (data_offset == NULL) {
-                goto error;
-            }
-        } else {
-            args[i] = (char *)str_ptr;
-        }
-    }
-
-    /* Initialize the data */
-    data = SCMalloc(sizeof(DetectBytetestData));
-    if (unlikely(data == NULL))
-        goto error;
-    data->base = DETECT_BYTETEST_BASE_UNSET;
-    data->flags = 0;
-
-    /*
-     * The first four options are required and positional.  The
-     * remaining arguments are flags and are not positional.
-     *
-     * The first four options have been collected into three
-     * arguments:
-     * - #1 -- byte count
-     * - #2 -- operator, including optional negation (!)
-     * - #3 -- test value and offset, comma separated
-     */
-
-    /* Number of bytes */
-    if (args[0][0] != '-' && isalpha((unsigned char)args[0][0])) {
+    if (data->nbytes_str != NULL) {
         if (nbytes_str == NULL) {
             SCLogError("byte_test supplied with "
                        "var name for nbytes.  \"value\" argument supplied to "
                        "this function has to be non-NULL");
             goto error;
         }
-        *nbytes_str = SCStrdup(args[0]);
+        *nbytes_str = SCStrdup(data->nbytes_str);
         if (*nbytes_str == NULL)
             goto error;
-        data->flags |= DETECT_BYTETEST_NBYTES_VAR;
-    } else {
-        if (StringParseUint32(&nbytes, 10, 0, args[0]) <= 0) {
-            SCLogError("Malformed number of bytes: %s", str_ptr);
-            goto error;
-        }
     }
 
-    /* The operator is the next arg; it may contain a negation ! as the first char */
-    data->op = 0;
-    if (args[1] != NULL) {
-        int op_offset = 0;
-        char *op_ptr;
-        if (args[1][op_offset] == '!') {
-            data->neg_op = true;
-            op_ptr = &args[1][1];
-            while (isspace((char)*op_ptr) || (*op_ptr == ',')) op_ptr++;
-            op_offset = (uint32_t)(op_ptr - &args[1][0]);
-        } else {
-            data->neg_op = false;
-        }
-        op_ptr = args[1] + op_offset;
-        if ((strcmp("=", op_ptr) == 0) || (data->neg_op
-                && strcmp("", op_ptr) == 0)) {
-            data->op |= DETECT_BYTETEST_OP_EQ;
-        } else if (strcmp("<", op_ptr) == 0) {
-            data->op |= DETECT_BYTETEST_OP_LT;
-        } else if (strcmp(">", op_ptr) == 0) {
-            data->op |= DETECT_BYTETEST_OP_GT;
-        } else if (strcmp("&", op_ptr) == 0) {
-            data->op |= DETECT_BYTETEST_OP_AND;
-        } else if (strcmp("^", op_ptr) == 0) {
-            data->op |= DETECT_BYTETEST_OP_OR;
-        } else if (strcmp(">=", op_ptr) == 0) {
-            data->op |= DETECT_BYTETEST_OP_GE;
-        } else if (strcmp("<=", op_ptr) == 0) {
-            data->op |= DETECT_BYTETEST_OP_LE;
-        } else {
-            SCLogError("Invalid operator");
+    if (data->value_str != NULL) {
+        if (value == NULL) {
+            SCLogError("byte_test supplied with "
+                       "var name for value.  \"value\" argument supplied to "
+                       "this function has to be non-NULL");
             goto error;
         }
-    }
-
-    if (test_value) {
-        /*
-         * test_value was created while fetching strings and contains the test value and offset,
-         * comma separated. The values was allocated by test_value (pcre2_substring_get_bynumber)
-         * and data_offset (SCStrdup), respectively; e.g., test_value,offset
-         */
-        char *end_ptr = test_value;
-        while (!(isspace((unsigned char)*end_ptr) || (*end_ptr == ','))) end_ptr++;
-        *end_ptr = '\0';
-
-        if (test_value[0] != '-' && isalpha((unsigned char)test_value[0])) {
-            if (value == NULL) {
-                SCLogError("byte_test supplied with "
-                           "var name for value.  \"value\" argument supplied to "
-                           "this function has to be non-NULL");
-                goto error;
-            }
-            *value = SCStrdup(test_value);
-            if (*value == NULL)
-                goto error;
-        } else {
-            if (ByteExtractStringUint64(&data->value, 0, 0, test_value) <= 0) {
-                SCLogError("Malformed value: %s", test_value);
-                goto error;
-            }
-        }
-    }
-
-    /* Offset -- note that this *also* contains test_value, offset so parse accordingly */
-    if (data_offset) {
-        char *end_ptr = data_offset;
-        while (!(isspace((unsigned char)*end_ptr) || (*end_ptr == ','))) end_ptr++;
-        str_ptr = ++end_ptr;
-        while (isspace((unsigned char)*str_ptr) || (*str_ptr == ',')) str_ptr++;
-        end_ptr = (char *)str_ptr;
-        while (!(isspace((unsigned char)*end_ptr) || (*end_ptr == ',')) && (*end_ptr != '\0'))
-            end_ptr++;
-        memmove(data_offset, str_ptr, end_ptr - str_ptr);
-        data_offset[end_ptr-str_ptr] = '\0';
-        if (data_offset[0] != '-' && isalpha((unsigned char)data_offset[0])) {
-            if (data_offset == NULL) {
-                SCLogError("byte_test supplied with "
-                           "var name for offset.  \"offset\" argument supplied to "
-                           "this function has to be non-NULL");
-                goto error;
-            }
-            *offset = SCStrdup(data_offset);
-            if (*offset == NULL)
-                goto error;
-        } else {
-            if (StringParseInt32(&data->offset, 0, 0, data_offset) <= 0) {
-                SCLogError("Malformed offset: %s", data_offset);
-                goto error;
-            }
-        }
-    }
-
-    /* The remaining options are flags. */
-    /** \todo Error on dups? */
-    int bitmask_index = -1;
-    for (i = 3; i < (ret - 1); i++) {
-        if (args[i] != NULL) {
-            if (strcmp("relative", args[i]) == 0) {
-                data->flags |= DETECT_BYTETEST_RELATIVE;
-            } else if (strcasecmp("string", args[i]) == 0) {
-                data->flags |= DETECT_BYTETEST_STRING;
-            } else if (strcasecmp("dec", args[i]) == 0) {
-                data->base |= DETECT_BYTETEST_BASE_DEC;
-            } else if (strcasecmp("hex", args[i]) == 0) {
-                data->base |= DETECT_BYTETEST_BASE_HEX;
-            } else if (strcasecmp("oct", args[i]) == 0) {
-                data->base |= DETECT_BYTETEST_BASE_OCT;
-            } else if (strcasecmp("big", args[i]) == 0) {
-                if (data->flags & DETECT_BYTETEST_LITTLE) {
-                    data->flags ^= DETECT_BYTETEST_LITTLE;
-                }
-                data->flags |= DETECT_BYTETEST_BIG;
-            } else if (strcasecmp("little", args[i]) == 0) {
-                data->flags |= DETECT_BYTETEST_LITTLE;
-            } else if (strcasecmp("dce", args[i]) == 0) {
-                data->flags |= DETECT_BYTETEST_DCE;
-            } else if (strncasecmp("bitmask", args[i], strlen("bitmask")) == 0) {
-                data->flags |= DETECT_BYTETEST_BITMASK;
-                bitmask_index = i;
-            } else {
-                SCLogError("Unknown value: \"%s\"", args[i]);
-                goto error;
-            }
-        }
-    }
-
-    if (!(data->flags & DETECT_BYTETEST_NBYTES_VAR)) {
-        if (!DetectBytetestValidateNbytes(data, nbytes, optstr)) {
+        *value = SCStrdup(data->value_str);
+        if (*value == NULL)
             goto error;
-        }
-
-        /* This is max 23 so it will fit in a byte (see above) */
-        data->nbytes = (uint8_t)nbytes;
     }
 
-    if (bitmask_index != -1 && data->flags & DETECT_BYTETEST_BITMASK) {
-        if (ByteExtractStringUint32(&data->bitmask, 0, 0, args[bitmask_index]+strlen("bitmask")) <= 0) {
-            SCLogError("Malformed bitmask value: %s", args[bitmask_index] + strlen("bitmask"));
+    if (data->offset_str != NULL) {
+        if (offset == NULL) {
+            SCLogError("byte_test supplied with "
+                       "var name for offset.  \"offset\" argument supplied to "
+                       "this function has to be non-NULL");
             goto error;
         }
-        /* determine how many trailing 0's are in the bitmask. This will be used
-         * to rshift the value after applying the bitmask
-         */
-        data->bitmask_shift_count = 0;
-        if (data->bitmask) {
-            uint32_t bmask = data->bitmask;
-            while (!(bmask & 0x1)){
-                bmask = bmask >> 1;
-                data->bitmask_shift_count++;
-            }
-        }
+        *offset = SCStrdup(data->offset_str);
+        if (*offset == NULL)
+            goto error;
     }
 
-    for (i = 0; i < (ret - 1); i++){
-        if (args[i] != NULL)
-            pcre2_substring_free((PCRE2_UCHAR8 *)args[i]);
-    }
-    if (data_offset) SCFree(data_offset);
-    if (test_value)
-        pcre2_substring_free((PCRE2_UCHAR8 *)test_value);
-    pcre2_match_data_free(match);
     return data;
 
 error:
-    for (i = 0; i < (ret - 1); i++){
-        if (args[i] != NULL)
-            pcre2_substring_free((PCRE2_UCHAR8 *)args[i]);
+    if (nbytes_str != NULL && *nbytes_str != NULL) {
+        SCFree(*nbytes_str);
+        *nbytes_str = NULL;
     }
-    if (data_offset) SCFree(data_offset);
-    if (test_value)
-        pcre2_substring_free((PCRE2_UCHAR8 *)test_value);
-    if (data) SCFree(data);
-    if (match) {
-        pcre2_match_data_free(match);
+    if (value != NULL && *value != NULL) {
+        SCFree(*value);
+        *value = NULL;
     }
+    SCByteTestFree(data);
     return NULL;
 }
 
@@ -728,8 +352,7 @@ static void DetectBytetestFree(DetectEngineCtx *de_ctx, void *ptr)
     if (ptr == NULL)
         return;
 
-    DetectBytetestData *data = (DetectBytetestData *)ptr;
-    SCFree(data);
+    SCByteTestFree(ptr);
 }
 
 
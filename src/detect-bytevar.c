@@ -0,0 +1,179 @@
+/* Copyright (C) 2025 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+/**
+ * \file
+ *
+ * Implements byte_var, comparing a byte_extract or byte_math variable
+ * with an integer value.
+ */
+
+#include "suricata-common.h"
+
+#include "detect.h"
+#include "detect-parse.h"
+#include "detect-engine.h"
+#include "detect-engine-buffer.h"
+#include "detect-byte.h"
+
+#include "detect-bytevar.h"
+
+#include "rust.h"
+
+#ifdef UNITTESTS
+static void DetectByteVarRegisterTests(void);
+#endif
+
+static int DetectByteVarSetup(DetectEngineCtx *de_ctx, Signature *s, const char *arg)
+{
+    DetectUintVarData *bvd = SCDetectUintVarParse(arg);
+    if (bvd == NULL) {
+        SCLogError("invalid byte_var argument \"%s\": expected <variable>, <value>", arg);
+        goto error;
+    }
+
+    DetectByteIndexType index;
+    if (!DetectByteRetrieveSMVar(bvd->name, s, &index)) {
+        SCLogError("byte_var: unknown variable \"%s\"; it must be the result of a "
+                   "preceding byte_extract or byte_math",
+                bvd->name);
+        goto error;
+    }
+    bvd->local_id = index;
+
+    int sm_list = DETECT_SM_LIST_PMATCH;
+    if (s->init_data->list != DETECT_SM_LIST_NOTSET) {
+        if (DetectBufferGetActiveList(de_ctx, s) == -1)
+            goto error;
+
+        sm_list = s->init_data->list;
+    }
+
+    if (SCSigMatchAppendSMToList(de_ctx, s, DETECT_BYTE_VAR, (SigMatchCtx *)bvd, sm_list) !=
+            NULL) {
+        SCReturnInt(0);
+    }
+
+    /* fall through */
+
+error:
+    SCLogDebug("error during byte_var setup");
+    if (bvd != NULL) {
+        SCDetectUintVarFree(bvd);
+    }
+    SCReturnInt(-1);
+}
+
+static void DetectByteVarFree(DetectEngineCtx *de_ctx, void *ptr)
+{
+    SCDetectUintVarFree(ptr);
+}
+
+bool DetectByteVarDoMatch(DetectEngineThreadCtx *det_ctx, const SigMatchCtx *ctx)
+{
+    const DetectUintVarData *bvd = (const DetectUintVarData *)ctx;
+    return SCDetectUintVarMatch(det_ctx->byte_values[bvd->local_id], bvd) == 1;
+}
+
+void DetectByteVarRegister(void)
+{
+    sigmatch_table[DETECT_BYTE_VAR].name = "byte_var";
+    sigmatch_table[DETECT_BYTE_VAR].desc =
+            "compare a byte_extract or byte_math variable with an integer";
+    sigmatch_table[DETECT_BYTE_VAR].url = "/rules/payload-keywords.html#byte-var";
+    sigmatch_table[DETECT_BYTE_VAR].Free = DetectByteVarFree;
+    sigmatch_table[DETECT_BYTE_VAR].Setup = DetectByteVarSetup;
+#ifdef UNITTESTS
+    sigmatch_table[DETECT_BYTE_VAR].RegisterTests = DetectByteVarRegisterTests;
+#endif
+}
+
+#ifdef UNITTESTS
+#include "util-unittest.h"
+#include "util-unittest-helper.h"
+
+static int DetectByteVarTestMatch(const char *sig)
+{
+    /* length field of 0x0032 (50) in units of 4 bytes */
+    uint8_t buf[] = { 0xab, 0x00, 0x32, 0x01, 0x02, 0x03 };
+    Packet *p = UTHBuildPacket(buf, sizeof(buf), IPPROTO_TCP);
+    if (p == NULL)
+        return -1;
+    int r = UTHPacketMatchSig(p, sig);
+    UTHFreePacket(p);
+    return r;
+}
+
+/** \test byte_math result compared with byte_var */
+static int DetectByteVarTest01(void)
+{
+    /* 50 * 4 = 200 */
+    FAIL_IF_NOT(DetectByteVarTestMatch("alert tcp any any -> any any "
+                                       "(content:\"|ab|\"; "
+                                       "byte_math:bytes 2, offset 0, oper *, rvalue 4, "
+                                       "result len, relative; "
+                                       "byte_var:len, >100; sid:1;)") == 1);
+    FAIL_IF_NOT(DetectByteVarTestMatch("alert tcp any any -> any any "
+                                       "(content:\"|ab|\"; "
+                                       "byte_math:bytes 2, offset 0, oper *, rvalue 4, "
+                                       "result len, relative; "
+                                       "byte_var:len, 200; sid:1;)") == 1);
+    FAIL_IF_NOT(DetectByteVarTestMatch("alert tcp any any -> any any "
+                                       "(content:\"|ab|\"; "
+                                       "byte_math:bytes 2, offset 0, oper *, rvalue 4, "
+                                       "result len, relative; "
+                                       "byte_var:len, <100; sid:1;)") == 0);
+    PASS;
+}
+
+/** \test byte_extract variable compared with byte_var */
+static int DetectByteVarTest02(void)
+{
+    FAIL_IF_NOT(DetectByteVarTestMatch("alert tcp any any -> any any "
+                                       "(content:\"|ab|\"; "
+                                       "byte_extract:2, 0, len, relative; "
+                                       "byte_var:len, 40-60; sid:1;)") == 1);
+    PASS;
+}
+
+/** \test unknown variables make the rule fail to load */
+static int DetectByteVarTest03(void)
+{
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+    FAIL_IF_NULL(de_ctx);
+    de_ctx->flags |= DE_QUIET;
+
+    Signature *s = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any "
+                                                 "(content:\"|ab|\"; byte_var:len, <100; sid:1;)");
+    FAIL_IF_NOT_NULL(s);
+
+    s = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any "
+                                      "(content:\"|ab|\"; byte_extract:2, 0, len, relative; "
+                                      "byte_var:len <100; sid:2;)");
+    FAIL_IF_NOT_NULL(s);
+
+    DetectEngineCtxFree(de_ctx);
+    PASS;
+}
+
+static void DetectByteVarRegisterTests(void)
+{
+    UtRegisterTest("DetectByteVarTest01", DetectByteVarTest01);
+    UtRegisterTest("DetectByteVarTest02", DetectByteVarTest02);
+    UtRegisterTest("DetectByteVarTest03", DetectByteVarTest03);
+}
+#endif /* UNITTESTS */
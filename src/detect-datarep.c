@@ -68,12 +68,12 @@ void DetectDatarepRegister (void)
  */
 int DetectDatarepBufferMatch(DetectEngineThreadCtx *det_ctx,
     const DetectDatarepData *sd,
-    const uint8_t *data, const uint32_t data_len)
+    const uint8_t *data, const uint32_t data_len, const SCTime_t ts)
 {
     if (data == NULL || data_len == 0)
         return 0;
 
-    DataRepResultType r = DatasetLookupwRep(sd->set, data, data_len, &sd->rep);
+    DataRepResultType r = DatasetLookupwRep(sd->set, data, data_len, &sd->rep, ts);
     if (!r.found)
         return 0;
 
@@ -332,7 +332,7 @@ static int DetectDatarepSetup (DetectEngineCtx *de_ctx, Signature *s, const char
         return -1;
     }
 
-    Dataset *set = DatasetGet(name, type, /* no save */ NULL, load, memcap, hashsize);
+    Dataset *set = DatasetGet(name, type, /* no save */ NULL, load, memcap, hashsize, false);
     if (set == NULL) {
         SCLogError("failed to set up datarep set '%s'.", name);
         return -1;
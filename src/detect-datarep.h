@@ -42,7 +42,7 @@ typedef struct DetectDatarepData_ {
 
 int DetectDatarepBufferMatch(DetectEngineThreadCtx *det_ctx,
     const DetectDatarepData *sd,
-    const uint8_t *data, const uint32_t data_len);
+    const uint8_t *data, const uint32_t data_len, const SCTime_t ts);
 
 /* prototypes */
 void DetectDatarepRegister (void);
@@ -36,12 +36,15 @@
 #include "detect-engine-mpm.h"
 #include "detect-engine-state.h"
 
+#include "util-byte.h"
 #include "util-debug.h"
 #include "util-print.h"
 #include "util-misc.h"
 #include "util-path.h"
 #include "util-conf.h"
 #include "util-validate.h"
+#include "util-unittest.h"
+#include "util-unittest-helper.h"
 
 #define DETECT_DATASET_CMD_SET      0
 #define DETECT_DATASET_CMD_UNSET    1
@@ -50,6 +53,9 @@
 
 static int DetectDatasetSetup (DetectEngineCtx *, Signature *, const char *);
 void DetectDatasetFree (DetectEngineCtx *, void *);
+#ifdef UNITTESTS
+static void DetectDatasetRegisterTests(void);
+#endif
 
 void DetectDatasetRegister (void)
 {
@@ -58,6 +64,9 @@ void DetectDatasetRegister (void)
     sigmatch_table[DETECT_DATASET].url = "/rules/dataset-keywords.html#dataset";
     sigmatch_table[DETECT_DATASET].Setup = DetectDatasetSetup;
     sigmatch_table[DETECT_DATASET].Free  = DetectDatasetFree;
+#ifdef UNITTESTS
+    sigmatch_table[DETECT_DATASET].RegisterTests = DetectDatasetRegisterTests;
+#endif
 }
 
 /*
@@ -67,15 +76,20 @@ void DetectDatasetRegister (void)
  */
 int DetectDatasetBufferMatch(DetectEngineThreadCtx *det_ctx,
     const DetectDatasetData *sd,
-    const uint8_t *data, const uint32_t data_len)
+    const uint8_t *data, const uint32_t data_len, const SCTime_t ts)
 {
     if (data == NULL || data_len == 0)
         return 0;
 
     switch (sd->cmd) {
         case DETECT_DATASET_CMD_ISSET: {
+            if (sd->count != NULL) {
+                const DataRepType lookup = { .value = 0 };
+                DataRepResultType r = DatasetLookupwRep(sd->set, data, data_len, &lookup, ts);
+                return SCDatasetCountMatch(r.found, &r.rep, sd->count) ? 1 : 0;
+            }
             //PrintRawDataFp(stdout, data, data_len);
-            int r = DatasetLookup(sd->set, data, data_len);
+            int r = DatasetLookup(sd->set, data, data_len, ts);
             SCLogDebug("r %d", r);
             if (r == 1)
                 return 1;
@@ -83,15 +97,23 @@ int DetectDatasetBufferMatch(DetectEngineThreadCtx *det_ctx,
         }
         case DETECT_DATASET_CMD_ISNOTSET: {
             //PrintRawDataFp(stdout, data, data_len);
-            int r = DatasetLookup(sd->set, data, data_len);
+            int r = DatasetLookup(sd->set, data, data_len, ts);
             SCLogDebug("r %d", r);
             if (r < 1)
                 return 1;
             break;
         }
         case DETECT_DATASET_CMD_SET: {
+            if (sd->from_var) {
+                uint8_t value[DATASET_VAR_MAX_LEN];
+                const uint32_t value_len = SCDatasetVarEncode(
+                        det_ctx->byte_values[sd->var_idx], sd->var_type, value, sizeof(value));
+                if (value_len == 0)
+                    return 0;
+                return DatasetAddTs(sd->set, value, value_len, ts) == 1 ? 1 : 0;
+            }
             //PrintRawDataFp(stdout, data, data_len);
-            int r = DatasetAdd(sd->set, data, data_len);
+            int r = DatasetAddTs(sd->set, data, data_len, ts);
             if (r == 1)
                 return 1;
             break;
@@ -110,7 +132,8 @@ int DetectDatasetBufferMatch(DetectEngineThreadCtx *det_ctx,
 
 static int DetectDatasetParse(const char *str, char *cmd, int cmd_len, char *name, int name_len,
         enum DatasetTypes *type, char *load, size_t load_size, char *save, size_t save_size,
-        uint64_t *memcap, uint32_t *hashsize)
+        uint64_t *memcap, uint32_t *hashsize, DetectU32Data **count, uint32_t *ttl, char *var,
+        size_t var_size, bool *nocase)
 {
     bool cmd_set = false;
     bool name_set = false;
@@ -153,6 +176,15 @@ static int DetectDatasetParse(const char *str, char *cmd, int cmd_len, char *nam
             }
             strlcpy(name, key, name_len);
             name_set = true;
+        } else if (strcmp(key, "nocase") == 0) {
+            if (val && strlen(val) != 0) {
+                return -1;
+            }
+            if (*nocase) {
+                SCLogWarning("'nocase' can only appear once");
+                return -1;
+            }
+            *nocase = true;
         } else {
             if (val == NULL) {
                 return -1;
@@ -203,6 +235,37 @@ static int DetectDatasetParse(const char *str, char *cmd, int cmd_len, char *nam
                 strlcpy(load, val, load_size);
                 strlcpy(save, val, save_size);
                 state_set = true;
+            } else if (strcmp(key, "count") == 0) {
+                if (*count != NULL) {
+                    SCLogWarning("'count' can only appear once");
+                    return -1;
+                }
+                SCLogDebug("count %s", val);
+                *count = DetectU32Parse(val);
+                if (*count == NULL) {
+                    SCLogError("invalid value for count: %s", val);
+                    return -1;
+                }
+            } else if (strcmp(key, "ttl") == 0) {
+                if (*ttl != 0) {
+                    SCLogWarning("'ttl' can only appear once");
+                    return -1;
+                }
+                SCLogDebug("ttl %s", val);
+                if (StringParseUint32(ttl, 10, 0, val) <= 0 || *ttl == 0) {
+                    SCLogError("invalid value for ttl: %s", val);
+                    return -1;
+                }
+            } else if (strcmp(key, "from_var") == 0) {
+                if (strlen(var) != 0) {
+                    SCLogWarning("'from_var' can only appear once");
+                    return -1;
+                }
+                SCLogDebug("from_var %s", val);
+                if (strlen(val) == 0 || strlcpy(var, val, var_size) >= var_size) {
+                    SCLogError("invalid value for from_var: %s", val);
+                    return -1;
+                }
             }
             if (strcmp(key, "memcap") == 0) {
                 if (ParseSizeStringU64(val, memcap) < 0) {
@@ -361,6 +424,11 @@ int DetectDatasetSetup (DetectEngineCtx *de_ctx, Signature *s, const char *rawst
     enum DatasetTypes type = DATASET_TYPE_NOTSET;
     char load[PATH_MAX] = "";
     char save[PATH_MAX] = "";
+    DetectU32Data *count = NULL;
+    uint32_t ttl = 0;
+    char var[64] = "";
+    DetectByteIndexType var_idx = 0;
+    bool nocase = false;
 
     if (DetectBufferGetActiveList(de_ctx, s) == -1) {
         SCLogError("datasets are only supported for sticky buffers");
@@ -373,9 +441,10 @@ int DetectDatasetSetup (DetectEngineCtx *de_ctx, Signature *s, const char *rawst
         SCReturnInt(-1);
     }
 
-    if (!DetectDatasetParse(rawstr, cmd_str, sizeof(cmd_str), name, sizeof(name), &type, load,
-                sizeof(load), save, sizeof(save), &memcap, &hashsize)) {
-        return -1;
+    if (DetectDatasetParse(rawstr, cmd_str, sizeof(cmd_str), name, sizeof(name), &type, load,
+                sizeof(load), save, sizeof(save), &memcap, &hashsize, &count, &ttl, var,
+                sizeof(var), &nocase) != 1) {
+        goto error;
     }
 
     if (strcmp(cmd_str,"isset") == 0) {
@@ -388,33 +457,71 @@ int DetectDatasetSetup (DetectEngineCtx *de_ctx, Signature *s, const char *rawst
         cmd = DETECT_DATASET_CMD_UNSET;
     } else {
         SCLogError("dataset action \"%s\" is not supported.", cmd_str);
-        return -1;
+        goto error;
+    }
+
+    if (count != NULL && cmd != DETECT_DATASET_CMD_ISSET) {
+        SCLogError("dataset 'count' is only supported with isset");
+        goto error;
+    }
+    if (strlen(var) != 0) {
+        if (cmd != DETECT_DATASET_CMD_SET) {
+            SCLogError("dataset 'from_var' is only supported with set");
+            goto error;
+        }
+        if (!DetectByteRetrieveSMVar(var, s, &var_idx)) {
+            SCLogError("unknown byte_extract or byte_math var \"%s\"", var);
+            goto error;
+        }
     }
 
     /* if just 'load' is set, we load data from the same dir as the
      * rule file. If load+save is used, we use data dir */
     if (strlen(save) == 0 && strlen(load) != 0) {
         if (SetupLoadPath(de_ctx, load, sizeof(load)) != 0)
-            return -1;
+            goto error;
     /* if just 'save' is set, we use either full path or the
      * data-dir */
     } else if (strlen(save) != 0 && strlen(load) == 0) {
         if (SetupSavePath(de_ctx, save, sizeof(save)) != 0)
-            return -1;
+            goto error;
     /* use 'save' logic for 'state', but put the resulting
      * path into 'load' as well. */
     } else if (strlen(save) != 0 && strlen(load) != 0 &&
             strcmp(save, load) == 0) {
         if (SetupSavePath(de_ctx, save, sizeof(save)) != 0)
-            return -1;
+            goto error;
         strlcpy(load, save, sizeof(load));
     }
 
     SCLogDebug("name '%s' load '%s' save '%s'", name, load, save);
-    Dataset *set = DatasetGet(name, type, save, load, memcap, hashsize);
+    Dataset *set = DatasetGet(name, type, save, load, memcap, hashsize, nocase);
     if (set == NULL) {
         SCLogError("failed to set up dataset '%s'.", name);
-        return -1;
+        goto error;
+    }
+    if (set->bloom != NULL &&
+            (count != NULL || ttl != 0 || cmd == DETECT_DATASET_CMD_UNSET)) {
+        SCLogError("dataset '%s' is a bloom filter, it does not support count, ttl or unset",
+                name);
+        goto error;
+    }
+    if (set->store != NULL &&
+            (count != NULL || ttl != 0 || cmd == DETECT_DATASET_CMD_SET ||
+                    cmd == DETECT_DATASET_CMD_UNSET)) {
+        SCLogError("dataset '%s' is read-only, it only supports isset and isnotset", name);
+        goto error;
+    }
+    if (ttl != 0) {
+        if (set->ttl != 0 && set->ttl != ttl) {
+            SCLogError("dataset '%s' already uses a ttl of %u", name, set->ttl);
+            goto error;
+        }
+        set->ttl = ttl;
+    }
+    if (strlen(var) != 0 && set->type != DATASET_TYPE_STRING && set->type != DATASET_TYPE_IPV4) {
+        SCLogError("dataset '%s': 'from_var' is only supported for string and ipv4 sets", name);
+        goto error;
     }
 
     cd = SCCalloc(1, sizeof(DetectDatasetData));
@@ -423,6 +530,12 @@ int DetectDatasetSetup (DetectEngineCtx *de_ctx, Signature *s, const char *rawst
 
     cd->set = set;
     cd->cmd = cmd;
+    cd->count = count;
+    if (strlen(var) != 0) {
+        cd->from_var = true;
+        cd->var_idx = var_idx;
+        cd->var_type = set->type == DATASET_TYPE_IPV4 ? DSIpv4 : DSString;
+    }
 
     SCLogDebug("cmd %s, name %s",
         cmd_str, strlen(name) ? name : "(none)");
@@ -438,6 +551,8 @@ int DetectDatasetSetup (DetectEngineCtx *de_ctx, Signature *s, const char *rawst
 error:
     if (cd != NULL)
         SCFree(cd);
+    if (count != NULL)
+        SCDetectU32Free(count);
     return -1;
 }
 
@@ -447,5 +562,177 @@ void DetectDatasetFree (DetectEngineCtx *de_ctx, void *ptr)
     if (fd == NULL)
         return;
 
+    if (fd->count != NULL)
+        SCDetectU32Free(fd->count);
     SCFree(fd);
 }
+
+#ifdef UNITTESTS
+#include "detect-engine-build.h"
+
+/** \test set with from_var adds the extracted value and bumps its count */
+static int DetectDatasetFromVarTest01(void)
+{
+    uint8_t buf[] = "dataset";
+    ThreadVars tv;
+    DetectEngineThreadCtx *det_ctx = NULL;
+
+    memset(&tv, 0, sizeof(ThreadVars));
+
+    Packet *p = UTHBuildPacketReal(
+            buf, sizeof(buf) - 1, IPPROTO_UDP, "192.168.1.5", "192.168.1.1", 41424, 53);
+    FAIL_IF_NULL(p);
+
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+    FAIL_IF_NULL(de_ctx);
+    de_ctx->flags |= DE_QUIET;
+
+    /* the first 4 bytes of the udp header are the source and destination ports */
+    Signature *s = DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; byte_extract:4,0,ports; "
+            "dataset:set,dataset-from-var-test,type ipv4,from_var ports; sid:1;)");
+    FAIL_IF_NULL(s);
+
+    SigGroupBuild(de_ctx);
+    DetectEngineThreadCtxInit(&tv, (void *)de_ctx, (void *)&det_ctx);
+    FAIL_IF_NULL(det_ctx);
+
+    Dataset *set = DatasetFind("dataset-from-var-test", DATASET_TYPE_IPV4);
+    FAIL_IF_NULL(set);
+
+    /* 41424 and 53 */
+    const uint8_t ports[4] = { 0xa1, 0xd0, 0x00, 0x35 };
+    const DataRepType lookup = { .value = 0 };
+
+    SigMatchSignatures(&tv, de_ctx, det_ctx, p);
+    DataRepResultType r = DatasetLookupwRep(set, ports, sizeof(ports), &lookup, p->ts);
+    FAIL_IF_NOT(r.found);
+    FAIL_IF_NOT(r.rep.count == 1);
+
+    SigMatchSignatures(&tv, de_ctx, det_ctx, p);
+    r = DatasetLookupwRep(set, ports, sizeof(ports), &lookup, p->ts);
+    FAIL_IF_NOT(r.found);
+    FAIL_IF_NOT(r.rep.count == 2);
+
+    DetectEngineThreadCtxDeinit(&tv, (void *)det_ctx);
+    DetectEngineCtxFree(de_ctx);
+    UTHFreePacket(p);
+    PASS;
+}
+
+/** \test from_var is only valid for set, with a known var and a string or ipv4 set */
+static int DetectDatasetFromVarTest02(void)
+{
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+    FAIL_IF_NULL(de_ctx);
+    de_ctx->flags |= DE_QUIET;
+
+    FAIL_IF_NOT_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; byte_extract:4,0,ports; "
+            "dataset:isset,dataset-from-var-test2,type ipv4,from_var ports; sid:1;)"));
+    FAIL_IF_NOT_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; byte_extract:4,0,ports; "
+            "dataset:set,dataset-from-var-test2,type ipv4,from_var other; sid:2;)"));
+    FAIL_IF_NOT_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; byte_extract:4,0,ports; "
+            "dataset:set,dataset-from-var-test3,type md5,from_var ports; sid:3;)"));
+    FAIL_IF_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; byte_extract:2,0,port; "
+            "dataset:set,dataset-from-var-test4,type string,from_var port; sid:4;)"));
+
+    DetectEngineCtxFree(de_ctx);
+    PASS;
+}
+
+/** \test a nocase set matches a string whatever its case, other sets are not affected */
+static int DetectDatasetNocaseTest01(void)
+{
+    uint8_t buf[] = "dataset";
+    ThreadVars tv;
+    DetectEngineThreadCtx *det_ctx = NULL;
+
+    memset(&tv, 0, sizeof(ThreadVars));
+
+    /* the ports are "AB" and "cd" */
+    Packet *p = UTHBuildPacketReal(
+            buf, sizeof(buf) - 1, IPPROTO_UDP, "192.168.1.5", "192.168.1.1", 16706, 25444);
+    FAIL_IF_NULL(p);
+
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+    FAIL_IF_NULL(de_ctx);
+    de_ctx->flags |= DE_QUIET;
+
+    FAIL_IF_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; slice:0,4; "
+            "dataset:isset,dataset-nocase-test,type string,nocase; sid:1;)"));
+    FAIL_IF_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; slice:0,4; "
+            "dataset:isset,dataset-case-test,type string; sid:2;)"));
+
+    SigGroupBuild(de_ctx);
+    DetectEngineThreadCtxInit(&tv, (void *)de_ctx, (void *)&det_ctx);
+    FAIL_IF_NULL(det_ctx);
+
+    Dataset *nocase = DatasetFind("dataset-nocase-test", DATASET_TYPE_STRING);
+    FAIL_IF_NULL(nocase);
+    Dataset *exact = DatasetFind("dataset-case-test", DATASET_TYPE_STRING);
+    FAIL_IF_NULL(exact);
+
+    FAIL_IF_NOT(DatasetAdd(nocase, (const uint8_t *)"abCD", 4) == 1);
+    FAIL_IF_NOT(DatasetAdd(nocase, (const uint8_t *)"ABCD", 4) == 0);
+    FAIL_IF_NOT(DatasetLookup(nocase, (const uint8_t *)"abcd", 4, p->ts) == 1);
+    FAIL_IF_NOT(DatasetAdd(exact, (const uint8_t *)"ABCD", 4) == 1);
+    FAIL_IF_NOT(DatasetLookup(exact, (const uint8_t *)"ABcd", 4, p->ts) == 0);
+
+    SigMatchSignatures(&tv, de_ctx, det_ctx, p);
+    FAIL_IF_NOT(PacketAlertCheck(p, 1));
+    FAIL_IF(PacketAlertCheck(p, 2));
+
+    DetectEngineThreadCtxDeinit(&tv, (void *)det_ctx);
+    DetectEngineCtxFree(de_ctx);
+    UTHFreePacket(p);
+    PASS;
+}
+
+/** \test nocase is only valid once, for string sets that are nocase */
+static int DetectDatasetNocaseTest02(void)
+{
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+    FAIL_IF_NULL(de_ctx);
+    de_ctx->flags |= DE_QUIET;
+
+    FAIL_IF_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; "
+            "dataset:isset,dataset-nocase-test2,type string,nocase; sid:1;)"));
+    /* the set can be used without nocase */
+    FAIL_IF_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; "
+            "dataset:isset,dataset-nocase-test2; sid:2;)"));
+    FAIL_IF_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; "
+            "dataset:isset,dataset-nocase-test3,type string; sid:3;)"));
+    FAIL_IF_NOT_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; "
+            "dataset:isset,dataset-nocase-test3,nocase; sid:4;)"));
+    FAIL_IF_NOT_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; "
+            "dataset:isset,dataset-nocase-test4,type md5,nocase; sid:5;)"));
+    FAIL_IF_NOT_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; "
+            "dataset:isset,dataset-nocase-test5,type string,nocase,nocase; sid:6;)"));
+    FAIL_IF_NOT_NULL(DetectEngineAppendSig(de_ctx,
+            "alert udp any any -> any any (udp.hdr; "
+            "dataset:isset,dataset-nocase-test6,type string,nocase yes; sid:7;)"));
+
+    DetectEngineCtxFree(de_ctx);
+    PASS;
+}
+
+static void DetectDatasetRegisterTests(void)
+{
+    UtRegisterTest("DetectDatasetFromVarTest01", DetectDatasetFromVarTest01);
+    UtRegisterTest("DetectDatasetFromVarTest02", DetectDatasetFromVarTest02);
+    UtRegisterTest("DetectDatasetNocaseTest01", DetectDatasetNocaseTest01);
+    UtRegisterTest("DetectDatasetNocaseTest02", DetectDatasetNocaseTest02);
+}
+#endif /* UNITTESTS */
@@ -25,15 +25,23 @@
 #define SURICATA_DETECT_DATASET_H
 
 #include "datasets.h"
+#include "detect-engine-uint.h"
+#include "detect-byte.h"
 
 typedef struct DetectDatasetData_ {
     Dataset *set;
     uint8_t cmd;
+    /* optional count comparison for isset */
+    DetectU32Data *count;
+    /* set: add the value of a byte_extract/byte_math var instead of the buffer */
+    bool from_var;
+    DetectByteIndexType var_idx;
+    DatasetType var_type;
 } DetectDatasetData;
 
 int DetectDatasetBufferMatch(DetectEngineThreadCtx *det_ctx,
     const DetectDatasetData *sd,
-    const uint8_t *data, const uint32_t data_len);
+    const uint8_t *data, const uint32_t data_len, const SCTime_t ts);
 
 /* prototypes */
 void DetectDatasetRegister (void);
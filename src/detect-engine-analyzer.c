@@ -722,6 +722,19 @@ static void DumpPcre(SCJsonBuilder *js, const DetectPcreData *cd)
     SCJbSetBool(js, "negated", cd->flags & DETECT_PCRE_NEGATE);
 }
 
+static void DumpTransforms(SCJsonBuilder *js, const DetectEngineTransforms *transforms)
+{
+    const char *names[DETECT_TRANSFORMS_MAX];
+    const char *options[DETECT_TRANSFORMS_MAX];
+
+    for (int t = 0; t < transforms->cnt; t++) {
+        const TransformData *td = &transforms->transforms[t];
+        names[t] = sigmatch_table[td->transform].name;
+        options[t] = td->optstr;
+    }
+    SCDetectTransformsToJson(js, names, options, (uint32_t)transforms->cnt);
+}
+
 static void DumpMatches(RuleAnalyzer *ctx, SCJsonBuilder *js, const SigMatchData *smd)
 {
     if (smd == NULL)
@@ -957,6 +970,13 @@ static void DumpMatches(RuleAnalyzer *ctx, SCJsonBuilder *js, const SigMatchData
                 SCJbClose(js);
                 break;
             }
+            case DETECT_ENTROPY: {
+                const DetectEntropyData *ed = (const DetectEntropyData *)smd->ctx;
+                SCJbOpenObject(js, "entropy");
+                SCDetectEntropyToJson(js, ed);
+                SCJbClose(js);
+                break;
+            }
         }
         SCJbClose(js);
 
@@ -1230,14 +1250,7 @@ void EngineAnalysisRules2(const DetectEngineCtx *de_ctx, const Signature *s)
         SCJbSetString(ctx.js, "name", name);
         SCJbSetBool(ctx.js, "is_mpm", pkt->mpm);
         if (pkt->v1.transforms != NULL) {
-            SCJbOpenArray(ctx.js, "transforms");
-            for (int t = 0; t < pkt->v1.transforms->cnt; t++) {
-                SCJbStartObject(ctx.js);
-                SCJbSetString(ctx.js, "name",
-                        sigmatch_table[pkt->v1.transforms->transforms[t].transform].name);
-                SCJbClose(ctx.js);
-            }
-            SCJbClose(ctx.js);
+            DumpTransforms(ctx.js, pkt->v1.transforms);
         }
         DumpMatches(&ctx, ctx.js, pkt->smd);
         SCJbClose(ctx.js);
@@ -1254,14 +1267,7 @@ void EngineAnalysisRules2(const DetectEngineCtx *de_ctx, const Signature *s)
         SCJbSetString(ctx.js, "name", name);
         SCJbSetBool(ctx.js, "is_mpm", frame->mpm);
         if (frame->v1.transforms != NULL) {
-            SCJbOpenArray(ctx.js, "transforms");
-            for (int t = 0; t < frame->v1.transforms->cnt; t++) {
-                SCJbStartObject(ctx.js);
-                SCJbSetString(ctx.js, "name",
-                        sigmatch_table[frame->v1.transforms->transforms[t].transform].name);
-                SCJbClose(ctx.js);
-            }
-            SCJbClose(ctx.js);
+            DumpTransforms(ctx.js, frame->v1.transforms);
         }
         DumpMatches(&ctx, ctx.js, frame->smd);
         SCJbClose(ctx.js);
@@ -1305,14 +1311,7 @@ void EngineAnalysisRules2(const DetectEngineCtx *de_ctx, const Signature *s)
             SCJbSetUint(ctx.js, "progress", app->progress);
 
             if (app->v2.transforms != NULL) {
-                SCJbOpenArray(ctx.js, "transforms");
-                for (int t = 0; t < app->v2.transforms->cnt; t++) {
-                    SCJbStartObject(ctx.js);
-                    SCJbSetString(ctx.js, "name",
-                            sigmatch_table[app->v2.transforms->transforms[t].transform].name);
-                    SCJbClose(ctx.js);
-                }
-                SCJbClose(ctx.js);
+                DumpTransforms(ctx.js, app->v2.transforms);
             }
             DumpMatches(&ctx, ctx.js, app->smd);
             SCJbClose(ctx.js);
@@ -191,6 +191,7 @@ int SCDetectSignatureAddTransform(Signature *s, int transform, void *options)
 
     s->init_data->transforms.transforms[s->init_data->transforms.cnt].transform = transform;
     s->init_data->transforms.transforms[s->init_data->transforms.cnt].options = options;
+    s->init_data->transforms.transforms[s->init_data->transforms.cnt].optstr = NULL;
 
     s->init_data->transforms.cnt++;
     SCLogDebug("Added transform #%d [%s]", s->init_data->transforms.cnt, s->sig_str);
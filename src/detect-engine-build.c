@@ -246,6 +246,11 @@ int SignatureIsIPOnly(DetectEngineCtx *de_ctx, const Signature *s)
                 (((DetectFlowbitsData *)sm->ctx)->cmd != DETECT_FLOWBITS_CMD_SET)) {
             return 0;
         }
+        /* iprep delta compares to the first value of the flow, so it needs
+         * the later packets that ip-only sigs don't inspect */
+        if (sm->type == DETECT_IPREP && ((DetectIPRepData *)sm->ctx)->isdelta) {
+            return 0;
+        }
     }
     sm = s->init_data->smlists[DETECT_SM_LIST_POSTMATCH];
     for ( ; sm != NULL; sm = sm->next) {
@@ -39,6 +39,7 @@
 #include "detect-isdataat.h"
 #include "detect-bytetest.h"
 #include "detect-bytemath.h"
+#include "detect-bytevar.h"
 #include "detect-bytejump.h"
 #include "detect-byte-extract.h"
 #include "detect-entropy.h"
@@ -76,6 +77,20 @@ struct DetectEngineContentInspectionCtx {
     } recursion;
 };
 
+/** \brief time of the packet being inspected
+ *
+ *  Transactions, files and the like are inspected without a packet, use
+ *  the time of the last packet of their flow then.
+ */
+static inline SCTime_t DetectEngineContentInspectionTime(const Packet *p, const Flow *f)
+{
+    if (p != NULL)
+        return p->ts;
+    if (f != NULL)
+        return f->lastts;
+    return TimeGet();
+}
+
 /**
  * \brief Run the actual payload match functions
  *
@@ -528,12 +543,17 @@ static int DetectEngineContentInspectionInternal(DetectEngineThreadCtx *det_ctx,
         const DetectBytejumpData *bjd = (const DetectBytejumpData *)smd->ctx;
         uint16_t bjflags = bjd->flags;
         int32_t offset = bjd->offset;
+        int64_t post_offset = bjd->post_offset;
         int32_t nbytes;
 
         if (bjflags & DETECT_BYTEJUMP_OFFSET_VAR) {
             offset = det_ctx->byte_values[offset];
         }
 
+        if (bjflags & DETECT_BYTEJUMP_POST_OFFSET_VAR) {
+            post_offset = (int64_t)det_ctx->byte_values[post_offset];
+        }
+
         if (bjflags & DETECT_BYTEJUMP_NBYTES_VAR) {
             nbytes = det_ctx->byte_values[bjd->nbytes];
         } else {
@@ -549,8 +569,8 @@ static int DetectEngineContentInspectionInternal(DetectEngineThreadCtx *det_ctx,
                       DETECT_BYTEJUMP_LITTLE: 0);
         }
 
-        if (!DetectBytejumpDoMatch(
-                    det_ctx, s, smd->ctx, buffer, buffer_len, bjflags, nbytes, offset)) {
+        if (!DetectBytejumpDoMatch(det_ctx, s, smd->ctx, buffer, buffer_len, bjflags, nbytes,
+                    offset, post_offset)) {
             goto no_match;
         }
 
@@ -562,13 +582,10 @@ static int DetectEngineContentInspectionInternal(DetectEngineThreadCtx *det_ctx,
         uint8_t endian = bed->endian;
 
         /* if we have dce enabled we will have to use the endianness
-         * specified by the dce header */
-        if ((bed->flags & DETECT_BYTE_EXTRACT_FLAG_ENDIAN) && endian == EndianDCE &&
-                flags & (DETECT_CI_FLAGS_DCE_LE | DETECT_CI_FLAGS_DCE_BE)) {
-
-            /* enable the endianness flag temporarily.  once we are done
-             * processing we reset the flags to the original value*/
-            endian |= ((flags & DETECT_CI_FLAGS_DCE_LE) ? LittleEndian : BigEndian);
+         * specified by the dce header, or the default one without it */
+        if (bed->flags & DETECT_BYTE_EXTRACT_FLAG_ENDIAN) {
+            endian = SCDetectByteEndianResolve(endian, (flags & DETECT_CI_FLAGS_DCE_LE) != 0,
+                    (flags & DETECT_CI_FLAGS_DCE_BE) != 0);
         }
 
         if (DetectByteExtractDoMatch(det_ctx, smd, s, buffer, buffer_len,
@@ -586,12 +603,10 @@ static int DetectEngineContentInspectionInternal(DetectEngineThreadCtx *det_ctx,
         uint8_t endian = bmd->endian;
 
         /* if we have dce enabled we will have to use the endianness
-         * specified by the dce header */
-        if ((bmd->flags & DETECT_BYTEMATH_FLAG_ENDIAN) && endian == (int)EndianDCE &&
-                flags & (DETECT_CI_FLAGS_DCE_LE | DETECT_CI_FLAGS_DCE_BE)) {
-            /* enable the endianness flag temporarily.  once we are done
-             * processing we reset the flags to the original value*/
-            endian = (uint8_t)((flags & DETECT_CI_FLAGS_DCE_LE) ? LittleEndian : BigEndian);
+         * specified by the dce header, or the default one without it */
+        if (bmd->flags & DETECT_BYTEMATH_FLAG_ENDIAN) {
+            endian = SCDetectByteEndianResolve(endian, (flags & DETECT_CI_FLAGS_DCE_LE) != 0,
+                    (flags & DETECT_CI_FLAGS_DCE_BE) != 0);
         }
         uint64_t rvalue;
         if (bmd->flags & DETECT_BYTEMATH_FLAG_RVALUE_VAR) {
@@ -616,6 +631,13 @@ static int DetectEngineContentInspectionInternal(DetectEngineThreadCtx *det_ctx,
                    bmd->local_id, det_ctx->byte_values[bmd->local_id]);
         goto match;
 
+    } else if (smd->type == DETECT_BYTE_VAR) {
+
+        if (!DetectByteVarDoMatch(det_ctx, smd->ctx)) {
+            goto no_match;
+        }
+        goto match;
+
     } else if (smd->type == DETECT_BSIZE) {
 
         const bool eof = (flags & DETECT_CI_FLAGS_END);
@@ -632,7 +654,8 @@ static int DetectEngineContentInspectionInternal(DetectEngineThreadCtx *det_ctx,
 
         //PrintRawDataFp(stdout, buffer, buffer_len);
         const DetectDatasetData *sd = (const DetectDatasetData *) smd->ctx;
-        int r = DetectDatasetBufferMatch(det_ctx, sd, buffer, buffer_len); //TODO buffer offset?
+        int r = DetectDatasetBufferMatch(det_ctx, sd, buffer, buffer_len,
+                DetectEngineContentInspectionTime(p, f)); //TODO buffer offset?
         if (r == 1) {
             goto match;
         }
@@ -642,7 +665,8 @@ static int DetectEngineContentInspectionInternal(DetectEngineThreadCtx *det_ctx,
 
         //PrintRawDataFp(stdout, buffer, buffer_len);
         const DetectDatarepData *sd = (const DetectDatarepData *) smd->ctx;
-        int r = DetectDatarepBufferMatch(det_ctx, sd, buffer, buffer_len); //TODO buffer offset?
+        int r = DetectDatarepBufferMatch(det_ctx, sd, buffer, buffer_len,
+                DetectEngineContentInspectionTime(p, f)); //TODO buffer offset?
         if (r == 1) {
             goto match;
         }
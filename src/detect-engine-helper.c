@@ -126,6 +126,18 @@ void SCDetectHelperKeywordAliasRegister(uint16_t kwid, const char *alias)
     sigmatch_table[kwid].alias = alias;
 }
 
+bool SCDetectHelperKeywordExists(const char *name)
+{
+    for (int i = 0; i < DETECT_TBLSIZE; i++) {
+        const SigTableElmt *st = &sigmatch_table[i];
+        if (st->name != NULL && strcasecmp(name, st->name) == 0)
+            return true;
+        if (st->alias != NULL && strcasecmp(name, st->alias) == 0)
+            return true;
+    }
+    return false;
+}
+
 int SCDetectHelperTransformRegister(const SCTransformTableElmt *kw)
 {
     int transform_id = SCDetectHelperNewKeywordId();
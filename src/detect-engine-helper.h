@@ -80,6 +80,7 @@ int SCDetectHelperNewKeywordId(void);
 
 uint16_t SCDetectHelperKeywordRegister(const SCSigTableAppLiteElmt *kw);
 void SCDetectHelperKeywordAliasRegister(uint16_t kwid, const char *alias);
+bool SCDetectHelperKeywordExists(const char *name);
 int SCDetectHelperBufferRegister(const char *name, AppProto alproto, uint8_t direction);
 
 int SCDetectHelperBufferMpmRegister(const char *name, const char *desc, AppProto alproto,
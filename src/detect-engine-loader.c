@@ -483,6 +483,9 @@ skip_regular_rules:
             SCDetectRequiresStatusLog(de_ctx->requirements, PROG_VER,
                     strlen(de_ctx->config_prefix) > 0 ? de_ctx->tenant_id : 0);
         }
+#ifdef HAVE_KEYWORD_METRICS
+        SCDetectKeywordSetupMetricsLog();
+#endif
     }
 
     if ((sig_stat->bad_sigs_total || sig_stat->bad_files) && de_ctx->failure_fatal) {
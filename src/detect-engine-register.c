@@ -121,6 +121,8 @@
 #include "detect-flow.h"
 #include "detect-flow-age.h"
 #include "detect-flow-pkts.h"
+#include "detect-flow-bit.h"
+#include "detect-flow-buffer.h"
 #include "detect-requires.h"
 #include "detect-tcp-window.h"
 #include "detect-ftpbounce.h"
@@ -211,6 +213,7 @@
 #include "detect-ja4-hash.h"
 #include "detect-ftp-command.h"
 #include "detect-entropy.h"
+#include "detect-bytevar.h"
 #include "detect-ftp-command-data.h"
 #include "detect-ftp-completion-code.h"
 #include "detect-ftp-reply.h"
@@ -616,6 +619,7 @@ void SigTableSetup(void)
     DetectBytejumpRegister();
     DetectBytemathRegister();
     DetectEntropyRegister();
+    DetectByteVarRegister();
     DetectSameipRegister();
     DetectGeoipRegister();
     DetectL3ProtoRegister();
@@ -632,6 +636,8 @@ void SigTableSetup(void)
     DetectFlowBytesRegister();
     DetectFlowBytesToServerRegister();
     DetectFlowBytesToClientRegister();
+    DetectFlowBitRegister();
+    DetectFlowBufferRegister();
     DetectRequiresRegister();
     DetectWindowRegister();
     DetectRpcRegister();
@@ -738,18 +744,28 @@ void SigTableSetup(void)
 
     DetectTransformCompressWhitespaceRegister();
     DetectTransformStripWhitespaceRegister();
+    DetectTransformStripLengthPrefixRegister();
+    DetectTransformTruncateRegister();
+    DetectTransformPadLeftRegister();
+    DetectTransformPadRightRegister();
+    DetectTransformSliceRegister();
     DetectTransformStripPseudoHeadersRegister();
     DetectTransformMd5Register();
     DetectTransformSha1Register();
     DetectTransformSha256Register();
     DetectTransformDotPrefixRegister();
     DetectTransformPcrexformRegister();
+    DetectTransformRegexReplaceRegister();
     DetectTransformUrlDecodeRegister();
     DetectTransformXorRegister();
+    DetectTransformReverseRegister();
     DetectTransformToLowerRegister();
     DetectTransformToUpperRegister();
     DetectTransformHeaderLowercaseRegister();
+    DetectTransformUnfoldRegister();
+    DetectTransformNormalizeNewlinesRegister();
     DetectTransformFromBase64DecodeRegister();
+    DetectTransformFromHexRegister();
     SCDetectTransformDomainRegister();
     DetectTransformLuaxformRegister();
 
@@ -757,6 +773,8 @@ void SigTableSetup(void)
 
     DetectVlanIdRegister();
     DetectVlanLayersRegister();
+    DetectVlanPcpRegister();
+    DetectVlanHasTagRegister();
 
     DetectEmailRegister();
 
@@ -768,6 +786,8 @@ void SigTableSetup(void)
     SCDetectRfbRegister();
     SCDetectSipRegister();
     SCDetectTemplateRegister();
+    SCDetectUriRegister();
+    SCDetectTlsSniLowerRegister();
     SCDetectLdapRegister();
     SCDetectSdpRegister();
     SCDetectDNSRegister();
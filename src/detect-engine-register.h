@@ -83,6 +83,7 @@ enum DetectKeywordId {
     DETECT_BYTEJUMP,
     DETECT_BYTEMATH,
     DETECT_BYTE_EXTRACT,
+    DETECT_BYTE_VAR,
     DETECT_DATASET,
     DETECT_DATAREP,
     DETECT_BASE64_DECODE,
@@ -132,6 +133,8 @@ enum DetectKeywordId {
     DETECT_FLOW_BYTES,
     DETECT_FLOW_BYTES_TO_SERVER,
     DETECT_FLOW_BYTES_TO_CLIENT,
+    DETECT_FLOW_BIT,
+    DETECT_FLOW_BUFFER,
 
     DETECT_REQUIRES,
 
@@ -325,6 +328,8 @@ enum DetectKeywordId {
 
     DETECT_VLAN_ID,
     DETECT_VLAN_LAYERS,
+    DETECT_VLAN_PCP,
+    DETECT_VLAN_HAS_TAG,
 
     /* make sure this stays last */
     DETECT_TBLSIZE_STATIC,
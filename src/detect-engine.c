@@ -1129,6 +1129,8 @@ static void DetectBufferTypeFreeFunc(void *data)
 
     /* Release transformation option memory, if any */
     for (int i = 0; i < map->transforms.cnt; i++) {
+        if (map->transforms.transforms[i].optstr != NULL)
+            SCFree(map->transforms.transforms[i].optstr);
         if (map->transforms.transforms[i].options == NULL)
             continue;
 
@@ -1672,6 +1674,13 @@ int DetectEngineBufferTypeGetByIdTransforms(
 
     SCLogDebug("res %p", res);
     if (res != NULL) {
+        /* the existing buffer keeps its own copy of the option strings */
+        for (int i = 0; i < transform_cnt; i++) {
+            if (transforms[i].optstr != NULL) {
+                SCFree(transforms[i].optstr);
+                transforms[i].optstr = NULL;
+            }
+        }
         return res->id;
     }
 
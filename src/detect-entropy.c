@@ -21,11 +21,16 @@
 #include "detect-parse.h"
 #include "detect-engine.h"
 #include "detect-engine-buffer.h"
+#include "detect-flowvar.h"
 
 #include "detect-entropy.h"
 
 #include "rust.h"
 
+#ifdef UNITTESTS
+static void DetectEntropyRegisterTests(void);
+#endif
+
 static int DetectEntropySetup(DetectEngineCtx *de_ctx, Signature *s, const char *arg)
 {
     DetectEntropyData *ded = SCDetectEntropyParse(arg);
@@ -60,10 +65,47 @@ static void DetectEntropyFree(DetectEngineCtx *de_ctx, void *ptr)
     SCDetectEntropyFree(ptr);
 }
 
+/** \brief store the histogram of the inspected bytes as a pktvar, logged with
+ *         the alert once the whole signature matched */
+static void DetectEntropyStoreHistogram(DetectEngineThreadCtx *det_ctx,
+        const DetectEntropyData *ded, const uint8_t *buffer, const uint32_t buffer_len)
+{
+    SCJsonBuilder *js = SCJbNewArray();
+    if (js == NULL)
+        return;
+    if (!SCDetectEntropyHistogramToJson(buffer, buffer_len, ded, js) || !SCJbClose(js))
+        goto end;
+
+    const size_t len = SCJbLen(js);
+    if (len > UINT16_MAX)
+        goto end;
+    uint8_t *key = (uint8_t *)SCStrdup("entropy_histogram");
+    uint8_t *value = SCMalloc(len);
+    if (unlikely(key == NULL || value == NULL)) {
+        SCFree(key);
+        SCFree(value);
+        goto end;
+    }
+    memcpy(value, SCJbPtr(js), len);
+    if (DetectVarStoreMatchKeyValue(det_ctx, key, (uint16_t)strlen((char *)key), value,
+                (uint16_t)len, DETECT_VAR_TYPE_PKT_POSTMATCH) != 0) {
+        SCFree(key);
+        SCFree(value);
+    }
+end:
+    SCJbFree(js);
+}
+
 bool DetectEntropyDoMatch(DetectEngineThreadCtx *det_ctx, const Signature *s,
         const SigMatchCtx *ctx, const uint8_t *buffer, const uint32_t buffer_len)
 {
-    return SCDetectEntropyMatch(buffer, buffer_len, (const DetectEntropyData *)ctx);
+    const DetectEntropyData *ded = (const DetectEntropyData *)ctx;
+    if (!SCDetectEntropyMatch(buffer, buffer_len, ded))
+        return false;
+
+    if (SCDetectEntropyLogsHistogram(ded))
+        DetectEntropyStoreHistogram(det_ctx, ded, buffer, buffer_len);
+    return true;
 }
 
 void DetectEntropyRegister(void)
@@ -73,4 +115,11 @@ void DetectEntropyRegister(void)
     sigmatch_table[DETECT_ENTROPY].url = "/rules/payload-keywords.html#entropy";
     sigmatch_table[DETECT_ENTROPY].Free = DetectEntropyFree;
     sigmatch_table[DETECT_ENTROPY].Setup = DetectEntropySetup;
+#ifdef UNITTESTS
+    sigmatch_table[DETECT_ENTROPY].RegisterTests = DetectEntropyRegisterTests;
+#endif
 }
+
+#ifdef UNITTESTS
+#include "tests/detect-entropy.c"
+#endif
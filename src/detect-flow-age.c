@@ -29,13 +29,9 @@ static int DetectFlowAgeMatch(
     if (p->flow == NULL) {
         return 0;
     }
-    uint64_t age = SCTIME_SECS(p->flow->lastts) - SCTIME_SECS(p->flow->startts);
-    if (age > UINT32_MAX) {
-        age = UINT32_MAX;
-    }
-
     const DetectU32Data *du32 = (const DetectU32Data *)ctx;
-    return DetectU32Match((uint32_t)age, du32);
+    const uint64_t start = SCTIME_SECS(p->flow->startts);
+    return SCDetectFlowAgeMatch(du32, start, SCTIME_SECS(p->flow->lastts)) ? 1 : 0;
 }
 
 static void DetectFlowAgeFree(DetectEngineCtx *de_ctx, void *ptr)
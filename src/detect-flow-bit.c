@@ -0,0 +1,181 @@
+/* Copyright (C) 2026 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+#include "suricata-common.h"
+#include "rust.h"
+#include "action-globals.h"
+#include "detect-flow-bit.h"
+#include "detect-engine.h"
+#include "detect-parse.h"
+#include "flow-storage.h"
+#include "util-unittest.h"
+#include "util-unittest-helper.h"
+
+/** flow storage holding the bits set on a flow, freed with the flow so
+ *  the bits don't outlive it */
+static FlowStorageId g_flow_bit_id = { .id = -1 };
+
+#ifdef UNITTESTS
+static void DetectFlowBitRegisterTests(void);
+#endif
+
+static int DetectFlowBitMatch(
+        DetectEngineThreadCtx *det_ctx, Packet *p, const Signature *s, const SigMatchCtx *ctx)
+{
+    if (p->flow == NULL) {
+        return 0;
+    }
+    DetectFlowBitState *state = FlowGetStorageById(p->flow, g_flow_bit_id);
+    DetectFlowBitState *cur = state;
+    const bool r = SCDetectFlowBitMatch((const DetectFlowBit *)ctx, &cur);
+    if (cur != state) {
+        FlowSetStorageById(p->flow, g_flow_bit_id, cur);
+    }
+    return r ? 1 : 0;
+}
+
+static void DetectFlowBitFree(DetectEngineCtx *de_ctx, void *ptr)
+{
+    SCDetectFlowBitFree(ptr);
+}
+
+static int DetectFlowBitSetup(DetectEngineCtx *de_ctx, Signature *s, const char *rawstr)
+{
+    DetectFlowBit *fb = SCDetectFlowBitParse(rawstr);
+    if (fb == NULL)
+        return -1;
+
+    int list = DETECT_SM_LIST_MATCH;
+    switch (SCDetectFlowBitCmd(fb)) {
+        case DETECT_FLOW_BIT_NOALERT:
+            SCDetectFlowBitFree(fb);
+            s->action &= ~ACTION_ALERT;
+            return 0;
+        case DETECT_FLOW_BIT_SET:
+            /* only set the bit once the entire sig has matched */
+            list = DETECT_SM_LIST_POSTMATCH;
+            break;
+        case DETECT_FLOW_BIT_ISSET:
+            break;
+    }
+
+    if (SCSigMatchAppendSMToList(de_ctx, s, DETECT_FLOW_BIT, (SigMatchCtx *)fb, list) == NULL) {
+        DetectFlowBitFree(de_ctx, fb);
+        return -1;
+    }
+    s->flags |= SIG_FLAG_REQUIRE_PACKET;
+
+    return 0;
+}
+
+void DetectFlowBitRegister(void)
+{
+    sigmatch_table[DETECT_FLOW_BIT].name = "flow.bit";
+    sigmatch_table[DETECT_FLOW_BIT].desc = "set or check a per-flow named bit";
+    sigmatch_table[DETECT_FLOW_BIT].url = "/rules/flow-keywords.html#flow-bit";
+    sigmatch_table[DETECT_FLOW_BIT].Match = DetectFlowBitMatch;
+    sigmatch_table[DETECT_FLOW_BIT].Setup = DetectFlowBitSetup;
+    sigmatch_table[DETECT_FLOW_BIT].Free = DetectFlowBitFree;
+#ifdef UNITTESTS
+    sigmatch_table[DETECT_FLOW_BIT].RegisterTests = DetectFlowBitRegisterTests;
+#endif
+
+    if (g_flow_bit_id.id == -1) {
+        g_flow_bit_id = FlowStorageRegister(
+                "flow.bit", sizeof(void *), NULL, SCDetectFlowBitStateFree);
+        if (g_flow_bit_id.id == -1) {
+            FatalError("Can't initiate flow storage for flow.bit");
+        }
+    }
+}
+
+#ifdef UNITTESTS
+#include "detect-engine-alert.h"
+#include "detect-engine-build.h"
+#include "flow-util.h"
+
+/** \test bit set by a packet is seen by a later packet of the same flow,
+ *        but not once the flow was cleared */
+static int DetectFlowBitTest01(void)
+{
+    ThreadVars th_v;
+    DetectEngineThreadCtx *det_ctx = NULL;
+    memset(&th_v, 0, sizeof(th_v));
+
+    FlowInitConfig(FLOW_QUIET);
+    Flow *f = FlowAlloc();
+    FAIL_IF_NULL(f);
+    FLOW_INITIALIZE(f);
+    f->proto = IPPROTO_TCP;
+
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+    FAIL_IF_NULL(de_ctx);
+    de_ctx->flags |= DE_QUIET;
+
+    Signature *s = DetectEngineAppendSig(de_ctx,
+            "alert tcp any any -> any any (content:\"login\"; "
+            "flow.bit:set,login; flow.bit:noalert; sid:1;)");
+    FAIL_IF_NULL(s);
+    FAIL_IF(s->action & ACTION_ALERT);
+    s = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (content:\"data\"; "
+                                      "flow.bit:isset,login; sid:2;)");
+    FAIL_IF_NULL(s);
+    FAIL_IF_NOT_NULL(DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any "
+                                                   "(flow.bit:set; sid:3;)"));
+
+    SigGroupBuild(de_ctx);
+    DetectEngineThreadCtxInit(&th_v, (void *)de_ctx, (void *)&det_ctx);
+
+    const char *payloads[] = { "data", "login", "data" };
+    const bool alerts[] = { false, false, true };
+    for (int i = 0; i < 3; i++) {
+        Packet *p = UTHBuildPacket((uint8_t *)payloads[i], strlen(payloads[i]), IPPROTO_TCP);
+        FAIL_IF_NULL(p);
+        UTHAssignFlow(p, f);
+        p->flowflags |= FLOW_PKT_TOSERVER;
+        SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+        FAIL_IF(PacketAlertCheck(p, 1));
+        FAIL_IF((PacketAlertCheck(p, 2) != 0) != alerts[i]);
+        UTHFreePacket(p);
+    }
+    FAIL_IF_NULL(FlowGetStorageById(f, g_flow_bit_id));
+
+    /* flow end: the bit goes away with the flow storage */
+    FlowClearMemory(f, FlowGetProtoMapping(f->proto));
+    FAIL_IF_NOT_NULL(FlowGetStorageById(f, g_flow_bit_id));
+    Packet *p = UTHBuildPacket((uint8_t *)"data", 4, IPPROTO_TCP);
+    FAIL_IF_NULL(p);
+    UTHAssignFlow(p, f);
+    p->flowflags |= FLOW_PKT_TOSERVER;
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+    FAIL_IF(PacketAlertCheck(p, 2));
+    UTHFreePacket(p);
+
+    DetectEngineThreadCtxDeinit(&th_v, (void *)det_ctx);
+    DetectEngineCtxFree(de_ctx);
+    FlowClearMemory(f, FlowGetProtoMapping(f->proto));
+    FLOW_DESTROY(f);
+    FlowFree(f);
+    FlowShutdown();
+    PASS;
+}
+
+static void DetectFlowBitRegisterTests(void)
+{
+    UtRegisterTest("DetectFlowBitTest01", DetectFlowBitTest01);
+}
+#endif /* UNITTESTS */
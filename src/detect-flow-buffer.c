@@ -0,0 +1,186 @@
+/* Copyright (C) 2026 Open Information Security Foundation
+ *
+ * You can copy, redistribute or modify this Program under the terms of
+ * the GNU General Public License version 2 as published by the Free
+ * Software Foundation.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * version 2 along with this program; if not, write to the Free Software
+ * Foundation, Inc., 51 Franklin Street, Fifth Floor, Boston, MA
+ * 02110-1301, USA.
+ */
+
+#include "suricata-common.h"
+#include "rust.h"
+#include "detect-flow-buffer.h"
+#include "detect-flow.h"
+#include "detect-engine.h"
+#include "detect-parse.h"
+#include "util-unittest.h"
+#include "util-unittest-helper.h"
+
+#ifdef UNITTESTS
+static void DetectFlowBufferRegisterTests(void);
+#endif
+
+/**
+ * \brief select the payload of a direction for the content keywords that
+ *        follow, like pkt_data does for both directions
+ *
+ * A signature inspects the packets of a single direction, so this sets the
+ * direction of the whole signature and fails if it already has the other.
+ * As that direction would also apply to the payload and buffer keywords
+ * before it, it must come first.
+ */
+static int DetectFlowBufferSetup(DetectEngineCtx *de_ctx, Signature *s, const char *rawstr)
+{
+    uint8_t dir = SCDetectFlowBufferParse(rawstr);
+    if (dir == 0) {
+        SCLogError("invalid flow.buffer option \"%s\", expected to_server or to_client", rawstr);
+        return -1;
+    }
+    if (s->init_data->transforms.cnt) {
+        SCLogError("previous transforms not consumed before 'flow.buffer'");
+        return -1;
+    }
+    if (s->init_data->smlists[DETECT_SM_LIST_PMATCH] != NULL || s->init_data->buffer_index > 0) {
+        SCLogError("rule %u: flow.buffer must come before the payload and buffer keywords",
+                s->id);
+        return -1;
+    }
+
+    const uint32_t flags = dir == DETECT_FLOW_TOSERVER ? SIG_FLAG_TOSERVER : SIG_FLAG_TOCLIENT;
+    if (DetectFlowSetupImplicit(s, flags) < 0) {
+        SCLogError("rule %u: flow.buffer:%s conflicts with the direction of the rule", s->id,
+                dir == DETECT_FLOW_TOSERVER ? "to_server" : "to_client");
+        return -1;
+    }
+    s->init_data->list = DETECT_SM_LIST_NOTSET;
+
+    return 0;
+}
+
+void DetectFlowBufferRegister(void)
+{
+    sigmatch_table[DETECT_FLOW_BUFFER].name = "flow.buffer";
+    sigmatch_table[DETECT_FLOW_BUFFER].desc =
+            "inspect the payload of one direction with the following content keywords";
+    sigmatch_table[DETECT_FLOW_BUFFER].url = "/rules/flow-keywords.html#flow-buffer";
+    sigmatch_table[DETECT_FLOW_BUFFER].Setup = DetectFlowBufferSetup;
+#ifdef UNITTESTS
+    sigmatch_table[DETECT_FLOW_BUFFER].RegisterTests = DetectFlowBufferRegisterTests;
+#endif
+}
+
+#ifdef UNITTESTS
+#include "detect-engine-alert.h"
+#include "detect-engine-build.h"
+#include "flow-util.h"
+
+/** \test options and direction conflicts */
+static int DetectFlowBufferTest01(void)
+{
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+    FAIL_IF_NULL(de_ctx);
+    de_ctx->flags |= DE_QUIET;
+
+    Signature *s = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any "
+                                                 "(flow.buffer:to_server; content:\"a\"; sid:1;)");
+    FAIL_IF_NULL(s);
+    FAIL_IF_NOT(s->flags & SIG_FLAG_TOSERVER);
+    FAIL_IF(s->flags & SIG_FLAG_TOCLIENT);
+    s = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (flow:established; "
+                                      "flow.buffer:to_client; content:\"a\"; sid:2;)");
+    FAIL_IF_NULL(s);
+    FAIL_IF_NOT(s->flags & SIG_FLAG_TOCLIENT);
+    FAIL_IF(s->flags & SIG_FLAG_TOSERVER);
+    s = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (flow.buffer:to_server; "
+                                      "content:\"a\"; content:\"b\"; sid:3;)");
+    FAIL_IF_NULL(s);
+    FAIL_IF_NOT(s->flags & SIG_FLAG_TOSERVER);
+
+    const char *invalid[] = {
+        "(flow.buffer; content:\"a\"; sid:10;)",
+        "(flow.buffer:either; content:\"a\"; sid:11;)",
+        "(flow:to_client; flow.buffer:to_server; content:\"a\"; sid:12;)",
+        "(flow.buffer:to_server; flow:to_client; content:\"a\"; sid:13;)",
+        "(flow.buffer:to_server; content:\"a\"; flow.buffer:to_client; content:\"b\"; sid:14;)",
+        "(http.uri; to_lowercase; flow.buffer:to_server; content:\"a\"; sid:15;)",
+        "(content:\"a\"; flow.buffer:to_server; content:\"b\"; sid:16;)",
+        "(http.uri; content:\"a\"; flow.buffer:to_server; content:\"b\"; sid:17;)",
+    };
+    char sig[256];
+    for (size_t i = 0; i < ARRAY_SIZE(invalid); i++) {
+        snprintf(sig, sizeof(sig), "alert tcp any any -> any any %s", invalid[i]);
+        FAIL_IF_NOT_NULL(DetectEngineAppendSig(de_ctx, sig));
+    }
+
+    DetectEngineCtxFree(de_ctx);
+    PASS;
+}
+
+/** \test same payload in both directions of a flow only matches the rule of
+ *        the selected direction, for tcp and udp */
+static int DetectFlowBufferTest02(void)
+{
+    ThreadVars th_v;
+    DetectEngineThreadCtx *det_ctx = NULL;
+    memset(&th_v, 0, sizeof(th_v));
+
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+    FAIL_IF_NULL(de_ctx);
+    de_ctx->flags |= DE_QUIET;
+
+    FAIL_IF_NULL(DetectEngineAppendSig(de_ctx,
+            "alert ip any any -> any any (flow.buffer:to_server; content:\"ping\"; sid:1;)"));
+    FAIL_IF_NULL(DetectEngineAppendSig(de_ctx,
+            "alert ip any any -> any any (flow.buffer:to_client; content:\"ping\"; sid:2;)"));
+    SigGroupBuild(de_ctx);
+    DetectEngineThreadCtxInit(&th_v, (void *)de_ctx, (void *)&det_ctx);
+
+    FlowInitConfig(FLOW_QUIET);
+    const uint8_t protos[] = { IPPROTO_TCP, IPPROTO_UDP };
+    for (size_t i = 0; i < ARRAY_SIZE(protos); i++) {
+        Flow *f = UTHBuildFlow(AF_INET, "1.1.1.1", "2.2.2.2", 1024, 80);
+        FAIL_IF_NULL(f);
+        f->proto = protos[i];
+
+        Packet *p1 = UTHBuildPacket((uint8_t *)"ping", 4, protos[i]);
+        FAIL_IF_NULL(p1);
+        UTHAssignFlow(p1, f);
+        p1->flowflags |= FLOW_PKT_TOSERVER;
+        Packet *p2 = UTHBuildPacketReal(
+                (uint8_t *)"ping", 4, protos[i], "2.2.2.2", "1.1.1.1", 80, 1024);
+        FAIL_IF_NULL(p2);
+        UTHAssignFlow(p2, f);
+        p2->flowflags |= FLOW_PKT_TOCLIENT;
+
+        SigMatchSignatures(&th_v, de_ctx, det_ctx, p1);
+        FAIL_IF_NOT(PacketAlertCheck(p1, 1));
+        FAIL_IF(PacketAlertCheck(p1, 2));
+        SigMatchSignatures(&th_v, de_ctx, det_ctx, p2);
+        FAIL_IF(PacketAlertCheck(p2, 1));
+        FAIL_IF_NOT(PacketAlertCheck(p2, 2));
+
+        UTHFreePacket(p1);
+        UTHFreePacket(p2);
+        UTHFreeFlow(f);
+    }
+
+    DetectEngineThreadCtxDeinit(&th_v, (void *)det_ctx);
+    DetectEngineCtxFree(de_ctx);
+    FlowShutdown();
+    PASS;
+}
+
+static void DetectFlowBufferRegisterTests(void)
+{
+    UtRegisterTest("DetectFlowBufferTest01", DetectFlowBufferTest01);
+    UtRegisterTest("DetectFlowBufferTest02", DetectFlowBufferTest02);
+}
+#endif /* UNITTESTS */
@@ -31,17 +31,7 @@ static int DetectFlowPktsMatch(
     }
 
     const DetectFlowPkts *df = (const DetectFlowPkts *)ctx;
-    if (df->dir == DETECT_FLOW_TOSERVER) {
-        return DetectU32Match(p->flow->todstpktcnt, &df->pkt_data);
-    } else if (df->dir == DETECT_FLOW_TOCLIENT) {
-        return DetectU32Match(p->flow->tosrcpktcnt, &df->pkt_data);
-    } else if (df->dir == DETECT_FLOW_TOEITHER) {
-        if (DetectU32Match(p->flow->tosrcpktcnt, &df->pkt_data)) {
-            return 1;
-        }
-        return DetectU32Match(p->flow->todstpktcnt, &df->pkt_data);
-    }
-    return 0;
+    return SCDetectFlowPktsMatch(df, p->flow->todstpktcnt, p->flow->tosrcpktcnt) ? 1 : 0;
 }
 
 static void DetectFlowPktsFree(DetectEngineCtx *de_ctx, void *ptr)
@@ -198,17 +188,7 @@ static int DetectFlowBytesMatch(
     }
 
     const DetectFlowBytes *df = (const DetectFlowBytes *)ctx;
-    if (df->dir == DETECT_FLOW_TOSERVER) {
-        return DetectU64Match(p->flow->todstbytecnt, &df->byte_data);
-    } else if (df->dir == DETECT_FLOW_TOCLIENT) {
-        return DetectU64Match(p->flow->tosrcbytecnt, &df->byte_data);
-    } else if (df->dir == DETECT_FLOW_TOEITHER) {
-        if (DetectU64Match(p->flow->tosrcbytecnt, &df->byte_data)) {
-            return 1;
-        }
-        return DetectU64Match(p->flow->todstbytecnt, &df->byte_data);
-    }
-    return 0;
+    return SCDetectFlowBytesMatch(df, p->flow->todstbytecnt, p->flow->tosrcbytecnt) ? 1 : 0;
 }
 
 static void DetectFlowBytesFree(DetectEngineCtx *de_ctx, void *ptr)
@@ -34,6 +34,7 @@
 
 #include "flow.h"
 #include "flow-var.h"
+#include "stream-tcp-private.h"
 
 #include "detect-flow.h"
 
@@ -44,7 +45,8 @@
 /**
  * \brief Regex for parsing our flow options
  */
-#define PARSE_REGEX  "^\\s*([A-z_]+)\\s*(?:,\\s*([A-z_]+))?\\s*(?:,\\s*([A-z_]+))?\\s*$"
+#define PARSE_REGEX                                                                                \
+    "^\\s*([^,]+?)\\s*(?:,\\s*([^,]+?))?\\s*(?:,\\s*([^,]+?))?\\s*(?:,\\s*([^,]+?))?\\s*$"
 
 static DetectParseRegex parse_regex;
 
@@ -80,40 +82,34 @@ void DetectFlowRegister (void)
     DetectSetupParseRegexes(PARSE_REGEX, &parse_regex);
 }
 
+/**
+ * \brief check if the stream engine recorded a gap in either direction of a tcp flow
+ */
+static bool FlowStreamHasGap(const Flow *f)
+{
+    if (f == NULL || f->proto != IPPROTO_TCP)
+        return false;
+    const TcpSession *ssn = (const TcpSession *)f->protoctx;
+    if (ssn == NULL)
+        return false;
+    return ((ssn->client.flags | ssn->server.flags) & STREAMTCP_STREAM_FLAG_HAS_GAP) != 0;
+}
+
 /**
  * \param pflags packet flags (p->flags)
  * \param pflowflags packet flow flags (p->flowflags)
  * \param dflags detect flow flags
  * \param match_cnt number of matches to trigger
+ * \param f flow of the packet, only used for has_gaps
  */
 static inline int FlowMatch(const uint32_t pflags, const uint8_t pflowflags, const uint16_t dflags,
-        const uint16_t match_cnt)
+        const uint16_t match_cnt, const Flow *f)
 {
-    uint8_t cnt = 0;
-
-    if ((dflags & DETECT_FLOW_FLAG_NO_FRAG) &&
-        (!(pflags & PKT_REBUILT_FRAGMENT))) {
-        cnt++;
-    } else if ((dflags & DETECT_FLOW_FLAG_ONLY_FRAG) &&
-        (pflags & PKT_REBUILT_FRAGMENT)) {
-        cnt++;
-    }
-
-    if ((dflags & DETECT_FLOW_FLAG_TOSERVER) && (pflowflags & FLOW_PKT_TOSERVER)) {
-        cnt++;
-    } else if ((dflags & DETECT_FLOW_FLAG_TOCLIENT) && (pflowflags & FLOW_PKT_TOCLIENT)) {
-        cnt++;
-    }
-
-    if ((dflags & DETECT_FLOW_FLAG_ESTABLISHED) && (pflowflags & FLOW_PKT_ESTABLISHED)) {
-        cnt++;
-    } else if (dflags & DETECT_FLOW_FLAG_NOT_ESTABLISHED && (!(pflowflags & FLOW_PKT_ESTABLISHED))) {
-        cnt++;
-    } else if (dflags & DETECT_FLOW_FLAG_STATELESS) {
-        cnt++;
-    }
-
-    return (match_cnt == cnt) ? 1 : 0;
+    const bool rebuilt_fragment = (pflags & PKT_REBUILT_FRAGMENT) != 0;
+    const bool stream_gap = (dflags & DETECT_FLOW_FLAG_HAS_GAPS) && FlowStreamHasGap(f);
+    const bool match =
+            SCDetectFlowMatchFlags(dflags, match_cnt, pflowflags, rebuilt_fragment, stream_gap);
+    return match ? 1 : 0;
 }
 
 /**
@@ -146,7 +142,7 @@ int DetectFlowMatch (DetectEngineThreadCtx *det_ctx, Packet *p,
 
     const DetectFlowData *fd = (const DetectFlowData *)ctx;
 
-    const int ret = FlowMatch(p->flags, p->flowflags, fd->flags, fd->match_cnt);
+    const int ret = FlowMatch(p->flags, p->flowflags, fd->flags, fd->match_cnt, p->flow);
     SCLogDebug("returning %" PRId32 " fd->match_cnt %" PRId32 " fd->flags 0x%02X p->flowflags 0x%02X",
         ret, fd->match_cnt, fd->flags, p->flowflags);
     SCReturnInt(ret);
@@ -166,14 +162,14 @@ static DetectFlowData *DetectFlowParse(
         DetectEngineCtx *de_ctx, const char *flowstr, uint16_t *parse_flags)
 {
     DetectFlowData *fd = NULL;
-    char *args[3] = {NULL,NULL,NULL};
+    char *args[4] = { NULL, NULL, NULL, NULL };
     int res = 0;
     size_t pcre2len;
-    char str1[16] = "", str2[16] = "", str3[16] = "";
+    char str1[32] = "", str2[32] = "", str3[32] = "", str4[32] = "";
     pcre2_match_data *match = NULL;
 
     int ret = DetectParsePcreExec(&parse_regex, &match, flowstr, 0, 0);
-    if (ret < 1 || ret > 4) {
+    if (ret < 1 || ret > 5) {
         SCLogError("parse error, ret %" PRId32 ", string %s", ret, flowstr);
         goto error;
     }
@@ -205,6 +201,15 @@ static DetectFlowData *DetectFlowParse(
             }
             args[2] = (char *)str3;
         }
+        if (ret > 4) {
+            pcre2len = sizeof(str4);
+            res = pcre2_substring_copy_bynumber(match, 4, (PCRE2_UCHAR8 *)str4, &pcre2len);
+            if (res < 0) {
+                SCLogError("pcre2_substring_copy_bynumber failed");
+                goto error;
+            }
+            args[3] = (char *)str4;
+        }
     }
 
     fd = SCMalloc(sizeof(DetectFlowData));
@@ -212,6 +217,9 @@ static DetectFlowData *DetectFlowParse(
         goto error;
     fd->flags = 0;
     fd->match_cnt = 0;
+    fd->pkts = NULL;
+    fd->bytes = NULL;
+    fd->age = NULL;
 
     for (int i = 0; i < (ret - 1); i++) {
         if (args[i]) {
@@ -272,6 +280,13 @@ static DetectFlowData *DetectFlowParse(
                 }
                 fd->flags |= DETECT_FLOW_FLAG_TOSERVER;
                 fd->match_cnt++;
+            } else if (strcasecmp(args[i], "has_gaps") == 0) {
+                if (fd->flags & DETECT_FLOW_FLAG_HAS_GAPS) {
+                    SCLogError("cannot set has_gaps flag is already set");
+                    goto error;
+                }
+                fd->flags |= DETECT_FLOW_FLAG_HAS_GAPS;
+                fd->match_cnt++;
             } else if (strcasecmp(args[i], "no_frag") == 0) {
                 if (fd->flags & DETECT_FLOW_FLAG_NO_FRAG) {
                     SCLogError("cannot set no_frag flag is already set");
@@ -315,12 +330,61 @@ static DetectFlowData *DetectFlowParse(
                     goto error;
                 }
                 *parse_flags |= DETECT_FLOW_FLAG_NOSTREAM;
+
+                /* counters, turned into flow.pkts and flow.bytes matches */
+
+            } else if (strncasecmp(args[i], "packets", 7) == 0) {
+                if (fd->pkts != NULL) {
+                    SCLogError("cannot set packets option, it is already set");
+                    goto error;
+                }
+                fd->pkts = SCDetectFlowPktsParseOption(args[i]);
+                if (fd->pkts == NULL) {
+                    SCLogError("invalid flow packets option \"%s\"", args[i]);
+                    goto error;
+                }
+            } else if (strncasecmp(args[i], "bytes", 5) == 0) {
+                if (fd->bytes != NULL) {
+                    SCLogError("cannot set bytes option, it is already set");
+                    goto error;
+                }
+                fd->bytes = SCDetectFlowBytesParseOption(args[i]);
+                if (fd->bytes == NULL) {
+                    SCLogError("invalid flow bytes option \"%s\"", args[i]);
+                    goto error;
+                }
+            } else if (strncasecmp(args[i], "age", 3) == 0) {
+                if (fd->age != NULL) {
+                    SCLogError("cannot set age option, it is already set");
+                    goto error;
+                }
+                fd->age = SCDetectFlowAgeParseOption(args[i]);
+                if (fd->age == NULL) {
+                    SCLogError("invalid flow age option \"%s\", expected a number of seconds",
+                            args[i]);
+                    goto error;
+                }
             } else {
                 SCLogError("invalid flow option \"%s\"", args[i]);
                 goto error;
             }
         }
     }
+
+    /* the counters apply to the direction the flow keyword matches on */
+    DetectFlowDir dir = DETECT_FLOW_TOEITHER;
+    if (fd->flags & DETECT_FLOW_FLAG_TOSERVER) {
+        dir = DETECT_FLOW_TOSERVER;
+    } else if (fd->flags & DETECT_FLOW_FLAG_TOCLIENT) {
+        dir = DETECT_FLOW_TOCLIENT;
+    }
+    if (fd->pkts != NULL) {
+        fd->pkts->dir = dir;
+    }
+    if (fd->bytes != NULL) {
+        fd->bytes->dir = dir;
+    }
+
     pcre2_match_data_free(match);
     return fd;
 
@@ -429,6 +493,31 @@ int DetectFlowSetup (DetectEngineCtx *de_ctx, Signature *s, const char *flowstr)
         s->init_data->init_flags |= SIG_FLAG_INIT_FLOW;
     }
 
+    if (fd->pkts != NULL) {
+        if (SCSigMatchAppendSMToList(de_ctx, s, DETECT_FLOW_PKTS, (SigMatchCtx *)fd->pkts,
+                    DETECT_SM_LIST_MATCH) == NULL) {
+            goto error;
+        }
+        fd->pkts = NULL;
+        s->flags |= SIG_FLAG_REQUIRE_PACKET;
+    }
+    if (fd->bytes != NULL) {
+        if (SCSigMatchAppendSMToList(de_ctx, s, DETECT_FLOW_BYTES, (SigMatchCtx *)fd->bytes,
+                    DETECT_SM_LIST_MATCH) == NULL) {
+            goto error;
+        }
+        fd->bytes = NULL;
+        s->flags |= SIG_FLAG_REQUIRE_PACKET;
+    }
+    if (fd->age != NULL) {
+        if (SCSigMatchAppendSMToList(de_ctx, s, DETECT_FLOW_AGE, (SigMatchCtx *)fd->age,
+                    DETECT_SM_LIST_MATCH) == NULL) {
+            goto error;
+        }
+        fd->age = NULL;
+        s->flags |= SIG_FLAG_REQUIRE_PACKET;
+    }
+
     if (appendsm) {
         if (SCSigMatchAppendSMToList(
                     de_ctx, s, DETECT_FLOW, (SigMatchCtx *)fd, DETECT_SM_LIST_MATCH) == NULL) {
@@ -461,6 +550,15 @@ error:
 void DetectFlowFree(DetectEngineCtx *de_ctx, void *ptr)
 {
     DetectFlowData *fd = (DetectFlowData *)ptr;
+    if (fd->pkts != NULL) {
+        SCDetectFlowPktsFree(fd->pkts);
+    }
+    if (fd->bytes != NULL) {
+        SCDetectFlowBytesFree(fd->bytes);
+    }
+    if (fd->age != NULL) {
+        SCDetectU32Free(fd->age);
+    }
     SCFree(fd);
 }
 
@@ -474,7 +572,7 @@ PrefilterPacketFlowMatch(DetectEngineThreadCtx *det_ctx, Packet *p, const void *
     if (!PrefilterPacketHeaderExtraMatch(ctx, p))
         return;
 
-    if (FlowMatch(p->flags, p->flowflags, ctx->v1.u16[0], ctx->v1.u16[1])) {
+    if (FlowMatch(p->flags, p->flowflags, ctx->v1.u16[0], ctx->v1.u16[1], p->flow)) {
         SCLogDebug("match: adding sids");
         PrefilterAddSids(&det_ctx->pmq, ctx->sigs_array, ctx->sigs_cnt);
     }
@@ -1086,9 +1184,9 @@ static int DetectFlowTestNoFragMatch(void)
     FAIL_IF_NULL(fd);
     FAIL_IF_NOT(fd->flags & DETECT_FLOW_FLAG_NO_FRAG);
     FAIL_IF_NOT(fd->match_cnt == 1);
-    FAIL_IF_NOT(FlowMatch(pflags, 0, fd->flags, fd->match_cnt));
+    FAIL_IF_NOT(FlowMatch(pflags, 0, fd->flags, fd->match_cnt, NULL));
     pflags |= PKT_REBUILT_FRAGMENT;
-    FAIL_IF(FlowMatch(pflags, 0, fd->flags, fd->match_cnt));
+    FAIL_IF(FlowMatch(pflags, 0, fd->flags, fd->match_cnt, NULL));
     PASS;
 }
 
@@ -1103,9 +1201,114 @@ static int DetectFlowTestOnlyFragMatch(void)
     FAIL_IF_NULL(fd);
     FAIL_IF_NOT(fd->flags & DETECT_FLOW_FLAG_ONLY_FRAG);
     FAIL_IF_NOT(fd->match_cnt == 1);
-    FAIL_IF(FlowMatch(pflags, 0, fd->flags, fd->match_cnt));
+    FAIL_IF(FlowMatch(pflags, 0, fd->flags, fd->match_cnt, NULL));
     pflags |= PKT_REBUILT_FRAGMENT;
-    FAIL_IF_NOT(FlowMatch(pflags, 0, fd->flags, fd->match_cnt));
+    FAIL_IF_NOT(FlowMatch(pflags, 0, fd->flags, fd->match_cnt, NULL));
+    PASS;
+}
+
+/**
+ * \test Test not_established matching combined with a direction over a tcp handshake.
+ */
+static int DetectFlowTestNotEstablishedMatch(void)
+{
+    uint16_t parsed_flags = 0;
+    DetectFlowData *fd = DetectFlowParse(NULL, "to_server, not_established", &parsed_flags);
+    FAIL_IF_NULL(fd);
+    FAIL_IF_NOT(fd->match_cnt == 2);
+    /* SYN */
+    FAIL_IF_NOT(FlowMatch(0, FLOW_PKT_TOSERVER, fd->flags, fd->match_cnt, NULL));
+    /* SYN/ACK */
+    FAIL_IF(FlowMatch(0, FLOW_PKT_TOCLIENT, fd->flags, fd->match_cnt, NULL));
+    /* ACK, only flagged established after the stream engine processed it */
+    FAIL_IF_NOT(FlowMatch(0, FLOW_PKT_TOSERVER, fd->flags, fd->match_cnt, NULL));
+    /* first data packet */
+    FAIL_IF(FlowMatch(
+            0, FLOW_PKT_TOSERVER | FLOW_PKT_ESTABLISHED, fd->flags, fd->match_cnt, NULL));
+    DetectFlowFree(NULL, fd);
+    PASS;
+}
+
+/**
+ * \test Test has_gaps matching on tcp flows with and without a gap and on a udp flow.
+ */
+static int DetectFlowTestHasGapsMatch(void)
+{
+    uint16_t parsed_flags = 0;
+    DetectFlowData *fd = DetectFlowParse(NULL, "established, has_gaps", &parsed_flags);
+    FAIL_IF_NULL(fd);
+    FAIL_IF_NOT(fd->flags & DETECT_FLOW_FLAG_HAS_GAPS);
+    FAIL_IF_NOT(fd->match_cnt == 2);
+    const uint8_t pflowflags = FLOW_PKT_TOSERVER | FLOW_PKT_ESTABLISHED;
+
+    TcpSession ssn;
+    memset(&ssn, 0, sizeof(ssn));
+    Flow f;
+    memset(&f, 0, sizeof(f));
+    f.proto = IPPROTO_TCP;
+    f.protoctx = &ssn;
+    FAIL_IF(FlowMatch(0, pflowflags, fd->flags, fd->match_cnt, &f));
+    ssn.server.flags |= STREAMTCP_STREAM_FLAG_HAS_GAP;
+    FAIL_IF_NOT(FlowMatch(0, pflowflags, fd->flags, fd->match_cnt, &f));
+    FAIL_IF(FlowMatch(0, FLOW_PKT_TOSERVER, fd->flags, fd->match_cnt, &f));
+
+    /* udp flows have no stream, whatever their protoctx holds */
+    f.proto = IPPROTO_UDP;
+    FAIL_IF(FlowMatch(0, pflowflags, fd->flags, fd->match_cnt, &f));
+    FAIL_IF(FlowMatch(0, pflowflags, fd->flags, fd->match_cnt, NULL));
+
+    DetectFlowFree(NULL, fd);
+    PASS;
+}
+
+/**
+ * \test Test parsing of the packets and bytes flow arguments.
+ */
+static int DetectFlowTestParseCounters(void)
+{
+    uint16_t parsed_flags = 0;
+    DetectFlowData *fd =
+            DetectFlowParse(NULL, "to_server, established, packets>100, bytes>=1500", &parsed_flags);
+    FAIL_IF_NULL(fd);
+    FAIL_IF_NOT(fd->flags == (DETECT_FLOW_FLAG_TOSERVER | DETECT_FLOW_FLAG_ESTABLISHED));
+    FAIL_IF_NOT(fd->match_cnt == 2);
+    FAIL_IF_NULL(fd->pkts);
+    FAIL_IF_NOT(fd->pkts->dir == DETECT_FLOW_TOSERVER);
+    FAIL_IF_NULL(fd->bytes);
+    FAIL_IF_NOT(fd->bytes->dir == DETECT_FLOW_TOSERVER);
+    FAIL_IF(SCDetectFlowPktsMatch(fd->pkts, 100, 200));
+    FAIL_IF_NOT(SCDetectFlowPktsMatch(fd->pkts, 101, 0));
+    DetectFlowFree(NULL, fd);
+
+    fd = DetectFlowParse(NULL, "packets<5", &parsed_flags);
+    FAIL_IF_NULL(fd);
+    FAIL_IF_NULL(fd->pkts);
+    FAIL_IF_NOT(fd->pkts->dir == DETECT_FLOW_TOEITHER);
+    DetectFlowFree(NULL, fd);
+
+    FAIL_IF_NOT_NULL(DetectFlowParse(NULL, "packets>1,packets<5", &parsed_flags));
+    FAIL_IF_NOT_NULL(DetectFlowParse(NULL, "packets>", &parsed_flags));
+    FAIL_IF_NOT_NULL(DetectFlowParse(NULL, "bytes", &parsed_flags));
+    PASS;
+}
+
+/**
+ * \test Test parsing of the age flow argument.
+ */
+static int DetectFlowTestParseAge(void)
+{
+    uint16_t parsed_flags = 0;
+    DetectFlowData *fd = DetectFlowParse(NULL, "established, age>3600", &parsed_flags);
+    FAIL_IF_NULL(fd);
+    FAIL_IF_NULL(fd->age);
+    FAIL_IF_NOT(fd->age->mode == DETECT_UINT_GT);
+    FAIL_IF_NOT(fd->age->arg1 == 3600);
+    FAIL_IF(SCDetectFlowAgeMatch(fd->age, 1000, 4600));
+    FAIL_IF_NOT(SCDetectFlowAgeMatch(fd->age, 1000, 4601));
+    DetectFlowFree(NULL, fd);
+
+    FAIL_IF_NOT_NULL(DetectFlowParse(NULL, "age>1kb", &parsed_flags));
+    FAIL_IF_NOT_NULL(DetectFlowParse(NULL, "age>1,age<5", &parsed_flags));
     PASS;
 }
 
@@ -1158,6 +1361,10 @@ static void DetectFlowRegisterTests(void)
         DetectFlowTestParseNoFragOnlyFrag);
     UtRegisterTest("DetectFlowTestNoFragMatch", DetectFlowTestNoFragMatch);
     UtRegisterTest("DetectFlowTestOnlyFragMatch", DetectFlowTestOnlyFragMatch);
+    UtRegisterTest("DetectFlowTestNotEstablishedMatch", DetectFlowTestNotEstablishedMatch);
+    UtRegisterTest("DetectFlowTestHasGapsMatch", DetectFlowTestHasGapsMatch);
+    UtRegisterTest("DetectFlowTestParseCounters", DetectFlowTestParseCounters);
+    UtRegisterTest("DetectFlowTestParseAge", DetectFlowTestParseAge);
 
     UtRegisterTest("DetectFlowSigTest01", DetectFlowSigTest01);
 }
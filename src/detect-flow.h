@@ -24,19 +24,16 @@
 #ifndef SURICATA_DETECT_FLOW_H
 #define SURICATA_DETECT_FLOW_H
 
-#define DETECT_FLOW_FLAG_TOSERVER        BIT_U16(0)
-#define DETECT_FLOW_FLAG_TOCLIENT        BIT_U16(1)
-#define DETECT_FLOW_FLAG_ESTABLISHED     BIT_U16(2)
-#define DETECT_FLOW_FLAG_NOT_ESTABLISHED BIT_U16(3)
-#define DETECT_FLOW_FLAG_STATELESS       BIT_U16(4)
-#define DETECT_FLOW_FLAG_ONLYSTREAM      BIT_U16(5)
-#define DETECT_FLOW_FLAG_NOSTREAM        BIT_U16(6)
-#define DETECT_FLOW_FLAG_NO_FRAG         BIT_U16(7)
-#define DETECT_FLOW_FLAG_ONLY_FRAG       BIT_U16(8)
+#include "rust.h"
 
 typedef struct DetectFlowData_ {
     uint16_t flags;     /* flags to match */
     uint8_t match_cnt;  /* number of matches we need */
+    /* packets, bytes and age options, only used during parsing: they are
+     * moved to their own flow.pkts, flow.bytes and flow.age sigmatches */
+    DetectFlowPkts *pkts;
+    DetectFlowBytes *bytes;
+    DetectUintData_u32 *age;
 } DetectFlowData;
 
 int DetectFlowSetupImplicit(Signature *s, uint32_t flags);
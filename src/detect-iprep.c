@@ -51,6 +51,7 @@
 
 #include "reputation.h"
 #include "host.h"
+#include "flow-storage.h"
 
 static int DetectIPRepMatch (DetectEngineThreadCtx *, Packet *,
         const Signature *, const SigMatchCtx *);
@@ -60,6 +61,19 @@ void DetectIPRepFree (DetectEngineCtx *, void *);
 static void IPRepRegisterTests(void);
 #endif
 
+/** flow storage for the values first seen by the delta mode. Side 0 is the
+ *  flow's source (client), side 1 its destination. -1 means not seen yet. */
+typedef struct IPRepFlowState_ {
+    int8_t first[2][SREP_MAX_CATS];
+} IPRepFlowState;
+
+static FlowStorageId g_iprep_flow_id = { .id = -1 };
+
+static void IPRepFlowStateFree(void *ptr)
+{
+    SCFree(ptr);
+}
+
 void DetectIPRepRegister (void)
 {
     sigmatch_table[DETECT_IPREP].name = "iprep";
@@ -73,6 +87,14 @@ void DetectIPRepRegister (void)
 #endif
     /* this is compatible to ip-only signatures */
     sigmatch_table[DETECT_IPREP].flags |= SIGMATCH_IPONLY_COMPAT;
+
+    if (g_iprep_flow_id.id == -1) {
+        g_iprep_flow_id =
+                FlowStorageRegister("iprep", sizeof(void *), NULL, IPRepFlowStateFree);
+        if (g_iprep_flow_id.id == -1) {
+            FatalError("Can't initiate flow storage for iprep");
+        }
+    }
 }
 
 static inline int8_t GetRep(const SReputation *r, const uint8_t cat, const uint32_t version)
@@ -85,9 +107,41 @@ static inline int8_t GetRep(const SReputation *r, const uint8_t cat, const uint3
     return -1;
 }
 
-/** \returns: -2 no host, -1 no rep entry, 0-127 rep values */
-static int8_t GetHostRepSrc(Packet *p, uint8_t cat, uint32_t version)
+/** \brief rep of category cat of host h, or with vals set the reps of all
+ *         the categories, -1 for the ones without an entry, in vals */
+static int8_t GetHostRep(const Host *h, uint8_t cat, uint32_t version, int8_t *vals)
 {
+    if (vals == NULL)
+        return GetRep(h->iprep, cat, version);
+
+    for (uint8_t c = 0; c < SREP_MAX_CATS; c++) {
+        vals[c] = GetRep(h->iprep, c, version);
+    }
+    return 0;
+}
+
+/** \brief rep of an IPv4-mapped IPv6 address, looked up by its IPv4 form.
+ *         The host is not the packet's so it is not stored in the packet.
+ *  \returns: -2 no host, -1 no rep entry, 0-127 rep values */
+static int8_t GetHostRepMapped(Address *v4, uint8_t cat, uint32_t version, int8_t *vals)
+{
+    Host *h = HostLookupHostFromHash(v4);
+    if (h == NULL)
+        return -2;
+    int8_t val = GetHostRep(h, cat, version, vals);
+    HostRelease(h);
+    return val;
+}
+
+/** \brief rep of the packet's source for category cat, or for all the
+ *         categories in vals if set
+ *  \returns: -2 no host, -1 no rep entry, 0-127 rep values */
+static int8_t GetHostRepsSrc(Packet *p, uint8_t cat, uint32_t version, int8_t *vals)
+{
+    Address v4;
+    if (SRepAddressIsMappedV4(&p->src, &v4))
+        return GetHostRepMapped(&v4, cat, version, vals);
+
     if (p->flags & PKT_HOST_SRC_LOOKED_UP && p->host_src == NULL) {
         return -2;
     } else if (p->host_src != NULL) {
@@ -95,7 +149,7 @@ static int8_t GetHostRepSrc(Packet *p, uint8_t cat, uint32_t version)
         HostLock(h);
         /* use_cnt: 1 for having iprep, 1 for packet ref */
         DEBUG_VALIDATE_BUG_ON(h->iprep != NULL && SC_ATOMIC_GET(h->use_cnt) < 2);
-        int8_t val = GetRep(h->iprep, cat, version);
+        int8_t val = GetHostRep(h, cat, version, vals);
         HostUnlock(h);
         return val;
     } else {
@@ -107,14 +161,18 @@ static int8_t GetHostRepSrc(Packet *p, uint8_t cat, uint32_t version)
         /* use_cnt: 1 for having iprep, 1 for HostLookupHostFromHash,
          * 1 for HostReference to packet */
         DEBUG_VALIDATE_BUG_ON(h->iprep != NULL && SC_ATOMIC_GET(h->use_cnt) < 3);
-        int8_t val = GetRep(h->iprep, cat, version);
+        int8_t val = GetHostRep(h, cat, version, vals);
         HostRelease(h); /* use_cnt >= 2: 1 for iprep, 1 for packet ref */
         return val;
     }
 }
 
-static int8_t GetHostRepDst(Packet *p, uint8_t cat, uint32_t version)
+static int8_t GetHostRepsDst(Packet *p, uint8_t cat, uint32_t version, int8_t *vals)
 {
+    Address v4;
+    if (SRepAddressIsMappedV4(&p->dst, &v4))
+        return GetHostRepMapped(&v4, cat, version, vals);
+
     if (p->flags & PKT_HOST_DST_LOOKED_UP && p->host_dst == NULL) {
         return -2;
     } else if (p->host_dst != NULL) {
@@ -122,7 +180,7 @@ static int8_t GetHostRepDst(Packet *p, uint8_t cat, uint32_t version)
         HostLock(h);
         /* use_cnt: 1 for having iprep, 1 for packet ref */
         DEBUG_VALIDATE_BUG_ON(h->iprep != NULL && SC_ATOMIC_GET(h->use_cnt) < 2);
-        int8_t val = GetRep(h->iprep, cat, version);
+        int8_t val = GetHostRep(h, cat, version, vals);
         HostUnlock(h);
         return val;
     } else {
@@ -134,12 +192,87 @@ static int8_t GetHostRepDst(Packet *p, uint8_t cat, uint32_t version)
         /* use_cnt: 1 for having iprep, 1 for HostLookupHostFromHash,
          * 1 for HostReference to packet */
         DEBUG_VALIDATE_BUG_ON(h->iprep != NULL && SC_ATOMIC_GET(h->use_cnt) < 3);
-        int8_t val = GetRep(h->iprep, cat, version);
+        int8_t val = GetHostRep(h, cat, version, vals);
         HostRelease(h); /* use_cnt >= 2: 1 for iprep, 1 for packet ref */
         return val;
     }
 }
 
+/** \returns: -2 no host, -1 no rep entry, 0-127 rep values */
+static inline int8_t GetHostRepSrc(Packet *p, uint8_t cat, uint32_t version)
+{
+    return GetHostRepsSrc(p, cat, version, NULL);
+}
+
+static inline int8_t GetHostRepDst(Packet *p, uint8_t cat, uint32_t version)
+{
+    return GetHostRepsDst(p, cat, version, NULL);
+}
+
+/** \brief check if an address is in the optional net: scope of the rule */
+static bool IPRepAddressInNet(const DetectIPRepData *rd, const Address *a)
+{
+    if (rd->net.version == 0)
+        return true;
+    if (a->family == AF_INET)
+        return SCDetectIPRepNetContains(rd, 4, a->addr_data8);
+    if (a->family == AF_INET6)
+        return SCDetectIPRepNetContains(rd, 6, a->addr_data8);
+    return false;
+}
+
+/** \brief delta mode: compare a value to the first one seen in the flow for
+ *         the same host and category
+ *  \param src true if val is the rep of the packet's source address */
+static int IPRepDeltaMatch(const DetectIPRepData *rd, Packet *p, const bool src, const int8_t val)
+{
+    if (p->flow == NULL || val < 0)
+        return 0;
+
+    IPRepFlowState *state = FlowGetStorageById(p->flow, g_iprep_flow_id);
+    if (state == NULL) {
+        state = SCMalloc(sizeof(*state));
+        if (unlikely(state == NULL))
+            return 0;
+        memset(state, -1, sizeof(*state));
+        FlowSetStorageById(p->flow, g_iprep_flow_id, state);
+    }
+
+    const bool toserver = (p->flowflags & FLOW_PKT_TOSERVER) != 0;
+    const int side = (src == toserver) ? 0 : 1;
+    return SCDetectIPRepDeltaMatch(rd, &state->first[side][rd->cat], val) ? 1 : 0;
+}
+
+/** \brief check a rep value against the rule, in value or delta mode */
+static inline int IPRepValueMatch(
+        const DetectIPRepData *rd, Packet *p, const bool src, const int8_t val)
+{
+    if (rd->isdelta)
+        return IPRepDeltaMatch(rd, p, src, val);
+    return DetectU8Match((uint8_t)val, &rd->du8);
+}
+
+/** \brief sum mode: compare the sum of the rep values of all categories of
+ *         the packet's source or destination address */
+static int IPRepSumMatch(
+        DetectEngineThreadCtx *det_ctx, const DetectIPRepData *rd, Packet *p, const bool src)
+{
+    const uint32_t version = det_ctx->de_ctx->srep_version;
+    int8_t vals[SREP_MAX_CATS];
+
+    /* one host lookup for all the categories */
+    if ((src ? GetHostRepsSrc(p, 0, version, vals) : GetHostRepsDst(p, 0, version, vals)) < 0)
+        memset(vals, -1, sizeof(vals));
+
+    /* categories without a host entry fall back to the networks */
+    for (uint8_t cat = 0; cat < SREP_MAX_CATS; cat++) {
+        if (vals[cat] < 0)
+            vals[cat] = src ? SRepCIDRGetIPRepSrc(det_ctx->de_ctx->srepCIDR_ctx, p, cat, version)
+                            : SRepCIDRGetIPRepDst(det_ctx->de_ctx->srepCIDR_ctx, p, cat, version);
+    }
+    return SCDetectIPRepSumMatch(rd, vals, SREP_MAX_CATS) ? 1 : 0;
+}
+
 /*
  * returns 0: no match
  *         1: match
@@ -155,37 +288,71 @@ static int DetectIPRepMatch (DetectEngineThreadCtx *det_ctx, Packet *p,
     uint32_t version = det_ctx->de_ctx->srep_version;
     int8_t val = 0;
 
+    /* addresses outside of the net: scope are not checked */
+    const bool src_in_net = IPRepAddressInNet(rd, &p->src);
+    const bool dst_in_net = IPRepAddressInNet(rd, &p->dst);
+
+    if (rd->issum) {
+        switch (rd->cmd) {
+            case IPRepCmdAny:
+                return (src_in_net && IPRepSumMatch(det_ctx, rd, p, true)) ||
+                       (dst_in_net && IPRepSumMatch(det_ctx, rd, p, false));
+            case IPRepCmdSrc:
+                return src_in_net && IPRepSumMatch(det_ctx, rd, p, true);
+            case IPRepCmdDst:
+                return dst_in_net && IPRepSumMatch(det_ctx, rd, p, false);
+            case IPRepCmdBoth:
+                return src_in_net && dst_in_net && IPRepSumMatch(det_ctx, rd, p, true) &&
+                       IPRepSumMatch(det_ctx, rd, p, false);
+        }
+        return 0;
+    }
+
     SCLogDebug("rd->cmd %u", rd->cmd);
     switch (rd->cmd) {
         case IPRepCmdAny:
+            if (!src_in_net && !dst_in_net)
+                return 0;
             if (!rd->isnotset) {
-                val = GetHostRepSrc(p, rd->cat, version);
-                if (val < 0)
-                    val = SRepCIDRGetIPRepSrc(det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
-                if (val >= 0) {
-                    if (DetectU8Match((uint8_t)val, &rd->du8))
-                        return 1;
+                if (src_in_net) {
+                    val = GetHostRepSrc(p, rd->cat, version);
+                    if (val < 0)
+                        val = SRepCIDRGetIPRepSrc(
+                                det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
+                    if (val >= 0) {
+                        if (IPRepValueMatch(rd, p, true, val))
+                            return 1;
+                    }
                 }
-                val = GetHostRepDst(p, rd->cat, version);
-                if (val < 0)
-                    val = SRepCIDRGetIPRepDst(det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
-                if (val >= 0) {
-                    return DetectU8Match((uint8_t)val, &rd->du8);
+                if (dst_in_net) {
+                    val = GetHostRepDst(p, rd->cat, version);
+                    if (val < 0)
+                        val = SRepCIDRGetIPRepDst(
+                                det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
+                    if (val >= 0) {
+                        return IPRepValueMatch(rd, p, false, val);
+                    }
                 }
             } else {
                 /* isnotset for any */
 
-                val = GetHostRepSrc(p, rd->cat, version);
-                if (val < 0)
-                    val = SRepCIDRGetIPRepSrc(det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
-                if (val < 0) {
-                    return 1;
+                if (src_in_net) {
+                    val = GetHostRepSrc(p, rd->cat, version);
+                    if (val < 0)
+                        val = SRepCIDRGetIPRepSrc(
+                                det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
+                    if (val < 0) {
+                        return 1;
+                    }
                 }
-                val = GetHostRepDst(p, rd->cat, version);
-                if (val < 0)
-                    val = SRepCIDRGetIPRepDst(det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
-                if (val < 0) {
-                    return 1;
+                if (dst_in_net) {
+                    val = GetHostRepDst(p, rd->cat, version);
+                    if (val < 0)
+                        val = SRepCIDRGetIPRepDst(
+                                det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
+                    if (val < 0) {
+                        return 1;
+                    }
                 }
                 /* both have a value, so none 'isnotset' */
                 return 0;
@@ -193,13 +360,15 @@ static int DetectIPRepMatch (DetectEngineThreadCtx *det_ctx, Packet *p,
             break;
 
         case IPRepCmdSrc:
+            if (!src_in_net)
+                return 0;
             val = GetHostRepSrc(p, rd->cat, version);
             SCLogDebug("checking src -- val %d (looking for cat %u, val %u)", val, rd->cat,
                     rd->du8.arg1);
             if (val < 0)
                 val = SRepCIDRGetIPRepSrc(det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
             if (val >= 0) {
-                return DetectU8Match((uint8_t)val, &rd->du8);
+                return IPRepValueMatch(rd, p, true, val);
             }
             /* implied: no value found */
             if (rd->isnotset) {
@@ -208,12 +377,14 @@ static int DetectIPRepMatch (DetectEngineThreadCtx *det_ctx, Packet *p,
             break;
 
         case IPRepCmdDst:
+            if (!dst_in_net)
+                return 0;
             SCLogDebug("checking dst");
             val = GetHostRepDst(p, rd->cat, version);
             if (val < 0)
                 val = SRepCIDRGetIPRepDst(det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
             if (val >= 0) {
-                return DetectU8Match((uint8_t)val, &rd->du8);
+                return IPRepValueMatch(rd, p, false, val);
             }
             /* implied: no value found */
             if (rd->isnotset) {
@@ -222,17 +393,23 @@ static int DetectIPRepMatch (DetectEngineThreadCtx *det_ctx, Packet *p,
             break;
 
         case IPRepCmdBoth:
+            if (!src_in_net || !dst_in_net)
+                return 0;
             if (!rd->isnotset) {
                 val = GetHostRepSrc(p, rd->cat, version);
                 if (val < 0)
                     val = SRepCIDRGetIPRepSrc(det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
-                if (val < 0 || DetectU8Match((uint8_t)val, &rd->du8) == 0)
+                if (val < 0)
+                    return 0;
+                /* in delta mode both sides need to record their first value */
+                const int src_match = IPRepValueMatch(rd, p, true, val);
+                if (src_match == 0 && !rd->isdelta)
                     return 0;
                 val = GetHostRepDst(p, rd->cat, version);
                 if (val < 0)
                     val = SRepCIDRGetIPRepDst(det_ctx->de_ctx->srepCIDR_ctx, p, rd->cat, version);
                 if (val >= 0) {
-                    return DetectU8Match((uint8_t)val, &rd->du8);
+                    return IPRepValueMatch(rd, p, false, val) && src_match;
                 }
             } else {
                 val = GetHostRepSrc(p, rd->cat, version);
@@ -930,6 +1107,475 @@ static int DetectIPRepTest11(void)
     PASS;
 }
 
+static int DetectIPRepTest12(void)
+{
+    ThreadVars th_v;
+    DetectEngineThreadCtx *det_ctx = NULL;
+    Signature *sig = NULL;
+    FILE *fd = NULL;
+    int r = 0;
+    Packet *p = UTHBuildPacket((uint8_t *)"lalala", 6, IPPROTO_TCP);
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+
+    HostInitConfig(HOST_QUIET);
+    memset(&th_v, 0, sizeof(th_v));
+
+    FAIL_IF_NULL(de_ctx);
+    FAIL_IF_NULL(p);
+
+    p->src.addr_data32[0] = UTHSetIPv4Address("10.0.0.1");
+    de_ctx->flags |= DE_QUIET;
+
+    SRepInit(de_ctx);
+    SRepResetVersion();
+
+    fd = DetectIPRepGenerateCategoriesDummy();
+    r = SRepLoadCatFileFromFD(fd);
+    FAIL_IF(r < 0);
+
+    fd = DetectIPRepGenerateNetworksDummy();
+    r = SRepLoadFileFromFD(de_ctx->srepCIDR_ctx, fd);
+    FAIL_IF(r < 0);
+
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP in net\"; "
+                                        "iprep:src,BadHosts,>,1,net:10.0.0.0/8; sid:1;rev:1;)");
+    FAIL_IF_NULL(sig);
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP not in net\"; "
+                                        "iprep:src,BadHosts,>,1,net:10.0.1.0/24; sid:2;rev:1;)");
+    FAIL_IF_NULL(sig);
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP v6 net\"; "
+                                        "iprep:src,BadHosts,>,1,net:2001:db8::/32; sid:3;rev:1;)");
+    FAIL_IF_NULL(sig);
+    /* malformed CIDR */
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP bad net\"; "
+                                        "iprep:src,BadHosts,>,1,net:10.0.0.0/33; sid:4;rev:1;)");
+    FAIL_IF_NOT_NULL(sig);
+
+    SigGroupBuild(de_ctx);
+    DetectEngineThreadCtxInit(&th_v, (void *)de_ctx, (void *)&det_ctx);
+
+    p->alerts.cnt = 0;
+    p->action = 0;
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+
+    FAIL_IF_NOT(PacketAlertCheck(p, 1));
+    FAIL_IF(PacketAlertCheck(p, 2));
+    FAIL_IF(PacketAlertCheck(p, 3));
+
+    UTHFreePacket(p);
+
+    DetectEngineThreadCtxDeinit(&th_v, (void *)det_ctx);
+    DetectEngineCtxFree(de_ctx);
+
+    HostShutdown();
+    PASS;
+}
+
+static int DetectIPRepTest13(void)
+{
+    ThreadVars th_v;
+    DetectEngineThreadCtx *det_ctx = NULL;
+    Signature *sig = NULL;
+    FILE *fd = NULL;
+    int r = 0;
+    Packet *p = UTHBuildPacket((uint8_t *)"lalala", 6, IPPROTO_TCP);
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+
+    HostInitConfig(HOST_QUIET);
+    memset(&th_v, 0, sizeof(th_v));
+
+    FAIL_IF_NULL(de_ctx);
+    FAIL_IF_NULL(p);
+
+    p->src.addr_data32[0] = UTHSetIPv4Address("10.0.0.1");
+    de_ctx->flags |= DE_QUIET;
+
+    SRepInit(de_ctx);
+    SRepResetVersion();
+
+    fd = DetectIPRepGenerateCategoriesDummy();
+    r = SRepLoadCatFileFromFD(fd);
+    FAIL_IF(r < 0);
+
+    fd = DetectIPRepGenerateNetworksDummy();
+    r = SRepLoadFileFromFD(de_ctx->srepCIDR_ctx, fd);
+    FAIL_IF(r < 0);
+
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP delta\"; "
+                                        "iprep:src,BadHosts,delta:0; sid:1;rev:1;)");
+    FAIL_IF_NULL(sig);
+    /* values are 0-127 */
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP delta\"; "
+                                        "iprep:src,BadHosts,delta:128; sid:2;rev:1;)");
+    FAIL_IF_NOT_NULL(sig);
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP delta\"; "
+                                        "iprep:src,BadHosts,delta:; sid:3;rev:1;)");
+    FAIL_IF_NOT_NULL(sig);
+
+    SigGroupBuild(de_ctx);
+    DetectEngineThreadCtxInit(&th_v, (void *)de_ctx, (void *)&det_ctx);
+
+    /* no flow to track the first value in */
+    p->alerts.cnt = 0;
+    p->action = 0;
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+    FAIL_IF(PacketAlertCheck(p, 1));
+
+    UTHFreePacket(p);
+
+    DetectEngineThreadCtxDeinit(&th_v, (void *)det_ctx);
+    DetectEngineCtxFree(de_ctx);
+
+    HostShutdown();
+    PASS;
+}
+
+static FILE *DetectIPRepGenerateNetworksDummy4(void)
+{
+    FILE *fd = NULL;
+    const char *buffer =
+        "2001:db8::1,1,20\n"
+        "10.0.0.1,1,30\n"
+        "::ffff:10.0.0.2,1,40";
+
+    fd = SCFmemopen((void *)buffer, strlen(buffer), "r");
+    if (fd == NULL)
+        SCLogDebug("Error with SCFmemopen()");
+
+    return fd;
+}
+
+static int DetectIPRepTest14(void)
+{
+    ThreadVars th_v;
+    DetectEngineThreadCtx *det_ctx = NULL;
+    Signature *sig = NULL;
+    FILE *fd = NULL;
+    int r = 0;
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+
+    HostInitConfig(HOST_QUIET);
+    memset(&th_v, 0, sizeof(th_v));
+
+    FAIL_IF_NULL(de_ctx);
+    de_ctx->flags |= DE_QUIET;
+
+    SRepInit(de_ctx);
+    SRepResetVersion();
+
+    fd = DetectIPRepGenerateCategoriesDummy();
+    r = SRepLoadCatFileFromFD(fd);
+    FAIL_IF(r < 0);
+
+    fd = DetectIPRepGenerateNetworksDummy4();
+    r = SRepLoadFileFromFD(de_ctx->srepCIDR_ctx, fd);
+    FAIL_IF(r < 0);
+
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP v6\"; "
+                                        "iprep:src,BadHosts,=,20; sid:1;rev:1;)");
+    FAIL_IF_NULL(sig);
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP v4\"; "
+                                        "iprep:src,BadHosts,=,30; sid:2;rev:1;)");
+    FAIL_IF_NULL(sig);
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP mapped\"; "
+                                        "iprep:src,BadHosts,=,40; sid:3;rev:1;)");
+    FAIL_IF_NULL(sig);
+
+    SigGroupBuild(de_ctx);
+    DetectEngineThreadCtxInit(&th_v, (void *)de_ctx, (void *)&det_ctx);
+
+    /* v6 hit */
+    Packet *p = UTHBuildPacketIPV6SrcDst(
+            (uint8_t *)"lalala", 6, IPPROTO_TCP, "2001:db8::1", "2001:db8::ff");
+    FAIL_IF_NULL(p);
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+    FAIL_IF_NOT(PacketAlertCheck(p, 1));
+    FAIL_IF(PacketAlertCheck(p, 2));
+    UTHFreePacket(p);
+
+    /* v6 miss */
+    p = UTHBuildPacketIPV6SrcDst(
+            (uint8_t *)"lalala", 6, IPPROTO_TCP, "2001:db8::2", "2001:db8::ff");
+    FAIL_IF_NULL(p);
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+    FAIL_IF(p->alerts.cnt != 0);
+    UTHFreePacket(p);
+
+    /* v4-mapped v6 resolves like its v4 form */
+    p = UTHBuildPacketIPV6SrcDst(
+            (uint8_t *)"lalala", 6, IPPROTO_TCP, "::ffff:10.0.0.1", "2001:db8::ff");
+    FAIL_IF_NULL(p);
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+    FAIL_IF_NOT(PacketAlertCheck(p, 2));
+    FAIL_IF(PacketAlertCheck(p, 1));
+    UTHFreePacket(p);
+
+    /* a v4-mapped entry is stored as v4 */
+    p = UTHBuildPacket((uint8_t *)"lalala", 6, IPPROTO_TCP);
+    FAIL_IF_NULL(p);
+    p->src.addr_data32[0] = UTHSetIPv4Address("10.0.0.2");
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+    FAIL_IF_NOT(PacketAlertCheck(p, 3));
+    UTHFreePacket(p);
+
+    DetectEngineThreadCtxDeinit(&th_v, (void *)det_ctx);
+    DetectEngineCtxFree(de_ctx);
+
+    HostShutdown();
+    PASS;
+}
+
+static FILE *DetectIPRepGenerateNetworksDummy5(void)
+{
+    FILE *fd = NULL;
+    const char *buffer =
+        "10.0.0.1,1,30\n"
+        "10.0.0.1,2,25\n"
+        "10.0.0.0/24,2,10";
+
+    fd = SCFmemopen((void *)buffer, strlen(buffer), "r");
+    if (fd == NULL)
+        SCLogDebug("Error with SCFmemopen()");
+
+    return fd;
+}
+
+static int DetectIPRepTest15(void)
+{
+    ThreadVars th_v;
+    DetectEngineThreadCtx *det_ctx = NULL;
+    Signature *sig = NULL;
+    FILE *fd = NULL;
+    int r = 0;
+    Packet *p = UTHBuildPacket((uint8_t *)"lalala", 6, IPPROTO_TCP);
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+
+    HostInitConfig(HOST_QUIET);
+    memset(&th_v, 0, sizeof(th_v));
+
+    FAIL_IF_NULL(de_ctx);
+    FAIL_IF_NULL(p);
+
+    p->src.addr_data32[0] = UTHSetIPv4Address("10.0.0.1");
+    p->dst.addr_data32[0] = UTHSetIPv4Address("192.168.0.1");
+    de_ctx->flags |= DE_QUIET;
+
+    SRepInit(de_ctx);
+    SRepResetVersion();
+
+    fd = DetectIPRepGenerateCategoriesDummy2();
+    r = SRepLoadCatFileFromFD(fd);
+    FAIL_IF(r < 0);
+
+    fd = DetectIPRepGenerateNetworksDummy5();
+    r = SRepLoadFileFromFD(de_ctx->srepCIDR_ctx, fd);
+    FAIL_IF(r < 0);
+
+    /* 30 + 25, the host entry takes precedence over the network */
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP sum\"; "
+                                        "iprep:src,sum,>,50; sid:1;rev:1;)");
+    FAIL_IF_NULL(sig);
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP sum\"; "
+                                        "iprep:src,sum,>,55; sid:2;rev:1;)");
+    FAIL_IF_NULL(sig);
+    /* no entries for the destination */
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP sum\"; "
+                                        "iprep:dst,sum,=,0; sid:3;rev:1;)");
+    FAIL_IF_NULL(sig);
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP sum\"; "
+                                        "iprep:both,sum,>,50; sid:4;rev:1;)");
+    FAIL_IF_NULL(sig);
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP sum\"; "
+                                        "iprep:src,sum,isset; sid:5;rev:1;)");
+    FAIL_IF_NOT_NULL(sig);
+
+    SigGroupBuild(de_ctx);
+    DetectEngineThreadCtxInit(&th_v, (void *)de_ctx, (void *)&det_ctx);
+
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+    FAIL_IF_NOT(PacketAlertCheck(p, 1));
+    FAIL_IF(PacketAlertCheck(p, 2));
+    FAIL_IF_NOT(PacketAlertCheck(p, 3));
+    FAIL_IF(PacketAlertCheck(p, 4));
+
+    UTHFreePacket(p);
+
+    DetectEngineThreadCtxDeinit(&th_v, (void *)det_ctx);
+    DetectEngineCtxFree(de_ctx);
+
+    HostShutdown();
+    PASS;
+}
+
+static FILE *DetectIPRepGenerateNetworksDummy6(void)
+{
+    FILE *fd = NULL;
+    const char *buffer = "10.0.0.1,1,20";
+
+    fd = SCFmemopen((void *)buffer, strlen(buffer), "r");
+    if (fd == NULL)
+        SCLogDebug("Error with SCFmemopen()");
+
+    return fd;
+}
+
+static int DetectIPRepTest16(void)
+{
+    ThreadVars th_v;
+    DetectEngineThreadCtx *det_ctx = NULL;
+    Signature *sig = NULL;
+    FILE *fd = NULL;
+    int r = 0;
+
+    HostInitConfig(HOST_QUIET);
+    FlowInitConfig(FLOW_QUIET);
+    memset(&th_v, 0, sizeof(th_v));
+
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+    FAIL_IF_NULL(de_ctx);
+    de_ctx->flags |= DE_QUIET;
+
+    SRepInit(de_ctx);
+    SRepResetVersion();
+
+    fd = DetectIPRepGenerateCategoriesDummy();
+    r = SRepLoadCatFileFromFD(fd);
+    FAIL_IF(r < 0);
+
+    fd = DetectIPRepGenerateNetworksDummy6();
+    r = SRepLoadFileFromFD(de_ctx->srepCIDR_ctx, fd);
+    FAIL_IF(r < 0);
+
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP delta\"; "
+                                        "iprep:src,BadHosts,delta:5; sid:1;rev:1;)");
+    FAIL_IF_NULL(sig);
+
+    SigGroupBuild(de_ctx);
+    /* ip-only sigs only see the first packet of the flow */
+    FAIL_IF(sig->type == SIG_TYPE_IPONLY);
+    DetectEngineThreadCtxInit(&th_v, (void *)de_ctx, (void *)&det_ctx);
+
+    Flow *f = FlowAlloc();
+    FAIL_IF_NULL(f);
+    FLOW_INITIALIZE(f);
+    f->proto = IPPROTO_TCP;
+    f->flags |= FLOW_IPV4;
+
+    Packet *p1 = UTHBuildPacketReal(
+            (uint8_t *)"lalala", 6, IPPROTO_TCP, "10.0.0.1", "10.0.0.2", 41424, 80);
+    FAIL_IF_NULL(p1);
+    p1->flow = f;
+    p1->flags |= PKT_HAS_FLOW;
+    p1->flowflags |= (FLOW_PKT_TOSERVER | FLOW_PKT_TOSERVER_FIRST);
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p1);
+    FAIL_IF(PacketAlertCheck(p1, 1));
+
+    /* the reputation of the source changes mid-flow */
+    Host *h = HostLookupHostFromHash(&p1->src);
+    FAIL_IF_NULL(h);
+    FAIL_IF_NULL(h->iprep);
+    ((SReputation *)h->iprep)->rep[1] = 30;
+    HostRelease(h);
+
+    Packet *p2 = UTHBuildPacketReal(
+            (uint8_t *)"lalala", 6, IPPROTO_TCP, "10.0.0.1", "10.0.0.2", 41424, 80);
+    FAIL_IF_NULL(p2);
+    p2->flow = f;
+    p2->flags |= PKT_HAS_FLOW;
+    p2->flowflags |= FLOW_PKT_TOSERVER;
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p2);
+    FAIL_IF_NOT(PacketAlertCheck(p2, 1));
+
+    UTHFreePacket(p1);
+    UTHFreePacket(p2);
+
+    DetectEngineThreadCtxDeinit(&th_v, (void *)det_ctx);
+    DetectEngineCtxFree(de_ctx);
+
+    FlowClearMemory(f, FlowGetProtoMapping(f->proto));
+    FLOW_DESTROY(f);
+    FlowFree(f);
+
+    FlowShutdown();
+    HostShutdown();
+    PASS;
+}
+
+static FILE *DetectIPRepGenerateNetworksDummy7(void)
+{
+    FILE *fd = NULL;
+    const char *buffer = "::ffff:10.0.0.0/120,1,30";
+
+    fd = SCFmemopen((void *)buffer, strlen(buffer), "r");
+    if (fd == NULL)
+        SCLogDebug("Error with SCFmemopen()");
+
+    return fd;
+}
+
+static int DetectIPRepTest17(void)
+{
+    ThreadVars th_v;
+    DetectEngineThreadCtx *det_ctx = NULL;
+    Signature *sig = NULL;
+    FILE *fd = NULL;
+    int r = 0;
+    DetectEngineCtx *de_ctx = DetectEngineCtxInit();
+
+    HostInitConfig(HOST_QUIET);
+    memset(&th_v, 0, sizeof(th_v));
+
+    FAIL_IF_NULL(de_ctx);
+    de_ctx->flags |= DE_QUIET;
+
+    SRepInit(de_ctx);
+    SRepResetVersion();
+
+    fd = DetectIPRepGenerateCategoriesDummy();
+    r = SRepLoadCatFileFromFD(fd);
+    FAIL_IF(r < 0);
+
+    fd = DetectIPRepGenerateNetworksDummy7();
+    r = SRepLoadFileFromFD(de_ctx->srepCIDR_ctx, fd);
+    FAIL_IF(r < 0);
+
+    sig = DetectEngineAppendSig(de_ctx, "alert tcp any any -> any any (msg:\"IPREP mapped net\"; "
+                                        "iprep:src,BadHosts,=,30; sid:1;rev:1;)");
+    FAIL_IF_NULL(sig);
+
+    SigGroupBuild(de_ctx);
+    DetectEngineThreadCtxInit(&th_v, (void *)de_ctx, (void *)&det_ctx);
+
+    /* the v4-mapped /120 covers 10.0.0.0/24 */
+    Packet *p = UTHBuildPacket((uint8_t *)"lalala", 6, IPPROTO_TCP);
+    FAIL_IF_NULL(p);
+    p->src.addr_data32[0] = UTHSetIPv4Address("10.0.0.5");
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+    FAIL_IF_NOT(PacketAlertCheck(p, 1));
+    UTHFreePacket(p);
+
+    p = UTHBuildPacket((uint8_t *)"lalala", 6, IPPROTO_TCP);
+    FAIL_IF_NULL(p);
+    p->src.addr_data32[0] = UTHSetIPv4Address("10.0.1.5");
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+    FAIL_IF(p->alerts.cnt != 0);
+    UTHFreePacket(p);
+
+    /* and so does a v4-mapped v6 packet address */
+    p = UTHBuildPacketIPV6SrcDst(
+            (uint8_t *)"lalala", 6, IPPROTO_TCP, "::ffff:10.0.0.9", "2001:db8::ff");
+    FAIL_IF_NULL(p);
+    SigMatchSignatures(&th_v, de_ctx, det_ctx, p);
+    FAIL_IF_NOT(PacketAlertCheck(p, 1));
+    UTHFreePacket(p);
+
+    DetectEngineThreadCtxDeinit(&th_v, (void *)det_ctx);
+    DetectEngineCtxFree(de_ctx);
+
+    HostShutdown();
+    PASS;
+}
+
 /**
  * \brief this function registers unit tests for IPRep
  */
@@ -946,5 +1592,11 @@ void IPRepRegisterTests(void)
     UtRegisterTest("DetectIPRepTest09", DetectIPRepTest09);
     UtRegisterTest("DetectIPRepTest10 -- isset", DetectIPRepTest10);
     UtRegisterTest("DetectIPRepTest11 -- isnotset", DetectIPRepTest11);
+    UtRegisterTest("DetectIPRepTest12 -- net", DetectIPRepTest12);
+    UtRegisterTest("DetectIPRepTest13 -- delta", DetectIPRepTest13);
+    UtRegisterTest("DetectIPRepTest14 -- ipv6", DetectIPRepTest14);
+    UtRegisterTest("DetectIPRepTest15 -- sum", DetectIPRepTest15);
+    UtRegisterTest("DetectIPRepTest16 -- delta mid-flow", DetectIPRepTest16);
+    UtRegisterTest("DetectIPRepTest17 -- v4-mapped network", DetectIPRepTest17);
 }
 #endif /* UNITTESTS */
@@ -869,6 +869,17 @@ static int DetectSetupDirection(Signature *s, char **str, bool only_dir)
     return 0;
 }
 
+/** \brief call the setup of keyword st, timing it with --enable-keyword-metrics */
+static inline int SigSetupKeyword(
+        const SigTableElmt *st, DetectEngineCtx *de_ctx, Signature *s, const char *str)
+{
+#ifdef HAVE_KEYWORD_METRICS
+    return SCDetectKeywordSetupTimed(st->name, st->Setup, de_ctx, s, str);
+#else
+    return st->Setup(de_ctx, s, str);
+#endif
+}
+
 static int SigParseOptions(DetectEngineCtx *de_ctx, Signature *s, char *optstr, char *output,
         size_t output_size, bool requires)
 {
@@ -1062,12 +1073,18 @@ static int SigParseOptions(DetectEngineCtx *de_ctx, Signature *s, char *optstr,
                 goto error;
             }
         }
-        setup_ret = st->Setup(de_ctx, s, ptr);
+        const int transforms_cnt = s->init_data->transforms.cnt;
+        setup_ret = SigSetupKeyword(st, de_ctx, s, ptr);
+        if (setup_ret >= 0 && st->Transform != NULL &&
+                s->init_data->transforms.cnt > transforms_cnt) {
+            /* keep the options for the rule analyzer */
+            s->init_data->transforms.transforms[transforms_cnt].optstr = SCStrdup(ptr);
+        }
         s->init_data->init_flags &= ~SIG_FLAG_INIT_FORCE_TOSERVER;
         s->init_data->init_flags &= ~SIG_FLAG_INIT_FORCE_TOCLIENT;
     } else {
         /* setup may or may not add a new SigMatch to the list */
-        setup_ret = st->Setup(de_ctx, s, NULL);
+        setup_ret = SigSetupKeyword(st, de_ctx, s, NULL);
     }
     if (setup_ret < 0) {
         SCLogDebug("\"%s\" failed to setup", st->name);
@@ -2052,6 +2069,10 @@ void SigFree(DetectEngineCtx *de_ctx, Signature *s)
                         de_ctx, s->init_data->transforms.transforms[i].options);
                 s->init_data->transforms.transforms[i].options = NULL;
             }
+            if (s->init_data->transforms.transforms[i].optstr) {
+                SCFree(s->init_data->transforms.transforms[i].optstr);
+                s->init_data->transforms.transforms[i].optstr = NULL;
+            }
         }
     }
     if (s->init_data) {
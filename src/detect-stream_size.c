@@ -72,35 +72,9 @@ void DetectStreamSizeRegister(void)
 
 static int DetectStreamSizeMatchAux(const DetectStreamSizeData *sd, const TcpSession *ssn)
 {
-    int ret = 0;
-    uint32_t csdiff = 0;
-    uint32_t ssdiff = 0;
-
-    if (sd->flags == StreamSizeServer) {
-        /* get the server stream size */
-        ssdiff = ssn->server.next_seq - ssn->server.isn;
-        ret = DetectU32Match(ssdiff, &sd->du32);
-
-    } else if (sd->flags == StreamSizeClient) {
-        /* get the client stream size */
-        csdiff = ssn->client.next_seq - ssn->client.isn;
-        ret = DetectU32Match(csdiff, &sd->du32);
-
-    } else if (sd->flags == StreamSizeBoth) {
-        ssdiff = ssn->server.next_seq - ssn->server.isn;
-        csdiff = ssn->client.next_seq - ssn->client.isn;
-
-        if (DetectU32Match(ssdiff, &sd->du32) && DetectU32Match(csdiff, &sd->du32))
-            ret = 1;
-
-    } else if (sd->flags == StreamSizeEither) {
-        ssdiff = ssn->server.next_seq - ssn->server.isn;
-        csdiff = ssn->client.next_seq - ssn->client.isn;
-
-        if (DetectU32Match(ssdiff, &sd->du32) || DetectU32Match(csdiff, &sd->du32))
-            ret = 1;
-    }
-    return ret;
+    const uint32_t csdiff = ssn->client.next_seq - ssn->client.isn;
+    const uint32_t ssdiff = ssn->server.next_seq - ssn->server.isn;
+    return SCDetectStreamSizeMatch(sd, csdiff, ssdiff) ? 1 : 0;
 }
 
 /**
@@ -185,6 +159,7 @@ static void PrefilterPacketStreamsizeMatch(
     dsd.du32.mode = ctx->v1.u8[0];
     dsd.flags = ctx->v1.u8[1];
     dsd.du32.arg1 = ctx->v1.u32[2];
+    dsd.du32.arg2 = ctx->v1.u32[3];
     const TcpSession *ssn = (TcpSession *)p->flow->protoctx;
     /* if we match, add all the sigs that use this prefilter. This means
      * that these will be inspected further */
@@ -199,12 +174,14 @@ static void PrefilterPacketStreamSizeSet(PrefilterPacketHeaderValue *v, void *sm
     v->u8[0] = a->du32.mode;
     v->u8[1] = a->flags;
     v->u32[2] = a->du32.arg1;
+    v->u32[3] = a->du32.arg2;
 }
 
 static bool PrefilterPacketStreamSizeCompare(PrefilterPacketHeaderValue v, void *smctx)
 {
     const DetectStreamSizeData *a = smctx;
-    if (v.u8[0] == a->du32.mode && v.u8[1] == a->flags && v.u32[2] == a->du32.arg1)
+    if (v.u8[0] == a->du32.mode && v.u8[1] == a->flags && v.u32[2] == a->du32.arg1 &&
+            v.u32[3] == a->du32.arg2)
         return true;
     return false;
 }
@@ -25,38 +25,7 @@ static int DetectVlanIdMatch(
 {
     const DetectVlanIdData *vdata = (const DetectVlanIdData *)ctx;
 
-    if (p->vlan_idx == 0) {
-        return 0;
-    }
-
-    switch (vdata->layer) {
-        case DETECT_VLAN_ID_ANY:
-            for (int i = 0; i < p->vlan_idx; i++) {
-                if (DetectU16Match(p->vlan_id[i], &vdata->du16)) {
-                    return 1;
-                }
-            }
-            return 0;
-        case DETECT_VLAN_ID_ALL:
-            for (int i = 0; i < p->vlan_idx; i++) {
-                if (!DetectU16Match(p->vlan_id[i], &vdata->du16)) {
-                    return 0;
-                }
-            }
-            return 1;
-        default:
-            if (vdata->layer < 0) { // Negative layer values for backward indexing.
-                if (((int16_t)p->vlan_idx) + vdata->layer < 0) {
-                    return 0;
-                }
-                return DetectU16Match(p->vlan_id[p->vlan_idx + vdata->layer], &vdata->du16);
-            } else {
-                if (p->vlan_idx < vdata->layer) {
-                    return 0;
-                }
-                return DetectU16Match(p->vlan_id[vdata->layer], &vdata->du16);
-            }
-    }
+    return SCDetectVlanIdMatch(p->vlan_id, p->vlan_idx, vdata) ? 1 : 0;
 }
 
 static void DetectVlanIdFree(DetectEngineCtx *de_ctx, void *ptr)
@@ -217,3 +186,112 @@ void DetectVlanLayersRegister(void)
     sigmatch_table[DETECT_VLAN_LAYERS].SupportsPrefilter = PrefilterVlanLayersIsPrefilterable;
     sigmatch_table[DETECT_VLAN_LAYERS].SetupPrefilter = PrefilterSetupVlanLayers;
 }
+
+static int DetectVlanPcpMatch(
+        DetectEngineThreadCtx *det_ctx, Packet *p, const Signature *s, const SigMatchCtx *ctx)
+{
+    const DetectU8Data *du8 = (const DetectU8Data *)ctx;
+    return SCDetectVlanPcpMatch(p->vlan_pcp, p->vlan_idx, du8) ? 1 : 0;
+}
+
+static void DetectVlanPcpFree(DetectEngineCtx *de_ctx, void *ptr)
+{
+    SCDetectU8Free(ptr);
+}
+
+static int DetectVlanPcpSetup(DetectEngineCtx *de_ctx, Signature *s, const char *rawstr)
+{
+    DetectU8Data *du8 = SCDetectVlanPcpParse(rawstr);
+    if (du8 == NULL) {
+        SCLogError("vlan pcp invalid %s", rawstr);
+        return -1;
+    }
+
+    if (SCSigMatchAppendSMToList(
+                de_ctx, s, DETECT_VLAN_PCP, (SigMatchCtx *)du8, DETECT_SM_LIST_MATCH) == NULL) {
+        DetectVlanPcpFree(de_ctx, du8);
+        return -1;
+    }
+    s->flags |= SIG_FLAG_REQUIRE_PACKET;
+
+    return 0;
+}
+
+static void PrefilterPacketVlanPcpMatch(
+        DetectEngineThreadCtx *det_ctx, Packet *p, const void *pectx)
+{
+    const PrefilterPacketHeaderCtx *ctx = pectx;
+
+    DetectU8Data du8;
+    du8.mode = ctx->v1.u8[0];
+    du8.arg1 = ctx->v1.u8[1];
+    du8.arg2 = ctx->v1.u8[2];
+
+    if (DetectVlanPcpMatch(det_ctx, p, NULL, (const SigMatchCtx *)&du8)) {
+        PrefilterAddSids(&det_ctx->pmq, ctx->sigs_array, ctx->sigs_cnt);
+    }
+}
+
+static int PrefilterSetupVlanPcp(DetectEngineCtx *de_ctx, SigGroupHead *sgh)
+{
+    return PrefilterSetupPacketHeader(de_ctx, sgh, DETECT_VLAN_PCP, SIG_MASK_REQUIRE_REAL_PKT,
+            PrefilterPacketU8Set, PrefilterPacketU8Compare, PrefilterPacketVlanPcpMatch);
+}
+
+static bool PrefilterVlanPcpIsPrefilterable(const Signature *s)
+{
+    return PrefilterIsPrefilterableById(s, DETECT_VLAN_PCP);
+}
+
+void DetectVlanPcpRegister(void)
+{
+    sigmatch_table[DETECT_VLAN_PCP].name = "vlan.pcp";
+    sigmatch_table[DETECT_VLAN_PCP].desc = "match priority code point of the outermost vlan";
+    sigmatch_table[DETECT_VLAN_PCP].url = "/rules/vlan-keywords.html#vlan-pcp";
+    sigmatch_table[DETECT_VLAN_PCP].Match = DetectVlanPcpMatch;
+    sigmatch_table[DETECT_VLAN_PCP].Setup = DetectVlanPcpSetup;
+    sigmatch_table[DETECT_VLAN_PCP].Free = DetectVlanPcpFree;
+    sigmatch_table[DETECT_VLAN_PCP].SupportsPrefilter = PrefilterVlanPcpIsPrefilterable;
+    sigmatch_table[DETECT_VLAN_PCP].SetupPrefilter = PrefilterSetupVlanPcp;
+}
+
+static int DetectVlanHasTagMatch(
+        DetectEngineThreadCtx *det_ctx, Packet *p, const Signature *s, const SigMatchCtx *ctx)
+{
+    const DetectVlanHasTagData *vdata = (const DetectVlanHasTagData *)ctx;
+    return SCDetectVlanHasTagMatch(p->vlan_idx, vdata) ? 1 : 0;
+}
+
+static void DetectVlanHasTagFree(DetectEngineCtx *de_ctx, void *ptr)
+{
+    SCDetectVlanHasTagFree(ptr);
+}
+
+static int DetectVlanHasTagSetup(DetectEngineCtx *de_ctx, Signature *s, const char *rawstr)
+{
+    DetectVlanHasTagData *vdata = SCDetectVlanHasTagParse(rawstr);
+    if (vdata == NULL) {
+        SCLogError("vlan has_tag invalid %s", rawstr);
+        return -1;
+    }
+
+    if (SCSigMatchAppendSMToList(de_ctx, s, DETECT_VLAN_HAS_TAG, (SigMatchCtx *)vdata,
+                DETECT_SM_LIST_MATCH) == NULL) {
+        DetectVlanHasTagFree(de_ctx, vdata);
+        return -1;
+    }
+    s->flags |= SIG_FLAG_REQUIRE_PACKET;
+
+    return 0;
+}
+
+void DetectVlanHasTagRegister(void)
+{
+    sigmatch_table[DETECT_VLAN_HAS_TAG].name = "vlan.has_tag";
+    sigmatch_table[DETECT_VLAN_HAS_TAG].desc = "match if the packet carries a vlan tag";
+    sigmatch_table[DETECT_VLAN_HAS_TAG].url = "/rules/vlan-keywords.html#vlan-has-tag";
+    sigmatch_table[DETECT_VLAN_HAS_TAG].Match = DetectVlanHasTagMatch;
+    sigmatch_table[DETECT_VLAN_HAS_TAG].Setup = DetectVlanHasTagSetup;
+    sigmatch_table[DETECT_VLAN_HAS_TAG].Free = DetectVlanHasTagFree;
+    sigmatch_table[DETECT_VLAN_HAS_TAG].flags = SIGMATCH_OPTIONAL_OPT;
+}
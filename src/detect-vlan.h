@@ -20,5 +20,7 @@
 
 void DetectVlanIdRegister(void);
 void DetectVlanLayersRegister(void);
+void DetectVlanPcpRegister(void);
+void DetectVlanHasTagRegister(void);
 
 #endif /* SURICATA_DETECT_VLAN_H */
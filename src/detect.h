@@ -387,6 +387,8 @@ typedef struct InspectionBufferMultipleForList {
 typedef struct TransformData_ {
     int transform;
     void *options;
+    /** option string of the transform in the rule, as logged by the rule analyzer */
+    char *optstr;
 } TransformData;
 
 typedef struct DetectEngineTransforms {
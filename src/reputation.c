@@ -90,6 +90,23 @@ static void SRepCIDRAddNetblock(SRepCIDRTree *cidr_ctx, char *ip, int cat, uint8
     user_data->version = SRepGetVersion();
     user_data->rep[cat] = value;
 
+    /* v4-mapped networks of at least /96 are stored as v4 */
+    char v4net[32];
+    char *slash = strchr(ip, '/');
+    if (slash != NULL && strchr(ip, ':') != NULL) {
+        IPRepAddr key;
+        uint8_t bits;
+        *slash = '\0';
+        bool mapped = SCIPRepAddrParse(ip, &key) && key.version == 4;
+        *slash = '/';
+        if (mapped && StringParseU8RangeCheck(&bits, 10, 0, slash + 1, 96, 128) >= 0) {
+            char addr[16];
+            PrintInet(AF_INET, key.addr, addr, sizeof(addr));
+            snprintf(v4net, sizeof(v4net), "%s/%u", addr, bits - 96);
+            ip = v4net;
+        }
+    }
+
     if (strchr(ip, ':') != NULL) {
         SCLogDebug("adding ipv6 host %s", ip);
         if (!SCRadix6AddKeyIPV6String(
@@ -132,12 +149,32 @@ static int8_t SRepCIDRGetIPv6IPRep(SRepCIDRTree *cidr_ctx, uint8_t *ipv6_addr, u
     return r->rep[cat];
 }
 
+/** \brief get the IPv4 form of an IPv4-mapped IPv6 address
+ *  \retval true if a is such an address, v4 is then set */
+bool SRepAddressIsMappedV4(const Address *a, Address *v4)
+{
+    if (a->family != AF_INET6)
+        return false;
+
+    IPRepAddr key;
+    if (!SCIPRepAddrFromBytes(6, a->addr_data8, &key) || key.version != 4)
+        return false;
+
+    memset(v4, 0x00, sizeof(*v4));
+    v4->family = AF_INET;
+    memcpy(v4->addr_data8, key.addr, 4);
+    return true;
+}
+
 int8_t SRepCIDRGetIPRepSrc(SRepCIDRTree *cidr_ctx, Packet *p, uint8_t cat, uint32_t version)
 {
     int8_t rep = -3;
+    Address v4;
 
     if (PacketIsIPv4(p))
         rep = SRepCIDRGetIPv4IPRep(cidr_ctx, (uint8_t *)GET_IPV4_SRC_ADDR_PTR(p), cat);
+    else if (PacketIsIPv6(p) && SRepAddressIsMappedV4(&p->src, &v4))
+        rep = SRepCIDRGetIPv4IPRep(cidr_ctx, v4.addr_data8, cat);
     else if (PacketIsIPv6(p))
         rep = SRepCIDRGetIPv6IPRep(cidr_ctx, (uint8_t *)GET_IPV6_SRC_ADDR(p), cat);
 
@@ -147,9 +184,12 @@ int8_t SRepCIDRGetIPRepSrc(SRepCIDRTree *cidr_ctx, Packet *p, uint8_t cat, uint3
 int8_t SRepCIDRGetIPRepDst(SRepCIDRTree *cidr_ctx, Packet *p, uint8_t cat, uint32_t version)
 {
     int8_t rep = -3;
+    Address v4;
 
     if (PacketIsIPv4(p))
         rep = SRepCIDRGetIPv4IPRep(cidr_ctx, (uint8_t *)GET_IPV4_DST_ADDR_PTR(p), cat);
+    else if (PacketIsIPv6(p) && SRepAddressIsMappedV4(&p->dst, &v4))
+        rep = SRepCIDRGetIPv4IPRep(cidr_ctx, v4.addr_data8, cat);
     else if (PacketIsIPv6(p))
         rep = SRepCIDRGetIPv6IPRep(cidr_ctx, (uint8_t *)GET_IPV6_DST_ADDR(p), cat);
 
@@ -308,12 +348,17 @@ static int SRepSplitLine(SRepCIDRTree *cidr_ctx, char *line, Address *ip, uint8_
         SRepCIDRAddNetblock(cidr_ctx, ptrs[0], c, v);
         return 1;
     } else {
-        if (inet_pton(AF_INET, ptrs[0], &ip->address) == 1) {
+        /* v4-mapped addresses are stored as v4 */
+        IPRepAddr key;
+        if (!SCIPRepAddrParse(ptrs[0], &key)) {
+            return -1;
+        }
+        if (key.version == 4) {
             ip->family = AF_INET;
-        } else if (inet_pton(AF_INET6, ptrs[0], &ip->address) == 1) {
-            ip->family = AF_INET6;
+            memcpy(ip->addr_data8, key.addr, 4);
         } else {
-            return -1;
+            ip->family = AF_INET6;
+            memcpy(ip->addr_data8, key.addr, 16);
         }
 
         *cat = c;
@@ -781,6 +781,7 @@ static void AFPReadFromRingSetupPacket(
     if ((ptv->flags & AFP_VLAN_IN_HEADER) &&
             (tp_status & TP_STATUS_VLAN_VALID || h.h2->tp_vlan_tci)) {
         p->vlan_id[0] = h.h2->tp_vlan_tci & 0x0fff;
+        p->vlan_pcp[0] = (h.h2->tp_vlan_tci >> 13) & 0x07;
         p->vlan_idx = 1;
         p->afp_v.vlan_tci = h.h2->tp_vlan_tci;
     }
@@ -971,6 +972,7 @@ static inline int AFPParsePacketV3(AFPThreadVars *ptv, struct tpacket_block_desc
     if ((ptv->flags & AFP_VLAN_IN_HEADER) &&
             (ppd->tp_status & TP_STATUS_VLAN_VALID || ppd->hv1.tp_vlan_tci)) {
         p->vlan_id[0] = ppd->hv1.tp_vlan_tci & 0x0fff;
+        p->vlan_pcp[0] = (ppd->hv1.tp_vlan_tci >> 13) & 0x07;
         p->vlan_idx = 1;
         p->afp_v.vlan_tci = (uint16_t)ppd->hv1.tp_vlan_tci;
     }
@@ -821,6 +821,44 @@ libhtp:\n\
     return RunTest(steps, sig, yaml);
 }
 
+/** \brief base64 body of the 256 byte values, of an entropy of 8 once decoded */
+#define BODY_BASE64_BYTES                                                                          \
+    "POST /upload HTTP/1.1\r\n"                                                                    \
+    "Host: www.openinfosecfoundation.org\r\n"                                                      \
+    "Content-Length: 344\r\n"                                                                      \
+    "\r\n"                                                                                         \
+    "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4"                 \
+    "OTo7PD0+P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5fYGFiY2RlZmdoaWprbG1ub3Bx"                 \
+    "cnN0dXZ3eHl6e3x9fn+AgYKDhIWGh4iJiouMjY6PkJGSk5SVlpeYmZqbnJ2en6ChoqOkpaanqKmq"                 \
+    "q6ytrq+wsbKztLW2t7i5uru8vb6/wMHCw8TFxsfIycrLzM3Oz9DR0tPU1dbX2Nna29zd3t/g4eLj"                 \
+    "5OXm5+jp6uvs7e7v8PHy8/T19vf4+fr7/P3+/w=="
+
+/** \test entropy measures the body after the from_base64 transform */
+static int DetectEngineHttpClientBodyTest32(void)
+{
+    struct TestSteps steps[] = {
+        { (const uint8_t *)BODY_BASE64_BYTES, 0, STREAM_TOSERVER, 1 },
+        { NULL, 0, 0, 0 },
+    };
+
+    const char *sig = "alert http any any -> any any (http.request_body; from_base64; "
+                      "entropy:value > 7; sid:1;)";
+    return RunTest(steps, sig, NULL);
+}
+
+/** \test without a transform, entropy measures the base64 text, of 64 symbols */
+static int DetectEngineHttpClientBodyTest33(void)
+{
+    struct TestSteps steps[] = {
+        { (const uint8_t *)BODY_BASE64_BYTES, 0, STREAM_TOSERVER, 0 },
+        { NULL, 0, 0, 0 },
+    };
+
+    const char *sig = "alert http any any -> any any (http.request_body; "
+                      "entropy:value > 7; sid:1;)";
+    return RunTest(steps, sig, NULL);
+}
+
 /**
  * \test Test that a signature containing a http_client_body is correctly parsed
  *       and the keyword is registered.
@@ -2948,6 +2986,10 @@ void DetectHttpClientBodyRegisterTests(void)
                    DetectEngineHttpClientBodyTest30);
     UtRegisterTest("DetectEngineHttpClientBodyTest31",
                    DetectEngineHttpClientBodyTest31);
+    UtRegisterTest("DetectEngineHttpClientBodyTest32",
+                   DetectEngineHttpClientBodyTest32);
+    UtRegisterTest("DetectEngineHttpClientBodyTest33",
+                   DetectEngineHttpClientBodyTest33);
 }
 
 #endif